@@ -0,0 +1,32 @@
+use std::sync::Mutex;
+
+/// The accessibility display settings of the operating system
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemAccessibility {
+    /// The user asked for opaque rather than translucent windows
+    pub reduce_transparency: bool,
+    /// The user asked for a higher contrast presentation
+    pub increase_contrast: bool,
+    /// The user asked for less animation
+    pub reduce_motion: bool,
+}
+
+/// What the gui last read from the system; used to resolve the
+/// `reduce_transparency`, `increase_contrast` and `reduce_motion`
+/// options when they are not explicitly set
+static SYSTEM_ACCESSIBILITY: Mutex<SystemAccessibility> = Mutex::new(SystemAccessibility {
+    reduce_transparency: false,
+    increase_contrast: false,
+    reduce_motion: false,
+});
+
+/// Records the system accessibility settings, returning true if
+/// that is a change from the previous value
+pub fn set_system_accessibility(settings: SystemAccessibility) -> bool {
+    let mut current = SYSTEM_ACCESSIBILITY.lock().unwrap();
+    std::mem::replace(&mut *current, settings) != settings
+}
+
+pub fn system_accessibility() -> SystemAccessibility {
+    *SYSTEM_ACCESSIBILITY.lock().unwrap()
+}
@@ -2,6 +2,7 @@ use crate::*;
 use luahelper::impl_lua_conversion_dynamic;
 use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use termwiz::cell::CellAttributes;
 use termwiz::color::ColorSpec as TWColorSpec;
 pub use termwiz::color::{AnsiColor, ColorAttribute, RgbColor, SrgbaTuple};
@@ -145,6 +146,11 @@ pub struct Palette {
     /// The color of selected text
     pub selection_fg: Option<RgbaColor>,
     pub selection_bg: Option<RgbaColor>,
+    /// The color of selected text in a pane that doesn't have focus.
+    /// If unspecified, the selection colors are used with the
+    /// background dimmed.
+    pub inactive_selection_fg: Option<RgbaColor>,
+    pub inactive_selection_bg: Option<RgbaColor>,
     /// A list of 8 colors corresponding to the basic ANSI palette
     pub ansi: Option<[RgbaColor; 8]>,
     /// A list of 8 colors corresponding to bright versions of the
@@ -210,6 +216,8 @@ impl Palette {
             cursor_border: overlay!(cursor_border),
             selection_fg: overlay!(selection_fg),
             selection_bg: overlay!(selection_bg),
+            inactive_selection_fg: overlay!(inactive_selection_fg),
+            inactive_selection_bg: overlay!(inactive_selection_bg),
             ansi: overlay!(ansi),
             brights: overlay!(brights),
             tab_bar: match (&self.tab_bar, &other.tab_bar) {
@@ -705,6 +713,56 @@ fn default_button_bg() -> RgbaColor {
     RgbColor::new_8bpc(0x33, 0x33, 0x33).into()
 }
 
+/// Whether the gui last decided that the dark color scheme applies;
+/// used to pick between `color_scheme_dark` and `color_scheme_light`
+static DARK_APPEARANCE: AtomicBool = AtomicBool::new(false);
+
+/// Records whether the dark color scheme applies, returning true if
+/// that is a change from the previous value
+pub fn set_dark_appearance(dark: bool) -> bool {
+    DARK_APPEARANCE.swap(dark, Ordering::Relaxed) != dark
+}
+
+pub fn is_dark_appearance() -> bool {
+    DARK_APPEARANCE.load(Ordering::Relaxed)
+}
+
+/// Switches between the light and dark color schemes at fixed times
+/// of day instead of following the system appearance
+#[derive(Debug, Clone, PartialEq, FromDynamic, ToDynamic)]
+pub struct ColorSchemeSchedule {
+    /// Local time of day, as "HH:MM", at which the light scheme starts
+    pub light: String,
+    /// Local time of day, as "HH:MM", at which the dark scheme starts
+    pub dark: String,
+}
+impl_lua_conversion_dynamic!(ColorSchemeSchedule);
+
+fn parse_time_of_day(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    if hours < 24 && minutes < 60 {
+        Some(hours * 60 + minutes)
+    } else {
+        None
+    }
+}
+
+impl ColorSchemeSchedule {
+    /// Returns whether the dark scheme applies at `minutes` past
+    /// midnight, or None if either of the times is malformed
+    pub fn is_dark_at(&self, minutes: u32) -> Option<bool> {
+        let light = parse_time_of_day(&self.light)?;
+        let dark = parse_time_of_day(&self.dark)?;
+        Some(if light <= dark {
+            !(light <= minutes && minutes < dark)
+        } else {
+            dark <= minutes && minutes < light
+        })
+    }
+}
+
 #[derive(Debug, Default, Clone, Eq, PartialEq, FromDynamic, ToDynamic)]
 pub struct ColorSchemeMetaData {
     pub name: Option<String>,
@@ -830,3 +888,31 @@ brights = [ "#8ca6a6" ,"#e5164a" ,"#00b368" ,"#b3694d" ,"#0094f0" ,"#ff5792" ,"#
         Some(&RgbColor::new_8bpc(0xfb, 0xda, 0xda).into())
     );
 }
+
+#[cfg(test)]
+#[test]
+fn test_color_scheme_schedule() {
+    let schedule = ColorSchemeSchedule {
+        light: "07:30".to_string(),
+        dark: "19:00".to_string(),
+    };
+    assert_eq!(schedule.is_dark_at(0), Some(true));
+    assert_eq!(schedule.is_dark_at(7 * 60 + 29), Some(true));
+    assert_eq!(schedule.is_dark_at(7 * 60 + 30), Some(false));
+    assert_eq!(schedule.is_dark_at(18 * 60 + 59), Some(false));
+    assert_eq!(schedule.is_dark_at(19 * 60), Some(true));
+
+    // A dark period that doesn't span midnight
+    let schedule = ColorSchemeSchedule {
+        light: "20:00".to_string(),
+        dark: "08:00".to_string(),
+    };
+    assert_eq!(schedule.is_dark_at(12 * 60), Some(true));
+    assert_eq!(schedule.is_dark_at(22 * 60), Some(false));
+
+    let schedule = ColorSchemeSchedule {
+        light: "7am".to_string(),
+        dark: "25:00".to_string(),
+    };
+    assert_eq!(schedule.is_dark_at(0), None);
+}
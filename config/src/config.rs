@@ -1,18 +1,21 @@
+use crate::accessibility::{system_accessibility, SystemAccessibility};
 use crate::background::{BackgroundLayer, Gradient};
 use crate::bell::{AudibleBell, EasingFunction, VisualBell};
 use crate::color::{
-    ColorSchemeFile, HsbTransform, Palette, SrgbaTuple, TabBarStyle, WindowFrameConfig,
+    is_dark_appearance, ColorSchemeFile, ColorSchemeSchedule, HsbTransform, Palette, SrgbaTuple,
+    TabBarStyle, WindowFrameConfig,
 };
 use crate::daemon::DaemonOptions;
 use crate::exec_domain::ExecDomain;
 use crate::font::{
     AllowSquareGlyphOverflow, DisplayPixelGeometry, FontLocatorSelection, FontRasterizerSelection,
-    FontShaperSelection, FreeTypeLoadFlags, FreeTypeLoadTarget, StyleRule, TextStyle,
+    FontShaperSelection, FontSynthesis, FreeTypeLoadFlags, FreeTypeLoadTarget, StyleRule,
+    TextStyle,
 };
 use crate::frontend::FrontEndSelection;
 use crate::keyassignment::{
-    KeyAssignment, KeyTable, KeyTableEntry, KeyTables, MouseEventTrigger, PaneEncoding,
-    SpawnCommand,
+    ClipboardCopyDestination, KeyAssignment, KeyTable, KeyTableEntry, KeyTables, MouseEventTrigger,
+    PaneEncoding, SpawnCommand,
 };
 use crate::keys::{DeferredKeyCode, Key, KeyNoAction, LeaderKey, Mouse};
 use crate::lua::make_lua_context;
@@ -25,14 +28,14 @@ use crate::{
     default_config_with_overrides_applied, default_one_point_oh, default_one_point_oh_f64,
     default_true, default_win32_acrylic_accent_color, CellWidth, GpuInfo,
     IntegratedTitleButtonColor, KeyMapPreference, LoadedConfig, MouseEventTriggerMods, RgbaColor,
-    SerialDomain, SystemBackdrop, WebGpuPowerPreference, CONFIG_DIRS, CONFIG_FILE_OVERRIDE,
-    CONFIG_OVERRIDES, CONFIG_SKIP,
+    SerialDomain, SystemBackdrop, WebGpuPowerPreference, WindowColorSpace, CONFIG_DIRS,
+    CONFIG_FILE_OVERRIDE, CONFIG_OVERRIDES, CONFIG_SKIP,
 };
 use anyhow::Context;
 use luahelper::impl_lua_conversion_dynamic;
 use mlua::FromLua;
 use portable_pty::CommandBuilder;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::ffi::{OsStr, OsString};
 use std::io::Read;
@@ -123,6 +126,30 @@ pub struct Config {
     #[dynamic(default)]
     pub font_rules: Vec<StyleRule>,
 
+    /// The font to use for bold text, in place of the bold version of
+    /// `font`.  Rules in `font_rules` take precedence over this.
+    #[dynamic(default)]
+    pub font_bold: Option<TextStyle>,
+
+    /// The font to use for italic text, in place of the italic version
+    /// of `font`.  Rules in `font_rules` take precedence over this.
+    #[dynamic(default)]
+    pub font_italic: Option<TextStyle>,
+
+    /// The font to use for bold italic text.  When not set, this is the
+    /// italic version of `font_bold`, or failing that the bold version
+    /// of `font_italic`, or failing that derived from `font`.
+    #[dynamic(default)]
+    pub font_bold_italic: Option<TextStyle>,
+
+    /// Which styles may be synthesized, by emboldening or slanting the
+    /// glyphs of another face, when a font has no face for the
+    /// requested weight or style.  A font can override this with its
+    /// own `synthesis` attribute, for example to only allow synthesis
+    /// for a CJK fallback font.
+    #[dynamic(default)]
+    pub font_synthesis: FontSynthesis,
+
     /// When true (the default), PaletteIndex 0-7 are shifted to
     /// bright when the font intensity is bold.  The brightening
     /// doesn't apply to text that is the default color.
@@ -194,6 +221,17 @@ pub struct Config {
     /// by the colors setting.
     pub color_scheme: Option<String>,
 
+    /// Color schemes to use while the system appearance is dark or
+    /// light respectively.  When set, these take precedence over
+    /// color_scheme and are switched at runtime as the appearance
+    /// changes.
+    pub color_scheme_dark: Option<String>,
+    pub color_scheme_light: Option<String>,
+
+    /// Pick between color_scheme_dark and color_scheme_light by the
+    /// time of day rather than by the system appearance
+    pub color_scheme_schedule: Option<ColorSchemeSchedule>,
+
     /// Named color schemes
     #[dynamic(default)]
     pub color_schemes: HashMap<String, Palette>,
@@ -205,6 +243,18 @@ pub struct Config {
     )]
     pub scrollback_lines: usize,
 
+    /// When true, lines that scroll beyond `scrollback_lines` are written
+    /// to encrypted, unlinked temporary files rather than discarded.
+    /// Spilled lines keep their colors and attributes, and can still be
+    /// scrolled to, searched and copied.
+    #[dynamic(default)]
+    pub scrollback_spill_to_disk: bool,
+
+    /// The most disk space, in bytes, that the spilled lines of each pane
+    /// may take up.  Beyond this, the oldest spilled lines are discarded.
+    #[dynamic(default = "default_scrollback_spill_max_bytes")]
+    pub scrollback_spill_max_bytes: usize,
+
     /// If no `prog` is specified on the command line, use this
     /// instead of running the user's shell.
     /// For example, to have `wezterm` always run `top` by default,
@@ -242,6 +292,32 @@ pub struct Config {
     #[dynamic(default = "default_pane_encoding")]
     pub default_encoding: PaneEncoding,
 
+    /// Lines written to each newly spawned pane, each followed by a
+    /// carriage return, regardless of the program that it runs.
+    /// Useful for sourcing an environment or defining aliases.
+    #[dynamic(default)]
+    pub post_spawn_commands: Vec<String>,
+
+    /// When true, panes spawned in the local domain without an explicit
+    /// encoding use the one named by the codeset of the child's locale,
+    /// eg: `LANG=zh_CN.GBK` selects GBK.
+    #[dynamic(default = "default_true")]
+    pub guess_encoding_from_locale: bool,
+
+    /// How many bytes of undecoded output to retain per pane, so that
+    /// `ReinterpretScrollbackWithEncoding` can decode it again after the
+    /// wrong encoding was used.  The default of 0 retains nothing.
+    #[dynamic(default)]
+    pub raw_output_retention_bytes: usize,
+
+    /// Once this many characters in a pane could not be converted to or
+    /// from its encoding, the `pane-encoding-errors` event is emitted.
+    /// It fires once per pane, and again after its encoding is changed.
+    /// Set to 0 to never emit the event.  Changes apply to panes that
+    /// are spawned afterwards.
+    #[dynamic(default = "default_encoding_error_event_threshold")]
+    pub encoding_error_event_threshold: usize,
+
     #[dynamic(default)]
     pub exit_behavior: ExitBehavior,
 
@@ -251,12 +327,70 @@ pub struct Config {
     #[dynamic(default = "default_clean_exits")]
     pub clean_exit_codes: Vec<u32>,
 
+    /// When true, and the child process of a pane appears to be reading
+    /// a password (local echo disabled in canonical mode), a lock is shown
+    /// in place of the cursor, and paste confirmation and the native line
+    /// editor shortcuts are suspended so that neither can interfere with
+    /// the password.
     #[dynamic(default = "default_true")]
     pub detect_password_input: bool,
 
+    /// When true, macOS Secure Keyboard Entry is enabled while the focused
+    /// pane is reading a password, preventing other applications from
+    /// observing the keystrokes.  Detecting passwords read by programs
+    /// requires `detect_password_input`; `kaku askpass` prompts are
+    /// always covered.
+    #[dynamic(default)]
+    pub secure_keyboard_entry_for_password_input: bool,
+
+    /// When true, the text and cursor position of the active pane are
+    /// exposed to screen readers and other assistive technology.
+    /// This is currently only supported on macOS.
+    #[dynamic(default = "default_true")]
+    pub screen_reader_support: bool,
+
+    /// When non-zero, the window is covered by a lock screen after this
+    /// many minutes without keyboard or mouse input, hiding the panes
+    /// until it is unlocked according to `session_unlock_method`.
+    /// The `LockSession` key assignment locks the window on demand.
+    #[dynamic(default)]
+    pub session_lock_idle_minutes: u64,
+
+    /// Controls what is required to dismiss the session lock screen.
+    #[dynamic(default)]
+    pub session_unlock_method: crate::window::SessionUnlockMethod,
+
+    /// When true, each command that shell integration reports as
+    /// finished is appended to a per-workspace log in the data
+    /// directory, along with its working directory, duration and
+    /// exit status.  The log can be browsed with the
+    /// `ShowCommandHistory` and `SearchCommandHistory` key assignments
+    /// or `kaku command-history`.
+    #[dynamic(default)]
+    pub command_history_log: bool,
+
+    /// When true, a green or red marker is drawn in the gutter beside
+    /// each prompt at which shell integration reported that the command
+    /// succeeded or failed.  Use the `ScrollToFailedPrompt` key assignment
+    /// to jump between the failed ones.  Markers need some `window_padding`
+    /// on the left, or a split to the left of the pane, to be drawn.
+    #[dynamic(default = "default_true")]
+    pub show_command_status_markers: bool,
+
+    /// When non-zero, a command tracked by shell integration that runs
+    /// for at least this many seconds raises a notification when it
+    /// finishes, unless its pane is the active pane of a focused window.
+    /// Clicking the notification focuses the pane.
+    #[dynamic(default)]
+    pub long_running_command_notification_secs: u64,
+
     /// Specifies a map of environment variables that should be set
     /// when spawning commands in the local domain.
     /// This is not used when working with remote domains.
+    /// The final environment can be adjusted just before the command is
+    /// spawned by handling the `adjust-spawn-environment` event, which is
+    /// passed the environment and the domain name, and may return a
+    /// replacement environment table.
     #[dynamic(default)]
     pub set_environment_variables: HashMap<String, String>,
 
@@ -269,6 +403,15 @@ pub struct Config {
     #[dynamic(default)]
     pub enable_kitty_keyboard: bool,
 
+    /// The set of kitty keyboard protocol progressive enhancement flags
+    /// that applications are permitted to enable, expressed as the
+    /// bitmask defined by the protocol (1 = disambiguate, 2 = event types,
+    /// 4 = alternate keys, 8 = all keys as escapes, 16 = associated text).
+    /// Flags requested by an application that are not in this mask are
+    /// silently dropped, and are not reported back by the query sequence.
+    #[dynamic(default = "default_kitty_keyboard_flags")]
+    pub kitty_keyboard_flags: u16,
+
     /// Whether the terminal should respond to requests to read the
     /// title string.
     /// Disabled by default for security concerns with shells that might
@@ -277,6 +420,50 @@ pub struct Config {
     #[dynamic(default)]
     pub enable_title_reporting: bool,
 
+    /// Whether applications may enable focus reporting (DECSET 1004),
+    /// which sends `CSI I` / `CSI O` as the pane gains or loses focus.
+    #[dynamic(default = "default_true")]
+    pub enable_focus_reporting: bool,
+
+    /// Whether applications may enable in-band resize notifications
+    /// (DECSET 2048), which report the size of the pane in cells and
+    /// pixels as `CSI 48 ; rows ; cols ; height ; width t` whenever
+    /// it changes.
+    #[dynamic(default = "default_true")]
+    pub enable_in_band_resize: bool,
+
+    /// Whether the terminal should respond to XTWINOPS queries for the
+    /// size of the text area and its cells (`CSI 14 t`, `CSI 16 t`,
+    /// `CSI 18 t` and `CSI 19 t`).
+    #[dynamic(default = "default_true")]
+    pub enable_window_size_reporting: bool,
+
+    /// How applications may change the cursor shape with DECSCUSR
+    /// (`CSI Ps SP q`), as used by vim and other modal editors to show
+    /// a bar in insert mode and a block in normal mode.
+    /// `"SteadyOnly"` applies the shape but never blinks and `"Ignore"`
+    /// always keeps `default_cursor_style`.
+    #[dynamic(default)]
+    pub cursor_style_escapes: CursorStyleEscapes,
+
+    /// Whether applications may change the cursor color with OSC 12
+    #[dynamic(default = "default_true")]
+    pub allow_cursor_color_escapes: bool,
+
+    /// The minimum contrast ratio against the background that a cursor
+    /// color set via OSC 12 must have.  Colors below this are ignored so
+    /// that an application can't make the cursor invisible.
+    #[dynamic(
+        default = "default_cursor_color_min_contrast",
+        validate = "validate_cursor_color_min_contrast"
+    )]
+    pub cursor_color_min_contrast: f32,
+
+    /// Whether applications may hide the cursor with DECTCEM
+    /// (`CSI ? 25 l`).  When false the cursor is always drawn.
+    #[dynamic(default = "default_true")]
+    pub allow_cursor_hiding: bool,
+
     /// Specifies the width of a new window, expressed in character cells
     #[dynamic(default = "default_initial_cols", validate = "validate_row_or_col")]
     pub initial_cols: u16,
@@ -284,7 +471,9 @@ pub struct Config {
     #[dynamic(default = "default_hyperlink_rules")]
     pub hyperlink_rules: Vec<hyperlink::Rule>,
 
-    /// What to set the TERM variable to
+    /// What to set the TERM variable to.
+    /// SSH and WSL domains may override this with their own `term`, and a
+    /// `SpawnCommand` may override it via `set_environment_variables`.
     #[dynamic(default = "default_term")]
     pub term: String,
 
@@ -348,12 +537,22 @@ pub struct Config {
     /// # when using the Fira Code font
     /// harfbuzz_features = ["zero"]
     /// ```
+    ///
+    /// A font in `font` or `font_rules` can override this with its own
+    /// `harfbuzz_features`, and can set the axes of a variable font
+    /// with `variation_axes`, such as `{ wght = 450, wdth = 90 }`.
     #[dynamic(default = "default_harfbuzz_features")]
     pub harfbuzz_features: Vec<String>,
 
     #[dynamic(default)]
     pub front_end: FrontEndSelection,
 
+    /// The front ends to try, in order, when `front_end` cannot be
+    /// initialized.  Entries that match `front_end` are skipped.
+    /// Defaults to OpenGL, then software rendering.
+    #[dynamic(default = "default_front_end_fallback")]
+    pub front_end_fallback: Vec<FrontEndSelection>,
+
     /// Whether to select the higher powered discrete GPU when
     /// the system has a choice of integrated or discrete.
     /// Defaults to low power.
@@ -366,6 +565,24 @@ pub struct Config {
     #[dynamic(default)]
     pub webgpu_preferred_adapter: Option<GpuInfo>,
 
+    /// When non-empty, only WebGpu adapters whose name or backend
+    /// contains one of these strings (ignoring case) will be used.
+    /// For example, `webgpu_adapter_allowlist = ["Metal"]`.
+    #[dynamic(default)]
+    pub webgpu_adapter_allowlist: Vec<String>,
+
+    /// The color space that colors are specified in; see WindowColorSpace
+    #[dynamic(default)]
+    pub window_color_space: WindowColorSpace,
+
+    /// Whether colors are blended in linear space, which is more
+    /// physically accurate, or in sRGB space, which matches the
+    /// anti-aliasing and transparency of most other terminals.
+    /// Only the WebGpu front end blends in linear space; OpenGL always
+    /// blends in sRGB space.  Takes effect for new windows.
+    #[dynamic(default = "default_true")]
+    pub linear_blending: bool,
+
     #[dynamic(default)]
     pub wsl_domains: Option<Vec<WslDomain>>,
 
@@ -382,6 +599,14 @@ pub struct Config {
     #[dynamic(default)]
     pub ssh_domains: Option<Vec<SshDomain>>,
 
+    /// When `ssh_domains` is set explicitly, also add the implicit
+    /// `SSH:host` and `SSHMUX:host` domains for the hosts listed in your
+    /// ssh config files, so that they show up in the launcher without
+    /// having to be repeated here.  Hosts that already have an explicitly
+    /// configured domain are skipped.
+    #[dynamic(default)]
+    pub ssh_domains_include_ssh_config_hosts: bool,
+
     #[dynamic(default)]
     pub ssh_backend: SshBackend,
 
@@ -415,6 +640,19 @@ pub struct Config {
     #[dynamic(default)]
     pub default_ssh_auth_sock: Option<String>,
 
+    /// When true, programs started in local panes have `SSH_ASKPASS` and
+    /// `GIT_ASKPASS` set to `kaku askpass`, which prompts for passwords
+    /// and passphrases in an overlay over the pane, even when the program
+    /// asking has no terminal.  `SSH_ASKPASS_REQUIRE=force` is set too,
+    /// so that ssh uses it without `DISPLAY`; set your own
+    /// `SSH_ASKPASS_REQUIRE` in `set_environment_variables` to change that.
+    /// Variables that are already set are left alone.
+    /// This is off by default because it sends every ssh prompt to the
+    /// overlay, including host key confirmations that are then typed
+    /// into a masked field.
+    #[dynamic(default)]
+    pub ssh_askpass: bool,
+
     /// How many ms to delay after reading a chunk of output
     /// in order to try to coalesce fragmented writes into
     /// a single bigger chunk of output and reduce the chances
@@ -425,6 +663,33 @@ pub struct Config {
     #[dynamic(default = "default_mux_env_remove")]
     pub mux_env_remove: Vec<String>,
 
+    /// When serving mux clients, push screen changes less often while
+    /// writes to a client are slow, so that output heavy panes don't
+    /// crowd out the responses to typing on slow links.
+    /// Changes in response to input are always sent immediately.
+    #[dynamic(default = "default_true")]
+    pub mux_adaptive_sync: bool,
+
+    /// When the round trip time to a mux server is at least this many
+    /// milliseconds, stop fetching image data for remote panes so that
+    /// the link is kept free for text.  Images that were already fetched
+    /// are still shown.
+    #[dynamic(default = "default_mux_reduced_fidelity_threshold_ms")]
+    pub mux_reduced_fidelity_threshold_ms: Option<u64>,
+
+    /// Whether to compress the larger PDUs, such as pane output, that are
+    /// exchanged with a mux server.  The client tells the server whether
+    /// it wants compressed data when it connects, so this is honored for
+    /// both directions of the connection.
+    #[dynamic(default = "default_true")]
+    pub mux_compression: bool,
+
+    /// The zstd compression level to use for mux PDUs when
+    /// `mux_compression` is enabled.  Higher levels trade CPU time for
+    /// smaller payloads.
+    #[dynamic(default = "default_mux_compression_level")]
+    pub mux_compression_level: i32,
+
     #[dynamic(default)]
     pub keys: Vec<Key>,
     #[dynamic(default)]
@@ -464,6 +729,15 @@ pub struct Config {
     #[dynamic(default = "default_true")]
     pub copy_on_select: bool,
 
+    /// Where text is copied to when a mouse selection is completed.
+    /// When unset, the destination specified by the `CompleteSelection`
+    /// mouse binding is used, which defaults to both the clipboard and
+    /// the primary selection.  Set to `"PrimarySelection"` to keep
+    /// selections out of the clipboard so that they are only available
+    /// to middle-click paste.
+    #[dynamic(default)]
+    pub copy_on_select_destination: Option<ClipboardCopyDestination>,
+
     #[dynamic(default)]
     pub daemon_options: DaemonOptions,
 
@@ -496,6 +770,21 @@ pub struct Config {
     /// active tab.  Clicking on a tab activates it.
     #[dynamic(default = "default_true")]
     pub enable_tab_bar: bool,
+
+    /// On macOS, use the native window tabbing instead of the tab bar.
+    /// Each Kaku window becomes a native tab, new tabs are opened as new
+    /// windows that macOS merges into the current tab group, and the
+    /// internal tab bar is hidden.  `ActivateTab`, `ActivateTabRelative`
+    /// and `ShowTabNavigator` act on the native tabs.
+    #[dynamic(default)]
+    pub use_native_macos_tabs: bool,
+
+    /// On macOS, add an item to the status bar (the right hand side of
+    /// the menu bar) with a menu for switching workspaces and running
+    /// the launch_menu entries and commands registered with
+    /// `wezterm.register_command`.
+    #[dynamic(default)]
+    pub macos_menu_bar_status_item: bool,
     #[dynamic(default = "default_true")]
     pub use_fancy_tab_bar: bool,
 
@@ -505,6 +794,20 @@ pub struct Config {
     #[dynamic(default = "default_true")]
     pub mouse_wheel_scrolls_tabs: bool,
 
+    /// If true, a multi-finger horizontal trackpad swipe switches to the
+    /// previous or next tab
+    #[dynamic(default = "default_true")]
+    pub swipe_to_switch_tabs: bool,
+
+    /// If true, pinching on the trackpad changes the font size
+    #[dynamic(default = "default_true")]
+    pub pinch_to_zoom_font_size: bool,
+
+    /// If true, the name of the active key table is shown in the
+    /// bottom-left corner of the window while it is active
+    #[dynamic(default = "default_true")]
+    pub show_key_table_indicator: bool,
+
     /// If true, tab bar titles are prefixed with the tab index
     #[dynamic(default = "default_true")]
     pub show_tab_index_in_tab_bar: bool,
@@ -588,6 +891,24 @@ pub struct Config {
     /// The image will be scaled to fit the window.
     #[dynamic(default)]
     pub window_background_image: Option<PathBuf>,
+
+    /// Specifies the path to a WGSL file that is drawn over the window
+    /// background, beneath the text.  The file must define
+    /// `fn background(uv: vec2<f32>) -> vec4<f32>`, where `uv` ranges
+    /// from (0, 0) at the top left of the window to (1, 1) at the bottom
+    /// right, and may read `bg.resolution` (in pixels) and `bg.time`
+    /// (in seconds).  The result is alpha blended over the background.
+    /// When the shader refers to `bg.time` the window is redrawn at
+    /// `animation_fps`.
+    /// This is only used by the WebGpu front end, and only when
+    /// `enable_custom_shaders` is true.
+    #[dynamic(default)]
+    pub window_background_shader: Option<PathBuf>,
+
+    /// Custom shaders, such as `window_background_shader`, are only
+    /// loaded when this is set to true.
+    #[dynamic(default)]
+    pub enable_custom_shaders: bool,
     #[dynamic(default)]
     pub window_background_gradient: Option<Gradient>,
     #[dynamic(default)]
@@ -658,6 +979,19 @@ pub struct Config {
     #[dynamic(default = "default_inactive_pane_hsb")]
     pub inactive_pane_hsb: HsbTransform,
 
+    /// Rounds the outer corners of the selection highlight.
+    /// Accepts the same units as `window_padding`, so `"0.25cell"`
+    /// rounds by a quarter of the cell height.  The radius is limited
+    /// to half the height of a line.
+    #[dynamic(try_from = "crate::units::PixelUnit", default)]
+    pub selection_corner_radius: Dimension,
+
+    /// Extends the selection highlight horizontally past the selected
+    /// cells by this amount on each side, so that the highlight doesn't
+    /// touch the first and last selected glyphs.
+    #[dynamic(try_from = "crate::units::PixelUnit", default)]
+    pub selection_padding: Dimension,
+
     #[dynamic(default = "default_one_point_oh")]
     pub text_background_opacity: f32,
 
@@ -677,7 +1011,52 @@ pub struct Config {
     #[dynamic(default = "default_anim_fps")]
     pub animation_fps: u8,
 
+    /// When true, eased transitions (blinking, the visual bell fade)
+    /// switch in a single step and the pane focus flash is suppressed.
+    /// When not set, this follows the "reduce motion" accessibility
+    /// setting of the system.  Use `Config::reduce_motion` to read the
+    /// effective value.
+    #[dynamic(default)]
+    pub reduce_motion: Option<bool>,
+
+    /// When true, window_background_opacity, text_background_opacity
+    /// and the opacity of background layers are treated as 1.0 and
+    /// window background blur is disabled.
+    /// When not set, this follows the "reduce transparency"
+    /// accessibility setting of the system.
+    #[dynamic(default)]
+    pub reduce_transparency: Option<bool>,
+
+    /// When true, text_min_contrast_ratio is raised to at least
+    /// `increase_contrast_min_ratio`.
+    /// When not set, this follows the "increase contrast" accessibility
+    /// setting of the system.
+    #[dynamic(default)]
+    pub increase_contrast: Option<bool>,
+
+    /// The minimum contrast ratio applied when `increase_contrast` is
+    /// in effect.  The default of 7.0 is the WCAG 2 AAA level.
+    #[dynamic(
+        default = "default_increase_contrast_min_ratio",
+        validate = "validate_increase_contrast_min_ratio"
+    )]
+    pub increase_contrast_min_ratio: f32,
+
+    /// Briefly highlights a pane in the visual_bell color when it
+    /// becomes the active pane, fading out over this many milliseconds.
+    /// 0 disables the flash.
     #[dynamic(default)]
+    pub pane_focus_flash_duration_ms: u64,
+    #[dynamic(default)]
+    pub pane_focus_flash_function: EasingFunction,
+
+    /// When set, foreground text whose contrast ratio against its cell
+    /// background falls below this value has its lightness adjusted,
+    /// keeping its hue, until the ratio is met.  The ratio is computed
+    /// as in WCAG 2, ranging from 1 (no contrast) to 21; 4.5 is a good
+    /// starting point for themes with poorly chosen palette entries.
+    /// The adjustment is made before `foreground_text_hsb` is applied.
+    #[dynamic(default, validate = "validate_min_contrast_ratio")]
     pub text_min_contrast_ratio: Option<f32>,
 
     #[dynamic(default)]
@@ -753,6 +1132,13 @@ pub struct Config {
     #[dynamic(default = "default_true")]
     pub use_dead_keys: bool,
 
+    /// When false, dead keys are disabled while the active pane is using
+    /// the alternate screen, so that full screen applications such as
+    /// editors receive accent keys immediately rather than waiting for
+    /// the next key press to complete a composition.
+    #[dynamic(default = "default_true")]
+    pub use_dead_keys_in_alt_screen: bool,
+
     #[dynamic(default)]
     pub launch_menu: Vec<SpawnCommand>,
 
@@ -782,6 +1168,13 @@ pub struct Config {
     #[dynamic(default)]
     pub enable_csi_u_key_encoding: bool,
 
+    /// The highest xterm `modifyOtherKeys` level that applications may
+    /// select with `CSI > 4 ; Pn m`.  Requests for a higher level are
+    /// clamped to this value, and setting it to 0 ignores the request
+    /// entirely so that keys are always sent in their legacy encoding.
+    #[dynamic(default = "default_max_modify_other_keys_level")]
+    pub max_modify_other_keys_level: u8,
+
     #[dynamic(default)]
     pub window_close_confirmation: WindowCloseConfirmation,
 
@@ -794,9 +1187,28 @@ pub struct Config {
     #[dynamic(default = "default_word_boundary")]
     pub selection_word_boundary: String,
 
+    /// When true, double clicking on a url or path selects the whole of
+    /// it, and double clicking inside a quoted string selects its contents,
+    /// before falling back to `selection_word_boundary`.
+    #[dynamic(default)]
+    pub smart_word_selection: bool,
+
+    /// The string sent back to the application in response to ENQ (^E)
     #[dynamic(default = "default_enq_answerback")]
     pub enq_answerback: String,
 
+    /// Overrides the parameters of the primary device attributes (DA1)
+    /// response, eg: `"62;22"` makes the terminal reply `CSI ? 62;22 c`.
+    /// When unset, the capabilities that are actually supported are reported.
+    #[dynamic(default, validate = "validate_device_attributes")]
+    pub primary_device_attributes: Option<String>,
+
+    /// Overrides the parameters of the secondary device attributes (DA2)
+    /// response, eg: `"41;380;0"` makes the terminal reply `CSI > 41;380;0 c`.
+    /// When unset, the terminal identifies as `1;277;0`.
+    #[dynamic(default, validate = "validate_device_attributes")]
+    pub secondary_device_attributes: Option<String>,
+
     #[dynamic(default)]
     pub adjust_window_size_when_changing_font_size: Option<bool>,
 
@@ -806,9 +1218,39 @@ pub struct Config {
     #[dynamic(default)]
     pub use_resize_increments: bool,
 
+    /// Show the terminal size in columns and rows while the window is
+    /// being resized with the mouse
+    #[dynamic(default)]
+    pub show_size_while_resizing: bool,
+
+    /// How much larger the font becomes while presentation mode,
+    /// toggled by the `TogglePresentationMode` action, is active
+    #[dynamic(default = "default_presentation_mode_font_scale")]
+    pub presentation_mode_font_scale: f64,
+
+    /// Whether the mouse wheel sends arrow keys to applications that use
+    /// the alternate screen without enabling mouse reporting, so that
+    /// the likes of `less` and `vim` scroll.  When false, wheel events
+    /// in the alternate screen go through the mouse bindings instead.
+    /// None of the default wheel bindings apply in the alternate screen,
+    /// so unless you add one with `alt_screen = true` (or `"Any"`), the
+    /// wheel does nothing there.
+    /// Applications that ask for this with DECSET 1007 always get it.
+    #[dynamic(default = "default_true")]
+    pub alternate_buffer_wheel_sends_arrow_keys: bool,
+
+    /// How many arrow keys are sent for each mouse wheel event
+    /// when `alternate_buffer_wheel_sends_arrow_keys` applies
     #[dynamic(default = "default_alternate_buffer_wheel_scroll_speed")]
     pub alternate_buffer_wheel_scroll_speed: u8,
 
+    /// Whether output in the primary screen is rewrapped to the new
+    /// width when a pane is resized.  When false, lines that have
+    /// scrolled into the scrollback keep their original width and
+    /// can be scrolled into view horizontally.
+    #[dynamic(default = "default_true")]
+    pub rewrap_on_resize: bool,
+
     #[dynamic(default = "default_status_update_interval")]
     pub status_update_interval: u64,
 
@@ -882,9 +1324,51 @@ pub struct Config {
     #[dynamic(default)]
     pub bell_dock_badge: bool,
 
+    /// Draw a thin bar along the bottom of each tab showing the progress
+    /// that its active pane reports via `OSC 9;4`
+    #[dynamic(default = "default_true")]
+    pub tab_progress_bar: bool,
+
+    /// Show a progress bar on the Dock icon while any pane reports
+    /// progress via `OSC 9;4`, averaging the panes reporting a percentage
+    #[dynamic(default)]
+    pub dock_progress_bar: bool,
+
+    /// Bounce the Dock icon when a bell or notification arrives while
+    /// Kaku is not the active application.  The request is cleared by
+    /// the system when Kaku is activated.
+    #[dynamic(default)]
+    pub bell_request_attention: bool,
+
+    /// Rules matched against pane titles and working directories.  When
+    /// a pane starts matching a rule, a toast is shown and its tab is
+    /// marked the same way as an unread bell.
+    #[dynamic(default)]
+    pub pane_watchlist: Vec<PaneWatchRule>,
+
+    /// Rules for highlighting lines shown by `kaku tail`
+    #[dynamic(default)]
+    pub tail_highlight_rules: Vec<TailHighlightRule>,
+
     #[dynamic(default)]
     pub canonicalize_pasted_newlines: Option<NewlineCanon>,
 
+    /// When true, control characters other than tab, carriage return
+    /// and newline are removed from pasted text before it is sent to
+    /// the pane, so that clipboard content cannot smuggle escape
+    /// sequences (such as a premature bracketed paste terminator)
+    /// into the running program.
+    #[dynamic(default = "default_true")]
+    pub sanitize_pasted_text: bool,
+
+    /// Pasting text that spans at least this many lines into a pane
+    /// that is not running a full screen application first shows a
+    /// confirmation overlay with a preview of the text.
+    /// Multi-line text that invokes `sudo` is always confirmed.
+    /// Set to 0 to never ask for confirmation.
+    #[dynamic(default = "default_paste_confirmation_lines")]
+    pub paste_confirmation_lines: usize,
+
     #[dynamic(default = "default_unicode_version")]
     pub unicode_version: u8,
 
@@ -969,11 +1453,23 @@ impl Config {
     /// so we defer producing the default list until someone explicitly
     /// asks for it
     pub fn ssh_domains(&self) -> Vec<SshDomain> {
-        if let Some(domains) = &self.ssh_domains {
-            domains.clone()
-        } else {
-            SshDomain::default_domains()
+        let Some(domains) = &self.ssh_domains else {
+            return SshDomain::default_domains();
+        };
+        let mut domains = domains.clone();
+        if self.ssh_domains_include_ssh_config_hosts {
+            for implicit in SshDomain::default_domains() {
+                let covered = domains.iter().any(|dom| {
+                    dom.name == implicit.name
+                        || (dom.remote_address == implicit.remote_address
+                            && dom.multiplexing == implicit.multiplexing)
+                });
+                if !covered {
+                    domains.push(implicit);
+                }
+            }
         }
+        domains
     }
 
     pub fn wsl_domains(&self) -> Vec<WslDomain> {
@@ -984,6 +1480,88 @@ impl Config {
         }
     }
 
+    /// Returns the encoding that should be used for a pane spawned into
+    /// the named domain when the spawn request didn't specify one.
+    /// SSH and WSL domains may override the global `default_encoding`;
+    /// the local domain always uses the global value.
+    pub fn default_encoding_for_domain(&self, domain_name: &str) -> PaneEncoding {
+        // Only consult explicitly configured domains; the implicit ssh
+        // domains are expensive to compute and never carry an encoding.
+        self.ssh_domains
+            .iter()
+            .flatten()
+            .find(|dom| dom.name == domain_name)
+            .and_then(|dom| dom.default_encoding)
+            .or_else(|| {
+                self.wsl_domains
+                    .iter()
+                    .flatten()
+                    .find(|dom| dom.name == domain_name)
+                    .and_then(|dom| dom.default_encoding)
+            })
+            .unwrap_or(self.default_encoding)
+    }
+
+    /// Returns the lines to write to a pane spawned into the named domain.
+    /// SSH and WSL domains may override the global `post_spawn_commands`.
+    /// Panes in unix and tls client domains are spawned by the mux
+    /// server, which applies its own configuration, so get none here.
+    pub fn post_spawn_commands_for_domain(&self, domain_name: &str) -> Vec<String> {
+        if self.unix_domains.iter().any(|dom| dom.name == domain_name)
+            || self.tls_clients.iter().any(|dom| dom.name == domain_name)
+        {
+            return vec![];
+        }
+        self.ssh_domains
+            .iter()
+            .flatten()
+            .find(|dom| dom.name == domain_name)
+            .and_then(|dom| dom.post_spawn_commands.clone())
+            .or_else(|| {
+                self.wsl_domains
+                    .iter()
+                    .flatten()
+                    .find(|dom| dom.name == domain_name)
+                    .and_then(|dom| dom.post_spawn_commands.clone())
+            })
+            .unwrap_or_else(|| self.post_spawn_commands.clone())
+    }
+
+    /// Sets the `term` and `set_environment_variables` configured for
+    /// the named SSH or WSL domain on `cmd`.  Variables that `cmd` already
+    /// sets explicitly, eg: from `SpawnCommand.set_environment_variables`,
+    /// are left alone.
+    pub fn apply_domain_environment(&self, cmd: &mut CommandBuilder, domain_name: &str) {
+        let (term, env) = match self
+            .ssh_domains
+            .iter()
+            .flatten()
+            .find(|dom| dom.name == domain_name)
+        {
+            Some(dom) => (dom.term.as_ref(), &dom.set_environment_variables),
+            None => match self
+                .wsl_domains
+                .iter()
+                .flatten()
+                .find(|dom| dom.name == domain_name)
+            {
+                Some(dom) => (dom.term.as_ref(), &dom.set_environment_variables),
+                None => return,
+            },
+        };
+
+        let explicit: HashSet<String> = cmd
+            .iter_extra_env_as_str()
+            .map(|(k, _)| k.to_string())
+            .collect();
+        let term = term.map(|term| ("TERM", term));
+        for (k, v) in env.iter().map(|(k, v)| (k.as_str(), v)).chain(term) {
+            if !explicit.contains(k) {
+                cmd.env(k, v);
+            }
+        }
+    }
+
     pub fn update_ulimit(&self) -> anyhow::Result<()> {
         #[cfg(unix)]
         {
@@ -1550,10 +2128,22 @@ impl Config {
         // Add some reasonable default font rules
         let reduced = self.font.reduce_first_font_to_family();
 
-        let italic = reduced.make_italic();
-
-        let bold = reduced.make_bold();
-        let bold_italic = bold.make_italic();
+        // font_bold, font_italic and font_bold_italic replace the
+        // styles that would otherwise be derived from font
+        let italic = self
+            .font_italic
+            .clone()
+            .unwrap_or_else(|| reduced.make_italic());
+
+        let bold = self
+            .font_bold
+            .clone()
+            .unwrap_or_else(|| reduced.make_bold());
+        let bold_italic = match (&self.font_bold_italic, &self.font_bold, &self.font_italic) {
+            (Some(style), _, _) => style.clone(),
+            (None, None, Some(_)) => italic.make_bold(),
+            (None, _, _) => bold.make_italic(),
+        };
 
         let half_bright = reduced.make_half_bright();
         let half_bright_italic = half_bright.make_italic();
@@ -1593,6 +2183,15 @@ impl Config {
             ..Default::default()
         });
 
+        let appearance_scheme = if is_dark_appearance() {
+            &cfg.color_scheme_dark
+        } else {
+            &cfg.color_scheme_light
+        };
+        if let Some(scheme_name) = appearance_scheme.clone() {
+            cfg.color_scheme = Some(scheme_name);
+        }
+
         // Only scan color scheme directories from disk when the user
         // references a scheme not already defined inline.  This avoids
         // directory enumeration + TOML parsing on every startup for users
@@ -1631,9 +2230,79 @@ impl Config {
             cfg.background.insert(0, bg);
         }
 
+        let accessibility = cfg.resolve_accessibility(system_accessibility());
+        cfg.apply_accessibility(accessibility);
+
         cfg
     }
 
+    /// Adjusts the appearance for the resolved accessibility settings
+    pub(crate) fn apply_accessibility(&mut self, accessibility: SystemAccessibility) {
+        if accessibility.reduce_transparency {
+            self.window_background_opacity = 1.0;
+            self.text_background_opacity = 1.0;
+            self.macos_window_background_blur = 0;
+            self.kde_window_background_blur = false;
+            for layer in &mut self.background {
+                layer.opacity = 1.0;
+            }
+        }
+
+        if accessibility.increase_contrast {
+            let ratio = self
+                .text_min_contrast_ratio
+                .unwrap_or(1.0)
+                .max(self.increase_contrast_min_ratio);
+            self.text_min_contrast_ratio.replace(ratio);
+        }
+    }
+
+    /// Resolves the accessibility options against the `system` settings,
+    /// which apply to the options that are not explicitly set
+    pub fn resolve_accessibility(&self, system: SystemAccessibility) -> SystemAccessibility {
+        SystemAccessibility {
+            reduce_transparency: self
+                .reduce_transparency
+                .unwrap_or(system.reduce_transparency),
+            increase_contrast: self.increase_contrast.unwrap_or(system.increase_contrast),
+            reduce_motion: self.reduce_motion.unwrap_or(system.reduce_motion),
+        }
+    }
+
+    /// Whether the color scheme is picked by the dark or light
+    /// appearance, so that the config has to be reloaded when it changes
+    pub fn depends_on_dark_appearance(&self) -> bool {
+        self.color_scheme_dark.is_some() || self.color_scheme_light.is_some()
+    }
+
+    /// Whether the config is adjusted by a system accessibility setting,
+    /// so that it has to be reloaded when those change.
+    /// `reduce_motion` is left out as it is checked as it is used.
+    pub fn follows_system_accessibility(&self) -> bool {
+        self.reduce_transparency.is_none() || self.increase_contrast.is_none()
+    }
+
+    /// Whether animations should be reduced, following the system
+    /// setting unless `reduce_motion` is set
+    pub fn reduce_motion(&self) -> bool {
+        self.resolve_accessibility(system_accessibility())
+            .reduce_motion
+    }
+
+    /// Whether the window should be drawn opaque, following the system
+    /// setting unless `reduce_transparency` is set
+    pub fn reduce_transparency(&self) -> bool {
+        self.resolve_accessibility(system_accessibility())
+            .reduce_transparency
+    }
+
+    /// Whether text contrast should be increased, following the system
+    /// setting unless `increase_contrast` is set
+    pub fn increase_contrast(&self) -> bool {
+        self.resolve_accessibility(system_accessibility())
+            .increase_contrast
+    }
+
     fn compute_color_scheme_dirs(&self) -> Vec<PathBuf> {
         let mut paths = self.color_scheme_dirs.clone();
         for dir in CONFIG_DIRS.iter() {
@@ -1799,7 +2468,12 @@ impl Config {
             }
         };
 
-        self.apply_cmd_defaults(&mut cmd, None, default_cwd);
+        // The environment defaults are left for the domain to apply when it
+        // spawns the command, as it needs to tell them apart from the
+        // variables that were set explicitly
+        if let Some(cwd) = default_cwd {
+            cmd.cwd(cwd);
+        }
 
         Ok(cmd)
     }
@@ -1822,6 +2496,14 @@ impl Config {
             }
         }
 
+        // Variables set explicitly on the command, eg: by a SpawnCommand or
+        // a domain, take precedence over the defaults applied below,
+        // including TERM and COLORTERM.
+        let explicit_env: Vec<(String, String)> = cmd
+            .iter_extra_env_as_str()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
         // Augment WSLENV so that TERM related environment propagates
         // across the win32/wsl boundary
         let mut wsl_env = std::env::var("WSLENV").ok();
@@ -1844,6 +2526,9 @@ impl Config {
                 cmd.env(k, v);
             }
         }
+        if let Some((_, v)) = explicit_env.iter().find(|(k, _)| k == "WSLENV") {
+            wsl_env.replace(v.clone());
+        }
 
         if wsl_env.is_some() || cfg!(windows) || crate::version::running_under_wsl() {
             let mut wsl_env = wsl_env.unwrap_or_default();
@@ -1856,8 +2541,13 @@ impl Config {
 
         #[cfg(unix)]
         cmd.umask(umask::UmaskSaver::saved_umask());
-        cmd.env("TERM", &self.term);
-        if self.term == "kaku" {
+        let term = explicit_env
+            .iter()
+            .find(|(k, _)| k == "TERM")
+            .map(|(_, v)| v.as_str())
+            .unwrap_or(&self.term);
+        cmd.env("TERM", term);
+        if term == "kaku" {
             if let Some(terminfo_dir) = bundled_terminfo_dir() {
                 if let Some(terminfo_dirs) =
                     merged_terminfo_dirs(std::env::var_os("TERMINFO_DIRS"), &terminfo_dir)
@@ -1871,6 +2561,12 @@ impl Config {
         // de-facto standard for identifying the terminal.
         cmd.env("TERM_PROGRAM", "Kaku");
         cmd.env("TERM_PROGRAM_VERSION", crate::wezterm_version());
+
+        for (k, v) in explicit_env {
+            if k != "WSLENV" {
+                cmd.env(k, v);
+            }
+        }
     }
 }
 
@@ -1935,10 +2631,30 @@ fn default_mux_output_parser_buffer_size() -> usize {
     128 * 1024
 }
 
+fn default_mux_reduced_fidelity_threshold_ms() -> Option<u64> {
+    Some(500)
+}
+
+fn default_mux_compression_level() -> i32 {
+    3
+}
+
 fn default_ratelimit_line_prefetches_per_second() -> u32 {
     50
 }
 
+fn default_paste_confirmation_lines() -> usize {
+    5
+}
+
+fn default_kitty_keyboard_flags() -> u16 {
+    0b11111
+}
+
+fn default_max_modify_other_keys_level() -> u8 {
+    2
+}
+
 fn default_cursor_blink_rate() -> u64 {
     800
 }
@@ -1961,6 +2677,10 @@ fn default_scrollback_lines() -> usize {
     3500
 }
 
+fn default_scrollback_spill_max_bytes() -> usize {
+    256 * 1024 * 1024
+}
+
 const MAX_SCROLLBACK_LINES: usize = 999_999_999;
 fn validate_scrollback_lines(value: &usize) -> Result<(), String> {
     if *value > MAX_SCROLLBACK_LINES {
@@ -1996,9 +2716,17 @@ pub fn default_hyperlink_rules() -> Vec<hyperlink::Rule> {
         // File paths: must start with /, ~/, ./ or ../
         // Supports file:line and file:line:col formats
         hyperlink::Rule::new(r"(?:~|\.\.?)?/[^\s\)\]\}>]+", "file://$0").unwrap(),
+        // Relative file locations as printed by compilers and linters,
+        // e.g. src/main.rs:12:5; the line number is required so that
+        // ordinary relative paths in prose are left alone.
+        hyperlink::Rule::new(r"\b[\w.\-]+(?:/[\w.\-]+)+:\d+(?::\d+)?\b", "file://$0").unwrap(),
     ]
 }
 
+fn default_front_end_fallback() -> Vec<FrontEndSelection> {
+    vec![FrontEndSelection::OpenGL, FrontEndSelection::Software]
+}
+
 fn default_harfbuzz_features() -> Vec<String> {
     ["kern", "liga", "clig"]
         .iter()
@@ -2093,6 +2821,10 @@ fn default_gui_startup_args() -> Vec<String> {
     vec!["start".to_string()]
 }
 
+fn default_encoding_error_event_threshold() -> usize {
+    16
+}
+
 fn default_pane_encoding() -> PaneEncoding {
     PaneEncoding::Utf8
 }
@@ -2166,6 +2898,10 @@ fn default_quit_when_all_windows_are_closed() -> bool {
     }
 }
 
+fn default_presentation_mode_font_scale() -> f64 {
+    1.5
+}
+
 fn default_alternate_buffer_wheel_scroll_speed() -> u8 {
     3
 }
@@ -2187,6 +2923,20 @@ fn default_enq_answerback() -> String {
     "".to_string()
 }
 
+fn validate_device_attributes(value: &Option<String>) -> Result<(), String> {
+    match value {
+        Some(value)
+            if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit() || c == ';') =>
+        {
+            Err(format!(
+                "device attributes {value:?} must be a non-empty list of \
+                 numbers separated by `;`"
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
 fn default_tab_max_width() -> usize {
     16
 }
@@ -2256,6 +3006,35 @@ const fn default_reverse_video_cursor_min_contrast() -> f32 {
     2.5
 }
 
+#[derive(FromDynamic, ToDynamic, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PaneWatchRule {
+    /// Regex matched against the pane title
+    #[dynamic(default)]
+    pub title: Option<String>,
+    /// Regex matched against the pane's current working directory
+    #[dynamic(default)]
+    pub cwd: Option<String>,
+    /// Toast text; defaults to the pane title
+    #[dynamic(default)]
+    pub message: Option<String>,
+}
+
+#[derive(FromDynamic, ToDynamic, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TailHighlightRule {
+    /// Regex matched against each line
+    pub regex: String,
+    /// Color of the matching text; defaults to red
+    #[dynamic(default)]
+    pub color: Option<RgbaColor>,
+    /// Color the whole line rather than just the matching text
+    #[dynamic(default)]
+    pub whole_line: bool,
+    /// Stop following the file when a new line matches, so that the
+    /// line stays in view
+    #[dynamic(default)]
+    pub pause: bool,
+}
+
 #[derive(FromDynamic, ToDynamic, Clone, Copy, Debug)]
 pub struct WindowPadding {
     #[dynamic(try_from = "crate::units::PixelUnit", default = "default_one_cell")]
@@ -2310,6 +3089,14 @@ pub enum NewlineCanon {
     CarriageReturnAndLineFeed,
 }
 
+#[derive(FromDynamic, ToDynamic, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CursorStyleEscapes {
+    #[default]
+    Allow,
+    SteadyOnly,
+    Ignore,
+}
+
 #[derive(FromDynamic, ToDynamic, Clone, Copy, Debug, Default)]
 pub enum WindowCloseConfirmation {
     #[default]
@@ -2486,6 +3273,43 @@ fn validate_row_or_col(value: &u16) -> Result<(), String> {
     }
 }
 
+fn validate_min_contrast_ratio(value: &Option<f32>) -> Result<(), String> {
+    match value {
+        Some(ratio) if !(1.0..=21.0).contains(ratio) => Err(format!(
+            "Illegal value {ratio} for text_min_contrast_ratio; it must be between 1 and 21!"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn default_increase_contrast_min_ratio() -> f32 {
+    7.0
+}
+
+fn validate_increase_contrast_min_ratio(value: &f32) -> Result<(), String> {
+    if (1.0..=21.0).contains(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Illegal value {value} for increase_contrast_min_ratio; it must be between 1 and 21!"
+        ))
+    }
+}
+
+fn default_cursor_color_min_contrast() -> f32 {
+    1.5
+}
+
+fn validate_cursor_color_min_contrast(value: &f32) -> Result<(), String> {
+    if (1.0..=21.0).contains(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Illegal value {value} for cursor_color_min_contrast; it must be between 1 and 21!"
+        ))
+    }
+}
+
 fn validate_line_height(value: &f64) -> Result<(), String> {
     if *value <= 0.0 {
         Err(format!(
@@ -2525,3 +3349,94 @@ fn default_macos_global_hotkey() -> Option<KeyNoAction> {
 fn default_colr_rasterizer() -> FontRasterizerSelection {
     FontRasterizerSelection::Harfbuzz
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spawn_environment_precedence() {
+        let mut config = Config::default_config();
+        config
+            .set_environment_variables
+            .insert("FOO".to_string(), "config".to_string());
+        config
+            .set_environment_variables
+            .insert("BAR".to_string(), "config".to_string());
+        config.ssh_domains = Some(vec![SshDomain {
+            name: "remote".to_string(),
+            term: Some("kaku".to_string()),
+            set_environment_variables: [
+                ("FOO".to_string(), "domain".to_string()),
+                ("BAR".to_string(), "domain".to_string()),
+            ]
+            .into(),
+            ..SshDomain::default()
+        }]);
+
+        let mut cmd = CommandBuilder::new_default_prog();
+        cmd.env("BAR", "spawn");
+        cmd.env("COLORTERM", "24bit");
+        config.apply_domain_environment(&mut cmd, "remote");
+        config.apply_cmd_defaults(&mut cmd, None, None);
+
+        assert_eq!(cmd.get_env("FOO"), Some(OsStr::new("domain")));
+        assert_eq!(cmd.get_env("BAR"), Some(OsStr::new("spawn")));
+        assert_eq!(cmd.get_env("TERM"), Some(OsStr::new("kaku")));
+        assert_eq!(cmd.get_env("COLORTERM"), Some(OsStr::new("24bit")));
+
+        let mut cmd = CommandBuilder::new_default_prog();
+        config.apply_domain_environment(&mut cmd, "local");
+        config.apply_cmd_defaults(&mut cmd, None, None);
+        assert_eq!(cmd.get_env("FOO"), Some(OsStr::new("config")));
+        assert_eq!(cmd.get_env("TERM"), Some(OsStr::new(config.term.as_str())));
+        assert_eq!(cmd.get_env("COLORTERM"), Some(OsStr::new("truecolor")));
+    }
+
+    #[test]
+    fn build_prog_leaves_the_environment_to_the_domain() {
+        let mut config = Config::default_config();
+        config.ssh_domains = Some(vec![SshDomain {
+            name: "remote".to_string(),
+            term: Some("xterm-kitty".to_string()),
+            set_environment_variables: [("FOO".to_string(), "domain".to_string())].into(),
+            ..SshDomain::default()
+        }]);
+        config
+            .set_environment_variables
+            .insert("FOO".to_string(), "config".to_string());
+
+        let mut cmd = config
+            .build_prog(Some(vec![OsStr::new("top")]), None, None)
+            .unwrap();
+        assert_eq!(cmd.iter_extra_env_as_str().count(), 0);
+
+        config.apply_domain_environment(&mut cmd, "remote");
+        config.apply_cmd_defaults(&mut cmd, None, None);
+        assert_eq!(cmd.get_env("FOO"), Some(OsStr::new("domain")));
+        assert_eq!(cmd.get_env("TERM"), Some(OsStr::new("xterm-kitty")));
+    }
+
+    #[test]
+    fn per_style_font_families() {
+        let mut config = Config::default_config();
+        config.font_bold = Some(TextStyle {
+            font: vec![crate::FontAttributes::new("Bold Family")],
+            foreground: None,
+        });
+        let config = config.compute_extra_defaults(None);
+
+        let family = |italic, intensity| {
+            config
+                .font_rules
+                .iter()
+                .find(|rule| rule.italic == Some(italic) && rule.intensity == Some(intensity))
+                .map(|rule| rule.font.font[0].family.clone())
+                .unwrap()
+        };
+        use wezterm_term::Intensity;
+        assert_eq!(family(false, Intensity::Bold), "Bold Family");
+        assert_eq!(family(true, Intensity::Bold), "Bold Family");
+        assert_ne!(family(true, Intensity::Normal), "Bold Family");
+    }
+}
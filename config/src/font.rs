@@ -218,6 +218,31 @@ impl FontWeight {
     }
 }
 
+/// Which styles may be synthesized, by emboldening or slanting the
+/// glyphs of another face, when a font has no face for them
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, FromDynamic, ToDynamic)]
+pub enum FontSynthesis {
+    /// Bold, italic and half bright may all be synthesized
+    #[default]
+    All,
+    /// Only bold and half bright may be synthesized
+    Bold,
+    /// Only italic may be synthesized
+    Italic,
+    /// Nothing is synthesized; the closest face is used as-is
+    None,
+}
+
+impl FontSynthesis {
+    pub fn allows_bold(self) -> bool {
+        matches!(self, Self::All | Self::Bold)
+    }
+
+    pub fn allows_italic(self) -> bool {
+        matches!(self, Self::All | Self::Italic)
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, FromDynamic, ToDynamic)]
 pub enum DisplayPixelGeometry {
     #[default]
@@ -380,13 +405,45 @@ pub struct FontAttributes {
     pub freetype_render_target: Option<FreeTypeLoadTarget>,
     #[dynamic(default)]
     pub freetype_load_flags: Option<FreeTypeLoadFlags>,
+    /// Scales the size of this font relative to the configured
+    /// font_size; useful for making a fallback font, such as a CJK
+    /// font, match the apparent size of the primary font.
     #[dynamic(default)]
     pub scale: Option<NotNan<f64>>,
+    /// Moves the glyphs of this font up by this fraction of the
+    /// configured font_size, or down when negative; useful for making
+    /// the baseline of a fallback font line up with the primary font.
+    /// It has no effect on the first font in a list.
+    #[dynamic(default)]
+    pub vertical_offset: Option<NotNan<f64>>,
     #[dynamic(default)]
     pub assume_emoji_presentation: Option<bool>,
+    /// Which styles may be synthesized for this font when it has
+    /// no face for them; overrides `font_synthesis`
+    #[dynamic(default)]
+    pub synthesis: Option<FontSynthesis>,
+    /// Design coordinates for the axes of a variable font, keyed
+    /// by axis tag, such as `{ wght = 450, wdth = 90 }`.
+    /// Axes that are not listed keep the values of the selected
+    /// named instance.
+    #[dynamic(default, validate = "validate_variation_axes")]
+    pub variation_axes: Option<BTreeMap<String, NotNan<f64>>>,
 }
 impl_lua_conversion_dynamic!(FontAttributes);
 
+pub(crate) fn validate_variation_axes(
+    axes: &Option<BTreeMap<String, NotNan<f64>>>,
+) -> Result<(), String> {
+    for tag in axes.iter().flat_map(|axes| axes.keys()) {
+        if tag.len() != 4 || !tag.bytes().all(|b| b.is_ascii_graphic() || b == b' ') {
+            return Err(format!(
+                "invalid variation axis tag `{tag}`; axis tags are 4 ASCII characters, such as `wght`"
+            ));
+        }
+    }
+    Ok(())
+}
+
 impl std::fmt::Display for FontAttributes {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         write!(
@@ -411,7 +468,24 @@ impl FontAttributes {
             freetype_render_target: None,
             freetype_load_flags: None,
             scale: None,
+            vertical_offset: None,
             assume_emoji_presentation: None,
+            synthesis: None,
+            variation_axes: None,
+        }
+    }
+
+    /// When `variation_axes` lists `tag`, changes its value; this is
+    /// how the bold, half bright and italic styles derived from a font
+    /// that pins an axis pick a matching design instead of inheriting
+    /// the pinned value
+    fn set_variation_axis_if_present(&mut self, tag: &str, value: u16) {
+        if let Some(axis) = self
+            .variation_axes
+            .as_mut()
+            .and_then(|axes| axes.get_mut(tag))
+        {
+            *axis = NotNan::from(value);
         }
     }
 
@@ -428,7 +502,10 @@ impl FontAttributes {
             freetype_render_target: None,
             freetype_load_flags: None,
             scale: None,
+            vertical_offset: None,
             assume_emoji_presentation: None,
+            synthesis: None,
+            variation_axes: None,
         }
     }
 }
@@ -447,7 +524,10 @@ impl Default for FontAttributes {
             freetype_render_target: None,
             freetype_load_flags: None,
             scale: None,
+            vertical_offset: None,
             assume_emoji_presentation: None,
+            synthesis: None,
+            variation_axes: None,
         }
     }
 }
@@ -547,6 +627,7 @@ impl TextStyle {
                     let mut attr = attr.clone();
                     attr.weight = attr.weight.bolder();
                     attr.is_synthetic = true;
+                    attr.set_variation_axis_if_present("wght", attr.weight.to_opentype_weight());
                     attr
                 })
                 .collect(),
@@ -563,6 +644,7 @@ impl TextStyle {
                     let mut attr = attr.clone();
                     attr.weight = attr.weight.lighter();
                     attr.is_synthetic = true;
+                    attr.set_variation_axis_if_present("wght", attr.weight.to_opentype_weight());
                     attr
                 })
                 .collect(),
@@ -580,6 +662,7 @@ impl TextStyle {
                     let mut attr = attr.clone();
                     attr.style = FontStyle::Italic;
                     attr.is_synthetic = true;
+                    attr.set_variation_axis_if_present("ital", 1);
                     attr
                 })
                 .collect(),
@@ -746,4 +829,32 @@ mod test {
             assert_eq!(style.font[0].family, "Inconsolata");
         }
     }
+
+    #[test]
+    fn derived_styles_adjust_variation_axes() {
+        let mut attr = FontAttributes::new("Recursive");
+        let mut axes = BTreeMap::new();
+        axes.insert("wght".to_string(), NotNan::from(450u16));
+        axes.insert("CASL".to_string(), NotNan::from(1u16));
+        attr.variation_axes = Some(axes);
+        let style = TextStyle {
+            font: vec![attr],
+            foreground: None,
+        };
+
+        let axis = |style: &TextStyle, tag: &str| {
+            style.font[0]
+                .variation_axes
+                .as_ref()
+                .and_then(|axes| axes.get(tag))
+                .map(|value| **value)
+        };
+
+        let bold = style.make_bold();
+        assert_eq!(axis(&bold, "wght"), Some(600.));
+        assert_eq!(axis(&bold, "CASL"), Some(1.));
+        assert_eq!(axis(&style.make_half_bright(), "wght"), Some(200.));
+        // Axes that aren't pinned are left for the font to pick
+        assert_eq!(axis(&style.make_italic(), "ital"), None);
+    }
 }
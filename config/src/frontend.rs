@@ -22,6 +22,23 @@ pub struct GpuInfo {
 }
 impl_lua_conversion_dynamic!(GpuInfo);
 
+impl GpuInfo {
+    /// Returns true if the name or backend of this adapter contains any
+    /// of the entries in `allowlist`, ignoring case.
+    /// An empty allowlist permits every adapter.
+    pub fn is_allowed_by(&self, allowlist: &[String]) -> bool {
+        if allowlist.is_empty() {
+            return true;
+        }
+        let name = self.name.to_lowercase();
+        let backend = self.backend.to_lowercase();
+        allowlist.iter().any(|entry| {
+            let entry = entry.to_lowercase();
+            name.contains(&entry) || backend.contains(&entry)
+        })
+    }
+}
+
 impl ToString for GpuInfo {
     fn to_string(&self) -> String {
         let mut result = format!(
@@ -55,3 +72,19 @@ impl Default for WebGpuPowerPreference {
         Self::LowPower
     }
 }
+
+/// How the colors produced by the renderer are interpreted by the
+/// window system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromDynamic, ToDynamic, Default)]
+pub enum WindowColorSpace {
+    /// Colors are sRGB, and are converted to the color profile of the
+    /// display.  This matches most other terminals.
+    #[default]
+    Srgb,
+    /// Colors are Display P3, which makes the full gamut of wide-gamut
+    /// displays available, at the cost of more saturated colors than
+    /// terminals that assume sRGB.
+    DisplayP3,
+    /// Colors are passed to the display without any conversion
+    Display,
+}
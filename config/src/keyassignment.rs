@@ -367,7 +367,10 @@ impl SpawnCommand {
                     .to_string(),
             );
         }
-        for (k, v) in cmd.iter_full_env_as_str() {
+        // Only the variables set on the command itself; the inherited
+        // environment would otherwise take precedence over the defaults
+        // applied when it is spawned
+        for (k, v) in cmd.iter_extra_env_as_str() {
             set_environment_variables.insert(k.to_string(), v.to_string());
         }
         let cwd = match cmd.get_cwd() {
@@ -651,6 +654,26 @@ fn default_message() -> String {
     "🛑 Really continue?".to_string()
 }
 
+#[derive(Debug, Clone, PartialEq, FromDynamic, ToDynamic)]
+pub struct PaneOutputLogging {
+    /// Where to write the log; a leading `~/` refers to the home directory.
+    /// The timing information is written alongside it with a `.timing`
+    /// extension appended.
+    pub path: String,
+    /// Also record the input that is sent to the pane
+    #[dynamic(default)]
+    pub include_input: bool,
+}
+
+impl PaneOutputLogging {
+    pub fn resolved_path(&self) -> std::path::PathBuf {
+        match self.path.strip_prefix("~/") {
+            Some(rest) => crate::HOME_DIR.join(rest),
+            None => self.path.clone().into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, FromDynamic, ToDynamic)]
 pub enum KeyAssignment {
     SpawnTab(SpawnTabDomain),
@@ -684,25 +707,62 @@ pub enum KeyAssignment {
     },
     ReopenLastClosedTab,
     ReloadConfiguration,
+    /// Fetches updates for every plugin loaded via `wezterm.plugin.require`
+    UpdatePlugins,
     MoveTabRelative(isize),
     MoveTab(usize),
     ScrollByPage(NotNan<f64>),
     ScrollByLine(isize),
     ScrollByCurrentEventWheelDelta,
     ScrollToPrompt(isize),
+    /// Like `ScrollToPrompt`, but only stops at prompts whose command
+    /// exited with a non-zero status
+    ScrollToFailedPrompt(isize),
     ScrollToTop,
     ScrollToBottom,
     ShowTabNavigator,
+    /// Shows a searchable grid previewing every tab in the window
+    ShowTabOverview,
+    /// Covers the window with the session lock screen
+    LockSession,
+    /// Shows the commands recorded by `command_history_log` for the
+    /// workspace of the window
+    ShowCommandHistory,
+    /// Fuzzy searches the commands recorded by `command_history_log`
+    /// in every workspace
+    SearchCommandHistory,
     ShowDebugOverlay,
+    ShowPaneProcessInfo,
+    /// Shows the most recent raw output of the pane, this many KiB of it
+    ShowPaneHexDump(usize),
+    ShowPaneLatency,
+    ToggleDebugHud,
+    TogglePresentationMode,
+    /// Tints the active tab, or removes its tint when None
+    SetTabColor(Option<crate::RgbaColor>),
+    /// Adds the active tab to the named group, or removes it from its
+    /// group when None
+    SetTabGroup(Option<String>),
+    /// Collapses or expands the active tab's group in the tab bar
+    ToggleTabGroupCollapsed,
+    /// Closes the idle tabs in the active tab's group
+    CloseTabGroup,
+    /// Moves every tab in the active tab's group into a new window
+    MoveTabGroupToNewWindow,
     HideApplication,
     QuitApplication,
     SpawnCommandInNewTab(SpawnCommand),
+    /// Spawns a copy of the active tab's layout, with the same working
+    /// directories, in another domain
+    RecreateTabInDomain(SpawnTabDomain),
     SpawnCommandInNewWindow(SpawnCommand),
     SplitHorizontal(SpawnCommand),
     SplitVertical(SpawnCommand),
     ShowLauncher,
     ShowLauncherArgs(LauncherActionArgs),
     ClearScrollback(ScrollbackEraseMode),
+    /// Overrides `scrollback_lines` for the active pane
+    SetPaneScrollbackLines(usize),
     Search(Pattern),
     ActivateCopyMode,
 
@@ -718,8 +778,15 @@ pub enum KeyAssignment {
     ActivatePaneDirection(PaneDirection),
     ActivatePaneByIndex(usize),
     TogglePaneZoomState,
+    /// Stops reading output from the active pane until toggled again
+    TogglePaneOutputPause,
     SetPaneZoomState(bool),
     SetPaneEncoding(PaneEncoding),
+    SetPaneInputEncoding(PaneEncoding),
+    SetPaneOutputEncoding(PaneEncoding),
+    ReinterpretScrollbackWithEncoding(PaneEncoding),
+    TogglePaneOutputLogging(PaneOutputLogging),
+    TogglePredictiveEcho,
     CloseCurrentPane {
         confirm: bool,
     },
@@ -758,6 +825,8 @@ pub enum KeyAssignment {
     TogglePaneSplitDirection,
     SplitPane(SplitPane),
     PaneSelect(PaneSelectArguments),
+    /// Labels every visible pane and tab; typing a label focuses it
+    ShowPaneJumpHints,
     CharSelect(CharSelectArguments),
 
     ResetTerminal,
@@ -843,6 +912,8 @@ pub enum CopyModeAssignment {
     JumpBackward { prev_char: bool },
     JumpAgain,
     JumpReverse,
+    AddMatchToSelectionAndMoveToNext,
+    ClearMatchSelections,
 }
 
 pub type KeyTable = HashMap<(KeyCode, Modifiers), KeyTableEntry>;
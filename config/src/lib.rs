@@ -21,6 +21,7 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use wezterm_dynamic::{FromDynamic, FromDynamicOptions, ToDynamic, UnknownFieldAction, Value};
 use wezterm_term::UnicodeVersion;
 
+mod accessibility;
 mod background;
 mod bell;
 mod cell;
@@ -46,6 +47,7 @@ pub mod window;
 mod wsl;
 
 pub use crate::config::*;
+pub use accessibility::*;
 pub use background::*;
 pub use bell::*;
 pub use cell::*;
@@ -847,6 +849,44 @@ mod tests {
         );
         assert_eq!(dirs, vec![PathBuf::from("/custom/config").join("kaku")]);
     }
+
+    #[test]
+    fn explicit_accessibility_options_override_system() {
+        let system = SystemAccessibility {
+            reduce_transparency: true,
+            increase_contrast: true,
+            reduce_motion: true,
+        };
+
+        let mut config = Config::default_config();
+        config.window_background_opacity = 0.5;
+        assert_eq!(config.resolve_accessibility(system), system);
+        let mut adjusted = config.clone();
+        adjusted.apply_accessibility(config.resolve_accessibility(system));
+        assert_eq!(adjusted.window_background_opacity, 1.0);
+
+        config.reduce_transparency = Some(false);
+        config.increase_contrast = Some(false);
+        config.reduce_motion = Some(false);
+        assert_eq!(
+            config.resolve_accessibility(system),
+            SystemAccessibility::default()
+        );
+        let mut adjusted = config.clone();
+        adjusted.apply_accessibility(config.resolve_accessibility(system));
+        assert_eq!(adjusted.window_background_opacity, 0.5);
+
+        config.reduce_transparency = Some(true);
+        config.reduce_motion = Some(true);
+        assert_eq!(
+            config.resolve_accessibility(SystemAccessibility::default()),
+            SystemAccessibility {
+                reduce_transparency: true,
+                increase_contrast: false,
+                reduce_motion: true,
+            }
+        );
+    }
 }
 
 pub fn set_config_file_override(path: &Path) {
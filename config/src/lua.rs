@@ -1,14 +1,16 @@
 use crate::exec_domain::{ExecDomain, ValueOrFunc};
+use crate::font::validate_variation_axes;
 use crate::keyassignment::KeyAssignment;
 use crate::{
-    Config, FontAttributes, FontStretch, FontStyle, FontWeight, FreeTypeLoadTarget, RgbaColor,
-    TextStyle,
+    Config, FontAttributes, FontStretch, FontStyle, FontSynthesis, FontWeight, FreeTypeLoadTarget,
+    RgbaColor, TextStyle,
 };
 use anyhow::{anyhow, Context};
 use luahelper::{from_lua_value_dynamic, lua_value_to_dynamic, to_lua};
 use mlua::{FromLua, IntoLuaMulti, Lua, Table, Value, Variadic};
 use ordered_float::NotNan;
 use portable_pty::CommandBuilder;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::path::Path;
 use std::sync::Mutex;
@@ -19,6 +21,7 @@ use wezterm_dynamic::{
 pub use mlua;
 
 static LUA_REGISTRY_USER_CALLBACK_COUNT: &str = "wezterm-user-callback-count";
+static LUA_REGISTRY_USER_COMMANDS: &str = "wezterm-user-commands";
 
 pub type SetupFunc = fn(&Lua) -> anyhow::Result<()>;
 
@@ -357,6 +360,8 @@ end
 
         lua.set_named_registry_value(LUA_REGISTRY_USER_CALLBACK_COUNT, 0)?;
         wezterm_mod.set("action_callback", lua.create_function(action_callback)?)?;
+        lua.set_named_registry_value(LUA_REGISTRY_USER_COMMANDS, lua.create_table()?)?;
+        wezterm_mod.set("register_command", lua.create_function(register_command)?)?;
         wezterm_mod.set("exec_domain", lua.create_function(exec_domain)?)?;
 
         wezterm_mod.set("utf16_to_utf8", lua.create_function(utf16_to_utf8)?)?;
@@ -543,7 +548,13 @@ struct LuaFontAttributes {
     #[dynamic(default)]
     pub scale: Option<NotNan<f64>>,
     #[dynamic(default)]
+    pub vertical_offset: Option<NotNan<f64>>,
+    #[dynamic(default)]
     pub assume_emoji_presentation: Option<bool>,
+    #[dynamic(default)]
+    pub synthesis: Option<FontSynthesis>,
+    #[dynamic(default, validate = "validate_variation_axes")]
+    pub variation_axes: Option<BTreeMap<String, NotNan<f64>>>,
 }
 impl<'lua> FromLua<'lua> for LuaFontAttributes {
     fn from_lua(value: Value<'lua>, _lua: &'lua Lua) -> Result<Self, mlua::Error> {
@@ -630,7 +641,10 @@ fn font<'lua>(
                 None => None,
             },
             scale: attrs.scale,
+            vertical_offset: attrs.vertical_offset,
             assume_emoji_presentation: attrs.assume_emoji_presentation,
+            synthesis: attrs.synthesis,
+            variation_axes: attrs.variation_axes,
         }));
 
     Ok(text_style)
@@ -679,7 +693,10 @@ fn font_with_fallback<'lua>(
                     None => None,
                 },
                 scale: attrs.scale,
+                vertical_offset: attrs.vertical_offset,
                 assume_emoji_presentation: attrs.assume_emoji_presentation,
+                synthesis: attrs.synthesis,
+                variation_axes: attrs.variation_axes,
             }));
     }
 
@@ -699,6 +716,51 @@ fn action_callback<'lua>(lua: &'lua Lua, callback: mlua::Function) -> mlua::Resu
     Ok(KeyAssignment::EmitEvent(user_event_id))
 }
 
+/// This implements `wezterm.register_command`, which adds an entry to
+/// the command palette and launcher.  The action may be either a key
+/// assignment or a lua function, which is wrapped as though it had been
+/// passed to `wezterm.action_callback`.
+///
+/// ```lua
+/// wezterm.register_command {
+///   label = "Say hello",
+///   doc = "Logs a greeting",
+///   action = function(window, pane)
+///     wezterm.log_info("hello from " .. pane:pane_id())
+///   end,
+/// }
+/// ```
+fn register_command<'lua>(lua: &'lua Lua, command: Table<'lua>) -> mlua::Result<()> {
+    let label: String = command.get("label")?;
+    let doc: Option<String> = command.get("doc")?;
+    let icon: Option<String> = command.get("icon")?;
+    let action = match command.get::<_, Value>("action")? {
+        Value::Function(callback) => action_callback(lua, callback)?,
+        Value::Nil => {
+            return Err(mlua::Error::external(format!(
+                "register_command: command `{label}` has no action"
+            )));
+        }
+        value => KeyAssignment::from_lua(value, lua)?,
+    };
+
+    let entry = lua.create_table()?;
+    entry.set("brief", label)?;
+    entry.set("doc", doc)?;
+    entry.set("icon", icon)?;
+    entry.set("action", action)?;
+
+    let commands: Table = lua.named_registry_value(LUA_REGISTRY_USER_COMMANDS)?;
+    commands.push(entry)?;
+    Ok(())
+}
+
+/// Returns the list of commands that were registered via
+/// `wezterm.register_command`, in registration order.
+pub fn registered_commands<'lua>(lua: &'lua Lua) -> mlua::Result<Value<'lua>> {
+    lua.named_registry_value(LUA_REGISTRY_USER_COMMANDS)
+}
+
 fn exec_domain<'lua>(
     lua: &'lua Lua,
     (name, fixup_command, label): (String, mlua::Function, Option<mlua::Value>),
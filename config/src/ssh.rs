@@ -1,4 +1,5 @@
 use crate::config::validate_domain_name;
+use crate::keyassignment::PaneEncoding;
 use crate::*;
 use luahelper::impl_lua_conversion_dynamic;
 use std::fmt::Display;
@@ -61,6 +62,11 @@ pub struct SshDomain {
     #[dynamic(default)]
     pub no_agent_auth: bool,
 
+    /// Whether to forward the local ssh agent to the remote host.
+    /// When not set, the `ForwardAgent` option from the ssh config
+    /// files is used.
+    pub forward_agent: Option<bool>,
+
     /// The username to use for authenticating with the remote host
     pub username: Option<String>,
 
@@ -81,6 +87,13 @@ pub struct SshDomain {
     #[dynamic(default)]
     pub overlay_lag_indicator: bool,
 
+    /// If true, and the connection to the multiplexer is lost for a reason
+    /// other than the remote end closing it, keep the panes open and try to
+    /// reconnect with an increasing delay between attempts.
+    /// Reconnecting may prompt for authentication again.
+    #[dynamic(default)]
+    pub reconnect_automatically: bool,
+
     /// The path to the wezterm binary on the remote host
     pub remote_wezterm_path: Option<String>,
     /// Override the entire `wezterm cli proxy` invocation that would otherwise
@@ -104,6 +117,21 @@ pub struct SshDomain {
 
     #[dynamic(default)]
     pub assume_shell: Shell,
+
+    /// The encoding used by panes spawned in this domain when no
+    /// explicit encoding is requested.  Falls back to `default_encoding`.
+    pub default_encoding: Option<PaneEncoding>,
+
+    /// Lines written to panes spawned in this domain once they have
+    /// started.  Falls back to `post_spawn_commands`.
+    pub post_spawn_commands: Option<Vec<String>>,
+    /// The value of `TERM` for panes spawned in this domain.
+    /// Falls back to `term`.
+    pub term: Option<String>,
+    /// Environment variables set for panes spawned in this domain,
+    /// in addition to and taking precedence over `set_environment_variables`.
+    #[dynamic(default)]
+    pub set_environment_variables: HashMap<String, String>,
 }
 impl_lua_conversion_dynamic!(SshDomain);
 
@@ -1,8 +1,9 @@
 //! Bridge our gui config into the terminal crate configuration
 
-use crate::{configuration, ConfigHandle, NewlineCanon};
+use crate::{configuration, ConfigHandle, CursorStyleEscapes, NewlineCanon};
 use std::sync::Mutex;
 use termwiz::cell::UnicodeVersion;
+use wezterm_input_types::KittyKeyboardFlags;
 use wezterm_term::color::ColorPalette;
 use wezterm_term::config::BidiMode;
 
@@ -52,10 +53,22 @@ impl wezterm_term::TerminalConfiguration for TermConfig {
         self.configuration().scrollback_lines
     }
 
+    fn scrollback_spill_to_disk(&self) -> bool {
+        self.configuration().scrollback_spill_to_disk
+    }
+
+    fn scrollback_spill_max_bytes(&self) -> usize {
+        self.configuration().scrollback_spill_max_bytes
+    }
+
     fn enable_csi_u_key_encoding(&self) -> bool {
         self.configuration().enable_csi_u_key_encoding
     }
 
+    fn max_modify_other_keys_level(&self) -> i64 {
+        self.configuration().max_modify_other_keys_level.into()
+    }
+
     fn color_palette(&self) -> ColorPalette {
         let client_palette = self.client_palette.lock().unwrap();
         if let Some(p) = client_palette.as_ref().cloned() {
@@ -66,12 +79,28 @@ impl wezterm_term::TerminalConfiguration for TermConfig {
         config.resolved_palette.clone().into()
     }
 
+    fn alternate_buffer_wheel_sends_arrow_keys(&self) -> bool {
+        self.configuration().alternate_buffer_wheel_sends_arrow_keys
+    }
+
+    fn rewrap_on_resize(&self) -> bool {
+        self.configuration().rewrap_on_resize
+    }
+
     fn alternate_buffer_wheel_scroll_speed(&self) -> u8 {
         self.configuration().alternate_buffer_wheel_scroll_speed
     }
 
     fn enq_answerback(&self) -> String {
-        configuration().enq_answerback.clone()
+        self.configuration().enq_answerback.clone()
+    }
+
+    fn primary_device_attributes(&self) -> Option<String> {
+        self.configuration().primary_device_attributes.clone()
+    }
+
+    fn secondary_device_attributes(&self) -> Option<String> {
+        self.configuration().secondary_device_attributes.clone()
     }
 
     fn enable_kitty_graphics(&self) -> bool {
@@ -82,10 +111,46 @@ impl wezterm_term::TerminalConfiguration for TermConfig {
         self.configuration().enable_title_reporting
     }
 
+    fn enable_focus_reporting(&self) -> bool {
+        self.configuration().enable_focus_reporting
+    }
+
+    fn enable_in_band_resize(&self) -> bool {
+        self.configuration().enable_in_band_resize
+    }
+
+    fn enable_window_size_reporting(&self) -> bool {
+        self.configuration().enable_window_size_reporting
+    }
+
+    fn cursor_style_escapes(&self) -> wezterm_term::config::CursorStyleEscapes {
+        match self.configuration().cursor_style_escapes {
+            CursorStyleEscapes::Allow => wezterm_term::config::CursorStyleEscapes::Allow,
+            CursorStyleEscapes::SteadyOnly => wezterm_term::config::CursorStyleEscapes::SteadyOnly,
+            CursorStyleEscapes::Ignore => wezterm_term::config::CursorStyleEscapes::Ignore,
+        }
+    }
+
+    fn allow_cursor_color_escapes(&self) -> bool {
+        self.configuration().allow_cursor_color_escapes
+    }
+
+    fn cursor_color_min_contrast(&self) -> f32 {
+        self.configuration().cursor_color_min_contrast
+    }
+
+    fn allow_cursor_hiding(&self) -> bool {
+        self.configuration().allow_cursor_hiding
+    }
+
     fn enable_kitty_keyboard(&self) -> bool {
         self.configuration().enable_kitty_keyboard
     }
 
+    fn kitty_keyboard_flags(&self) -> KittyKeyboardFlags {
+        KittyKeyboardFlags::from_bits_truncate(self.configuration().kitty_keyboard_flags)
+    }
+
     fn canonicalize_pasted_newlines(&self) -> wezterm_term::config::NewlineCanon {
         match self.configuration().canonicalize_pasted_newlines {
             None => wezterm_term::config::NewlineCanon::default(),
@@ -7,3 +7,16 @@ pub enum WindowLevel {
     Normal = 0,
     AlwaysOnTop = 3,
 }
+
+/// How a locked session (see `session_lock_idle_minutes` and the
+/// `LockSession` key assignment) is unlocked.
+#[derive(Debug, Default, Clone, ToDynamic, PartialEq, Eq, FromDynamic)]
+pub enum SessionUnlockMethod {
+    /// Pressing Enter unlocks the session
+    #[default]
+    Enter,
+    /// The passphrase must be typed followed by Enter.
+    /// The passphrase is held in the config in plain text: the lock
+    /// hides the screen from onlookers, it is not a security boundary.
+    Passphrase(String),
+}
@@ -1,4 +1,5 @@
 use crate::config::validate_domain_name;
+use crate::keyassignment::PaneEncoding;
 use crate::*;
 use luahelper::impl_lua_conversion_dynamic;
 use std::collections::HashMap;
@@ -12,6 +13,19 @@ pub struct WslDomain {
     pub username: Option<String>,
     pub default_cwd: Option<PathBuf>,
     pub default_prog: Option<Vec<String>>,
+    /// The encoding used by panes spawned in this domain when no
+    /// explicit encoding is requested.  Falls back to `default_encoding`.
+    pub default_encoding: Option<PaneEncoding>,
+    /// Lines written to panes spawned in this domain once they have
+    /// started.  Falls back to `post_spawn_commands`.
+    pub post_spawn_commands: Option<Vec<String>>,
+    /// The value of `TERM` for panes spawned in this domain.
+    /// Falls back to `term`.
+    pub term: Option<String>,
+    /// Environment variables set for panes spawned in this domain,
+    /// in addition to and taking precedence over `set_environment_variables`.
+    #[dynamic(default)]
+    pub set_environment_variables: HashMap<String, String>,
 }
 impl_lua_conversion_dynamic!(WslDomain);
 
@@ -29,6 +43,10 @@ impl WslDomain {
                     username: None,
                     default_cwd: Some("~".into()),
                     default_prog: None,
+                    default_encoding: None,
+                    post_spawn_commands: None,
+                    term: None,
+                    set_environment_variables: HashMap::new(),
                 });
             }
         }
@@ -28,6 +28,7 @@ use std::convert::TryInto;
 use std::io::Cursor;
 use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use termwiz::hyperlink::Hyperlink;
 use termwiz::image::{ImageData, TextureCoordinate};
@@ -288,17 +289,29 @@ pub struct DecodedPdu {
 /// If the serialized size is larger than this, then we'll consider compressing it
 const COMPRESS_THRESH: usize = 32;
 
-fn serialize<T: serde::Serialize>(t: &T) -> Result<(Vec<u8>, bool), Error> {
+/// The zstd compression level used when the peer hasn't asked for
+/// anything different
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = zstd::DEFAULT_COMPRESSION_LEVEL;
+
+/// Serializes `t`, compressing it at `compression_level` if that is
+/// enabled and makes the data smaller.
+/// Returns the data, whether it is compressed and the uncompressed size.
+fn serialize<T: serde::Serialize>(
+    t: &T,
+    compression_level: Option<i32>,
+) -> Result<(Vec<u8>, bool, usize), Error> {
     let mut uncompressed = Vec::new();
     let mut encode = varbincode::Serializer::new(&mut uncompressed);
     t.serialize(&mut encode)?;
+    let uncompressed_len = uncompressed.len();
 
-    if uncompressed.len() <= COMPRESS_THRESH {
-        return Ok((uncompressed, false));
-    }
+    let compression_level = match compression_level {
+        Some(level) if uncompressed_len > COMPRESS_THRESH => level,
+        _ => return Ok((uncompressed, false, uncompressed_len)),
+    };
     // It's a little heavy; let's try compressing it
     let mut compressed = Vec::new();
-    let mut compress = zstd::Encoder::new(&mut compressed, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+    let mut compress = zstd::Encoder::new(&mut compressed, compression_level)?;
     let mut encode = varbincode::Serializer::new(&mut compress);
     t.serialize(&mut encode)?;
     drop(encode);
@@ -307,16 +320,111 @@ fn serialize<T: serde::Serialize>(t: &T) -> Result<(Vec<u8>, bool), Error> {
     log::debug!(
         "serialized+compress len {} vs {}",
         compressed.len(),
-        uncompressed.len()
+        uncompressed_len
     );
 
-    if compressed.len() < uncompressed.len() {
-        Ok((compressed, true))
+    if compressed.len() < uncompressed_len {
+        Ok((compressed, true, uncompressed_len))
     } else {
-        Ok((uncompressed, false))
+        Ok((uncompressed, false, uncompressed_len))
+    }
+}
+
+/// Counts the bytes that pass through a reader
+struct CountingReader<R> {
+    inner: R,
+    count: usize,
+}
+
+impl<R: std::io::Read> std::io::Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let size = self.inner.read(buf)?;
+        self.count += size;
+        Ok(size)
+    }
+}
+
+/// Deserializes a `T` from `data`, returning it along with the size
+/// of its uncompressed serialized form
+fn deserialize_counted<T: serde::de::DeserializeOwned>(
+    data: &[u8],
+    is_compressed: bool,
+) -> Result<(T, usize), Error> {
+    if is_compressed {
+        let decompress = zstd::Decoder::new(data)?;
+        let mut counter = CountingReader {
+            inner: decompress,
+            count: 0,
+        };
+        let mut decode = varbincode::Deserializer::new(&mut counter);
+        let t = serde::Deserialize::deserialize(&mut decode)?;
+        Ok((t, counter.count))
+    } else {
+        Ok((deserialize(data, false)?, data.len()))
+    }
+}
+
+/// Running totals for the frames sent or received over a connection,
+/// used to see how effective compression is.
+/// Sizes cover the PDU data and exclude the frame headers.
+#[derive(Default, Debug)]
+pub struct FrameStats {
+    frames: AtomicU64,
+    compressed_frames: AtomicU64,
+    payload_bytes: AtomicU64,
+    wire_bytes: AtomicU64,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStatsSnapshot {
+    pub frames: u64,
+    pub compressed_frames: u64,
+    /// The size of the data before compression
+    pub payload_bytes: u64,
+    /// The size of the data as it was sent over the connection
+    pub wire_bytes: u64,
+}
+
+impl FrameStatsSnapshot {
+    /// Returns the percentage of the payload that compression saved
+    pub fn saved_percent(&self) -> f64 {
+        if self.payload_bytes == 0 {
+            return 0.;
+        }
+        let saved = self.payload_bytes.saturating_sub(self.wire_bytes);
+        saved as f64 * 100. / self.payload_bytes as f64
+    }
+}
+
+impl FrameStats {
+    fn record(&self, payload_bytes: usize, wire_bytes: usize, is_compressed: bool) {
+        self.frames.fetch_add(1, Ordering::Relaxed);
+        if is_compressed {
+            self.compressed_frames.fetch_add(1, Ordering::Relaxed);
+        }
+        self.payload_bytes
+            .fetch_add(payload_bytes as u64, Ordering::Relaxed);
+        self.wire_bytes
+            .fetch_add(wire_bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> FrameStatsSnapshot {
+        FrameStatsSnapshot {
+            frames: self.frames.load(Ordering::Relaxed),
+            compressed_frames: self.compressed_frames.load(Ordering::Relaxed),
+            payload_bytes: self.payload_bytes.load(Ordering::Relaxed),
+            wire_bytes: self.wire_bytes.load(Ordering::Relaxed),
+        }
     }
 }
 
+/// Frame statistics for both directions of a connection
+#[derive(Default, Debug)]
+pub struct ConnectionStats {
+    pub sent: FrameStats,
+    pub received: FrameStats,
+}
+
 fn deserialize<T: serde::de::DeserializeOwned, R: std::io::Read>(
     mut r: R,
     is_compressed: bool,
@@ -347,7 +455,7 @@ macro_rules! pdu {
                     Pdu::Invalid{..} => bail!("attempted to serialize Pdu::Invalid"),
                     $(
                         Pdu::$name(s) => {
-                            let (data, is_compressed) = serialize(s)?;
+                            let (data, is_compressed, _) = serialize(s, Some(DEFAULT_COMPRESSION_LEVEL))?;
                             let encoded_size = encode_raw($vers, serial, &data, is_compressed, w)?;
                             log::debug!("encode {} size={encoded_size}", stringify!($name));
                             metrics::histogram!("pdu.size", "pdu" => stringify!($name)).record(encoded_size as f64);
@@ -359,12 +467,27 @@ macro_rules! pdu {
             }
 
             pub async fn encode_async<W: Unpin + AsyncWriteExt>(&self, w: &mut W, serial: u64) -> Result<(), Error> {
+                self.encode_async_with(w, serial, Some(DEFAULT_COMPRESSION_LEVEL), None).await
+            }
+
+            /// Like encode_async, but compresses at `compression_level`, or
+            /// not at all if that is None, and records the frame in `stats`
+            pub async fn encode_async_with<W: Unpin + AsyncWriteExt>(
+                &self,
+                w: &mut W,
+                serial: u64,
+                compression_level: Option<i32>,
+                stats: Option<&FrameStats>,
+            ) -> Result<(), Error> {
                 match self {
                     Pdu::Invalid{..} => bail!("attempted to serialize Pdu::Invalid"),
                     $(
                         Pdu::$name(s) => {
-                            let (data, is_compressed) = serialize(s)?;
+                            let (data, is_compressed, payload_size) = serialize(s, compression_level)?;
                             let encoded_size = encode_raw_async($vers, serial, &data, is_compressed, w).await?;
+                            if let Some(stats) = stats {
+                                stats.record(payload_size, data.len(), is_compressed);
+                            }
                             log::debug!("encode_async {} size={encoded_size}", stringify!($name));
                             metrics::histogram!("pdu.size", "pdu" => stringify!($name)).record(encoded_size as f64);
                             metrics::histogram!("pdu.size.rate", "pdu" => stringify!($name)).record(encoded_size as f64);
@@ -413,15 +536,33 @@ macro_rules! pdu {
                 where R: std::marker::Unpin,
                       R: AsyncRead,
                       R: std::fmt::Debug
+            {
+                Self::decode_async_with(r, max_serial, None).await
+            }
+
+            /// Like decode_async, but records the frame in `stats`
+            pub async fn decode_async_with<R>(
+                r: &mut R,
+                max_serial: Option<u64>,
+                stats: Option<&FrameStats>,
+            ) -> Result<DecodedPdu, Error>
+                where R: std::marker::Unpin,
+                      R: AsyncRead,
+                      R: std::fmt::Debug
             {
                 let decoded = decode_raw_async(r, max_serial).await.context("decoding a PDU")?;
                 match decoded.ident {
                     $(
                         $vers => {
                             metrics::histogram!("pdu.size", "pdu" => stringify!($name)).record(decoded.data.len() as f64);
+                            let (pdu, payload_size) =
+                                deserialize_counted(decoded.data.as_slice(), decoded.is_compressed)?;
+                            if let Some(stats) = stats {
+                                stats.record(payload_size, decoded.data.len(), decoded.is_compressed);
+                            }
                             Ok(DecodedPdu {
                                 serial: decoded.serial,
-                                pdu: Pdu::$name(deserialize(decoded.data.as_slice(), decoded.is_compressed)?)
+                                pdu: Pdu::$name(pdu)
                             })
                         }
                     ,)*
@@ -441,7 +582,7 @@ macro_rules! pdu {
 /// The overall version of the codec.
 /// This must be bumped when backwards incompatible changes
 /// are made to the types and protocol.
-pub const CODEC_VERSION: usize = 45;
+pub const CODEC_VERSION: usize = 51;
 
 // Defines the Pdu enum.
 // Each struct has an explicit identifying number.
@@ -502,6 +643,11 @@ pdu! {
     GetPaneDirection: 60,
     GetPaneDirectionResponse: 61,
     AdjustPaneSize: 62,
+    GetRawOutput: 63,
+    GetRawOutputResponse: 64,
+    SetPaneOutputLogging: 65,
+    RequestSecret: 66,
+    RequestSecretResponse: 67,
 }
 
 impl Pdu {
@@ -834,6 +980,8 @@ pub struct WindowWorkspaceChanged {
 pub struct SetClientId {
     pub client_id: ClientId,
     pub is_proxy: bool,
+    /// Whether the client would like the data sent to it to be compressed
+    pub compression: bool,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
@@ -881,6 +1029,50 @@ pub struct GetPaneDirectionResponse {
     pub pane_id: Option<PaneId>,
 }
 
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct GetRawOutput {
+    pub pane_id: PaneId,
+    pub max_bytes: usize,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct GetRawOutputResponse {
+    pub pane_id: PaneId,
+    pub data: Vec<u8>,
+}
+
+/// Starts logging the pty traffic of a pane to `path`, or stops
+/// logging if `path` is None
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct SetPaneOutputLogging {
+    pub pane_id: PaneId,
+    pub path: Option<PathBuf>,
+    pub include_input: bool,
+}
+
+/// Asks the GUI showing `pane_id` to prompt the user for a secret,
+/// such as a password or passphrase, on behalf of a program in that pane
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct RequestSecret {
+    pub pane_id: PaneId,
+    pub prompt: String,
+}
+
+/// The secret that was entered, or None if the prompt was cancelled
+#[derive(Deserialize, Serialize, PartialEq)]
+pub struct RequestSecretResponse {
+    pub secret: Option<String>,
+}
+
+// Keep the secret out of any logged PDUs
+impl std::fmt::Debug for RequestSecretResponse {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("RequestSecretResponse")
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
 pub struct ActivatePaneDirection {
     pub pane_id: PaneId,
@@ -1173,6 +1365,48 @@ mod test {
         }
     }
 
+    #[test]
+    fn compression_stats() {
+        let pdu = Pdu::GetRawOutputResponse(GetRawOutputResponse {
+            pane_id: 1,
+            data: b"hello world ".repeat(100),
+        });
+        let sent = FrameStats::default();
+        let mut encoded = Vec::new();
+        smol::block_on(async {
+            pdu.encode_async_with(
+                &mut encoded,
+                1,
+                Some(DEFAULT_COMPRESSION_LEVEL),
+                Some(&sent),
+            )
+            .await
+            .unwrap();
+            pdu.encode_async_with(&mut encoded, 2, None, Some(&sent))
+                .await
+                .unwrap();
+        });
+
+        let sent = sent.snapshot();
+        assert_eq!(sent.frames, 2);
+        assert_eq!(sent.compressed_frames, 1);
+        assert!(sent.wire_bytes < sent.payload_bytes);
+        assert!(sent.saved_percent() > 0.);
+
+        let received = FrameStats::default();
+        let mut cursor = smol::io::Cursor::new(encoded);
+        smol::block_on(async {
+            for serial in 1..=2 {
+                let decoded = Pdu::decode_async_with(&mut cursor, None, Some(&received))
+                    .await
+                    .unwrap();
+                assert_eq!(decoded.serial, serial);
+                assert_eq!(decoded.pdu, pdu);
+            }
+        });
+        assert_eq!(received.snapshot(), sent);
+    }
+
     #[test]
     fn test_pdu_ping() {
         let mut encoded = Vec::new();
@@ -1074,33 +1074,49 @@ impl LinearRgba {
             return None;
         }
 
-        let [_fg_l, fg_a, fg_b, fg_alpha] = self.to_oklaba();
-
-        let reduced_lum = ((bg_lum + 0.05) / min_ratio - 0.05).clamp(0.05, 1.0);
-        let reduced_col = Self::from_oklaba(reduced_lum, fg_a, fg_b, fg_alpha);
-        let reduced_ratio = reduced_col.contrast_ratio(other);
+        let [fg_l, fg_a, fg_b, fg_alpha] = self.to_oklaba();
+        let with_lightness = |l: f32| Self::from_oklaba(l, fg_a, fg_b, fg_alpha);
+
+        // Adjust only the perceptual lightness, preserving the hue and
+        // chroma of the foreground. Contrast increases monotonically as
+        // we move the lightness towards `limit`, so bisect for the
+        // lightness closest to the original that satisfies min_ratio.
+        let search = |limit: f32| -> (Self, f32, bool) {
+            let extreme = with_lightness(limit);
+            let extreme_ratio = extreme.contrast_ratio(other);
+            if extreme_ratio < min_ratio {
+                return (extreme, extreme_ratio, false);
+            }
+            let (mut failing, mut passing) = (fg_l, limit);
+            for _ in 0..16 {
+                let mid = (failing + passing) / 2.;
+                if with_lightness(mid).contrast_ratio(other) >= min_ratio {
+                    passing = mid;
+                } else {
+                    failing = mid;
+                }
+            }
+            let col = with_lightness(passing);
+            (col, col.contrast_ratio(other), true)
+        };
 
-        let increased_lum = ((bg_lum + 0.05) * min_ratio - 0.05).clamp(0.05, 1.0);
-        let increased_col = Self::from_oklaba(increased_lum, fg_a, fg_b, fg_alpha);
-        let increased_ratio = reduced_col.contrast_ratio(other);
+        let (reduced_col, reduced_ratio, reduced_ok) = search(0.);
+        let (increased_col, increased_ratio, increased_ok) = search(1.);
 
-        // Prefer the reduced luminance version if the fg is dimmer than bg
-        if fg_lum < bg_lum {
-            if reduced_ratio >= min_ratio {
-                return Some(reduced_col);
-            }
+        // Prefer to keep the foreground on the same side of the background
+        if fg_lum < bg_lum && reduced_ok {
+            return Some(reduced_col);
         }
         // Otherwise, let's find a satisfactory alternative
-        if increased_ratio >= min_ratio {
+        if increased_ok {
             return Some(increased_col);
         }
-        if reduced_ratio >= min_ratio {
+        if reduced_ok {
             return Some(reduced_col);
         }
-
         // Didn't find one that satifies the min_ratio, but did we find
         // one that is better than the existing ratio?
-        if reduced_ratio > ratio {
+        if reduced_ratio > ratio && reduced_ratio >= increased_ratio {
             return Some(reduced_col);
         }
         if increased_ratio > ratio {
@@ -1192,6 +1208,26 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn ensure_contrast_ratio() {
+        let bg = LinearRgba::with_srgba(0, 0, 0, 255);
+        let fg = LinearRgba::with_srgba(0x30, 0x30, 0x30, 255);
+        assert!(fg.contrast_ratio(&bg) < 4.5);
+        let adjusted = fg.ensure_contrast_ratio(&bg, 4.5).unwrap();
+        let ratio = adjusted.contrast_ratio(&bg);
+        assert!(ratio >= 4.5, "contrast({}) >= 4.5", ratio);
+
+        let bg = LinearRgba::with_srgba(0xff, 0xff, 0xff, 255);
+        let fg = LinearRgba::with_srgba(0xe0, 0xe0, 0x40, 255);
+        let adjusted = fg.ensure_contrast_ratio(&bg, 4.5).unwrap();
+        let ratio = adjusted.contrast_ratio(&bg);
+        assert!(ratio >= 4.5, "contrast({}) >= 4.5", ratio);
+
+        let fg = LinearRgba::with_srgba(0, 0, 0, 255);
+        assert_eq!(fg.ensure_contrast_ratio(&bg, 4.5), None);
+    }
+
     #[test]
     fn srgba_contrast_ratio() {
         let a = SrgbaTuple::from_str("hsl:0   100  50").unwrap();
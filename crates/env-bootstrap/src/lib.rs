@@ -204,6 +204,7 @@ fn register_lua_modules() {
     // Deferred modules loaded lazily on first access
     for func in [
         battery::register,
+        http_funcs::register,
         plugin::register,
         ssh_funcs::register,
         url_funcs::register,
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[cfg(feature = "lua")]
 use wezterm_dynamic::{FromDynamic, ToDynamic};
@@ -23,6 +24,16 @@ pub enum LocalProcessStatus {
     Unknown,
 }
 
+/// A point-in-time sample of the resources consumed by a process.
+/// CPU utilization can be derived by comparing two samples.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct LocalProcessResourceUsage {
+    /// Total user and system CPU time consumed by the process so far
+    pub cpu_time: Duration,
+    /// The resident set size of the process, in bytes
+    pub resident_size: u64,
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "lua", derive(FromDynamic, ToDynamic))]
 pub struct LocalProcessInfo {
@@ -94,4 +105,9 @@ impl LocalProcessInfo {
     pub fn executable_path(_pid: u32) -> Option<PathBuf> {
         None
     }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn resource_usage(_pid: u32) -> Option<LocalProcessResourceUsage> {
+        None
+    }
 }
@@ -110,6 +110,16 @@ pub trait VTActor {
     /// character.
     fn print(&mut self, b: char);
 
+    /// Print a run of characters, as though `print` had been called for
+    /// each of them in turn.  The parser uses this for runs of plain text
+    /// found in the ground state; actors that can handle a whole string
+    /// at once should override it, as that is considerably cheaper.
+    fn print_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.print(c);
+        }
+    }
+
     /// The C0 or C1 control function should be executed, which may have any one of a variety of
     /// effects, including changing the cursor position, suspending or resuming communications or
     /// changing the shift states in effect.
@@ -752,10 +762,75 @@ impl VTParser {
     /// This may result in some number of calls to the methods on the
     /// provided `actor`.
     pub fn parse(&mut self, bytes: &[u8], actor: &mut dyn VTActor) {
-        for b in bytes {
-            self.parse_byte(*b, actor);
+        let mut bytes = bytes;
+        while let Some((&first, rest)) = bytes.split_first() {
+            if self.state == State::Ground {
+                let (text, rest) = split_plain_text(bytes);
+                if !text.is_empty() {
+                    actor.print_str(text);
+                    bytes = rest;
+                    continue;
+                }
+            }
+            self.parse_byte(first, actor);
+            bytes = rest;
+        }
+    }
+}
+
+const LANES: usize = core::mem::size_of::<u64>();
+const ONES: u64 = u64::from_ne_bytes([0x01; LANES]);
+const HIGH_BITS: u64 = ONES * 0x80;
+
+/// Returns the length of the leading run of `bytes` that contains no
+/// C0 control (including ESC), checking eight bytes at a time.
+/// The run may include UTF-8 sequences, which the caller must validate.
+fn no_c0_run_len(bytes: &[u8]) -> usize {
+    let mut idx = 0;
+    while let Some(chunk) = bytes.get(idx..idx + LANES) {
+        let mut word = [0u8; LANES];
+        word.copy_from_slice(chunk);
+        let word = u64::from_ne_bytes(word);
+        // Sets the high bit of any byte that is < 0x20
+        let below_space = word.wrapping_sub(ONES * 0x20) & !word & HIGH_BITS;
+        if below_space != 0 {
+            break;
         }
+        idx += LANES;
     }
+    idx + bytes[idx..].iter().take_while(|&&b| b >= 0x20).count()
+}
+
+/// Splits off the leading run of `bytes` that, in the ground state,
+/// would produce nothing but `print` actions: printable ASCII and
+/// complete UTF-8 sequences other than UTF-8 encoded C1 controls.
+/// Anything else, including a sequence that continues in the next
+/// buffer, is left for the state machine.
+fn split_plain_text(bytes: &[u8]) -> (&str, &[u8]) {
+    let len = no_c0_run_len(bytes);
+    let text = match core::str::from_utf8(&bytes[..len]) {
+        Ok(text) => text,
+        Err(err) => {
+            // Safe to unwrap: this prefix was just validated
+            core::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap()
+        }
+    };
+    // U+0080..=U+009F are C1 controls, encoded as 0xC2 0x80..=0x9F.
+    // 0xC2 is never a continuation byte so this can't misfire.
+    let mut len = text.len();
+    if !text.is_ascii() {
+        let raw = text.as_bytes();
+        let mut from = 0;
+        while let Some(pos) = raw[from..].iter().position(|&b| b == 0xc2) {
+            let idx = from + pos;
+            if raw[idx + 1] < 0xa0 {
+                len = idx;
+                break;
+            }
+            from = idx + 2;
+        }
+    }
+    (&text[..len], &bytes[len..])
 }
 
 #[cfg(test)]
@@ -770,6 +845,39 @@ mod test {
         actor.into_vec()
     }
 
+    #[test]
+    fn test_plain_text_fast_path() {
+        let inputs: &[&[u8]] = &[
+            b"hello world, this is longer than eight bytes\r\n",
+            "\u{6f22}\u{5b57} and ascii\x1b[1mbold\x1b[0m \u{1f680}".as_bytes(),
+            b"before\xc2\x9bcsi\xc2\x85after\xc2\xa0nbsp",
+            b"bad \xff\xfe utf8 \xe6\xbc",
+            b"osc \x1b]0;title \xe6\xbc\xa2\x07 done\x7f",
+        ];
+        for input in inputs {
+            let mut parser = VTParser::new();
+            let mut bytewise = CollectingVTActor::default();
+            for &b in *input {
+                parser.parse_byte(b, &mut bytewise);
+            }
+            let expected = bytewise.into_vec();
+
+            // Splitting the input anywhere must not change the result
+            for split in 0..=input.len() {
+                let mut parser = VTParser::new();
+                let mut actor = CollectingVTActor::default();
+                parser.parse(&input[..split], &mut actor);
+                parser.parse(&input[split..], &mut actor);
+                assert!(
+                    actor.into_vec() == expected,
+                    "{:?} split at {}",
+                    input,
+                    split
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_mixed() {
         assert_eq!(
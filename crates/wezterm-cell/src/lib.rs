@@ -210,6 +210,9 @@ impl CellAttributes {
     bitfield!(overline, set_overline, 12);
     bitfield!(semantic_type, set_semantic_type, SemanticType, 0b11, 13);
     bitfield!(vertical_align, set_vertical_align, VerticalAlign, 0b11, 15);
+    // DECSCA character protection; protected cells are skipped
+    // by the selective erase operations.
+    bitfield!(protected, set_protected, 17);
 
     pub const fn blank() -> Self {
         Self {
@@ -458,6 +461,8 @@ impl CellAttributes {
         res.set_underline(Underline::None);
         res.set_overline(false);
         res.set_strikethrough(false);
+        // Erased cells are never protected
+        res.set_protected(false);
         res
     }
 
@@ -514,6 +519,9 @@ impl CellAttributes {
             Background(value) => {
                 self.set_background(*value);
             }
+            UnderlineColor(value) => {
+                self.set_underline_color(*value);
+            }
             Hyperlink(value) => {
                 self.set_hyperlink(value.clone());
             }
@@ -994,6 +1002,7 @@ pub enum AttributeChange {
     Invisible(bool),
     Foreground(ColorAttribute),
     Background(ColorAttribute),
+    UnderlineColor(ColorAttribute),
     Hyperlink(Option<Arc<Hyperlink>>),
 }
 
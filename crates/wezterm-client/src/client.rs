@@ -30,6 +30,7 @@ use std::os::unix::process::CommandExt;
 #[cfg(windows)]
 use std::os::windows::io::{AsRawSocket, AsSocket, BorrowedSocket, RawSocket};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use thiserror::Error;
@@ -59,6 +60,7 @@ pub struct Client {
     client_domain_config: ClientDomainConfig,
     pub is_reconnectable: bool,
     pub is_local: bool,
+    stats: Arc<ConnectionStats>,
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -341,14 +343,21 @@ fn client_thread(
     reconnectable: &mut Reconnectable,
     local_domain_id: Option<DomainId>,
     rx: &mut Receiver<ReaderMessage>,
+    stats: &ConnectionStats,
 ) -> anyhow::Result<()> {
-    block_on(client_thread_async(reconnectable, local_domain_id, rx))
+    block_on(client_thread_async(
+        reconnectable,
+        local_domain_id,
+        rx,
+        stats,
+    ))
 }
 
 async fn client_thread_async(
     reconnectable: &mut Reconnectable,
     local_domain_id: Option<DomainId>,
     rx: &mut Receiver<ReaderMessage>,
+    stats: &ConnectionStats,
 ) -> anyhow::Result<()> {
     let mut next_serial = 1u64;
 
@@ -388,13 +397,21 @@ async fn client_thread_async(
                 next_serial += 1;
                 promises.map.insert(serial, promise);
 
-                pdu.encode_async(&mut stream, serial)
+                let config = configuration();
+                let compression_level = if config.mux_compression {
+                    Some(config.mux_compression_level)
+                } else {
+                    None
+                };
+                pdu.encode_async_with(&mut stream, serial, compression_level, Some(&stats.sent))
                     .await
                     .context("encoding a PDU to send to the server")?;
                 stream.flush().await.context("flushing PDU to server")?;
             }
             Ok(ReaderMessage::Readable) => {
-                match Pdu::decode_async(&mut stream, Some(next_serial)).await {
+                match Pdu::decode_async_with(&mut stream, Some(next_serial), Some(&stats.received))
+                    .await
+                {
                     Ok(decoded) => {
                         log::debug!(
                             "decoded serial {} {}",
@@ -540,6 +557,8 @@ struct Reconnectable {
     config: ClientDomainConfig,
     stream: Option<Box<dyn AsyncReadAndWrite>>,
     tls_creds: Option<GetTlsCredsResponse>,
+    /// Receives whether the ssh proxy command exited successfully
+    proxy_exit: Option<std::sync::mpsc::Receiver<bool>>,
 }
 
 struct SshStream {
@@ -604,6 +623,7 @@ impl Reconnectable {
             config,
             stream,
             tls_creds: None,
+            proxy_exit: None,
         }
     }
 
@@ -636,15 +656,21 @@ impl Reconnectable {
             // the set of tabs and we'd have confusing and inconsistent state
             ClientDomainConfig::Unix(_) => false,
             ClientDomainConfig::Tls(_) => true,
-            // It *does* make sense to reconnect with an ssh session, but we
-            // need to grow some smarts about whether the disconnect was because
-            // we sent CTRL-D to close the last session, or whether it was a network
-            // level disconnect, because we will otherwise throw up authentication
-            // dialogs that would be annoying
-            ClientDomainConfig::Ssh(_) => false,
+            // It *does* make sense to reconnect with an ssh session, but
+            // reconnecting may throw up authentication dialogs that would be
+            // annoying, so it is opt-in.
+            ClientDomainConfig::Ssh(ssh) => ssh.reconnect_automatically,
         }
     }
 
+    /// Returns true if an EOF from the server was a clean close of the
+    /// session, which we don't reconnect for, rather than a dropped
+    /// transport.  For ssh, both show up as EOF, so we tell them apart
+    /// by the exit status of the proxy command.
+    fn closed_cleanly(&mut self) -> bool {
+        is_clean_close(self.proxy_exit.take().as_ref())
+    }
+
     fn connect(
         &mut self,
         initial: bool,
@@ -714,10 +740,21 @@ impl Reconnectable {
         // the proxy, and prevents us from hanging forever after the process
         // has died
         let mut child = exec.child;
-        std::thread::spawn(move || match child.wait() {
-            Err(err) => log::error!("waiting on {} failed: {:#}", cmd, err),
-            Ok(status) if !status.success() => log::error!("{}: {}", cmd, status),
-            _ => {}
+        let (exit_tx, exit_rx) = std::sync::mpsc::channel();
+        self.proxy_exit.replace(exit_rx);
+        std::thread::spawn(move || {
+            let success = match child.wait() {
+                Err(err) => {
+                    log::error!("waiting on {} failed: {:#}", cmd, err);
+                    false
+                }
+                Ok(status) if !status.success() => {
+                    log::error!("{}: {}", cmd, status);
+                    false
+                }
+                Ok(_) => true,
+            };
+            exit_tx.send(success).ok();
         });
 
         let stream: Box<dyn AsyncReadAndWrite> = Box::new(Async::new(SshStream {
@@ -1037,6 +1074,87 @@ impl Reconnectable {
     }
 }
 
+/// How long to wait for the ssh proxy command to report its exit
+/// status after the server connection reached EOF
+const PROXY_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Decides whether an EOF from the server was a clean close.
+/// Without a proxy command, EOF means that the server closed the
+/// connection.  With one, a dropped ssh session ends the proxy without
+/// a successful exit status, or without any status at all.
+fn is_clean_close(proxy_exit: Option<&std::sync::mpsc::Receiver<bool>>) -> bool {
+    match proxy_exit {
+        Some(exit) => exit.recv_timeout(PROXY_EXIT_TIMEOUT).unwrap_or(false),
+        None => true,
+    }
+}
+
+/// Changes in the state of the connection to a remote domain.
+/// These are surfaced to lua as `mux-domain-*` events.
+enum ConnectionEvent {
+    Disconnected { reason: String },
+    Reconnecting { attempt: usize, delay: Duration },
+    Reconnected,
+}
+
+impl ConnectionEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Disconnected { .. } => "mux-domain-disconnected",
+            Self::Reconnecting { .. } => "mux-domain-reconnecting",
+            Self::Reconnected => "mux-domain-reconnected",
+        }
+    }
+}
+
+/// Records the connection state on the ClientDomain so that its panes
+/// can indicate that they are disconnected, and emits the corresponding
+/// lua event
+fn notify_connection_event(local_domain_id: DomainId, event: ConnectionEvent) {
+    promise::spawn::spawn_into_main_thread(async move {
+        let mux = Mux::get();
+        let Some(domain) = mux.get_domain(local_domain_id) else {
+            return;
+        };
+        let domain_name = domain.domain_name().to_string();
+
+        if let Ok(inner) = ClientDomain::get_client_inner_for_domain(local_domain_id) {
+            let connected = matches!(event, ConnectionEvent::Reconnected);
+            if inner.is_connected() != connected {
+                inner.set_connected(connected);
+                // Repaint the panes so that they reflect the new state
+                for pane in mux.iter_panes() {
+                    if pane.domain_id() == local_domain_id {
+                        mux.notify(mux::MuxNotification::PaneOutput(pane.pane_id()));
+                    }
+                }
+            }
+        }
+
+        let name = event.name();
+        let result = config::with_lua_config_on_main_thread(move |lua| async move {
+            if let Some(lua) = lua {
+                let args = match event {
+                    ConnectionEvent::Disconnected { reason } => {
+                        lua.pack_multi((domain_name, reason))?
+                    }
+                    ConnectionEvent::Reconnecting { attempt, delay } => {
+                        lua.pack_multi((domain_name, attempt, delay.as_secs_f64()))?
+                    }
+                    ConnectionEvent::Reconnected => lua.pack_multi(domain_name)?,
+                };
+                config::lua::emit_event(&lua, (name.to_string(), args)).await?;
+            }
+            Ok(())
+        })
+        .await;
+        if let Err(err) = result {
+            log::error!("while processing {name} event: {err:#}");
+        }
+    })
+    .detach();
+}
+
 impl Client {
     fn new(local_domain_id: Option<DomainId>, mut reconnectable: Reconnectable) -> Self {
         let client_domain_config = reconnectable.config.clone();
@@ -1044,6 +1162,8 @@ impl Client {
         let is_local = reconnectable.is_local();
         let (sender, mut receiver) = unbounded();
         let client_id = ClientId::new();
+        let stats = Arc::new(ConnectionStats::default());
+        let thread_stats = Arc::clone(&stats);
 
         thread::spawn(move || {
             const BASE_INTERVAL: Duration = Duration::from_secs(1);
@@ -1051,7 +1171,12 @@ impl Client {
 
             let mut backoff = BASE_INTERVAL;
             loop {
-                if let Err(e) = client_thread(&mut reconnectable, local_domain_id, &mut receiver) {
+                if let Err(e) = client_thread(
+                    &mut reconnectable,
+                    local_domain_id,
+                    &mut receiver,
+                    &thread_stats,
+                ) {
                     if !reconnectable.reconnectable() || local_domain_id.is_none() {
                         log::debug!("client thread ended: {}", e);
                         break;
@@ -1061,9 +1186,11 @@ impl Client {
 
                     if let Some(ioerr) = e.root_cause().downcast_ref::<std::io::Error>() {
                         if let std::io::ErrorKind::UnexpectedEof = ioerr.kind() {
-                            // Don't reconnect for a simple EOF
-                            log::error!("server closed connection ({})", e);
-                            break;
+                            if reconnectable.closed_cleanly() {
+                                // Don't reconnect for a simple EOF
+                                log::error!("server closed connection ({})", e);
+                                break;
+                            }
                         }
                     }
 
@@ -1072,10 +1199,26 @@ impl Client {
                         break;
                     }
 
+                    notify_connection_event(
+                        local_domain_id,
+                        ConnectionEvent::Disconnected {
+                            reason: format!("{e:#}"),
+                        },
+                    );
+
                     let mut ui = ConnectionUI::new();
                     ui.title("wezterm: Reconnecting...");
 
+                    let mut attempt = 0;
                     loop {
+                        attempt += 1;
+                        notify_connection_event(
+                            local_domain_id,
+                            ConnectionEvent::Reconnecting {
+                                attempt,
+                                delay: backoff,
+                            },
+                        );
                         ui.sleep_with_reason(
                             &format!("client disconnected {}; will reconnect", e),
                             backoff,
@@ -1087,6 +1230,10 @@ impl Client {
                             Ok(_) => {
                                 backoff = BASE_INTERVAL;
                                 log::error!("Reconnected!");
+                                notify_connection_event(
+                                    local_domain_id,
+                                    ConnectionEvent::Reconnected,
+                                );
                                 promise::spawn::spawn_into_main_thread(async move {
                                     ClientDomain::reattach(local_domain_id, ui).await.ok();
                                 })
@@ -1138,9 +1285,15 @@ impl Client {
             is_local,
             client_id,
             client_domain_config,
+            stats,
         }
     }
 
+    /// Returns the frame statistics for the connection to the server
+    pub fn connection_stats(&self) -> &ConnectionStats {
+        &self.stats
+    }
+
     pub fn into_client_domain_config(self) -> ClientDomainConfig {
         self.client_domain_config
     }
@@ -1166,6 +1319,7 @@ impl Client {
                 self.set_client_id(SetClientId {
                     client_id: self.client_id.clone(),
                     is_proxy: false,
+                    compression: configuration().mux_compression,
                 })
                 .await?;
                 Ok(info)
@@ -1389,4 +1543,31 @@ impl Client {
         GetPaneDirectionResponse
     );
     rpc!(adjust_pane_size, AdjustPaneSize, UnitResponse);
+    rpc!(get_raw_output, GetRawOutput, GetRawOutputResponse);
+    rpc!(set_pane_output_logging, SetPaneOutputLogging, UnitResponse);
+    rpc!(request_secret, RequestSecret, RequestSecretResponse);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn eof_is_clean_close() {
+        // Unix and tls domains have no proxy command
+        assert!(is_clean_close(None));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(true).unwrap();
+        assert!(is_clean_close(Some(&rx)));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(false).unwrap();
+        assert!(!is_clean_close(Some(&rx)));
+
+        // The proxy went away without reporting a status
+        let (tx, rx) = std::sync::mpsc::channel::<bool>();
+        drop(tx);
+        assert!(!is_clean_close(Some(&rx)));
+    }
 }
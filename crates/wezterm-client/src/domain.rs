@@ -4,7 +4,7 @@ use anyhow::{anyhow, bail};
 use async_trait::async_trait;
 use codec::{ListPanesResponse, SpawnV2, SplitPane};
 use config::keyassignment::{PaneEncoding, SpawnTabDomain};
-use config::{SshDomain, TlsDomainClient, UnixDomain};
+use config::{configuration, SshDomain, TlsDomainClient, UnixDomain};
 use mux::connui::{ConnectionUI, ConnectionUIParams};
 use mux::domain::{alloc_domain_id, Domain, DomainId, DomainState, SplitSource};
 use mux::pane::{Pane, PaneId};
@@ -14,6 +14,7 @@ use mux::{Mux, MuxNotification};
 use portable_pty::CommandBuilder;
 use promise::spawn::spawn_into_new_thread;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use wezterm_term::TerminalSize;
 
@@ -26,6 +27,10 @@ pub struct ClientInner {
     remote_to_local_tab: Mutex<HashMap<TabId, TabId>>,
     remote_to_local_pane: Mutex<HashMap<PaneId, PaneId>>,
     pub focused_remote_pane_id: Mutex<Option<PaneId>>,
+    connected: AtomicBool,
+    /// Moving average of the round trip time for input, in milliseconds
+    input_rtt_ms: AtomicU64,
+    predictive_echo_disabled: AtomicBool,
 }
 
 impl ClientInner {
@@ -246,8 +251,55 @@ impl ClientInner {
             remote_to_local_tab: Mutex::new(HashMap::new()),
             remote_to_local_pane: Mutex::new(HashMap::new()),
             focused_remote_pane_id: Mutex::new(None),
+            connected: AtomicBool::new(true),
+            input_rtt_ms: AtomicU64::new(0),
+            predictive_echo_disabled: AtomicBool::new(false),
         }
     }
+
+    /// Returns false if predictive local echo has been switched off
+    /// for this domain while it is running
+    pub fn predictive_echo_enabled(&self) -> bool {
+        !self.predictive_echo_disabled.load(Ordering::Relaxed)
+    }
+
+    /// Switches predictive local echo on or off, returning true if
+    /// it is now enabled.  Prediction also requires
+    /// `local_echo_threshold_ms` to be set for the domain.
+    pub fn toggle_predictive_echo(&self) -> bool {
+        // Returns the prior value of the disabled flag
+        self.predictive_echo_disabled
+            .fetch_xor(true, Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_input_rtt(&self, rtt_ms: u64) {
+        let prior = self.input_rtt_ms.load(Ordering::Relaxed);
+        let average = if prior == 0 {
+            rtt_ms
+        } else {
+            (prior * 7 + rtt_ms) / 8
+        };
+        self.input_rtt_ms.store(average, Ordering::Relaxed);
+    }
+
+    /// Returns true if the link to the server is slow enough that we
+    /// should avoid fetching image data, per `mux_reduced_fidelity_threshold_ms`
+    pub fn reduced_fidelity(&self) -> bool {
+        match configuration().mux_reduced_fidelity_threshold_ms {
+            Some(threshold) => self.input_rtt_ms.load(Ordering::Relaxed) >= threshold,
+            None => false,
+        }
+    }
+
+    /// Returns false while the connection is down and we are trying
+    /// to reconnect
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Relaxed);
+    }
 }
 
 pub struct ClientDomain {
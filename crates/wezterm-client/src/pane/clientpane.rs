@@ -192,6 +192,15 @@ impl ClientPane {
                             alert: Alert::OutputSinceFocusLost,
                         });
                     }
+                    Alert::TabColorChanged(color) => {
+                        if let Some((_domain, _window_id, tab_id)) =
+                            mux.resolve_pane_id(self.local_pane_id)
+                        {
+                            if let Some(tab) = mux.get_tab(tab_id) {
+                                tab.set_color(color.map(Into::into));
+                            }
+                        }
+                    }
                     Alert::Progress(progress) => {
                         *self.progress.lock() = progress.clone();
                         mux.notify(MuxNotification::Alert {
@@ -267,6 +276,14 @@ impl Pane for ClientPane {
             Value::String("is_tardy".to_string()),
             Value::Bool(inner.is_tardy()),
         );
+        map.insert(
+            Value::String("is_connected".to_string()),
+            Value::Bool(inner.client.is_connected()),
+        );
+        map.insert(
+            Value::String("reduced_fidelity".to_string()),
+            Value::Bool(inner.client.reduced_fidelity()),
+        );
         map.insert(
             Value::String("since_last_response_ms".to_string()),
             Value::U64(inner.last_recv_time.elapsed().as_millis() as u64),
@@ -606,6 +623,12 @@ impl Pane for ClientPane {
         }
     }
 
+    fn echo_latency(&self) -> Option<Arc<mux::latency::EchoLatency>> {
+        Some(Arc::clone(
+            &self.renderable.lock().inner.borrow().echo_latency,
+        ))
+    }
+
     fn copy_user_vars(&self) -> HashMap<String, String> {
         self.user_vars.lock().clone()
     }
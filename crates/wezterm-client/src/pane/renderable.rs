@@ -4,6 +4,7 @@ use anyhow::anyhow;
 use codec::*;
 use config::{configuration, ConfigHandle};
 use lru::LruCache;
+use mux::latency::EchoLatency;
 use mux::pane::PaneId;
 use mux::renderable::{RenderableDimensions, StableCursorPosition};
 use mux::Mux;
@@ -76,6 +77,9 @@ pub struct RenderableInner {
     pub last_recv_time: Instant,
     last_late_dirty: Instant,
     last_input_rtt: u64,
+    pub echo_latency: Arc<EchoLatency>,
+    /// Rows that hold predicted content that the server has yet to confirm
+    predicted_rows: RangeSet<StableRowIndex>,
 
     pub input_serial: InputSerial,
 }
@@ -115,6 +119,8 @@ impl RenderableInner {
             last_recv_time: now,
             last_late_dirty: now,
             last_input_rtt: 0,
+            echo_latency: Arc::new(EchoLatency::default()),
+            predicted_rows: RangeSet::new(),
             input_serial: InputSerial::empty(),
             seqno: SEQ_ZERO,
         }
@@ -136,10 +142,12 @@ impl RenderableInner {
     /// Predictive echo can be noisy when the link is working well,
     /// so we only employ it when it looks like the latency is high.
     fn should_predict(&self) -> bool {
-        self.client
-            .local_echo_threshold_ms
-            .map(|thresh| self.last_input_rtt >= thresh)
-            .unwrap_or(false)
+        self.client.predictive_echo_enabled()
+            && self
+                .client
+                .local_echo_threshold_ms
+                .map(|thresh| self.last_input_rtt >= thresh)
+                .unwrap_or(false)
     }
 
     /// Compute a "prediction" and apply it to the line data that we
@@ -192,7 +200,7 @@ impl RenderableInner {
                 let cell = Cell::new(
                     c,
                     CellAttributes::default()
-                        .set_underline(Underline::Double)
+                        .set_underline(Underline::Dotted)
                         .clone(),
                 );
 
@@ -234,11 +242,13 @@ impl RenderableInner {
             Some(LineEntry::Stale(mut line)) | Some(LineEntry::Line(mut line)) => {
                 self.apply_prediction(c, &mut line);
                 self.lines.put(row, LineEntry::Line(line));
+                self.predicted_rows.add(row);
             }
             Some(LineEntry::LineAndFetching(mut line, instant)) => {
                 self.apply_prediction(c, &mut line);
                 self.lines
                     .put(row, LineEntry::LineAndFetching(line, instant));
+                self.predicted_rows.add(row);
             }
             Some(entry) => {
                 self.lines.put(row, entry);
@@ -249,7 +259,7 @@ impl RenderableInner {
 
     fn apply_paste_prediction(&mut self, row: usize, text: &str, line: &mut Line) {
         let attrs = CellAttributes::default()
-            .set_underline(Underline::Double)
+            .set_underline(Underline::Dotted)
             .clone();
 
         let text_line = Line::from_text(text, &attrs, SEQ_ZERO, None);
@@ -282,11 +292,13 @@ impl RenderableInner {
                 Some(LineEntry::Stale(mut line)) | Some(LineEntry::Line(mut line)) => {
                     self.apply_paste_prediction(idx, paste_line, &mut line);
                     self.lines.put(row, LineEntry::Line(line));
+                    self.predicted_rows.add(row);
                 }
                 Some(LineEntry::LineAndFetching(mut line, instant)) => {
                     self.apply_paste_prediction(idx, paste_line, &mut line);
                     self.lines
                         .put(row, LineEntry::LineAndFetching(line, instant));
+                    self.predicted_rows.add(row);
                 }
                 Some(entry) => {
                     self.lines.put(row, entry);
@@ -331,6 +343,9 @@ impl RenderableInner {
         // long it took for this response to come back
         if let Some(serial) = delta.input_serial {
             self.last_input_rtt = serial.elapsed_millis();
+            self.client.record_input_rtt(self.last_input_rtt);
+            self.echo_latency
+                .record(Duration::from_millis(self.last_input_rtt));
         }
 
         // When it comes to updating the cursor position, if the update was tagged
@@ -349,6 +364,19 @@ impl RenderableInner {
         {
             self.cursor_position = delta.cursor_position;
         }
+
+        // Once the server has caught up with the most recent input, any
+        // predictions that it didn't confirm by changing the line, such
+        // as keys that the application didn't echo, have to be replaced
+        // by what the server actually has
+        if delta.input_serial.is_some()
+            && delta.input_serial.unwrap_or(InputSerial::empty()) >= self.input_serial
+        {
+            for r in self.predicted_rows.iter() {
+                dirty.add_range(r.clone());
+            }
+            self.predicted_rows = RangeSet::new();
+        }
         self.dimensions = delta.dimensions;
         self.title = delta.title;
         self.working_dir = delta.working_dir.map(Into::into);
@@ -651,12 +679,15 @@ pub(crate) async fn hydrate_lines(
         return lines;
     }
 
+    // On a slow link we only show images that we already have
+    let reduced_fidelity = client.reduced_fidelity();
+
     let mut requests = HashMap::new();
     let mut data_by_hash = HashMap::new();
     for im in &image_cells {
         if let Some(data) = IMAGE_LRU.lock().unwrap().get(&im.data_hash) {
             data_by_hash.insert(im.data_hash, Arc::clone(data));
-        } else {
+        } else if !reduced_fidelity {
             requests
                 .entry(&im.data_hash)
                 .or_insert_with(|| GetImageCell {
@@ -761,27 +792,35 @@ impl RenderableState {
                 }
             };
 
-            if inner.client.overlay_lag_indicator && idx == inner.dimensions.physical_top {
-                if inner.is_tardy() {
-                    let status = format!(
+            if idx == inner.dimensions.physical_top
+                && (!inner.client.is_connected()
+                    || (inner.client.overlay_lag_indicator && inner.is_tardy()))
+            {
+                let status = if inner.client.is_connected() {
+                    format!(
                         "wezterm: {:.0?}⏳since last response",
                         inner.last_recv_time.elapsed()
-                    );
-                    // Right align it in the tab
-                    let col = inner
-                        .dimensions
-                        .cols
-                        .saturating_sub(wezterm_term::unicode_column_width(&status, None));
-
-                    let mut attr = CellAttributes::default();
-                    attr.set_foreground(AnsiColor::White);
-                    attr.set_background(AnsiColor::Blue);
-
-                    result
-                        .last_mut()
-                        .unwrap()
-                        .overlay_text_with_attribute(col, &status, attr, SEQ_ZERO);
-                }
+                    )
+                } else {
+                    format!(
+                        "disconnected {:.0?} ago, reconnecting…",
+                        inner.last_recv_time.elapsed()
+                    )
+                };
+                // Right align it in the tab
+                let col = inner
+                    .dimensions
+                    .cols
+                    .saturating_sub(wezterm_term::unicode_column_width(&status, None));
+
+                let mut attr = CellAttributes::default();
+                attr.set_foreground(AnsiColor::White);
+                attr.set_background(AnsiColor::Blue);
+
+                result
+                    .last_mut()
+                    .unwrap()
+                    .overlay_text_with_attribute(col, &status, attr, SEQ_ZERO);
             }
 
             inner.lines.put(idx, entry);
@@ -838,7 +877,7 @@ impl RenderableState {
 
         // If we're behind receiving an update, invalidate the top row so
         // that the indicator will update in a more timely fashion
-        if inner.is_tardy() {
+        if inner.is_tardy() || !inner.client.is_connected() {
             // ... but take care to avoid always reporting it as dirty, so
             // that we don't end up busy looping just to repaint it
             if inner.last_late_dirty.elapsed() >= Duration::from_secs(1) {
@@ -0,0 +1,60 @@
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use wezterm_escape_parser::parser::Parser;
+
+const SIZE: usize = 1024 * 1024;
+
+fn repeat_to_size(line: &str) -> Vec<u8> {
+    line.repeat(SIZE / line.len() + 1).into_bytes()
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let inputs = [
+        (
+            "ascii",
+            repeat_to_size(
+                "The quick brown fox jumps over the lazy dog; 0123456789 !@#$%^&*()\r\n",
+            ),
+        ),
+        (
+            "cjk",
+            repeat_to_size("漢字かなカナ한국어의텍스트中文字符\r\n"),
+        ),
+        (
+            "sgr",
+            repeat_to_size(
+                "\x1b[1;38;5;196mred\x1b[0m \x1b[4;32mgreen\x1b[0m \x1b[7mrev\x1b[0m\r\n",
+            ),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("parse");
+    for (name, data) in &inputs {
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_function(format!("{name}/per_char"), |b| {
+            b.iter(|| {
+                let mut parser = Parser::new();
+                let mut count = 0;
+                parser.parse(black_box(data), |action| {
+                    black_box(action);
+                    count += 1;
+                });
+                count
+            })
+        });
+        group.bench_function(format!("{name}/coalesced"), |b| {
+            b.iter(|| {
+                let mut parser = Parser::new();
+                let mut count = 0;
+                parser.parse_coalesced(black_box(data), |action| {
+                    black_box(action);
+                    count += 1;
+                });
+                count
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -135,6 +135,10 @@ pub enum CSI {
     /// ECMA-48 SCP
     SelectCharacterPath(CharacterPath, i64),
 
+    /// DECSCA: when true, subsequently printed characters are protected
+    /// from the selective erase operations (DECSED, DECSEL and DECSERA)
+    SelectCharacterProtection(bool),
+
     /// Unknown or unspecified; should be rare and is rather
     /// large, so it is boxed and kept outside of the enum
     /// body to help reduce space usage in the common cases.
@@ -246,6 +250,9 @@ impl Display for CSI {
                     (a, n) => write!(f, "{};{} k", a, n)?,
                 }
             }
+            CSI::SelectCharacterProtection(protect) => {
+                write!(f, "{}\"q", if *protect { 1 } else { 0 })?
+            }
         };
         Ok(())
     }
@@ -281,6 +288,7 @@ pub enum DeviceAttributeCodes {
     UserWindows = 18,
     HorizontalScrolling = 21,
     AnsiColor = 22,
+    RectangularEditing = 28,
     AnsiTextLocator = 29,
 }
 
@@ -587,6 +595,42 @@ pub enum Window {
         bottom: OneBased,
         right: OneBased,
     },
+    /// DECCRA; copy a rectangular area, including its attributes, so
+    /// that its top left corner is at dest_top, dest_left.
+    /// An omitted bottom or right edge is the edge of the page.
+    CopyRectangularArea {
+        top: OneBased,
+        left: OneBased,
+        bottom: OneBased,
+        right: OneBased,
+        page: u32,
+        dest_top: OneBased,
+        dest_left: OneBased,
+        dest_page: u32,
+    },
+    /// DECFRA; fill a rectangular area with a character, using the
+    /// current graphic rendition
+    FillRectangularArea {
+        ch: char,
+        top: OneBased,
+        left: OneBased,
+        bottom: OneBased,
+        right: OneBased,
+    },
+    /// DECERA; erase a rectangular area, ignoring character protection
+    EraseRectangularArea {
+        top: OneBased,
+        left: OneBased,
+        bottom: OneBased,
+        right: OneBased,
+    },
+    /// DECSERA; erase the unprotected characters in a rectangular area
+    SelectiveEraseRectangularArea {
+        top: OneBased,
+        left: OneBased,
+        bottom: OneBased,
+        right: OneBased,
+    },
 }
 
 fn numstr_or_empty(x: &Option<i64>) -> String {
@@ -659,6 +703,39 @@ impl Display for Window {
                 "{};{};{};{};{};{}*y",
                 request_id, page_number, top, left, bottom, right,
             ),
+            Window::CopyRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+                page,
+                dest_top,
+                dest_left,
+                dest_page,
+            } => write!(
+                f,
+                "{};{};{};{};{};{};{};{}$v",
+                top, left, bottom, right, page, dest_top, dest_left, dest_page
+            ),
+            Window::FillRectangularArea {
+                ch,
+                top,
+                left,
+                bottom,
+                right,
+            } => write!(f, "{};{};{};{};{}$x", *ch as u32, top, left, bottom, right),
+            Window::EraseRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+            } => write!(f, "{};{};{};{}$z", top, left, bottom, right),
+            Window::SelectiveEraseRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+            } => write!(f, "{};{};{};{}${{", top, left, bottom, right),
         }
     }
 }
@@ -950,6 +1027,10 @@ pub enum DecPrivateModeCode {
     /// Grapheme clustering mode
     GraphemeClustering = 2027,
 
+    /// In-band window resize notifications
+    /// <https://gist.github.com/rockorager/e695fb2924d36b2bcf1fff4a3704bd83>
+    InBandResizeNotifications = 2048,
+
     /// Applies to sixel and regis modes
     UsePrivateColorRegistersForEachGraphic = 1070,
 
@@ -1231,6 +1312,14 @@ pub enum Edit {
 
     /// REP - Repeat the preceding character n times
     Repeat(u32),
+
+    /// DECSED - Selective Erase in Display.
+    /// As EraseInDisplay, but leaves protected characters alone.
+    SelectiveEraseInDisplay(EraseInDisplay),
+
+    /// DECSEL - Selective Erase in Line.
+    /// As EraseInLine, but leaves protected characters alone.
+    SelectiveEraseInLine(EraseInLine),
 }
 
 trait EncodeCSIParam {
@@ -1281,6 +1370,14 @@ impl Display for Edit {
             Edit::ScrollUp(n) => n.write_csi(f, "S")?,
             Edit::EraseInDisplay(n) => n.write_csi(f, "J")?,
             Edit::Repeat(n) => n.write_csi(f, "b")?,
+            Edit::SelectiveEraseInDisplay(n) => {
+                write!(f, "?")?;
+                n.write_csi(f, "J")?
+            }
+            Edit::SelectiveEraseInLine(n) => {
+                write!(f, "?")?;
+                n.write_csi(f, "K")?
+            }
         }
         Ok(())
     }
@@ -1834,6 +1931,17 @@ impl<'a> CSIParser<'a> {
             ('k', [.., CsiParam::P(b' ')]) => self.select_character_path(params),
             ('q', [.., CsiParam::P(b' ')]) => self.cursor_style(params),
             ('y', [.., CsiParam::P(b'*')]) => self.checksum_area(params),
+            ('q', [.., CsiParam::P(b'"')]) => self.decsca(params),
+            ('v', [.., CsiParam::P(b'$')]) => self.copy_area(params),
+            ('x', [.., CsiParam::P(b'$')]) => self.fill_area(params),
+            ('z', [.., CsiParam::P(b'$')]) => self.erase_area(params, false),
+            ('{', [.., CsiParam::P(b'$')]) => self.erase_area(params, true),
+            ('J', [CsiParam::P(b'?'), ..]) => {
+                parse!(Edit, SelectiveEraseInDisplay, self.focus(params, 1, 0))
+            }
+            ('K', [CsiParam::P(b'?'), ..]) => {
+                parse!(Edit, SelectiveEraseInLine, self.focus(params, 1, 0))
+            }
 
             ('c', [CsiParam::P(b'='), ..]) => self
                 .req_tertiary_device_attributes(params)
@@ -2084,6 +2192,72 @@ impl<'a> CSIParser<'a> {
         })))
     }
 
+    fn decsca(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
+        let params = Cracked::parse(&params[..params.len() - 1])?;
+        if params.len() > 1 {
+            return Err(());
+        }
+        match params.opt_int(0).unwrap_or(0) {
+            1 => Ok(CSI::SelectCharacterProtection(true)),
+            0 | 2 => Ok(CSI::SelectCharacterProtection(false)),
+            _ => Err(()),
+        }
+    }
+
+    fn copy_area(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
+        let params = Cracked::parse(&params[..params.len() - 1])?;
+        let page = |idx| u32::try_from(params.opt_int(idx).unwrap_or(1).max(1)).map_err(|_| ());
+        Ok(CSI::Window(Box::new(Window::CopyRectangularArea {
+            top: OneBased::from_optional_esc_param(params.get(0))?,
+            left: OneBased::from_optional_esc_param(params.get(1))?,
+            bottom: OneBased::from_optional_esc_param_with_big_default(params.get(2))?,
+            right: OneBased::from_optional_esc_param_with_big_default(params.get(3))?,
+            page: page(4)?,
+            dest_top: OneBased::from_optional_esc_param(params.get(5))?,
+            dest_left: OneBased::from_optional_esc_param(params.get(6))?,
+            dest_page: page(7)?,
+        })))
+    }
+
+    fn fill_area(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
+        let params = Cracked::parse(&params[..params.len() - 1])?;
+        let ch = params
+            .opt_int(0)
+            .and_then(|c| u32::try_from(c).ok())
+            .and_then(char::from_u32)
+            .ok_or(())?;
+        Ok(CSI::Window(Box::new(Window::FillRectangularArea {
+            ch,
+            top: OneBased::from_optional_esc_param(params.get(1))?,
+            left: OneBased::from_optional_esc_param(params.get(2))?,
+            bottom: OneBased::from_optional_esc_param_with_big_default(params.get(3))?,
+            right: OneBased::from_optional_esc_param_with_big_default(params.get(4))?,
+        })))
+    }
+
+    fn erase_area(&mut self, params: &'a [CsiParam], selective: bool) -> Result<CSI, ()> {
+        let params = Cracked::parse(&params[..params.len() - 1])?;
+        let top = OneBased::from_optional_esc_param(params.get(0))?;
+        let left = OneBased::from_optional_esc_param(params.get(1))?;
+        let bottom = OneBased::from_optional_esc_param_with_big_default(params.get(2))?;
+        let right = OneBased::from_optional_esc_param_with_big_default(params.get(3))?;
+        Ok(CSI::Window(Box::new(if selective {
+            Window::SelectiveEraseRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+            }
+        } else {
+            Window::EraseRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+            }
+        })))
+    }
+
     fn dsr(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
         match params {
             [CsiParam::Integer(5)] => {
@@ -2997,6 +3171,25 @@ mod test {
         res
     }
 
+    fn parse_with_intermediate(
+        control: char,
+        params: &[i64],
+        intermediate: u8,
+        expected: &str,
+    ) -> Vec<CSI> {
+        let mut cparams = vec![];
+        for &p in params {
+            if !cparams.is_empty() {
+                cparams.push(CsiParam::P(b';'));
+            }
+            cparams.push(CsiParam::Integer(p));
+        }
+        cparams.push(CsiParam::P(intermediate));
+        let res = CSI::parse(&cparams, false, control).collect();
+        assert_eq!(encode(&res), expected, "parsed -> {res:?}");
+        res
+    }
+
     fn encode(seq: &Vec<CSI>) -> String {
         let mut res = Vec::new();
         for s in seq {
@@ -3066,6 +3259,82 @@ mod test {
         );
     }
 
+    #[test]
+    fn rectangular_areas() {
+        assert_eq!(
+            parse_with_intermediate('x', &[65, 2, 3, 4, 5], b'$', "\x1b[65;2;3;4;5$x"),
+            vec![CSI::Window(Box::new(Window::FillRectangularArea {
+                ch: 'A',
+                top: OneBased::new(2),
+                left: OneBased::new(3),
+                bottom: OneBased::new(4),
+                right: OneBased::new(5),
+            }))]
+        );
+        assert_eq!(
+            parse_with_intermediate('z', &[], b'$', "\x1b[1;1;4294967295;4294967295$z"),
+            vec![CSI::Window(Box::new(Window::EraseRectangularArea {
+                top: OneBased::new(1),
+                left: OneBased::new(1),
+                bottom: OneBased::new(u32::MAX),
+                right: OneBased::new(u32::MAX),
+            }))]
+        );
+        assert_eq!(
+            parse_with_intermediate('{', &[2, 2, 3, 3], b'$', "\x1b[2;2;3;3${"),
+            vec![CSI::Window(Box::new(
+                Window::SelectiveEraseRectangularArea {
+                    top: OneBased::new(2),
+                    left: OneBased::new(2),
+                    bottom: OneBased::new(3),
+                    right: OneBased::new(3),
+                }
+            ))]
+        );
+        assert_eq!(
+            parse_with_intermediate(
+                'v',
+                &[1, 2, 3, 4, 1, 5, 6, 1],
+                b'$',
+                "\x1b[1;2;3;4;1;5;6;1$v"
+            ),
+            vec![CSI::Window(Box::new(Window::CopyRectangularArea {
+                top: OneBased::new(1),
+                left: OneBased::new(2),
+                bottom: OneBased::new(3),
+                right: OneBased::new(4),
+                page: 1,
+                dest_top: OneBased::new(5),
+                dest_left: OneBased::new(6),
+                dest_page: 1,
+            }))]
+        );
+    }
+
+    #[test]
+    fn selective_erase() {
+        assert_eq!(
+            parse_with_intermediate('q', &[1], b'"', "\x1b[1\"q"),
+            vec![CSI::SelectCharacterProtection(true)]
+        );
+        assert_eq!(
+            parse_with_intermediate('q', &[], b'"', "\x1b[0\"q"),
+            vec![CSI::SelectCharacterProtection(false)]
+        );
+        assert_eq!(
+            CSI::parse(&[CsiParam::P(b'?'), CsiParam::Integer(2)], false, 'J').collect::<Vec<_>>(),
+            vec![CSI::Edit(Edit::SelectiveEraseInDisplay(
+                EraseInDisplay::EraseDisplay
+            ))]
+        );
+        assert_eq!(
+            encode(&vec![CSI::Edit(Edit::SelectiveEraseInLine(
+                EraseInLine::EraseToStartOfLine
+            ))]),
+            "\x1b[?1K"
+        );
+    }
+
     #[test]
     fn blinks() {
         assert_eq!(
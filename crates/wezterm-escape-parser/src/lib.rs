@@ -63,25 +63,31 @@ pub enum Action {
 
 impl Action {
     /// Append this `Action` to a `Vec<Action>`.
-    /// If this `Action` is `Print` and the last element is `Print` or
-    /// `PrintString` then the elements are combined into `PrintString`
-    /// to reduce heap utilization.
+    /// If this `Action` is `Print` or `PrintString` and the last element
+    /// is `Print` or `PrintString` then the elements are combined into
+    /// `PrintString` to reduce heap utilization.
     pub fn append_to(self, dest: &mut Vec<Self>) {
-        if let Action::Print(c) = &self {
-            match dest.last_mut() {
-                Some(Action::PrintString(s)) => {
-                    s.push(*c);
-                    return;
-                }
-                Some(Action::Print(prior)) => {
-                    let mut s = prior.to_string();
-                    dest.pop();
-                    s.push(*c);
-                    dest.push(Action::PrintString(s));
-                    return;
+        match (&self, dest.last_mut()) {
+            (Action::Print(c), Some(Action::PrintString(s))) => {
+                s.push(*c);
+                return;
+            }
+            (Action::PrintString(text), Some(Action::PrintString(s))) => {
+                s.push_str(text);
+                return;
+            }
+            (Action::Print(_) | Action::PrintString(_), Some(Action::Print(prior))) => {
+                let mut s = prior.to_string();
+                dest.pop();
+                match self {
+                    Action::Print(c) => s.push(c),
+                    Action::PrintString(text) => s.push_str(&text),
+                    _ => unreachable!(),
                 }
-                _ => {}
+                dest.push(Action::PrintString(s));
+                return;
             }
+            _ => {}
         }
         dest.push(self);
     }
@@ -594,6 +600,14 @@ impl OneBased {
         Self::from_esc_param(o.unwrap_or(&CsiParam::Integer(1)))
     }
 
+    /// Map a value from an optional escape sequence parameter.
+    /// Absence and 0 are both equivalent to max_value.
+    pub fn from_optional_esc_param_with_big_default(
+        o: Option<&CsiParam>,
+    ) -> core::result::Result<Self, ()> {
+        Self::from_esc_param_with_big_default(o.unwrap_or(&CsiParam::Integer(0)))
+    }
+
     /// Return the underlying value as a 0-based value
     pub fn as_zero_based(self) -> u32 {
         self.value.saturating_sub(1)
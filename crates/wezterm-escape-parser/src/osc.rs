@@ -590,9 +590,9 @@ impl Display for OperatingSystemCommand {
                 }
             }
             ChangeColorNumber(specs) => {
-                write!(f, "4;")?;
+                write!(f, "4")?;
                 for pair in specs {
-                    write!(f, "{};{}", pair.palette_index, pair.color)?
+                    write!(f, ";{};{}", pair.palette_index, pair.color)?
                 }
             }
             ChangeDynamicColors(first_color, colors) => {
@@ -1422,6 +1422,51 @@ mod test {
         );
     }
 
+    #[test]
+    fn change_color_number() {
+        assert_eq!(
+            parse(&["4", "1", "?"], "\x1b]4;1;?\x1b\\"),
+            OperatingSystemCommand::ChangeColorNumber(vec![ChangeColorPair {
+                palette_index: 1,
+                color: ColorOrQuery::Query,
+            }])
+        );
+        assert_eq!(
+            parse(
+                &["4", "1", "#ff0000", "2", "?"],
+                "\x1b]4;1;rgb:ffff/0000/0000;2;?\x1b\\"
+            ),
+            OperatingSystemCommand::ChangeColorNumber(vec![
+                ChangeColorPair {
+                    palette_index: 1,
+                    color: ColorOrQuery::Color(SrgbaTuple(1., 0., 0., 1.)),
+                },
+                ChangeColorPair {
+                    palette_index: 2,
+                    color: ColorOrQuery::Query,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn change_dynamic_colors() {
+        assert_eq!(
+            parse(&["11", "?"], "\x1b]11;?\x1b\\"),
+            OperatingSystemCommand::ChangeDynamicColors(
+                DynamicColorNumber::TextBackgroundColor,
+                vec![ColorOrQuery::Query]
+            )
+        );
+        assert_eq!(
+            parse(&["10", "?", "?"], "\x1b]10;?;?\x1b\\"),
+            OperatingSystemCommand::ChangeDynamicColors(
+                DynamicColorNumber::TextForegroundColor,
+                vec![ColorOrQuery::Query, ColorOrQuery::Query]
+            )
+        );
+    }
+
     #[test]
     fn title() {
         assert_eq!(
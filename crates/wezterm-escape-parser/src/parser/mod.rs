@@ -89,7 +89,19 @@ impl Parser {
         return tmux_parser.advance_bytes(bytes);
     }
 
-    pub fn parse<F: FnMut(Action)>(&mut self, bytes: &[u8], mut callback: F) {
+    pub fn parse<F: FnMut(Action)>(&mut self, bytes: &[u8], callback: F) {
+        self.parse_impl(bytes, callback, false)
+    }
+
+    /// Like `parse`, but runs of plain text may be delivered as a single
+    /// `Action::PrintString` rather than as one `Action::Print` per char.
+    /// This is considerably cheaper for consumers that apply the actions
+    /// to a terminal model.
+    pub fn parse_coalesced<F: FnMut(Action)>(&mut self, bytes: &[u8], callback: F) {
+        self.parse_impl(bytes, callback, true)
+    }
+
+    fn parse_impl<F: FnMut(Action)>(&mut self, bytes: &[u8], mut callback: F, coalesce: bool) {
         #[cfg(feature = "tmux_cc")]
         let is_tmux_mode: bool = self.state.borrow().tmux_state.is_some();
         #[cfg(feature = "tmux_cc")]
@@ -108,6 +120,7 @@ impl Parser {
                     let mut perform = Performer {
                         callback: &mut callback,
                         state: &mut parser_state,
+                        coalesce,
                     };
                     self.state_machine
                         .parse(unparsed_str.as_bytes(), &mut perform);
@@ -119,6 +132,7 @@ impl Parser {
         let mut perform = Performer {
             callback: &mut callback,
             state: &mut self.state.borrow_mut(),
+            coalesce,
         };
         self.state_machine.parse(bytes, &mut perform);
     }
@@ -144,6 +158,7 @@ impl Parser {
                     *first.borrow_mut() = Some(action);
                 },
                 state: &mut self.state.borrow_mut(),
+                coalesce: false,
             };
             for (idx, b) in bytes.iter().enumerate() {
                 self.state_machine.parse_byte(*b, &mut perform);
@@ -181,6 +196,7 @@ impl Parser {
                 &mut Performer {
                     callback: &mut |action| actions.push(action),
                     state: &mut self.state.borrow_mut(),
+                    coalesce: false,
                 },
             );
             if !actions.is_empty() && self.state_machine.is_ground() {
@@ -196,6 +212,8 @@ impl Parser {
 struct Performer<'a, F: FnMut(Action) + 'a> {
     callback: &'a mut F,
     state: &'a mut ParseState,
+    /// Whether runs of text are emitted as `Action::PrintString`
+    coalesce: bool,
 }
 
 fn is_short_dcs(intermediates: &[u8], byte: u8) -> bool {
@@ -212,6 +230,21 @@ impl<'a, F: FnMut(Action)> VTActor for Performer<'a, F> {
         (self.callback)(Action::Print(c));
     }
 
+    fn print_str(&mut self, s: &str) {
+        if !self.coalesce {
+            for c in s.chars() {
+                self.print(c);
+            }
+            return;
+        }
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => (self.callback)(Action::Print(c)),
+            (Some(_), Some(_)) => (self.callback)(Action::PrintString(s.to_owned())),
+            (None, _) => {}
+        }
+    }
+
     fn execute_c0_or_c1(&mut self, byte: u8) {
         match FromPrimitive::from_u8(byte) {
             Some(code) => (self.callback)(Action::Control(code)),
@@ -354,7 +387,7 @@ mod test {
         CharacterPath, DecPrivateMode, DecPrivateModeCode, Device, Intensity, Mode, Sgr, Underline,
         Window, XtSmGraphics, XtSmGraphicsItem, XtermKeyModifierResource,
     };
-    use crate::{EscCode, OneBased};
+    use crate::{ControlCode, EscCode, OneBased};
     use k9::assert_equal as assert_eq;
     use std::io::Write;
 
@@ -555,6 +588,27 @@ mod test {
         assert_eq!(encode(&actions), "hello");
     }
 
+    #[test]
+    fn coalesced_parse() {
+        let mut p = Parser::new();
+        let mut actions = vec![];
+        p.parse_coalesced("héllo\r\nx\x1b[1m文字".as_bytes(), |action| {
+            actions.push(action)
+        });
+        assert_eq!(
+            vec![
+                Action::PrintString("héllo".to_string()),
+                Action::Control(ControlCode::CarriageReturn),
+                Action::Control(ControlCode::LineFeed),
+                Action::Print('x'),
+                Action::CSI(CSI::Sgr(Sgr::Intensity(Intensity::Bold))),
+                Action::PrintString("文字".to_string()),
+            ],
+            actions
+        );
+        assert_eq!(encode(&actions), "héllo\r\nx\x1b[1m文字");
+    }
+
     #[test]
     fn basic_bold() {
         let mut p = Parser::new();
@@ -615,6 +615,62 @@ impl Face {
         ft_result(unsafe { FT_Select_Size(self.face, idx as i32) }, ()).context("FT_Select_Size")
     }
 
+    /// Applies design coordinates to the axes of a variable font,
+    /// given as (axis tag, value) pairs.  Values are clamped to the
+    /// range of their axis, and axes that aren't listed keep the
+    /// values of the selected named instance.
+    pub fn set_variation_axes(&mut self, axes: &[(String, f64)]) -> anyhow::Result<()> {
+        if axes.is_empty() {
+            return Ok(());
+        }
+        let mut mm = std::ptr::null_mut();
+        unsafe {
+            ft_result(FT_Get_MM_Var(self.face, &mut mm), ())
+                .context("FT_Get_MM_Var: is this a variable font?")?;
+            let num_axis = (*mm).num_axis as usize;
+            let axis_info = from_raw_parts((*mm).axis, num_axis);
+
+            let mut coords = vec![FT_Fixed::from_num(0); num_axis];
+            let mut result = ft_result(
+                FT_Get_Var_Design_Coordinates(self.face, num_axis as _, coords.as_mut_ptr()),
+                (),
+            )
+            .context("FT_Get_Var_Design_Coordinates");
+
+            if result.is_ok() {
+                for (tag, value) in axes {
+                    let found = match tag.as_bytes() {
+                        &[a, b, c, d] => {
+                            let tag = ft_make_tag(a, b, c, d);
+                            axis_info.iter().position(|axis| axis.tag == tag)
+                        }
+                        _ => None,
+                    };
+                    match found {
+                        Some(idx) => {
+                            let axis = &axis_info[idx];
+                            let value = value
+                                .max(axis.minimum.to_num::<f64>())
+                                .min(axis.maximum.to_num::<f64>());
+                            coords[idx] = FT_Fixed::from_num(value);
+                        }
+                        None => {
+                            log::warn!("{:?} has no variation axis `{}`", self.source, tag);
+                        }
+                    }
+                }
+                result = ft_result(
+                    FT_Set_Var_Design_Coordinates(self.face, num_axis as _, coords.as_mut_ptr()),
+                    (),
+                )
+                .context("FT_Set_Var_Design_Coordinates");
+            }
+
+            FT_Done_MM_Var(self.lib, mm);
+            result
+        }
+    }
+
     pub fn set_transform(&mut self, matrix: Option<FT_Matrix>) {
         let mut matrix = matrix;
         unsafe {
@@ -357,6 +357,27 @@ impl Font {
         }
     }
 
+    /// Applies design coordinates to the axes of a variable font,
+    /// given as (axis tag, value) pairs
+    pub fn set_variation_axes(&mut self, axes: &[(String, f64)]) {
+        let variations: Vec<hb_variation_t> = axes
+            .iter()
+            .filter_map(|(tag, value)| match tag.as_bytes() {
+                &[a, b, c, d] => Some(hb_variation_t {
+                    tag: hb_tag(a, b, c, d),
+                    value: *value as f32,
+                }),
+                _ => None,
+            })
+            .collect();
+        if variations.is_empty() {
+            return;
+        }
+        unsafe {
+            hb_font_set_variations(self.font, variations.as_ptr(), variations.len() as c_uint);
+        }
+    }
+
     /// Perform shaping.  On entry, Buffer holds the text to shape.
     /// Once done, Buffer holds the output glyph and position info
     pub fn shape(&mut self, buf: &mut Buffer, features: &[hb_feature_t]) {
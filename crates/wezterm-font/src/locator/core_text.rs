@@ -286,7 +286,10 @@ fn build_fallback_list_impl() -> anyhow::Result<Vec<ParsedFont>> {
         freetype_render_target: None,
         freetype_load_flags: None,
         scale: None,
+        vertical_offset: None,
         assume_emoji_presentation: None,
+        synthesis: None,
+        variation_axes: None,
     };
     if let Ok(descriptors) = descriptor_from_attr(&symbols) {
         for descriptor in descriptors.iter() {
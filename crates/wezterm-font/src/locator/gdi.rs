@@ -326,7 +326,10 @@ impl FontLocator for GdiFontLocator {
                         freetype_render_target: None,
                         freetype_load_flags: None,
                         scale: None,
+                        vertical_offset: None,
                         assume_emoji_presentation: None,
+                        synthesis: None,
+                        variation_axes: None,
                     };
 
                     if !resolved.contains(&attr) {
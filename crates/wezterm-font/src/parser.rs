@@ -42,6 +42,10 @@ pub struct ParsedFont {
     pub freetype_render_target: Option<FreeTypeLoadTarget>,
     pub freetype_load_flags: Option<FreeTypeLoadFlags>,
     pub scale: Option<f64>,
+    pub vertical_offset: Option<f64>,
+    /// Design coordinates to apply to the axes of a variable font,
+    /// as (axis tag, value) pairs
+    pub variation_axes: Vec<(String, f64)>,
 }
 
 impl std::fmt::Debug for ParsedFont {
@@ -63,6 +67,8 @@ impl std::fmt::Debug for ParsedFont {
             .field("freetype_render_target", &self.freetype_render_target)
             .field("freetype_load_flags", &self.freetype_load_flags)
             .field("scale", &self.scale)
+            .field("vertical_offset", &self.vertical_offset)
+            .field("variation_axes", &self.variation_axes)
             .finish()
     }
 }
@@ -88,6 +94,8 @@ impl Clone for ParsedFont {
             freetype_load_flags: self.freetype_load_flags,
             is_built_in_fallback: self.is_built_in_fallback,
             scale: self.scale,
+            vertical_offset: self.vertical_offset,
+            variation_axes: self.variation_axes.clone(),
             palettes: self.palettes.clone(),
         }
     }
@@ -337,6 +345,8 @@ impl ParsedFont {
                 && p.freetype_load_flags.is_none()
                 && p.harfbuzz_features.is_none()
                 && p.scale.is_none()
+                && p.vertical_offset.is_none()
+                && p.variation_axes.is_empty()
             {
                 code.push_str(&format!("  \"{}\",\n", p.names.family));
             } else {
@@ -353,6 +363,9 @@ impl ParsedFont {
                 if let Some(scale) = p.scale {
                     code.push_str(&format!(", scale={}", scale));
                 }
+                if let Some(offset) = p.vertical_offset {
+                    code.push_str(&format!(", vertical_offset={}", offset));
+                }
                 if let Some(item) = p.freetype_load_flags {
                     code.push_str(&format!(", freetype_load_flags=\"{}\"", item.to_string()));
                 }
@@ -374,6 +387,16 @@ impl ParsedFont {
                     }
                     code.push('}');
                 }
+                if !p.variation_axes.is_empty() {
+                    code.push_str(", variation_axes={");
+                    for (idx, (tag, value)) in p.variation_axes.iter().enumerate() {
+                        if idx > 0 {
+                            code.push_str(", ");
+                        }
+                        code.push_str(&format!("[\"{}\"]={}", tag, value));
+                    }
+                    code.push('}');
+                }
                 code.push_str("},\n")
             }
             code.push_str("\n");
@@ -529,6 +552,8 @@ impl ParsedFont {
             freetype_load_target: None,
             freetype_load_flags: None,
             scale: None,
+            vertical_offset: None,
+            variation_axes: vec![],
             palettes,
         })
     }
@@ -773,6 +798,14 @@ impl ParsedFont {
         fonts.drain(idx..=idx).next().map(|p| p.synthesize(attr))
     }
 
+    /// Returns the value that `variation_axes` gives the axis `tag`
+    pub fn variation_axis(&self, tag: &str) -> Option<f64> {
+        self.variation_axes
+            .iter()
+            .find(|(t, _)| t == tag)
+            .map(|(_, value)| *value)
+    }
+
     /// Update self to reflect whether the rasterizer might need to synthesize
     /// italic for this font.
     pub fn synthesize(mut self, attr: &FontAttributes) -> Self {
@@ -781,6 +814,13 @@ impl ParsedFont {
         self.freetype_load_target = attr.freetype_load_target;
         self.freetype_load_flags = attr.freetype_load_flags;
         self.scale = attr.scale.map(|f| *f);
+        self.vertical_offset = attr.vertical_offset.map(|f| *f);
+        self.variation_axes = attr
+            .variation_axes
+            .iter()
+            .flatten()
+            .map(|(tag, value)| (tag.clone(), **value))
+            .collect();
 
         self.synthesize_italic = self.style == FontStyle::Normal && attr.style != FontStyle::Normal;
         self.synthesize_bold = attr.weight >= FontWeight::DEMIBOLD
@@ -790,6 +830,30 @@ impl ParsedFont {
             && attr.weight < self.weight
             && self.weight >= FontWeight::REGULAR;
 
+        // When the weight or slant is pinned through the axes of a
+        // variable font, the font draws it and it must not be
+        // synthesized on top
+        if self.variation_axis("wght").is_some() {
+            self.synthesize_bold = false;
+            self.synthesize_dim = false;
+        }
+        if self.variation_axis("ital").is_some_and(|v| v > 0.)
+            || self.variation_axis("slnt").is_some_and(|v| v != 0.)
+        {
+            self.synthesize_italic = false;
+        }
+
+        let synthesis = attr
+            .synthesis
+            .unwrap_or_else(|| config::configuration().font_synthesis);
+        if !synthesis.allows_bold() {
+            self.synthesize_bold = false;
+            self.synthesize_dim = false;
+        }
+        if !synthesis.allows_italic() {
+            self.synthesize_italic = false;
+        }
+
         match attr.assume_emoji_presentation {
             Some(assume) => {
                 self.assume_emoji_presentation = assume;
@@ -384,6 +384,13 @@ impl FreeTypeRasterizer {
         log::trace!("Rasterizier wants {:?}", parsed);
         let lib = ftwrap::Library::new()?;
         let mut face = lib.face_from_locator(&parsed.handle)?;
+        if let Err(err) = face.set_variation_axes(&parsed.variation_axes) {
+            log::warn!(
+                "{:?}: unable to apply variation_axes: {:#}",
+                parsed.handle,
+                err
+            );
+        }
         let has_color = unsafe {
             (((*face.face).face_flags as u32) & (ftwrap::FT_FACE_FLAG_COLOR as u32)) != 0
         };
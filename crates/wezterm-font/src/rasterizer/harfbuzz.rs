@@ -20,6 +20,7 @@ impl HarfbuzzRasterizer {
     pub fn from_locator(parsed: &ParsedFont) -> anyhow::Result<Self> {
         let mut font = Font::from_locator(&parsed.handle)?;
         font.set_ot_funcs();
+        font.set_variation_axes(&parsed.variation_axes);
 
         if parsed.synthesize_italic {
             font.set_synthetic_slant(FAKE_ITALIC_SKEW as f32);
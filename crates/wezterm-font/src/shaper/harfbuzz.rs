@@ -157,9 +157,16 @@ impl HarfbuzzShaper {
                 if opt_pair.is_none() {
                     let handle = &self.handles[font_idx];
                     log::trace!("shaper wants {} {:?}", font_idx, handle);
-                    let face = self.lib.face_from_locator(&handle.handle)?;
+                    let mut face = self.lib.face_from_locator(&handle.handle)?;
+                    if let Err(err) = face.set_variation_axes(&handle.variation_axes) {
+                        log::warn!(
+                            "{:?}: unable to apply variation_axes: {:#}",
+                            handle.handle,
+                            err
+                        );
+                    }
 
-                    let font = if USE_OT_FACE {
+                    let mut font = if USE_OT_FACE {
                         harfbuzz::Font::from_locator(&handle.handle)?
                     } else {
                         let (load_flags, _) = ftwrap::compute_load_flags_from_config(
@@ -172,6 +179,9 @@ impl HarfbuzzShaper {
                         font.set_load_flags(load_flags);
                         font
                     };
+                    // The shaper needs the same coordinates as the
+                    // rasterizer for its advances to match the glyphs
+                    font.set_variation_axes(&handle.variation_axes);
 
                     let features = match &handle.harfbuzz_features {
                         Some(features) => features
@@ -653,6 +663,9 @@ impl FontShaper for HarfbuzzShaper {
             let diff = metrics.descender - (metrics.descender / scale);
             metrics.force_y_adjust = diff;
         }
+        if let Some(offset) = self.handles[font_idx].vertical_offset {
+            metrics.force_y_adjust += PixelLength::new(offset * size * dpi as f64 / 72.);
+        }
 
         self.metrics.borrow_mut().insert(key, metrics);
 
@@ -861,7 +874,10 @@ mod test {
                     freetype_render_target: None,
                     harfbuzz_features: None,
                     scale: None,
+                    vertical_offset: None,
                     assume_emoji_presentation: None,
+                    synthesis: None,
+                    variation_axes: None,
                 },
                 14,
             )
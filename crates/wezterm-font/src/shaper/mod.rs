@@ -79,6 +79,7 @@ pub struct FontMetrics {
     /// between the scaled and unscaled versions of the descender.
     /// This represents a y-adjustment that should be applied to
     /// the glyph to make it appear to line up better.
+    /// Any `vertical_offset` configured for the font is included.
     /// <https://github.com/wezterm/wezterm/issues/1803>
     pub force_y_adjust: PixelLength,
 }
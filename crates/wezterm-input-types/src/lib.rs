@@ -1219,11 +1219,69 @@ static PHYSKEYCODE_MAP: LazyLock<HashMap<String, PhysKeyCode>> =
 static INV_PHYSKEYCODE_MAP: LazyLock<HashMap<PhysKeyCode, String>> =
     LazyLock::new(PhysKeyCode::make_inv_map);
 
+/// Maps a W3C `KeyboardEvent.code` name, such as `KeyA` or `ArrowLeft`,
+/// to the equivalent name used by `PhysKeyCode`, so that users can bind
+/// physical keys using the names shown by browser based key inspectors.
+fn w3c_code_to_phys_name(s: &str) -> Option<&str> {
+    if let Some(letter) = s.strip_prefix("Key") {
+        if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_uppercase()) {
+            return Some(letter);
+        }
+    }
+    if let Some(digit) = s.strip_prefix("Digit") {
+        if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) {
+            return Some(digit);
+        }
+    }
+    Some(match s {
+        "Numpad0" => "Keypad0",
+        "Numpad1" => "Keypad1",
+        "Numpad2" => "Keypad2",
+        "Numpad3" => "Keypad3",
+        "Numpad4" => "Keypad4",
+        "Numpad5" => "Keypad5",
+        "Numpad6" => "Keypad6",
+        "Numpad7" => "Keypad7",
+        "Numpad8" => "Keypad8",
+        "Numpad9" => "Keypad9",
+        "NumpadAdd" => "KeypadAdd",
+        "NumpadSubtract" => "KeypadSubtract",
+        "NumpadMultiply" => "KeypadMultiply",
+        "NumpadDivide" => "KeypadDivide",
+        "NumpadDecimal" => "KeypadDecimal",
+        "NumpadEnter" => "KeypadEnter",
+        "NumpadEqual" => "KeypadEquals",
+        "ArrowLeft" => "LeftArrow",
+        "ArrowRight" => "RightArrow",
+        "ArrowUp" => "UpArrow",
+        "ArrowDown" => "DownArrow",
+        "Enter" => "Return",
+        "Backquote" => "Grave",
+        "BracketLeft" => "LeftBracket",
+        "BracketRight" => "RightBracket",
+        "ShiftLeft" => "LeftShift",
+        "ShiftRight" => "RightShift",
+        "ControlLeft" => "LeftControl",
+        "ControlRight" => "RightControl",
+        "AltLeft" => "LeftAlt",
+        "AltRight" => "RightAlt",
+        "MetaLeft" => "LeftWindows",
+        "MetaRight" => "RightWindows",
+        "Fn" => "Function",
+        "AudioVolumeMute" => "VolumeMute",
+        "AudioVolumeUp" => "VolumeUp",
+        "AudioVolumeDown" => "VolumeDown",
+        _ => return None,
+    })
+}
+
 impl TryFrom<&str> for PhysKeyCode {
     type Error = String;
     fn try_from(s: &str) -> core::result::Result<PhysKeyCode, String> {
         if let Some(code) = Self::name_to_code(s) {
             Ok(code)
+        } else if let Some(code) = w3c_code_to_phys_name(s).and_then(Self::name_to_code) {
+            Ok(code)
         } else {
             Err(format!("invalid PhysKeyCode '{}'", s))
         }
@@ -3208,4 +3266,20 @@ mod test {
             "\u{1b}[102;14u".to_string()
         );
     }
+
+    #[test]
+    fn phys_key_code_w3c_names() {
+        assert_eq!(PhysKeyCode::try_from("KeyA"), Ok(PhysKeyCode::A));
+        assert_eq!(PhysKeyCode::try_from("Digit1"), Ok(PhysKeyCode::K1));
+        assert_eq!(
+            PhysKeyCode::try_from("ArrowLeft"),
+            Ok(PhysKeyCode::LeftArrow)
+        );
+        assert_eq!(
+            PhysKeyCode::try_from("NumpadEnter"),
+            Ok(PhysKeyCode::KeypadEnter)
+        );
+        assert_eq!(PhysKeyCode::try_from("A"), Ok(PhysKeyCode::A));
+        assert!(PhysKeyCode::try_from("KeyAB").is_err());
+    }
 }
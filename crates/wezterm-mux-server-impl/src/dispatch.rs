@@ -85,7 +85,17 @@ where
                 handler.process_one(decoded);
             }
             Ok(Item::WritePdu(decoded)) => {
-                match decoded.pdu.encode_async(&mut stream, decoded.serial).await {
+                let start = std::time::Instant::now();
+                match decoded
+                    .pdu
+                    .encode_async_with(
+                        &mut stream,
+                        decoded.serial,
+                        handler.compression_level(),
+                        None,
+                    )
+                    .await
+                {
                     Ok(()) => {}
                     Err(err) => {
                         if let Some(err) = err.root_cause().downcast_ref::<std::io::Error>() {
@@ -107,6 +117,7 @@ where
                         return Err(err).context("flushing PDU to client");
                     }
                 }
+                handler.record_write(start.elapsed());
             }
             Ok(Item::Notif(MuxNotification::PaneOutput(pane_id))) => {
                 handler.schedule_pane_push(pane_id);
@@ -203,7 +214,11 @@ where
                 stream.flush().await.context("flushing PDU to client")?;
             }
             Ok(Item::Notif(MuxNotification::ActiveWorkspaceChanged(_))) => {}
+            Ok(Item::Notif(MuxNotification::TabColorChanged(_))) => {}
+            Ok(Item::Notif(MuxNotification::TabGroupChanged(_))) => {}
             Ok(Item::Notif(MuxNotification::Empty)) => {}
+            Ok(Item::Notif(MuxNotification::PaneEncodingErrors(_))) => {}
+            Ok(Item::Notif(MuxNotification::SecretRequested(_))) => {}
             Err(err) => {
                 log::error!("process_async Err {}", err);
                 return Ok(());
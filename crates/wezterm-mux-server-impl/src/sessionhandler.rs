@@ -2,6 +2,7 @@ use crate::PKI;
 use anyhow::{anyhow, Context};
 use codec::*;
 use config::TermConfig;
+use mux::askpass::SecretRequest;
 use mux::client::ClientId;
 use mux::domain::SplitSource;
 use mux::pane::{CachePolicy, Pane, PaneId};
@@ -10,7 +11,7 @@ use mux::{Mux, MuxNotification};
 use promise::spawn::spawn_into_main_thread;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use termwiz::surface::SequenceNo;
 use url::Url;
 use wezterm_term::terminal::Alert;
@@ -45,6 +46,8 @@ pub(crate) struct PerPane {
     seqno: SequenceNo,
     config_generation: usize,
     pub(crate) notifications: Vec<Alert>,
+    last_push: Option<Instant>,
+    push_scheduled: bool,
 }
 
 impl PerPane {
@@ -153,6 +156,7 @@ fn maybe_push_pane_changes(
             pdu: Pdu::GetPaneRenderChangesResponse(resp),
             serial: 0,
         })?;
+        per_pane.last_push = Some(Instant::now());
     }
 
     let config = config::configuration();
@@ -195,11 +199,45 @@ fn maybe_push_pane_changes(
     Ok(())
 }
 
+/// The longest that we'll hold back pushing changes to a pane
+const MAX_PUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Tracks how long it takes to write PDUs to the client.  Writes block
+/// once the buffers between us and a slow client are full, so this is
+/// a cheap proxy for the available bandwidth.
+#[derive(Default)]
+struct LinkEstimate {
+    write_time: Duration,
+}
+
+impl LinkEstimate {
+    fn record_write(&mut self, elapsed: Duration) {
+        // Exponentially weighted moving average
+        self.write_time = (self.write_time * 7 + elapsed) / 8;
+    }
+
+    /// How long to wait between unsolicited pushes of changes to a pane
+    fn min_push_interval(&self) -> Duration {
+        if !config::configuration().mux_adaptive_sync {
+            return Duration::ZERO;
+        }
+        let interval = (self.write_time * 4).min(MAX_PUSH_INTERVAL);
+        if interval < Duration::from_millis(1) {
+            Duration::ZERO
+        } else {
+            interval
+        }
+    }
+}
+
 pub struct SessionHandler {
     to_write_tx: PduSender,
+    link: LinkEstimate,
     per_pane: HashMap<PaneId, Arc<Mutex<PerPane>>>,
     client_id: Option<Arc<ClientId>>,
     proxy_client_id: Option<ClientId>,
+    /// Whether the client asked for the data we send it to be compressed
+    compression: bool,
 }
 
 impl Drop for SessionHandler {
@@ -215,9 +253,11 @@ impl SessionHandler {
     pub fn new(to_write_tx: PduSender) -> Self {
         Self {
             to_write_tx,
+            link: LinkEstimate::default(),
             per_pane: HashMap::new(),
             client_id: None,
             proxy_client_id: None,
+            compression: true,
         }
     }
 
@@ -229,10 +269,43 @@ impl SessionHandler {
         )
     }
 
+    /// Records how long it took to write a PDU to the client
+    pub fn record_write(&mut self, elapsed: Duration) {
+        self.link.record_write(elapsed);
+    }
+
+    /// Returns the zstd level to compress PDUs sent to this client at,
+    /// or None if they should be sent uncompressed
+    pub fn compression_level(&self) -> Option<i32> {
+        let config = config::configuration();
+        if self.compression && config.mux_compression {
+            Some(config.mux_compression_level)
+        } else {
+            None
+        }
+    }
+
     pub fn schedule_pane_push(&mut self, pane_id: PaneId) {
         let sender = self.to_write_tx.clone();
         let per_pane = self.per_pane(pane_id);
+        let delay = {
+            let mut per_pane = per_pane.lock().unwrap();
+            if per_pane.push_scheduled {
+                // The pending push will pick up these changes too
+                return;
+            }
+            per_pane.push_scheduled = true;
+            let interval = self.link.min_push_interval();
+            per_pane
+                .last_push
+                .map(|last| interval.saturating_sub(last.elapsed()))
+                .unwrap_or_default()
+        };
         spawn_into_main_thread(async move {
+            if !delay.is_zero() {
+                smol::Timer::after(delay).await;
+            }
+            per_pane.lock().unwrap().push_scheduled = false;
             let mux = Mux::get();
             let pane = mux
                 .get_pane(pane_id)
@@ -297,6 +370,7 @@ impl SessionHandler {
             Pdu::SetClientId(SetClientId {
                 mut client_id,
                 is_proxy,
+                compression,
             }) => {
                 if is_proxy {
                     if self.proxy_client_id.is_none() {
@@ -306,6 +380,7 @@ impl SessionHandler {
                         self.proxy_client_id.replace(client_id);
                     }
                 } else {
+                    self.compression = compression;
                     // If this session is a proxy, override the incoming id with
                     // the proxy information so that it is clear what is going
                     // on from the `wezterm cli list-clients` information
@@ -609,6 +684,77 @@ impl SessionHandler {
                 .detach();
             }
 
+            Pdu::GetRawOutput(GetRawOutput { pane_id, max_bytes }) => {
+                spawn_into_main_thread(async move {
+                    catch(
+                        move || {
+                            let mux = Mux::get();
+                            let pane = mux
+                                .get_pane(pane_id)
+                                .ok_or_else(|| anyhow!("no such pane {}", pane_id))?;
+                            let data = pane
+                                .raw_output_history()
+                                .map(|history| history.lock().tail(max_bytes))
+                                .unwrap_or_default();
+                            Ok(Pdu::GetRawOutputResponse(GetRawOutputResponse {
+                                pane_id,
+                                data,
+                            }))
+                        },
+                        send_response,
+                    )
+                })
+                .detach();
+            }
+
+            Pdu::SetPaneOutputLogging(SetPaneOutputLogging {
+                pane_id,
+                path,
+                include_input,
+            }) => {
+                spawn_into_main_thread(async move {
+                    catch(
+                        move || {
+                            let mux = Mux::get();
+                            let pane = mux
+                                .get_pane(pane_id)
+                                .ok_or_else(|| anyhow!("no such pane {}", pane_id))?;
+                            let output_log = pane.output_log().ok_or_else(|| {
+                                anyhow!("pane {} does not support output logging", pane_id)
+                            })?;
+                            match path {
+                                Some(path) => output_log.start(&path, include_input)?,
+                                None => {
+                                    output_log.stop();
+                                }
+                            }
+                            Ok(Pdu::UnitResponse(UnitResponse {}))
+                        },
+                        send_response,
+                    )
+                })
+                .detach();
+            }
+
+            Pdu::RequestSecret(RequestSecret { pane_id, prompt }) => {
+                spawn_into_main_thread(async move {
+                    let mux = Mux::get();
+                    if mux.get_pane(pane_id).is_none() {
+                        send_response(Err(anyhow!("no such pane {}", pane_id)));
+                        return;
+                    }
+                    // Answered by the GUI window showing the pane, once
+                    // the user has entered the secret
+                    let request = SecretRequest::new(pane_id, prompt, move |result| {
+                        send_response(result.map(|secret| {
+                            Pdu::RequestSecretResponse(RequestSecretResponse { secret })
+                        }))
+                    });
+                    mux.notify(MuxNotification::SecretRequested(request));
+                })
+                .detach();
+            }
+
             Pdu::ActivatePaneDirection(ActivatePaneDirection { pane_id, direction }) => {
                 spawn_into_main_thread(async move {
                     catch(
@@ -996,6 +1142,8 @@ impl SessionHandler {
             | Pdu::UnitResponse { .. }
             | Pdu::LivenessResponse { .. }
             | Pdu::GetPaneDirectionResponse { .. }
+            | Pdu::GetRawOutputResponse { .. }
+            | Pdu::RequestSecretResponse { .. }
             | Pdu::SearchScrollbackResponse { .. }
             | Pdu::GetLinesResponse { .. }
             | Pdu::GetCodecVersionResponse { .. }
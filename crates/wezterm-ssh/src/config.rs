@@ -559,6 +559,14 @@ impl Config {
                 .unwrap_or_else(|| "22".to_string()),
         );
 
+        // We make the connection to the host ourselves, so ProxyJump
+        // has to be expressed as the equivalent ProxyCommand
+        if !result.contains_key("proxycommand") {
+            if let Some(command) = result.get("proxyjump").and_then(|j| proxy_jump_command(j)) {
+                result.insert("proxycommand".to_string(), command);
+            }
+        }
+
         for (k, v) in &mut result {
             if let Some(tokens) = self.should_expand_tokens(k) {
                 self.expand_tokens(v, tokens, &token_map);
@@ -798,6 +806,62 @@ impl Config {
     }
 }
 
+/// Returns the ProxyCommand that is equivalent to a ProxyJump list of
+/// `[user@]host[:port]` or `ssh://[user@]host[:port]` hops; the last hop
+/// makes the final connection and any earlier hops are passed along to it.
+fn proxy_jump_command(jump: &str) -> Option<String> {
+    if jump == "none" {
+        return None;
+    }
+    let mut hops: Vec<&str> = jump.split(',').map(str::trim).collect();
+    let last = hops.pop().filter(|hop| !hop.is_empty())?;
+    let (user, host, port) = split_jump_hop(last)?;
+    let mut command = "ssh".to_string();
+    if !hops.is_empty() {
+        command.push_str(" -J ");
+        command.push_str(&hops.join(","));
+    }
+    // ssh doesn't accept a port as part of the destination
+    if let Some(port) = port {
+        command.push_str(" -p ");
+        command.push_str(port);
+    }
+    command.push_str(" -W %h:%p ");
+    if let Some(user) = user {
+        command.push_str(user);
+        command.push('@');
+    }
+    command.push_str(host);
+    Some(command)
+}
+
+/// Splits a ProxyJump hop into its user, host and port.
+/// IPv6 addresses need to be bracketed to carry a port.
+fn split_jump_hop(hop: &str) -> Option<(Option<&str>, &str, Option<&str>)> {
+    let hop = hop.strip_prefix("ssh://").unwrap_or(hop);
+    let (user, host_port) = match hop.rsplit_once('@') {
+        Some((user, host_port)) => (Some(user), host_port),
+        None => (None, hop),
+    };
+    let (host, port) = if let Some(bracketed) = host_port.strip_prefix('[') {
+        let (host, rest) = bracketed.split_once(']')?;
+        if rest.is_empty() {
+            (host, None)
+        } else {
+            (host, Some(rest.strip_prefix(':')?))
+        }
+    } else {
+        match host_port.split_once(':') {
+            Some((host, port)) if !port.contains(':') => (host, Some(port)),
+            _ => (host_port, None),
+        }
+    };
+    if host.is_empty() || port.is_some_and(|port| port.parse::<u16>().is_err()) {
+        return None;
+    }
+    Some((user, host, port))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1114,6 +1178,70 @@ Config {
         );
     }
 
+    #[test]
+    fn parse_proxy_jump() {
+        let mut config = Config::new();
+
+        let mut fake_env = ConfigMap::new();
+        fake_env.insert("HOME".to_string(), "/home/me".to_string());
+        fake_env.insert("USER".to_string(), "me".to_string());
+        config.assign_environment(fake_env);
+
+        config.add_config_string(
+            r#"
+        Host foo
+            HostName server-%h
+            Port 2222
+            ProxyJump gw1,admin@gw2:2200
+
+        Host bar
+            ProxyJump none
+            "#,
+        );
+
+        let opts = config.for_host("foo");
+        snapshot!(
+            opts,
+            r#"
+{
+    "hostname": "server-foo",
+    "identityfile": "/home/me/.ssh/id_dsa /home/me/.ssh/id_ecdsa /home/me/.ssh/id_ed25519 /home/me/.ssh/id_rsa",
+    "port": "2222",
+    "proxycommand": "ssh -J gw1 -p 2200 -W server-foo:2222 admin@gw2",
+    "proxyjump": "gw1,admin@gw2:2200",
+    "user": "me",
+    "userknownhostsfile": "/home/me/.ssh/known_hosts /home/me/.ssh/known_hosts2",
+}
+"#
+        );
+
+        let opts = config.for_host("bar");
+        assert!(!opts.contains_key("proxycommand"));
+    }
+
+    #[test]
+    fn proxy_jump_hops() {
+        assert_eq!(proxy_jump_command("gw").as_deref(), Some("ssh -W %h:%p gw"));
+        assert_eq!(
+            proxy_jump_command("ssh://admin@gw:2200").as_deref(),
+            Some("ssh -p 2200 -W %h:%p admin@gw")
+        );
+        assert_eq!(
+            proxy_jump_command("gw1:22, admin@[fe80::1]:2200").as_deref(),
+            Some("ssh -J gw1:22 -p 2200 -W %h:%p admin@fe80::1")
+        );
+        assert_eq!(
+            proxy_jump_command("[fe80::1]").as_deref(),
+            Some("ssh -W %h:%p fe80::1")
+        );
+        assert_eq!(
+            proxy_jump_command("fe80::1").as_deref(),
+            Some("ssh -W %h:%p fe80::1")
+        );
+        assert_eq!(proxy_jump_command("gw:port"), None);
+        assert_eq!(proxy_jump_command("[fe80::1]2200"), None);
+    }
+
     #[test]
     fn multiple_identityfile() {
         let mut config = Config::new();
@@ -10,18 +10,100 @@ pub struct HostVerificationFailed {
     pub file: Option<std::path::PathBuf>,
 }
 
+/// How the user chose to respond to a host key that isn't trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostVerificationDecision {
+    /// Don't connect
+    Reject,
+    /// Connect this time, without recording the key
+    AcceptOnce,
+    /// Connect and add the key to the known hosts file
+    AcceptPermanently,
+}
+
+impl From<bool> for HostVerificationDecision {
+    fn from(trust_host: bool) -> Self {
+        if trust_host {
+            Self::AcceptPermanently
+        } else {
+            Self::Reject
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct HostVerificationEvent {
     pub message: String,
-    pub(crate) reply: Sender<bool>,
+    pub remote_address: String,
+    pub fingerprint: String,
+    /// True if the host presented a different key from the one in the
+    /// known hosts file.  Such a key can only be accepted once; the
+    /// stale entry has to be removed from the file by hand.
+    pub key_changed: bool,
+    pub(crate) reply: Sender<HostVerificationDecision>,
 }
 
 impl HostVerificationEvent {
     pub async fn answer(self, trust_host: bool) -> anyhow::Result<()> {
-        Ok(self.reply.send(trust_host).await?)
+        self.decide(trust_host.into()).await
     }
     pub fn try_answer(self, trust_host: bool) -> anyhow::Result<()> {
-        Ok(self.reply.try_send(trust_host)?)
+        Ok(self.reply.try_send(trust_host.into())?)
+    }
+    pub async fn decide(self, decision: HostVerificationDecision) -> anyhow::Result<()> {
+        Ok(self.reply.send(decision).await?)
+    }
+}
+
+impl crate::sessioninner::SessionInner {
+    /// Asks the user whether to trust the host key, returning their decision
+    fn confirm_host_key(
+        &mut self,
+        message: String,
+        remote_address: String,
+        fingerprint: String,
+        key_changed: bool,
+    ) -> anyhow::Result<HostVerificationDecision> {
+        let (reply, confirm) = bounded(1);
+        self.tx_event
+            .try_send(SessionEvent::HostVerify(HostVerificationEvent {
+                message,
+                remote_address,
+                fingerprint,
+                key_changed,
+                reply,
+            }))
+            .context("sending HostVerify request to user")?;
+
+        let decision = smol::block_on(confirm.recv())
+            .context("waiting for host verification confirmation from user")?;
+        match decision {
+            HostVerificationDecision::Reject => anyhow::bail!("user declined to trust host"),
+            HostVerificationDecision::AcceptPermanently if key_changed => {
+                log::warn!("the changed host key is only being trusted for this connection");
+                Ok(HostVerificationDecision::AcceptOnce)
+            }
+            decision => Ok(decision),
+        }
+    }
+
+    /// Tells the user that the host key doesn't match the known hosts
+    /// file, then lets them decide whether to connect anyway this time
+    fn confirm_changed_host_key(&mut self, failed: HostVerificationFailed) -> anyhow::Result<()> {
+        let message = format!(
+            "The host key for {} does not match the one recorded in your known hosts file.\n\
+             Fingerprint: {}.\n\
+             Connect this time anyway?",
+            failed.remote_address, failed.key
+        );
+        let remote_address = failed.remote_address.clone();
+        let fingerprint = failed.key.clone();
+        self.tx_event
+            .try_send(SessionEvent::HostVerificationFailed(failed))
+            .context("sending HostVerificationFailed event to user")?;
+        self.confirm_host_key(message, remote_address, fingerprint, true)
+            .context("Host key verification failed")?;
+        Ok(())
     }
 }
 
@@ -40,27 +122,22 @@ impl crate::sessioninner::SessionInner {
         match sess.is_known_server()? {
             libssh_rs::KnownHosts::Ok => Ok(()),
             libssh_rs::KnownHosts::NotFound | libssh_rs::KnownHosts::Unknown => {
-                let (reply, confirm) = bounded(1);
-                self.tx_event
-                    .try_send(SessionEvent::HostVerify(HostVerificationEvent {
-                        message: format!(
-                            "SSH host {}:{} is not yet trusted.\n\
+                let decision = self.confirm_host_key(
+                    format!(
+                        "SSH host {}:{} is not yet trusted.\n\
                                     Fingerprint: {}.\n\
                                     Trust and continue connecting?",
-                            hostname, port, key
-                        ),
-                        reply,
-                    }))
-                    .context("sending HostVerify request to user")?;
-
-                let trusted = smol::block_on(confirm.recv())
-                    .context("waiting for host verification confirmation from user")?;
+                        hostname, port, key
+                    ),
+                    format!("{hostname}:{port}"),
+                    key,
+                    false,
+                )?;
 
-                if !trusted {
-                    anyhow::bail!("user declined to trust host");
+                if decision == HostVerificationDecision::AcceptPermanently {
+                    sess.update_known_hosts_file()?;
                 }
-
-                Ok(sess.update_known_hosts_file()?)
+                Ok(())
             }
             libssh_rs::KnownHosts::Changed => {
                 let mut file = None;
@@ -71,15 +148,11 @@ impl crate::sessioninner::SessionInner {
                     }
                 }
 
-                let failed = HostVerificationFailed {
+                self.confirm_changed_host_key(HostVerificationFailed {
                     remote_address: format!("{hostname}:{port}"),
                     key,
                     file,
-                };
-                self.tx_event
-                    .try_send(SessionEvent::HostVerificationFailed(failed))
-                    .context("sending HostVerificationFailed event to user")?;
-                anyhow::bail!("Host key verification failed");
+                })
             }
             libssh_rs::KnownHosts::Other => {
                 anyhow::bail!(
@@ -102,28 +175,35 @@ impl crate::sessioninner::SessionInner {
     ) -> anyhow::Result<()> {
         use anyhow::anyhow;
         use std::io::Write;
-        use std::path::Path;
+        use std::path::PathBuf;
 
         let mut known_hosts = sess.known_hosts().context("preparing known hosts")?;
 
-        let known_hosts_files = self
+        let mut known_hosts_files: Vec<PathBuf> = self
             .config
             .get("userknownhostsfile")
             .unwrap()
             .split_whitespace()
-            .map(|s| s.to_string());
+            .map(PathBuf::from)
+            .collect();
+        // If none of the files exist yet, check against an empty list so
+        // that the user is still asked about the host, and record it in
+        // the first file
+        if known_hosts_files.iter().any(|file| file.exists()) {
+            known_hosts_files.retain(|file| file.exists());
+        } else {
+            known_hosts_files.truncate(1);
+        }
 
         for file in known_hosts_files {
-            let file = Path::new(&file);
+            let file = file.as_path();
 
-            if !file.exists() {
-                continue;
+            if file.exists() {
+                known_hosts
+                    .read_file(&file, ssh2::KnownHostFileKind::OpenSSH)
+                    .with_context(|| format!("reading known_hosts file {}", file.display()))?;
             }
 
-            known_hosts
-                .read_file(&file, ssh2::KnownHostFileKind::OpenSSH)
-                .with_context(|| format!("reading known_hosts file {}", file.display()))?;
-
             let (key, key_type) = sess
                 .host_key()
                 .ok_or_else(|| anyhow!("failed to get ssh host key"))?;
@@ -155,24 +235,20 @@ impl crate::sessioninner::SessionInner {
             match known_hosts.check_port(&remote_host_name, port, key) {
                 ssh2::CheckResult::Match => {}
                 ssh2::CheckResult::NotFound => {
-                    let (reply, confirm) = bounded(1);
-                    self.tx_event
-                        .try_send(SessionEvent::HostVerify(HostVerificationEvent {
-                            message: format!(
-                                "SSH host {} is not yet trusted.\n\
+                    let decision = self.confirm_host_key(
+                        format!(
+                            "SSH host {} is not yet trusted.\n\
                                 {:?} Fingerprint: {}.\n\
                                 Trust and continue connecting?",
-                                remote_address, key_type, fingerprint
-                            ),
-                            reply,
-                        }))
-                        .context("sending HostVerify request to user")?;
-
-                    let trusted = smol::block_on(confirm.recv())
-                        .context("waiting for host verification confirmation from user")?;
+                            remote_address, key_type, fingerprint
+                        ),
+                        remote_address.to_string(),
+                        fingerprint,
+                        false,
+                    )?;
 
-                    if !trusted {
-                        anyhow::bail!("user declined to trust host");
+                    if decision == HostVerificationDecision::AcceptOnce {
+                        return Ok(());
                     }
 
                     let host_and_port = if port != 22 {
@@ -185,20 +261,21 @@ impl crate::sessioninner::SessionInner {
                         .add(&host_and_port, key, &remote_address, key_type.into())
                         .context("adding known_hosts entry in memory")?;
 
+                    if let Some(parent) = file.parent() {
+                        std::fs::create_dir_all(parent)
+                            .with_context(|| format!("creating {}", parent.display()))?;
+                    }
                     known_hosts
                         .write_file(&file, ssh2::KnownHostFileKind::OpenSSH)
                         .with_context(|| format!("writing known_hosts file {}", file.display()))?;
                 }
                 ssh2::CheckResult::Mismatch => {
-                    let failed = HostVerificationFailed {
+                    self.confirm_changed_host_key(HostVerificationFailed {
                         remote_address: remote_address.to_string(),
                         key: fingerprint,
                         file: Some(file.to_path_buf()),
-                    };
-                    self.tx_event
-                        .try_send(SessionEvent::HostVerificationFailed(failed))
-                        .context("sending HostVerificationFailed event to user")?;
-                    anyhow::bail!("Host key verification failed");
+                    })?;
+                    return Ok(());
                 }
                 ssh2::CheckResult::Failure => {
                     anyhow::bail!("failed to check the known hosts");
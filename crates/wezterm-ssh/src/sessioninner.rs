@@ -296,6 +296,11 @@ impl SessionInner {
             sess.trace(ssh2::TraceFlags::all());
         }
         sess.set_blocking(true);
+        // libssh picks this up when it parses the ssh config files,
+        // but libssh2 has to be told explicitly
+        if let Some("yes") = self.config.get("compression").map(|s| s.as_str()) {
+            sess.set_compress(true);
+        }
         sess.set_tcp_stream(sock);
         sess.handshake()
             .with_context(|| format!("ssh handshake with {}", remote_address))?;
@@ -419,6 +419,30 @@ impl Line {
         )
     }
 
+    /// Record the outcome of the command entered on this line's prompt,
+    /// as reported by the shell.  `None` clears any recorded outcome.
+    pub fn set_command_succeeded(&mut self, succeeded: Option<bool>, seqno: SequenceNo) {
+        self.bits.remove(LineBits::COMMAND_STATUS_MASK);
+        match succeeded {
+            Some(true) => self.bits.insert(LineBits::COMMAND_SUCCEEDED),
+            Some(false) => self.bits.insert(LineBits::COMMAND_FAILED),
+            None => {}
+        }
+        self.update_last_change_seqno(seqno);
+    }
+
+    /// Returns the outcome of the command entered on this line's prompt,
+    /// if one was recorded via `set_command_succeeded`.
+    pub fn command_succeeded(&self) -> Option<bool> {
+        if self.bits.contains(LineBits::COMMAND_FAILED) {
+            Some(false)
+        } else if self.bits.contains(LineBits::COMMAND_SUCCEEDED) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
     fn invalidate_zones(&mut self) {
         self.zones.clear();
     }
@@ -49,5 +49,17 @@ bitflags! {
         /// Otherwise, the auto-detect direction is used, falling back
         /// to the direction specified by the RTL bit.
         const AUTO_DETECT_DIRECTION = 1<<8;
+
+        /// true if the command entered on this line's prompt
+        /// exited with a zero status
+        const COMMAND_SUCCEEDED = 1<<9;
+
+        /// true if the command entered on this line's prompt
+        /// exited with a non-zero status
+        const COMMAND_FAILED = 1<<10;
+
+        const COMMAND_STATUS_MASK =
+            Self::COMMAND_SUCCEEDED.bits() |
+            Self::COMMAND_FAILED.bits();
     }
 }
@@ -20,6 +20,11 @@ impl ColorEase {
         out_function: EasingFunction,
         start: Option<Instant>,
     ) -> Self {
+        let (in_function, out_function) = if config::configuration().reduce_motion() {
+            (EasingFunction::Constant, EasingFunction::Constant)
+        } else {
+            (in_function, out_function)
+        };
         Self {
             in_duration: Duration::from_millis(in_duration_ms).as_secs_f32(),
             in_function,
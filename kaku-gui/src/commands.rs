@@ -392,7 +392,15 @@ impl CommandDef {
         fn is_rare_action(action: &KeyAssignment) -> bool {
             matches!(
                 action,
-                ShowDebugOverlay | OpenUri(_) | ScrollToTop | ScrollToBottom | ToggleAlwaysOnBottom
+                ShowDebugOverlay
+                    | ShowPaneProcessInfo
+                    | ShowPaneHexDump(_)
+                    | ShowPaneLatency
+                    | ToggleDebugHud
+                    | OpenUri(_)
+                    | ScrollToTop
+                    | ScrollToBottom
+                    | ToggleAlwaysOnBottom
             )
         }
 
@@ -686,9 +694,12 @@ impl CommandDef {
 
         let mut commands = Self::actions_for_palette_and_menubar(config);
         commands.retain(|cmd| !cmd.menubar.is_empty());
+        commands.extend(Self::user_commands_for_menubar());
 
         // Prefer to put the menus in this order
-        let mut order: Vec<&'static str> = vec!["Kaku", "Shell", "Edit", "View", "Window", "Help"];
+        let mut order: Vec<&'static str> = vec![
+            "Kaku", "Shell", "Edit", "View", "Commands", "Window", "Help",
+        ];
         // Add any other menus on the end
         for cmd in &commands {
             if !order.contains(&cmd.menubar[0]) {
@@ -1005,6 +1016,109 @@ impl CommandDef {
                 item.get_menu().map(|menu| menu.remove_item(&item));
             }
         }
+
+        Self::update_status_item(config);
+    }
+
+    /// Returns the commands registered via `wezterm.register_command`,
+    /// placed in the "Commands" menu
+    #[cfg(target_os = "macos")]
+    fn user_commands_for_menubar() -> Vec<ExpandedCommand> {
+        crate::termwindow::palette::registered_user_commands()
+            .into_iter()
+            .map(|entry| ExpandedCommand {
+                brief: entry.brief.into(),
+                doc: entry.doc.unwrap_or_default().into(),
+                action: entry.action,
+                keys: vec![],
+                menubar: &["Commands"],
+                icon: entry.icon.map(Into::into),
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn update_status_item(_config: &ConfigHandle) {}
+
+    /// Creates, refreshes or removes the status bar item according to
+    /// `macos_menu_bar_status_item`.
+    /// Unlike the main menu, the status item menu is not in use while
+    /// we rebuild it, so it is simply recreated from scratch.  This is
+    /// called whenever the set of workspaces changes so that the list
+    /// of workspaces stays current.
+    #[cfg(target_os = "macos")]
+    pub fn update_status_item(config: &ConfigHandle) {
+        use std::cell::RefCell;
+        use window::os::macos::menu::*;
+
+        thread_local! {
+            static STATUS_ITEM: RefCell<Option<StatusItem>> = RefCell::new(None);
+        }
+
+        if !config.macos_menu_bar_status_item {
+            STATUS_ITEM.with(|status| status.borrow_mut().take());
+            return;
+        }
+
+        #[allow(unexpected_cfgs)] // <https://github.com/SSheldon/rust-objc/issues/125>
+        let kaku_perform_key_assignment_sel = sel!(kakuPerformKeyAssignment:);
+
+        let menu = Menu::new_with_title("Kaku");
+        let add_item = |title: &str, action: KeyAssignment, checked: bool| {
+            let item = MenuItem::new_with(title, Some(kaku_perform_key_assignment_sel), "");
+            item.set_represented_item(RepresentedItem::KeyAssignment(action));
+            item.set_state(checked);
+            menu.add_item(&item);
+        };
+
+        let mut active_workspace = None;
+        if let Some(mux) = Mux::try_get() {
+            let active = mux.active_workspace();
+            for workspace in mux.iter_workspaces() {
+                let checked = workspace == active;
+                add_item(
+                    &workspace,
+                    KeyAssignment::SwitchToWorkspace {
+                        name: Some(workspace.clone()),
+                        spawn: None,
+                    },
+                    checked,
+                );
+            }
+            active_workspace.replace(active);
+        }
+        add_item(
+            "Create new Workspace",
+            KeyAssignment::SwitchToWorkspace {
+                name: None,
+                spawn: None,
+            },
+            false,
+        );
+
+        let launchers: Vec<ExpandedCommand> = Self::actions_for_palette_and_menubar(config)
+            .into_iter()
+            .filter(|cmd| matches!(cmd.action, SpawnCommandInNewTab(_)))
+            .chain(Self::user_commands_for_menubar())
+            .collect();
+        if !launchers.is_empty() {
+            menu.add_item(&MenuItem::new_separator());
+            for cmd in launchers {
+                add_item(&cmd.brief, cmd.action, false);
+            }
+        }
+
+        menu.add_item(&MenuItem::new_separator());
+        add_item("New Window", SpawnWindow, false);
+
+        STATUS_ITEM.with(|status| {
+            let mut status = status.borrow_mut();
+            let item = status.get_or_insert_with(|| StatusItem::new("Kaku"));
+            if let Some(active) = &active_workspace {
+                item.set_tool_tip(&format!("Kaku: workspace {active}"));
+            }
+            item.set_menu(&menu);
+        });
     }
 }
 
@@ -1105,6 +1219,74 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &["Edit"],
             icon: None,
         },
+        SetTabColor(None) => CommandDef {
+            brief: "Clear tab color".into(),
+            doc: "Removes the color assigned to the active tab".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: None,
+        },
+        SetTabColor(Some(color)) => CommandDef {
+            brief: format!("Set tab color to {color}").into(),
+            doc: "Tints the tab bar entry and pane borders of the active tab".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: None,
+        },
+        SetTabGroup(None) => CommandDef {
+            brief: "Remove tab from group".into(),
+            doc: "Removes the active tab from its tab group".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: None,
+        },
+        SetTabGroup(Some(group)) => CommandDef {
+            brief: format!("Add tab to group `{group}`").into(),
+            doc: "Adds the active tab to the named tab group".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: None,
+        },
+        ToggleTabGroupCollapsed => CommandDef {
+            brief: "Collapse/expand tab group".into(),
+            doc: "Collapses or expands the active tab's group in the tab bar".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: None,
+        },
+        CloseTabGroup => CommandDef {
+            brief: "Close tab group".into(),
+            doc: "Closes the tabs in the active tab's group, leaving open \
+                  any that still have running processes"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: None,
+        },
+        MoveTabGroupToNewWindow => CommandDef {
+            brief: "Move tab group to new window".into(),
+            doc: "Moves every tab in the active tab's group into a new window".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: None,
+        },
+        TogglePresentationMode => CommandDef {
+            brief: "Toggle Presentation Mode".into(),
+            doc: "Enlarges the font in every window, hides the tab bar \
+                  and suppresses notifications until toggled off again"
+                .into(),
+            keys: vec![],
+            args: &[],
+            menubar: &["View"],
+            icon: None,
+        },
         ToggleFullScreen => CommandDef {
             brief: "Toggle Full Screen".into(),
             doc: "Toggle full screen mode".into(),
@@ -1201,6 +1383,16 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &[],
             icon: None,
         },
+        SetPaneScrollbackLines(lines) => CommandDef {
+            brief: format!("Keep {lines} lines of scrollback in this pane").into(),
+            doc: "Changes the scrollback size of the active pane, discarding \
+                  any lines beyond the new size"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
         Search(Pattern::CurrentSelectionOrEmptyString) => CommandDef {
             brief: "Search".into(),
             doc: "Search in current pane".into(),
@@ -1284,6 +1476,14 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &["Window"],
             icon: None,
         },
+        ShowPaneJumpHints => CommandDef {
+            brief: "Jump to Pane or Tab".into(),
+            doc: "Labels every visible pane and tab; typing a label focuses it".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &["Window"],
+            icon: None,
+        },
         PaneSelect(PaneSelectArguments {
             mode: PaneSelectMode::SwapWithActive,
             ..
@@ -1411,6 +1611,19 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &[],
             icon: None,
         },
+        RecreateTabInDomain(domain) => CommandDef {
+            brief: match domain {
+                SpawnTabDomain::DomainName(name) => format!("Recreate Tab in {name}").into(),
+                _ => "Recreate Tab in Domain".into(),
+            },
+            doc: "Spawns a tab with the same split layout and working directories \
+                  as the active tab in another domain"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: None,
+        },
         SpawnCommandInNewTab(cmd) => CommandDef {
             brief: label_string(action, format!("Spawn a new Tab with {cmd:?}").to_string()).into(),
             doc: format!("Spawn a new Tab with {cmd:?}").into(),
@@ -1496,6 +1709,53 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &[],
             icon: None,
         },
+        SetPaneInputEncoding(encoding) => CommandDef {
+            brief: format!("Set Pane Input Encoding to {encoding}").into(),
+            doc: format!("Encodes input sent to the current pane as {encoding}").into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
+        SetPaneOutputEncoding(encoding) => CommandDef {
+            brief: format!("Set Pane Output Encoding to {encoding}").into(),
+            doc: format!("Decodes output from the current pane as {encoding}").into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
+        ReinterpretScrollbackWithEncoding(encoding) => CommandDef {
+            brief: format!("Reinterpret Scrollback as {encoding}").into(),
+            doc: format!("Decodes the retained output of the current pane again as {encoding}")
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
+        TogglePredictiveEcho => CommandDef {
+            brief: "Toggle predictive echo".into(),
+            doc: "Switches speculative local echo of typed characters on or off \
+                  for the remote domain of the current pane"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
+        TogglePaneOutputLogging(logging) => CommandDef {
+            brief: "Toggle pane output logging".into(),
+            doc: format!(
+                "Starts or stops appending the raw output of the current pane to {}",
+                logging.path
+            )
+            .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
         EmitEvent(name) => {
             if name == "run-kaku-ai-config" {
                 CommandDef {
@@ -1740,6 +2000,16 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &[],
             icon: None,
         },
+        UpdatePlugins => CommandDef {
+            brief: "Update plugins".into(),
+            doc: "Fetches the latest version of every installed plugin, \
+                  respecting any pinned rev, then reloads the configuration"
+                .into(),
+            keys: vec![],
+            args: &[],
+            menubar: &[],
+            icon: None,
+        },
         QuitApplication => CommandDef {
             brief: "Quit Kaku".into(),
             doc: "Quits Kaku".into(),
@@ -1875,6 +2145,23 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
                 icon: None,
             }
         }
+        ScrollToFailedPrompt(n) => {
+            let (direction, amount) = if *n < 0 { ("up", -n) } else { ("down", *n) };
+            let ordinal = english_ordinal(amount);
+            CommandDef {
+                brief: format!("Scroll {direction} {amount} failed command(s)").into(),
+                doc: format!(
+                    "Scrolls the viewport {direction} to the \
+                             {ordinal} prompt in that direction whose \
+                             command exited with a non-zero status"
+                )
+                .into(),
+                keys: vec![],
+                args: &[ArgType::ActivePane],
+                menubar: &[],
+                icon: None,
+            }
+        }
         ScrollByCurrentEventWheelDelta => CommandDef {
             brief: "Scrolls based on the mouse wheel position \
                 in the current mouse event"
@@ -2029,6 +2316,16 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &["Window"],
             icon: None,
         },
+        TogglePaneOutputPause => CommandDef {
+            brief: "Pause Pane Output".into(),
+            doc: "Stops reading output from the active pane so that it can be read, \
+                  or resumes it"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
         ActivateLastTab => CommandDef {
             brief: "Last Active Tab".into(),
             doc: "Switch to last active tab".into(),
@@ -2061,6 +2358,46 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &[],
             icon: None,
         },
+        ShowPaneProcessInfo => CommandDef {
+            brief: "Show pane processes".into(),
+            doc: "Shows the process tree of the active pane with live CPU and memory usage".into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &["Shell"],
+            icon: None,
+        },
+        ShowPaneHexDump(kib) => CommandDef {
+            brief: "Show pane output as hex".into(),
+            doc: format!(
+                "Shows the last {kib} KiB of raw output from the active pane \
+                 as a hex dump with escape sequences highlighted"
+            )
+            .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
+        ShowPaneLatency => CommandDef {
+            brief: "Show pane latency".into(),
+            doc: "Shows rolling statistics for how long the active pane takes \
+                  to respond to key presses"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: None,
+        },
+        ToggleDebugHud => CommandDef {
+            brief: "Toggle performance HUD".into(),
+            doc: "Shows or hides an overlay with frame timing, quad counts, \
+                  glyph cache and pty throughput statistics"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: None,
+        },
         ShowTabNavigator => CommandDef {
             brief: "Tab Navigator".into(),
             doc: "Interactive tab switcher".into(),
@@ -2069,6 +2406,41 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &["Window"],
             icon: None,
         },
+        ShowTabOverview => CommandDef {
+            brief: "Tab Overview".into(),
+            doc: "Shows a grid previewing every tab, searchable by title or process".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &["Window"],
+            icon: None,
+        },
+        ShowCommandHistory => CommandDef {
+            brief: "Command History".into(),
+            doc: "Shows the commands run in this workspace, with their exit status and duration"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &["Window"],
+            icon: None,
+        },
+        SearchCommandHistory => CommandDef {
+            brief: "Search Command History".into(),
+            doc: "Fuzzy searches the commands run in every workspace, \
+                  to paste or re-run one in the active pane"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &["Window"],
+            icon: None,
+        },
+        LockSession => CommandDef {
+            brief: "Lock Session".into(),
+            doc: "Hides the panes behind a lock screen until it is unlocked".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &["Window"],
+            icon: None,
+        },
         DetachDomain(SpawnTabDomain::CurrentPaneDomain) => CommandDef {
             brief: "Detach the domain of the active pane".into(),
             doc: "Detaches (disconnects from) the domain of the active pane".into(),
@@ -2502,6 +2874,7 @@ fn compute_default_actions() -> Vec<KeyAssignment> {
         ScrollByPage(NotNan::new(1.0).unwrap()),
         ScrollToTop,
         ScrollToBottom,
+        TogglePresentationMode,
         // ----------------- Window
         ToggleFullScreen,
         Hide,
@@ -2511,6 +2884,7 @@ fn compute_default_actions() -> Vec<KeyAssignment> {
             mode: PaneSelectMode::Activate,
             show_pane_ids: false,
         }),
+        ShowPaneJumpHints,
         PaneSelect(PaneSelectArguments {
             alphabet: String::new(),
             mode: PaneSelectMode::SwapWithActive,
@@ -2558,14 +2932,24 @@ fn compute_default_actions() -> Vec<KeyAssignment> {
         ActivatePaneDirection(PaneDirection::Up),
         ActivatePaneDirection(PaneDirection::Down),
         TogglePaneZoomState,
+        TogglePaneOutputPause,
         ActivateLastTab,
         ShowTabNavigator,
+        ShowTabOverview,
+        ShowCommandHistory,
+        SearchCommandHistory,
+        LockSession,
         // ----------------- Help
         OpenUri("https://github.com/tw93/Kaku".to_string()),
         OpenUri("https://github.com/tw93/Kaku/issues/".to_string()),
         ShowDebugOverlay,
+        ShowPaneProcessInfo,
+        ShowPaneHexDump(4),
+        ShowPaneLatency,
+        ToggleDebugHud,
         // ----------------- Misc
         OpenLinkAtMouseCursor,
+        UpdatePlugins,
     ];
 
     actions.extend(
@@ -4,6 +4,7 @@ use crate::termwindow::TermWindowNotif;
 use crate::TermWindow;
 use ::window::*;
 use anyhow::{Context, Error};
+use chrono::Timelike;
 use config::keyassignment::{KeyAssignment, SpawnCommand, SpawnTabDomain};
 use config::{ConfigSubscription, NotificationHandling};
 use mux::client::ClientId;
@@ -15,7 +16,7 @@ use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
-use wezterm_term::{Alert, ClipboardSelection};
+use wezterm_term::{Alert, ClipboardSelection, Progress};
 use wezterm_toast_notification::*;
 
 pub const SET_DEFAULT_TERMINAL_EVENT: &str = "set-default-terminal";
@@ -29,6 +30,10 @@ pub struct GuiFrontEnd {
     config_subscription: RefCell<Option<ConfigSubscription>>,
     /// Global count of unread bell events across all windows
     unread_bell_count: RefCell<usize>,
+    /// Whether presentation mode is active in all windows
+    presentation_mode: RefCell<bool>,
+    /// What the Dock icon currently shows for OSC 9;4 progress
+    dock_progress: RefCell<Option<DockProgress>>,
 }
 
 impl Drop for GuiFrontEnd {
@@ -270,9 +275,15 @@ impl GuiFrontEnd {
             client_id: client_id.clone(),
             config_subscription: RefCell::new(None),
             unread_bell_count: RefCell::new(0),
+            presentation_mode: RefCell::new(false),
+            dock_progress: RefCell::new(None),
         });
 
         mux.subscribe(move |n| {
+            if let MuxNotification::PaneRemoved(pane_id) = &n {
+                // Otherwise the pane's pty read rate is kept forever
+                crate::stats::forget_pane(*pane_id);
+            }
             match n {
                 MuxNotification::WorkspaceRenamed {
                     old_workspace,
@@ -297,6 +308,7 @@ impl GuiFrontEnd {
                         if !fe.is_switching_workspace() {
                             fe.reconcile_workspace();
                         }
+                        crate::commands::CommandDef::update_status_item(&config::configuration());
                     })
                     .detach();
                 }
@@ -310,10 +322,23 @@ impl GuiFrontEnd {
                     .detach();
                 }
                 MuxNotification::TabTitleChanged { .. } => {}
+                MuxNotification::TabColorChanged(_) => {}
+                MuxNotification::TabGroupChanged(_) => {}
                 MuxNotification::WindowTitleChanged { .. } => {}
                 MuxNotification::TabResized(_) => {}
-                MuxNotification::TabAddedToWindow { .. } => {}
-                MuxNotification::PaneRemoved(_) => {}
+                MuxNotification::PaneEncodingErrors(_) => {}
+                MuxNotification::SecretRequested(_) => {
+                    // Handled via TermWindowNotif
+                }
+                MuxNotification::TabAddedToWindow { tab_id, window_id } => {
+                    if cfg!(target_os = "macos") && config::configuration().use_native_macos_tabs
+                    {
+                        promise::spawn::spawn_into_main_thread(async move {
+                            move_tab_to_own_window(tab_id, window_id);
+                        })
+                        .detach();
+                    }
+                }
                 MuxNotification::WindowInvalidated(_) => {}
                 MuxNotification::PaneOutput(_) => {}
                 MuxNotification::PaneAdded(_) => {}
@@ -337,6 +362,9 @@ impl GuiFrontEnd {
                             let show = match config.notification_handling {
                                 NotificationHandling::NeverShow => false,
                                 NotificationHandling::AlwaysShow => true,
+                                // Presentation mode silences everything
+                                // that isn't explicitly always shown
+                                _ if in_presentation_mode() => false,
                                 NotificationHandling::SuppressFromFocusedPane => f_pane != pane_id,
                                 NotificationHandling::SuppressFromFocusedTab => f_tab != tab_id,
                                 NotificationHandling::SuppressFromFocusedWindow => {
@@ -352,15 +380,40 @@ impl GuiFrontEnd {
                                 // notification is clicked
                                 persistent_toast_notification(title, message);
                             }
+                            if config.bell_request_attention
+                                && f_window != window_id
+                                && !in_presentation_mode()
+                            {
+                                promise::spawn::spawn_into_main_thread(async move {
+                                    if let Some(conn) = Connection::get() {
+                                        conn.request_user_attention();
+                                    }
+                                })
+                                .detach();
+                            }
                         }
                     }
                 }
                 MuxNotification::Alert {
                     pane_id: _,
-                    alert: Alert::Bell | Alert::Progress(_),
+                    alert: Alert::Bell,
                 } => {
                     // Handled via TermWindowNotif; NOP it here.
                 }
+                MuxNotification::Alert {
+                    pane_id: _,
+                    alert: Alert::Progress(_),
+                }
+                | MuxNotification::PaneRemoved(_) => {
+                    // The tab bars are updated via TermWindowNotif;
+                    // the Dock icon is shared by all windows
+                    promise::spawn::spawn_into_main_thread(async move {
+                        if let Some(fe) = try_front_end() {
+                            fe.update_dock_progress();
+                        }
+                    })
+                    .detach();
+                }
                 MuxNotification::Alert {
                     pane_id: _,
                     alert:
@@ -370,7 +423,9 @@ impl GuiFrontEnd {
                         | Alert::WindowTitleChanged(_)
                         | Alert::TabTitleChanged(_)
                         | Alert::IconTitleChanged(_)
-                        | Alert::SetUserVar { .. },
+                        | Alert::TabColorChanged(_)
+                        | Alert::SetUserVar { .. }
+                        | Alert::CommandFinished { .. },
                 } => {}
                 MuxNotification::Empty => {
                     #[cfg(target_os = "macos")]
@@ -676,6 +731,12 @@ impl GuiFrontEnd {
                     KeyAssignment::SpawnCommandInNewWindow(spawn) => {
                         spawn_command(&spawn, SpawnWhere::NewWindow);
                     }
+                    KeyAssignment::SwitchToWorkspace {
+                        name: Some(name), ..
+                    } => {
+                        // Picked from the status item while no window is focused
+                        self.switch_workspace(&name);
+                    }
                     _ => {
                         log::warn!("unhandled perform: {action:?}");
                     }
@@ -858,6 +919,98 @@ impl GuiFrontEnd {
         }
     }
 
+    /// Shows the progress reported by panes via OSC 9;4 on the Dock icon,
+    /// if `dock_progress_bar` is enabled.  The percentages reported by
+    /// panes are averaged; panes in an error state don't contribute, so
+    /// the bar is removed once nothing is making progress.
+    pub fn update_dock_progress(&self) {
+        let mut progress = None;
+        if config::configuration().dock_progress_bar {
+            let mut total = 0u32;
+            let mut count = 0u32;
+            let mut indeterminate = false;
+            for pane in Mux::get().iter_panes() {
+                match pane.get_progress() {
+                    Progress::Percentage(pct) => {
+                        total += pct.min(100) as u32;
+                        count += 1;
+                    }
+                    Progress::Indeterminate => indeterminate = true,
+                    Progress::Error(_) | Progress::None => {}
+                }
+            }
+            if count > 0 {
+                progress = Some(DockProgress::Percentage((total / count) as u8));
+            } else if indeterminate {
+                progress = Some(DockProgress::Indeterminate);
+            }
+        }
+        if self.dock_progress.replace(progress) != progress {
+            self.connection.set_dock_progress(progress);
+        }
+    }
+
+    /// Works out whether the dark color scheme applies, from
+    /// color_scheme_schedule if one is configured and otherwise from the
+    /// system appearance, and picks up the system accessibility display
+    /// settings.  When either changes in a way that the config depends
+    /// on, the config is reloaded so that color_scheme_dark or
+    /// color_scheme_light, and the accessibility adjustments, take effect,
+    /// and true is returned.  Otherwise this only records the new state,
+    /// which avoids a full reload at startup for most users.
+    pub fn sync_appearance(&self) -> bool {
+        let config = config::configuration();
+        let scheduled = config.color_scheme_schedule.as_ref().and_then(|schedule| {
+            let now = chrono::Local::now();
+            let dark = schedule.is_dark_at(now.hour() * 60 + now.minute());
+            if dark.is_none() {
+                log::warn!(
+                    "color_scheme_schedule {schedule:?} is invalid: \
+                     expected times of day as \"HH:MM\""
+                );
+            }
+            dark
+        });
+        let dark = scheduled.unwrap_or_else(|| {
+            matches!(
+                self.connection.get_appearance(),
+                Appearance::Dark | Appearance::DarkHighContrast
+            )
+        });
+        let dark_changed = config::set_dark_appearance(dark);
+        let accessibility_changed =
+            config::set_system_accessibility(self.connection.get_system_accessibility());
+        if (dark_changed && config.depends_on_dark_appearance())
+            || (accessibility_changed && config.follows_system_accessibility())
+        {
+            config::reload();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_presentation_mode(&self) -> bool {
+        *self.presentation_mode.borrow()
+    }
+
+    /// Toggles presentation mode in every window; notifications are
+    /// suppressed while it is active
+    pub fn toggle_presentation_mode(&self) {
+        let enabled = {
+            let mut mode = self.presentation_mode.borrow_mut();
+            *mode = !*mode;
+            *mode
+        };
+        for gui_win in self.gui_windows() {
+            gui_win
+                .window
+                .notify(TermWindowNotif::Apply(Box::new(move |tw| {
+                    tw.set_presentation_mode(enabled)
+                })));
+        }
+    }
+
     pub fn is_switching_workspace(&self) -> bool {
         *self.switching_workspaces.borrow()
     }
@@ -884,6 +1037,41 @@ pub fn try_front_end() -> Option<Rc<GuiFrontEnd>> {
     FRONT_END.with(|f| f.borrow().as_ref().map(Rc::clone))
 }
 
+/// Returns true if presentation mode is active; safe to call from
+/// any thread, returning false when not on the gui thread
+fn in_presentation_mode() -> bool {
+    FRONT_END.with(|f| {
+        f.borrow()
+            .as_ref()
+            .is_some_and(|fe| fe.is_presentation_mode())
+    })
+}
+
+/// With `use_native_macos_tabs` each mux window holds a single tab,
+/// and macOS groups their gui windows as tabs.  A tab that was added to
+/// a window that already has one, whether by a key assignment,
+/// `kaku cli spawn`, lua or attaching a domain, is moved into a new
+/// window of its own, which macOS then merges into the tab group.
+fn move_tab_to_own_window(tab_id: mux::tab::TabId, window_id: MuxWindowId) {
+    let mux = Mux::get();
+    let Some(tab) = mux.get_tab(tab_id) else {
+        return;
+    };
+    let workspace = match mux.get_window(window_id) {
+        Some(window) if window.len() > 1 && window.idx_by_id(tab_id).is_some() => {
+            window.get_workspace().to_string()
+        }
+        _ => return,
+    };
+    let new_window = mux.new_empty_window(Some(workspace), None);
+    if let Some(mut window) = mux.get_window_mut(window_id) {
+        window.remove_by_id(tab_id);
+    }
+    if let Err(err) = mux.add_tab_to_window(&tab, *new_window) {
+        log::error!("Failed to move tab {tab_id} to a native tab: {err:#}");
+    }
+}
+
 pub fn front_end() -> Rc<GuiFrontEnd> {
     FRONT_END
         .with(|f| f.borrow().as_ref().map(Rc::clone))
@@ -921,6 +1109,24 @@ pub fn try_new() -> Result<Rc<GuiFrontEnd>, Error> {
     let front_end = GuiFrontEnd::try_new()?;
     FRONT_END.with(|f| *f.borrow_mut() = Some(Rc::clone(&front_end)));
 
+    // Pick up the initial appearance, then keep checking so that a
+    // color_scheme_schedule switches schemes at the configured times
+    front_end.sync_appearance();
+    promise::spawn::spawn(async {
+        loop {
+            smol::Timer::after(std::time::Duration::from_secs(60)).await;
+            let fe = front_end();
+            if fe.sync_appearance() {
+                for gui_win in fe.gui_windows() {
+                    gui_win.window.notify(TermWindowNotif::Apply(Box::new(|tw| {
+                        tw.emit_window_event("appearance-changed", None)
+                    })));
+                }
+            }
+        }
+    })
+    .detach();
+
     let config_subscription = config::subscribe_to_config_reload({
         move || {
             // This callback may run while the config mutex is held;
@@ -930,6 +1136,7 @@ pub fn try_new() -> Result<Rc<GuiFrontEnd>, Error> {
                 if let Some(conn) = Connection::get() {
                     conn.sync_global_hotkey();
                 }
+                crate::commands::CommandDef::update_status_item(&config::configuration());
             })
             .detach();
             // TODO(macos): AppKit does not allow safe async menubar reconstruction
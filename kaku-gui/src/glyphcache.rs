@@ -556,7 +556,8 @@ impl DecodedImage {
 }
 
 /// A number of items here are HashMaps rather than LfuCaches;
-/// eviction is managed by recreating Self when the Atlas is filled
+/// eviction is managed by evicting pages from the Atlas when it is
+/// filled (see `evict_lru_page`), or by recreating Self as a last resort
 pub struct GlyphCache {
     glyph_cache: HashMap<GlyphKey, Rc<CachedGlyph>>,
     pub atlas: Atlas,
@@ -691,6 +692,28 @@ impl GlyphCache {
         Ok(glyph)
     }
 
+    /// Evict the least recently drawn page of the atlas, and forget
+    /// everything that was cached on it.  Returns false if no page
+    /// could be evicted.
+    /// Sprites from the evicted page may still be referenced by shaped
+    /// lines and laid out elements held elsewhere; the caller is
+    /// responsible for invalidating those.
+    pub fn evict_lru_page(&mut self) -> bool {
+        let Some(page) = self.atlas.evict_lru_page() else {
+            return false;
+        };
+        log::trace!("evicted glyph atlas page {page}");
+        let keep = |sprite: &Sprite| sprite.page() != page;
+        self.glyph_cache
+            .retain(|_, glyph| glyph.texture.as_ref().is_none_or(keep));
+        self.frame_cache.retain(|_, sprite| keep(sprite));
+        self.line_glyphs.retain(|_, sprite| keep(sprite));
+        self.block_glyphs.retain(|_, sprite| keep(sprite));
+        self.cursor_glyphs.retain(|_, sprite| keep(sprite));
+        self.color.retain(|_, sprite| keep(sprite));
+        true
+    }
+
     pub fn config_changed(&mut self) {
         let config = self.fonts.config();
         self.image_cache.update_config(&config);
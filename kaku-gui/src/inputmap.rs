@@ -111,6 +111,30 @@ impl InputMap {
                     },
                     ScrollByCurrentEventWheelDelta
                 ],
+                [
+                    MouseEventTriggerMods {
+                        mods: Modifiers::NONE,
+                        mouse_reporting: false,
+                        alt_screen: MouseEventAltScreen::False,
+                    },
+                    MouseEventTrigger::Down {
+                        streak: 1,
+                        button: MouseButton::WheelLeft(1),
+                    },
+                    ScrollByCurrentEventWheelDelta
+                ],
+                [
+                    MouseEventTriggerMods {
+                        mods: Modifiers::NONE,
+                        mouse_reporting: false,
+                        alt_screen: MouseEventAltScreen::False,
+                    },
+                    MouseEventTrigger::Down {
+                        streak: 1,
+                        button: MouseButton::WheelRight(1),
+                    },
+                    ScrollByCurrentEventWheelDelta
+                ],
                 [
                     MouseEventTriggerMods {
                         mods: Modifiers::NONE,
@@ -0,0 +1,185 @@
+//! `kaku ls-fonts`: explains how the configured font stack resolves,
+//! and which font ends up rendering each character of a given string.
+use anyhow::Context;
+use config::ConfigHandle;
+use termwiz::cell::CellAttributes;
+use termwiz::surface::{Line, SEQ_ZERO};
+use wezterm_font::parser::ParsedFont;
+use wezterm_font::{FontConfiguration, RasterizedGlyph};
+use wezterm_gui_subcommands::LsFontsCommand;
+
+/// Whether `parsed` is the built-in font that draws placeholder boxes
+/// for codepoints that no other font covers
+fn is_last_resort(parsed: &ParsedFont) -> bool {
+    let family = &parsed.names().family;
+    family == "Last Resort High-Efficiency" || family == ".LastResort"
+}
+
+fn parse_codepoints(codepoints: &str) -> anyhow::Result<String> {
+    codepoints
+        .split(',')
+        .map(|cp| {
+            let cp = cp.trim().trim_start_matches("U+").trim_start_matches("u+");
+            let value = u32::from_str_radix(cp, 16)
+                .with_context(|| format!("{cp} is not a hex codepoint"))?;
+            char::from_u32(value).ok_or_else(|| anyhow::anyhow!("{cp} is not a valid codepoint"))
+        })
+        .collect()
+}
+
+fn print_ascii_glyph(glyph: &RasterizedGlyph) {
+    for row in glyph.data.chunks(glyph.width.max(1) * 4).take(glyph.height) {
+        let line: String = row
+            .chunks(4)
+            .map(|pixel| match pixel[3] {
+                0..=63 => ' ',
+                64..=191 => '+',
+                _ => '#',
+            })
+            .collect();
+        println!("    {}", line.trim_end());
+    }
+}
+
+fn explain_text(
+    config: &ConfigHandle,
+    font_config: &FontConfiguration,
+    text: &str,
+    rasterize_ascii: bool,
+) -> anyhow::Result<()> {
+    let line = Line::from_text(text, &CellAttributes::default(), SEQ_ZERO, None);
+    let mut missing = vec![];
+
+    for cluster in line.cluster(None) {
+        let style = font_config.match_style(config, &cluster.attrs);
+        let font = font_config.resolve_font(style)?;
+        let handles = font.clone_handles();
+        let infos = font.blocking_shape(
+            &cluster.text,
+            Some(cluster.presentation),
+            cluster.direction,
+            None,
+            None,
+        )?;
+
+        for info in infos {
+            let cell_idx = cluster.byte_to_cell_idx(info.cluster as usize);
+            let Some(cell) = line.get_cell(cell_idx) else {
+                continue;
+            };
+            let text = cell.str();
+            let escaped = text.escape_unicode().to_string();
+            let parsed = &handles[info.font_idx];
+            let is_missing = info.glyph_pos == 0 || is_last_resort(parsed);
+            if is_missing {
+                missing.push(text.to_string());
+            }
+
+            println!(
+                "{text:4} {escaped:12} x_adv={:<2} cells={:<2} glyph={:<4} {}{}",
+                info.x_advance.get(),
+                info.num_cells,
+                info.glyph_pos,
+                parsed.lua_name(),
+                if is_missing { "  <-- NO GLYPH" } else { "" },
+            );
+            println!("{:38}{}", "", parsed.handle.diagnostic_string());
+
+            if rasterize_ascii {
+                let glyph = font.rasterize_glyph(info.glyph_pos, info.font_idx)?;
+                print_ascii_glyph(&glyph);
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        println!();
+        println!(
+            "No configured or fallback font has a glyph for: {}",
+            missing.join(" ")
+        );
+        println!(
+            "Install a font that covers them and add it to `font = wezterm.font_with_fallback {{ ... }}`"
+        );
+    }
+    Ok(())
+}
+
+fn print_fallback(
+    font_config: &FontConfiguration,
+    style: &config::TextStyle,
+) -> anyhow::Result<()> {
+    let font = font_config.resolve_font(style)?;
+    for line in ParsedFont::lua_fallback(&font.clone_handles()).lines() {
+        println!("  {line}");
+    }
+    Ok(())
+}
+
+pub fn run_ls_fonts(config: ConfigHandle, cmd: &LsFontsCommand) -> anyhow::Result<()> {
+    let font_config = FontConfiguration::new(
+        Some(config.clone()),
+        config.dpi.unwrap_or_else(|| ::window::default_dpi()) as usize,
+    )?;
+
+    let text = match (&cmd.text, &cmd.codepoints) {
+        (Some(text), _) => Some(text.clone()),
+        (None, Some(codepoints)) => Some(parse_codepoints(codepoints)?),
+        (None, None) => None,
+    };
+    if let Some(text) = text {
+        return explain_text(&config, &font_config, &text, cmd.rasterize_ascii);
+    }
+
+    println!("Primary font:");
+    print_fallback(&font_config, &config.font)?;
+
+    for rule in &config.font_rules {
+        let mut condition = "When".to_string();
+        if let Some(intensity) = &rule.intensity {
+            condition.push_str(&format!(" Intensity={intensity:?}"));
+        }
+        if let Some(underline) = &rule.underline {
+            condition.push_str(&format!(" Underline={underline:?}"));
+        }
+        if let Some(italic) = rule.italic {
+            condition.push_str(&format!(" Italic={italic}"));
+        }
+        if let Some(blink) = &rule.blink {
+            condition.push_str(&format!(" Blink={blink:?}"));
+        }
+        if let Some(reverse) = rule.reverse {
+            condition.push_str(&format!(" Reverse={reverse}"));
+        }
+        if let Some(strikethrough) = rule.strikethrough {
+            condition.push_str(&format!(" Strikethrough={strikethrough}"));
+        }
+        if let Some(invisible) = rule.invisible {
+            condition.push_str(&format!(" Invisible={invisible}"));
+        }
+        println!();
+        println!("{condition}:");
+        print_fallback(&font_config, &rule.font)?;
+    }
+
+    println!();
+    let font_dirs = font_config.list_fonts_in_font_dirs();
+    if !font_dirs.is_empty() {
+        println!("Fonts found using font_dirs=[{}]:", config.font_dirs.len());
+        for font in font_dirs {
+            println!("{} -- {}", font.lua_name(), font.handle.diagnostic_string());
+        }
+        println!();
+    }
+
+    if cmd.list_system {
+        let mut fonts = font_config.list_system_fonts()?;
+        fonts.sort_by_cached_key(|font| font.lua_name());
+        println!("Fonts found using system font locator:");
+        for font in fonts {
+            println!("{} -- {}", font.lua_name(), font.handle.diagnostic_string());
+        }
+    }
+
+    Ok(())
+}
@@ -23,7 +23,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use wezterm_client::domain::ClientDomain;
 use wezterm_font::FontConfiguration;
-use wezterm_gui_subcommands::{name_equals_value, StartCommand};
+use wezterm_gui_subcommands::{name_equals_value, LsFontsCommand, StartCommand};
 use wezterm_mux_server_impl::update_mux_domains;
 use wezterm_toast_notification::*;
 
@@ -34,6 +34,7 @@ mod download;
 mod frontend;
 mod glyphcache;
 mod inputmap;
+mod lsfonts;
 mod overlay;
 mod quad;
 mod renderstate;
@@ -110,6 +111,9 @@ enum SubCommand {
     /// <https://github.com/clap-rs/clap/issues/1335>
     #[command(short_flag_alias = 'e', hide = true)]
     BlockingStart(StartCommand),
+
+    #[command(name = "ls-fonts", about = "Display information about fonts")]
+    LsFonts(LsFontsCommand),
 }
 
 fn have_panes_in_domain_and_ws(domain: &Arc<dyn Domain>, workspace: &Option<String>) -> bool {
@@ -199,7 +203,7 @@ async fn spawn_tab_in_domain_if_mux_is_empty(
             config.initial_size(dpi as u32, None),
             cmd,
             None,
-            config.default_encoding,
+            config.default_encoding_for_domain(domain.domain_name()),
             window_id,
         )
         .await?;
@@ -368,7 +372,7 @@ async fn async_run_terminal_gui(
                     config.initial_size(dpi as u32, None),
                     cmd.clone(),
                     None,
-                    config.default_encoding,
+                    config.default_encoding_for_domain(domain.domain_name()),
                     window_id,
                 )
                 .await?;
@@ -899,5 +903,6 @@ fn run() -> anyhow::Result<()> {
             res
         }
         SubCommand::BlockingStart(_) => unreachable!(),
+        SubCommand::LsFonts(cmd) => lsfonts::run_ls_fonts(config, &cmd),
     }
 }
@@ -0,0 +1,76 @@
+use mux::askpass::SecretRequest;
+use mux::termwiztermtab::TermWizTerminal;
+use std::sync::Arc;
+use termwiz::cell::{AttributeChange, Intensity};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers};
+use termwiz::surface::Change;
+use termwiz::terminal::Terminal;
+
+/// Prompts for the secret asked for by `kaku askpass`, without echoing
+/// it, and answers `request` with it.  Escape or Ctrl-C cancels.
+pub fn show_askpass(request: Arc<SecretRequest>, mut term: TermWizTerminal) -> anyhow::Result<()> {
+    let result = read_secret(&request.prompt, &mut term);
+    // If the overlay was cancelled, let the program asking know
+    // rather than leaving it to wait
+    request.answer(result.as_ref().ok().cloned().flatten());
+    result.map(|_| ())
+}
+
+fn read_secret(prompt: &str, term: &mut TermWizTerminal) -> anyhow::Result<Option<String>> {
+    term.set_raw_mode()?;
+    term.no_grab_mouse_in_raw_mode();
+
+    let prompt = prompt
+        .trim_end()
+        .replace("\r\n", "\n")
+        .replace('\n', "\r\n");
+    let mut secret = String::new();
+
+    loop {
+        term.render(&[
+            Change::ClearScreen(ColorAttribute::Default),
+            AttributeChange::Intensity(Intensity::Bold).into(),
+            Change::Text("🔑 A program in this pane is asking for a secret\r\n".to_string()),
+            AttributeChange::Intensity(Intensity::Half).into(),
+            Change::Text("Enter to submit, Escape to cancel\r\n\r\n".to_string()),
+            AttributeChange::Intensity(Intensity::Normal).into(),
+            Change::Text(format!("{prompt} ")),
+            Change::Text("•".repeat(secret.chars().count())),
+        ])?;
+        term.flush()?;
+
+        match term.poll_input(None)? {
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Escape,
+                ..
+            }))
+            | Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('c') | KeyCode::Char('d'),
+                modifiers: Modifiers::CTRL,
+            })) => return Ok(None),
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Enter,
+                ..
+            })) => return Ok(Some(secret)),
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Backspace,
+                ..
+            })) => {
+                secret.pop();
+            }
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('u'),
+                modifiers: Modifiers::CTRL,
+            })) => secret.clear(),
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Char(c),
+                modifiers: Modifiers::NONE | Modifiers::SHIFT,
+            })) => secret.push(c),
+            Some(InputEvent::Paste(text)) => {
+                secret.push_str(text.trim_end_matches(['\r', '\n']));
+            }
+            Some(_) | None => {}
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use crate::overlay::selector::{matcher_pattern, matcher_score};
+use mux::command_history::CommandHistoryEntry;
+use mux::pane::Pane;
+use mux::termwiztermtab::TermWizTerminal;
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::Arc;
+use termwiz::cell::{AttributeChange, Intensity};
+use termwiz::color::{AnsiColor, ColorAttribute};
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers};
+use termwiz::surface::{Change, CursorVisibility, Position};
+use termwiz::terminal::Terminal;
+
+/// Which history the overlay shows
+pub enum CommandHistoryScope {
+    /// Every command recorded in the named workspace
+    Workspace(String),
+    /// The most recent run of each distinct command, from all workspaces
+    AllWorkspaces,
+}
+
+struct HistoryRow {
+    workspace: String,
+    entry: CommandHistoryEntry,
+}
+
+/// Formats a command duration compactly, eg: `250ms`, `1.5s` or `2m05s`
+pub fn format_duration(duration_ms: u64) -> String {
+    if duration_ms < 1000 {
+        format!("{duration_ms}ms")
+    } else if duration_ms < 60_000 {
+        format!("{:.1}s", duration_ms as f64 / 1000.)
+    } else {
+        let secs = duration_ms / 1000;
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+fn format_status(status: Option<i32>) -> String {
+    match status {
+        Some(status) => status.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        s.to_string()
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut res: String = s.chars().take(width - 1).collect();
+        res.push('…');
+        res
+    }
+}
+
+fn load_rows(scope: &CommandHistoryScope) -> anyhow::Result<Vec<HistoryRow>> {
+    match scope {
+        CommandHistoryScope::Workspace(workspace) => Ok(mux::command_history::load(workspace)?
+            .into_iter()
+            .map(|entry| HistoryRow {
+                workspace: workspace.clone(),
+                entry,
+            })
+            .collect()),
+        CommandHistoryScope::AllWorkspaces => {
+            let mut seen = HashSet::new();
+            let mut rows: Vec<HistoryRow> = mux::command_history::load_all()?
+                .into_iter()
+                .rev()
+                .filter(|(_, entry)| seen.insert(entry.command.clone()))
+                .map(|(workspace, entry)| HistoryRow { workspace, entry })
+                .collect();
+            rows.reverse();
+            Ok(rows)
+        }
+    }
+}
+
+/// Returns the indices of the rows matching `filter`.
+/// With no filter, the newest come first; otherwise the best fuzzy
+/// matches on the command line or its cwd come first, breaking ties
+/// in favor of the most recent.
+fn filter_rows(rows: &[HistoryRow], filter: &str) -> Vec<usize> {
+    if filter.is_empty() {
+        return (0..rows.len()).rev().collect();
+    }
+    let pattern = matcher_pattern(filter);
+    let mut scored: Vec<(u32, usize)> = rows
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, row)| {
+            let command = matcher_score(&pattern, &row.entry.command);
+            let cwd = row
+                .entry
+                .cwd
+                .as_deref()
+                .and_then(|cwd| matcher_score(&pattern, cwd));
+            command.max(cwd).map(|score| (score, idx))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.cmp(a));
+    scored.into_iter().map(|(_, idx)| idx).collect()
+}
+
+/// Shows recorded command history, filtered by fuzzy matching what is
+/// typed.  Enter pastes the selected command into `pane` without
+/// running it; Alt-Enter or Ctrl-Enter pastes it and runs it.
+pub fn show_command_history(
+    pane: Arc<dyn Pane>,
+    scope: CommandHistoryScope,
+    mut term: TermWizTerminal,
+) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+
+    let rows = load_rows(&scope)?;
+    let show_workspace = matches!(scope, CommandHistoryScope::AllWorkspaces);
+    let title = match &scope {
+        CommandHistoryScope::Workspace(workspace) => {
+            format!("Command history for workspace {workspace}")
+        }
+        CommandHistoryScope::AllWorkspaces => "Command history".to_string(),
+    };
+    let mut filter = String::new();
+    let mut matches = filter_rows(&rows, &filter);
+    let mut selected = 0;
+    let mut top = 0;
+
+    loop {
+        let size = term.get_screen_size()?;
+        let max_rows = size.rows.saturating_sub(3);
+        if selected < top {
+            top = selected;
+        } else if selected >= top + max_rows {
+            top = selected + 1 - max_rows;
+        }
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorVisibility(CursorVisibility::Hidden),
+            AttributeChange::Intensity(Intensity::Bold).into(),
+            Change::Text(format!(
+                "{title} (Enter to paste, Alt-Enter to run, Escape to close): {filter}_\r\n"
+            )),
+            Change::Text(format!(
+                "{:>6} {:>8}  {}\r\n",
+                "STATUS",
+                "TIME",
+                if show_workspace {
+                    "WORKSPACE: COMMAND / CWD"
+                } else {
+                    "COMMAND / CWD"
+                }
+            )),
+            AttributeChange::Intensity(Intensity::Normal).into(),
+        ];
+
+        if rows.is_empty() {
+            changes.push(Change::Text(
+                "No commands have been recorded; \
+                 set `command_history_log = true` and enable shell integration."
+                    .to_string(),
+            ));
+        }
+
+        for (row, &idx) in matches.iter().enumerate().skip(top).take(max_rows) {
+            let HistoryRow { workspace, entry } = &rows[idx];
+            let mut prefix = format!(
+                "{:>6} {:>8}  ",
+                format_status(entry.exit_status),
+                format_duration(entry.duration_ms)
+            );
+            if show_workspace {
+                prefix.push_str(&format!("{workspace}: "));
+            }
+            let avail = size.cols.saturating_sub(prefix.chars().count());
+            let failed = entry.exit_status.is_some_and(|status| status != 0);
+            if row == selected {
+                changes.push(AttributeChange::Reverse(true).into());
+            }
+            if failed {
+                changes.push(AttributeChange::Foreground(AnsiColor::Red.into()).into());
+            }
+            changes.push(Change::Text(prefix));
+            changes.push(AttributeChange::Foreground(ColorAttribute::Default).into());
+            let command = truncate(&entry.command.replace('\n', " "), avail);
+            let used = command.chars().count() + 2;
+            changes.push(Change::Text(command));
+            if let Some(cwd) = &entry.cwd {
+                if used < avail {
+                    changes.push(AttributeChange::Intensity(Intensity::Half).into());
+                    changes.push(Change::Text(format!("  {}", truncate(cwd, avail - used))));
+                    changes.push(AttributeChange::Intensity(Intensity::Normal).into());
+                }
+            }
+            changes.push(AttributeChange::Reverse(false).into());
+            changes.push(Change::Text("\r\n".to_string()));
+        }
+
+        changes.push(Change::CursorPosition {
+            x: Position::Absolute(0),
+            y: Position::Absolute(0),
+        });
+        term.render(&changes)?;
+        term.flush()?;
+
+        match term.poll_input(None)? {
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Escape,
+                ..
+            })) => return Ok(()),
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Enter,
+                modifiers,
+            })) => {
+                if let Some(&idx) = matches.get(selected) {
+                    pane.send_paste(&rows[idx].entry.command)?;
+                    if modifiers.intersects(Modifiers::ALT | Modifiers::CTRL) {
+                        pane.writer().write_all(b"\r")?;
+                    }
+                }
+                return Ok(());
+            }
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::UpArrow,
+                ..
+            })) => {
+                selected = selected.saturating_sub(1);
+            }
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::DownArrow,
+                ..
+            })) => {
+                if selected + 1 < matches.len() {
+                    selected += 1;
+                }
+            }
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Backspace,
+                ..
+            })) => {
+                filter.pop();
+                matches = filter_rows(&rows, &filter);
+                selected = 0;
+            }
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Char(c),
+                modifiers: Modifiers::NONE | Modifiers::SHIFT,
+            })) => {
+                filter.push(c);
+                matches = filter_rows(&rows, &filter);
+                selected = 0;
+            }
+            Some(_) => {}
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(command: &str, cwd: &str) -> HistoryRow {
+        HistoryRow {
+            workspace: "default".to_string(),
+            entry: CommandHistoryEntry {
+                command: command.to_string(),
+                cwd: Some(cwd.to_string()),
+                started: 0,
+                duration_ms: 0,
+                exit_status: Some(0),
+                pane_id: 0,
+                domain: "local".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn filtering() {
+        let rows = vec![
+            row("ls", "/home"),
+            row("cargo build", "/src"),
+            row("cargo test", "/src"),
+        ];
+        assert_eq!(filter_rows(&rows, ""), vec![2, 1, 0]);
+        assert_eq!(filter_rows(&rows, "cgtst"), vec![2]);
+        let cargo = filter_rows(&rows, "CARGO");
+        assert_eq!(cargo.len(), 2);
+        assert!(!cargo.contains(&0));
+        assert_eq!(filter_rows(&rows, "home"), vec![0]);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(250), "250ms");
+        assert_eq!(format_duration(1500), "1.5s");
+        assert_eq!(format_duration(125_000), "2m05s");
+    }
+}
@@ -29,7 +29,7 @@ fn run_confirmation_impl(message: &str, term: &mut TermWizTerminal) -> anyhow::R
     // Now we want to vertically center the prompt in the view.
     // After the prompt there will be a blank line and then the "buttons",
     // so we add two to the number of rows.
-    let top_row = (size.rows - (message_rows + 2)) / 2;
+    let top_row = size.rows.saturating_sub(message_rows + 2) / 2;
 
     let button_row = top_row + message_rows + 1;
     let mut active = ActiveButton::None;
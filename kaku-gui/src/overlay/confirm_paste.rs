@@ -0,0 +1,90 @@
+use super::confirm;
+use crate::TermWindow;
+use mux::pane::PaneId;
+use mux::termwiztermtab::TermWizTerminal;
+use mux::Mux;
+
+/// Maximum number of lines of the pasted text shown in the preview
+const PREVIEW_LINES: usize = 8;
+/// Preview lines longer than this are truncated
+const PREVIEW_LINE_WIDTH: usize = 120;
+
+/// Returns true if `text` invokes `sudo` as a word anywhere in it
+pub fn paste_mentions_sudo(text: &str) -> bool {
+    text.split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|' | '(' | '`'))
+        .any(|word| word == "sudo")
+}
+
+fn paste_confirmation_message(text: &str) -> String {
+    let lines: Vec<&str> = text.trim_end_matches(['\r', '\n']).lines().collect();
+
+    let mut message = format!("📋 Paste {} lines into this pane?", lines.len());
+    if paste_mentions_sudo(text) {
+        message.push_str("\n⚠️ The pasted text runs sudo.");
+    }
+    message.push('\n');
+
+    for line in lines.iter().take(PREVIEW_LINES) {
+        message.push_str("\n│ ");
+        match line.char_indices().nth(PREVIEW_LINE_WIDTH) {
+            Some((idx, _)) => {
+                message.push_str(&line[..idx]);
+                message.push('…');
+            }
+            None => message.push_str(line),
+        }
+    }
+    if lines.len() > PREVIEW_LINES {
+        message.push_str(&format!("\n│ … {} more lines", lines.len() - PREVIEW_LINES));
+    }
+
+    message
+}
+
+pub fn confirm_paste(
+    pane_id: PaneId,
+    text: String,
+    mut term: TermWizTerminal,
+    window: ::window::Window,
+) -> anyhow::Result<()> {
+    if confirm::run_confirmation(&paste_confirmation_message(&text), &mut term)? {
+        promise::spawn::spawn_into_main_thread(async move {
+            let mux = Mux::get();
+            let pane = match mux.get_pane(pane_id) {
+                Some(pane) => pane,
+                None => return,
+            };
+            if let Err(err) = pane.send_paste(&text) {
+                log::warn!("failed to paste into pane {pane_id}: {err:#}");
+            }
+        })
+        .detach();
+    }
+    TermWindow::schedule_cancel_overlay_for_pane(window, pane_id);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sudo_detection() {
+        assert!(paste_mentions_sudo("sudo rm -rf /tmp/x\n"));
+        assert!(paste_mentions_sudo("cd /tmp && sudo make install"));
+        assert!(paste_mentions_sudo("echo hi;sudo reboot"));
+        assert!(!paste_mentions_sudo("echo pseudocode"));
+        assert!(!paste_mentions_sudo("visudo_helper --check"));
+    }
+
+    #[test]
+    fn preview_is_truncated() {
+        let text = (0..20).map(|i| format!("line {i}\n")).collect::<String>();
+        let message = paste_confirmation_message(&text);
+        assert!(message.starts_with("📋 Paste 20 lines"));
+        assert!(message.contains("│ line 7"));
+        assert!(!message.contains("│ line 8"));
+        assert!(message.ends_with("│ … 12 more lines"));
+    }
+}
@@ -89,12 +89,98 @@ struct CopyRenderable {
     searching: Option<Searching>,
     pending_jump: Option<PendingJump>,
     last_jump: Option<Jump>,
+    /// Additional matches that have been added to the selection;
+    /// these are yanked together with the active match
+    selected_matches: SelectedMatches,
 }
 
 struct Searching {
     remain: StableRowIndex,
 }
 
+/// The matches that have been added to the selection, along with the
+/// pattern that found them.  The search results are recomputed from
+/// scratch whenever the pattern or the pane content changes, so these
+/// are only trusted once they have been found again.
+#[derive(Default)]
+struct SelectedMatches {
+    matches: Vec<SearchResult>,
+    pattern: Option<Pattern>,
+}
+
+/// Compares the position of two results; `match_id` is assigned
+/// afresh each time that the search runs
+fn same_match(a: &SearchResult, b: &SearchResult) -> bool {
+    (a.start_y, a.start_x, a.end_y, a.end_x) == (b.start_y, b.start_x, b.end_y, b.end_x)
+}
+
+impl SelectedMatches {
+    fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    fn contains(&self, result: &SearchResult) -> bool {
+        self.matches.iter().any(|m| same_match(m, result))
+    }
+
+    fn add(&mut self, pattern: &Pattern, result: SearchResult) {
+        if self.pattern.as_ref() != Some(pattern) {
+            self.matches.clear();
+            self.pattern = Some(pattern.clone());
+        }
+        if !self.contains(&result) {
+            self.matches.push(result);
+        }
+    }
+
+    /// Forgets all of the matches, returning them so that the lines
+    /// they are on can be redrawn
+    fn clear(&mut self) -> Vec<SearchResult> {
+        self.pattern.take();
+        std::mem::take(&mut self.matches)
+    }
+
+    /// Called as the search is started over for `pattern`.  Matches
+    /// found by some other pattern no longer apply.
+    fn search_started(&mut self, pattern: &Pattern) -> Vec<SearchResult> {
+        if self.pattern.as_ref() == Some(pattern) {
+            vec![]
+        } else {
+            self.clear()
+        }
+    }
+
+    /// Called once the search has completed; drops the matches that
+    /// are no longer among its `results`
+    fn search_completed(&mut self, results: &[SearchResult]) {
+        self.matches
+            .retain(|m| results.iter().any(|result| same_match(m, result)));
+    }
+
+    /// Returns the matches to yank, in the order that they appear in
+    /// the scrollback, including the `active` match.  Only matches that
+    /// are part of the current `results` are returned, so a search that
+    /// is still running can't yank stale positions.
+    fn to_copy(
+        &self,
+        active: Option<&SearchResult>,
+        results: &[SearchResult],
+    ) -> Vec<SearchResult> {
+        let mut matches: Vec<SearchResult> = results
+            .iter()
+            .filter(|result| self.contains(result) || Some(*result) == active)
+            .copied()
+            .collect();
+        matches.sort();
+        matches.dedup_by(|a, b| same_match(a, b));
+        matches
+    }
+}
+
 #[derive(Debug)]
 struct MatchResult {
     range: Range<usize>,
@@ -167,6 +253,7 @@ impl CopyOverlay {
             searching: None,
             pending_jump: None,
             last_jump: None,
+            selected_matches: SelectedMatches::default(),
         };
 
         let search_row = render.compute_search_row();
@@ -314,6 +401,9 @@ impl CopyRenderable {
         self.results.clear();
         self.by_line.clear();
         self.result_pos.take();
+        for result in self.selected_matches.search_started(&self.get_pattern()) {
+            self.dirty_results.add(result.start_y);
+        }
 
         {
             let mut patterns = SAVED_PATTERN.lock();
@@ -395,6 +485,7 @@ impl CopyRenderable {
         let dims = self.delegate.get_dimensions();
         if range.start == dims.scrollback_top {
             self.searching.take();
+            self.selected_matches.search_completed(&self.results);
             return;
         }
 
@@ -456,9 +547,6 @@ impl CopyRenderable {
 
     fn clamp_cursor_to_scrollback(&mut self) {
         let dims = self.delegate.get_dimensions();
-        if self.cursor.x >= dims.cols {
-            self.cursor.x = dims.cols - 1;
-        }
         if self.cursor.y < dims.scrollback_top {
             self.cursor.y = dims.scrollback_top;
         }
@@ -467,6 +555,14 @@ impl CopyRenderable {
         if self.cursor.y >= max_row {
             self.cursor.y = max_row - 1;
         }
+
+        // Lines that are wider than the pane can be walked to their end
+        let y = self.cursor.y;
+        let (_, lines) = self.delegate.get_lines(y..y + 1);
+        let width = lines.first().map_or(0, |line| line.len()).max(dims.cols);
+        if self.cursor.x >= width {
+            self.cursor.x = width - 1;
+        }
     }
 
     fn select_to_cursor_pos(&mut self) {
@@ -553,6 +649,7 @@ impl CopyRenderable {
     }
 
     fn adjust_viewport_for_cursor_position(&self) {
+        self.adjust_horizontal_scroll_for_cursor_position();
         let dims = self.dimensions();
 
         if dims.top > self.cursor.y {
@@ -574,6 +671,21 @@ impl CopyRenderable {
         }
     }
 
+    fn adjust_horizontal_scroll_for_cursor_position(&self) {
+        let pane = Arc::clone(&self.delegate);
+        let cols = pane.get_dimensions().cols;
+        let x = self.cursor.x;
+        self.window
+            .notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+                let column = term_window.get_horizontal_scroll(pane.pane_id());
+                if x < column {
+                    term_window.set_horizontal_scroll(&pane, x);
+                } else if x >= column + cols {
+                    term_window.set_horizontal_scroll(&pane, x + 1 - cols);
+                }
+            })));
+    }
+
     fn set_viewport(&self, row: Option<StableRowIndex>) {
         let dims = self.delegate.get_dimensions();
         let pane_id = self.delegate.pane_id();
@@ -738,9 +850,99 @@ impl CopyRenderable {
     fn clear_pattern(&mut self) {
         self.search_line.clear();
         self.search_selection_anchor = None;
+        self.selected_matches.clear();
         self.update_search();
     }
 
+    /// Returns true if the result at `idx` should be highlighted as
+    /// part of the selection: either it is the active match, or it has
+    /// been added to the set of selected matches.
+    fn is_selected_result(&self, idx: usize) -> bool {
+        Some(idx) == self.result_pos
+            || self
+                .results
+                .get(idx)
+                .map(|result| self.selected_matches.contains(result))
+                .unwrap_or(false)
+    }
+
+    /// Add the active match to the set of selected matches and
+    /// advance to the next match, similar to "select next occurrence"
+    /// in a text editor.
+    fn add_match_to_selection_and_move_to_next(&mut self) {
+        let result = match self.result_pos.and_then(|idx| self.results.get(idx)) {
+            Some(result) => *result,
+            None => return,
+        };
+        let pattern = self.get_pattern();
+        self.selected_matches.add(&pattern, result);
+        self.next_match();
+        self.dirty_results.add(result.start_y);
+        self.window.invalidate();
+    }
+
+    fn clear_match_selections(&mut self) {
+        for result in self.selected_matches.clear() {
+            self.dirty_results.add(result.start_y);
+        }
+        self.window.invalidate();
+    }
+
+    fn result_text(&self, result: &SearchResult) -> String {
+        let (top, lines) = self.delegate.get_lines(result.start_y..result.end_y + 1);
+        let mut text = String::new();
+        for (idx, line) in lines.iter().enumerate() {
+            let y = top + idx as StableRowIndex;
+            let start = if y == result.start_y {
+                result.start_x
+            } else {
+                0
+            };
+            let end = if y == result.end_y {
+                result.end_x
+            } else {
+                usize::max_value()
+            };
+            let cols = line.columns_as_str(start..end);
+            if y == result.end_y || line.last_cell_was_wrapped() {
+                text.push_str(&cols);
+            } else {
+                text.push_str(cols.trim_end());
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    /// If matches have been added to the selection, yank the text of
+    /// all of them, in the order that they appear in the scrollback,
+    /// joined by newlines.  Returns false if there is no such
+    /// multi-match selection, in which case the regular selection
+    /// should be copied instead.
+    fn copy_selected_matches(&self, dest: ClipboardCopyDestination) -> bool {
+        if self.selected_matches.is_empty() {
+            return false;
+        }
+        let active = self.result_pos.and_then(|idx| self.results.get(idx));
+        let matches = self.selected_matches.to_copy(active, &self.results);
+        if matches.is_empty() {
+            return false;
+        }
+
+        let text = matches
+            .iter()
+            .map(|result| self.result_text(result))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.window
+            .notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+                term_window.copy_to_clipboard(dest, text);
+                term_window.show_copy_toast();
+            })));
+        true
+    }
+
     fn edit_pattern(&mut self) {
         self.editing_search = true;
         self.search_selection_anchor = None;
@@ -1423,9 +1625,16 @@ impl Pane for CopyOverlay {
                     JumpBackward { prev_char } => render.jump(false, *prev_char),
                     JumpAgain => render.jump_again(false),
                     JumpReverse => render.jump_again(true),
+                    AddMatchToSelectionAndMoveToNext => {
+                        render.add_match_to_selection_and_move_to_next()
+                    }
+                    ClearMatchSelections => render.clear_match_selections(),
                 }
                 PerformAssignmentResult::Handled
             }
+            KeyAssignment::CopyTo(dest) if render.copy_selected_matches(*dest) => {
+                PerformAssignmentResult::Handled
+            }
             _ => PerformAssignmentResult::Unhandled,
         }
     }
@@ -1454,6 +1663,10 @@ impl Pane for CopyOverlay {
         self.delegate.erase_scrollback(erase_mode)
     }
 
+    fn set_scrollback_lines(&self, lines: Option<usize>) {
+        self.delegate.set_scrollback_lines(lines)
+    }
+
     fn is_mouse_grabbed(&self) -> bool {
         // Force grabbing off while we're searching
         false
@@ -1577,10 +1790,15 @@ impl Pane for CopyOverlay {
                             None => String::new(),
                         };
 
+                        let selected = match self.renderer.selected_matches.len() {
+                            0 => String::new(),
+                            n => format!(" {n} selected."),
+                        };
+
                         line.overlay_text_with_attribute(
                             0,
                             &format!(
-                                "Search: {} ({}/{} matches. {}{remain})",
+                                "Search: {} ({}/{} matches.{selected} {}{remain})",
                                 *pattern,
                                 self.renderer.result_pos.map(|x| x + 1).unwrap_or(0),
                                 self.renderer.results.len(),
@@ -1598,7 +1816,7 @@ impl Pane for CopyOverlay {
                                 if let Some(cell) =
                                     line.cells_mut_for_attr_changes_only().get_mut(cell_idx)
                                 {
-                                    if Some(m.result_index) == self.renderer.result_pos {
+                                    if self.renderer.is_selected_result(m.result_index) {
                                         cell.attrs_mut()
                                             .set_background(
                                                 colors
@@ -1670,10 +1888,14 @@ impl Pane for CopyOverlay {
                     Pattern::CaseInSensitiveString(_) => "ignore-case",
                     Pattern::Regex(_) => "regex",
                 };
+                let selected = match renderer.selected_matches.len() {
+                    0 => String::new(),
+                    n => format!(" {n} selected."),
+                };
                 line.overlay_text_with_attribute(
                     0,
                     &format!(
-                        "Search: {} ({}/{} matches. {})",
+                        "Search: {} ({}/{} matches.{selected} {})",
                         *pattern,
                         renderer.result_pos.map(|x| x + 1).unwrap_or(0),
                         renderer.results.len(),
@@ -1689,7 +1911,7 @@ impl Pane for CopyOverlay {
                     for cell_idx in m.range.clone() {
                         if let Some(cell) = line.cells_mut_for_attr_changes_only().get_mut(cell_idx)
                         {
-                            if Some(m.result_index) == renderer.result_pos {
+                            if renderer.is_selected_result(m.result_index) {
                                 cell.attrs_mut()
                                     .set_background(
                                         colors
@@ -2106,6 +2328,21 @@ pub fn copy_key_table() -> KeyTable {
             Modifiers::NONE,
             KeyAssignment::CopyMode(CopyModeAssignment::JumpAgain),
         ),
+        (
+            WKeyCode::Char('a'),
+            Modifiers::NONE,
+            KeyAssignment::CopyMode(CopyModeAssignment::AddMatchToSelectionAndMoveToNext),
+        ),
+        (
+            WKeyCode::Char('A'),
+            Modifiers::SHIFT,
+            KeyAssignment::CopyMode(CopyModeAssignment::ClearMatchSelections),
+        ),
+        (
+            WKeyCode::Char('A'),
+            Modifiers::NONE,
+            KeyAssignment::CopyMode(CopyModeAssignment::ClearMatchSelections),
+        ),
         (
             WKeyCode::Char(','),
             Modifiers::NONE,
@@ -2156,3 +2393,76 @@ pub fn copy_key_table() -> KeyTable {
     }
     table
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn result(y: StableRowIndex, x: usize, match_id: usize) -> SearchResult {
+        SearchResult {
+            start_y: y,
+            start_x: x,
+            end_y: y,
+            end_x: x + 3,
+            match_id,
+        }
+    }
+
+    #[test]
+    fn selected_matches_are_copied_in_scrollback_order() {
+        let pattern = Pattern::CaseSensitiveString("foo".to_string());
+        let results = vec![result(9, 0, 0), result(5, 2, 0), result(1, 4, 0)];
+
+        let mut selected = SelectedMatches::default();
+        selected.add(&pattern, results[0]);
+        selected.add(&pattern, results[2]);
+        selected.add(&pattern, results[0]);
+        assert_eq!(selected.len(), 2);
+
+        assert_eq!(
+            selected.to_copy(Some(&results[1]), &results),
+            vec![results[2], results[1], results[0]]
+        );
+        assert_eq!(
+            selected.to_copy(Some(&results[0]), &results),
+            vec![results[2], results[0]]
+        );
+    }
+
+    #[test]
+    fn changing_the_pattern_forgets_the_selected_matches() {
+        let foo = Pattern::CaseSensitiveString("foo".to_string());
+        let mut selected = SelectedMatches::default();
+        selected.add(&foo, result(1, 0, 0));
+        selected.add(&foo, result(2, 0, 0));
+
+        assert!(selected.search_started(&foo).is_empty());
+        assert_eq!(selected.len(), 2);
+
+        // The new pattern happens to match at the same positions, but
+        // the selection was made for the old one
+        let bar = Pattern::CaseInSensitiveString("foo".to_string());
+        assert_eq!(selected.search_started(&bar).len(), 2);
+        let results = vec![result(2, 0, 0), result(1, 0, 0)];
+        assert!(selected.is_empty());
+        assert!(selected.to_copy(None, &results).is_empty());
+    }
+
+    #[test]
+    fn stale_matches_are_not_copied() {
+        let pattern = Pattern::CaseSensitiveString("foo".to_string());
+        let mut selected = SelectedMatches::default();
+        selected.add(&pattern, result(1, 0, 0));
+        selected.add(&pattern, result(2, 0, 0));
+
+        // The pane content changed, and the search is run again; the
+        // result at row 1 moved and match ids were reassigned
+        assert!(selected.search_started(&pattern).is_empty());
+        let results = vec![result(2, 0, 7), result(1, 6, 7)];
+        assert_eq!(selected.to_copy(None, &results), vec![result(2, 0, 7)]);
+
+        selected.search_completed(&results);
+        assert_eq!(selected.len(), 1);
+        assert!(selected.contains(&result(2, 0, 7)));
+    }
+}
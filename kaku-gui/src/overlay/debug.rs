@@ -1,7 +1,7 @@
 use crate::scripting::guiwin::GuiWin;
 use chrono::prelude::*;
 use futures::FutureExt;
-use log::Level;
+use log::{Level, LevelFilter};
 use luahelper::ValuePrinter;
 use mlua::Value;
 use mux::termwiztermtab::TermWizTerminal;
@@ -174,7 +174,10 @@ pub fn show_debug_overlay(
 
     term.render(&[Change::Title("Kaku Doctor".to_string())])?;
 
-    fn print_new_log_entries(term: &mut TermWizTerminal) -> termwiz::Result<()> {
+    fn print_new_log_entries(
+        term: &mut TermWizTerminal,
+        max_level: LevelFilter,
+    ) -> termwiz::Result<()> {
         let entries = env_bootstrap::ringlog::get_entries();
         let mut changes = vec![];
         for entry in entries {
@@ -185,6 +188,9 @@ pub fn show_debug_overlay(
                 }
             }
             LATEST_LOG_ENTRY.lock().unwrap().replace(entry.then);
+            if entry.level > max_level {
+                continue;
+            }
 
             changes.push(Change::AllAttributes(CellAttributes::default()));
             changes.push(Change::Text(entry.then.format("%H:%M:%S%.3f ").to_string()));
@@ -224,6 +230,7 @@ pub fn show_debug_overlay(
          {opengl_info}\r\n\
          {}\
          Enter lua statements or expressions and hit Enter.\r\n\
+         Type :help for commands to filter logs and inspect the mux.\r\n\
          Press ESC or CTRL-D to exit\r\n",
         doctor_snapshot.placeholder_text(),
     ))])?;
@@ -233,9 +240,11 @@ pub fn show_debug_overlay(
     // so the panel does not appear to "require Enter" to show results.
     doctor_snapshot.wait_for_initial_render(&mut term)?;
 
+    let mut max_level = LevelFilter::Trace;
+
     loop {
         doctor_snapshot.render_if_ready(&mut term)?;
-        print_new_log_entries(&mut term)?;
+        print_new_log_entries(&mut term, max_level)?;
         let mut editor = LineEditor::new(&mut term);
         editor.set_prompt("> ");
         if let Some(line) = editor.read_line(host.as_mut().unwrap())? {
@@ -244,6 +253,15 @@ pub fn show_debug_overlay(
             }
             host.as_mut().unwrap().add_history(&line);
 
+            if let Some(command) = line.strip_prefix(':') {
+                let text = match run_repl_command(command, &mut max_level) {
+                    Ok(text) => text,
+                    Err(err) => format!("{:#}", err),
+                };
+                term.render(&[Change::Text(format!("{}\r\n", text.replace("\n", "\r\n")))])?;
+                continue;
+            }
+
             let passed_host = host.take().unwrap();
 
             let (host_res, text) =
@@ -267,6 +285,106 @@ pub fn show_debug_overlay(
     }
 }
 
+const REPL_COMMAND_HELP: &str = "\
+:level [error|warn|info|debug|trace]  show or set the most verbose log level to display
+:mux                                  summarize windows, tabs, panes, domains and mux traffic
+:help                                 show this help";
+
+/// Handles the `:command` lines entered into the REPL
+fn run_repl_command(command: &str, max_level: &mut LevelFilter) -> anyhow::Result<String> {
+    let mut words = command.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("level"), None) => Ok(format!("Showing log entries up to {}", max_level)),
+        (Some("level"), Some(level)) => {
+            *max_level = level
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Unknown log level {level:?}"))?;
+            Ok(format!("Showing log entries up to {}", max_level))
+        }
+        (Some("mux"), None) => Ok(smol::block_on(promise::spawn::spawn_into_main_thread(
+            async move { mux_state_text() },
+        ))),
+        (Some("help"), None) | (None, _) => Ok(REPL_COMMAND_HELP.to_string()),
+        _ => anyhow::bail!("Unknown command :{command}; type :help for a list"),
+    }
+}
+
+/// Describes the current mux state in a form suitable for pasting
+/// into a bug report
+fn mux_state_text() -> String {
+    let Some(mux) = mux::Mux::try_get() else {
+        return "The mux is not running".to_string();
+    };
+    let mut lines = vec![];
+
+    for domain in mux.iter_domains() {
+        lines.push(format!(
+            "domain {} {:?} {:?}",
+            domain.domain_id(),
+            domain.domain_name(),
+            domain.state()
+        ));
+        if let Ok(inner) =
+            wezterm_client::domain::ClientDomain::get_client_inner_for_domain(domain.domain_id())
+        {
+            let stats = inner.client.connection_stats();
+            lines.push(frame_stats_text("sent", stats.sent.snapshot()));
+            lines.push(frame_stats_text("received", stats.received.snapshot()));
+        }
+    }
+
+    for window_id in mux.iter_windows() {
+        let Some(window) = mux.get_window(window_id) else {
+            continue;
+        };
+        lines.push(format!(
+            "window {window_id} workspace={:?} title={:?}",
+            window.get_workspace(),
+            window.get_title()
+        ));
+        for tab in window.iter() {
+            lines.push(format!(
+                "  tab {} title={:?}",
+                tab.tab_id(),
+                tab.get_title()
+            ));
+            for pos in tab.iter_panes() {
+                let pane = &pos.pane;
+                let cwd = pane
+                    .get_current_working_dir(mux::pane::CachePolicy::AllowStale)
+                    .map(|url| url.to_string())
+                    .unwrap_or_default();
+                lines.push(format!(
+                    "    pane {}{} domain={} size={}x{} dead={} title={:?} cwd={:?}",
+                    pane.pane_id(),
+                    if pos.is_active { "*" } else { "" },
+                    pane.domain_id(),
+                    pos.width,
+                    pos.height,
+                    pane.is_dead(),
+                    pane.get_title(),
+                    cwd
+                ));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Summarizes how much data has crossed a mux connection in one
+/// direction and how much of it compression saved
+fn frame_stats_text(direction: &str, stats: codec::FrameStatsSnapshot) -> String {
+    format!(
+        "  {direction} {} pdus ({} compressed), {} bytes as {} on the wire, {:.1}% saved",
+        stats.frames,
+        stats.compressed_frames,
+        stats.payload_bytes,
+        stats.wire_bytes,
+        stats.saved_percent()
+    )
+}
+
 struct PendingDoctorSnapshot {
     receiver: Option<mpsc::Receiver<String>>,
     rendered: bool,
@@ -0,0 +1,246 @@
+use mux::pane::Pane;
+use mux::termwiztermtab::TermWizTerminal;
+use std::sync::Arc;
+use termwiz::cell::{AttributeChange, Intensity};
+use termwiz::color::{AnsiColor, ColorAttribute};
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, MouseButtons, MouseEvent};
+use termwiz::surface::{Change, CursorVisibility, Position};
+use termwiz::terminal::Terminal;
+
+const BYTES_PER_ROW: usize = 16;
+
+/// How a byte in the dump is highlighted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteKind {
+    Text,
+    Control,
+    Escape,
+}
+
+/// Classifies each byte of `data`, marking the bytes that make up escape
+/// sequences (CSI, OSC, DCS and friends, along with two byte ESC
+/// sequences) so that they can be told apart from ordinary control
+/// characters and text.
+fn classify(data: &[u8]) -> Vec<ByteKind> {
+    let mut kinds = Vec::with_capacity(data.len());
+    let mut idx = 0;
+    while idx < data.len() {
+        let b = data[idx];
+        if b != 0x1b {
+            kinds.push(if b < 0x20 || b == 0x7f {
+                ByteKind::Control
+            } else {
+                ByteKind::Text
+            });
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        idx += 1;
+        match data.get(idx) {
+            Some(b'[') => {
+                idx += 1;
+                while idx < data.len() && !(0x40..=0x7e).contains(&data[idx]) {
+                    idx += 1;
+                }
+                idx += 1;
+            }
+            Some(b']' | b'P' | b'X' | b'^' | b'_') => {
+                idx += 1;
+                while idx < data.len() {
+                    if data[idx] == 0x07 {
+                        idx += 1;
+                        break;
+                    }
+                    if data[idx] == 0x1b && data.get(idx + 1) == Some(&b'\\') {
+                        idx += 2;
+                        break;
+                    }
+                    idx += 1;
+                }
+            }
+            Some(_) => {
+                // Skip intermediates, then the final byte
+                while idx < data.len() && (0x20..=0x2f).contains(&data[idx]) {
+                    idx += 1;
+                }
+                idx += 1;
+            }
+            None => {}
+        }
+        let end = idx.min(data.len());
+        kinds.extend(std::iter::repeat_n(ByteKind::Escape, end - start));
+        idx = end;
+    }
+    kinds
+}
+
+fn attribute_for(kind: ByteKind) -> ColorAttribute {
+    match kind {
+        ByteKind::Text => ColorAttribute::Default,
+        ByteKind::Control => AnsiColor::Teal.into(),
+        ByteKind::Escape => AnsiColor::Yellow.into(),
+    }
+}
+
+/// Renders a single row of the dump as changes, grouping runs of bytes
+/// with the same highlighting
+fn render_row(offset: usize, data: &[u8], kinds: &[ByteKind], changes: &mut Vec<Change>) {
+    changes.push(AttributeChange::Intensity(Intensity::Half).into());
+    changes.push(Change::Text(format!("{offset:08x}  ")));
+    changes.push(AttributeChange::Intensity(Intensity::Normal).into());
+
+    let mut current = ByteKind::Text;
+    let mut set_kind = |kind: ByteKind, changes: &mut Vec<Change>| {
+        if kind != current {
+            changes.push(AttributeChange::Foreground(attribute_for(kind)).into());
+            current = kind;
+        }
+    };
+
+    for col in 0..BYTES_PER_ROW {
+        match data.get(col) {
+            Some(b) => {
+                set_kind(kinds[col], changes);
+                changes.push(Change::Text(format!("{b:02x} ")));
+            }
+            None => changes.push(Change::Text("   ".to_string())),
+        }
+        if col == BYTES_PER_ROW / 2 - 1 {
+            changes.push(Change::Text(" ".to_string()));
+        }
+    }
+
+    set_kind(ByteKind::Text, changes);
+    changes.push(Change::Text(" |".to_string()));
+    for (b, kind) in data.iter().zip(kinds.iter()) {
+        set_kind(*kind, changes);
+        let c = if b.is_ascii_graphic() || *b == b' ' {
+            *b as char
+        } else {
+            '.'
+        };
+        changes.push(Change::Text(c.to_string()));
+    }
+    set_kind(ByteKind::Text, changes);
+    changes.push(Change::Text("|\r\n".to_string()));
+}
+
+/// Shows the most recent `max_bytes` of raw output from `pane` as a
+/// hex+ASCII dump with escape sequences highlighted.
+pub fn show_pane_hex_dump(
+    pane: Arc<dyn Pane>,
+    max_bytes: usize,
+    mut term: TermWizTerminal,
+) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+
+    let data = pane
+        .raw_output_history()
+        .map(|history| history.lock().tail(max_bytes));
+    let kinds = data.as_deref().map(classify).unwrap_or_default();
+    let total_rows = data
+        .as_ref()
+        .map(|data| data.len().div_ceil(BYTES_PER_ROW))
+        .unwrap_or(0);
+
+    // Start at the most recent output
+    let mut top = usize::MAX;
+
+    loop {
+        let size = term.get_screen_size()?;
+        let visible = size.rows.saturating_sub(3).max(1);
+        top = top.min(total_rows.saturating_sub(visible));
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorVisibility(CursorVisibility::Hidden),
+            AttributeChange::Intensity(Intensity::Bold).into(),
+            Change::Text(format!(
+                "Raw output of pane {} ({} bytes, arrows/PgUp/PgDn to scroll, q or Escape to close)\r\n\r\n",
+                pane.pane_id(),
+                data.as_ref().map(|data| data.len()).unwrap_or(0),
+            )),
+            AttributeChange::Intensity(Intensity::Normal).into(),
+        ];
+
+        match &data {
+            None => changes.push(Change::Text(
+                "Raw output is not retained for this pane.\r\n\
+                 Set raw_output_retention_bytes in your config to enable it."
+                    .to_string(),
+            )),
+            Some(data) if data.is_empty() => changes.push(Change::Text(
+                "No output has been retained for this pane yet.".to_string(),
+            )),
+            Some(data) => {
+                for row in top..(top + visible).min(total_rows) {
+                    let start = row * BYTES_PER_ROW;
+                    let end = (start + BYTES_PER_ROW).min(data.len());
+                    render_row(start, &data[start..end], &kinds[start..end], &mut changes);
+                }
+            }
+        }
+
+        changes.push(Change::CursorPosition {
+            x: Position::Absolute(0),
+            y: Position::Absolute(0),
+        });
+        term.render(&changes)?;
+        term.flush()?;
+
+        match term.poll_input(None)? {
+            Some(InputEvent::Key(KeyEvent {
+                key: KeyCode::Escape | KeyCode::Char('q'),
+                ..
+            })) => return Ok(()),
+            Some(InputEvent::Key(KeyEvent { key, .. })) => match key {
+                KeyCode::UpArrow | KeyCode::Char('k') => top = top.saturating_sub(1),
+                KeyCode::DownArrow | KeyCode::Char('j') => top = top.saturating_add(1),
+                KeyCode::PageUp => top = top.saturating_sub(visible),
+                KeyCode::PageDown => top = top.saturating_add(visible),
+                KeyCode::Home | KeyCode::Char('g') => top = 0,
+                KeyCode::End | KeyCode::Char('G') => top = usize::MAX,
+                _ => {}
+            },
+            Some(InputEvent::Mouse(MouseEvent { mouse_buttons, .. }))
+                if mouse_buttons == MouseButtons::VERT_WHEEL | MouseButtons::WHEEL_POSITIVE =>
+            {
+                top = top.saturating_sub(3)
+            }
+            Some(InputEvent::Mouse(MouseEvent { mouse_buttons, .. }))
+                if mouse_buttons == MouseButtons::VERT_WHEEL =>
+            {
+                top = top.saturating_add(3)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ByteKind::*;
+
+    #[test]
+    fn escape_classification() {
+        assert_eq!(classify(b"a\r\n"), vec![Text, Control, Control]);
+        assert_eq!(
+            classify(b"\x1b[1mx"),
+            vec![Escape, Escape, Escape, Escape, Text]
+        );
+        assert_eq!(
+            classify(b"\x1b]0;t\x07x"),
+            vec![Escape, Escape, Escape, Escape, Escape, Escape, Text]
+        );
+        assert_eq!(
+            classify(b"\x1b]0\x1b\\x"),
+            vec![Escape, Escape, Escape, Escape, Escape, Text]
+        );
+        assert_eq!(classify(b"\x1b(Bx"), vec![Escape, Escape, Escape, Text]);
+        // An incomplete sequence at the end of the buffer
+        assert_eq!(classify(b"x\x1b[3"), vec![Text, Escape, Escape, Escape]);
+    }
+}
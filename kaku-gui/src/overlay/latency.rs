@@ -0,0 +1,110 @@
+use mux::latency::LatencyStats;
+use mux::pane::Pane;
+use mux::termwiztermtab::TermWizTerminal;
+use mux::Mux;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use termwiz::cell::{AttributeChange, Intensity};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, MouseButtons, MouseEvent};
+use termwiz::surface::{Change, CursorVisibility, Position};
+use termwiz::terminal::Terminal;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn stats_lines(stats: &LatencyStats) -> Vec<String> {
+    vec![
+        format!("samples  {}", stats.samples),
+        format!("last     {}", format_ms(stats.last)),
+        format!("min      {}", format_ms(stats.min)),
+        format!("mean     {}", format_ms(stats.mean)),
+        format!("p90      {}", format_ms(stats.p90)),
+        format!("max      {}", format_ms(stats.max)),
+    ]
+}
+
+/// Shows rolling statistics for the time it takes input sent to `pane`
+/// to be echoed back, refreshing them every second until the user
+/// dismisses the overlay.
+pub fn show_pane_latency(pane: Arc<dyn Pane>, mut term: TermWizTerminal) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+
+    let domain_name = Mux::get()
+        .get_domain(pane.domain_id())
+        .map(|domain| domain.domain_name().to_string())
+        .unwrap_or_default();
+    let latency = pane.echo_latency();
+
+    loop {
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorVisibility(CursorVisibility::Hidden),
+            AttributeChange::Intensity(Intensity::Bold).into(),
+            Change::Text(format!(
+                "Echo latency for pane {} in domain {domain_name} \
+                 (press r to reset, q or Escape to close)\r\n\r\n",
+                pane.pane_id(),
+            )),
+            AttributeChange::Intensity(Intensity::Normal).into(),
+        ];
+
+        match latency.as_ref().map(|latency| latency.stats()) {
+            None => changes.push(Change::Text(
+                "Latency is not measured for this pane.".to_string(),
+            )),
+            Some(None) => changes.push(Change::Text(
+                "Nothing has been measured yet.\r\n\
+                 Type in the pane and then show this overlay again; the time \
+                 between each key press and the response to it is recorded."
+                    .to_string(),
+            )),
+            Some(Some(stats)) => {
+                for line in stats_lines(&stats) {
+                    changes.push(Change::Text(format!("{line}\r\n")));
+                }
+            }
+        }
+
+        changes.push(Change::CursorPosition {
+            x: Position::Absolute(0),
+            y: Position::Absolute(0),
+        });
+        term.render(&changes)?;
+        term.flush()?;
+
+        let deadline = Instant::now() + REFRESH_INTERVAL;
+        loop {
+            let remain = deadline.saturating_duration_since(Instant::now());
+            if remain.is_zero() {
+                break;
+            }
+            match term.poll_input(Some(remain))? {
+                Some(InputEvent::Key(KeyEvent {
+                    key: KeyCode::Escape | KeyCode::Char('q'),
+                    ..
+                })) => return Ok(()),
+                Some(InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('r'),
+                    ..
+                })) => {
+                    if let Some(latency) = &latency {
+                        latency.reset();
+                    }
+                    break;
+                }
+                Some(InputEvent::Mouse(MouseEvent { mouse_buttons, .. }))
+                    if mouse_buttons != MouseButtons::NONE =>
+                {
+                    return Ok(());
+                }
+                Some(InputEvent::Resized { .. }) => break,
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+}
@@ -11,7 +11,9 @@ use crate::overlay::quickselect;
 use crate::overlay::selector::{matcher_pattern, matcher_score};
 use crate::termwindow::TermWindowNotif;
 use config::configuration;
-use config::keyassignment::KeyAssignment::SetPaneEncoding;
+use config::keyassignment::KeyAssignment::{
+    ReinterpretScrollbackWithEncoding, SetPaneEncoding, SetPaneInputEncoding, SetPaneOutputEncoding,
+};
 use config::keyassignment::{
     KeyAssignment, LauncherActionArgs, PaneEncoding, SpawnCommand, SpawnTabDomain,
 };
@@ -21,8 +23,8 @@ use mux::termwiztermtab::TermWizTerminal;
 use mux::window::WindowId;
 use mux::Mux;
 use rayon::prelude::*;
-use std::collections::BTreeMap;
-use termwiz::cell::{AttributeChange, CellAttributes};
+use std::collections::{BTreeMap, HashMap};
+use termwiz::cell::{AttributeChange, CellAttributes, Intensity};
 use termwiz::color::ColorAttribute;
 use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent};
 use termwiz::surface::{Change, Position};
@@ -32,6 +34,9 @@ use window::WindowOps;
 
 pub use config::keyassignment::LauncherFlags;
 
+/// How many of a tab's trailing output lines are previewed next to it
+const PREVIEW_LINES: usize = 3;
+
 #[derive(Clone)]
 struct Entry {
     pub label: String,
@@ -42,6 +47,8 @@ pub struct LauncherTabEntry {
     pub title: String,
     pub tab_idx: usize,
     pub pane_count: Option<usize>,
+    /// The last few lines of the tab's active pane
+    pub preview: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -64,6 +71,7 @@ pub struct LauncherArgs {
     help_text: String,
     fuzzy_help_text: String,
     alphabet: String,
+    user_commands: Vec<(String, KeyAssignment)>,
 }
 
 impl LauncherArgs {
@@ -99,20 +107,24 @@ impl LauncherArgs {
             window
                 .iter()
                 .enumerate()
-                .map(|(tab_idx, tab)| {
+                .filter_map(|(tab_idx, tab)| {
+                    // A tab that is being torn down may have no panes left
+                    let pane = tab.get_active_pane()?;
                     let tab_title = tab.get_title();
                     let title = if tab_title.is_empty() {
-                        tab.get_active_pane()
-                            .expect("tab to have a pane")
-                            .get_title()
+                        pane.get_title()
                     } else {
                         tab_title
                     };
-                    LauncherTabEntry {
+                    Some(LauncherTabEntry {
                         title,
                         tab_idx,
                         pane_count: tab.count_panes(),
-                    }
+                        preview: crate::termwindow::taboverview::pane_preview_lines(
+                            &pane,
+                            PREVIEW_LINES,
+                        ),
+                    })
                 })
                 .collect()
         } else {
@@ -156,6 +168,15 @@ impl LauncherArgs {
             vec![]
         };
 
+        let user_commands = if flags.contains(LauncherFlags::COMMANDS) {
+            crate::termwindow::palette::registered_user_commands()
+                .into_iter()
+                .map(|entry| (entry.brief, entry.action))
+                .collect()
+        } else {
+            vec![]
+        };
+
         Self {
             flags,
             domains,
@@ -168,6 +189,7 @@ impl LauncherArgs {
             help_text: help_text.to_string(),
             fuzzy_help_text: fuzzy_help_text.to_string(),
             alphabet: alphabet.to_string(),
+            user_commands,
         }
     }
 }
@@ -200,9 +222,21 @@ struct LauncherState {
     selection: String,
     always_fuzzy: bool,
     parent_state: Option<ParentLauncherState>,
+    /// Output previews keyed by tab index, shown beside tab entries
+    tab_previews: HashMap<usize, String>,
 }
 
 impl LauncherState {
+    fn preview_for(&self, entry: &Entry) -> Option<&str> {
+        match entry.action {
+            KeyAssignment::ActivateTab(tab_idx) => self
+                .tab_previews
+                .get(&(tab_idx as usize))
+                .map(|s| s.as_str()),
+            _ => None,
+        }
+    }
+
     fn update_filter(&mut self) {
         if self.filter_term.is_empty() {
             self.filtered_entries = self.entries.clone();
@@ -223,7 +257,11 @@ impl LauncherState {
             .par_iter()
             .enumerate()
             .filter_map(|(row_idx, entry)| {
-                let score = matcher_score(&pattern, &entry.label)?;
+                // Tabs can also be found by what they are displaying
+                let preview_score = self
+                    .preview_for(entry)
+                    .and_then(|preview| matcher_score(&pattern, preview));
+                let score = matcher_score(&pattern, &entry.label).max(preview_score)?;
                 Some(MatchResult { row_idx, score })
             })
             .collect();
@@ -324,6 +362,10 @@ impl LauncherState {
         }
 
         for tab in &args.tabs {
+            if !tab.preview.is_empty() {
+                self.tab_previews
+                    .insert(tab.tab_idx, tab.preview.join(" │ "));
+            }
             self.entries.push(Entry {
                 label: match tab.pane_count {
                     Some(pane_count) => format!("{}. {pane_count} panes", tab.title),
@@ -343,6 +385,12 @@ impl LauncherState {
         }
 
         if args.flags.contains(LauncherFlags::COMMANDS) {
+            for (label, action) in &args.user_commands {
+                self.entries.push(Entry {
+                    label: label.clone(),
+                    action: action.clone(),
+                });
+            }
             let commands = crate::commands::CommandDef::expanded_commands(&config);
             for cmd in commands {
                 if matches!(
@@ -354,6 +402,9 @@ impl LauncherState {
                         | KeyAssignment::Nop
                         | KeyAssignment::Multiple(_)
                         | KeyAssignment::SetPaneEncoding(_)
+                        | KeyAssignment::SetPaneInputEncoding(_)
+                        | KeyAssignment::SetPaneOutputEncoding(_)
+                        | KeyAssignment::ReinterpretScrollbackWithEncoding(_)
                 ) {
                     // Filter out some noisy, repetitive entries
                     continue;
@@ -512,9 +563,21 @@ impl LauncherState {
             if line.len() > max_width {
                 line.resize(max_width, termwiz::surface::SEQ_ZERO);
             }
+            let remaining = max_width.saturating_sub(line.len() + 2);
             changes.append(&mut line.changes(&attr));
             changes.push(Change::Text(" ".to_string()));
 
+            if let Some(preview) = self.preview_for(entry) {
+                if remaining > 0 {
+                    changes.push(AttributeChange::Intensity(Intensity::Half).into());
+                    changes.push(Change::Text(format!(
+                        " {}",
+                        truncate_right(preview, remaining)
+                    )));
+                    changes.push(AttributeChange::Intensity(Intensity::Normal).into());
+                }
+            }
+
             if used_reverse_for_selection {
                 changes.push(AttributeChange::Reverse(false).into());
             }
@@ -583,12 +646,33 @@ impl LauncherState {
         };
         self.parent_state = Some(parent);
         self.entries.clear();
-        for encoding in PaneEncoding::ordered_list() {
+        let encodings = PaneEncoding::ordered_list();
+        for encoding in &encodings {
             self.entries.push(Entry {
                 label: format!("Set pane encoding to {encoding}"),
-                action: SetPaneEncoding(encoding),
+                action: SetPaneEncoding(*encoding),
+            });
+        }
+        for encoding in &encodings {
+            self.entries.push(Entry {
+                label: format!("Set pane input encoding only to {encoding}"),
+                action: SetPaneInputEncoding(*encoding),
             });
         }
+        for encoding in &encodings {
+            self.entries.push(Entry {
+                label: format!("Set pane output encoding only to {encoding}"),
+                action: SetPaneOutputEncoding(*encoding),
+            });
+        }
+        if configuration().raw_output_retention_bytes > 0 {
+            for encoding in &encodings {
+                self.entries.push(Entry {
+                    label: format!("Reinterpret scrollback as {encoding}"),
+                    action: ReinterpretScrollbackWithEncoding(*encoding),
+                });
+            }
+        }
         self.help_text =
             "Select encoding  |  Enter = set  |  Esc = back  |  / = filter".to_string();
         self.active_idx = 0;
@@ -778,6 +862,7 @@ pub fn launcher(
         alphabet: args.alphabet.clone(),
         always_fuzzy: filtering,
         parent_state: None,
+        tab_previews: HashMap::new(),
     };
 
     term.set_raw_mode()?;
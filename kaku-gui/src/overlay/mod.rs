@@ -6,21 +6,33 @@ use std::pin::Pin;
 use std::sync::Arc;
 use wezterm_term::{TerminalConfiguration, TerminalSize};
 
+pub mod askpass;
+pub mod command_history;
 pub mod confirm;
 pub mod confirm_close_pane;
+pub mod confirm_paste;
 pub mod copy;
 pub mod debug;
+pub mod hex_dump;
+pub mod latency;
 pub mod launcher;
+pub mod process_info;
 pub mod prompt;
 pub mod quickselect;
 pub mod selector;
 
+pub use askpass::show_askpass;
+pub use command_history::{show_command_history, CommandHistoryScope};
 #[cfg(not(target_os = "macos"))]
 pub use confirm_close_pane::confirm_close_window;
 pub use confirm_close_pane::{confirm_close_pane, confirm_close_tab, confirm_quit_program};
+pub use confirm_paste::confirm_paste;
 pub use copy::{CopyModeParams, CopyOverlay};
 pub use debug::show_debug_overlay;
+pub use hex_dump::show_pane_hex_dump;
+pub use latency::show_pane_latency;
 pub use launcher::{launcher, LauncherArgs, LauncherFlags};
+pub use process_info::show_pane_process_info;
 pub use quickselect::QuickSelectOverlay;
 
 pub fn start_overlay<T, F>(
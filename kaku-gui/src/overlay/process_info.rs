@@ -0,0 +1,207 @@
+use mux::pane::{CachePolicy, Pane};
+use mux::termwiztermtab::TermWizTerminal;
+use procinfo::{LocalProcessInfo, LocalProcessResourceUsage};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use termwiz::cell::{AttributeChange, Intensity};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, MouseButtons, MouseEvent};
+use termwiz::surface::{Change, CursorVisibility, Position};
+use termwiz::terminal::Terminal;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A row in the rendered process tree
+struct ProcessRow {
+    depth: usize,
+    pid: u32,
+    command: String,
+    cwd: String,
+    usage: Option<LocalProcessResourceUsage>,
+}
+
+fn flatten_tree(info: &LocalProcessInfo, depth: usize, rows: &mut Vec<ProcessRow>) {
+    let command = if info.argv.is_empty() {
+        info.name.clone()
+    } else {
+        shlex::try_join(info.argv.iter().map(|s| s.as_str()))
+            .unwrap_or_else(|_| info.argv.join(" "))
+    };
+    rows.push(ProcessRow {
+        depth,
+        pid: info.pid,
+        command,
+        cwd: info.cwd.display().to_string(),
+        usage: LocalProcessInfo::resource_usage(info.pid),
+    });
+
+    let mut children: Vec<&LocalProcessInfo> = info.children.values().collect();
+    children.sort_by_key(|child| child.pid);
+    for child in children {
+        flatten_tree(child, depth + 1, rows);
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "K", "M", "G"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes}{}", UNITS[unit])
+    } else {
+        format!("{value:.1}{}", UNITS[unit])
+    }
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        s.to_string()
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut res: String = s.chars().take(width - 1).collect();
+        res.push('…');
+        res
+    }
+}
+
+/// Shows the foreground process tree of `pane`, refreshing it every
+/// second until the user dismisses the overlay.
+pub fn show_pane_process_info(
+    pane: Arc<dyn Pane>,
+    mut term: TermWizTerminal,
+) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+
+    let mut prior: HashMap<u32, LocalProcessResourceUsage> = HashMap::new();
+    let mut prior_time = Instant::now();
+
+    loop {
+        let size = term.get_screen_size()?;
+        let now = Instant::now();
+        let elapsed = now.duration_since(prior_time).as_secs_f64();
+
+        let mut rows = vec![];
+        if let Some(info) = pane.get_foreground_process_info(CachePolicy::FetchImmediate) {
+            flatten_tree(&info, 0, &mut rows);
+        }
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorVisibility(CursorVisibility::Hidden),
+            AttributeChange::Intensity(Intensity::Bold).into(),
+            Change::Text(format!(
+                "Processes in pane {} (refreshed every {}s, press q or Escape to close)\r\n\r\n",
+                pane.pane_id(),
+                REFRESH_INTERVAL.as_secs()
+            )),
+            Change::Text(format!(
+                "{:>7} {:>6} {:>8}  {}\r\n",
+                "PID", "CPU%", "RSS", "COMMAND / CWD"
+            )),
+            AttributeChange::Intensity(Intensity::Normal).into(),
+        ];
+
+        if rows.is_empty() {
+            changes.push(Change::Text(
+                "The foreground process information is not available for this pane.".to_string(),
+            ));
+        }
+
+        let mut current = HashMap::new();
+        let max_rows = size.rows.saturating_sub(4) / 2;
+        for row in rows.iter().take(max_rows) {
+            let (cpu, rss) = match row.usage {
+                Some(usage) => {
+                    current.insert(row.pid, usage);
+                    let cpu = match prior.get(&row.pid) {
+                        Some(prev) if elapsed > 0.0 => {
+                            let delta = usage.cpu_time.saturating_sub(prev.cpu_time);
+                            format!("{:.1}", 100.0 * delta.as_secs_f64() / elapsed)
+                        }
+                        _ => "-".to_string(),
+                    };
+                    (cpu, format_bytes(usage.resident_size))
+                }
+                None => ("-".to_string(), "-".to_string()),
+            };
+
+            let indent = "  ".repeat(row.depth);
+            let prefix = format!("{:>7} {:>6} {:>8}  {indent}", row.pid, cpu, rss);
+            let avail = size.cols.saturating_sub(prefix.len());
+            changes.push(Change::Text(format!(
+                "{prefix}{}\r\n",
+                truncate(&row.command, avail)
+            )));
+            changes.push(AttributeChange::Intensity(Intensity::Half).into());
+            changes.push(Change::Text(format!(
+                "{:>24}{indent}  {}\r\n",
+                "",
+                truncate(&row.cwd, avail.saturating_sub(2))
+            )));
+            changes.push(AttributeChange::Intensity(Intensity::Normal).into());
+        }
+        if rows.len() > max_rows {
+            changes.push(Change::Text(format!(
+                "... {} more processes",
+                rows.len() - max_rows
+            )));
+        }
+
+        changes.push(Change::CursorPosition {
+            x: Position::Absolute(0),
+            y: Position::Absolute(0),
+        });
+        term.render(&changes)?;
+        term.flush()?;
+
+        prior = current;
+        prior_time = now;
+
+        let deadline = now + REFRESH_INTERVAL;
+        loop {
+            let remain = deadline.saturating_duration_since(Instant::now());
+            if remain.is_zero() {
+                break;
+            }
+            match term.poll_input(Some(remain))? {
+                Some(InputEvent::Key(KeyEvent {
+                    key: KeyCode::Escape | KeyCode::Char('q'),
+                    ..
+                })) => return Ok(()),
+                Some(InputEvent::Mouse(MouseEvent { mouse_buttons, .. }))
+                    if mouse_buttons != MouseButtons::NONE =>
+                {
+                    return Ok(());
+                }
+                Some(InputEvent::Resized { .. }) => break,
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_formatting() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5K");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0G");
+    }
+
+    #[test]
+    fn truncation() {
+        assert_eq!(truncate("hello", 10), "hello");
+        assert_eq!(truncate("hello", 4), "hel…");
+        assert_eq!(truncate("hello", 0), "");
+    }
+}
@@ -23,7 +23,7 @@ use wezterm_term::{
 };
 use window::WindowOps;
 
-const PATTERNS: [&str; 14] = [
+const PATTERNS: [&str; 15] = [
     // markdown_url
     r"\[[^]]*\]\(([^)]+)\)",
     // url
@@ -34,6 +34,8 @@ const PATTERNS: [&str; 14] = [
     r"\+\+\+ b/(\S+)",
     // docker
     r"sha256:([0-9a-f]{64})",
+    // file_location
+    r"(?:[.\w\-@~]+)?(?:/+[.\w\-@]+)+:\d+(?::\d+)?",
     // path
     r"(?:[.\w\-@~]+)?(?:/+[.\w\-@]+)+",
     // color
@@ -460,6 +462,10 @@ impl Pane for QuickSelectOverlay {
         self.delegate.erase_scrollback(erase_mode)
     }
 
+    fn set_scrollback_lines(&self, lines: Option<usize>) {
+        self.delegate.set_scrollback_lines(lines)
+    }
+
     fn is_mouse_grabbed(&self) -> bool {
         // Force grabbing off while we're searching
         false
@@ -609,6 +609,23 @@ impl RenderState {
         Ok(layer)
     }
 
+    /// Returns the number of quads allocated so far for the current frame,
+    /// summed across all layers
+    pub fn quad_count(&self) -> usize {
+        self.layers
+            .borrow()
+            .iter()
+            .map(|layer| {
+                layer
+                    .vb
+                    .borrow()
+                    .iter()
+                    .map(|vb| *vb.next_quad.borrow())
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
     /// Returns true if any of the layers needed more quads to be allocated,
     /// and if we successfully allocated them.
     /// Returns false if the quads were sufficient.
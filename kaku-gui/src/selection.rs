@@ -5,7 +5,7 @@ use mux::pane::Pane;
 use std::cmp::Ordering;
 use std::ops::Range;
 use termwiz::surface::line::DoubleClickRange;
-use wezterm_term::{SemanticZone, StableRowIndex};
+use wezterm_term::{Line, SemanticZone, StableRowIndex};
 
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
 pub struct Selection {
@@ -169,6 +169,70 @@ fn is_double_click_word(s: &str) -> bool {
     }
 }
 
+/// Characters that can never be part of a smart-selected url or path
+fn is_smart_token_delimiter(s: &str) -> bool {
+    s.is_empty()
+        || s.chars().all(char::is_whitespace)
+        || matches!(
+            s,
+            "\"" | "'" | "`" | "<" | ">" | "(" | ")" | "[" | "]" | "{" | "}" | "|"
+        )
+}
+
+fn looks_like_url_or_path(token: &str) -> bool {
+    token.contains("://") || token.starts_with('~') || token.contains('/')
+}
+
+/// Computes a "semantic" double click range around the cell at `click_idx`:
+/// a url or path if the click is on one, otherwise the contents of the
+/// quoted string that surrounds the click.  Returns None if neither
+/// applies, in which case the regular word boundary rules should be used.
+fn smart_word_range(line: &Line, click_idx: usize) -> Option<Range<usize>> {
+    let cells: Vec<(usize, usize, String)> = line
+        .visible_cells()
+        .map(|cell| (cell.cell_index(), cell.width(), cell.str().to_string()))
+        .collect();
+    let pos = cells
+        .iter()
+        .position(|(idx, width, _)| click_idx >= *idx && click_idx < idx + (*width).max(1))?;
+    let cell_range =
+        |first: usize, last: usize| cells[first].0..cells[last].0 + cells[last].1.max(1);
+
+    if !is_smart_token_delimiter(&cells[pos].2) {
+        let mut left = pos;
+        while left > 0 && !is_smart_token_delimiter(&cells[left - 1].2) {
+            left -= 1;
+        }
+        let mut right = pos;
+        while right + 1 < cells.len() && !is_smart_token_delimiter(&cells[right + 1].2) {
+            right += 1;
+        }
+        // Trailing punctuation is almost always part of the surrounding
+        // prose rather than the url or path itself
+        while right > left && matches!(cells[right].2.as_str(), "." | "," | ";" | ":" | "!" | "?") {
+            right -= 1;
+        }
+        if right >= pos {
+            let token: String = cells[left..=right]
+                .iter()
+                .map(|(_, _, s)| s.as_str())
+                .collect();
+            if looks_like_url_or_path(&token) {
+                return Some(cell_range(left, right));
+            }
+        }
+    }
+
+    let is_quote = |s: &str| matches!(s, "\"" | "'" | "`");
+    if is_quote(&cells[pos].2) {
+        return None;
+    }
+    let open = (0..pos).rev().find(|&i| is_quote(&cells[i].2))?;
+    let quote = &cells[open].2;
+    let close = (pos + 1..cells.len()).find(|&i| cells[i].2 == *quote)?;
+    Some(cell_range(open + 1, close - 1))
+}
+
 impl SelectionRange {
     /// Create a new range that starts at the specified location
     pub fn start(start: SelectionCoordinate) -> Self {
@@ -242,10 +306,18 @@ impl SelectionRange {
 
             if let SelectionX::Cell(start_x) = start.x {
                 let start_idx = logical.xy_to_logical_x(start_x, start.y);
-                return match logical
-                    .logical
-                    .compute_double_click_range(start_idx, is_double_click_word)
-                {
+                let smart_range = if config::configuration().smart_word_selection {
+                    smart_word_range(&logical.logical, start_idx)
+                } else {
+                    None
+                };
+                let click_range = match smart_range {
+                    Some(range) => DoubleClickRange::Range(range),
+                    None => logical
+                        .logical
+                        .compute_double_click_range(start_idx, is_double_click_word),
+                };
+                return match click_range {
                     DoubleClickRange::RangeWithWrap(click_range)
                     | DoubleClickRange::Range(click_range) => {
                         let (start_y, start_x) =
@@ -352,3 +424,40 @@ impl SelectionRange {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use termwiz::surface::SEQ_ZERO;
+
+    fn smart(text: &str, click: usize) -> Option<String> {
+        let line = Line::from_text(text, &Default::default(), SEQ_ZERO, None);
+        smart_word_range(&line, click).map(|range| text[range].to_string())
+    }
+
+    #[test]
+    fn smart_selects_urls_and_paths() {
+        assert_eq!(
+            smart("see https://example.com/a?b=c, ok", 8).as_deref(),
+            Some("https://example.com/a?b=c")
+        );
+        assert_eq!(
+            smart("edit ~/src/main.rs.", 7).as_deref(),
+            Some("~/src/main.rs")
+        );
+    }
+
+    #[test]
+    fn smart_selects_quoted_strings() {
+        assert_eq!(
+            smart("echo \"hello world\" done", 8).as_deref(),
+            Some("hello world")
+        );
+    }
+
+    #[test]
+    fn smart_falls_back_to_words() {
+        assert_eq!(smart("plain words here", 7), None);
+        assert_eq!(smart("say \"hi\"", 4), None);
+    }
+}
@@ -66,6 +66,14 @@ fn hsv2rgb(c: vec3<f32>) -> vec3<f32>
     return c.z * mix(K.xxx, clamp(p - K.xxx, vec3(0.0), vec3(1.0)), c.y);
 }
 
+fn to_srgb(c: vec4<f32>) -> vec4<f32>
+{
+  let cutoff = c.rgb < vec3<f32>(0.0031308);
+  let higher = vec3<f32>(1.055) * pow(c.rgb, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+  let lower = c.rgb * vec3<f32>(12.92);
+  return vec4<f32>(select(higher, lower, cutoff), c.a);
+}
+
 fn apply_hsv(c: vec4<f32>, transform: vec3<f32>) -> vec4<f32>
 {
   let hsv = rgb2hsv(c.rgb) * transform;
@@ -120,5 +128,12 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 
   color = apply_hsv(color, hsv);
 
+  // LINEAR_BLENDING is defined when the shader is loaded.
+  // Without it, the surface doesn't encode to sRGB for us, so we do it
+  // here, and blending then takes place in sRGB space.
+  if !LINEAR_BLENDING {
+    color = to_srgb(color);
+  }
+
   return color;
 }
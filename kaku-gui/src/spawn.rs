@@ -3,10 +3,13 @@ use config::keyassignment::{PaneEncoding, SpawnCommand};
 use config::TermConfig;
 use mux::activity::Activity;
 use mux::domain::SplitSource;
+use mux::pane::PaneId;
 use mux::tab::SplitRequest;
 use mux::window::WindowId as MuxWindowId;
 use mux::Mux;
+use mux_lua::MuxPane;
 use portable_pty::CommandBuilder;
+use std::rc::Rc;
 use std::sync::Arc;
 use wezterm_term::TerminalSize;
 
@@ -69,11 +72,9 @@ pub async fn spawn_command_internal(
             lossy_cwd
         }
     });
-    // Remember whether an encoding was explicitly requested before consuming the field.
-    let explicit_encoding = spawn.encoding.is_some();
-    let encoding: PaneEncoding = spawn
-        .encoding
-        .unwrap_or_else(|| config::configuration().default_encoding);
+    // When no encoding is requested, the mux picks the default for the
+    // target domain.
+    let encoding: Option<PaneEncoding> = spawn.encoding;
 
     let cmd_builder = match (
         spawn.args.as_ref(),
@@ -125,9 +126,10 @@ pub async fn spawn_command_internal(
                     .await
                     .context("split_pane")?;
                 pane.set_config(term_config);
+                trigger_pane_spawned(pane.pane_id());
                 // Only override encoding when explicitly requested; otherwise the
                 // encoding inherited from the source pane by domain::split_pane stands.
-                if explicit_encoding {
+                if let Some(encoding) = encoding {
                     pane.set_encoding(encoding);
                 }
             } else {
@@ -144,7 +146,7 @@ pub async fn spawn_command_internal(
                     spawn.domain,
                     cmd_builder,
                     cwd,
-                    Some(encoding),
+                    encoding,
                     size,
                     current_pane_id,
                     workspace,
@@ -159,6 +161,7 @@ pub async fn spawn_command_internal(
             if Some(window_id) == src_window_id {
                 pane.set_config(term_config);
             }
+            trigger_pane_spawned(pane.pane_id());
         }
     };
 
@@ -166,3 +169,23 @@ pub async fn spawn_command_internal(
 
     Ok(())
 }
+
+/// Emits the `pane-spawned` event for a pane created by a spawn command
+fn trigger_pane_spawned(pane_id: PaneId) {
+    async fn do_event(lua: Option<Rc<mlua::Lua>>, pane: MuxPane) -> anyhow::Result<()> {
+        if let Some(lua) = lua {
+            let args = lua.pack_multi(pane)?;
+            config::lua::emit_event(&lua, ("pane-spawned".to_string(), args)).await?;
+        }
+        Ok(())
+    }
+
+    promise::spawn::spawn(async move {
+        if let Err(err) =
+            config::with_lua_config_on_main_thread(move |lua| do_event(lua, MuxPane(pane_id))).await
+        {
+            log::error!("while processing pane-spawned event: {:#}", err);
+        }
+    })
+    .detach();
+}
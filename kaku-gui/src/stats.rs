@@ -19,6 +19,7 @@ struct ThroughputInner {
     hist: Histogram<u64>,
     last: Option<Instant>,
     count: u64,
+    previous: u64,
 }
 
 struct Throughput {
@@ -32,6 +33,7 @@ impl Throughput {
                 hist: Histogram::new(2).expect("failed to create histogram"),
                 last: None,
                 count: 0,
+                previous: 0,
             }),
         }
     }
@@ -39,6 +41,13 @@ impl Throughput {
         self.inner.lock().current()
     }
 
+    /// Returns the total from the most recently completed one second window
+    fn previous(&self) -> u64 {
+        let mut inner = self.inner.lock();
+        inner.current();
+        inner.previous
+    }
+
     fn percentiles(&self) -> (u64, u64, u64) {
         let inner = self.inner.lock();
         let p50 = inner.hist.value_at_percentile(50.);
@@ -54,6 +63,11 @@ impl ThroughputInner {
             let elapsed = last.elapsed();
             if elapsed > Duration::from_secs(1) {
                 self.hist.record(self.count).ok();
+                self.previous = if elapsed > Duration::from_secs(2) {
+                    0
+                } else {
+                    self.count
+                };
                 self.count = 0;
                 self.last = Some(Instant::now());
             }
@@ -69,6 +83,11 @@ impl ThroughputInner {
             let elapsed = last.elapsed();
             if elapsed > Duration::from_secs(1) {
                 self.hist.record(self.count).ok();
+                self.previous = if elapsed > Duration::from_secs(2) {
+                    0
+                } else {
+                    self.count
+                };
                 self.count = 0;
                 self.last = Some(Instant::now());
             }
@@ -354,6 +373,84 @@ impl Recorder for Stats {
     }
 }
 
+/// A snapshot of the metrics that are shown by the debug HUD
+pub struct HudStats {
+    /// p50, p75 and p95 of the time taken to paint a frame
+    pub frame_time: Option<(Duration, Duration, Duration)>,
+    /// Fraction of glyph cache lookups in the last second that were hits
+    pub glyph_cache_hit_rate: Option<f64>,
+}
+
+fn find_throughput<'a>(
+    inner: &'a Inner,
+    name: &str,
+    label: Option<(&str, &str)>,
+) -> Option<&'a Arc<Throughput>> {
+    inner.throughput.iter().find_map(|(key, tput)| {
+        if key.name() != name {
+            return None;
+        }
+        let matched = match label {
+            Some((label_key, label_value)) => key
+                .labels()
+                .any(|l| l.key() == label_key && l.value() == label_value),
+            None => key.labels().next().is_none(),
+        };
+        matched.then_some(tput)
+    })
+}
+
+pub fn hud_stats() -> HudStats {
+    let inner = INNER.lock();
+    let frame_time = inner
+        .histograms
+        .iter()
+        .find(|(key, _)| key.name() == "gui.paint.impl")
+        .map(|(_, hist)| hist.latency_percentiles());
+
+    let hits = find_throughput(&inner, "glyph_cache.glyph_cache.hit.rate", None)
+        .map(|t| t.previous())
+        .unwrap_or(0);
+    let misses = find_throughput(&inner, "glyph_cache.glyph_cache.miss.rate", None)
+        .map(|t| t.previous())
+        .unwrap_or(0);
+    let glyph_cache_hit_rate = if hits + misses > 0 {
+        Some(hits as f64 / (hits + misses) as f64)
+    } else {
+        None
+    };
+
+    HudStats {
+        frame_time,
+        glyph_cache_hit_rate,
+    }
+}
+
+/// Returns the number of bytes read from the pty of the specified
+/// pane during the most recently completed one second window
+pub fn pane_read_rate(pane_id: mux::pane::PaneId) -> u64 {
+    let inner = INNER.lock();
+    let pane_id = pane_id.to_string();
+    find_throughput(
+        &inner,
+        "read_from_pane_pty.pane.bytes.rate",
+        Some(("pane", &pane_id)),
+    )
+    .map(|t| t.previous())
+    .unwrap_or(0)
+}
+
+/// Discards the metrics that are labelled with the specified pane,
+/// once that pane has been removed
+pub fn forget_pane(pane_id: mux::pane::PaneId) {
+    let mut inner = INNER.lock();
+    let pane_id = pane_id.to_string();
+    inner.throughput.retain(|key, _| {
+        !key.labels()
+            .any(|l| l.key() == "pane" && l.value() == pane_id)
+    });
+}
+
 pub fn register(lua: &Lua) -> anyhow::Result<()> {
     let metrics_mod = get_or_create_sub_module(lua, "metrics")?;
     metrics_mod.set(
@@ -375,6 +472,7 @@ pub fn register(lua: &Lua) -> anyhow::Result<()> {
             let counters: HashMap<String, HashMap<String, u64>> = inner
                 .throughput
                 .iter_mut()
+                .filter(|(k, _)| k.labels().next().is_none())
                 .map(|(k, tput)| {
                     let mut res = HashMap::new();
                     res.insert("current".to_string(), tput.current());
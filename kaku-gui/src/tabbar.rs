@@ -4,6 +4,7 @@ use finl_unicode::grapheme_clusters::Graphemes;
 use mlua::FromLua;
 use mux::pane::CachePolicy;
 use mux::Mux;
+use std::collections::HashSet;
 use std::path::Path;
 use termwiz::cell::{unicode_column_width, Cell, CellAttributes};
 use termwiz::color::{AnsiColor, ColorSpec};
@@ -27,6 +28,7 @@ pub enum TabBarItem {
     LeftStatus,
     RightStatus,
     Tab { tab_idx: usize, active: bool },
+    TabGroup { tab_idx: usize, collapsed: bool },
     NewTabButton,
     WindowButton(IntegratedTitleButton),
 }
@@ -385,6 +387,41 @@ fn is_tab_hover(mouse_x: Option<usize>, x: usize, tab_title_len: usize) -> bool
         .unwrap_or(false)
 }
 
+/// How a tab is laid out with respect to its tab group
+#[derive(Debug, PartialEq, Eq)]
+struct TabGroupPlacement<'a> {
+    /// The name of the group, if this is the first tab of that group,
+    /// in which case a header for it is emitted ahead of the tab
+    header: Option<&'a str>,
+    /// Whether the group of this tab is collapsed
+    collapsed: bool,
+    /// Whether the tab itself is shown
+    visible: bool,
+}
+
+fn tab_group_placements<'a>(
+    groups: &[Option<&'a str>],
+    active_tab_no: usize,
+    collapsed_groups: &HashSet<String>,
+) -> Vec<TabGroupPlacement<'a>> {
+    let mut prior_group: Option<&str> = None;
+    groups
+        .iter()
+        .enumerate()
+        .map(|(tab_idx, &group)| {
+            let collapsed = group.is_some_and(|g| collapsed_groups.contains(g));
+            let header = group.filter(|_| group != prior_group);
+            prior_group = group;
+            TabGroupPlacement {
+                header,
+                collapsed,
+                // The active tab stays visible even when its group is collapsed
+                visible: !collapsed || tab_idx == active_tab_no,
+            }
+        })
+        .collect()
+}
+
 impl TabBarState {
     pub fn default() -> Self {
         Self {
@@ -507,6 +544,7 @@ impl TabBarState {
         config: &ConfigHandle,
         left_status: &str,
         right_status: &str,
+        collapsed_groups: &HashSet<String>,
     ) -> Self {
         let colors = colors.cloned().unwrap_or_else(TabBarColors::default);
 
@@ -627,9 +665,47 @@ impl TabBarState {
             line.append_line(left_status_line, SEQ_ZERO);
         }
 
+        let groups: Vec<Option<&str>> = tab_info
+            .iter()
+            .map(|tab| tab.tab_group.as_deref())
+            .collect();
+        let placements = tab_group_placements(&groups, active_tab_no, collapsed_groups);
         for (tab_idx, tab_title) in tab_titles.iter().enumerate() {
-            let tab_title_len = tab_title.len.min(tab_width_max);
             let active = tab_idx == active_tab_no;
+            let TabGroupPlacement {
+                header,
+                collapsed,
+                visible,
+            } = placements[tab_idx];
+
+            // Emit a header ahead of the first tab of each group; clicking
+            // it collapses or expands the tabs that follow.
+            if let Some(name) = header {
+                let marker = if collapsed { '▸' } else { '▾' };
+                let header = parse_status_text(
+                    &format!(" {marker} {name} "),
+                    if config.use_fancy_tab_bar {
+                        CellAttributes::default()
+                    } else {
+                        inactive_cell_attrs.clone()
+                    },
+                );
+                let width = header.len();
+                items.push(TabEntry {
+                    item: TabBarItem::TabGroup { tab_idx, collapsed },
+                    title: header.clone(),
+                    x,
+                    width,
+                });
+                line.append_line(header, SEQ_ZERO);
+                x += width;
+            }
+
+            if !visible {
+                continue;
+            }
+
+            let tab_title_len = tab_title.len.min(tab_width_max);
             let hover = !active && is_tab_hover(mouse_x, x, tab_title_len);
 
             // Recompute the title so that it factors in both the hover state
@@ -663,6 +739,13 @@ impl TabBarState {
                 },
             );
 
+            if let Some(color) = tab_info[tab_idx].tab_color {
+                for cell in tab_line.cells_mut_for_attr_changes_only() {
+                    cell.attrs_mut()
+                        .set_background(ColorSpec::TrueColor(*color));
+                }
+            }
+
             let title = tab_line.clone();
             if tab_line.len() > tab_width_max {
                 tab_line.resize(tab_width_max, SEQ_ZERO);
@@ -927,4 +1010,27 @@ mod test {
     fn ignore_non_ssh_command() {
         assert!(ssh_target_from_command("ls -la").is_none());
     }
+
+    #[test]
+    fn collapsed_groups_keep_header_and_active_tab() {
+        let groups = [None, Some("a"), Some("a"), Some("b"), Some("a")];
+        let collapsed_groups: HashSet<String> = ["a".to_string()].iter().cloned().collect();
+        let placement = |header, collapsed, visible| TabGroupPlacement {
+            header,
+            collapsed,
+            visible,
+        };
+
+        assert_eq!(
+            tab_group_placements(&groups, 2, &collapsed_groups),
+            vec![
+                placement(None, false, true),
+                placement(Some("a"), true, false),
+                placement(None, true, true),
+                placement(Some("b"), false, true),
+                // Not adjacent to the other tabs of "a", so it gets its own header
+                placement(Some("a"), true, false),
+            ]
+        );
+    }
 }
@@ -0,0 +1,125 @@
+//! Exposes the text of the active pane to screen readers via
+//! `WindowOps::set_accessible_text`.
+use crate::termwindow::TermWindow;
+use ::window::{AccessibleLine, AccessibleText, Point, Rect, Size, WindowOps};
+use mux::pane::PaneId;
+use termwiz::surface::{CursorVisibility, SequenceNo};
+use wezterm_term::{Line, StableRowIndex};
+
+/// The inputs that `AccessibleText` was built from.  When these are
+/// unchanged, so is the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibleTextKey {
+    pane_id: PaneId,
+    seqno: SequenceNo,
+    top: StableRowIndex,
+    cursor: Option<(usize, StableRowIndex)>,
+    bounds: Rect,
+    cell_size: Size,
+}
+
+impl TermWindow {
+    /// Passes the visible text and cursor position of the active pane
+    /// to the window, if it changed since the last frame.  Nothing is
+    /// exposed while the session is locked.
+    pub fn update_accessible_text(&mut self) {
+        if !self.config.screen_reader_support || self.is_session_locked() {
+            if self.accessible_text_key.take().is_some() {
+                if let Some(window) = self.window.as_ref() {
+                    window.set_accessible_text(AccessibleText::default());
+                }
+            }
+            return;
+        }
+
+        let pos = match self
+            .get_panes_to_render()
+            .into_iter()
+            .find(|pos| pos.is_active)
+        {
+            Some(pos) => pos,
+            None => return,
+        };
+        let pane = &pos.pane;
+        let pane_id = pane.pane_id();
+        let dims = pane.get_dimensions();
+        let top = self.get_viewport(pane_id).unwrap_or(dims.physical_top);
+        let rows = dims.viewport_rows as StableRowIndex;
+
+        let cursor = pane.get_cursor_position();
+        let cursor = (cursor.visibility == CursorVisibility::Visible
+            && cursor.y >= top
+            && cursor.y < top + rows)
+            .then_some((cursor.x, cursor.y));
+
+        let (padding_left, padding_top) = self.padding_left_top();
+        let border = self.get_os_border();
+        let tab_bar_height = if self.show_tab_bar && !self.config.tab_bar_at_bottom {
+            self.tab_bar_pixel_height().unwrap_or(0.)
+        } else {
+            0.
+        };
+        let cell_size = self.render_metrics.cell_size;
+        let bounds = Rect::new(
+            Point::new(
+                (padding_left + border.left.get() as f32) as isize
+                    + pos.left as isize * cell_size.width,
+                (tab_bar_height + padding_top + border.top.get() as f32) as isize
+                    + pos.top as isize * cell_size.height,
+            ),
+            Size::new(
+                pos.width as isize * cell_size.width,
+                pos.height as isize * cell_size.height,
+            ),
+        );
+
+        let key = AccessibleTextKey {
+            pane_id,
+            seqno: pane.get_current_seqno(),
+            top,
+            cursor,
+            bounds,
+            cell_size,
+        };
+        if self.accessible_text_key.as_ref() == Some(&key) {
+            return;
+        }
+
+        let (first_row, lines) = pane.get_lines(top..top + rows);
+        let lines = lines.iter().map(accessible_line).collect::<Vec<_>>();
+        let text = AccessibleText {
+            cursor_line: cursor.map(|(_, y)| (y - first_row).max(0) as usize),
+            cursor_column: cursor.map(|(x, _)| x).unwrap_or(0),
+            lines,
+            bounds,
+            cell_size,
+        };
+
+        if let Some(window) = self.window.as_ref() {
+            window.set_accessible_text(text);
+        }
+        self.accessible_text_key.replace(key);
+    }
+}
+
+/// Returns the text of `line`, without trailing whitespace, along with
+/// the cell column at which each of its chars starts
+fn accessible_line(line: &Line) -> AccessibleLine {
+    let mut text = String::new();
+    let mut columns = vec![];
+    let mut end = 0;
+    for cell in line.visible_cells() {
+        for c in cell.str().chars() {
+            text.push(c);
+            columns.push(cell.cell_index());
+        }
+        end = cell.cell_index() + cell.width();
+    }
+    text.truncate(text.trim_end().len());
+    let chars = text.chars().count();
+    // The line ends where the trimmed whitespace started
+    let end = columns.get(chars).copied().unwrap_or(end);
+    columns.truncate(chars);
+    columns.push(end);
+    AccessibleLine { text, columns }
+}
@@ -393,7 +393,15 @@ impl Element {
 pub enum ElementContent {
     Text(String),
     Children(Vec<Element>),
-    Poly { line_width: isize, poly: SizedPoly },
+    Poly {
+        line_width: isize,
+        poly: SizedPoly,
+    },
+    /// An element that was laid out earlier, relative to the origin and
+    /// with a zindex of zero.  It is placed at the origin of the bounds
+    /// it is given, which allows callers to cache the layout of parts of
+    /// a larger element that rarely change.
+    Computed(Box<ComputedElement>),
 }
 
 pub struct LayoutContext<'a> {
@@ -444,6 +452,15 @@ impl ComputedElement {
         }
     }
 
+    fn offset_zindex(&mut self, delta: i8) {
+        self.zindex += delta;
+        if let ComputedElementContent::Children(kids) = &mut self.content {
+            for kid in kids {
+                kid.offset_zindex(delta);
+            }
+        }
+    }
+
     pub fn ui_items(&self) -> Vec<UIItem> {
         let mut items = vec![];
         self.ui_item_impl(&mut items);
@@ -820,6 +837,12 @@ impl super::TermWindow {
                     },
                 })
             }
+            ElementContent::Computed(computed) => {
+                let mut computed = (**computed).clone();
+                computed.translate(context.bounds.origin.to_vector());
+                computed.offset_zindex(element.zindex + context.zindex);
+                Ok(computed)
+            }
         }
     }
 
@@ -1,7 +1,9 @@
-use crate::termwindow::TermWindowNotif;
+use crate::overlay::confirm_paste::paste_mentions_sudo;
+use crate::overlay::{confirm_paste, start_overlay_pane};
+use crate::termwindow::{OverlayState, TermWindowNotif};
 use crate::TermWindow;
 use config::keyassignment::{ClipboardCopyDestination, ClipboardPasteSource};
-use mux::pane::Pane;
+use mux::pane::{Pane, PaneId};
 use mux::Mux;
 use smol::Timer;
 use std::collections::HashMap;
@@ -57,6 +59,21 @@ impl TermWindow {
         }
     }
 
+    /// Copies the text of a just-completed mouse selection, honoring
+    /// `copy_on_select` and `copy_on_select_destination`.
+    pub fn copy_completed_selection(&mut self, dest: ClipboardCopyDestination, text: String) {
+        if !self.config.copy_on_select {
+            return;
+        }
+        let dest = self.config.copy_on_select_destination.unwrap_or(dest);
+        self.copy_to_clipboard(dest, text);
+        // The primary selection is invisible to the user, so only
+        // announce copies that land in the clipboard.
+        if dest != ClipboardCopyDestination::PrimarySelection {
+            self.show_copy_toast();
+        }
+    }
+
     fn show_toast_internal(&mut self, message: String, lifetime: Duration) {
         let now = Instant::now();
         let fade_after = lifetime.saturating_sub(Duration::from_millis(500));
@@ -142,6 +159,59 @@ impl TermWindow {
         self.show_toast("Copied".to_string());
     }
 
+    /// Sends `text` to `pane` as a paste, applying the paste safety
+    /// policy from the config: control characters are stripped and
+    /// large or privileged multi-line pastes into a shell prompt are
+    /// routed through a confirmation overlay first.  Pastes into a
+    /// password prompt are never held for confirmation.
+    pub fn paste_text(&mut self, pane: &Arc<dyn Pane>, text: String) -> anyhow::Result<()> {
+        let text = if self.config.sanitize_pasted_text {
+            sanitize_paste(text)
+        } else {
+            text
+        };
+
+        let threshold = self.config.paste_confirmation_lines;
+        let needs_confirmation = threshold > 0
+            && !self.is_overlay_pane(pane.pane_id())
+            && !pane.is_alt_screen_active()
+            && !self.pane_has_password_input(pane)
+            && match paste_line_count(&text) {
+                lines if lines >= threshold => true,
+                lines => (lines > 1 || text.ends_with('\n')) && paste_mentions_sudo(&text),
+            };
+
+        if !needs_confirmation {
+            return pane.send_paste(&text);
+        }
+
+        let window = self.window.clone().unwrap();
+        let (overlay, future) = start_overlay_pane(self, pane, move |pane_id, term| {
+            confirm_paste(pane_id, text, term, window)
+        });
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+        Ok(())
+    }
+
+    fn is_overlay_pane(&self, pane_id: PaneId) -> bool {
+        let is_overlay = |overlay: &Option<OverlayState>| {
+            overlay
+                .as_ref()
+                .map(|overlay| overlay.pane.pane_id() == pane_id)
+                .unwrap_or(false)
+        };
+        self.pane_state
+            .borrow()
+            .values()
+            .any(|state| is_overlay(&state.overlay))
+            || self
+                .tab_state
+                .borrow()
+                .values()
+                .any(|state| is_overlay(&state.overlay))
+    }
+
     pub fn paste_from_clipboard(&mut self, pane: &Arc<dyn Pane>, clipboard: ClipboardPasteSource) {
         let pane_id = pane.pane_id();
         log::trace!(
@@ -175,7 +245,7 @@ impl TermWindow {
                                 mux.get_pane(pane_id)
                             })
                         {
-                            if let Err(err) = pane.send_paste(&clip) {
+                            if let Err(err) = myself.paste_text(&pane, clip) {
                                 log::warn!(
                                     "failed to paste clipboard content into pane {pane_id}: {err:#}"
                                 );
@@ -193,6 +263,27 @@ impl TermWindow {
     }
 }
 
+fn is_unsafe_paste_char(c: char) -> bool {
+    match c {
+        '\t' | '\n' | '\r' => false,
+        c => c.is_control(),
+    }
+}
+
+/// Removes C0/C1 control characters (including ESC) from pasted text,
+/// preserving tabs and line endings.
+fn sanitize_paste(text: String) -> String {
+    if text.chars().any(is_unsafe_paste_char) {
+        text.chars().filter(|&c| !is_unsafe_paste_char(c)).collect()
+    } else {
+        text
+    }
+}
+
+fn paste_line_count(text: &str) -> usize {
+    text.trim_end_matches(['\r', '\n']).lines().count()
+}
+
 fn data_to_paste_string(
     data: ClipboardData,
     quote_dropped_files: config::DroppedFileQuoting,
@@ -248,3 +339,24 @@ fn quote_path_for_clipboard_paste(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_strips_escape_sequences() {
+        assert_eq!(
+            sanitize_paste("echo hi\x1b[201~\nrm -rf ~\r\n".to_string()),
+            "echo hi[201~\nrm -rf ~\r\n"
+        );
+        assert_eq!(sanitize_paste("a\tb\u{9b}c\x7f".to_string()), "a\tbc");
+    }
+
+    #[test]
+    fn line_count_ignores_trailing_newlines() {
+        assert_eq!(paste_line_count("one"), 1);
+        assert_eq!(paste_line_count("one\n"), 1);
+        assert_eq!(paste_line_count("one\r\ntwo\r\n"), 2);
+    }
+}
@@ -95,6 +95,11 @@ impl KeyTableState {
         self.stack.last().map(|entry| entry.name.as_str())
     }
 
+    /// Returns when the current table will expire, if it has a timeout
+    pub fn current_expiration(&self) -> Option<Instant> {
+        self.stack.last().and_then(|entry| entry.expiration)
+    }
+
     fn lookup_key(
         &mut self,
         input_map: &InputMap,
@@ -1114,8 +1119,11 @@ impl super::TermWindow {
         // Fallback for shell prompt line-editing habits on macOS.
         // We intentionally emit readline/zle emacs controls here so that
         // behavior works even when modified cursor CSI sequences are not bound.
+        // The shortcuts emit widget sequences that a password prompt would
+        // take literally, so leave the keys alone while one is active.
         if self.pane_state(pane.pane_id()).overlay.is_none()
             && !leader_active
+            && !self.pane_has_password_input(&pane)
             && self.maybe_handle_native_line_editor_shortcut(&pane, &window_key, context)
         {
             return;
@@ -7,9 +7,10 @@ use crate::inputmap::InputMap;
 #[cfg(not(target_os = "macos"))]
 use crate::overlay::confirm_close_window;
 use crate::overlay::{
-    confirm_close_pane, confirm_close_tab, confirm_quit_program, launcher, show_debug_overlay,
-    start_overlay, start_overlay_pane, CopyModeParams, CopyOverlay, LauncherArgs, LauncherFlags,
-    QuickSelectOverlay,
+    confirm_close_pane, confirm_close_tab, confirm_quit_program, launcher, show_askpass,
+    show_command_history, show_debug_overlay, show_pane_hex_dump, show_pane_latency,
+    show_pane_process_info, start_overlay, start_overlay_pane, CommandHistoryScope, CopyModeParams,
+    CopyOverlay, LauncherArgs, LauncherFlags, QuickSelectOverlay,
 };
 use crate::resize_increment_calculator::ResizeIncrementCalculator;
 use crate::scripting::guiwin::GuiWin;
@@ -27,21 +28,25 @@ use crate::termwindow::render::{
     CachedLineState, LineQuadCacheKey, LineQuadCacheValue, LineToEleShapeCacheKey,
     LineToElementShapeItem,
 };
+use crate::termwindow::sessionlock::SessionLock;
 use crate::termwindow::webgpu::WebGpuState;
 use ::wezterm_term::input::{ClickPosition, MouseButton as TMB};
 use ::window::*;
 use anyhow::{anyhow, ensure, Context};
 use config::keyassignment::{
-    Confirmation, KeyAssignment, LauncherActionArgs, PaneDirection, PaneEncoding, Pattern,
-    PromptInputLine, QuickSelectArguments, RotationDirection, SpawnCommand, SplitSize,
+    Confirmation, KeyAssignment, LauncherActionArgs, PaneDirection, PaneEncoding,
+    PaneSelectArguments, Pattern, PromptInputLine, QuickSelectArguments, RotationDirection,
+    SpawnCommand, SplitSize,
 };
 use config::window::WindowLevel;
 use config::{
     configuration, AudibleBell, ConfigHandle, Dimension, DimensionContext, FrontEndSelection,
-    GeometryOrigin, GuiPosition, TermConfig, WindowCloseConfirmation,
+    GeometryOrigin, GuiPosition, NotificationHandling, RgbaColor, TermConfig,
+    WindowCloseConfirmation,
 };
 use lfucache::*;
 use mlua::{FromLua, LuaSerdeExt, UserData, UserDataFields};
+use mux::askpass::SecretRequest;
 use mux::pane::{
     CachePolicy, CloseReason, Pane, PaneId, Pattern as MuxPattern, PerformAssignmentResult,
 };
@@ -56,9 +61,9 @@ use mux_lua::MuxPane;
 use smol::channel::Sender;
 use smol::Timer;
 use std::cell::{RefCell, RefMut};
-use std::collections::{HashMap, LinkedList};
+use std::collections::{HashMap, HashSet, LinkedList};
 use std::ops::Add;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -72,6 +77,7 @@ use wezterm_term::color::ColorPalette;
 use wezterm_term::input::LastMouseClick;
 use wezterm_term::{Alert, Progress, StableRowIndex, TerminalConfiguration, TerminalSize};
 
+mod accessibility;
 pub mod background;
 pub mod box_model;
 pub mod charselect;
@@ -85,7 +91,10 @@ mod prevcursor;
 pub mod render;
 pub mod resize;
 mod selection;
+pub mod sessionlock;
 pub mod spawn;
+pub mod taboverview;
+mod watchlist;
 pub mod webgpu;
 use crate::spawn::SpawnWhere;
 use prevcursor::PrevCursorPos;
@@ -430,6 +439,9 @@ impl UIItem {
 pub struct SemanticZoneCache {
     seqno: SequenceNo,
     zones: Vec<StableRowIndex>,
+    /// The subset of `zones` whose command failed, and the seqno
+    /// at which that was computed
+    failed_zones: Option<(SequenceNo, Vec<StableRowIndex>)>,
 }
 
 pub struct OverlayState {
@@ -443,6 +455,15 @@ pub struct PaneState {
     /// Otherwise, the viewport is at the bottom of the
     /// scrollback.
     viewport: Option<StableRowIndex>,
+    /// While the mouse is held to select text in a pane that is
+    /// following its output, the top row of the screen when the
+    /// selection started.  It is used in place of the viewport so
+    /// that new output doesn't scroll the text under the mouse and
+    /// move the end of the selection.
+    selection_drag_top: Option<StableRowIndex>,
+    /// The first column shown when the pane holds lines that are
+    /// wider than it is.
+    horizontal_scroll: usize,
     selection: Selection,
     /// If is_some(), rather than display the actual tab
     /// contents, we're overlaying a little internal application
@@ -450,7 +471,10 @@ pub struct PaneState {
     pub overlay: Option<OverlayState>,
 
     bell_start: Option<Instant>,
+    focus_flash_start: Option<Instant>,
     pub has_unread_bell: bool,
+    /// The pane_watchlist rule the pane matched when last checked
+    watch_rule: Option<usize>,
     pub mouse_terminal_coords: Option<(ClickPosition, StableRowIndex)>,
 }
 
@@ -464,6 +488,8 @@ pub struct TabInformation {
     pub active_pane: Option<PaneInformation>,
     pub window_id: MuxWindowId,
     pub tab_title: String,
+    pub tab_color: Option<RgbaColor>,
+    pub tab_group: Option<String>,
 }
 
 impl UserData for TabInformation {
@@ -493,6 +519,8 @@ impl UserData for TabInformation {
         });
         fields.add_field_method_get("window_id", |_, this| Ok(this.window_id));
         fields.add_field_method_get("tab_title", |_, this| Ok(this.tab_title.clone()));
+        fields.add_field_method_get("tab_color", |_, this| Ok(this.tab_color.map(String::from)));
+        fields.add_field_method_get("tab_group", |_, this| Ok(this.tab_group.clone()));
         fields.add_field_method_get("window_title", |_, this| {
             let mux = Mux::get();
             let window = mux.get_window(this.window_id).ok_or_else(|| {
@@ -510,6 +538,7 @@ pub struct PaneInformation {
     pub pane_index: usize,
     pub is_active: bool,
     pub is_zoomed: bool,
+    pub is_output_paused: bool,
     pub has_unseen_output: bool,
     pub left: usize,
     pub top: usize,
@@ -528,6 +557,7 @@ impl UserData for PaneInformation {
         fields.add_field_method_get("pane_index", |_, this| Ok(this.pane_index));
         fields.add_field_method_get("is_active", |_, this| Ok(this.is_active));
         fields.add_field_method_get("is_zoomed", |_, this| Ok(this.is_zoomed));
+        fields.add_field_method_get("is_output_paused", |_, this| Ok(this.is_output_paused));
         fields.add_field_method_get("has_unseen_output", |_, this| Ok(this.has_unseen_output));
         fields.add_field_method_get("left", |_, this| Ok(this.left));
         fields.add_field_method_get("top", |_, this| Ok(this.top));
@@ -641,6 +671,18 @@ pub struct TermWindow {
     os_parameters: Option<parameters::Parameters>,
     /// When we most recently received keyboard focus
     pub focused: Option<Instant>,
+    /// Whether this window asked for secure keyboard entry
+    secure_keyboard_entry: bool,
+    /// `kaku askpass` prompts being shown, keyed by overlay pane id
+    askpass_overlays: HashMap<PaneId, Arc<SecretRequest>>,
+    /// What the text most recently passed to `set_accessible_text`
+    /// was built from, so that unchanged frames can skip rebuilding it
+    accessible_text_key: Option<accessibility::AccessibleTextKey>,
+    /// When we most recently received keyboard or mouse input,
+    /// used to decide when to apply `session_lock_idle_minutes`
+    last_input: Instant,
+    /// The deadline of the pending idle lock check, if any
+    idle_lock_check: Option<Instant>,
     fonts: Rc<FontConfiguration>,
     /// Window dimensions and dpi
     pub dimensions: Dimensions,
@@ -658,11 +700,16 @@ pub struct TermWindow {
     /// If is_some, the LEADER modifier is active until the specified instant.
     leader_is_down: Option<std::time::Instant>,
     dead_key_status: DeadKeyStatus,
+    /// Whether we have asked the window to disable dead keys because
+    /// of `use_dead_keys_in_alt_screen`
+    dead_keys_suppressed: bool,
     key_table_state: KeyTableState,
     show_tab_bar: bool,
     show_scroll_bar: bool,
     tab_bar: TabBarState,
     fancy_tab_bar: Option<box_model::ComputedElement>,
+    /// Layout of each tab in the fancy tab bar, keyed by tab index
+    fancy_tab_cache: RefCell<HashMap<usize, render::fancy_tab_bar::CachedTab>>,
     pub right_status: String,
     pub left_status: String,
     last_ui_item: Option<UIItem>,
@@ -689,6 +736,12 @@ pub struct TermWindow {
     current_mouse_capture: Option<MouseCapture>,
 
     opengl_info: Option<String>,
+    /// The front end that was actually initialized, which may differ
+    /// from the configured one if it fell back to another
+    front_end: FrontEndSelection,
+    /// Receives the OpenGL context created by `switch_to_software_renderer`
+    software_context: Rc<RefCell<Option<anyhow::Result<Rc<glium::backend::Context>>>>>,
+    switching_to_software: bool,
 
     /// Keeps track of double and triple clicks
     last_mouse_click: Option<LastMouseClick>,
@@ -749,6 +802,19 @@ pub struct TermWindow {
     /// Toast notification: (start_time, message, lifetime)
     toast: Option<(Instant, String, Duration)>,
 
+    /// Whether the performance HUD is drawn over the window content
+    show_debug_hud: bool,
+
+    /// The font scale to return to when presentation mode ends;
+    /// Some while presentation mode is active in this window
+    presentation_restore_font_scale: Option<f64>,
+
+    /// Tab groups whose inactive tabs are hidden from the tab bar
+    collapsed_tab_groups: HashSet<String>,
+
+    /// pane_watchlist rules compiled from the current config
+    pane_watchlist: Option<watchlist::CompiledWatchlist>,
+
     /// Stack of working dirs from recently closed tabs, for ReopenLastClosedTab.
     /// Most recently closed is at the back.
     closed_tabs: std::collections::VecDeque<std::path::PathBuf>,
@@ -860,6 +926,7 @@ impl TermWindow {
 
             for state in self.pane_state.borrow_mut().values_mut() {
                 state.mouse_terminal_coords.take();
+                state.selection_drag_top.take();
             }
         }
 
@@ -882,13 +949,22 @@ impl TermWindow {
         }
 
         self.update_title();
+        self.update_secure_keyboard_entry();
         self.emit_window_event("window-focus-changed", None);
     }
 
     fn created(&mut self, ctx: RenderContext) -> anyhow::Result<()> {
         self.render_state = None;
 
-        let render_info = ctx.renderer_info();
+        let render_info = if self.front_end == self.config.front_end {
+            ctx.renderer_info()
+        } else {
+            format!(
+                "{} (fallback from front_end={:?})",
+                ctx.renderer_info(),
+                self.config.front_end
+            )
+        };
         self.opengl_info.replace(render_info.clone());
 
         match RenderState::new(ctx, &self.fonts, &self.render_metrics, ATLAS_SIZE) {
@@ -957,7 +1033,9 @@ impl TermWindow {
 
         // Initially we have only a single tab, so take that into account
         // for the tab bar state.
-        let show_tab_bar = config.enable_tab_bar && !config.hide_tab_bar_if_only_one_tab;
+        let show_tab_bar = config.enable_tab_bar
+            && !config.hide_tab_bar_if_only_one_tab
+            && !config.use_native_macos_tabs;
         let tab_bar_height = if show_tab_bar {
             Self::tab_bar_pixel_height_impl(&config, &fontconfig, &render_metrics)? as usize
         } else {
@@ -1071,6 +1149,11 @@ impl TermWindow {
             config_overrides: wezterm_dynamic::Value::default(),
             palette: None,
             focused: None,
+            secure_keyboard_entry: false,
+            askpass_overlays: HashMap::new(),
+            accessible_text_key: None,
+            last_input: Instant::now(),
+            idle_lock_check: None,
             mux_window_id,
             mux_window_id_for_subscriptions: Arc::new(Mutex::new(mux_window_id)),
             fonts: Rc::clone(&fontconfig),
@@ -1085,10 +1168,12 @@ impl TermWindow {
             input_map: InputMap::new(&config),
             leader_is_down: None,
             dead_key_status: DeadKeyStatus::None,
+            dead_keys_suppressed: false,
             show_tab_bar,
             show_scroll_bar: config.enable_scroll_bar,
             tab_bar: TabBarState::default(),
             fancy_tab_bar: None,
+            fancy_tab_cache: RefCell::new(HashMap::new()),
             right_status: String::new(),
             left_status: String::new(),
             last_mouse_coords: (0, -1),
@@ -1169,7 +1254,14 @@ impl TermWindow {
             key_table_state: KeyTableState::default(),
             modal: RefCell::new(None),
             opengl_info: None,
+            front_end: config.front_end,
+            software_context: Rc::new(RefCell::new(None)),
+            switching_to_software: false,
             toast: None,
+            show_debug_hud: false,
+            presentation_restore_font_scale: None,
+            collapsed_tab_groups: HashSet::new(),
+            pane_watchlist: None,
             live_resizing: false,
             closed_tabs: std::collections::VecDeque::new(),
         };
@@ -1221,6 +1313,7 @@ impl TermWindow {
         {
             let mut myself = tw.borrow_mut();
             myself.load_os_parameters();
+            myself.schedule_idle_lock_check();
         }
         crate::startup_trace::mark("  window.show() start");
         window.show();
@@ -1241,23 +1334,54 @@ impl TermWindow {
         config::enable_deferred_watchers();
 
         crate::startup_trace::mark("  GPU init start");
-        let (gl, webgpu) = match config.front_end {
-            FrontEndSelection::WebGpu => match WebGpuState::new(&window, dimensions, &config).await
-            {
-                Ok(state) => (None, Some(Rc::new(state))),
+        let mut front_ends = vec![config.front_end];
+        for front_end in &config.front_end_fallback {
+            if !front_ends.contains(front_end) {
+                front_ends.push(*front_end);
+            }
+        }
+        let mut errors = vec![];
+        let mut initialized = None;
+        for front_end in front_ends {
+            let result = match front_end {
+                FrontEndSelection::WebGpu => {
+                    match WebGpuState::new(&window, dimensions, &config).await {
+                        Ok(state) => {
+                            state.load_background_shader(&config).await;
+                            Ok(RenderContext::WebGpu(Rc::new(state)))
+                        }
+                        Err(err) => Err(err),
+                    }
+                }
+                FrontEndSelection::OpenGL => window.enable_opengl().await.map(RenderContext::Glium),
+                FrontEndSelection::Software => window
+                    .enable_software_opengl()
+                    .await
+                    .map(RenderContext::Glium),
+            };
+            match result {
+                Ok(ctx) => {
+                    initialized.replace((front_end, ctx));
+                    break;
+                }
                 Err(err) => {
-                    log::error!(
-                        "WebGpu initialization failed; falling back to OpenGL. Error: {:#}",
-                        err
-                    );
-                    let gl = window.enable_opengl().await.with_context(|| {
-                        "WebGpu initialization failed and OpenGL fallback also failed"
-                    })?;
-                    (Some(gl), None)
+                    log::error!("{front_end:?} initialization failed: {err:#}");
+                    errors.push(format!("{front_end:?}: {err:#}"));
                 }
-            },
-            _ => (Some(window.enable_opengl().await?), None),
-        };
+            }
+        }
+        let (front_end, render_context) = initialized.ok_or_else(|| {
+            anyhow!(
+                "Unable to initialize any front end. Tried:\n{}",
+                errors.join("\n")
+            )
+        })?;
+        if front_end != config.front_end {
+            log::warn!(
+                "Using the {front_end:?} front end because {:?} failed to initialize",
+                config.front_end
+            );
+        }
         crate::startup_trace::mark("  GPU init done");
 
         {
@@ -1279,20 +1403,27 @@ impl TermWindow {
                 );
             }
 
-            if let Some(gl) = gl {
-                myself.gl.replace(Rc::clone(&gl));
-                myself.created(RenderContext::Glium(Rc::clone(&gl)))?;
-            }
-            if let Some(webgpu) = webgpu {
-                myself.webgpu.replace(Rc::clone(&webgpu));
-                myself.created(RenderContext::WebGpu(Rc::clone(&webgpu)))?;
+            match &render_context {
+                RenderContext::Glium(gl) => {
+                    myself.gl.replace(Rc::clone(gl));
+                }
+                RenderContext::WebGpu(webgpu) => {
+                    myself.webgpu.replace(Rc::clone(webgpu));
+                }
             }
+            myself.front_end = front_end;
+            myself.created(render_context)?;
             myself.subscribe_to_pane_updates();
             myself.emit_window_event("window-config-reloaded", None);
             myself.emit_status_event();
         }
 
         crate::update::start_update_checker();
+        if front_end().is_presentation_mode() {
+            window.notify(TermWindowNotif::Apply(Box::new(|tw| {
+                tw.set_presentation_mode(true)
+            })));
+        }
         front_end().record_known_window(window, mux_window_id);
 
         Ok(())
@@ -1306,6 +1437,7 @@ impl TermWindow {
         log::trace!("{event:?}");
         match event {
             WindowEvent::Destroyed => {
+                self.set_secure_keyboard_entry(false);
                 self.window.take();
                 self.event_states.clear();
                 // Ensure that we cancel any overlays we had running, so
@@ -1333,10 +1465,13 @@ impl TermWindow {
                 // global config here once per window, which could
                 // be nasty for folks with a lot of windows.
                 // <https://github.com/wezterm/wezterm/issues/2295>
-                config::reload();
+                if !front_end().sync_appearance() {
+                    config::reload();
+                }
                 // Defer per-window reload to avoid re-entrant RefCell borrow
                 // while dispatching the current window event.
                 self.schedule_silent_config_reload(window);
+                self.emit_window_event("appearance-changed", None);
                 Ok(true)
             }
             WindowEvent::PerformKeyAssignment(action) => {
@@ -1351,6 +1486,7 @@ impl TermWindow {
                 Ok(true)
             }
             WindowEvent::MouseEvent(event) => {
+                self.last_input = Instant::now();
                 self.mouse_event_impl(event, window);
                 Ok(true)
             }
@@ -1358,6 +1494,10 @@ impl TermWindow {
                 self.mouse_leave_impl(window);
                 Ok(true)
             }
+            WindowEvent::Gesture(gesture) => {
+                self.gesture_impl(gesture);
+                Ok(true)
+            }
             WindowEvent::Resized {
                 dimensions,
                 window_state,
@@ -1386,10 +1526,12 @@ impl TermWindow {
                 Ok(true)
             }
             WindowEvent::RawKeyEvent(event) => {
+                self.last_input = Instant::now();
                 self.raw_key_event_impl(event, window);
                 Ok(true)
             }
             WindowEvent::KeyEvent(event) => {
+                self.last_input = Instant::now();
                 self.key_event_impl(event, window);
                 Ok(true)
             }
@@ -1407,7 +1549,11 @@ impl TermWindow {
                 Ok(true)
             }
             WindowEvent::NeedRepaint => {
-                if self.resizes_pending > 0 {
+                if !self.window_state.can_paint() {
+                    // Don't render frames that nobody can see; this also
+                    // stops animations from scheduling further frames
+                    Ok(true)
+                } else if self.resizes_pending > 0 {
                     self.is_repaint_pending = true;
                     Ok(true)
                 } else if self.webgpu.is_some() {
@@ -1428,7 +1574,7 @@ impl TermWindow {
                     Some(pane) => pane,
                     None => return Ok(true),
                 };
-                pane.send_paste(text.as_str())?;
+                self.paste_text(&pane, text)?;
                 Ok(true)
             }
             WindowEvent::DroppedUrl(urls) => {
@@ -1474,6 +1620,11 @@ impl TermWindow {
         };
 
         if gl.is_context_lost() {
+            if self.front_end != FrontEndSelection::Software {
+                log::error!("opengl context was lost; switching to the software renderer");
+                self.switch_to_software_renderer(window);
+                return false;
+            }
             log::error!("opengl context was lost; should reinit");
             window.close();
             front_end().forget_known_window(window);
@@ -1510,6 +1661,15 @@ impl TermWindow {
                         log::debug!("wgpu surface timeout, skipping frame");
                         return Ok(false);
                     }
+                    Some(wgpu::SurfaceError::OutOfMemory) => {
+                        log::error!(
+                            "wgpu surface is out of memory; switching to the software renderer"
+                        );
+                        if let Some(window) = self.window.clone() {
+                            self.switch_to_software_renderer(&window);
+                        }
+                        return Ok(false);
+                    }
                     _ => {}
                 }
                 Err(err)
@@ -1522,6 +1682,59 @@ impl TermWindow {
         Ok(true)
     }
 
+    /// Replace the renderer of this window with the software renderer.
+    /// This is used when the GPU stops working after the window was
+    /// created, for example when the OpenGL context is lost.
+    /// The new context is created asynchronously, and takes effect in
+    /// `software_renderer_created`.
+    fn switch_to_software_renderer(&mut self, window: &Window) {
+        if self.switching_to_software {
+            return;
+        }
+        self.switching_to_software = true;
+
+        let slot = Rc::clone(&self.software_context);
+        let window = window.clone();
+        promise::spawn::spawn(async move {
+            let result = window.enable_software_opengl().await;
+            slot.borrow_mut().replace(result);
+            window.notify(TermWindowNotif::Apply(Box::new(|tw| {
+                tw.software_renderer_created()
+            })));
+        })
+        .detach();
+    }
+
+    fn software_renderer_created(&mut self) {
+        self.switching_to_software = false;
+        let Some(result) = self.software_context.borrow_mut().take() else {
+            return;
+        };
+        let window = match self.window.clone() {
+            Some(window) => window,
+            None => return,
+        };
+        match result {
+            Ok(gl) => {
+                self.webgpu.take();
+                self.gl.replace(Rc::clone(&gl));
+                self.front_end = FrontEndSelection::Software;
+                if let Err(err) = self.created(RenderContext::Glium(gl)) {
+                    log::error!("failed to set up the software renderer: {err:#}");
+                }
+                self.invalidate_fancy_tab_bar();
+                self.invalidate_modal();
+                self.show_toast("GPU rendering failed; using the software renderer".to_string());
+                window.invalidate();
+            }
+            Err(err) => {
+                log::error!("software renderer initialization failed: {err:#}");
+                window.close();
+                front_end().forget_known_window(&window);
+            }
+        }
+    }
+
     fn dispatch_notif(&mut self, notif: TermWindowNotif, window: &Window) -> anyhow::Result<()> {
         fn chan_err<T>(e: smol::channel::TrySendError<T>) -> anyhow::Error {
             anyhow::anyhow!("{}", e)
@@ -1621,15 +1834,21 @@ impl TermWindow {
                 } => {
                     self.emit_user_var_event(pane_id, name, value);
                 }
-                MuxNotification::WindowTitleChanged { .. }
-                | MuxNotification::Alert {
+                MuxNotification::Alert {
                     alert:
-                        Alert::OutputSinceFocusLost
-                        | Alert::CurrentWorkingDirectoryChanged
+                        Alert::CurrentWorkingDirectoryChanged
                         | Alert::WindowTitleChanged(_)
                         | Alert::TabTitleChanged(_)
-                        | Alert::IconTitleChanged(_)
-                        | Alert::Progress(_),
+                        | Alert::IconTitleChanged(_),
+                    pane_id,
+                } => {
+                    self.check_pane_watchlist(pane_id);
+                    self.update_title();
+                }
+                MuxNotification::WindowTitleChanged { .. }
+                | MuxNotification::Alert {
+                    alert:
+                        Alert::OutputSinceFocusLost | Alert::TabColorChanged(_) | Alert::Progress(_),
                     ..
                 } => {
                     self.update_title();
@@ -1642,6 +1861,9 @@ impl TermWindow {
                     // ensure that we invalidate that as part of
                     // this overall invalidation for the palette
                     self.dispatch_notif(TermWindowNotif::InvalidateShapeCache, window)?;
+                    // Tab titles are colored using the palette too; the
+                    // cached tabs are keyed on it, so only rebuild the bar
+                    self.invalidate_fancy_tab_bar_layout();
                     self.mux_pane_output_event(pane_id);
                 }
                 MuxNotification::Alert {
@@ -1668,7 +1890,7 @@ impl TermWindow {
                     // a RefCell double-borrow panic at runtime.
                     let is_inactive = self
                         .get_active_pane_or_overlay()
-                        .map_or(true, |p| p.pane_id() != pane_id);
+                        .is_none_or(|p| p.pane_id() != pane_id);
 
                     let window_has_focus = self.focused.is_some();
                     let mut per_pane = self.pane_state(pane_id);
@@ -1686,9 +1908,29 @@ impl TermWindow {
                     if should_mark_unread {
                         front_end().adjust_unread_bell_count(1);
                     }
+                    if self.config.bell_request_attention
+                        && !window_has_focus
+                        && self.presentation_restore_font_scale.is_none()
+                    {
+                        Connection::get()
+                            .expect("on main thread")
+                            .request_user_attention();
+                    }
 
                     window.invalidate();
                 }
+                MuxNotification::Alert {
+                    alert:
+                        Alert::CommandFinished {
+                            command,
+                            exit_status,
+                            duration,
+                            ..
+                        },
+                    pane_id,
+                } => {
+                    self.notify_long_running_command(pane_id, &command, exit_status, duration);
+                }
                 MuxNotification::Alert {
                     alert: Alert::ToastNotification { .. },
                     ..
@@ -1740,7 +1982,13 @@ impl TermWindow {
                 MuxNotification::SaveToDownloads { .. } => {
                     // Handled by frontend
                 }
-                MuxNotification::PaneFocused(_) => {
+                MuxNotification::PaneFocused(pane_id) => {
+                    if self.config.pane_focus_flash_duration_ms > 0 && !self.config.reduce_motion()
+                    {
+                        self.pane_state(pane_id)
+                            .focus_flash_start
+                            .replace(Instant::now());
+                    }
                     // Also handled by clientpane
                     self.update_title_post_status();
                 }
@@ -1748,7 +1996,19 @@ impl TermWindow {
                     // Also handled by wezterm-client
                     self.update_title_post_status();
                 }
-                MuxNotification::TabTitleChanged { .. } => {
+                MuxNotification::PaneEncodingErrors(pane_id) => {
+                    if self.window_contains_pane(pane_id) {
+                        self.emit_window_event("pane-encoding-errors", Some(pane_id));
+                    }
+                }
+                MuxNotification::SecretRequested(request) => {
+                    if self.window_contains_pane(request.pane_id) {
+                        self.show_askpass(request);
+                    }
+                }
+                MuxNotification::TabTitleChanged { .. }
+                | MuxNotification::TabColorChanged(_)
+                | MuxNotification::TabGroupChanged(_) => {
                     self.update_title_post_status();
                 }
                 MuxNotification::PaneRemoved(pane_id) => {
@@ -1813,6 +2073,49 @@ impl TermWindow {
         window.set_inner_size(width, height);
     }
 
+    /// Returns true if `pane` appears to be reading a password and
+    /// `detect_password_input` is enabled.
+    pub fn pane_has_password_input(&self, pane: &Arc<dyn Pane>) -> bool {
+        if !self.config.detect_password_input {
+            return false;
+        }
+        match pane.get_metadata() {
+            Value::Object(obj) => matches!(
+                obj.get(&Value::String("password_input".to_string())),
+                Some(Value::Bool(true))
+            ),
+            _ => false,
+        }
+    }
+
+    /// Enables secure keyboard entry while this window is focused and its
+    /// active pane is reading a password or showing a `kaku askpass`
+    /// prompt, if so configured.
+    pub fn update_secure_keyboard_entry(&mut self) {
+        self.askpass_overlays
+            .retain(|_, request| !request.is_answered());
+        let enable = self.config.secure_keyboard_entry_for_password_input
+            && self.focused.is_some()
+            && self
+                .get_active_pane_or_overlay()
+                .map(|pane| {
+                    self.pane_has_password_input(&pane)
+                        || self.askpass_overlays.contains_key(&pane.pane_id())
+                })
+                .unwrap_or(false);
+        self.set_secure_keyboard_entry(enable);
+    }
+
+    fn set_secure_keyboard_entry(&mut self, enable: bool) {
+        if self.secure_keyboard_entry == enable {
+            return;
+        }
+        self.secure_keyboard_entry = enable;
+        if let Some(conn) = Connection::get() {
+            conn.set_secure_keyboard_entry(enable);
+        }
+    }
+
     /// Take care to remove our panes from the mux, otherwise
     /// we can leave the mux with no windows but some panes
     /// and it won't believe that we are empty.
@@ -1894,12 +2197,19 @@ impl TermWindow {
             return tab_overlay.pane_id() == pane_id;
         }
 
+        // The siblings of a zoomed pane are not drawn
+        if let Some(zoomed) = tab.get_zoomed_pane() {
+            return zoomed.pane_id() == pane_id;
+        }
+
         tab.contains_pane(pane_id)
     }
 
     fn mux_pane_output_event(&mut self, pane_id: PaneId) {
         metrics::histogram!("mux.pane_output_event.rate").record(1.);
-        if self.is_pane_visible(pane_id) {
+        // While the window is minimized there is nothing to repaint;
+        // we catch up when it is restored
+        if self.window_state.can_paint() && self.is_pane_visible(pane_id) {
             if let Some(ref win) = self.window {
                 win.invalidate();
             }
@@ -1922,6 +2232,7 @@ impl TermWindow {
             MuxNotification::PaneFocused(_)
             | MuxNotification::PaneRemoved(_)
             | MuxNotification::PaneOutput(_)
+            | MuxNotification::PaneEncodingErrors(_)
             | MuxNotification::Alert { .. } => {
                 // Verify window still exists
                 let mux = Mux::get();
@@ -1983,6 +2294,7 @@ impl TermWindow {
                 MuxNotification::PaneOutput(pane_id)
                 | MuxNotification::PaneFocused(pane_id)
                 | MuxNotification::PaneRemoved(pane_id)
+                | MuxNotification::PaneEncodingErrors(pane_id)
                 | MuxNotification::PaneAdded(pane_id) => {
                     if can_resolve_pane_ownership {
                         let mux = dominated_mux.as_ref().expect("checked above");
@@ -2010,8 +2322,20 @@ impl TermWindow {
                         }
                     }
                 }
+                MuxNotification::SecretRequested(request) => {
+                    if can_resolve_pane_ownership {
+                        let mux = dominated_mux.as_ref().expect("checked above");
+                        if let Some((_, window_id, _)) = mux.resolve_pane_id(request.pane_id) {
+                            if window_id != mux_window_id {
+                                return true;
+                            }
+                        }
+                    }
+                }
                 // Tab notifications: check tab ownership
                 MuxNotification::TabResized(tab_id)
+                | MuxNotification::TabColorChanged(tab_id)
+                | MuxNotification::TabGroupChanged(tab_id)
                 | MuxNotification::TabTitleChanged { tab_id, .. } => {
                     if let Some(ref mux) = dominated_mux {
                         if let Some(window_id) = mux.window_containing_tab(*tab_id) {
@@ -2184,6 +2508,9 @@ impl TermWindow {
     /// Decide whether the tab bar should be visible based on tab count,
     /// fullscreen state, and config.
     fn should_show_tab_bar(&self, num_tabs: usize) -> bool {
+        if self.config.use_native_macos_tabs || self.presentation_restore_font_scale.is_some() {
+            return false;
+        }
         let is_full_screen = self.window_state.contains(WindowState::FULL_SCREEN);
         if is_full_screen {
             // Always show tab bar in fullscreen mode to display the right status (time)
@@ -2309,6 +2636,19 @@ impl TermWindow {
         self.input_map = InputMap::new(&config);
         self.leader_is_down = None;
         self.render_state.as_mut().map(|rs| rs.config_changed());
+        if let Some(webgpu) = self.webgpu.clone() {
+            // Re-read the background shader, so that edits to it are
+            // picked up along with the config
+            let config = config.clone();
+            let window = self.window.clone();
+            promise::spawn::spawn(async move {
+                webgpu.load_background_shader(&config).await;
+                if let Some(window) = window {
+                    window.invalidate();
+                }
+            })
+            .detach();
+        }
         let dimensions = self.dimensions;
 
         if let Err(err) = self.fonts.config_changed(&config) {
@@ -2365,11 +2705,14 @@ impl TermWindow {
         );
 
         self.invalidate_modal();
+        self.schedule_idle_lock_check();
         self.emit_window_event("window-config-reloaded", None);
 
         // Sync Dock badge in case bell_dock_badge was toggled.
         // Passing 0 re-evaluates badge state without changing the count.
         front_end().adjust_unread_bell_count(0);
+        // Likewise for dock_progress_bar
+        front_end().update_dock_progress();
     }
 
     fn invalidate_modal(&mut self) {
@@ -2429,6 +2772,15 @@ impl TermWindow {
         self.update_title_impl();
     }
 
+    /// Refreshes the title and the key table indicator after the key
+    /// table stack has been changed
+    fn key_table_changed(&mut self) {
+        self.update_title();
+        if let Some(window) = self.window.as_ref() {
+            window.invalidate();
+        }
+    }
+
     fn window_contains_pane(&mut self, pane_id: PaneId) -> bool {
         let mux = Mux::get();
 
@@ -2440,6 +2792,64 @@ impl TermWindow {
         return window_id == self.mux_window_id;
     }
 
+    /// Raises a toast notification when a command that ran for longer than
+    /// `long_running_command_notification_secs` finishes in a pane that the
+    /// user isn't looking at.  Clicking the notification focuses that pane.
+    fn notify_long_running_command(
+        &mut self,
+        pane_id: PaneId,
+        command: &str,
+        exit_status: Option<i32>,
+        duration: Duration,
+    ) {
+        let threshold = self.config.long_running_command_notification_secs;
+        if threshold == 0
+            || duration < Duration::from_secs(threshold)
+            || self.config.notification_handling == NotificationHandling::NeverShow
+            || front_end().is_presentation_mode()
+            || !self.window_contains_pane(pane_id)
+        {
+            return;
+        }
+
+        let is_active = self
+            .get_active_pane_or_overlay()
+            .is_some_and(|p| p.pane_id() == pane_id);
+        if is_active && self.focused.is_some() {
+            return;
+        }
+
+        let title = match exit_status {
+            Some(0) | None => "Command finished".to_string(),
+            Some(status) => format!("Command failed with status {status}"),
+        };
+        let duration = crate::overlay::command_history::format_duration(
+            duration.as_millis().try_into().unwrap_or(u64::MAX),
+        );
+        // Don't reveal what was run while the session is locked
+        let message = if self.is_session_locked() {
+            format!("Finished after {duration}")
+        } else {
+            format!("{} ({duration})", command.replace('\n', " "))
+        };
+
+        // The kaku://open-tab url activates the pane that owns the tty
+        let url = Mux::get()
+            .get_pane(pane_id)
+            .and_then(|pane| pane.tty_name())
+            .and_then(|tty| url::Url::parse_with_params("kaku://open-tab", &[("tty", tty)]).ok());
+        match url {
+            Some(url) => {
+                wezterm_toast_notification::persistent_toast_notification_with_click_to_open_url(
+                    &title,
+                    &message,
+                    url.as_str(),
+                )
+            }
+            None => wezterm_toast_notification::persistent_toast_notification(&title, &message),
+        }
+    }
+
     fn emit_user_var_event(&mut self, pane_id: PaneId, name: String, value: String) {
         let window_contains_pane = self.window_contains_pane(pane_id);
 
@@ -2545,10 +2955,11 @@ impl TermWindow {
             &self.config,
             &self.left_status,
             &self.right_status,
+            &self.collapsed_tab_groups,
         );
         if new_tab_bar != self.tab_bar {
             self.tab_bar = new_tab_bar;
-            self.invalidate_fancy_tab_bar();
+            self.invalidate_fancy_tab_bar_layout();
             self.invalidate_modal();
             if let Some(window) = self.window.as_ref() {
                 window.invalidate();
@@ -2598,12 +3009,17 @@ impl TermWindow {
             Some(title) => title,
             None => {
                 if let (Some(pos), Some(tab)) = (active_pane, active_tab) {
+                    let flags = format!(
+                        "{}{}",
+                        if pos.is_zoomed { "[Z] " } else { "" },
+                        if pos.is_output_paused { "[P] " } else { "" }
+                    );
                     if num_tabs == 1 {
-                        format!("{}{}", if pos.is_zoomed { "[Z] " } else { "" }, pos.title)
+                        format!("{}{}", flags, pos.title)
                     } else {
                         format!(
                             "{}[{}/{}] {}",
-                            if pos.is_zoomed { "[Z] " } else { "" },
+                            flags,
                             tab.tab_index + 1,
                             num_tabs,
                             pos.title
@@ -2615,6 +3031,13 @@ impl TermWindow {
             }
         };
 
+        // Don't leak what is running through the title while locked
+        let title = if self.is_session_locked() {
+            "Locked".to_string()
+        } else {
+            title
+        };
+
         if let Some(window) = self.window.as_ref() {
             window.set_title(&title);
 
@@ -2643,6 +3066,78 @@ impl TermWindow {
         self.schedule_next_status_update();
     }
 
+    pub fn is_session_locked(&self) -> bool {
+        self.get_modal()
+            .map(|modal| modal.downcast_ref::<SessionLock>().is_some())
+            .unwrap_or(false)
+    }
+
+    pub fn lock_session(&mut self) {
+        if self.is_session_locked() {
+            return;
+        }
+        self.set_modal(Rc::new(SessionLock::new()));
+        self.update_title();
+    }
+
+    pub fn unlock_session(&mut self) {
+        if !self.is_session_locked() {
+            return;
+        }
+        self.cancel_modal();
+        self.last_input = Instant::now();
+        self.update_title();
+        self.schedule_idle_lock_check();
+    }
+
+    /// Arranges to lock the session once `session_lock_idle_minutes`
+    /// have passed without input.  Input doesn't reschedule the timer;
+    /// instead, when it fires it re-arms itself relative to the most
+    /// recent input.
+    fn schedule_idle_lock_check(&mut self) {
+        let minutes = self.config.session_lock_idle_minutes;
+        if minutes == 0 || self.is_session_locked() {
+            self.idle_lock_check = None;
+            return;
+        }
+        let Some(window) = self.window.as_ref() else {
+            return;
+        };
+
+        let Some(target) = self
+            .last_input
+            .checked_add(Duration::from_secs(minutes.saturating_mul(60)))
+        else {
+            return;
+        };
+        if target <= Instant::now() {
+            self.idle_lock_check = None;
+            self.lock_session();
+            return;
+        }
+        if self
+            .idle_lock_check
+            .map(|pending| pending <= target)
+            .unwrap_or(false)
+        {
+            // An earlier check will re-arm itself when it fires
+            return;
+        }
+        self.idle_lock_check = Some(target);
+
+        let window = window.clone();
+        promise::spawn::spawn(async move {
+            Timer::at(target).await;
+            window.notify(TermWindowNotif::Apply(Box::new(move |tw| {
+                if tw.idle_lock_check == Some(target) {
+                    tw.idle_lock_check = None;
+                    tw.schedule_idle_lock_check();
+                }
+            })));
+        })
+        .detach();
+    }
+
     fn schedule_next_status_update(&mut self) {
         if let Some(window) = self.window.as_ref() {
             let now = Instant::now();
@@ -2684,6 +3179,13 @@ impl TermWindow {
                 self.render_metrics.cell_size,
             );
             win.set_text_cursor_position(r);
+
+            let suppress_dead_keys =
+                !self.config.use_dead_keys_in_alt_screen && pos.pane.is_alt_screen_active();
+            if suppress_dead_keys != self.dead_keys_suppressed {
+                self.dead_keys_suppressed = suppress_dead_keys;
+                win.set_dead_keys_suppressed(suppress_dead_keys);
+            }
         }
     }
 
@@ -2919,6 +3421,99 @@ impl TermWindow {
         promise::spawn::spawn(future).detach();
     }
 
+    fn show_pane_process_info(&mut self, pane: &Arc<dyn Pane>) {
+        let target = Arc::clone(pane);
+        let (overlay, future) = start_overlay_pane(self, pane, move |_pane_id, term| {
+            show_pane_process_info(target, term)
+        });
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_command_history(&mut self, pane: &Arc<dyn Pane>, scope: CommandHistoryScope) {
+        let target = Arc::clone(pane);
+        let (overlay, future) = start_overlay_pane(self, pane, move |_pane_id, term| {
+            show_command_history(target, scope, term)
+        });
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    /// Prompts for a secret requested by `kaku askpass` in an overlay over
+    /// the pane that asked, bringing that pane forward so that the prompt
+    /// is seen; the program asking is blocked until it is answered.
+    fn show_askpass(&mut self, request: Arc<SecretRequest>) {
+        let mux = Mux::get();
+        let pane = match mux.get_pane(request.pane_id) {
+            Some(pane) => pane,
+            None => return,
+        };
+        if let Err(err) = mux.focus_pane_and_containing_tab(pane.pane_id()) {
+            log::error!("focusing pane {} for askpass: {err:#}", pane.pane_id());
+        }
+
+        let target = Arc::clone(&request);
+        let (overlay, future) = start_overlay_pane(self, &pane, move |_pane_id, term| {
+            show_askpass(target, term)
+        });
+        self.askpass_overlays.insert(overlay.pane_id(), request);
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+        if let Some(window) = self.window.as_ref() {
+            window.invalidate();
+        }
+    }
+
+    fn show_pane_hex_dump(&mut self, pane: &Arc<dyn Pane>, kib: usize) {
+        let target = Arc::clone(pane);
+        let max_bytes = kib.saturating_mul(1024);
+        let (overlay, future) = start_overlay_pane(self, pane, move |_pane_id, term| {
+            show_pane_hex_dump(target, max_bytes, term)
+        });
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_pane_latency(&mut self, pane: &Arc<dyn Pane>) {
+        let target = Arc::clone(pane);
+        let (overlay, future) = start_overlay_pane(self, pane, move |_pane_id, term| {
+            show_pane_latency(target, term)
+        });
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    /// Updates plugins on a background thread, since fetching can take
+    /// a while, then reports the outcome in a toast
+    fn update_plugins(&mut self) {
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        self.show_toast("Updating plugins...".to_string());
+        std::thread::spawn(move || {
+            let message = match plugin::update_all() {
+                Ok(results) if results.is_empty() => "No plugins are installed".to_string(),
+                Ok(results) => {
+                    let failed: Vec<&str> = results
+                        .iter()
+                        .filter(|(_, result)| result.is_err())
+                        .map(|(url, _)| url.as_str())
+                        .collect();
+                    if failed.is_empty() {
+                        format!("Updated {} plugin(s)", results.len())
+                    } else {
+                        format!("Failed to update: {}", failed.join(", "))
+                    }
+                }
+                Err(err) => format!("Failed to update plugins: {err:#}"),
+            };
+            config::reload();
+            window.notify(TermWindowNotif::Apply(Box::new(move |tw| {
+                tw.show_toast(message);
+            })));
+        });
+    }
+
     fn show_tab_navigator(&mut self) {
         let mux = Mux::get();
         let active_tab_idx = match mux.get_window(self.mux_window_id) {
@@ -3047,7 +3642,43 @@ impl TermWindow {
         &cache.zones
     }
 
-    fn scroll_to_prompt(&mut self, amount: isize, pane: &Arc<dyn Pane>) -> anyhow::Result<()> {
+    /// Returns the Prompt semantic zones at which a command was entered
+    /// that the shell reported as having exited with a non-zero status
+    fn get_failed_prompt_zones(&mut self, pane: &Arc<dyn Pane>) -> &[StableRowIndex] {
+        let cache = self
+            .semantic_zones
+            .entry(pane.pane_id())
+            .or_insert_with(SemanticZoneCache::default);
+
+        let seqno = pane.get_current_seqno();
+        if cache.failed_zones.as_ref().map(|(s, _)| *s) != Some(seqno) {
+            let zones = pane.get_semantic_zones().unwrap_or_else(|_| vec![]);
+            let mut failed: Vec<StableRowIndex> = zones
+                .into_iter()
+                .filter(|zone| zone.semantic_type == wezterm_term::SemanticType::Prompt)
+                .filter(|zone| {
+                    // The status is recorded on the line where the command
+                    // was entered, which may be the last line of a
+                    // multi-line prompt
+                    let (_, lines) = pane.get_lines(zone.start_y..zone.end_y + 1);
+                    lines
+                        .iter()
+                        .any(|line| line.command_succeeded() == Some(false))
+                })
+                .map(|zone| zone.start_y)
+                .collect();
+            failed.dedup();
+            cache.failed_zones = Some((seqno, failed));
+        }
+        &cache.failed_zones.as_ref().unwrap().1
+    }
+
+    fn scroll_to_prompt(
+        &mut self,
+        amount: isize,
+        failed_only: bool,
+        pane: &Arc<dyn Pane>,
+    ) -> anyhow::Result<()> {
         // Exit peek mode when scroll_to_prompt leaves current viewport
         if pane.is_primary_peek() {
             pane.set_primary_peek(false);
@@ -3057,7 +3688,11 @@ impl TermWindow {
             .get_viewport(pane.pane_id())
             .unwrap_or(dims.physical_top);
         let zone = {
-            let zones = self.get_semantic_prompt_zones(&pane);
+            let zones = if failed_only {
+                self.get_failed_prompt_zones(&pane)
+            } else {
+                self.get_semantic_prompt_zones(&pane)
+            };
             let idx = match zones.binary_search(&position) {
                 Ok(idx) | Err(idx) => idx,
             };
@@ -3095,6 +3730,12 @@ impl TermWindow {
         if let Some(event) = &self.current_mouse_event {
             let amount = match event.kind {
                 MouseEventKind::VertWheel(amount) => -amount,
+                MouseEventKind::HorzWheel(amount) => {
+                    let column = self.get_horizontal_scroll(pane.pane_id()) as isize;
+                    let column = column.saturating_sub(amount.into()).max(0);
+                    self.set_horizontal_scroll(pane, column as usize);
+                    return Ok(());
+                }
                 _ => return Ok(()),
             };
             self.scroll_by_line(amount.into(), pane)?;
@@ -3195,15 +3836,15 @@ impl TermWindow {
                     until_unknown: *until_unknown,
                     prevent_fallback: *prevent_fallback,
                 });
-                self.update_title();
+                self.key_table_changed();
             }
             PopKeyTable => {
                 self.key_table_state.pop();
-                self.update_title();
+                self.key_table_changed();
             }
             ClearKeyTableStack => {
                 self.key_table_state.clear_stack();
-                self.update_title();
+                self.key_table_changed();
             }
             Multiple(actions) => {
                 for a in actions {
@@ -3216,6 +3857,23 @@ impl TermWindow {
             SpawnWindow => {
                 self.spawn_command(&SpawnCommand::default(), SpawnWhere::NewWindow);
             }
+            RecreateTabInDomain(domain) => {
+                let mux = Mux::get();
+                if let Some(tab) = mux.get_active_tab_for_window(self.mux_window_id) {
+                    let tab_id = tab.tab_id();
+                    let window_id = self.mux_window_id;
+                    let domain = domain.clone();
+                    promise::spawn::spawn(async move {
+                        if let Err(err) = Mux::get()
+                            .recreate_tab_in_domain(tab_id, domain, window_id)
+                            .await
+                        {
+                            log::error!("Failed to recreate tab {tab_id}: {err:#}");
+                        }
+                    })
+                    .detach();
+                }
+            }
             SpawnCommandInNewTab(spawn) => {
                 self.spawn_command(spawn, SpawnWhere::NewTab);
             }
@@ -3289,6 +3947,18 @@ impl TermWindow {
             PasteFrom(source) => {
                 self.paste_from_clipboard(pane, *source);
             }
+            // With native tabs, each window holds a single tab and
+            // the tabs are the windows in its macOS tab group
+            ActivateTabRelative(n) if self.config.use_native_macos_tabs => {
+                if let Some(w) = window.as_ref() {
+                    w.activate_native_tab_relative(*n, true);
+                }
+            }
+            ActivateTabRelativeNoWrap(n) if self.config.use_native_macos_tabs => {
+                if let Some(w) = window.as_ref() {
+                    w.activate_native_tab_relative(*n, false);
+                }
+            }
             ActivateTabRelative(n) => {
                 self.activate_tab_relative(*n, true)?;
             }
@@ -3304,6 +3974,11 @@ impl TermWindow {
                     self.reset_font_and_window_size(&w)?
                 }
             }
+            ActivateTab(n) if self.config.use_native_macos_tabs => {
+                if let Some(w) = window.as_ref() {
+                    w.activate_native_tab(*n);
+                }
+            }
             ActivateTab(n) => {
                 self.activate_tab(*n)?;
             }
@@ -3350,16 +4025,68 @@ impl TermWindow {
             }
             Nop | DisableDefaultAssignment => {}
             ReloadConfiguration => {}
+            UpdatePlugins => self.update_plugins(),
             MoveTab(n) => self.move_tab(*n)?,
             MoveTabRelative(n) => self.move_tab_relative(*n)?,
             ScrollByPage(n) => self.scroll_by_page(**n, pane)?,
             ScrollByLine(n) => self.scroll_by_line(*n, pane)?,
             ScrollByCurrentEventWheelDelta => self.scroll_by_current_event_wheel_delta(pane)?,
-            ScrollToPrompt(n) => self.scroll_to_prompt(*n, pane)?,
+            ScrollToPrompt(n) => self.scroll_to_prompt(*n, false, pane)?,
+            ScrollToFailedPrompt(n) => self.scroll_to_prompt(*n, true, pane)?,
             ScrollToTop => self.scroll_to_top(pane),
             ScrollToBottom => self.scroll_to_bottom(pane),
+            ShowTabNavigator if self.config.use_native_macos_tabs => {
+                if let Some(w) = window.as_ref() {
+                    w.toggle_native_tab_overview();
+                }
+            }
             ShowTabNavigator => self.show_tab_navigator(),
+            ShowTabOverview => {
+                let modal = crate::termwindow::taboverview::TabOverview::new(self);
+                self.set_modal(Rc::new(modal));
+            }
+            LockSession => self.lock_session(),
             ShowDebugOverlay => self.show_debug_overlay(),
+            ShowPaneProcessInfo => self.show_pane_process_info(pane),
+            ShowCommandHistory => {
+                if let Some(window) = Mux::get().get_window(self.mux_window_id) {
+                    let workspace = window.get_workspace().to_string();
+                    drop(window);
+                    self.show_command_history(pane, CommandHistoryScope::Workspace(workspace));
+                }
+            }
+            SearchCommandHistory => {
+                self.show_command_history(pane, CommandHistoryScope::AllWorkspaces)
+            }
+            ShowPaneHexDump(kib) => self.show_pane_hex_dump(pane, *kib),
+            ShowPaneLatency => self.show_pane_latency(pane),
+            ToggleDebugHud => {
+                self.show_debug_hud = !self.show_debug_hud;
+                if let Some(window) = self.window.as_ref() {
+                    window.invalidate();
+                }
+            }
+            TogglePresentationMode => front_end().toggle_presentation_mode(),
+            SetTabColor(color) => {
+                if let Some(tab) = Mux::get().get_active_tab_for_window(self.mux_window_id) {
+                    tab.set_color(*color);
+                }
+            }
+            SetTabGroup(group) => {
+                if let Some(tab) = Mux::get().get_active_tab_for_window(self.mux_window_id) {
+                    tab.set_group(group.clone());
+                }
+            }
+            ToggleTabGroupCollapsed => {
+                let active_idx = Mux::get()
+                    .get_window(self.mux_window_id)
+                    .map(|window| window.get_active_idx());
+                if let Some(tab_idx) = active_idx {
+                    self.toggle_tab_group_collapsed(tab_idx);
+                }
+            }
+            CloseTabGroup => self.close_tab_group(),
+            MoveTabGroupToNewWindow => self.move_tab_group_to_new_window(),
             ShowLauncher => self.show_launcher(),
             ShowLauncherArgs(args) => {
                 let title = args.title.clone().unwrap_or("Launcher".to_string());
@@ -3462,19 +4189,15 @@ impl TermWindow {
             CompleteSelectionOrOpenLinkAtMouseCursor(dest) => {
                 let text = self.selection_text(pane);
                 if !text.is_empty() {
-                    if self.config.copy_on_select {
-                        self.copy_to_clipboard(*dest, text);
-                        self.show_copy_toast();
-                    }
+                    self.copy_completed_selection(*dest, text);
                 } else {
                     self.do_open_link_at_mouse_cursor(pane);
                 }
             }
             CompleteSelection(dest) => {
                 let text = self.selection_text(pane);
-                if !text.is_empty() && self.config.copy_on_select {
-                    self.copy_to_clipboard(*dest, text);
-                    self.show_copy_toast();
+                if !text.is_empty() {
+                    self.copy_completed_selection(*dest, text);
                 }
             }
             ClearScrollback(erase_mode) => {
@@ -3482,6 +4205,11 @@ impl TermWindow {
                 let window = self.window.as_ref().unwrap();
                 window.invalidate();
             }
+            SetPaneScrollbackLines(lines) => {
+                pane.set_scrollback_lines(Some(*lines));
+                let window = self.window.as_ref().unwrap();
+                window.invalidate();
+            }
             Search(pattern) => {
                 if let Some(pane) = self.get_active_pane_or_overlay() {
                     let mut replace_current = false;
@@ -3619,6 +4347,17 @@ impl TermWindow {
                 };
                 tab.toggle_zoom();
             }
+            TogglePaneOutputPause => {
+                if let Some(paused) = pane.output_paused() {
+                    let was_paused = paused.fetch_xor(true, Ordering::Relaxed);
+                    self.show_toast(if was_paused {
+                        "Output resumed".to_string()
+                    } else {
+                        "Output paused".to_string()
+                    });
+                    self.update_title();
+                }
+            }
             SetPaneZoomState(zoomed) => {
                 let mux = Mux::get();
                 let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
@@ -3711,7 +4450,7 @@ impl TermWindow {
                                 ),
                                 None,
                                 None,
-                                config.default_encoding,
+                                config.default_encoding_for_domain(domain.domain_name()),
                                 window,
                             )
                             .await?;
@@ -3778,6 +4517,14 @@ impl TermWindow {
                 let modal = crate::termwindow::paneselect::PaneSelector::new(self, args);
                 self.set_modal(Rc::new(modal));
             }
+            ShowPaneJumpHints => {
+                let modal = crate::termwindow::paneselect::PaneSelector::new(
+                    self,
+                    &PaneSelectArguments::default(),
+                )
+                .with_tab_hints();
+                self.set_modal(Rc::new(modal));
+            }
             CharSelect(args) => {
                 let modal = crate::termwindow::charselect::CharSelector::new(self, args);
                 self.set_modal(Rc::new(modal));
@@ -3804,6 +4551,57 @@ impl TermWindow {
                     pane.set_encoding(encoding);
                 }
             }
+            SetPaneInputEncoding(encoding) => {
+                if let Some(pane) = self.get_active_pane_no_overlay() {
+                    pane.set_input_encoding(*encoding);
+                }
+            }
+            SetPaneOutputEncoding(encoding) => {
+                if let Some(pane) = self.get_active_pane_no_overlay() {
+                    pane.set_output_encoding(*encoding);
+                }
+            }
+            ReinterpretScrollbackWithEncoding(encoding) => {
+                if let Some(pane) = self.get_active_pane_no_overlay() {
+                    if let Err(err) = pane.reinterpret_output(*encoding) {
+                        log::warn!("ReinterpretScrollbackWithEncoding: {err:#}");
+                        self.show_toast(format!("{err:#}"));
+                    }
+                }
+            }
+            TogglePredictiveEcho => {
+                match wezterm_client::domain::ClientDomain::get_client_inner_for_domain(
+                    pane.domain_id(),
+                ) {
+                    Ok(client) => {
+                        if client.toggle_predictive_echo() {
+                            self.show_toast("Predictive echo enabled".to_string());
+                        } else {
+                            self.show_toast("Predictive echo disabled".to_string());
+                        }
+                    }
+                    Err(_) => self.show_toast(
+                        "Predictive echo is only available for remote mux domains".to_string(),
+                    ),
+                }
+            }
+            TogglePaneOutputLogging(logging) => {
+                let Some(output_log) = pane.output_log() else {
+                    self.show_toast("This pane does not support output logging".to_string());
+                    return Ok(PerformAssignmentResult::Handled);
+                };
+                let path = logging.resolved_path();
+                match output_log.toggle(&path, logging.include_input) {
+                    Ok(true) => {
+                        self.show_toast(format!("Logging pane output to {}", path.display()))
+                    }
+                    Ok(false) => self.show_toast("Stopped logging pane output".to_string()),
+                    Err(err) => {
+                        log::warn!("TogglePaneOutputLogging: {err:#}");
+                        self.show_toast(format!("{err:#}"));
+                    }
+                }
+            }
         };
         Ok(PerformAssignmentResult::Handled)
     }
@@ -3819,11 +4617,16 @@ impl TermWindow {
         if let Some(link) = self.current_highlight.as_ref().cloned() {
             let uri = link.uri().to_string();
             let is_file_uri = uri.starts_with("file://");
-            let resolved_path = if is_file_uri {
-                self.resolve_file_path(pane, &uri)
+            let (resolved_path, location) = if is_file_uri {
+                let (_, line, col) = Self::parse_file_location(&uri);
+                (
+                    self.resolve_file_path(pane, &uri),
+                    line.map(|line| (line, col)),
+                )
             } else {
-                None
+                (None, None)
             };
+            let editor = self.file_location_editor();
 
             let window = GuiWin::new(self);
             let pane = MuxPane(pane.pane_id());
@@ -3834,11 +4637,13 @@ impl TermWindow {
                 pane: MuxPane,
                 link: String,
                 resolved_path: Option<PathBuf>,
+                location: Option<(usize, Option<usize>)>,
+                editor: Option<Vec<String>>,
             ) -> anyhow::Result<()> {
-                let default_click = match lua {
+                let default_click = match &lua {
                     Some(lua) => {
-                        let args = lua.pack_multi((window, pane, link.clone()))?;
-                        config::lua::emit_event(&lua, ("open-uri".to_string(), args))
+                        let args = lua.pack_multi((window.clone(), pane, link.clone()))?;
+                        config::lua::emit_event(lua, ("open-uri".to_string(), args))
                             .await
                             .map_err(|e| {
                                 log::error!("while processing open-uri event: {:#}", e);
@@ -3847,35 +4652,108 @@ impl TermWindow {
                     }
                     None => true,
                 };
-                if default_click {
-                    if let Some(path) = resolved_path {
-                        if path.exists() {
-                            log::info!("Opening file path: {:?}", path);
-                            std::thread::spawn(move || {
-                                let mut cmd = std::process::Command::new("/usr/bin/open");
-                                if path.is_file() {
-                                    cmd.arg("-R");
-                                }
-                                cmd.arg(&path).status().ok();
-                            });
-                        } else {
-                            log::warn!("File does not exist: {:?}", path);
+                if !default_click {
+                    return Ok(());
+                }
+
+                // A file location such as `src/main.rs:12:5` is offered to
+                // the `open-file-location` event first, and otherwise opened
+                // in $EDITOR in a new tab, positioned at the line.
+                if let (Some(path), Some((line, col))) = (&resolved_path, location) {
+                    if path.is_file() {
+                        let default_open = match &lua {
+                            Some(lua) => {
+                                let args = lua.pack_multi((
+                                    window.clone(),
+                                    pane,
+                                    path.to_string_lossy().into_owned(),
+                                    line,
+                                    col,
+                                ))?;
+                                config::lua::emit_event(
+                                    lua,
+                                    ("open-file-location".to_string(), args),
+                                )
+                                .await
+                                .map_err(|e| {
+                                    log::error!(
+                                        "while processing open-file-location event: {:#}",
+                                        e
+                                    );
+                                    e
+                                })?
+                            }
+                            None => true,
+                        };
+                        if !default_open {
+                            return Ok(());
                         }
+                        if let Some(editor) = editor {
+                            let spawn = SpawnCommand {
+                                args: Some(TermWindow::editor_args_for_location(
+                                    editor, path, line,
+                                )),
+                                cwd: path.parent().map(|p| p.to_path_buf()),
+                                ..Default::default()
+                            };
+                            window.window.notify(TermWindowNotif::Apply(Box::new(
+                                move |term_window| {
+                                    term_window.spawn_command(&spawn, SpawnWhere::NewTab);
+                                },
+                            )));
+                            return Ok(());
+                        }
+                    }
+                }
+
+                if let Some(path) = resolved_path {
+                    if path.exists() {
+                        log::info!("Opening file path: {:?}", path);
+                        std::thread::spawn(move || {
+                            let mut cmd = std::process::Command::new("/usr/bin/open");
+                            if path.is_file() {
+                                cmd.arg("-R");
+                            }
+                            cmd.arg(&path).status().ok();
+                        });
                     } else {
-                        log::info!("clicking {}", link);
-                        wezterm_open_url::open_url(&link);
+                        log::warn!("File does not exist: {:?}", path);
                     }
+                } else {
+                    log::info!("clicking {}", link);
+                    wezterm_open_url::open_url(&link);
                 }
                 Ok(())
             }
 
             promise::spawn::spawn(config::with_lua_config_on_main_thread(move |lua| {
-                open_uri(lua, window, pane, uri, resolved_path)
+                open_uri(lua, window, pane, uri, resolved_path, location, editor)
             }))
             .detach();
         }
     }
 
+    /// Returns the terminal editor command line used to open file
+    /// locations, taken from `VISUAL` or `EDITOR` in
+    /// `set_environment_variables`, falling back to our own environment.
+    fn file_location_editor(&self) -> Option<Vec<String>> {
+        ["VISUAL", "EDITOR"].iter().find_map(|name| {
+            let value = self
+                .config
+                .set_environment_variables
+                .get(*name)
+                .cloned()
+                .or_else(|| std::env::var(name).ok())?;
+            shlex::split(&value).filter(|args| !args.is_empty())
+        })
+    }
+
+    fn editor_args_for_location(mut editor: Vec<String>, path: &Path, line: usize) -> Vec<String> {
+        editor.push(format!("+{line}"));
+        editor.push(path.to_string_lossy().into_owned());
+        editor
+    }
+
     fn resolve_file_path(&self, pane: &Arc<dyn Pane>, uri: &str) -> Option<PathBuf> {
         let decoded_uri_path = url::Url::parse(uri)
             .ok()
@@ -3984,6 +4862,72 @@ impl TermWindow {
         }
     }
 
+    /// Collapses or expands the group of the tab at `tab_idx`
+    fn toggle_tab_group_collapsed(&mut self, tab_idx: usize) {
+        let group = match Mux::get().get_window(self.mux_window_id) {
+            Some(window) => window.get_by_idx(tab_idx).and_then(|tab| tab.get_group()),
+            None => None,
+        };
+        if let Some(group) = group {
+            if !self.collapsed_tab_groups.remove(&group) {
+                self.collapsed_tab_groups.insert(group);
+            }
+            self.update_title();
+        }
+    }
+
+    /// Returns the active tab's group along with every tab in this
+    /// window that belongs to it
+    fn active_tab_group(&self) -> Option<(String, Vec<Arc<Tab>>)> {
+        let mux = Mux::get();
+        let window = mux.get_window(self.mux_window_id)?;
+        let group = window.get_active()?.get_group()?;
+        let tabs = window
+            .iter()
+            .filter(|tab| tab.get_group().as_deref() == Some(group.as_str()))
+            .cloned()
+            .collect();
+        Some((group, tabs))
+    }
+
+    fn close_tab_group(&mut self) {
+        let Some((group, tabs)) = self.active_tab_group() else {
+            return;
+        };
+        let mux = Mux::get();
+        let mut busy = 0;
+        for tab in tabs {
+            if tab.can_close_without_prompting(CloseReason::Tab) {
+                mux.remove_tab(tab.tab_id());
+            } else {
+                busy += 1;
+            }
+        }
+        if busy > 0 {
+            self.show_toast(format!(
+                "{busy} tab(s) in group \"{group}\" have running processes and were left open"
+            ));
+        } else {
+            self.collapsed_tab_groups.remove(&group);
+        }
+    }
+
+    fn move_tab_group_to_new_window(&mut self) {
+        let Some((_group, tabs)) = self.active_tab_group() else {
+            return;
+        };
+        let mux = Mux::get();
+        let new_window = mux.new_empty_window(Some(mux.active_workspace()), None);
+        for tab in tabs {
+            if let Some(mut window) = mux.get_window_mut(self.mux_window_id) {
+                window.remove_by_id(tab.tab_id());
+            }
+            if let Err(err) = mux.add_tab_to_window(&tab, *new_window) {
+                log::error!("Failed to move tab {} to new window: {err:#}", tab.tab_id());
+            }
+        }
+    }
+
     fn close_current_tab(&mut self, confirm: bool) {
         let mux = Mux::get();
         let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
@@ -4071,7 +5015,8 @@ impl TermWindow {
     }
 
     pub fn get_viewport(&self, pane_id: PaneId) -> Option<StableRowIndex> {
-        self.pane_state(pane_id).viewport
+        let state = self.pane_state(pane_id);
+        state.viewport.or(state.selection_drag_top)
     }
 
     pub fn set_viewport(
@@ -4109,6 +5054,32 @@ impl TermWindow {
         self.window.as_ref().unwrap().invalidate();
     }
 
+    pub fn get_horizontal_scroll(&self, pane_id: PaneId) -> usize {
+        self.pane_state(pane_id).horizontal_scroll
+    }
+
+    /// Scroll the pane so that `column` is the first one shown,
+    /// limited to what is needed to reveal the end of the widest
+    /// line in the viewport.
+    pub fn set_horizontal_scroll(&mut self, pane: &Arc<dyn Pane>, column: usize) {
+        let dims = pane.get_dimensions();
+        let top = self
+            .get_viewport(pane.pane_id())
+            .unwrap_or(dims.physical_top);
+        let (_, lines) = pane.get_lines(top..top + dims.viewport_rows as StableRowIndex);
+        let widest_line = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let column = column.min(widest_line.saturating_sub(dims.cols));
+
+        let mut state = self.pane_state(pane.pane_id());
+        if column != state.horizontal_scroll {
+            state.horizontal_scroll = column;
+            drop(state);
+            if let Some(win) = self.window.as_ref() {
+                win.invalidate();
+            }
+        }
+    }
+
     fn maybe_scroll_to_bottom_for_input(&mut self, pane: &Arc<dyn Pane>) {
         if self.config.scroll_to_bottom_on_input {
             self.scroll_to_bottom(pane);
@@ -4125,7 +5096,10 @@ impl TermWindow {
     }
 
     fn scroll_to_bottom(&mut self, pane: &Arc<dyn Pane>) {
-        self.pane_state(pane.pane_id()).viewport = None;
+        let mut state = self.pane_state(pane.pane_id());
+        state.viewport = None;
+        state.horizontal_scroll = 0;
+        drop(state);
         pane.set_primary_peek(false);
     }
 
@@ -4190,6 +5164,10 @@ impl TermWindow {
             pane_index: pos.index,
             is_active: pos.is_active,
             is_zoomed: pos.is_zoomed,
+            is_output_paused: pos
+                .pane
+                .output_paused()
+                .is_some_and(|paused| paused.load(Ordering::Relaxed)),
             has_unseen_output: pos.pane.has_unseen_output(),
             left: pos.left,
             top: pos.top,
@@ -4227,6 +5205,8 @@ impl TermWindow {
                         .unwrap_or(false),
                     window_id: self.mux_window_id,
                     tab_title: tab.get_title(),
+                    tab_color: tab.get_color(),
+                    tab_group: tab.get_group(),
                     active_pane: panes
                         .iter()
                         .find(|p| p.is_active)
@@ -4393,6 +5373,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn editor_args_place_line_before_path() {
+        assert_eq!(
+            TermWindow::editor_args_for_location(
+                vec!["nvim".to_string(), "-p".to_string()],
+                std::path::Path::new("/src/main.rs"),
+                12
+            ),
+            vec!["nvim", "-p", "+12", "/src/main.rs"]
+        );
+    }
+
     #[test]
     fn unrelated_user_var_never_triggers_config_reload() {
         assert!(!TermWindow::should_reload_config_for_user_var(
@@ -1,10 +1,11 @@
 use crate::tabbar::TabBarItem;
+use crate::termwindow::resize::ScaleChange;
 use crate::termwindow::{
     GuiWin, MouseCapture, PositionedSplit, ScrollHit, TermWindowNotif, UIItem, UIItemType, TMB,
 };
 use ::window::{
-    MouseButtons as WMB, MouseCursor, MouseEvent, MouseEventKind as WMEK, MousePress, WindowOps,
-    WindowState,
+    Gesture, MouseButtons as WMB, MouseCursor, MouseEvent, MouseEventKind as WMEK, MousePress,
+    WindowOps, WindowState,
 };
 use config::keyassignment::{KeyAssignment, MouseEventTrigger, SpawnTabDomain};
 use config::MouseEventAltScreen;
@@ -178,6 +179,9 @@ impl super::TermWindow {
             WMEK::Release(ref press) => {
                 self.current_mouse_capture = None;
                 self.current_mouse_buttons.retain(|p| p != press);
+                if self.current_mouse_buttons.is_empty() {
+                    self.release_selection_drag();
+                }
                 if press == &MousePress::Left {
                     let was_dragging_window = self.is_window_dragging;
                     self.is_window_dragging = false;
@@ -417,6 +421,28 @@ impl super::TermWindow {
         context.invalidate();
     }
 
+    pub fn gesture_impl(&mut self, gesture: Gesture) {
+        match gesture {
+            Gesture::SwipeBack | Gesture::SwipeForward => {
+                if !self.config.swipe_to_switch_tabs {
+                    return;
+                }
+                let delta = if gesture == Gesture::SwipeBack { -1 } else { 1 };
+                if let Err(err) = self.activate_tab_relative(delta, true) {
+                    log::debug!("activate_tab_relative on swipe failed: {err:#}");
+                }
+            }
+            Gesture::Magnify(amount) => {
+                if !self.config.pinch_to_zoom_font_size {
+                    return;
+                }
+                self.pending_scale_changes
+                    .push_back(ScaleChange::Relative((1.0 + amount).max(0.5)));
+                self.apply_pending_scale_changes();
+            }
+        }
+    }
+
     fn drag_split(
         &mut self,
         mut item: UIItem,
@@ -661,6 +687,9 @@ impl super::TermWindow {
                 TabBarItem::NewTabButton { .. } => {
                     self.do_new_tab_button_click(MousePress::Left);
                 }
+                TabBarItem::TabGroup { tab_idx, .. } => {
+                    self.toggle_tab_group_collapsed(tab_idx);
+                }
                 TabBarItem::None | TabBarItem::LeftStatus | TabBarItem::RightStatus => {
                     let maximized = self
                         .window_state
@@ -715,6 +744,7 @@ impl super::TermWindow {
                 TabBarItem::None
                 | TabBarItem::LeftStatus
                 | TabBarItem::RightStatus
+                | TabBarItem::TabGroup { .. }
                 | TabBarItem::WindowButton(_) => {}
             },
             WMEK::Press(MousePress::Right) => match item {
@@ -727,6 +757,7 @@ impl super::TermWindow {
                 TabBarItem::None
                 | TabBarItem::LeftStatus
                 | TabBarItem::RightStatus
+                | TabBarItem::TabGroup { .. }
                 | TabBarItem::WindowButton(_) => {}
             },
             WMEK::Move => match item {
@@ -745,6 +776,7 @@ impl super::TermWindow {
                 }
                 TabBarItem::WindowButton(_)
                 | TabBarItem::Tab { .. }
+                | TabBarItem::TabGroup { .. }
                 | TabBarItem::NewTabButton { .. } => {}
             },
             WMEK::VertWheel(n) => {
@@ -1003,12 +1035,15 @@ impl super::TermWindow {
             .get_viewport(pane.pane_id())
             .unwrap_or(dims.physical_top)
             + row as StableRowIndex;
+        // The column of the cell under the mouse, which differs from the
+        // column on screen when lines are scrolled horizontally
+        let cell_column = column + self.get_horizontal_scroll(pane.pane_id());
 
         self.pane_state(pane.pane_id())
             .mouse_terminal_coords
             .replace((
                 ClickPosition {
-                    column,
+                    column: cell_column,
                     row,
                     x_pixel_offset,
                     y_pixel_offset,
@@ -1039,7 +1074,7 @@ impl super::TermWindow {
         let mut find_link = FindCurrentLink {
             current: None,
             stable_row,
-            column,
+            column: cell_column,
         };
         pane.with_lines_mut(stable_row..stable_row + 1, &mut find_link);
         let new_highlight = find_link.current;
@@ -1156,8 +1191,10 @@ impl super::TermWindow {
             && !pane.is_alt_screen_active()
             && !pane.is_mouse_grabbed()
             && foreground_bin == Some("less");
-        let bypass_wheel_assignment_in_alt =
-            is_wheel_event && pane.is_alt_screen_active() && !pane.is_mouse_grabbed();
+        let bypass_wheel_assignment_in_alt = is_wheel_event
+            && self.config.alternate_buffer_wheel_sends_arrow_keys
+            && pane.is_alt_screen_active()
+            && !pane.is_mouse_grabbed();
         if less_without_alt {
             let (key, amount) = match event.kind {
                 WMEK::VertWheel(amount) if amount > 0 => (KeyCode::UpArrow, amount as usize),
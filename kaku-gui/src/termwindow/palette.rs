@@ -134,6 +134,27 @@ pub struct UserPaletteEntry {
 }
 impl_lua_conversion_dynamic!(UserPaletteEntry);
 
+/// Returns the commands registered via `wezterm.register_command`.
+/// Must be called on the main thread.
+pub fn registered_user_commands() -> Vec<UserPaletteEntry> {
+    match config::run_immediate_with_lua_config(|lua| {
+        let mut entries: Vec<UserPaletteEntry> = vec![];
+        if let Some(lua) = lua {
+            let result = config::lua::registered_commands(&*lua)?;
+            if !matches!(&result, mlua::Value::Nil) {
+                entries = from_lua_value_dynamic(result)?;
+            }
+        }
+        Ok(entries)
+    }) {
+        Ok(entries) => entries,
+        Err(err) => {
+            log::warn!("register_command: {err:#}");
+            vec![]
+        }
+    }
+}
+
 fn build_commands(term_window: &mut TermWindow) -> Vec<ExpandedCommand> {
     fn is_palette_noise_action(action: &KeyAssignment) -> bool {
         matches!(
@@ -164,6 +185,17 @@ fn build_commands(term_window: &mut TermWindow) -> Vec<ExpandedCommand> {
 
     let mut commands = CommandDef::actions_for_palette_only(&config::configuration());
 
+    for entry in registered_user_commands() {
+        commands.push(ExpandedCommand {
+            brief: entry.brief.into(),
+            doc: entry.doc.unwrap_or_default().into(),
+            action: entry.action,
+            keys: vec![],
+            menubar: &[],
+            icon: entry.icon.map(Into::into),
+        });
+    }
+
     match config::run_immediate_with_lua_config(|lua| {
         let mut entries: Vec<UserPaletteEntry> = vec![];
 
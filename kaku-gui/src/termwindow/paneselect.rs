@@ -1,26 +1,33 @@
+use crate::tabbar::TabBarItem;
 use crate::termwindow::box_model::*;
 use crate::termwindow::modal::Modal;
 use crate::termwindow::render::corners::{
     BOTTOM_LEFT_ROUNDED_CORNER, BOTTOM_RIGHT_ROUNDED_CORNER, TOP_LEFT_ROUNDED_CORNER,
     TOP_RIGHT_ROUNDED_CORNER,
 };
-use crate::termwindow::DimensionContext;
+use crate::termwindow::{DimensionContext, UIItemType};
 use crate::utilsprites::RenderMetrics;
 use crate::TermWindow;
+use ::window::RectF;
 use config::keyassignment::{KeyAssignment, PaneSelectArguments, PaneSelectMode};
 use config::Dimension;
 use mux::Mux;
 use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+use wezterm_font::LoadedFont;
 use wezterm_term::{KeyCode, KeyModifiers, MouseEvent};
 
 pub struct PaneSelector {
     element: RefCell<Option<Vec<ComputedElement>>>,
     labels: RefCell<Vec<String>>,
+    /// Tab indices for the labels that follow the pane labels
+    tab_targets: RefCell<Vec<usize>>,
     selection: RefCell<String>,
     alphabet: String,
     mode: PaneSelectMode,
     was_zoomed: bool,
     show_pane_ids: bool,
+    include_tabs: bool,
 }
 
 impl PaneSelector {
@@ -42,19 +49,69 @@ impl PaneSelector {
         Self {
             element: RefCell::new(None),
             labels: RefCell::new(vec![]),
+            tab_targets: RefCell::new(vec![]),
             selection: RefCell::new(String::new()),
             alphabet,
             mode: args.mode,
             was_zoomed,
             show_pane_ids: args.show_pane_ids,
+            include_tabs: false,
         }
     }
 
+    /// Also labels the tabs in the tab bar, so that a single hint can
+    /// jump to either a pane in the active tab or to another tab
+    pub fn with_tab_hints(mut self) -> Self {
+        self.include_tabs = true;
+        self
+    }
+
+    fn hint_element(term_window: &TermWindow, font: &Rc<LoadedFont>, caption: String) -> Element {
+        Element::new(font, ElementContent::Text(caption))
+            .colors(ElementColors {
+                border: BorderColor::new(
+                    term_window.config.pane_select_bg_color.to_linear().into(),
+                ),
+                bg: term_window.config.pane_select_bg_color.to_linear().into(),
+                text: term_window.config.pane_select_fg_color.to_linear().into(),
+            })
+            .padding(BoxDimension {
+                left: Dimension::Cells(0.25),
+                right: Dimension::Cells(0.25),
+                top: Dimension::Cells(0.),
+                bottom: Dimension::Cells(0.),
+            })
+            .border(BoxDimension::new(Dimension::Pixels(1.)))
+            .border_corners(Some(Corners {
+                top_left: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: TOP_LEFT_ROUNDED_CORNER,
+                },
+                top_right: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: TOP_RIGHT_ROUNDED_CORNER,
+                },
+                bottom_left: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: BOTTOM_LEFT_ROUNDED_CORNER,
+                },
+                bottom_right: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: BOTTOM_RIGHT_ROUNDED_CORNER,
+                },
+            }))
+    }
+
     fn compute(
         term_window: &mut TermWindow,
         alphabet: &str,
         show_pane_ids: bool,
-    ) -> anyhow::Result<(Vec<ComputedElement>, Vec<String>)> {
+        include_tabs: bool,
+    ) -> anyhow::Result<(Vec<ComputedElement>, Vec<String>, Vec<usize>)> {
         let font = term_window
             .fonts
             .pane_select_font()
@@ -71,8 +128,48 @@ impl PaneSelector {
         let top_pixel_y = top_bar_height + padding_top + border.top.get() as f32;
 
         let panes = term_window.get_panes_to_render();
-        let labels =
-            crate::overlay::quickselect::compute_labels_for_alphabet(alphabet, panes.len());
+        let tab_items: Vec<(usize, RectF)> = if include_tabs {
+            term_window
+                .ui_items
+                .iter()
+                .filter_map(|item| match item.item_type {
+                    UIItemType::TabBar(TabBarItem::Tab { tab_idx, .. }) => Some((
+                        tab_idx,
+                        euclid::rect(
+                            item.x as f32,
+                            item.y as f32,
+                            item.width as f32,
+                            item.height as f32,
+                        ),
+                    )),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        let num_panes = panes.len();
+        let labels = crate::overlay::quickselect::compute_labels_for_alphabet(
+            alphabet,
+            num_panes + tab_items.len(),
+        );
+        let dimensions = term_window.dimensions;
+        let layout_context = |bounds: RectF| LayoutContext {
+            height: DimensionContext {
+                dpi: dimensions.dpi as f32,
+                pixel_max: dimensions.pixel_height as f32,
+                pixel_cell: metrics.cell_size.height as f32,
+            },
+            width: DimensionContext {
+                dpi: dimensions.dpi as f32,
+                pixel_max: dimensions.pixel_width as f32,
+                pixel_cell: metrics.cell_size.width as f32,
+            },
+            bounds,
+            metrics: &metrics,
+            gl_state: term_window.render_state.as_ref().unwrap(),
+            zindex: 100,
+        };
 
         let mut elements = vec![];
         for pos in panes {
@@ -81,80 +178,36 @@ impl PaneSelector {
             } else {
                 labels[pos.index].clone()
             };
-            let element = Element::new(&font, ElementContent::Text(caption))
-                .colors(ElementColors {
-                    border: BorderColor::new(
-                        term_window.config.pane_select_bg_color.to_linear().into(),
-                    ),
-                    bg: term_window.config.pane_select_bg_color.to_linear().into(),
-                    text: term_window.config.pane_select_fg_color.to_linear().into(),
-                })
-                .padding(BoxDimension {
-                    left: Dimension::Cells(0.25),
-                    right: Dimension::Cells(0.25),
-                    top: Dimension::Cells(0.),
-                    bottom: Dimension::Cells(0.),
-                })
-                .border(BoxDimension::new(Dimension::Pixels(1.)))
-                .border_corners(Some(Corners {
-                    top_left: SizedPoly {
-                        width: Dimension::Cells(0.25),
-                        height: Dimension::Cells(0.25),
-                        poly: TOP_LEFT_ROUNDED_CORNER,
-                    },
-                    top_right: SizedPoly {
-                        width: Dimension::Cells(0.25),
-                        height: Dimension::Cells(0.25),
-                        poly: TOP_RIGHT_ROUNDED_CORNER,
-                    },
-                    bottom_left: SizedPoly {
-                        width: Dimension::Cells(0.25),
-                        height: Dimension::Cells(0.25),
-                        poly: BOTTOM_LEFT_ROUNDED_CORNER,
-                    },
-                    bottom_right: SizedPoly {
-                        width: Dimension::Cells(0.25),
-                        height: Dimension::Cells(0.25),
-                        poly: BOTTOM_RIGHT_ROUNDED_CORNER,
-                    },
-                }));
+            let element = Self::hint_element(term_window, &font, caption);
 
-            let dimensions = term_window.dimensions;
             let pane_dims = pos.pane.get_dimensions();
 
             let computed = term_window.compute_element(
-                &LayoutContext {
-                    height: DimensionContext {
-                        dpi: dimensions.dpi as f32,
-                        pixel_max: dimensions.pixel_height as f32,
-                        pixel_cell: metrics.cell_size.height as f32,
-                    },
-                    width: DimensionContext {
-                        dpi: dimensions.dpi as f32,
-                        pixel_max: dimensions.pixel_width as f32,
-                        pixel_cell: metrics.cell_size.width as f32,
-                    },
-                    bounds: euclid::rect(
-                        padding_left
-                            + ((pos.left as f32 + pane_dims.cols as f32 / 2.)
-                                * term_window.render_metrics.cell_size.width as f32),
-                        top_pixel_y
-                            + ((pos.top as f32 + pane_dims.viewport_rows as f32 / 2.)
-                                * term_window.render_metrics.cell_size.height as f32),
-                        pane_dims.cols as f32 * term_window.render_metrics.cell_size.width as f32,
-                        pane_dims.viewport_rows as f32
-                            * term_window.render_metrics.cell_size.height as f32,
-                    ),
-                    metrics: &metrics,
-                    gl_state: term_window.render_state.as_ref().unwrap(),
-                    zindex: 100,
-                },
+                &layout_context(euclid::rect(
+                    padding_left
+                        + ((pos.left as f32 + pane_dims.cols as f32 / 2.)
+                            * term_window.render_metrics.cell_size.width as f32),
+                    top_pixel_y
+                        + ((pos.top as f32 + pane_dims.viewport_rows as f32 / 2.)
+                            * term_window.render_metrics.cell_size.height as f32),
+                    pane_dims.cols as f32 * term_window.render_metrics.cell_size.width as f32,
+                    pane_dims.viewport_rows as f32
+                        * term_window.render_metrics.cell_size.height as f32,
+                )),
                 &element,
             )?;
             elements.push(computed);
         }
 
-        Ok((elements, labels))
+        let mut tab_targets = vec![];
+        for (i, (tab_idx, bounds)) in tab_items.into_iter().enumerate() {
+            let element = Self::hint_element(term_window, &font, labels[num_panes + i].clone());
+            let computed = term_window.compute_element(&layout_context(bounds), &element)?;
+            elements.push(computed);
+            tab_targets.push(tab_idx);
+        }
+
+        Ok((elements, labels, tab_targets))
     }
 
     fn perform_selection(
@@ -254,9 +307,16 @@ impl Modal for PaneSelector {
                 selection.push(c);
 
                 // and if we have a complete match, activate that pane
-                if let Some(pane_index) = self.labels.borrow().iter().position(|s| s == &*selection)
-                {
-                    self.perform_selection(pane_index, term_window)?;
+                let position = self.labels.borrow().iter().position(|s| s == &*selection);
+                if let Some(idx) = position {
+                    let num_panes = self.labels.borrow().len() - self.tab_targets.borrow().len();
+                    if idx < num_panes {
+                        self.perform_selection(idx, term_window)?;
+                    } else {
+                        let tab_idx = self.tab_targets.borrow()[idx - num_panes];
+                        term_window.cancel_modal();
+                        term_window.activate_tab(tab_idx as isize)?;
+                    }
                     return Ok(true);
                 }
             }
@@ -280,9 +340,15 @@ impl Modal for PaneSelector {
         term_window: &mut TermWindow,
     ) -> anyhow::Result<Ref<'_, [ComputedElement]>> {
         if self.element.borrow().is_none() {
-            let (element, labels) = Self::compute(term_window, &self.alphabet, self.show_pane_ids)?;
+            let (element, labels, tab_targets) = Self::compute(
+                term_window,
+                &self.alphabet,
+                self.show_pane_ids,
+                self.include_tabs,
+            )?;
             self.element.borrow_mut().replace(element);
             *self.labels.borrow_mut() = labels;
+            *self.tab_targets.borrow_mut() = tab_targets;
         }
         Ok(Ref::map(self.element.borrow(), |v| {
             v.as_ref().unwrap().as_slice()
@@ -0,0 +1,111 @@
+use crate::termwindow::render::text_box::TextBoxCorner;
+use std::time::{Duration, Instant};
+use window::color::LinearRgba;
+
+/// How often the HUD is refreshed while it is visible
+const HUD_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+fn format_rate(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B/s")
+    } else {
+        format!("{value:.1} {}/s", UNITS[unit])
+    }
+}
+
+impl crate::TermWindow {
+    fn debug_hud_lines(&self) -> Vec<String> {
+        let stats = crate::stats::hud_stats();
+        let mut lines = vec![format!(
+            "fps {:.1}  last frame {:.2?}",
+            self.fps, self.last_frame_duration
+        )];
+
+        match stats.frame_time {
+            Some((p50, p75, p95)) => {
+                lines.push(format!("frame p50 {p50:.2?}  p75 {p75:.2?}  p95 {p95:.2?}"))
+            }
+            None => lines.push("frame time: no samples".to_string()),
+        }
+
+        lines.push(format!("front end {:?}", self.front_end));
+        if let Some(info) = &self.opengl_info {
+            lines.push(info.clone());
+        }
+
+        if let Some(gl_state) = self.render_state.as_ref() {
+            lines.push(format!("quads {}", gl_state.quad_count()));
+        }
+
+        match stats.glyph_cache_hit_rate {
+            Some(rate) => lines.push(format!("glyph cache hit rate {:.1}%", rate * 100.0)),
+            None => lines.push("glyph cache hit rate -".to_string()),
+        }
+
+        let mux = mux::Mux::get();
+        for pos in self.get_panes_to_render() {
+            let pane_id = pos.pane.pane_id();
+            let mut line = format!(
+                "pane {pane_id} read {}",
+                format_rate(crate::stats::pane_read_rate(pane_id))
+            );
+            if let Some(depth) = mux.pane_output_queue_depth(pane_id) {
+                line.push_str(&format!(
+                    "  queue {}/{} ({} KiB)",
+                    depth.chunks,
+                    depth.capacity,
+                    depth.bytes / 1024
+                ));
+            }
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    /// Render the performance HUD in the top-right corner of the window
+    pub fn paint_debug_hud(&mut self) -> anyhow::Result<()> {
+        if !self.show_debug_hud {
+            return Ok(());
+        }
+
+        let lines = self.debug_hud_lines();
+        self.paint_text_box(
+            lines,
+            TextBoxCorner::TopRight,
+            LinearRgba(0.0, 0.0, 0.0, 0.75),
+            LinearRgba(0.85, 1.0, 0.85, 1.0),
+        )?;
+
+        // Keep the numbers live even when nothing else is changing
+        let next = Instant::now() + HUD_REFRESH_INTERVAL;
+        let mut anim = self.has_animation.borrow_mut();
+        match *anim {
+            Some(existing) if existing <= next => {}
+            _ => {
+                *anim = Some(next);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_formatting() {
+        assert_eq!(format_rate(0), "0 B/s");
+        assert_eq!(format_rate(2048), "2.0 KiB/s");
+        assert_eq!(format_rate(5 * 1024 * 1024), "5.0 MiB/s");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::colorease::ColorEaseUniform;
-use crate::termwindow::webgpu::ShaderUniform;
+use crate::termwindow::webgpu::{BackgroundShaderUniform, ShaderUniform};
 use crate::termwindow::RenderFrame;
 use crate::uniforms::UniformBuilder;
 use ::window::glium;
@@ -69,6 +69,8 @@ impl crate::TermWindow {
             });
 
         let mut cleared = false;
+        let mut drew_background_shader = false;
+        let mut animated_background = false;
         let foreground_text_hsb = self.config.foreground_text_hsb;
         let foreground_text_hsb = [
             foreground_text_hsb.hue,
@@ -139,6 +141,50 @@ impl crate::TermWindow {
                 }
 
                 vb.next_index();
+
+                // The backgrounds are the first thing drawn in the lowest
+                // layer; lay the custom background shader over them,
+                // beneath everything else.
+                if idx == 0 && !drew_background_shader {
+                    drew_background_shader = true;
+                    if let Some(shader) = webgpu.background_shader.borrow().as_ref() {
+                        let uniforms = shader.create_uniform(
+                            &webgpu.device,
+                            BackgroundShaderUniform {
+                                resolution: [
+                                    self.dimensions.pixel_width as f32,
+                                    self.dimensions.pixel_height as f32,
+                                ],
+                                time: milliseconds as f32 / 1000.,
+                                _padding: 0.,
+                            },
+                        );
+                        let mut render_pass =
+                            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                                label: Some("Background Shader Pass"),
+                                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                                    view: &view,
+                                    resolve_target: None,
+                                    ops: wgpu::Operations {
+                                        load: if cleared {
+                                            wgpu::LoadOp::Load
+                                        } else {
+                                            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+                                        },
+                                        store: wgpu::StoreOp::Store,
+                                    },
+                                })],
+                                depth_stencil_attachment: None,
+                                occlusion_query_set: None,
+                                timestamp_writes: None,
+                            });
+                        cleared = true;
+                        render_pass.set_pipeline(&shader.pipeline);
+                        render_pass.set_bind_group(0, &uniforms, &[]);
+                        render_pass.draw(0..3, 0..1);
+                        animated_background = shader.animated;
+                    }
+                }
             }
         }
 
@@ -146,6 +192,18 @@ impl crate::TermWindow {
         webgpu.queue.submit(std::iter::once(encoder.finish()));
         output.present();
 
+        if animated_background {
+            let next = std::time::Instant::now()
+                + std::time::Duration::from_millis(1000 / self.config.animation_fps.max(1) as u64);
+            let mut anim = self.has_animation.borrow_mut();
+            match *anim {
+                Some(existing) if existing <= next => {}
+                _ => {
+                    *anim = Some(next);
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -7,9 +7,11 @@ use crate::termwindow::render::window_buttons::window_button_element;
 use crate::termwindow::{UIItem, UIItemType};
 use crate::utilsprites::RenderMetrics;
 use config::{Dimension, DimensionContext, TabBarColors};
+use std::collections::HashSet;
 use std::rc::Rc;
 use wezterm_font::LoadedFont;
 use wezterm_term::color::{ColorAttribute, ColorPalette};
+use wezterm_term::Line;
 use window::{IntegratedTitleButtonAlignment, IntegratedTitleButtonStyle};
 
 const X_BUTTON: &[Poly] = &[
@@ -50,9 +52,38 @@ const PLUS_BUTTON: &[Poly] = &[
     },
 ];
 
+/// The inputs that determine the layout of a tab in the fancy tab bar
+#[derive(Debug, PartialEq)]
+struct TabLayoutKey {
+    item: TabBarItem,
+    title: Line,
+    max_width: f32,
+    close_button: bool,
+    /// The title colors are resolved against the palette of the active
+    /// pane, which escape sequences such as OSC 4 can change at any time
+    palette: ColorPalette,
+}
+
+/// A tab that was laid out by a prior call to `build_fancy_tab_bar`
+pub struct CachedTab {
+    key: TabLayoutKey,
+    computed: ComputedElement,
+}
+
 impl crate::TermWindow {
+    /// Discard the fancy tab bar, along with the layout of every tab.
+    /// This is needed when the fonts, colors, dimensions or glyph atlas
+    /// change underneath it.
     pub fn invalidate_fancy_tab_bar(&mut self) {
         self.fancy_tab_bar.take();
+        self.fancy_tab_cache.borrow_mut().clear();
+    }
+
+    /// Discard the fancy tab bar, but keep the layout of the individual
+    /// tabs so that only those whose title or state changed are shaped
+    /// again when it is next built.
+    pub fn invalidate_fancy_tab_bar_layout(&mut self) {
+        self.fancy_tab_bar.take();
     }
 
     pub fn build_fancy_tab_bar(&self, palette: &ColorPalette) -> anyhow::Result<ComputedElement> {
@@ -132,6 +163,23 @@ impl crate::TermWindow {
                     })
                     .border(BoxDimension::new(Dimension::Pixels(0.)))
                     .colors(bar_colors.clone()),
+                TabBarItem::TabGroup { .. } => element
+                    .item_type(UIItemType::TabBar(item.item))
+                    .line_height(Some(1.75))
+                    .margin(BoxDimension {
+                        left: Dimension::Cells(0.),
+                        right: Dimension::Cells(0.),
+                        top: Dimension::Cells(0.0),
+                        bottom: Dimension::Cells(0.),
+                    })
+                    .padding(BoxDimension {
+                        left: Dimension::Cells(0.5),
+                        right: Dimension::Cells(0.),
+                        top: Dimension::Cells(0.),
+                        bottom: Dimension::Cells(0.),
+                    })
+                    .border(BoxDimension::new(Dimension::Pixels(0.)))
+                    .colors(bar_colors.clone()),
                 TabBarItem::NewTabButton => Element::new(
                     &font,
                     ElementContent::Poly {
@@ -310,6 +358,36 @@ impl crate::TermWindow {
             - (1.5 * metrics.cell_size.width as f32))
             .max(0.);
 
+        let is_fullscreen = self.window_state.contains(window::WindowState::FULL_SCREEN);
+        let border = self.get_os_border();
+        // In fullscreen, start from 0 since left_padding already handles alignment
+        let bounds_left = if is_fullscreen {
+            0.0
+        } else {
+            border.left.get() as f32
+        };
+        let bounds_width = if is_fullscreen {
+            self.dimensions.pixel_width as f32
+        } else {
+            self.dimensions.pixel_width as f32 - (border.left + border.right).get() as f32
+        };
+        let layout_context = LayoutContext {
+            height: DimensionContext {
+                dpi: self.dimensions.dpi as f32,
+                pixel_max: self.dimensions.pixel_height as f32,
+                pixel_cell: metrics.cell_size.height as f32,
+            },
+            width: DimensionContext {
+                dpi: self.dimensions.dpi as f32,
+                pixel_max: self.dimensions.pixel_width as f32,
+                pixel_cell: metrics.cell_size.width as f32,
+            },
+            bounds: euclid::rect(bounds_left, 0., bounds_width, tab_bar_height),
+            metrics: &metrics,
+            gl_state: self.render_state.as_ref().unwrap(),
+            zindex: 10,
+        };
+
         // Reserve space for the native titlebar buttons
         if self
             .config
@@ -328,6 +406,15 @@ impl crate::TermWindow {
             );
         }
 
+        // Tabs are laid out on their own, relative to the origin, so that
+        // the result can be reused until that particular tab changes.
+        let tab_context = LayoutContext {
+            bounds: euclid::rect(0., 0., bounds_width, tab_bar_height),
+            zindex: 0,
+            ..layout_context
+        };
+        let mut seen_tabs = HashSet::new();
+
         for item in items {
             match item.item {
                 TabBarItem::LeftStatus => left_status.push(item_to_elem(item)),
@@ -342,24 +429,56 @@ impl crate::TermWindow {
                     }
                 }
                 TabBarItem::Tab { tab_idx, active } => {
-                    let mut elem = item_to_elem(item);
-                    elem.max_width = Some(Dimension::Pixels(max_tab_width));
-                    elem.content = match elem.content {
-                        ElementContent::Text(_) => unreachable!(),
-                        ElementContent::Poly { .. } => unreachable!(),
-                        ElementContent::Children(mut kids) => {
-                            if self.config.show_close_tab_button_in_tabs {
-                                kids.push(make_x_button(&font, &metrics, &colors, tab_idx, active));
-                            }
-                            ElementContent::Children(kids)
+                    seen_tabs.insert(tab_idx);
+                    let key = TabLayoutKey {
+                        item: item.item,
+                        title: item.title.clone(),
+                        max_width: max_tab_width,
+                        close_button: self.config.show_close_tab_button_in_tabs,
+                        palette: palette.clone(),
+                    };
+                    let mut tab_cache = self.fancy_tab_cache.borrow_mut();
+                    let computed = match tab_cache.get(&tab_idx) {
+                        Some(cached) if cached.key == key => cached.computed.clone(),
+                        _ => {
+                            let mut elem = item_to_elem(item);
+                            elem.max_width = Some(Dimension::Pixels(max_tab_width));
+                            elem.content = match elem.content {
+                                ElementContent::Children(mut kids) => {
+                                    if key.close_button {
+                                        kids.push(make_x_button(
+                                            &font, &metrics, &colors, tab_idx, active,
+                                        ));
+                                    }
+                                    ElementContent::Children(kids)
+                                }
+                                _ => unreachable!(),
+                            };
+                            let computed = self.compute_element(&tab_context, &elem)?;
+                            tab_cache.insert(
+                                tab_idx,
+                                CachedTab {
+                                    key,
+                                    computed: computed.clone(),
+                                },
+                            );
+                            computed
                         }
                     };
-                    left_eles.push(elem);
+                    left_eles.push(
+                        Element::new(&font, ElementContent::Computed(Box::new(computed)))
+                            .vertical_align(VerticalAlign::Bottom),
+                    );
                 }
                 _ => left_eles.push(item_to_elem(item)),
             }
         }
 
+        // Forget tabs that have since been closed
+        self.fancy_tab_cache
+            .borrow_mut()
+            .retain(|tab_idx, _| seen_tabs.contains(tab_idx));
+
         let mut children = vec![];
 
         if !left_status.is_empty() {
@@ -378,7 +497,6 @@ impl crate::TermWindow {
                 || self.config.integrated_title_button_style
                     == IntegratedTitleButtonStyle::MacOsNative);
 
-        let is_fullscreen = self.window_state.contains(window::WindowState::FULL_SCREEN);
         // In fullscreen, macOS native buttons are hidden, so no extra space needed
         let left_padding = if is_fullscreen {
             // Minimal padding in fullscreen - tabs start near the left edge
@@ -422,38 +540,7 @@ impl crate::TermWindow {
             .vertical_align(VerticalAlign::Bottom)
             .colors(bar_colors);
 
-        let border = self.get_os_border();
-        // In fullscreen, start from 0 since left_padding already handles alignment
-        let bounds_left = if is_fullscreen {
-            0.0
-        } else {
-            border.left.get() as f32
-        };
-        let bounds_width = if is_fullscreen {
-            self.dimensions.pixel_width as f32
-        } else {
-            self.dimensions.pixel_width as f32 - (border.left + border.right).get() as f32
-        };
-
-        let mut computed = self.compute_element(
-            &LayoutContext {
-                height: DimensionContext {
-                    dpi: self.dimensions.dpi as f32,
-                    pixel_max: self.dimensions.pixel_height as f32,
-                    pixel_cell: metrics.cell_size.height as f32,
-                },
-                width: DimensionContext {
-                    dpi: self.dimensions.dpi as f32,
-                    pixel_max: self.dimensions.pixel_width as f32,
-                    pixel_cell: metrics.cell_size.width as f32,
-                },
-                bounds: euclid::rect(bounds_left, 0., bounds_width, tab_bar_height),
-                metrics: &metrics,
-                gl_state: self.render_state.as_ref().unwrap(),
-                zindex: 10,
-            },
-            &tabs,
-        )?;
+        let mut computed = self.compute_element(&layout_context, &tabs)?;
 
         computed.translate(euclid::vec2(
             0.,
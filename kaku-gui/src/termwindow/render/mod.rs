@@ -13,8 +13,8 @@ use ::window::bitmaps::{TextureCoord, TextureRect, TextureSize};
 use ::window::{DeadKeyStatus, PointF, RectF, SizeF, WindowOps};
 use anyhow::{anyhow, Context};
 use config::{
-    BoldBrightening, ConfigHandle, DimensionContext, HorizontalWindowContentAlignment, TextStyle,
-    VerticalWindowContentAlignment, VisualBellTarget,
+    BoldBrightening, ConfigHandle, DimensionContext, EasingFunction,
+    HorizontalWindowContentAlignment, TextStyle, VerticalWindowContentAlignment, VisualBellTarget,
 };
 use euclid::num::Zero;
 use mux::pane::{Pane, PaneId};
@@ -36,6 +36,7 @@ use window::color::LinearRgba;
 
 pub mod borders;
 pub mod corners;
+pub mod debug_hud;
 pub mod draw;
 pub mod fancy_tab_bar;
 pub mod paint;
@@ -43,6 +44,7 @@ pub mod pane;
 pub mod screen_line;
 pub mod split;
 pub mod tab_bar;
+pub mod text_box;
 pub mod window_buttons;
 
 /// The data that we associate with a line; we use this to cache it shape hash
@@ -61,7 +63,12 @@ pub struct LineQuadCacheKey {
     /// Only set if cursor.y == stable_row
     pub composing: Option<String>,
     pub selection: Range<usize>,
+    /// The selected columns on the lines above and below; these
+    /// decide which corners of the selection are rounded
+    pub selection_above: Range<usize>,
+    pub selection_below: Range<usize>,
     pub shape_hash: [u8; 16],
+    pub horizontal_scroll: usize,
     pub top_pixel_y: NotNan<f32>,
     pub left_pixel_x: NotNan<f32>,
     pub phys_line_idx: usize,
@@ -133,7 +140,16 @@ pub struct RenderScreenLineParams<'a> {
     pub pixel_width: f32,
     pub stable_line_idx: Option<StableRowIndex>,
     pub line: &'a Line,
+    /// The number of columns scrolled off the left edge of the pane.
+    /// The whole of `line` is shaped, and then drawn shifted left by
+    /// this many cells; `selection` and `cursor` are already shifted.
+    pub horizontal_scroll: usize,
     pub selection: Range<usize>,
+    /// The selected columns on the lines above and below; the corners
+    /// of `selection` that they don't cover are rounded per
+    /// `selection_corner_radius`
+    pub selection_above: Range<usize>,
+    pub selection_below: Range<usize>,
     pub cursor: &'a StableCursorPosition,
     pub palette: &'a ColorPalette,
     pub dims: &'a RenderableDimensions,
@@ -235,31 +251,55 @@ impl crate::TermWindow {
         config: &ConfigHandle,
         target: VisualBellTarget,
     ) -> Option<f32> {
+        if config.visual_bell.target != target {
+            return None;
+        }
         let mut per_pane = self.pane_state(pane.pane_id());
-        if let Some(ringing) = per_pane.bell_start {
-            if config.visual_bell.target == target {
-                let mut color_ease = ColorEase::new(
-                    config.visual_bell.fade_in_duration_ms,
-                    config.visual_bell.fade_in_function,
-                    config.visual_bell.fade_out_duration_ms,
-                    config.visual_bell.fade_out_function,
-                    Some(ringing),
-                );
-
-                let intensity = color_ease.intensity_one_shot();
-
-                match intensity {
-                    None => {
-                        per_pane.bell_start.take();
-                    }
-                    Some((intensity, next)) => {
-                        self.update_next_frame_time(Some(next));
-                        return Some(intensity);
-                    }
-                }
+        self.one_shot_intensity(
+            &mut per_pane.bell_start,
+            ColorEase::new(
+                config.visual_bell.fade_in_duration_ms,
+                config.visual_bell.fade_in_function,
+                config.visual_bell.fade_out_duration_ms,
+                config.visual_bell.fade_out_function,
+                None,
+            ),
+        )
+    }
+
+    fn get_intensity_if_focus_flashing(
+        &self,
+        pane: &Arc<dyn Pane>,
+        config: &ConfigHandle,
+    ) -> Option<f32> {
+        let mut per_pane = self.pane_state(pane.pane_id());
+        self.one_shot_intensity(
+            &mut per_pane.focus_flash_start,
+            ColorEase::new(
+                0,
+                EasingFunction::Constant,
+                config.pane_focus_flash_duration_ms,
+                config.pane_focus_flash_function,
+                None,
+            ),
+        )
+    }
+
+    /// Evaluates `ease` as a one-shot animation that began at `start`,
+    /// scheduling the next frame while it runs and clearing `start`
+    /// once it has completed
+    fn one_shot_intensity(&self, start: &mut Option<Instant>, mut ease: ColorEase) -> Option<f32> {
+        ease.update_start((*start)?);
+        match ease.intensity_one_shot() {
+            None => {
+                start.take();
+                None
+            }
+            Some((intensity, next)) => {
+                self.update_next_frame_time(Some(next));
+                Some(intensity)
             }
         }
-        None
     }
 
     pub fn filled_rectangle<'a>(
@@ -467,6 +507,7 @@ impl crate::TermWindow {
             .cached_image(image.image_data(), Some(padding), self.allow_images)
             .context("cached_image")?;
         self.update_next_frame_time(next_due);
+        sprite.mark_used();
         let width = sprite.coords.size.width;
         let height = sprite.coords.size.height;
 
@@ -881,6 +922,23 @@ impl crate::TermWindow {
         }
     }
 
+    /// Make room in the texture atlas by evicting its least recently
+    /// drawn page, rather than recreating it from scratch.
+    /// Returns false if there was nothing that could be evicted.
+    pub fn evict_texture_atlas_page(&mut self) -> bool {
+        let evicted = match self.render_state.as_ref() {
+            Some(render_state) => render_state.glyph_cache.borrow_mut().evict_lru_page(),
+            None => false,
+        };
+        if evicted {
+            // Shaped lines hold on to glyphs that may have been on that page
+            self.shape_generation += 1;
+            self.shape_cache.borrow_mut().clear();
+            self.line_to_ele_shape_cache.borrow_mut().clear();
+        }
+        evicted
+    }
+
     pub fn recreate_texture_atlas(&mut self, size: Option<usize>) -> anyhow::Result<()> {
         self.shape_generation += 1;
         self.shape_cache.borrow_mut().clear();
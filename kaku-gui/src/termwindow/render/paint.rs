@@ -9,7 +9,7 @@ use crate::utilsprites::RenderMetrics;
 use ::window::bitmaps::atlas::OutOfTextureSpace;
 use ::window::WindowOps;
 use anyhow::Context;
-use config::Dimension;
+use config::{Dimension, FrontEndSelection};
 use smol::Timer;
 use std::collections::HashSet;
 use std::time::{Duration, Instant};
@@ -31,8 +31,17 @@ impl crate::TermWindow {
         // If nothing on screen needs animating, then we can avoid
         // invalidating as frequently
         *self.has_animation.borrow_mut() = None;
-        // Start with the assumption that we should allow images to render
-        self.allow_images = AllowImage::Yes;
+        // Start with the assumption that we should allow images to render.
+        // The software renderer rasterizes every texel on the CPU, so it
+        // draws images at half resolution to keep frames affordable.
+        self.allow_images = if self.front_end == FrontEndSelection::Software {
+            AllowImage::Scale(2)
+        } else {
+            AllowImage::Yes
+        };
+
+        self.update_secure_keyboard_entry();
+        self.update_accessible_text();
 
         let start = Instant::now();
 
@@ -46,7 +55,16 @@ impl crate::TermWindow {
             }
         }
 
-        'pass: for pass in 0.. {
+        self.render_state
+            .as_ref()
+            .unwrap()
+            .glyph_cache
+            .borrow_mut()
+            .atlas
+            .begin_frame();
+
+        let mut recreated_atlas = false;
+        'pass: loop {
             match self.paint_pass() {
                 Ok(_) => match self.render_state.as_mut().unwrap().allocated_more_quads() {
                     Ok(allocated) => {
@@ -67,7 +85,19 @@ impl crate::TermWindow {
                         current_size,
                     }) = err.root_cause().downcast_ref::<OutOfTextureSpace>()
                     {
-                        let result = if pass == 0 {
+                        // Prefer to make room by evicting glyphs that haven't
+                        // been drawn recently, so that we don't have to
+                        // rasterize everything again
+                        if self.evict_texture_atlas_page() {
+                            log::trace!("evicted a texture atlas page");
+                            self.invalidate_fancy_tab_bar();
+                            self.invalidate_modal();
+                            continue;
+                        }
+
+                        let clearing = !recreated_atlas;
+                        recreated_atlas = true;
+                        let result = if clearing {
                             // Let's try clearing out the atlas and trying again
                             // self.clear_texture_atlas()
                             log::trace!("recreate_texture_atlas");
@@ -88,7 +118,7 @@ impl crate::TermWindow {
                                 AllowImage::No | _ => {
                                     log::error!(
                                         "Failed to {} texture: {}",
-                                        if pass == 0 { "clear" } else { "resize" },
+                                        if clearing { "clear" } else { "resize" },
                                         err
                                     );
                                     break 'pass;
@@ -486,6 +516,7 @@ impl crate::TermWindow {
         if self.show_tab_bar {
             self.paint_tab_bar(&mut layers).context("paint_tab_bar")?;
             self.paint_tab_bell_indicators(&mut layers)?;
+            self.paint_tab_progress_bars(&mut layers)?;
         }
 
         self.paint_window_borders(&mut layers)
@@ -493,6 +524,9 @@ impl crate::TermWindow {
         drop(layers);
         self.paint_modal().context("paint_modal")?;
         self.paint_toast().context("paint_toast")?;
+        self.paint_key_table_indicator()
+            .context("paint_key_table_indicator")?;
+        self.paint_debug_hud().context("paint_debug_hud")?;
 
         Ok(())
     }
@@ -604,6 +638,70 @@ impl crate::TermWindow {
         Ok(())
     }
 
+    /// Draw a thin bar along the bottom of tabs whose active pane reports
+    /// progress: green for a percentage, red for an error and a dimmed,
+    /// full width bar when the progress is indeterminate.
+    fn paint_tab_progress_bars(
+        &mut self,
+        layers: &mut crate::quad::TripleLayerQuadAllocator,
+    ) -> anyhow::Result<()> {
+        use crate::tabbar::TabBarItem;
+        use wezterm_term::Progress;
+
+        if !self.config.tab_progress_bar {
+            return Ok(());
+        }
+        let mux = mux::Mux::get();
+        let mux_window = match mux.get_window(self.mux_window_id) {
+            Some(w) => w,
+            None => return Ok(()),
+        };
+        let progress: Vec<Progress> = mux_window
+            .iter()
+            .map(|tab| {
+                tab.get_active_pane()
+                    .map(|pane| pane.get_progress())
+                    .unwrap_or_default()
+            })
+            .collect();
+        drop(mux_window);
+        if progress.iter().all(|p| *p == Progress::None) {
+            return Ok(());
+        }
+
+        let palette = self.palette();
+        let green = palette.colors.0[2].to_linear();
+        let red = palette.colors.0[1].to_linear();
+        const BAR_HEIGHT: f32 = 2.0;
+
+        for ui_item in &self.ui_items {
+            if let crate::termwindow::UIItemType::TabBar(TabBarItem::Tab { tab_idx, .. }) =
+                &ui_item.item_type
+            {
+                let (fraction, color) = match progress.get(*tab_idx) {
+                    Some(Progress::Percentage(pct)) => ((*pct).min(100) as f32 / 100., green),
+                    Some(Progress::Error(pct)) => ((*pct).min(100) as f32 / 100., red),
+                    Some(Progress::Indeterminate) => (1., green.mul_alpha(0.5)),
+                    Some(Progress::None) | None => continue,
+                };
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(
+                        ui_item.x as f32,
+                        (ui_item.y + ui_item.height) as f32 - BAR_HEIGHT,
+                        ui_item.width as f32 * fraction,
+                        BAR_HEIGHT,
+                    ),
+                    color,
+                )
+                .context("tab progress bar")?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Render the toast notification
     pub fn paint_toast(&mut self) -> anyhow::Result<()> {
         let (toast_at, message, lifetime) = match &self.toast {
@@ -15,11 +15,14 @@ use mux::renderable::{RenderableDimensions, StableCursorPosition};
 use mux::tab::PositionedPane;
 use ordered_float::NotNan;
 use std::time::Instant;
-use wezterm_dynamic::Value;
+use termwiz::surface::CursorVisibility;
 use wezterm_term::color::{ColorAttribute, ColorPalette};
 use wezterm_term::{Line, StableRowIndex};
 use window::color::LinearRgba;
 
+/// Peak opacity of the pane focus flash
+const FOCUS_FLASH_MAX_ALPHA: f32 = 0.25;
+
 impl crate::TermWindow {
     fn paint_pane_box_model(&mut self, pos: &PositionedPane) -> anyhow::Result<()> {
         let computed = self.build_pane(pos)?;
@@ -85,6 +88,17 @@ impl crate::TermWindow {
         let current_viewport = self.get_viewport(pane_id);
         let dims = pos.pane.get_dimensions();
 
+        // Lines that are wider than the pane are drawn from the
+        // horizontal scroll position, and the cursor moves with them
+        let horizontal_scroll = self.get_horizontal_scroll(pane_id);
+        let cursor = match cursor.x.checked_sub(horizontal_scroll) {
+            Some(x) => StableCursorPosition { x, ..cursor },
+            None => StableCursorPosition {
+                visibility: CursorVisibility::Hidden,
+                ..cursor
+            },
+        };
+
         let gl_state = self.render_state.as_ref().unwrap();
 
         let cursor_border_color = palette.cursor_border.to_linear();
@@ -232,6 +246,24 @@ impl crate::TermWindow {
                     Some(config.inactive_pane_hsb)
                 });
             }
+
+            // A newly focused pane gets a translucent wash of the bell
+            // color that fades out
+            if let Some(intensity) = self.get_intensity_if_focus_flashing(&pos.pane, &config) {
+                let LinearRgba(r, g, b, _) = config
+                    .resolved_palette
+                    .visual_bell
+                    .as_deref()
+                    .unwrap_or(&palette.foreground)
+                    .to_linear();
+                self.filled_rectangle(
+                    layers,
+                    0,
+                    background_rect,
+                    LinearRgba::with_components(r, g, b, intensity * FOCUS_FLASH_MAX_ALPHA),
+                )
+                .context("filled_rectangle")?;
+            }
         }
 
         // TODO: we only have a single scrollbar in a single position.
@@ -308,8 +340,26 @@ impl crate::TermWindow {
         };
 
         let start = Instant::now();
-        let selection_fg = palette.selection_fg.to_linear();
-        let selection_bg = palette.selection_bg.to_linear();
+        // Selections in unfocused panes stay visible, but dimmed so that
+        // it is clear which pane copy and paste will act upon
+        let (selection_fg, selection_bg) = if self.focused.is_some() && pos.is_active {
+            (
+                palette.selection_fg.to_linear(),
+                palette.selection_bg.to_linear(),
+            )
+        } else {
+            let colors = &config.resolved_palette;
+            (
+                colors
+                    .inactive_selection_fg
+                    .map(|c| c.to_linear())
+                    .unwrap_or_else(|| palette.selection_fg.to_linear()),
+                colors
+                    .inactive_selection_bg
+                    .map(|c| c.to_linear())
+                    .unwrap_or_else(|| palette.selection_bg.to_linear().mul_alpha(0.5)),
+            )
+        };
         let cursor_fg = palette.cursor_fg.to_linear();
         let cursor_bg = palette.cursor_bg.to_linear();
         let cursor_is_default_color =
@@ -328,6 +378,9 @@ impl crate::TermWindow {
                 term_window: &'a mut crate::TermWindow,
                 selrange: Option<SelectionRange>,
                 rectangular: bool,
+                horizontal_scroll: usize,
+                /// The width of the widest line seen so far
+                widest_line: usize,
                 dims: RenderableDimensions,
                 top_pixel_y: f32,
                 left_pixel_x: f32,
@@ -347,6 +400,9 @@ impl crate::TermWindow {
                 white_space: TextureRect,
                 filled_box: TextureRect,
                 window_is_transparent: bool,
+                /// The (succeeded, failed) colors of the command status
+                /// markers, if they are enabled
+                command_status_colors: Option<(LinearRgba, LinearRgba)>,
                 layers: &'a mut TripleLayerQuadAllocator<'b>,
                 error: Option<anyhow::Error>,
             }
@@ -362,10 +418,21 @@ impl crate::TermWindow {
 
             let content_pixel_width = pane_pixel_width;
 
+            let command_status_colors = if config.show_command_status_markers {
+                Some((
+                    palette.colors.0[2].to_linear(),
+                    palette.colors.0[1].to_linear(),
+                ))
+            } else {
+                None
+            };
+
             let mut render = LineRender {
                 term_window: self,
                 selrange,
                 rectangular,
+                horizontal_scroll,
+                widest_line: 0,
                 dims,
                 top_pixel_y,
                 left_pixel_x,
@@ -385,6 +452,7 @@ impl crate::TermWindow {
                 white_space,
                 filled_box,
                 window_is_transparent,
+                command_status_colors,
                 layers,
                 error: None,
             };
@@ -397,11 +465,25 @@ impl crate::TermWindow {
                     line: &&mut Line,
                 ) -> anyhow::Result<()> {
                     let stable_row = stable_top + line_idx as StableRowIndex;
-                    let selrange = self
-                        .selrange
-                        .map_or(0..0, |sel| sel.cols_for_row(stable_row, self.rectangular));
-                    // Constrain to the pane width!
-                    let selrange = selrange.start..selrange.end.min(self.dims.cols);
+                    self.render_command_status(line_idx, line)?;
+
+                    self.widest_line = self.widest_line.max(line.len());
+                    let cols_for_row = |row: StableRowIndex| {
+                        let cols = self
+                            .selrange
+                            .map_or(0..0, |sel| sel.cols_for_row(row, self.rectangular));
+                        // Shift by the horizontal scroll and constrain
+                        // to the pane width!
+                        let start = cols.start.saturating_sub(self.horizontal_scroll);
+                        let end = cols.end.saturating_sub(self.horizontal_scroll);
+                        start.min(self.dims.cols)..end.min(self.dims.cols)
+                    };
+                    let selrange = cols_for_row(stable_row);
+                    let (selection_above, selection_below) = if selrange.is_empty() {
+                        (0..0, 0..0)
+                    } else {
+                        (cols_for_row(stable_row - 1), cols_for_row(stable_row + 1))
+                    };
                     let show_terminal_cursor = self.term_window.get_modal().is_none();
                     let pane_is_active_for_cursor = self.pos.is_active && show_terminal_cursor;
 
@@ -428,20 +510,7 @@ impl crate::TermWindow {
                                 }
                                 _ => None,
                             },
-                            if self.term_window.config.detect_password_input {
-                                match self.pos.pane.get_metadata() {
-                                    Value::Object(obj) => {
-                                        match obj.get(&Value::String("password_input".to_string()))
-                                        {
-                                            Some(Value::Bool(b)) => *b,
-                                            _ => false,
-                                        }
-                                    }
-                                    _ => false,
-                                }
-                            } else {
-                                false
-                            },
+                            self.term_window.pane_has_password_input(&self.pos.pane),
                         )
                     } else {
                         (None, None, false)
@@ -458,8 +527,11 @@ impl crate::TermWindow {
                         quad_generation: self.term_window.quad_generation,
                         composing: composing.clone(),
                         selection: selrange.clone(),
+                        selection_above: selection_above.clone(),
+                        selection_below: selection_below.clone(),
                         cursor,
                         shape_hash,
+                        horizontal_scroll: self.horizontal_scroll,
                         top_pixel_y: NotNan::new(self.top_pixel_y).unwrap()
                             + (line_idx + self.pos.top) as f32
                                 * self.term_window.render_metrics.cell_size.height as f32,
@@ -503,7 +575,12 @@ impl crate::TermWindow {
                             if let DeadKeyStatus::Composing(composing) =
                                 &self.term_window.dead_key_status
                             {
-                                Some((self.cursor.x, composing.to_string()))
+                                // The line is shaped in full, so this is
+                                // the column before the horizontal scroll
+                                Some((
+                                    self.cursor.x + self.horizontal_scroll,
+                                    composing.to_string(),
+                                ))
                             } else {
                                 None
                             }
@@ -521,7 +598,10 @@ impl crate::TermWindow {
                                 pixel_width: self.content_pixel_width,
                                 stable_line_idx: Some(stable_row),
                                 line: &line,
+                                horizontal_scroll: self.horizontal_scroll,
                                 selection: selrange.clone(),
+                                selection_above,
+                                selection_below,
                                 cursor: &self.cursor,
                                 palette: &self.palette,
                                 dims: &self.dims,
@@ -577,6 +657,46 @@ impl crate::TermWindow {
 
                     Ok(())
                 }
+
+                /// Draws a green or red bar in the gutter to the left of a
+                /// line on which the shell reported the exit status of the
+                /// command entered there
+                fn render_command_status(
+                    &mut self,
+                    line_idx: usize,
+                    line: &Line,
+                ) -> anyhow::Result<()> {
+                    let Some((succeeded_color, failed_color)) = self.command_status_colors else {
+                        return Ok(());
+                    };
+                    let color = match line.command_succeeded() {
+                        Some(true) => succeeded_color,
+                        Some(false) => failed_color,
+                        None => return Ok(()),
+                    };
+                    let metrics = &self.term_window.render_metrics;
+                    let cell_height = metrics.cell_size.height as f32;
+                    // The marker goes in the padding or split gutter; when
+                    // there is no room there, leave it out rather than
+                    // cover the first column of text
+                    let width = (metrics.cell_size.width as f32 / 4.)
+                        .max(2.)
+                        .min(self.left_pixel_x);
+                    if width < 1. {
+                        return Ok(());
+                    }
+                    let x = self.left_pixel_x - width;
+                    let y = self.top_pixel_y + (line_idx + self.pos.top) as f32 * cell_height;
+                    self.term_window
+                        .filled_rectangle(
+                            self.layers,
+                            2,
+                            euclid::rect(x, y, width, cell_height),
+                            color,
+                        )
+                        .context("filled_rectangle")?;
+                    Ok(())
+                }
             }
 
             impl<'a, 'b> WithPaneLines for LineRender<'a, 'b> {
@@ -594,6 +714,34 @@ impl crate::TermWindow {
             if let Some(error) = render.error.take() {
                 return Err(error).context("error while calling with_lines_mut");
             }
+            let widest_line = render.widest_line;
+
+            // The wide lines may have scrolled out of the viewport, or
+            // been cleared; catch up with what is left on the next frame
+            let max_scroll = widest_line.saturating_sub(dims.cols);
+            if horizontal_scroll > max_scroll {
+                self.pane_state(pane_id).horizontal_scroll = max_scroll;
+                self.window.as_ref().unwrap().invalidate();
+            }
+
+            if widest_line > dims.cols {
+                // A thin bar along the bottom of the pane showing which
+                // part of the widest line is in view
+                let bar_height = (cell_height / 8.).max(2.);
+                let bar_y = top_pixel_y + (pos.top + pos.height) as f32 * cell_height - bar_height;
+                let thumb_width =
+                    (pane_pixel_width * dims.cols as f32 / widest_line as f32).max(cell_width);
+                let thumb_x = left_pixel_x
+                    + (pane_pixel_width - thumb_width) * horizontal_scroll.min(max_scroll) as f32
+                        / max_scroll as f32;
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(thumb_x, bar_y, thumb_width, bar_height),
+                    palette.scrollbar_thumb.to_linear(),
+                )
+                .context("filled_rectangle")?;
+            }
         }
 
         /*
@@ -1,4 +1,8 @@
 use crate::quad::{QuadTrait, TripleLayerQuadAllocator, TripleLayerQuadAllocatorTrait};
+use crate::termwindow::render::corners::{
+    BOTTOM_LEFT_ROUNDED_CORNER, BOTTOM_RIGHT_ROUNDED_CORNER, TOP_LEFT_ROUNDED_CORNER,
+    TOP_RIGHT_ROUNDED_CORNER,
+};
 use crate::termwindow::render::{
     resolve_fg_color_attr, same_hyperlink, update_next_frame_time, ClusterStyleCache,
     ComputeCellFgBgParams, ComputeCellFgBgResult, LineToElementParams, LineToElementShape,
@@ -6,7 +10,7 @@ use crate::termwindow::render::{
 };
 use crate::termwindow::LineToElementShapeItem;
 use anyhow::Context;
-use config::{HsbTransform, TextStyle};
+use config::{DimensionContext, HsbTransform, TextStyle};
 use std::ops::Range;
 use std::rc::Rc;
 use std::time::Instant;
@@ -68,8 +72,19 @@ impl crate::TermWindow {
         let (_bidi_enabled, bidi_direction) = params.line.bidi_info();
         let direction = bidi_direction.direction();
 
+        // The cursor and selection are in pane columns, while the cells
+        // of the line are offset by the horizontal scroll
+        let scroll_px = params.horizontal_scroll as f32 * cell_width;
+        let clip_px = if params.horizontal_scroll > 0 {
+            params.left_pixel_x..params.left_pixel_x + params.pixel_width
+        } else {
+            f32::NEG_INFINITY..f32::INFINITY
+        };
+
         let cursor_cell = if params.stable_line_idx == Some(params.cursor.y) {
-            params.line.get_cell(params.cursor.x)
+            params
+                .line
+                .get_cell(params.cursor.x + params.horizontal_scroll)
         } else {
             None
         };
@@ -139,6 +154,13 @@ impl crate::TermWindow {
             }
         }
 
+        // The offset of the cell at column `x` of the line from the left
+        // of the pane, taking the horizontal scroll into account
+        let cell_x = |x: usize| match direction {
+            Direction::LeftToRight => x as f32 * cell_width - scroll_px,
+            Direction::RightToLeft => (num_cols as f32 - x as f32) * cell_width + scroll_px,
+        };
+
         if params.dims.reverse_video {
             let mut quad = self
                 .filled_rectangle(
@@ -200,9 +222,9 @@ impl crate::TermWindow {
             if !bg_is_default {
                 let x = params.left_pixel_x
                     + if params.use_pixel_positioning {
-                        item.x_pos
+                        item.x_pos - scroll_px
                     } else {
-                        phys(cluster.first_cell_idx, num_cols, direction) as f32 * cell_width
+                        cell_x(cluster.first_cell_idx)
                     };
 
                 let mut width = if params.use_pixel_positioning {
@@ -233,15 +255,15 @@ impl crate::TermWindow {
                 // stretch across the whole span
                 for i in 0..cluster_width {
                     let rel_x = if params.use_pixel_positioning {
-                        item.x_pos
+                        item.x_pos - scroll_px
                     } else {
-                        phys(cluster.first_cell_idx + i, num_cols, direction) as f32 * cell_width
+                        cell_x(cluster.first_cell_idx + i)
                     };
 
                     // Clip underlines to the visible content area, matching
                     // the background intersection logic above.
-                    if rel_x + cell_width > params.pixel_width {
-                        break;
+                    if rel_x < 0. || rel_x + cell_width > params.pixel_width {
+                        continue;
                     }
 
                     let x = gl_x + params.left_pixel_x + rel_x;
@@ -261,16 +283,8 @@ impl crate::TermWindow {
         let selection_pixel_range = if !params.selection.is_empty() {
             let start = params.left_pixel_x + (params.selection.start as f32 * cell_width);
             let width = (params.selection.end - params.selection.start) as f32 * cell_width;
-            let mut quad = self
-                .filled_rectangle(
-                    layers,
-                    0,
-                    euclid::rect(start, params.top_pixel_y, width, cell_height),
-                    params.selection_bg,
-                )
-                .context("filled_rectangle")?;
-
-            quad.set_hsv(hsv);
+            self.render_selection_background(&params, start, width, hsv, layers)
+                .context("render_selection_background")?;
 
             start..start + width
         } else {
@@ -400,8 +414,8 @@ impl crate::TermWindow {
         let mut visual_cell_idx = 0;
 
         let mut cluster_x_pos = match direction {
-            Direction::LeftToRight => 0.,
-            Direction::RightToLeft => params.pixel_width,
+            Direction::LeftToRight => -scroll_px,
+            Direction::RightToLeft => params.pixel_width + scroll_px,
         };
 
         for item in shaped.iter() {
@@ -443,6 +457,11 @@ impl crate::TermWindow {
                 }
 
                 for glyph_idx in 0..info.pos.num_cells as usize {
+                    let Some(cell_idx) =
+                        (visual_cell_idx + glyph_idx).checked_sub(params.horizontal_scroll)
+                    else {
+                        continue;
+                    };
                     for img in &images {
                         if img.z_index() < 0 {
                             self.populate_image_quad(
@@ -450,7 +469,7 @@ impl crate::TermWindow {
                                 gl_state,
                                 layers,
                                 0,
-                                visual_cell_idx + glyph_idx,
+                                cell_idx,
                                 &params,
                                 hsv,
                                 item.fg_color,
@@ -502,7 +521,9 @@ impl crate::TermWindow {
                                 0.
                             };
 
-                        if pos_x > params.pixel_width {
+                        // Right-to-left lines that are scrolled start beyond
+                        // the right edge; they are clipped below instead
+                        if direction == Direction::LeftToRight && pos_x > params.pixel_width {
                             log::trace!("breaking on overflow {} > {}", pos_x, params.pixel_width);
                             break;
                         }
@@ -565,8 +586,12 @@ impl crate::TermWindow {
                         }
 
                         let adjust = (glyph.x_offset + glyph.bearing_x).get() as f32;
-                        let texture_range = pos_x + adjust
-                            ..pos_x + adjust + (texture.coords.size.width as f32 * width_scale);
+                        let texture_start = pos_x + adjust;
+                        let texture_range = intersection(
+                            &(texture_start
+                                ..texture_start + (texture.coords.size.width as f32 * width_scale)),
+                            &clip_px,
+                        );
 
                         // First bucket the ranges according to cursor position
                         let (left, mid, right) = range3(&texture_range, &cursor_range_pixels);
@@ -619,6 +644,10 @@ impl crate::TermWindow {
                                 texture.coords.size.height,
                             );
 
+                            // Only part of the sprite is drawn, so we can't use
+                            // texture_coords(), but its page still needs to be
+                            // kept from being evicted
+                            texture.mark_used();
                             let texture_rect = texture.texture.to_texture_coords(pixel_rect);
 
                             let mut quad = layers.allocate(1).context("layers.allocate(1)")?;
@@ -646,13 +675,14 @@ impl crate::TermWindow {
                 }
 
                 for glyph_idx in 0..info.pos.num_cells as usize {
+                    let Some(cell_idx) =
+                        (visual_cell_idx + glyph_idx).checked_sub(params.horizontal_scroll)
+                    else {
+                        continue;
+                    };
                     for img in &images {
                         if img.z_index() >= 0 {
-                            overlay_images.push((
-                                visual_cell_idx + glyph_idx,
-                                img.clone(),
-                                item.fg_color,
-                            ));
+                            overlay_images.push((cell_idx, img.clone(), item.fg_color));
                         }
                     }
                 }
@@ -698,6 +728,131 @@ impl crate::TermWindow {
         })
     }
 
+    /// Fills the selection highlight of a line, widened by
+    /// `selection_padding` and with its outer corners rounded by
+    /// `selection_corner_radius`.  A corner is an outer corner when
+    /// the adjacent line doesn't also select the cell at that corner.
+    fn render_selection_background(
+        &self,
+        params: &RenderScreenLineParams,
+        start: f32,
+        width: f32,
+        hsv: Option<HsbTransform>,
+        layers: &mut TripleLayerQuadAllocator,
+    ) -> anyhow::Result<()> {
+        let cell_width = self.render_metrics.cell_size.width as f32;
+        let cell_height = self.render_metrics.cell_size.height as f32;
+        let dpi = self.dimensions.dpi as f32;
+        let color = params.selection_bg;
+
+        let padding = params
+            .config
+            .selection_padding
+            .evaluate_as_pixels(DimensionContext {
+                dpi,
+                pixel_max: cell_width,
+                pixel_cell: cell_width,
+            });
+        let left = (start - padding).max(params.left_pixel_x);
+        let right = (start + width + padding).min(params.left_pixel_x + params.pixel_width);
+        let width = right - left;
+        let top = params.top_pixel_y;
+        let bottom = top + cell_height;
+
+        let radius = params
+            .config
+            .selection_corner_radius
+            .evaluate_as_pixels(DimensionContext {
+                dpi,
+                pixel_max: cell_height,
+                pixel_cell: cell_height,
+            })
+            .min(cell_height / 2.)
+            .min(width / 2.)
+            .floor();
+
+        let first = params.selection.start;
+        let last = params.selection.end - 1;
+        let corners = [
+            (
+                !params.selection_above.contains(&first),
+                euclid::point2(left, top),
+                TOP_LEFT_ROUNDED_CORNER,
+            ),
+            (
+                !params.selection_above.contains(&last),
+                euclid::point2(right - radius, top),
+                TOP_RIGHT_ROUNDED_CORNER,
+            ),
+            (
+                !params.selection_below.contains(&first),
+                euclid::point2(left, bottom - radius),
+                BOTTOM_LEFT_ROUNDED_CORNER,
+            ),
+            (
+                !params.selection_below.contains(&last),
+                euclid::point2(right - radius, bottom - radius),
+                BOTTOM_RIGHT_ROUNDED_CORNER,
+            ),
+        ];
+
+        if radius < 1. || !corners.iter().any(|(rounded, _, _)| *rounded) {
+            self.filled_rectangle(
+                layers,
+                0,
+                euclid::rect(left, top, width, cell_height),
+                color,
+            )?
+            .set_hsv(hsv);
+            return Ok(());
+        }
+
+        // Split the highlight into a full width middle band, plus top
+        // and bottom bands that leave room for the corner pieces
+        self.filled_rectangle(
+            layers,
+            0,
+            euclid::rect(left, top + radius, width, cell_height - 2. * radius),
+            color,
+        )?
+        .set_hsv(hsv);
+        for y in [top, bottom - radius] {
+            self.filled_rectangle(
+                layers,
+                0,
+                euclid::rect(left + radius, y, width - 2. * radius, radius),
+                color,
+            )?
+            .set_hsv(hsv);
+        }
+
+        for (rounded, point, poly) in corners {
+            let mut quad = if rounded {
+                let mut quad = self.poly_quad(
+                    layers,
+                    0,
+                    point,
+                    poly,
+                    1,
+                    euclid::size2(radius, radius),
+                    color,
+                )?;
+                quad.set_grayscale();
+                quad
+            } else {
+                self.filled_rectangle(
+                    layers,
+                    0,
+                    euclid::rect(point.x, point.y, radius, radius),
+                    color,
+                )?
+            };
+            quad.set_hsv(hsv);
+        }
+
+        Ok(())
+    }
+
     fn build_line_element_shape(
         &self,
         params: LineToElementParams,
@@ -2,6 +2,7 @@ use crate::termwindow::render::TripleLayerQuadAllocator;
 use crate::termwindow::{UIItem, UIItemType};
 use mux::pane::Pane;
 use mux::tab::{PositionedSplit, SplitDirection};
+use mux::Mux;
 use std::sync::Arc;
 
 impl crate::TermWindow {
@@ -16,7 +17,13 @@ impl crate::TermWindow {
         let cell_width = self.render_metrics.cell_size.width as f32;
         let cell_height = self.render_metrics.cell_size.height as f32;
 
-        let foreground = palette.split.to_linear();
+        // A tab color, if one is assigned, takes the place of the
+        // split color from the palette
+        let foreground = Mux::get()
+            .get_active_tab_for_window(self.mux_window_id)
+            .and_then(|tab| tab.get_color())
+            .map(|color| color.to_linear())
+            .unwrap_or_else(|| palette.split.to_linear());
 
         let border = self.get_os_border();
         let first_row_offset = if self.show_tab_bar && !self.config.tab_bar_at_bottom {
@@ -84,7 +84,10 @@ impl crate::TermWindow {
                 pixel_width: self.dimensions.pixel_width as f32,
                 stable_line_idx: None,
                 line: self.tab_bar.line(),
+                horizontal_scroll: 0,
                 selection: 0..0,
+                selection_above: 0..0,
+                selection_below: 0..0,
                 cursor: &Default::default(),
                 palette: &palette,
                 dims: &RenderableDimensions {
@@ -0,0 +1,143 @@
+use crate::termwindow::box_model::*;
+use crate::termwindow::DimensionContext;
+use crate::utilsprites::RenderMetrics;
+use config::Dimension;
+use window::color::LinearRgba;
+
+/// Which corner of the terminal area a text box is anchored to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextBoxCorner {
+    TopRight,
+    BottomLeft,
+}
+
+impl crate::TermWindow {
+    /// Render `lines` in a box with a solid background, anchored to
+    /// `corner` of the area not occupied by the tab bar
+    pub fn paint_text_box(
+        &mut self,
+        lines: Vec<String>,
+        corner: TextBoxCorner,
+        bg_color: LinearRgba,
+        text_color: LinearRgba,
+    ) -> anyhow::Result<()> {
+        let font = self.fonts.title_font()?;
+        let metrics = RenderMetrics::with_font_metrics(&font.metrics());
+
+        let max_len = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let children = lines
+            .into_iter()
+            .map(|line| {
+                Element::new(&font, ElementContent::Text(line))
+                    .colors(ElementColors {
+                        border: BorderColor::default(),
+                        bg: LinearRgba::TRANSPARENT.into(),
+                        text: text_color.into(),
+                    })
+                    .display(DisplayType::Block)
+            })
+            .collect::<Vec<_>>();
+        let num_lines = children.len();
+
+        let element = Element::new(&font, ElementContent::Children(children))
+            .colors(ElementColors {
+                border: BorderColor::new(bg_color.into()),
+                bg: bg_color.into(),
+                text: text_color.into(),
+            })
+            .padding(BoxDimension {
+                left: Dimension::Cells(0.5),
+                right: Dimension::Cells(0.5),
+                top: Dimension::Cells(0.25),
+                bottom: Dimension::Cells(0.25),
+            })
+            .border(BoxDimension::new(Dimension::Pixels(0.0)));
+
+        let dimensions = self.dimensions;
+        let border = self.get_os_border();
+        let cell_width = metrics.cell_size.width as f32;
+        let cell_height = metrics.cell_size.height as f32;
+        let width = (max_len as f32 + 1.5) * cell_width;
+        let height = (num_lines as f32 + 0.5) * cell_height;
+        let tab_bar_height = if self.show_tab_bar {
+            self.tab_bar_pixel_height()?
+        } else {
+            0.
+        };
+
+        let (left, top) = match corner {
+            TextBoxCorner::TopRight => {
+                let left =
+                    dimensions.pixel_width as f32 - width - cell_width - border.right.get() as f32;
+                let top_bar = if self.config.tab_bar_at_bottom {
+                    0.
+                } else {
+                    tab_bar_height
+                };
+                (left, border.top.get() as f32 + top_bar + cell_height * 0.5)
+            }
+            TextBoxCorner::BottomLeft => {
+                let bottom_bar = if self.config.tab_bar_at_bottom {
+                    tab_bar_height
+                } else {
+                    0.
+                };
+                let top = dimensions.pixel_height as f32
+                    - height
+                    - bottom_bar
+                    - border.bottom.get() as f32
+                    - cell_height * 0.5;
+                (border.left.get() as f32 + cell_width, top)
+            }
+        };
+
+        let computed = self.compute_element(
+            &LayoutContext {
+                height: DimensionContext {
+                    dpi: dimensions.dpi as f32,
+                    pixel_max: dimensions.pixel_height as f32,
+                    pixel_cell: cell_height,
+                },
+                width: DimensionContext {
+                    dpi: dimensions.dpi as f32,
+                    pixel_max: dimensions.pixel_width as f32,
+                    pixel_cell: cell_width,
+                },
+                bounds: euclid::rect(left.max(0.), top.max(0.), width, height),
+                metrics: &metrics,
+                gl_state: self.render_state.as_ref().unwrap(),
+                zindex: 120,
+            },
+            &element,
+        )?;
+
+        let gl_state = self.render_state.as_ref().unwrap();
+        self.render_element(&computed, gl_state, None)
+    }
+
+    /// Render the name of the active key table, if any, so that modal
+    /// key bindings are visible while they are in effect.  Key tables
+    /// that belong to overlays, such as copy mode, have their own UI
+    /// and are not shown here.
+    pub fn paint_key_table_indicator(&mut self) -> anyhow::Result<()> {
+        if !self.config.show_key_table_indicator {
+            return Ok(());
+        }
+        let Some(name) = self.key_table_state.current_table().map(|s| s.to_string()) else {
+            return Ok(());
+        };
+        // Repaint when the table times out so that the indicator goes away
+        if let Some(expiry) = self.key_table_state.current_expiration() {
+            self.update_next_frame_time(Some(expiry));
+        }
+
+        let palette = self.palette();
+        let bg = palette.colors.0[4].to_linear();
+        self.paint_text_box(
+            vec![name],
+            TextBoxCorner::BottomLeft,
+            LinearRgba(bg.0, bg.1, bg.2, 0.9),
+            LinearRgba(1.0, 1.0, 1.0, 1.0),
+        )
+    }
+}
@@ -42,6 +42,17 @@ impl super::TermWindow {
             log::trace!("new dimensions are zero: NOP!");
             return;
         }
+        if self.dimensions == dimensions
+            && (self.window_state ^ window_state) == WindowState::HIDDEN
+        {
+            // Minimized or restored: nothing about the layout changed
+            self.window_state = window_state;
+            if window_state.can_paint() {
+                // Catch up on any output that arrived while hidden
+                window.invalidate();
+            }
+            return;
+        }
         if self.dimensions == dimensions && self.window_state == window_state {
             // Even if the geometry didn't change, live resize state transitions
             // still matter for flushing deferred work.
@@ -89,6 +100,10 @@ impl super::TermWindow {
         if let Some(modal) = self.get_modal() {
             modal.reconfigure(self);
         }
+        if live_resizing && self.config.show_size_while_resizing {
+            let size = self.current_cell_dimensions();
+            self.show_toast_for(format!("{}×{}", size.cols, size.rows), 1000);
+        }
         if !live_resizing {
             if self.pending_config_reload_after_resize {
                 self.pending_config_reload_after_resize = false;
@@ -490,7 +505,39 @@ impl super::TermWindow {
             self.apply_dimensions(&dimensions, None, window);
         }
 
-        persist_current_font_size(&self.config, self.fonts.get_font_scale());
+        if self.presentation_restore_font_scale.is_none() {
+            persist_current_font_size(&self.config, self.fonts.get_font_scale());
+        }
+    }
+
+    /// Enters or leaves presentation mode in this window.
+    /// Entering scales the font by `presentation_mode_font_scale` and
+    /// hides the tab bar; leaving restores the font scale that was in
+    /// effect beforehand.  The window itself keeps its size.
+    pub fn set_presentation_mode(&mut self, enabled: bool) {
+        let window = match self.window.clone() {
+            Some(window) => window,
+            None => return,
+        };
+        let font_scale = if enabled {
+            if self.presentation_restore_font_scale.is_some() {
+                return;
+            }
+            let font_scale = self.fonts.get_font_scale();
+            self.presentation_restore_font_scale = Some(font_scale);
+            font_scale * self.config.presentation_mode_font_scale
+        } else {
+            match self.presentation_restore_font_scale.take() {
+                Some(font_scale) => font_scale,
+                None => return,
+            }
+        };
+
+        let dimensions = self.dimensions;
+        self.apply_scale_change(&dimensions, font_scale);
+        self.apply_dimensions(&dimensions, None, &window);
+        self.update_title();
+        window.invalidate();
     }
 
     pub fn decrease_font_size(&mut self) {
@@ -527,7 +574,9 @@ impl super::TermWindow {
             dpi: size.dpi,
         };
 
-        let show_tab_bar = config.enable_tab_bar && !config.hide_tab_bar_if_only_one_tab;
+        let show_tab_bar = config.enable_tab_bar
+            && !config.hide_tab_bar_if_only_one_tab
+            && !config.use_native_macos_tabs;
         let tab_bar_height = if show_tab_bar {
             self.tab_bar_pixel_height()? as usize
         } else {
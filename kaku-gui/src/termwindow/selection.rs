@@ -109,6 +109,33 @@ impl super::TermWindow {
         s
     }
 
+    /// Holds the viewport of a pane that is following its output for
+    /// as long as the mouse is held down to select text, so that output
+    /// arriving mid-drag doesn't shift the selection.
+    fn hold_viewport_for_selection_drag(&mut self, pane: &Arc<dyn Pane>) {
+        if self.current_mouse_buttons.is_empty() {
+            return;
+        }
+        let mut state = self.pane_state(pane.pane_id());
+        if state.viewport.is_none() && state.selection_drag_top.is_none() {
+            state.selection_drag_top = Some(pane.get_dimensions().physical_top);
+        }
+    }
+
+    /// Lets panes whose viewport was held by
+    /// `hold_viewport_for_selection_drag` follow their output again
+    pub fn release_selection_drag(&mut self) {
+        let mut released = false;
+        for state in self.pane_state.borrow_mut().values_mut() {
+            released |= state.selection_drag_top.take().is_some();
+        }
+        if released {
+            if let Some(window) = self.window.as_ref() {
+                window.invalidate();
+            }
+        }
+    }
+
     pub fn clear_selection(&mut self, pane: &Arc<dyn Pane>) {
         let mut selection = self.selection(pane.pane_id());
         selection.clear();
@@ -120,6 +147,7 @@ impl super::TermWindow {
             Some(coords) => coords,
             None => return,
         };
+        self.hold_viewport_for_selection_drag(pane);
         let x = position.column;
         match mode {
             SelectionMode::Cell | SelectionMode::Block => {
@@ -243,6 +271,7 @@ impl super::TermWindow {
             Some(coords) => (coords.0.column, coords.1),
             None => return,
         };
+        self.hold_viewport_for_selection_drag(pane);
         match mode {
             SelectionMode::Line => {
                 let start = SelectionCoordinate::x_y(x, y);
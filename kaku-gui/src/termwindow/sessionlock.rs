@@ -0,0 +1,194 @@
+use crate::termwindow::box_model::*;
+use crate::termwindow::modal::Modal;
+use crate::termwindow::DimensionContext;
+use crate::utilsprites::RenderMetrics;
+use crate::TermWindow;
+use ::window::RectF;
+use config::keyassignment::KeyAssignment;
+use config::window::SessionUnlockMethod;
+use config::Dimension;
+use std::cell::{Ref, RefCell};
+use wezterm_term::{KeyCode, KeyModifiers, MouseEvent};
+use window::color::LinearRgba;
+
+/// Covers the whole window, hiding the panes and tabs behind it,
+/// until the configured `session_unlock_method` is satisfied.
+/// All key assignments are swallowed while the lock is in place.
+pub struct SessionLock {
+    element: RefCell<Option<Vec<ComputedElement>>>,
+    input: RefCell<String>,
+    failed: RefCell<bool>,
+}
+
+impl SessionLock {
+    pub fn new() -> Self {
+        Self {
+            element: RefCell::new(None),
+            input: RefCell::new(String::new()),
+            failed: RefCell::new(false),
+        }
+    }
+
+    fn prompt(&self, method: &SessionUnlockMethod) -> String {
+        match method {
+            SessionUnlockMethod::Enter => "Locked. Press Enter to unlock".to_string(),
+            SessionUnlockMethod::Passphrase(_) => {
+                // Echo a mask rather than the passphrase itself
+                let mask = "*".repeat(self.input.borrow().chars().count());
+                if *self.failed.borrow() {
+                    format!("Locked. Incorrect passphrase, try again: {mask}_")
+                } else {
+                    format!("Locked. Enter passphrase: {mask}_")
+                }
+            }
+        }
+    }
+
+    fn compute(&self, term_window: &mut TermWindow) -> anyhow::Result<Vec<ComputedElement>> {
+        let font = term_window
+            .fonts
+            .char_select_font()
+            .expect("to resolve char selection font");
+        let metrics = RenderMetrics::with_font_metrics(&font.metrics());
+        let cell_width = metrics.cell_size.width as f32;
+        let cell_height = metrics.cell_size.height as f32;
+
+        let dimensions = term_window.dimensions;
+        let window_width = dimensions.pixel_width as f32;
+        let window_height = dimensions.pixel_height as f32;
+        let layout_context = |bounds: RectF| LayoutContext {
+            height: DimensionContext {
+                dpi: dimensions.dpi as f32,
+                pixel_max: window_height,
+                pixel_cell: cell_height,
+            },
+            width: DimensionContext {
+                dpi: dimensions.dpi as f32,
+                pixel_max: window_width,
+                pixel_cell: cell_width,
+            },
+            bounds,
+            metrics: &metrics,
+            gl_state: term_window.render_state.as_ref().unwrap(),
+            zindex: 100,
+        };
+
+        // An opaque backdrop, so that a translucent window background
+        // doesn't let the panes show through
+        let bg = term_window.palette().background.to_linear();
+        let bg = LinearRgba::with_components(bg.0, bg.1, bg.2, 1.0);
+        let fg = term_window.config.char_select_fg_color.to_linear();
+
+        let backdrop = Element::new(&font, ElementContent::Text(String::new()))
+            .colors(ElementColors {
+                border: BorderColor::default(),
+                bg: bg.into(),
+                text: fg.into(),
+            })
+            .min_width(Some(Dimension::Pixels(window_width)))
+            .min_height(Some(Dimension::Pixels(window_height)));
+        let mut elements = vec![term_window.compute_element(
+            &layout_context(euclid::rect(0., 0., window_width, window_height)),
+            &backdrop,
+        )?];
+
+        let prompt = self.prompt(&term_window.config.session_unlock_method);
+        let prompt_width = (prompt.chars().count() as f32 + 1.) * cell_width;
+        let element = Element::new(&font, ElementContent::Text(prompt))
+            .colors(ElementColors {
+                border: BorderColor::default(),
+                bg: term_window.config.char_select_bg_color.to_linear().into(),
+                text: fg.into(),
+            })
+            .padding(BoxDimension::new(Dimension::Cells(0.5)));
+        elements.push(term_window.compute_element(
+            &layout_context(euclid::rect(
+                ((window_width - prompt_width) / 2.).max(0.),
+                ((window_height - cell_height) / 2.).max(0.),
+                prompt_width.min(window_width),
+                cell_height * 2.,
+            )),
+            &element,
+        )?);
+
+        Ok(elements)
+    }
+
+    fn try_unlock(&self, term_window: &mut TermWindow) {
+        let unlocked = match &term_window.config.session_unlock_method {
+            SessionUnlockMethod::Enter => true,
+            SessionUnlockMethod::Passphrase(passphrase) => *self.input.borrow() == *passphrase,
+        };
+        self.input.borrow_mut().clear();
+        if unlocked {
+            term_window.unlock_session();
+        } else {
+            *self.failed.borrow_mut() = true;
+            term_window.invalidate_modal();
+        }
+    }
+}
+
+impl Modal for SessionLock {
+    fn perform_assignment(
+        &self,
+        _assignment: &KeyAssignment,
+        _term_window: &mut TermWindow,
+    ) -> bool {
+        true
+    }
+
+    fn mouse_event(&self, _event: MouseEvent, _term_window: &mut TermWindow) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn key_down(
+        &self,
+        key: KeyCode,
+        mods: KeyModifiers,
+        term_window: &mut TermWindow,
+    ) -> anyhow::Result<bool> {
+        let wants_passphrase = matches!(
+            term_window.config.session_unlock_method,
+            SessionUnlockMethod::Passphrase(_)
+        );
+        match (key, mods) {
+            (KeyCode::Enter, KeyModifiers::NONE) => {
+                self.try_unlock(term_window);
+                return Ok(true);
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT)
+                if wants_passphrase =>
+            {
+                self.input.borrow_mut().push(c);
+            }
+            (KeyCode::Backspace, KeyModifiers::NONE) => {
+                self.input.borrow_mut().pop();
+            }
+            (KeyCode::Char('u'), KeyModifiers::CTRL) => {
+                self.input.borrow_mut().clear();
+            }
+            // Swallow everything else so that nothing reaches the panes
+            _ => return Ok(true),
+        }
+        term_window.invalidate_modal();
+        Ok(true)
+    }
+
+    fn computed_element(
+        &self,
+        term_window: &mut TermWindow,
+    ) -> anyhow::Result<Ref<'_, [ComputedElement]>> {
+        if self.element.borrow().is_none() {
+            let element = self.compute(term_window)?;
+            self.element.borrow_mut().replace(element);
+        }
+        Ok(Ref::map(self.element.borrow(), |v| {
+            v.as_ref().unwrap().as_slice()
+        }))
+    }
+
+    fn reconfigure(&self, _term_window: &mut TermWindow) {
+        self.element.borrow_mut().take();
+    }
+}
@@ -0,0 +1,461 @@
+use crate::overlay::selector::{matcher_pattern, matcher_score};
+use crate::termwindow::box_model::*;
+use crate::termwindow::modal::Modal;
+use crate::termwindow::render::corners::{
+    BOTTOM_LEFT_ROUNDED_CORNER, BOTTOM_RIGHT_ROUNDED_CORNER, TOP_LEFT_ROUNDED_CORNER,
+    TOP_RIGHT_ROUNDED_CORNER,
+};
+use crate::termwindow::DimensionContext;
+use crate::utilsprites::RenderMetrics;
+use crate::TermWindow;
+use ::window::RectF;
+use config::keyassignment::KeyAssignment;
+use config::Dimension;
+use finl_unicode::grapheme_clusters::Graphemes;
+use mux::pane::{CachePolicy, Pane};
+use mux::Mux;
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+use termwiz::cell::unicode_column_width;
+use wezterm_font::LoadedFont;
+use wezterm_term::{KeyCode, KeyModifiers, MouseEvent, StableRowIndex};
+use window::color::LinearRgba;
+
+/// How many lines of output each card previews at most
+const PREVIEW_LINES: usize = 12;
+
+struct OverviewEntry {
+    tab_idx: usize,
+    title: String,
+    process: String,
+    preview: Vec<String>,
+}
+
+pub struct TabOverview {
+    element: RefCell<Option<Vec<ComputedElement>>>,
+    entries: Vec<OverviewEntry>,
+    selection: RefCell<String>,
+    /// Indices into `entries`, in display order
+    matches: RefCell<Vec<usize>>,
+    selected: RefCell<usize>,
+    columns: RefCell<usize>,
+    /// Bounds of each displayed card, parallel to `matches`
+    cards: RefCell<Vec<RectF>>,
+}
+
+/// Returns up to `max_lines` of the pane's visible text, ending at its
+/// last non-blank line
+pub fn pane_preview_lines(pane: &Arc<dyn Pane>, max_lines: usize) -> Vec<String> {
+    let dims = pane.get_dimensions();
+    let (_top, lines) =
+        pane.get_lines(dims.physical_top..dims.physical_top + dims.viewport_rows as StableRowIndex);
+    let mut text: Vec<String> = lines
+        .iter()
+        .map(|line| line.as_str().trim_end().to_string())
+        .collect();
+    while text.last().is_some_and(|line| line.is_empty()) {
+        text.pop();
+    }
+    let skip = text.len().saturating_sub(max_lines);
+    text.split_off(skip)
+}
+
+/// Truncates `text` so that it occupies no more than `max_cols` cells
+fn truncate_to_width(text: &str, max_cols: usize) -> String {
+    let mut result = String::new();
+    let mut width = 0;
+    for g in Graphemes::new(text) {
+        width += unicode_column_width(g, None);
+        if width > max_cols {
+            break;
+        }
+        result.push_str(g);
+    }
+    result
+}
+
+fn compute_matches(selection: &str, entries: &[OverviewEntry]) -> Vec<usize> {
+    if selection.is_empty() {
+        return (0..entries.len()).collect();
+    }
+    let pattern = matcher_pattern(selection);
+    let mut scores: Vec<(usize, u32)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, entry)| {
+            matcher_score(&pattern, &format!("{} {}", entry.title, entry.process))
+                .map(|score| (idx, score))
+        })
+        .collect();
+    scores.sort_by(|a, b| a.1.cmp(&b.1).reverse());
+    scores.into_iter().map(|(idx, _)| idx).collect()
+}
+
+impl TabOverview {
+    pub fn new(term_window: &mut TermWindow) -> Self {
+        let mux = Mux::get();
+        let mut entries = vec![];
+        let mut active_tab_idx = 0;
+        if let Some(window) = mux.get_window(term_window.mux_window_id) {
+            active_tab_idx = window.get_active_idx();
+            for (tab_idx, tab) in window.iter().enumerate() {
+                let Some(pane) = tab.get_active_pane() else {
+                    continue;
+                };
+                let tab_title = tab.get_title();
+                let title = if tab_title.is_empty() {
+                    pane.get_title()
+                } else {
+                    tab_title
+                };
+                let process = pane
+                    .get_foreground_process_name(CachePolicy::AllowStale)
+                    .and_then(|name| {
+                        std::path::Path::new(&name)
+                            .file_name()
+                            .map(|base| base.to_string_lossy().to_string())
+                    })
+                    .unwrap_or_default();
+                entries.push(OverviewEntry {
+                    tab_idx,
+                    title,
+                    process,
+                    preview: pane_preview_lines(&pane, PREVIEW_LINES),
+                });
+            }
+        }
+        let matches = compute_matches("", &entries);
+        // Tabs without a pane are skipped, so the active tab index
+        // needs to be mapped to its place in the display order
+        let selected = matches
+            .iter()
+            .position(|&entry_idx| entries[entry_idx].tab_idx == active_tab_idx)
+            .unwrap_or(0);
+
+        Self {
+            element: RefCell::new(None),
+            entries,
+            selection: RefCell::new(String::new()),
+            matches: RefCell::new(matches),
+            selected: RefCell::new(selected),
+            columns: RefCell::new(1),
+            cards: RefCell::new(vec![]),
+        }
+    }
+
+    fn card_element(
+        term_window: &TermWindow,
+        font: &Rc<LoadedFont>,
+        entry: &OverviewEntry,
+        selected: bool,
+        max_cols: usize,
+        max_lines: usize,
+        size: (f32, f32),
+    ) -> Element {
+        let fg: LinearRgba = term_window.config.char_select_fg_color.to_linear();
+        let bg: LinearRgba = term_window.config.char_select_bg_color.to_linear();
+        let dim = LinearRgba::with_components(fg.0, fg.1, fg.2, fg.3 * 0.6);
+
+        let heading = if entry.process.is_empty() {
+            format!("{}: {}", entry.tab_idx + 1, entry.title)
+        } else {
+            format!("{}: {} ({})", entry.tab_idx + 1, entry.title, entry.process)
+        };
+        let mut children = vec![Element::new(
+            font,
+            ElementContent::Text(truncate_to_width(&heading, max_cols)),
+        )
+        .colors(ElementColors {
+            border: BorderColor::default(),
+            bg: LinearRgba::TRANSPARENT.into(),
+            text: fg.into(),
+        })
+        .display(DisplayType::Block)];
+
+        let skip = entry.preview.len().saturating_sub(max_lines);
+        for line in entry.preview.iter().skip(skip) {
+            children.push(
+                Element::new(
+                    font,
+                    ElementContent::Text(truncate_to_width(line, max_cols)),
+                )
+                .colors(ElementColors {
+                    border: BorderColor::default(),
+                    bg: LinearRgba::TRANSPARENT.into(),
+                    text: dim.into(),
+                })
+                .display(DisplayType::Block),
+            );
+        }
+
+        let (width, height) = size;
+        Element::new(font, ElementContent::Children(children))
+            .colors(ElementColors {
+                border: BorderColor::new(if selected { fg } else { bg }),
+                bg: bg.into(),
+                text: fg.into(),
+            })
+            .margin(BoxDimension::new(Dimension::Cells(0.25)))
+            .padding(BoxDimension::new(Dimension::Cells(0.25)))
+            .border(BoxDimension::new(Dimension::Pixels(if selected {
+                2.
+            } else {
+                1.
+            })))
+            .border_corners(Some(Corners {
+                top_left: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: TOP_LEFT_ROUNDED_CORNER,
+                },
+                top_right: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: TOP_RIGHT_ROUNDED_CORNER,
+                },
+                bottom_left: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: BOTTOM_LEFT_ROUNDED_CORNER,
+                },
+                bottom_right: SizedPoly {
+                    width: Dimension::Cells(0.25),
+                    height: Dimension::Cells(0.25),
+                    poly: BOTTOM_RIGHT_ROUNDED_CORNER,
+                },
+            }))
+            .min_width(Some(Dimension::Pixels(width)))
+            .max_width(Some(Dimension::Pixels(width)))
+            .min_height(Some(Dimension::Pixels(height)))
+    }
+
+    fn compute(
+        &self,
+        term_window: &mut TermWindow,
+    ) -> anyhow::Result<(Vec<ComputedElement>, Vec<RectF>, usize)> {
+        let font = term_window
+            .fonts
+            .char_select_font()
+            .expect("to resolve char selection font");
+        let metrics = RenderMetrics::with_font_metrics(&font.metrics());
+        let cell_width = metrics.cell_size.width as f32;
+        let cell_height = metrics.cell_size.height as f32;
+
+        let top_bar_height = if term_window.show_tab_bar && !term_window.config.tab_bar_at_bottom {
+            term_window.tab_bar_pixel_height().unwrap()
+        } else {
+            0.
+        };
+        let (padding_left, padding_top) = term_window.padding_left_top();
+        let border = term_window.get_os_border();
+        // mouse_event maps clicks from this same origin
+        let content_x = padding_left + border.left.get() as f32;
+        let top_pixel_y = top_bar_height + padding_top + border.top.get() as f32;
+        let size = term_window.terminal_size;
+        let area_width = size.cols as f32 * term_window.render_metrics.cell_size.width as f32;
+        let area_height = size.rows as f32 * term_window.render_metrics.cell_size.height as f32;
+
+        let dimensions = term_window.dimensions;
+        let layout_context = |bounds: RectF| LayoutContext {
+            height: DimensionContext {
+                dpi: dimensions.dpi as f32,
+                pixel_max: dimensions.pixel_height as f32,
+                pixel_cell: cell_height,
+            },
+            width: DimensionContext {
+                dpi: dimensions.dpi as f32,
+                pixel_max: dimensions.pixel_width as f32,
+                pixel_cell: cell_width,
+            },
+            bounds,
+            metrics: &metrics,
+            gl_state: term_window.render_state.as_ref().unwrap(),
+            zindex: 100,
+        };
+
+        let prompt = Element::new(
+            &font,
+            ElementContent::Text(format!("Tabs: {}_", self.selection.borrow())),
+        )
+        .colors(ElementColors {
+            border: BorderColor::default(),
+            bg: term_window.config.char_select_bg_color.to_linear().into(),
+            text: term_window.config.char_select_fg_color.to_linear().into(),
+        })
+        .padding(BoxDimension::new(Dimension::Cells(0.25)))
+        .display(DisplayType::Block);
+        let mut elements = vec![term_window.compute_element(
+            &layout_context(euclid::rect(
+                content_x,
+                top_pixel_y,
+                area_width,
+                cell_height * 1.5,
+            )),
+            &prompt,
+        )?];
+
+        let matches = self.matches.borrow();
+        let count = matches.len().max(1);
+        let columns = (count as f32).sqrt().ceil() as usize;
+        let rows = count.div_ceil(columns);
+        let grid_top = top_pixel_y + cell_height * 2.;
+        let card_width = area_width / columns as f32;
+        let card_height = (area_height - cell_height * 2.) / rows as f32;
+        // Leave room for the margin, border and padding around the text
+        let inner_width = card_width - cell_width * 1.5;
+        let inner_height = card_height - cell_height;
+        let max_cols = (inner_width / cell_width).max(1.) as usize;
+        let max_lines = ((inner_height / cell_height) as usize).saturating_sub(1);
+
+        let selected = *self.selected.borrow();
+        let mut cards = vec![];
+        for (display_idx, &entry_idx) in matches.iter().enumerate() {
+            let bounds = euclid::rect(
+                content_x + (display_idx % columns) as f32 * card_width,
+                grid_top + (display_idx / columns) as f32 * card_height,
+                card_width,
+                card_height,
+            );
+            let element = Self::card_element(
+                term_window,
+                &font,
+                &self.entries[entry_idx],
+                display_idx == selected,
+                max_cols,
+                max_lines,
+                (inner_width, inner_height),
+            );
+            elements.push(term_window.compute_element(&layout_context(bounds), &element)?);
+            cards.push(bounds);
+        }
+
+        Ok((elements, cards, columns))
+    }
+
+    fn updated_input(&self) {
+        let matches = compute_matches(&self.selection.borrow(), &self.entries);
+        *self.matches.borrow_mut() = matches;
+        *self.selected.borrow_mut() = 0;
+    }
+
+    fn move_by(&self, delta: isize) {
+        let limit = self.matches.borrow().len();
+        if limit == 0 {
+            return;
+        }
+        let mut selected = self.selected.borrow_mut();
+        *selected = (*selected as isize + delta).clamp(0, limit as isize - 1) as usize;
+    }
+
+    fn activate(&self, display_idx: usize, term_window: &mut TermWindow) -> anyhow::Result<()> {
+        let tab_idx = match self.matches.borrow().get(display_idx) {
+            Some(&entry_idx) => self.entries[entry_idx].tab_idx,
+            None => return Ok(()),
+        };
+        term_window.cancel_modal();
+        term_window.activate_tab(tab_idx as isize)
+    }
+}
+
+impl Modal for TabOverview {
+    fn perform_assignment(
+        &self,
+        _assignment: &KeyAssignment,
+        _term_window: &mut TermWindow,
+    ) -> bool {
+        false
+    }
+
+    fn mouse_event(&self, event: MouseEvent, term_window: &mut TermWindow) -> anyhow::Result<()> {
+        if event.button != wezterm_term::MouseButton::Left
+            || event.kind != wezterm_term::MouseEventKind::Press
+        {
+            return Ok(());
+        }
+        let top_bar_height = if term_window.show_tab_bar && !term_window.config.tab_bar_at_bottom {
+            term_window.tab_bar_pixel_height().unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        let (padding_left, padding_top) = term_window.padding_left_top();
+        let border = term_window.get_os_border();
+        let content_x = padding_left + border.left.get() as f32;
+        let content_y = top_bar_height + padding_top + border.top.get() as f32;
+        let cell_width = term_window.render_metrics.cell_size.width as f32;
+        let cell_height = term_window.render_metrics.cell_size.height as f32;
+        let point = euclid::point2(
+            content_x + event.x as f32 * cell_width + event.x_pixel_offset as f32,
+            content_y + event.y as f32 * cell_height + event.y_pixel_offset as f32,
+        );
+
+        let hit = self
+            .cards
+            .borrow()
+            .iter()
+            .position(|card| card.contains(point));
+        if let Some(display_idx) = hit {
+            self.activate(display_idx, term_window)?;
+        }
+        Ok(())
+    }
+
+    fn key_down(
+        &self,
+        key: KeyCode,
+        mods: KeyModifiers,
+        term_window: &mut TermWindow,
+    ) -> anyhow::Result<bool> {
+        let columns = *self.columns.borrow() as isize;
+        match (key, mods) {
+            (KeyCode::Escape, KeyModifiers::NONE) | (KeyCode::Char('g'), KeyModifiers::CTRL) => {
+                term_window.cancel_modal();
+                return Ok(true);
+            }
+            (KeyCode::LeftArrow, KeyModifiers::NONE) => self.move_by(-1),
+            (KeyCode::RightArrow, KeyModifiers::NONE) => self.move_by(1),
+            (KeyCode::UpArrow, KeyModifiers::NONE) => self.move_by(-columns),
+            (KeyCode::DownArrow, KeyModifiers::NONE) => self.move_by(columns),
+            (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT) => {
+                // Type to filter by title or process name
+                self.selection.borrow_mut().push(c);
+                self.updated_input();
+            }
+            (KeyCode::Backspace, KeyModifiers::NONE) => {
+                self.selection.borrow_mut().pop();
+                self.updated_input();
+            }
+            (KeyCode::Char('u'), KeyModifiers::CTRL) => {
+                self.selection.borrow_mut().clear();
+                self.updated_input();
+            }
+            (KeyCode::Enter, KeyModifiers::NONE) => {
+                let selected = *self.selected.borrow();
+                self.activate(selected, term_window)?;
+                return Ok(true);
+            }
+            _ => return Ok(false),
+        }
+        term_window.invalidate_modal();
+        Ok(true)
+    }
+
+    fn computed_element(
+        &self,
+        term_window: &mut TermWindow,
+    ) -> anyhow::Result<Ref<'_, [ComputedElement]>> {
+        if self.element.borrow().is_none() {
+            let (element, cards, columns) = self.compute(term_window)?;
+            self.element.borrow_mut().replace(element);
+            *self.cards.borrow_mut() = cards;
+            *self.columns.borrow_mut() = columns;
+        }
+        Ok(Ref::map(self.element.borrow(), |v| {
+            v.as_ref().unwrap().as_slice()
+        }))
+    }
+
+    fn reconfigure(&self, _term_window: &mut TermWindow) {
+        self.element.borrow_mut().take();
+    }
+}
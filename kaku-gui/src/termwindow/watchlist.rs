@@ -0,0 +1,186 @@
+//! Raises a toast when a pane's title or working directory starts
+//! matching one of the `pane_watchlist` rules.
+use crate::frontend::front_end;
+use crate::termwindow::TermWindow;
+use config::{ConfigHandle, PaneWatchRule};
+use fancy_regex::Regex;
+use mux::pane::{CachePolicy, PaneId};
+use mux::Mux;
+
+struct CompiledRule {
+    title: Option<Regex>,
+    cwd: Option<Regex>,
+    message: Option<String>,
+}
+
+/// The watchlist from a given config generation, with its regexes compiled
+pub struct CompiledWatchlist {
+    generation: usize,
+    rules: Vec<CompiledRule>,
+}
+
+impl CompiledWatchlist {
+    fn new(config: &ConfigHandle) -> Self {
+        Self::with_rules(config.generation(), &config.pane_watchlist)
+    }
+
+    fn with_rules(generation: usize, rules: &[PaneWatchRule]) -> Self {
+        fn compile(pattern: &Option<String>) -> anyhow::Result<Option<Regex>> {
+            pattern
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .map_err(Into::into)
+        }
+
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match (compile(&rule.title), compile(&rule.cwd)) {
+                (Ok(None), Ok(None)) => None,
+                (Ok(title), Ok(cwd)) => Some(CompiledRule {
+                    title,
+                    cwd,
+                    message: rule.message.clone(),
+                }),
+                (Err(err), _) | (_, Err(err)) => {
+                    log::error!("pane_watchlist: ignoring rule {rule:?}: {err:#}");
+                    None
+                }
+            })
+            .collect();
+
+        Self { generation, rules }
+    }
+
+    /// Returns the index of the first rule matching both `title` and `cwd`
+    fn matching_rule(&self, title: &str, cwd: &str) -> Option<usize> {
+        let matches = |re: &Option<Regex>, text: &str| {
+            re.as_ref()
+                .is_none_or(|re| re.is_match(text).unwrap_or(false))
+        };
+        self.rules
+            .iter()
+            .position(|rule| matches(&rule.title, title) && matches(&rule.cwd, cwd))
+    }
+}
+
+impl TermWindow {
+    /// Called when a pane's title or cwd changes.  Only the transition
+    /// into a matching rule is reported, so a title that stays the same
+    /// across several updates toasts once.
+    pub(crate) fn check_pane_watchlist(&mut self, pane_id: PaneId) {
+        if self.config.pane_watchlist.is_empty() || !self.window_contains_pane(pane_id) {
+            return;
+        }
+        let generation = self.config.generation();
+        if self
+            .pane_watchlist
+            .as_ref()
+            .is_none_or(|watchlist| watchlist.generation != generation)
+        {
+            self.pane_watchlist = Some(CompiledWatchlist::new(&self.config));
+        }
+
+        let Some(pane) = Mux::get().get_pane(pane_id) else {
+            return;
+        };
+        let title = pane.get_title();
+        let cwd = pane
+            .get_current_working_dir(CachePolicy::AllowStale)
+            .map(|url| match url.to_file_path() {
+                Ok(path) => path.display().to_string(),
+                Err(_) => url.to_string(),
+            })
+            .unwrap_or_default();
+
+        let Some(watchlist) = self.pane_watchlist.as_ref() else {
+            return;
+        };
+        let matched = watchlist.matching_rule(&title, &cwd);
+        let previous = std::mem::replace(&mut self.pane_state(pane_id).watch_rule, matched);
+        let Some(rule_idx) = matched.filter(|&idx| previous != Some(idx)) else {
+            return;
+        };
+        let message = watchlist.rules[rule_idx]
+            .message
+            .clone()
+            .unwrap_or_else(|| title.clone());
+        self.show_toast(message);
+
+        // Mark the tab in the same way as an unread bell
+        let is_inactive = self
+            .get_active_pane_or_overlay()
+            .is_none_or(|p| p.pane_id() != pane_id);
+        if is_inactive {
+            let mut state = self.pane_state(pane_id);
+            if !state.has_unread_bell {
+                state.has_unread_bell = true;
+                drop(state);
+                front_end().adjust_unread_bell_count(1);
+            }
+        }
+        if let Some(window) = self.window.as_ref() {
+            window.invalidate();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rule(title: Option<&str>, cwd: Option<&str>) -> PaneWatchRule {
+        PaneWatchRule {
+            title: title.map(String::from),
+            cwd: cwd.map(String::from),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn rule_matching() {
+        let watchlist = CompiledWatchlist::with_rules(
+            0,
+            &[
+                rule(Some("^ERROR"), None),
+                rule(None, Some("/prod$")),
+                rule(Some("deploy"), Some("^/srv/")),
+            ],
+        );
+        assert_eq!(watchlist.matching_rule("ERROR: disk full", "/tmp"), Some(0));
+        assert_eq!(watchlist.matching_rule("vim", "/home/prod"), Some(1));
+        assert_eq!(watchlist.matching_rule("deploy", "/srv/app"), Some(2));
+        // Both patterns of a rule have to match
+        assert_eq!(watchlist.matching_rule("deploy", "/home"), None);
+        assert_eq!(watchlist.matching_rule("an ERROR", "/tmp"), None);
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let watchlist = CompiledWatchlist::with_rules(
+            0,
+            &[
+                rule(Some("build"), Some("/work")),
+                rule(Some("build"), None),
+                rule(Some(".*"), None),
+            ],
+        );
+        assert_eq!(watchlist.matching_rule("build", "/work"), Some(0));
+        assert_eq!(watchlist.matching_rule("build", "/home"), Some(1));
+        assert_eq!(watchlist.matching_rule("other", "/work"), Some(2));
+    }
+
+    #[test]
+    fn invalid_and_empty_rules_are_skipped() {
+        let watchlist = CompiledWatchlist::with_rules(
+            0,
+            &[
+                rule(Some("("), None),
+                rule(None, None),
+                rule(Some("x"), None),
+            ],
+        );
+        assert_eq!(watchlist.rules.len(), 1);
+        assert_eq!(watchlist.matching_rule("x", ""), Some(0));
+    }
+}
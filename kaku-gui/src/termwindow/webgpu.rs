@@ -21,6 +21,162 @@ pub struct ShaderUniform {
     // sampler2D atlas_linear_sampler;
 }
 
+/// The uniforms available to a custom background shader
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BackgroundShaderUniform {
+    pub resolution: [f32; 2],
+    pub time: f32,
+    pub _padding: f32,
+}
+
+/// Wraps the user supplied `background` function with a vertex shader
+/// that covers the window, and the only bindings it is given.
+/// `KAKU_LINEAR_BLENDING` is defined when the shader is loaded.
+const BACKGROUND_SHADER_PRELUDE: &str = r#"
+struct BackgroundUniform {
+  resolution: vec2<f32>,
+  time: f32,
+  _padding: f32,
+};
+@group(0) @binding(0) var<uniform> bg: BackgroundUniform;
+
+struct BackgroundVertex {
+  @builtin(position) position: vec4<f32>,
+  @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn kaku_background_vs(@builtin(vertex_index) index: u32) -> BackgroundVertex {
+  // A single triangle that covers the whole viewport
+  let pos = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+  var out: BackgroundVertex;
+  out.position = vec4<f32>(pos * 2.0 - 1.0, 0.0, 1.0);
+  out.uv = vec2<f32>(pos.x, 1.0 - pos.y);
+  return out;
+}
+
+fn kaku_background_to_srgb(c: vec4<f32>) -> vec4<f32> {
+  let cutoff = c.rgb < vec3<f32>(0.0031308);
+  let higher = vec3<f32>(1.055) * pow(c.rgb, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+  let lower = c.rgb * vec3<f32>(12.92);
+  return vec4<f32>(select(higher, lower, cutoff), c.a);
+}
+
+@fragment
+fn kaku_background_fs(in: BackgroundVertex) -> @location(0) vec4<f32> {
+  let color = background(in.uv);
+  // Match shader.wgsl: without linear blending, the surface doesn't
+  // encode to sRGB for us
+  if !KAKU_LINEAR_BLENDING {
+    return kaku_background_to_srgb(color);
+  }
+  return color;
+}
+"#;
+
+/// Returns true if the `background` function, or a function that it
+/// calls, reads `bg.time`, so that the shader is animated
+fn background_uses_time(module: &naga::Module) -> bool {
+    use naga::Expression;
+
+    let Some((bg, _)) = module
+        .global_variables
+        .iter()
+        .find(|(_, var)| var.name.as_deref() == Some("bg"))
+    else {
+        return false;
+    };
+    let mut pending: Vec<_> = module
+        .functions
+        .iter()
+        .filter(|(_, func)| func.name.as_deref() == Some("background"))
+        .map(|(handle, _)| handle)
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    while let Some(handle) = pending.pop() {
+        if !seen.insert(handle) {
+            continue;
+        }
+        let func = &module.functions[handle];
+        let is_bg = |expr: naga::Handle<Expression>| match func.expressions[expr] {
+            Expression::GlobalVariable(var) => var == bg,
+            _ => false,
+        };
+        for (_, expr) in func.expressions.iter() {
+            match *expr {
+                // time is the second member of BackgroundUniform
+                Expression::AccessIndex { base, index: 1 } if is_bg(base) => return true,
+                // A copy of the whole uniform may be used for anything
+                Expression::Load { pointer } if is_bg(pointer) => return true,
+                _ => {}
+            }
+        }
+        called_functions(&func.body, &mut pending);
+    }
+    false
+}
+
+fn called_functions(block: &naga::Block, calls: &mut Vec<naga::Handle<naga::Function>>) {
+    use naga::Statement;
+
+    for statement in block.iter() {
+        match statement {
+            Statement::Call { function, .. } => calls.push(*function),
+            Statement::Block(block) => called_functions(block, calls),
+            Statement::If { accept, reject, .. } => {
+                called_functions(accept, calls);
+                called_functions(reject, calls);
+            }
+            Statement::Switch { cases, .. } => {
+                for case in cases {
+                    called_functions(&case.body, calls);
+                }
+            }
+            Statement::Loop {
+                body, continuing, ..
+            } => {
+                called_functions(body, calls);
+                called_functions(continuing, calls);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A user supplied shader that is drawn over the window background.
+/// It runs in a render pass of its own and is bound to nothing but
+/// its uniforms, so it can neither read nor disturb the rest of the frame.
+pub struct BackgroundShader {
+    pub pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    /// Whether the shader depends on the time, and so needs to be
+    /// redrawn continuously
+    pub animated: bool,
+}
+
+impl BackgroundShader {
+    pub fn create_uniform(
+        &self,
+        device: &wgpu::Device,
+        uniform: BackgroundShaderUniform,
+    ) -> wgpu::BindGroup {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("BackgroundShaderUniform Buffer"),
+            contents: bytemuck::cast_slice(&[uniform]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+            label: Some("BackgroundShaderUniform Bind Group"),
+        })
+    }
+}
+
 pub struct WebGpuState {
     pub adapter_info: wgpu::AdapterInfo,
     pub downlevel_caps: wgpu::DownlevelCapabilities,
@@ -35,6 +191,7 @@ pub struct WebGpuState {
     pub texture_nearest_sampler: wgpu::Sampler,
     pub texture_linear_sampler: wgpu::Sampler,
     pub handle: RawHandlePair,
+    pub background_shader: RefCell<Option<BackgroundShader>>,
 }
 
 pub struct RawHandlePair {
@@ -242,6 +399,12 @@ impl WebGpuState {
                     continue;
                 }
 
+                if !adapter_info_to_gpu_info(a.get_info())
+                    .is_allowed_by(&config.webgpu_adapter_allowlist)
+                {
+                    continue;
+                }
+
                 let info = a.get_info();
 
                 if preference.name != info.name {
@@ -304,6 +467,21 @@ impl WebGpuState {
             );
         }
 
+        if let Some(a) = &adapter {
+            let info = adapter_info_to_gpu_info(a.get_info());
+            if !info.is_allowed_by(&config.webgpu_adapter_allowlist) {
+                log::warn!(
+                    "{} is not in webgpu_adapter_allowlist; looking for another adapter",
+                    info.to_string()
+                );
+                adapter = instance.enumerate_adapters(backends).into_iter().find(|a| {
+                    a.is_surface_supported(&surface)
+                        && adapter_info_to_gpu_info(a.get_info())
+                            .is_allowed_by(&config.webgpu_adapter_allowlist)
+                });
+            }
+        }
+
         let adapter = adapter.ok_or_else(|| {
             let adapters = compute_compatibility_list(&instance, backends, &surface);
             anyhow!(
@@ -338,13 +516,24 @@ impl WebGpuState {
 
         let queue = Arc::new(queue);
 
-        // Explicitly request an SRGB format, if available
-        let pref_format_srgb = caps.formats[0].add_srgb_suffix();
-        let format = if caps.formats.contains(&pref_format_srgb) {
-            pref_format_srgb
+        // Explicitly request an SRGB format, if available, so that the
+        // hardware blends in linear space.  Otherwise, prefer a format
+        // without the SRGB suffix, and encode the colors in the shader.
+        let format = if config.linear_blending {
+            let pref_format_srgb = caps.formats[0].add_srgb_suffix();
+            if caps.formats.contains(&pref_format_srgb) {
+                pref_format_srgb
+            } else {
+                caps.formats[0]
+            }
         } else {
-            caps.formats[0]
+            caps.formats
+                .iter()
+                .copied()
+                .find(|format| !format.is_srgb())
+                .unwrap_or(caps.formats[0])
         };
+        let linear_blending = format.is_srgb();
 
         // Need to check that this is supported, as trying to set
         // view_formats without it will cause surface.configure
@@ -383,7 +572,16 @@ impl WebGpuState {
         };
         surface.configure(&device, &config);
 
-        let shader = device.create_shader_module(wgpu::include_wgsl!("../shader.wgsl"));
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shader.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(
+                format!(
+                    "const LINEAR_BLENDING: bool = {linear_blending};\n{}",
+                    include_str!("../shader.wgsl")
+                )
+                .into(),
+            ),
+        });
 
         let shader_uniform_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -506,6 +704,114 @@ impl WebGpuState {
             texture_bind_group_layout,
             texture_nearest_sampler,
             texture_linear_sampler,
+            background_shader: RefCell::new(None),
+        })
+    }
+
+    /// Load `window_background_shader`, replacing any previously loaded
+    /// background shader.  Problems with the shader are logged, and
+    /// leave the window without a background shader.
+    pub async fn load_background_shader(&self, config: &ConfigHandle) {
+        let shader = match &config.window_background_shader {
+            Some(path) if config.enable_custom_shaders => match std::fs::read_to_string(path) {
+                Ok(source) => match self.compile_background_shader(&source).await {
+                    Ok(shader) => Some(shader),
+                    Err(err) => {
+                        log::error!("window_background_shader {}: {:#}", path.display(), err);
+                        None
+                    }
+                },
+                Err(err) => {
+                    log::error!("window_background_shader {}: {:#}", path.display(), err);
+                    None
+                }
+            },
+            _ => None,
+        };
+        self.background_shader.replace(shader);
+    }
+
+    async fn compile_background_shader(&self, source: &str) -> anyhow::Result<BackgroundShader> {
+        // Problems with the shader would otherwise be fatal, so collect
+        // them here instead
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let linear_blending = self.config.borrow().format.is_srgb();
+        let source = format!(
+            "const KAKU_LINEAR_BLENDING: bool = {linear_blending};\n\
+             {BACKGROUND_SHADER_PRELUDE}\n{source}"
+        );
+        // A shader that fails to parse is reported by the validation below
+        let animated =
+            naga::front::wgsl::parse_str(&source).is_ok_and(|module| background_uses_time(&module));
+
+        let module = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("window_background_shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+
+        let bind_group_layout =
+            self.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                    label: Some("BackgroundShaderUniform bind group layout"),
+                });
+
+        let layout = self
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Background Pipeline Layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let pipeline = self
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Background Pipeline"),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: Some("kaku_background_vs"),
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: Some("kaku_background_fs"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: self.config.borrow().format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            });
+
+        if let Some(err) = self.device.pop_error_scope().await {
+            anyhow::bail!("{err}");
+        }
+
+        Ok(BackgroundShader {
+            pipeline,
+            bind_group_layout,
+            animated,
         })
     }
 
@@ -550,3 +856,41 @@ impl WebGpuState {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn uses_time(source: &str) -> bool {
+        let source = format!(
+            "const KAKU_LINEAR_BLENDING: bool = true;\n{BACKGROUND_SHADER_PRELUDE}\n{source}"
+        );
+        background_uses_time(&naga::front::wgsl::parse_str(&source).unwrap())
+    }
+
+    #[test]
+    fn detect_time_uniform() {
+        assert!(!uses_time(
+            "fn background(uv: vec2<f32>) -> vec4<f32> {
+               // bg.time is only mentioned here
+               return vec4<f32>(uv / bg.resolution, 0.0, 1.0);
+             }"
+        ));
+        assert!(uses_time(
+            "fn pulse() -> f32 { return sin(bg.time); }
+             fn background(uv: vec2<f32>) -> vec4<f32> {
+               if uv.x > 0.5 {
+                 return vec4<f32>(pulse(), 0.0, 0.0, 1.0);
+               }
+               return vec4<f32>(0.0);
+             }"
+        ));
+        // Unused functions don't count
+        assert!(!uses_time(
+            "fn pulse() -> f32 { return sin(bg.time); }
+             fn background(uv: vec2<f32>) -> vec4<f32> {
+               return vec4<f32>(uv, 0.0, 1.0);
+             }"
+        ));
+    }
+}
@@ -161,6 +161,10 @@ impl UtilSprites {
         buffer.clear_rect(cell_rect, black);
         let white_space = glyph_cache.atlas.allocate(&buffer)?;
 
+        // These are held for as long as the atlas, so they must not be evicted
+        glyph_cache.atlas.pin(&filled_box);
+        glyph_cache.atlas.pin(&white_space);
+
         Ok(Self {
             white_space,
             filled_box,
@@ -0,0 +1,89 @@
+//! `kaku askpass`: an `SSH_ASKPASS` / `GIT_ASKPASS` compatible helper.
+//!
+//! The prompt is shown in an overlay over the Kaku pane that the asking
+//! program was started from, and the secret that is entered is printed
+//! to stdout.  When that isn't possible, for example because the program
+//! wasn't started from a Kaku pane, we fall back to prompting on the
+//! controlling terminal, if there is one.
+use anyhow::Context;
+use clap::Parser;
+use mux::pane::PaneId;
+use std::io::Write;
+use wezterm_client::client::Client;
+
+#[derive(Debug, Parser, Clone)]
+pub struct AskPassCommand {
+    /// The prompt to show, as passed by ssh or git
+    #[arg(default_value = "Password:")]
+    prompt: String,
+}
+
+impl AskPassCommand {
+    pub async fn run(self, connect: impl FnOnce() -> anyhow::Result<Client>) -> anyhow::Result<()> {
+        let secret = match self.request_from_pane(connect).await {
+            Ok(Some(secret)) => secret,
+            Ok(None) => anyhow::bail!("cancelled"),
+            Err(err) => {
+                log::debug!("unable to prompt in a pane: {err:#}");
+                prompt_on_tty(&self.prompt)
+                    .context("no Kaku pane or terminal to prompt for the secret")?
+            }
+        };
+        let mut stdout = std::io::stdout();
+        writeln!(stdout, "{secret}")?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    async fn request_from_pane(
+        &self,
+        connect: impl FnOnce() -> anyhow::Result<Client>,
+    ) -> anyhow::Result<Option<String>> {
+        let pane_id: PaneId = std::env::var("WEZTERM_PANE")
+            .context("WEZTERM_PANE is not set")?
+            .parse()
+            .context("parsing WEZTERM_PANE")?;
+        let client = connect()?;
+        let response = client
+            .request_secret(codec::RequestSecret {
+                pane_id,
+                prompt: self.prompt.clone(),
+            })
+            .await?;
+        Ok(response.secret)
+    }
+}
+
+/// Prompts on the controlling terminal with echo disabled
+#[cfg(unix)]
+fn prompt_on_tty(prompt: &str) -> anyhow::Result<String> {
+    use std::io::BufRead;
+    use std::os::unix::io::AsRawFd;
+
+    let tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .context("opening /dev/tty")?;
+    let fd = tty.as_raw_fd();
+    let mut termios = termios::Termios::from_fd(fd)?;
+    let original = termios;
+
+    write!(&tty, "{prompt} ")?;
+    termios.c_lflag &= !termios::ECHO;
+    termios::tcsetattr(fd, termios::TCSANOW, &termios)?;
+
+    let mut line = String::new();
+    let result = std::io::BufReader::new(&tty).read_line(&mut line);
+
+    termios::tcsetattr(fd, termios::TCSANOW, &original)?;
+    writeln!(&tty)?;
+
+    result?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(not(unix))]
+fn prompt_on_tty(_prompt: &str) -> anyhow::Result<String> {
+    anyhow::bail!("prompting on the terminal is not supported on this system")
+}
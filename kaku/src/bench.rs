@@ -0,0 +1,260 @@
+//! `kaku bench`: synthetic throughput and latency measurements, so that
+//! performance changes can be compared with numbers rather than by feel.
+//!
+//! Throughput is measured by feeding generated output through the same
+//! terminal model that panes use, in process.  Input latency is measured
+//! against the running gui or mux server, using a scratch pane that
+//! echoes back what is written to it.
+use anyhow::Context;
+use clap::{Parser, ValueEnum};
+use config::keyassignment::SpawnTabDomain;
+use portable_pty::cmdbuilder::CommandBuilder;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wezterm_client::client::Client;
+use wezterm_term::{StableRowIndex, Terminal, TerminalSize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Workload {
+    /// Plain printable ASCII
+    Ascii,
+    /// Double width CJK text
+    Cjk,
+    /// Short runs of text, each with its own SGR color and attributes
+    Sgr,
+}
+
+#[derive(Debug, Parser, Clone)]
+pub struct BenchCommand {
+    /// How many MiB of output to generate for each workload
+    #[arg(long, default_value = "16")]
+    megabytes: usize,
+
+    /// Only run these workloads; the default is to run all of them
+    #[arg(long, value_enum)]
+    workload: Vec<Workload>,
+
+    /// Also measure input latency against the running Kaku instance
+    #[arg(long)]
+    latency: bool,
+
+    /// How many keystrokes to time when measuring latency
+    #[arg(long, default_value = "200")]
+    samples: usize,
+}
+
+const COLS: usize = 80;
+const ROWS: usize = 24;
+/// The size of the chunks passed to the parser, similar to a pty read
+const CHUNK: usize = 64 * 1024;
+
+fn generate(workload: Workload, len: usize) -> Vec<u8> {
+    let mut out = String::with_capacity(len + COLS * 4);
+    let mut line = 0usize;
+    while out.len() < len {
+        match workload {
+            Workload::Ascii => {
+                for col in 0..COLS - 1 {
+                    out.push((b'!' + ((line + col) % 94) as u8) as char);
+                }
+            }
+            Workload::Cjk => {
+                const TEXT: &[char] = &['漢', '字', '你', '好', '世', '界', 'か', 'ナ', '한', '국'];
+                for col in 0..(COLS - 1) / 2 {
+                    out.push(TEXT[(line + col) % TEXT.len()]);
+                }
+            }
+            Workload::Sgr => {
+                for word in 0..(COLS - 1) / 8 {
+                    let color = (line * 7 + word) % 256;
+                    let attr = [1, 3, 4, 7][word % 4];
+                    out.push_str(&format!("\x1b[{attr};38;5;{color}mword{word:03}\x1b[0m "));
+                }
+            }
+        }
+        out.push_str("\r\n");
+        line += 1;
+    }
+    out.into_bytes()
+}
+
+fn run_throughput(workload: Workload, megabytes: usize, config: &config::ConfigHandle) {
+    let data = generate(workload, megabytes * 1024 * 1024);
+    let mut terminal = Terminal::new(
+        TerminalSize {
+            rows: ROWS,
+            cols: COLS,
+            ..Default::default()
+        },
+        Arc::new(config::TermConfig::with_config(config.clone())),
+        "kaku-bench",
+        config::wezterm_version(),
+        Box::new(std::io::sink()),
+    );
+
+    let start = Instant::now();
+    for chunk in data.chunks(CHUNK) {
+        terminal.advance_bytes(chunk);
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    let lines = data.iter().filter(|&&b| b == b'\n').count();
+
+    println!(
+        "{:<6} {:>8.1} MiB/s {:>10.0} lines/s  ({} MiB in {:.2}s)",
+        format!("{workload:?}").to_lowercase(),
+        data.len() as f64 / (1024. * 1024.) / elapsed,
+        lines as f64 / elapsed,
+        megabytes,
+        elapsed
+    );
+}
+
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    let idx = ((pct / 100.) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[idx.min(sorted.len() - 1)]
+}
+
+/// Returns true once a line on the visible screen of `pane_id` reads `marker`
+async fn screen_shows(
+    client: &Client,
+    pane_id: mux::pane::PaneId,
+    marker: &str,
+) -> anyhow::Result<bool> {
+    let info = client
+        .get_dimensions(codec::GetPaneRenderableDimensions { pane_id })
+        .await?;
+    let top = info.dimensions.physical_top;
+    let lines = client
+        .get_lines(codec::GetLines {
+            pane_id,
+            lines: vec![top..top + info.dimensions.viewport_rows as StableRowIndex],
+        })
+        .await?;
+    Ok(lines
+        .lines
+        .extract_data()
+        .0
+        .iter()
+        .any(|(_, line)| line.as_str().trim_end() == marker))
+}
+
+async fn run_latency(
+    client: Client,
+    samples: usize,
+    config: &config::ConfigHandle,
+) -> anyhow::Result<()> {
+    // Raw mode, so that each write is echoed as soon as cat reads it
+    let command = CommandBuilder::from_argv(vec![
+        "sh".into(),
+        "-c".into(),
+        "stty raw -echo; exec cat".into(),
+    ]);
+    let spawned = client
+        .spawn_v2(codec::SpawnV2 {
+            domain: SpawnTabDomain::DefaultDomain,
+            window_id: None,
+            command: Some(command),
+            command_dir: None,
+            size: config.initial_size(0, None),
+            // A separate workspace keeps the scratch window out of sight
+            workspace: "kaku-bench".to_string(),
+        })
+        .await?;
+    let pane_id = spawned.pane_id;
+    // Give the shell a moment to switch the tty into raw mode
+    smol::Timer::after(Duration::from_millis(300)).await;
+
+    let mut timings = Vec::with_capacity(samples);
+    let result: anyhow::Result<()> = async {
+        for n in 0..samples {
+            let marker = format!("kaku-bench-{n}");
+            let start = Instant::now();
+            client
+                .write_to_pane(codec::WriteToPane {
+                    pane_id,
+                    data: format!("{marker}\r\n").into_bytes(),
+                })
+                .await?;
+            while !screen_shows(&client, pane_id, &marker).await? {
+                anyhow::ensure!(
+                    start.elapsed() < Duration::from_secs(5),
+                    "timed out waiting for the pane to echo {marker}"
+                );
+            }
+            timings.push(start.elapsed());
+        }
+        Ok(())
+    }
+    .await;
+    client
+        .kill_pane(codec::KillPane { pane_id })
+        .await
+        .context("closing the benchmark pane")?;
+    result?;
+
+    timings.sort();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.;
+    println!(
+        "input latency over {} samples: p50 {:.2}ms  p90 {:.2}ms  p99 {:.2}ms  max {:.2}ms",
+        timings.len(),
+        ms(percentile(&timings, 50.)),
+        ms(percentile(&timings, 90.)),
+        ms(percentile(&timings, 99.)),
+        ms(*timings.last().unwrap_or(&Duration::ZERO)),
+    );
+    println!("(includes one round trip to the mux to observe the echo)");
+    Ok(())
+}
+
+impl BenchCommand {
+    pub async fn run(
+        self,
+        opts: &crate::Opt,
+        connect: impl FnOnce() -> anyhow::Result<Client>,
+    ) -> anyhow::Result<()> {
+        let config = crate::init_config(opts)?;
+        let workloads = if self.workload.is_empty() {
+            vec![Workload::Ascii, Workload::Cjk, Workload::Sgr]
+        } else {
+            self.workload.clone()
+        };
+
+        println!(
+            "parse throughput ({COLS}x{ROWS}, {} KiB chunks):",
+            CHUNK / 1024
+        );
+        for workload in workloads {
+            run_throughput(workload, self.megabytes.max(1), &config);
+        }
+
+        if self.latency {
+            println!();
+            anyhow::ensure!(self.samples > 0, "--samples must be at least 1");
+            run_latency(connect()?, self.samples, &config).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generated_lines_fit_the_screen() {
+        for workload in [Workload::Ascii, Workload::Cjk, Workload::Sgr] {
+            let data = String::from_utf8(generate(workload, 4096)).unwrap();
+            assert!(data.len() >= 4096);
+            for line in data.split("\r\n").filter(|l| !l.is_empty()) {
+                let visible: usize = if workload == Workload::Sgr {
+                    line.split('\x1b')
+                        .map(|part| part.split_once('m').map_or(part, |(_, t)| t).len())
+                        .sum()
+                } else {
+                    line.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+                };
+                assert!(visible < COLS, "{workload:?}: {line:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use clap::Parser;
+use mux::pane::PaneId;
+use std::io::Write;
+use wezterm_client::client::Client;
+
+#[derive(Debug, Parser, Clone)]
+pub struct GetRawOutput {
+    /// Specify the target pane.
+    /// The default is to use the current pane based on the
+    /// environment variable WEZTERM_PANE.
+    #[arg(long)]
+    pane_id: Option<PaneId>,
+
+    /// The maximum number of bytes to output, counting back from
+    /// the most recent output.
+    /// The default is to output everything that has been retained.
+    #[arg(long)]
+    bytes: Option<usize>,
+}
+
+impl GetRawOutput {
+    pub async fn run(self, client: Client) -> anyhow::Result<()> {
+        let pane_id = client.resolve_pane_id(self.pane_id).await?;
+        let response = client
+            .get_raw_output(codec::GetRawOutput {
+                pane_id,
+                max_bytes: self.bytes.unwrap_or(usize::MAX),
+            })
+            .await?;
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&response.data)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
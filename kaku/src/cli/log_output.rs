@@ -0,0 +1,47 @@
+use clap::{Parser, ValueHint};
+use mux::pane::PaneId;
+use std::path::PathBuf;
+use wezterm_client::client::Client;
+
+#[derive(Debug, Parser, Clone)]
+pub struct LogOutput {
+    /// Specify the target pane.
+    /// The default is to use the current pane based on the
+    /// environment variable WEZTERM_PANE.
+    #[arg(long)]
+    pane_id: Option<PaneId>,
+
+    /// Also record the input that is sent to the pane
+    #[arg(long, conflicts_with = "stop")]
+    include_input: bool,
+
+    /// Stop logging the pane
+    #[arg(long, conflicts_with = "path")]
+    stop: bool,
+
+    /// The file to append the raw output to.
+    /// The timing information is written alongside it with a
+    /// `.timing` extension appended, in the format used by
+    /// `scriptreplay`.
+    #[arg(required_unless_present = "stop", value_hint=ValueHint::FilePath)]
+    path: Option<PathBuf>,
+}
+
+impl LogOutput {
+    pub async fn run(self, client: Client) -> anyhow::Result<()> {
+        let pane_id = client.resolve_pane_id(self.pane_id).await?;
+        // The mux server may have a different working directory
+        let path = match self.path {
+            Some(path) => Some(std::env::current_dir()?.join(path)),
+            None => None,
+        };
+        client
+            .set_pane_output_logging(codec::SetPaneOutputLogging {
+                pane_id,
+                path,
+                include_input: self.include_input,
+            })
+            .await?;
+        Ok(())
+    }
+}
@@ -8,10 +8,12 @@ mod activate_pane_direction;
 mod activate_tab;
 mod adjust_pane_size;
 mod get_pane_direction;
+mod get_raw_output;
 mod get_text;
 mod kill_pane;
 mod list;
 mod list_clients;
+mod log_output;
 mod move_pane_to_new_tab;
 mod proxy;
 mod rename_workspace;
@@ -121,6 +123,17 @@ Outputs the pane-id for the newly created pane on success"
     #[command(name = "get-text", rename_all = "kebab")]
     GetText(get_text::GetText),
 
+    /// Retrieves the raw bytes most recently output by a pane, before
+    /// they were decoded, and writes them to stdout.
+    /// Requires `raw_output_retention_bytes` to be set in the config.
+    #[command(name = "get-raw-output", rename_all = "kebab")]
+    GetRawOutput(get_raw_output::GetRawOutput),
+
+    /// Start or stop appending the raw output of a pane to a file,
+    /// along with timing information, like `script(1)`
+    #[command(name = "log-output", rename_all = "kebab")]
+    LogOutput(log_output::LogOutput),
+
     /// Activate an adjacent pane in the specified direction.
     #[command(name = "activate-pane-direction", rename_all = "kebab")]
     ActivatePaneDirection(activate_pane_direction::ActivatePaneDirection),
@@ -165,19 +178,21 @@ Outputs the pane-id for the newly created pane on success"
     ZoomPane(zoom_pane::ZoomPane),
 }
 
-async fn run_cli_async(opts: &crate::Opt, cli: CliCommand) -> anyhow::Result<()> {
+fn connect(no_auto_start: bool, prefer_mux: bool, class: Option<&str>) -> anyhow::Result<Client> {
     let mut ui = mux::connui::ConnectionUI::new_headless();
     let initial = true;
 
-    let client = Client::new_default_unix_domain(
+    Client::new_default_unix_domain(
         initial,
         &mut ui,
-        cli.no_auto_start,
-        cli.prefer_mux,
-        cli.class
-            .as_deref()
-            .unwrap_or(wezterm_gui_subcommands::DEFAULT_WINDOW_CLASS),
-    )?;
+        no_auto_start,
+        prefer_mux,
+        class.unwrap_or(wezterm_gui_subcommands::DEFAULT_WINDOW_CLASS),
+    )
+}
+
+async fn run_cli_async(opts: &crate::Opt, cli: CliCommand) -> anyhow::Result<()> {
+    let client = connect(cli.no_auto_start, cli.prefer_mux, cli.class.as_deref())?;
 
     match cli.sub {
         CliSubCommand::ListClients(cmd) => cmd.run(client).await,
@@ -186,6 +201,8 @@ async fn run_cli_async(opts: &crate::Opt, cli: CliCommand) -> anyhow::Result<()>
         CliSubCommand::SplitPane(cmd) => cmd.run(client).await,
         CliSubCommand::SendText(cmd) => cmd.run(client).await,
         CliSubCommand::GetText(cmd) => cmd.run(client).await,
+        CliSubCommand::GetRawOutput(cmd) => cmd.run(client).await,
+        CliSubCommand::LogOutput(cmd) => cmd.run(client).await,
         CliSubCommand::SpawnCommand(cmd) => cmd.run(client, &crate::init_config(opts)?).await,
         CliSubCommand::Proxy(cmd) => cmd.run(client, &crate::init_config(opts)?).await,
         CliSubCommand::TlsCreds(cmd) => cmd.run(client).await,
@@ -210,6 +227,63 @@ pub fn run_cli(opts: &crate::Opt, cli: CliCommand) -> anyhow::Result<()> {
     }
 }
 
+/// Runs `kaku record` against the running gui or mux server
+pub fn run_record(cmd: crate::record::RecordCommand) -> anyhow::Result<()> {
+    let executor = promise::spawn::ScopedExecutor::new();
+    match promise::spawn::block_on(executor.run(async move {
+        let client = connect(false, false, None)?;
+        cmd.run(client).await
+    })) {
+        Ok(_) => Ok(()),
+        Err(err) => crate::terminate_with_error(err),
+    }
+}
+
+/// Runs `kaku askpass`; the mux is not started if it isn't running,
+/// as the secret is then prompted for on the terminal instead
+pub fn run_askpass(cmd: crate::askpass::AskPassCommand) -> anyhow::Result<()> {
+    let executor = promise::spawn::ScopedExecutor::new();
+    match promise::spawn::block_on(
+        executor.run(async move { cmd.run(|| connect(true, false, None)).await }),
+    ) {
+        Ok(_) => Ok(()),
+        Err(err) => crate::terminate_with_error(err),
+    }
+}
+
+/// Runs `kaku replay`; only `--new-tab` needs to talk to the mux
+pub fn run_replay(opts: &crate::Opt, cmd: crate::record::ReplayCommand) -> anyhow::Result<()> {
+    let executor = promise::spawn::ScopedExecutor::new();
+    match promise::spawn::block_on(
+        executor.run(async move { cmd.run(opts, || connect(false, false, None)).await }),
+    ) {
+        Ok(_) => Ok(()),
+        Err(err) => crate::terminate_with_error(err),
+    }
+}
+
+/// Runs `kaku tail`; only `--new-tab` needs to talk to the mux
+pub fn run_tail(opts: &crate::Opt, cmd: crate::tail::TailCommand) -> anyhow::Result<()> {
+    let executor = promise::spawn::ScopedExecutor::new();
+    match promise::spawn::block_on(
+        executor.run(async move { cmd.run(opts, || connect(false, false, None)).await }),
+    ) {
+        Ok(_) => Ok(()),
+        Err(err) => crate::terminate_with_error(err),
+    }
+}
+
+/// Runs `kaku bench`; only `--latency` needs to talk to the mux
+pub fn run_bench(opts: &crate::Opt, cmd: crate::bench::BenchCommand) -> anyhow::Result<()> {
+    let executor = promise::spawn::ScopedExecutor::new();
+    match promise::spawn::block_on(
+        executor.run(async move { cmd.run(opts, || connect(false, false, None)).await }),
+    ) {
+        Ok(_) => Ok(()),
+        Err(err) => crate::terminate_with_error(err),
+    }
+}
+
 pub fn resolve_relative_cwd(cwd: Option<OsString>) -> anyhow::Result<Option<String>> {
     match cwd {
         None => Ok(None),
@@ -0,0 +1,99 @@
+use clap::Parser;
+use mux::command_history::CommandHistoryEntry;
+use serde::Serializer as _;
+use tabout::{tabulate_output, Alignment, Column};
+
+#[derive(Debug, Parser, Clone)]
+pub struct CommandHistoryCommand {
+    /// Which workspace's history to show
+    #[arg(long, default_value = mux::DEFAULT_WORKSPACE)]
+    workspace: String,
+
+    /// Show at most this many of the most recent commands.
+    /// Use 0 to show them all.
+    #[arg(long, default_value_t = 50)]
+    limit: usize,
+
+    /// Only show commands that exited with a non-zero status
+    #[arg(long)]
+    failed: bool,
+
+    /// Only show commands whose command line contains this text
+    #[arg(long)]
+    search: Option<String>,
+
+    /// Output JSON rather than a table
+    #[arg(long)]
+    json: bool,
+}
+
+impl CommandHistoryCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let entries = mux::command_history::load(&self.workspace)?;
+        let mut entries: Vec<&CommandHistoryEntry> = entries
+            .iter()
+            .filter(|entry| !self.failed || entry.exit_status.is_some_and(|status| status != 0))
+            .filter(|entry| match &self.search {
+                Some(search) => entry.command.contains(search.as_str()),
+                None => true,
+            })
+            .collect();
+        if self.limit > 0 && entries.len() > self.limit {
+            entries.drain(..entries.len() - self.limit);
+        }
+
+        if self.json {
+            let mut writer = serde_json::Serializer::pretty(std::io::stdout().lock());
+            writer.collect_seq(entries.iter())?;
+            println!();
+            return Ok(());
+        }
+
+        let cols = vec![
+            Column {
+                name: "STARTED (UTC)".to_string(),
+                alignment: Alignment::Left,
+            },
+            Column {
+                name: "DURATION".to_string(),
+                alignment: Alignment::Right,
+            },
+            Column {
+                name: "STATUS".to_string(),
+                alignment: Alignment::Right,
+            },
+            Column {
+                name: "PANEID".to_string(),
+                alignment: Alignment::Right,
+            },
+            Column {
+                name: "CWD".to_string(),
+                alignment: Alignment::Left,
+            },
+            Column {
+                name: "COMMAND".to_string(),
+                alignment: Alignment::Left,
+            },
+        ];
+        let data = entries
+            .iter()
+            .map(|entry| {
+                vec![
+                    chrono::DateTime::from_timestamp(entry.started as i64, 0)
+                        .map(|started| started.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default(),
+                    format!("{:.1}s", entry.duration_ms as f64 / 1000.),
+                    entry
+                        .exit_status
+                        .map(|status| status.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    entry.pane_id.to_string(),
+                    entry.cwd.clone().unwrap_or_default(),
+                    entry.command.replace('\n', " "),
+                ]
+            })
+            .collect::<Vec<_>>();
+        tabulate_output(&cols, &data, &mut std::io::stdout().lock())?;
+        Ok(())
+    }
+}
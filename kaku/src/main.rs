@@ -11,13 +11,20 @@ use umask::UmaskSaver;
 use wezterm_gui_subcommands::*;
 
 mod ai_config;
+mod askpass;
 mod assistant_config;
+mod bench;
 mod cli;
+mod command_history;
 mod config_cmd;
 mod config_tui;
 mod doctor;
 mod init;
+mod record;
 mod reset;
+mod show_config;
+mod tail;
+mod terminfo;
 mod update;
 mod utils;
 
@@ -132,6 +139,62 @@ enum SubCommand {
     )]
     Reset(reset::ResetCommand),
 
+    #[command(
+        name = "ls-fonts",
+        about = "List the fonts used by the configured font stack, \
+                 and explain which font renders a given text"
+    )]
+    LsFonts(LsFontsCommand),
+
+    #[command(
+        name = "askpass",
+        about = "Prompt for a password in the Kaku pane that runs this; \
+                 used as SSH_ASKPASS and GIT_ASKPASS"
+    )]
+    AskPass(askpass::AskPassCommand),
+
+    #[command(
+        name = "bench",
+        about = "Measure terminal parsing throughput and input latency"
+    )]
+    Bench(bench::BenchCommand),
+
+    #[command(
+        name = "record",
+        about = "Record the output of a pane to a file, until Enter is pressed"
+    )]
+    Record(record::RecordCommand),
+
+    #[command(
+        name = "replay",
+        about = "Replay a recording made by `kaku record` or `kaku cli log-output`"
+    )]
+    Replay(record::ReplayCommand),
+
+    #[command(
+        name = "show-config",
+        about = "Print the effective configuration as JSON, or validate it with --check"
+    )]
+    ShowConfig(show_config::ShowConfigCommand),
+
+    #[command(
+        name = "tail",
+        about = "Follow a file as it grows, like `tail -f`, without needing a shell"
+    )]
+    Tail(tail::TailCommand),
+
+    #[command(
+        name = "install-terminfo",
+        about = "Install the kaku terminfo entry locally or on a remote host"
+    )]
+    InstallTerminfo(terminfo::InstallTerminfoCommand),
+
+    #[command(
+        name = "command-history",
+        about = "Show the commands recorded by `command_history_log`"
+    )]
+    CommandHistory(command_history::CommandHistoryCommand),
+
     #[command(
         name = "cli",
         about = "Interact with experimental mux server",
@@ -142,11 +205,22 @@ enum SubCommand {
     #[command(
         name = "set-working-directory",
         about = "Advise the terminal of the current working directory by \
-                 emitting an OSC 7 escape sequence",
-        hide = true
+                 emitting an OSC 7 escape sequence"
     )]
     SetCwd(SetCwdCommand),
 
+    #[command(
+        name = "imgcat",
+        about = "Output an image to the terminal using the iTerm2 inline image protocol"
+    )]
+    ImgCat(ImgCatCommand),
+
+    #[command(
+        name = "set-user-var",
+        about = "Set a user var on the current pane by emitting an OSC 1337 escape sequence"
+    )]
+    SetUserVar(SetUserVarCommand),
+
     /// Generate shell completion information
     #[command(name = "shell-completion", hide = true)]
     ShellCompletion {
@@ -156,7 +230,9 @@ enum SubCommand {
     },
 }
 
-use termwiz::escape::osc::OperatingSystemCommand;
+use termwiz::escape::osc::{
+    ITermDimension, ITermFileData, ITermProprietary, OperatingSystemCommand,
+};
 
 #[derive(Debug, Parser, Clone)]
 struct SetCwdCommand {
@@ -205,6 +281,121 @@ impl SetCwdCommand {
     }
 }
 
+fn parse_dimension(s: &str) -> Result<ITermDimension, String> {
+    s.parse()
+        .map_err(|_| format!("{s} is not auto, N, Npx or N%"))
+}
+
+#[derive(Debug, Parser, Clone)]
+struct ImgCatCommand {
+    /// Specify the display width; defaults to "auto" which automatically selects
+    /// an appropriate size.  You may also use an integer value `N` to specify the
+    /// number of cells, or `Npx` to specify the number of pixels, or `N%` to
+    /// size relative to the terminal width.
+    #[arg(long, value_parser = parse_dimension)]
+    width: Option<ITermDimension>,
+
+    /// Specify the display height; defaults to "auto" which automatically selects
+    /// an appropriate size.  You may also use an integer value `N` to specify the
+    /// number of cells, or `Npx` to specify the number of pixels, or `N%` to
+    /// size relative to the terminal height.
+    #[arg(long, value_parser = parse_dimension)]
+    height: Option<ITermDimension>,
+
+    /// Do not respect the aspect ratio.  The default is to respect the aspect
+    /// ratio
+    #[arg(long)]
+    no_preserve_aspect_ratio: bool,
+
+    /// Do not move the cursor after displaying the image
+    #[arg(long)]
+    no_move_cursor: bool,
+
+    /// How to manage passing the escape through to tmux
+    #[arg(long, value_parser)]
+    tmux_passthru: Option<TmuxPassthru>,
+
+    /// The name of the image file to be displayed.
+    /// If omitted, will attempt to read it from stdin.
+    #[arg(value_parser, value_hint=ValueHint::FilePath)]
+    file_name: Option<OsString>,
+}
+
+impl ImgCatCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let mut data = Vec::new();
+        let name = match &self.file_name {
+            Some(file_name) => {
+                let mut f = std::fs::File::open(file_name)
+                    .with_context(|| anyhow!("reading image file: {:?}", file_name))?;
+                std::io::Read::read_to_end(&mut f, &mut data)?;
+                std::path::Path::new(file_name)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            }
+            None => {
+                let mut stdin = std::io::stdin();
+                std::io::Read::read_to_end(&mut stdin, &mut data)?;
+                None
+            }
+        };
+
+        let osc = OperatingSystemCommand::ITermProprietary(ITermProprietary::File(Box::new(
+            ITermFileData {
+                name,
+                size: Some(data.len()),
+                width: self.width.unwrap_or_default(),
+                height: self.height.unwrap_or_default(),
+                preserve_aspect_ratio: !self.no_preserve_aspect_ratio,
+                inline: true,
+                do_not_move_cursor: self.no_move_cursor,
+                data,
+            },
+        )));
+        let tmux = self.tmux_passthru.unwrap_or_default();
+        let encoded = tmux.encode(osc.to_string());
+        println!("{encoded}");
+        Ok(())
+    }
+}
+
+#[derive(Debug, Parser, Clone)]
+struct SetUserVarCommand {
+    /// The name of the user var to set
+    #[arg(value_parser)]
+    name: String,
+
+    /// The value to assign.
+    /// If omitted, will read the value from stdin.
+    #[arg(value_parser)]
+    value: Option<String>,
+
+    /// How to manage passing the escape through to tmux
+    #[arg(long, value_parser)]
+    tmux_passthru: Option<TmuxPassthru>,
+}
+
+impl SetUserVarCommand {
+    fn run(&self) -> anyhow::Result<()> {
+        let value = match &self.value {
+            Some(value) => value.clone(),
+            None => {
+                let mut value = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut value)?;
+                value
+            }
+        };
+        let osc = OperatingSystemCommand::ITermProprietary(ITermProprietary::SetUserVar {
+            name: self.name.clone(),
+            value,
+        });
+        let tmux = self.tmux_passthru.unwrap_or_default();
+        let encoded = tmux.encode(osc.to_string());
+        print!("{encoded}");
+        Ok(())
+    }
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum, Default)]
 enum TmuxPassthru {
     Disable,
@@ -302,7 +493,7 @@ fn run() -> anyhow::Result<()> {
     };
 
     match cmd {
-        SubCommand::Start(_) | SubCommand::BlockingStart(_) => {
+        SubCommand::Start(_) | SubCommand::BlockingStart(_) | SubCommand::LsFonts(_) => {
             env_bootstrap::bootstrap();
             delegate_to_gui(saver)
         }
@@ -311,6 +502,8 @@ fn run() -> anyhow::Result<()> {
             cli::run_cli(&opts, cli)
         }
         SubCommand::SetCwd(cmd) => cmd.run(),
+        SubCommand::ImgCat(cmd) => cmd.run(),
+        SubCommand::SetUserVar(cmd) => cmd.run(),
         SubCommand::ShellCompletion { shell } => {
             use clap::CommandFactory;
             let mut cmd = Opt::command();
@@ -323,6 +516,32 @@ fn run() -> anyhow::Result<()> {
         SubCommand::Init(cmd) => cmd.run(),
         SubCommand::Doctor(cmd) => cmd.run(),
         SubCommand::Reset(cmd) => cmd.run(),
+        SubCommand::InstallTerminfo(cmd) => cmd.run(),
+        SubCommand::CommandHistory(cmd) => cmd.run(),
+        SubCommand::AskPass(cmd) => {
+            env_bootstrap::bootstrap();
+            cli::run_askpass(cmd)
+        }
+        SubCommand::Bench(cmd) => {
+            env_bootstrap::bootstrap();
+            cli::run_bench(&opts, cmd)
+        }
+        SubCommand::Record(cmd) => {
+            env_bootstrap::bootstrap();
+            cli::run_record(cmd)
+        }
+        SubCommand::Replay(cmd) => {
+            env_bootstrap::bootstrap();
+            cli::run_replay(&opts, cmd)
+        }
+        SubCommand::ShowConfig(cmd) => {
+            env_bootstrap::bootstrap();
+            cmd.run(&opts)
+        }
+        SubCommand::Tail(cmd) => {
+            env_bootstrap::bootstrap();
+            cli::run_tail(&opts, cmd)
+        }
         SubCommand::Ai(cmd) => cmd.run(),
     }
 }
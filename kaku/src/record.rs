@@ -0,0 +1,470 @@
+//! `kaku record` and `kaku replay`: capture a pane's output via the mux
+//! server's pane logging, and play it back at an adjustable speed.
+//!
+//! Recordings are either the `script(1)` style raw output plus
+//! `<path>.timing` file written by `kaku cli log-output`, or, when the
+//! path ends in `.cast`, an asciicast v2 file converted from that log.
+use anyhow::{anyhow, Context};
+use clap::{Parser, ValueHint};
+use config::keyassignment::SpawnTabDomain;
+use mux::pane::PaneId;
+use portable_pty::cmdbuilder::CommandBuilder;
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use wezterm_client::client::Client;
+use wezterm_term::TerminalSize;
+
+#[derive(Debug, Parser, Clone)]
+pub struct RecordCommand {
+    /// Specify the pane to record.
+    /// The default is to use the current pane based on the
+    /// environment variable WEZTERM_PANE.
+    #[arg(long, conflicts_with = "title")]
+    pane_id: Option<PaneId>,
+
+    /// Record the first pane whose title contains this text
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Also record the input that is sent to the pane
+    #[arg(long)]
+    include_input: bool,
+
+    /// Where to save the recording.  A `.cast` extension produces an
+    /// asciicast v2 file, anything else produces the raw output along
+    /// with a `.timing` file in the format used by `scriptreplay`.
+    #[arg(value_hint=ValueHint::FilePath)]
+    path: PathBuf,
+}
+
+#[derive(Debug, Parser, Clone)]
+pub struct ReplayCommand {
+    /// Playback speed; 2 plays twice as fast
+    #[arg(long, default_value = "1.0")]
+    speed: f64,
+
+    /// Shorten pauses longer than this many seconds, so that idle
+    /// periods in the recording don't stall the replay
+    #[arg(long)]
+    max_delay: Option<f64>,
+
+    /// Replay into a new tab in the window of the current pane,
+    /// rather than into this terminal
+    #[arg(long)]
+    new_tab: bool,
+
+    /// Wait for Enter once the replay is complete
+    #[arg(long, hide = true)]
+    wait: bool,
+
+    /// The recording to replay
+    #[arg(value_hint=ValueHint::FilePath)]
+    path: PathBuf,
+}
+
+fn is_cast(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "cast")
+}
+
+fn timing_path(path: &Path) -> PathBuf {
+    let mut timing = path.as_os_str().to_owned();
+    timing.push(".timing");
+    PathBuf::from(timing)
+}
+
+async fn read_line_from_stdin() -> anyhow::Result<()> {
+    smol::unblock(|| {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)
+    })
+    .await?;
+    Ok(())
+}
+
+/// Finds the pane to record, along with its current size
+async fn resolve_pane(
+    client: &Client,
+    pane_id: Option<PaneId>,
+    title: Option<&str>,
+) -> anyhow::Result<(PaneId, TerminalSize)> {
+    let pane_id = match title {
+        Some(_) => None,
+        None => Some(client.resolve_pane_id(pane_id).await?),
+    };
+
+    let panes = client.list_panes().await?;
+    for tabroot in panes.tabs {
+        let mut cursor = tabroot.into_tree().cursor();
+        loop {
+            if let Some(entry) = cursor.leaf_mut() {
+                let matched = match (pane_id, title) {
+                    (Some(pane_id), _) => entry.pane_id == pane_id,
+                    (None, Some(title)) => entry.title.contains(title),
+                    (None, None) => false,
+                };
+                if matched {
+                    return Ok((entry.pane_id, entry.size));
+                }
+            }
+            match cursor.preorder_next() {
+                Ok(c) => cursor = c,
+                Err(_) => break,
+            }
+        }
+    }
+
+    match title {
+        Some(title) => Err(anyhow!("no pane has a title containing {title:?}")),
+        None => Err(anyhow!("pane {pane_id:?} not found")),
+    }
+}
+
+impl RecordCommand {
+    pub async fn run(self, client: Client) -> anyhow::Result<()> {
+        let (pane_id, size) = resolve_pane(&client, self.pane_id, self.title.as_deref()).await?;
+
+        // The mux server may have a different working directory
+        let path = std::env::current_dir()?.join(&self.path);
+        let parent = path
+            .parent()
+            .ok_or_else(|| anyhow!("{} has no parent directory", path.display()))?;
+        // A .cast file is converted from a raw log once recording stops
+        let scratch = if is_cast(&path) {
+            Some(
+                tempfile::Builder::new()
+                    .prefix(".kaku-record")
+                    .tempdir_in(parent)
+                    .context("creating temporary directory")?,
+            )
+        } else {
+            None
+        };
+        let log_path = match &scratch {
+            Some(dir) => dir.path().join("output"),
+            None => path.clone(),
+        };
+
+        client
+            .set_pane_output_logging(codec::SetPaneOutputLogging {
+                pane_id,
+                path: Some(log_path.clone()),
+                include_input: self.include_input,
+            })
+            .await?;
+        eprintln!(
+            "Recording pane {pane_id} to {}; press Enter to stop",
+            path.display()
+        );
+
+        let waited = read_line_from_stdin().await;
+        client
+            .set_pane_output_logging(codec::SetPaneOutputLogging {
+                pane_id,
+                path: None,
+                include_input: false,
+            })
+            .await?;
+        waited?;
+
+        if scratch.is_some() {
+            let events = load_script_log(&log_path)?;
+            let mut file = std::fs::File::create(&path)
+                .with_context(|| format!("creating {}", path.display()))?;
+            write_cast(&mut file, &events, &size)?;
+        }
+        eprintln!("Saved {}", path.display());
+        Ok(())
+    }
+}
+
+impl ReplayCommand {
+    pub async fn run(
+        self,
+        opts: &crate::Opt,
+        connect: impl FnOnce() -> anyhow::Result<Client>,
+    ) -> anyhow::Result<()> {
+        if self.new_tab {
+            let config = crate::init_config(opts)?;
+            return self.spawn_in_new_tab(connect()?, &config).await;
+        }
+
+        let events = if is_cast(&self.path) {
+            load_cast(&self.path)?
+        } else {
+            load_script_log(&self.path)?
+        };
+        let speed = if self.speed > 0. { self.speed } else { 1. };
+
+        let mut stdout = std::io::stdout();
+        let mut elapsed = 0.;
+        for event in &events {
+            // Recorded input isn't replayed, but the time it took is
+            elapsed += event.delay;
+            if !event.is_output {
+                continue;
+            }
+            let mut delay = std::mem::take(&mut elapsed) / speed;
+            if let Some(max_delay) = self.max_delay {
+                delay = delay.min(max_delay);
+            }
+            // A crafted file may hold delays that no Duration can hold
+            if let Ok(delay) = Duration::try_from_secs_f64(delay) {
+                smol::Timer::after(delay).await;
+            }
+            stdout.write_all(&event.data)?;
+            stdout.flush()?;
+        }
+
+        if self.wait {
+            eprint!("\r\n[replay complete; press Enter to close]");
+            read_line_from_stdin().await?;
+        }
+        Ok(())
+    }
+
+    async fn spawn_in_new_tab(
+        &self,
+        client: Client,
+        config: &config::ConfigHandle,
+    ) -> anyhow::Result<()> {
+        let path = std::env::current_dir()?.join(&self.path);
+        let mut args = vec![
+            "replay".into(),
+            "--wait".into(),
+            "--speed".into(),
+            self.speed.to_string().into(),
+        ];
+        if let Some(max_delay) = self.max_delay {
+            args.push("--max-delay".into());
+            args.push(max_delay.to_string().into());
+        }
+        args.push(path.into_os_string());
+        spawn_kaku_in_new_tab(client, config, args).await
+    }
+}
+
+/// Runs this kaku executable with `args` in a new tab in the window of
+/// the current pane, and prints the id of the new pane.
+/// No shell is involved, so this works even where there is none.
+pub(crate) async fn spawn_kaku_in_new_tab(
+    client: Client,
+    config: &config::ConfigHandle,
+    args: Vec<OsString>,
+) -> anyhow::Result<()> {
+    let (pane_id, _) = resolve_pane(&client, None, None).await?;
+    let panes = client.list_panes().await?;
+    let window_id = panes.tabs.into_iter().find_map(|tabroot| {
+        let mut cursor = tabroot.into_tree().cursor();
+        loop {
+            if let Some(entry) = cursor.leaf_mut() {
+                if entry.pane_id == pane_id {
+                    return Some(entry.window_id);
+                }
+            }
+            match cursor.preorder_next() {
+                Ok(c) => cursor = c,
+                Err(_) => return None,
+            }
+        }
+    });
+
+    let exe = std::env::current_exe().context("resolving the kaku executable")?;
+    let mut argv = vec![exe.into_os_string()];
+    argv.extend(args);
+
+    let spawned = client
+        .spawn_v2(codec::SpawnV2 {
+            domain: SpawnTabDomain::DefaultDomain,
+            window_id,
+            command: Some(CommandBuilder::from_argv(argv)),
+            command_dir: None,
+            size: config.initial_size(0, None),
+            workspace: config
+                .default_workspace
+                .as_deref()
+                .unwrap_or(mux::DEFAULT_WORKSPACE)
+                .to_string(),
+        })
+        .await?;
+    println!("{}", spawned.pane_id);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct Event {
+    /// Seconds since the previous event
+    delay: f64,
+    is_output: bool,
+    data: Vec<u8>,
+}
+
+/// Loads the raw log and `.timing` file written by pane output logging.
+/// The timing entries are either `<I|O> <delay> <length>`, or the older
+/// two field `<delay> <length>` form which only describes output.
+fn load_script_log(path: &Path) -> anyhow::Result<Vec<Event>> {
+    let mut data = vec![];
+    std::fs::File::open(path)
+        .with_context(|| format!("opening {}", path.display()))?
+        .read_to_end(&mut data)?;
+    let timing_path = timing_path(path);
+    let timing = std::fs::File::open(&timing_path)
+        .with_context(|| format!("opening {}", timing_path.display()))?;
+    parse_script_log(&data, BufReader::new(timing))
+}
+
+fn parse_script_log(data: &[u8], timing: impl BufRead) -> anyhow::Result<Vec<Event>> {
+    let mut events = vec![];
+    let mut offset = 0;
+    for line in timing.lines() {
+        let line = line?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (is_output, delay, len) = match fields.as_slice() {
+            [kind, delay, len] => (*kind == "O", delay, len),
+            [delay, len] => (true, delay, len),
+            [] => continue,
+            _ => anyhow::bail!("malformed timing entry {line:?}"),
+        };
+        let delay: f64 = delay.parse().with_context(|| format!("in {line:?}"))?;
+        let len: usize = len.parse().with_context(|| format!("in {line:?}"))?;
+        let end = offset
+            .checked_add(len)
+            .ok_or_else(|| anyhow!("timing entry {line:?} has an invalid length"))?;
+        let chunk = data
+            .get(offset..end)
+            .ok_or_else(|| anyhow!("timing file refers past the end of the log"))?;
+        offset = end;
+        events.push(Event {
+            delay,
+            is_output,
+            data: chunk.to_vec(),
+        });
+    }
+    Ok(events)
+}
+
+/// Loads an asciicast v2 file; its event times are relative to the
+/// start of the recording rather than to the previous event
+fn load_cast(path: &Path) -> anyhow::Result<Vec<Event>> {
+    let file = std::fs::File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut events = vec![];
+    let mut last = 0.;
+    // The first line is the header
+    for line in BufReader::new(file).lines().skip(1) {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (time, kind, data): (f64, String, String) =
+            serde_json::from_str(&line).with_context(|| format!("parsing {line:?}"))?;
+        events.push(Event {
+            delay: (time - last).max(0.),
+            is_output: kind == "o",
+            data: data.into_bytes(),
+        });
+        last = time;
+    }
+    Ok(events)
+}
+
+/// Appends `bytes` to `pending` and returns the longest valid UTF-8
+/// prefix, leaving any incomplete trailing sequence for the next chunk
+fn take_utf8(pending: &mut Vec<u8>, bytes: &[u8]) -> String {
+    pending.extend_from_slice(bytes);
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        // Genuinely invalid bytes; replace them rather than stalling
+        Err(_) => pending.len(),
+    };
+    let text = String::from_utf8_lossy(&pending[..valid]).into_owned();
+    pending.drain(..valid);
+    text
+}
+
+fn write_cast(out: &mut impl Write, events: &[Event], size: &TerminalSize) -> anyhow::Result<()> {
+    let header = serde_json::json!({
+        "version": 2,
+        "width": size.cols,
+        "height": size.rows,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    });
+    writeln!(out, "{header}")?;
+
+    let mut time = 0.;
+    let mut pending_output = vec![];
+    let mut pending_input = vec![];
+    for event in events {
+        time += event.delay;
+        let (kind, pending) = if event.is_output {
+            ("o", &mut pending_output)
+        } else {
+            ("i", &mut pending_input)
+        };
+        let text = take_utf8(pending, &event.data);
+        if !text.is_empty() {
+            writeln!(out, "{}", serde_json::json!([time, kind, text]))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_both_timing_formats() {
+        let events = parse_script_log(b"helloabc", &b"O 0.5 5\nI 0.25 2\n0.1 1\n"[..]).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event {
+                    delay: 0.5,
+                    is_output: true,
+                    data: b"hello".to_vec()
+                },
+                Event {
+                    delay: 0.25,
+                    is_output: false,
+                    data: b"ab".to_vec()
+                },
+                Event {
+                    delay: 0.1,
+                    is_output: true,
+                    data: b"c".to_vec()
+                },
+            ]
+        );
+        assert!(parse_script_log(b"hi", &b"O 0.5 5\n"[..]).is_err());
+        let huge = format!("O 0.5 1\nO 0.5 {}\n", usize::MAX);
+        assert!(parse_script_log(b"hi", huge.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn cast_keeps_split_utf8_together() {
+        let snowman = "☃".as_bytes();
+        let events = vec![
+            Event {
+                delay: 1.,
+                is_output: true,
+                data: snowman[..1].to_vec(),
+            },
+            Event {
+                delay: 0.5,
+                is_output: true,
+                data: snowman[1..].to_vec(),
+            },
+        ];
+        let mut out = vec![];
+        write_cast(&mut out, &events, &TerminalSize::default()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1], r#"[1.5,"o","☃"]"#);
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Context;
+use clap::Parser;
+use config::meta::{ConfigContainer, ConfigMeta};
+use serde_json::{json, Value as JValue};
+use wezterm_dynamic::{FromDynamic, ToDynamic, Value as DynValue};
+
+#[derive(Debug, Parser, Clone, Default)]
+pub struct ShowConfigCommand {
+    /// Only validate the configuration: print any errors or warnings
+    /// and exit non-zero if there were any, without printing the config.
+    #[arg(long, conflicts_with = "schema")]
+    check: bool,
+
+    /// Print the name, type, default value and documentation of every
+    /// config option instead of the effective configuration.
+    #[arg(long)]
+    schema: bool,
+}
+
+impl ShowConfigCommand {
+    pub fn run(&self, opts: &crate::Opt) -> anyhow::Result<()> {
+        if self.schema {
+            return print_json(&schema());
+        }
+
+        // Report problems on stderr rather than in an error window
+        config::assign_error_callback(|_| {});
+        crate::init_config(opts)?;
+        let problems = config::configuration_warnings_and_errors();
+        for problem in &problems {
+            eprintln!("{problem}");
+        }
+        let config = config::configuration_result()?;
+
+        if self.check {
+            anyhow::ensure!(
+                problems.is_empty(),
+                "configuration has {} problem(s)",
+                problems.len()
+            );
+            eprintln!("Configuration OK");
+            return Ok(());
+        }
+
+        print_json(&dyn_to_json(config.to_dynamic())?)
+    }
+}
+
+fn print_json(value: &JValue) -> anyhow::Result<()> {
+    let text = serde_json::to_string_pretty(value).context("encode json")?;
+    println!("{text}");
+    Ok(())
+}
+
+fn schema() -> JValue {
+    let config = config::Config::default_config();
+    let options = config
+        .get_config_options()
+        .iter()
+        .map(|option| {
+            let container = match option.container {
+                ConfigContainer::None => None,
+                ConfigContainer::Option => Some("option"),
+                ConfigContainer::Vec => Some("vec"),
+                ConfigContainer::Map => Some("map"),
+            };
+            let default = option
+                .default_value
+                .and_then(|default| dyn_to_json(default()).ok());
+            json!({
+                "name": option.name,
+                "type": option.type_name,
+                "container": container,
+                "default": default,
+                "doc": option.doc.trim(),
+            })
+        })
+        .collect();
+    JValue::Array(options)
+}
+
+fn dyn_to_json(value: DynValue) -> anyhow::Result<JValue> {
+    Ok(match value {
+        DynValue::Null => JValue::Null,
+        DynValue::Bool(b) => JValue::Bool(b),
+        DynValue::String(s) => JValue::String(s),
+        DynValue::Array(a) => JValue::Array(
+            a.into_iter()
+                .map(dyn_to_json)
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        DynValue::Object(o) => {
+            let mut result = serde_json::Map::new();
+            for (k, v) in o {
+                let k = String::from_dynamic(&k, Default::default())?;
+                result.insert(k, dyn_to_json(v)?);
+            }
+            JValue::Object(result)
+        }
+        DynValue::U64(u) => JValue::Number(u.into()),
+        DynValue::I64(i) => JValue::Number(i.into()),
+        DynValue::F64(f) => JValue::Number(
+            serde_json::Number::from_f64(*f)
+                .ok_or_else(|| anyhow::anyhow!("number {f:?} cannot be represented in json"))?,
+        ),
+    })
+}
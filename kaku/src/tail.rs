@@ -0,0 +1,722 @@
+//! `kaku tail`: a pager that follows a file as it grows, like `tail -F`,
+//! but without needing a shell or any other tools to be present.
+//!
+//! The file is decoded using the same machinery as the pane encodings,
+//! escape sequences are stripped, and lines matching the
+//! `tail_highlight_rules` from the config are colored.  Following can be
+//! paused to scroll back and search, or paused automatically when a rule
+//! with `pause = true` matches a new line.
+use anyhow::Context;
+use clap::{Parser, ValueHint};
+use config::keyassignment::PaneEncoding;
+use config::TailHighlightRule;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::ExecutableCommand;
+use fancy_regex::Regex;
+use mux::pane_encoding::{encoding_from_locale_env, PaneOutputDecoder};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Terminal;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use termwiz::cell::unicode_column_width;
+use termwiz::escape::parser::Parser as EscapeParser;
+use termwiz::escape::{Action, ControlCode};
+use wezterm_client::client::Client;
+
+/// How often to check the file for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Read at most this much of an existing file when starting up
+const MAX_INITIAL_READ: u64 = 64 * 1024 * 1024;
+
+const TAB_WIDTH: usize = 8;
+
+#[derive(Debug, Parser, Clone)]
+pub struct TailCommand {
+    /// The encoding of the file.
+    /// The default is to guess it from the locale, falling back to UTF-8.
+    #[arg(long)]
+    encoding: Option<PaneEncoding>,
+
+    /// How many of the lines already in the file to show
+    #[arg(long, short = 'n', default_value_t = 1000)]
+    lines: usize,
+
+    /// Keep at most this many lines for scrolling back through
+    #[arg(long, default_value_t = 100_000)]
+    max_lines: usize,
+
+    /// Highlight text matching this regex, in addition to the
+    /// `tail_highlight_rules` from the config.  May be repeated.
+    #[arg(long)]
+    highlight: Vec<String>,
+
+    /// Open in a new tab in the window of the current pane,
+    /// rather than in this terminal
+    #[arg(long)]
+    new_tab: bool,
+
+    /// The file to follow.  It doesn't need to exist yet.
+    #[arg(value_hint=ValueHint::FilePath)]
+    path: PathBuf,
+}
+
+impl TailCommand {
+    pub async fn run(
+        self,
+        opts: &crate::Opt,
+        connect: impl FnOnce() -> anyhow::Result<Client>,
+    ) -> anyhow::Result<()> {
+        let config = crate::init_config(opts)?;
+        if self.new_tab {
+            return self.spawn_in_new_tab(connect()?, &config).await;
+        }
+
+        let encoding = self
+            .encoding
+            .or_else(|| encoding_from_locale_env(|name| std::env::var_os(name)))
+            .unwrap_or(PaneEncoding::Utf8);
+        let highlighter = Highlighter::new(&config.tail_highlight_rules, &self.highlight)?;
+        let mut follower = FileFollower::new(&self.path, encoding);
+        let initial = follower.read_initial(self.lines)?;
+
+        let mut pager = Pager::new(
+            self.path.display().to_string(),
+            self.max_lines.max(1),
+            highlighter,
+        );
+        pager.append(initial, false);
+        run_pager(&mut pager, &mut follower)
+    }
+
+    async fn spawn_in_new_tab(
+        &self,
+        client: Client,
+        config: &config::ConfigHandle,
+    ) -> anyhow::Result<()> {
+        let path = std::env::current_dir()?.join(&self.path);
+        let mut args = vec![
+            "tail".into(),
+            "--lines".into(),
+            self.lines.to_string().into(),
+            "--max-lines".into(),
+            self.max_lines.to_string().into(),
+        ];
+        if let Some(encoding) = self.encoding {
+            args.push("--encoding".into());
+            args.push(encoding.to_string().into());
+        }
+        for highlight in &self.highlight {
+            args.push("--highlight".into());
+            args.push(highlight.into());
+        }
+        args.push(path.into_os_string());
+        crate::record::spawn_kaku_in_new_tab(client, config, args).await
+    }
+}
+
+/// Turns the raw bytes of the file into lines of text: decodes them from
+/// the file encoding, strips escape sequences and expands tabs.
+/// Bytes may be pushed in arbitrary chunks; a line is only returned once
+/// its newline has been seen.
+struct LineSplitter {
+    encoding: PaneEncoding,
+    decoder: PaneOutputDecoder,
+    parser: EscapeParser,
+    partial: String,
+}
+
+impl LineSplitter {
+    fn new(encoding: PaneEncoding) -> Self {
+        Self {
+            encoding,
+            decoder: PaneOutputDecoder::default(),
+            parser: EscapeParser::new(),
+            partial: String::new(),
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        let decoded = self.decoder.decode(self.encoding, bytes);
+        let mut lines = vec![];
+        let partial = &mut self.partial;
+        self.parser.parse(&decoded, |action| match action {
+            Action::Print(c) => partial.push(c),
+            Action::PrintString(s) => partial.push_str(&s),
+            Action::Control(ControlCode::HorizontalTab) => {
+                let width = unicode_column_width(partial, None);
+                let spaces = TAB_WIDTH - (width % TAB_WIDTH);
+                partial.extend(std::iter::repeat_n(' ', spaces));
+            }
+            Action::Control(ControlCode::LineFeed) => lines.push(std::mem::take(partial)),
+            _ => {}
+        });
+        lines
+    }
+}
+
+#[cfg(unix)]
+fn file_identity(meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Reads what is appended to a file.  If the file is truncated or
+/// replaced, as happens when logs are rotated, it is read again from
+/// the start.
+struct FileFollower {
+    path: PathBuf,
+    encoding: PaneEncoding,
+    file: Option<File>,
+    identity: Option<(u64, u64)>,
+    offset: u64,
+    splitter: LineSplitter,
+    /// Describes the most recent truncation or rotation, until taken
+    notice: Option<String>,
+}
+
+impl FileFollower {
+    fn new(path: &Path, encoding: PaneEncoding) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            encoding,
+            file: None,
+            identity: None,
+            offset: 0,
+            splitter: LineSplitter::new(encoding),
+            notice: None,
+        }
+    }
+
+    fn open(&mut self) -> anyhow::Result<bool> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => {
+                return Err(err).with_context(|| format!("opening {}", self.path.display()))
+            }
+        };
+        self.identity = file_identity(&file.metadata()?);
+        self.file = Some(file);
+        self.offset = 0;
+        self.splitter = LineSplitter::new(self.encoding);
+        Ok(true)
+    }
+
+    /// Returns the last `lines` complete lines already in the file
+    fn read_initial(&mut self, lines: usize) -> anyhow::Result<Vec<String>> {
+        if !self.open()? {
+            self.notice = Some("waiting for the file to be created".to_string());
+            return Ok(vec![]);
+        }
+        let len = self.file.as_ref().expect("just opened").metadata()?.len();
+        self.offset = len.saturating_sub(MAX_INITIAL_READ);
+        let skip_partial_line = self.offset > 0;
+
+        let mut result = self.read_appended()?;
+        if skip_partial_line && !result.is_empty() {
+            // We started reading in the middle of a line
+            result.remove(0);
+        }
+        let excess = result.len().saturating_sub(lines);
+        result.drain(..excess);
+        Ok(result)
+    }
+
+    /// Returns the complete lines that have been appended since the last call
+    fn poll(&mut self) -> anyhow::Result<Vec<String>> {
+        let meta = match std::fs::metadata(&self.path) {
+            Ok(meta) => meta,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                if self.file.take().is_some() {
+                    self.notice = Some("file removed; waiting for it to reappear".to_string());
+                }
+                return Ok(vec![]);
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("reading {}", self.path.display()))
+            }
+        };
+
+        if self.file.is_some() {
+            if file_identity(&meta) != self.identity {
+                self.notice = Some("file replaced; reading it from the start".to_string());
+                self.file.take();
+            } else if meta.len() < self.offset {
+                self.notice = Some("file truncated; reading it from the start".to_string());
+                self.file.take();
+            }
+        }
+        if self.file.is_none() && !self.open()? {
+            return Ok(vec![]);
+        }
+        self.read_appended()
+    }
+
+    fn read_appended(&mut self) -> anyhow::Result<Vec<String>> {
+        let file = self.file.as_mut().expect("file to be open");
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut data = vec![];
+        file.read_to_end(&mut data)
+            .with_context(|| format!("reading {}", self.path.display()))?;
+        self.offset += data.len() as u64;
+        Ok(self.splitter.push(&data))
+    }
+}
+
+struct HighlightRule {
+    regex: Regex,
+    style: Style,
+    whole_line: bool,
+    pause: bool,
+}
+
+/// Colors text matching the highlight rules
+struct Highlighter {
+    rules: Vec<HighlightRule>,
+}
+
+impl Highlighter {
+    fn new(config_rules: &[TailHighlightRule], extra: &[String]) -> anyhow::Result<Self> {
+        let mut rules = vec![];
+        for rule in config_rules {
+            match Regex::new(&rule.regex) {
+                Ok(regex) => {
+                    let color = rule
+                        .color
+                        .map(|color| {
+                            let (r, g, b, _) = color.to_srgb_u8();
+                            Color::Rgb(r, g, b)
+                        })
+                        .unwrap_or(Color::Red);
+                    rules.push(HighlightRule {
+                        regex,
+                        style: Style::default().fg(color),
+                        whole_line: rule.whole_line,
+                        pause: rule.pause,
+                    });
+                }
+                Err(err) => log::error!("tail_highlight_rules: ignoring {rule:?}: {err:#}"),
+            }
+        }
+        for pattern in extra {
+            rules.push(HighlightRule {
+                regex: Regex::new(pattern).with_context(|| format!("--highlight {pattern}"))?,
+                style: Style::default().fg(Color::Red),
+                whole_line: false,
+                pause: false,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Returns the byte ranges of `text` to style, in the order that
+    /// they should be applied
+    fn ranges(&self, text: &str) -> Vec<(Range<usize>, Style)> {
+        let mut ranges = vec![];
+        for rule in &self.rules {
+            if rule.whole_line {
+                if rule.regex.is_match(text).unwrap_or(false) {
+                    ranges.push((0..text.len(), rule.style));
+                }
+                continue;
+            }
+            for m in rule.regex.find_iter(text).flatten() {
+                if !m.range().is_empty() {
+                    ranges.push((m.range(), rule.style));
+                }
+            }
+        }
+        ranges
+    }
+
+    /// Returns the regex of the first pausing rule that matches `text`
+    fn pause_rule(&self, text: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.pause && rule.regex.is_match(text).unwrap_or(false))
+            .map(|rule| rule.regex.as_str())
+    }
+}
+
+/// Returns the byte ranges where `query` occurs in `text`, ignoring
+/// ASCII case
+fn search_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return vec![];
+    }
+    let query = query.to_ascii_lowercase();
+    text.to_ascii_lowercase()
+        .match_indices(&query)
+        .map(|(idx, m)| idx..idx + m.len())
+        .collect()
+}
+
+struct Pager {
+    title: String,
+    lines: VecDeque<String>,
+    max_lines: usize,
+    highlighter: Highlighter,
+    /// When true, the newest lines are kept in view
+    following: bool,
+    /// The first line in view when not following
+    top: usize,
+    /// The height of the view when it was last drawn
+    height: usize,
+    /// How many lines have arrived since following was paused
+    unseen: usize,
+    /// The committed search
+    search: Option<String>,
+    /// The search being typed
+    editing_search: Option<String>,
+    notice: Option<String>,
+}
+
+impl Pager {
+    fn new(title: String, max_lines: usize, highlighter: Highlighter) -> Self {
+        Self {
+            title,
+            lines: VecDeque::new(),
+            max_lines,
+            highlighter,
+            following: true,
+            top: 0,
+            height: 1,
+            unseen: 0,
+            search: None,
+            editing_search: None,
+            notice: None,
+        }
+    }
+
+    fn max_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.height)
+    }
+
+    fn view_top(&self) -> usize {
+        if self.following {
+            self.max_top()
+        } else {
+            self.top.min(self.max_top())
+        }
+    }
+
+    /// Adds newly read lines.  When `check_triggers` is true, a line
+    /// matching a pausing highlight rule stops following.
+    fn append(&mut self, new_lines: Vec<String>, check_triggers: bool) {
+        for line in new_lines {
+            if check_triggers && self.following {
+                if let Some(rule) = self.highlighter.pause_rule(&line) {
+                    self.following = false;
+                    // Keep the matching line at the bottom of the view
+                    self.top = (self.lines.len() + 1).saturating_sub(self.height);
+                    self.notice = Some(format!("paused on a match for {rule}"));
+                }
+            }
+            if !self.following {
+                self.unseen += 1;
+            }
+            self.lines.push_back(line);
+        }
+        let excess = self.lines.len().saturating_sub(self.max_lines);
+        if excess > 0 {
+            self.lines.drain(..excess);
+            self.top = self.top.saturating_sub(excess);
+        }
+    }
+
+    fn pause(&mut self) {
+        if self.following {
+            self.top = self.view_top();
+            self.following = false;
+        }
+    }
+
+    fn resume(&mut self) {
+        self.following = true;
+        self.unseen = 0;
+        self.notice = None;
+    }
+
+    fn scroll_by(&mut self, amount: isize) {
+        self.pause();
+        self.top = (self.top as isize + amount).clamp(0, self.max_top() as isize) as usize;
+    }
+
+    /// Scrolls to the nearest line containing the search text, looking
+    /// towards older lines when `older` is true
+    fn find(&mut self, older: bool) {
+        let Some(query) = self.search.clone() else {
+            return;
+        };
+        let top = self.view_top();
+        let found = if older {
+            (0..top)
+                .rev()
+                .find(|&idx| !search_matches(&self.lines[idx], &query).is_empty())
+        } else {
+            (top + 1..self.lines.len())
+                .find(|&idx| !search_matches(&self.lines[idx], &query).is_empty())
+        };
+        match found {
+            Some(idx) => {
+                self.pause();
+                self.top = idx.min(self.max_top());
+                self.notice = None;
+            }
+            None => self.notice = Some(format!("no more matches for {query}")),
+        }
+    }
+
+    fn styled_line(&self, text: &str) -> Line<'static> {
+        let mut styles = vec![Style::default(); text.len()];
+        let mut ranges = self.highlighter.ranges(text);
+        if let Some(query) = &self.search {
+            let matched = Style::default().add_modifier(Modifier::REVERSED);
+            ranges.extend(
+                search_matches(text, query)
+                    .into_iter()
+                    .map(|range| (range, matched)),
+            );
+        }
+        for (range, style) in ranges {
+            for s in &mut styles[range] {
+                *s = s.patch(style);
+            }
+        }
+
+        let mut spans = vec![];
+        let mut start = 0;
+        for (idx, _) in text.char_indices().skip(1) {
+            if styles[idx] != styles[start] {
+                spans.push(Span::styled(text[start..idx].to_string(), styles[start]));
+                start = idx;
+            }
+        }
+        if start < text.len() {
+            spans.push(Span::styled(text[start..].to_string(), styles[start]));
+        }
+        Line::from(spans)
+    }
+
+    fn status(&self) -> String {
+        if let Some(query) = &self.editing_search {
+            return format!("/{query}_");
+        }
+        let mut status = format!(" {} ", self.title);
+        if self.following {
+            status.push_str("| following ");
+        } else if self.unseen > 0 {
+            status.push_str(&format!("| paused, {} new lines ", self.unseen));
+        } else {
+            status.push_str("| paused ");
+        }
+        if let Some(notice) = &self.notice {
+            status.push_str(&format!("| {notice} "));
+        }
+        status.push_str("| space: pause/resume  /: search  n/N: older/newer match  q: quit");
+        status
+    }
+
+    /// Handles a key press, returning false when the pager should exit
+    fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if let Some(query) = &mut self.editing_search {
+            match code {
+                KeyCode::Esc => self.editing_search = None,
+                KeyCode::Enter => {
+                    let query = std::mem::take(query);
+                    self.editing_search = None;
+                    self.search = if query.is_empty() { None } else { Some(query) };
+                    self.find(true);
+                }
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => query.push(c),
+                _ => {}
+            }
+            return true;
+        }
+
+        let page = self.height.max(1) as isize;
+        match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') | KeyCode::Char('p') => {
+                if self.following {
+                    self.pause();
+                } else {
+                    self.resume();
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::PageUp | KeyCode::Char('b') => self.scroll_by(-page),
+            KeyCode::PageDown | KeyCode::Char('f') => self.scroll_by(page),
+            KeyCode::Home | KeyCode::Char('g') => {
+                self.pause();
+                self.top = 0;
+            }
+            KeyCode::End | KeyCode::Char('G') => self.resume(),
+            KeyCode::Char('/') => self.editing_search = Some(String::new()),
+            KeyCode::Char('n') => self.find(true),
+            KeyCode::Char('N') => self.find(false),
+            _ => {}
+        }
+        true
+    }
+}
+
+fn run_pager(pager: &mut Pager, follower: &mut FileFollower) -> anyhow::Result<()> {
+    enable_raw_mode().context("enable raw mode")?;
+    let mut stdout = std::io::stdout();
+    stdout
+        .execute(EnterAlternateScreen)
+        .context("enter alternate screen")?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout)).context("create terminal")?;
+
+    let result = pager_loop(&mut terminal, pager, follower);
+
+    disable_raw_mode().context("disable raw mode")?;
+    terminal
+        .backend_mut()
+        .execute(LeaveAlternateScreen)
+        .context("leave alternate screen")?;
+    result
+}
+
+fn pager_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    pager: &mut Pager,
+    follower: &mut FileFollower,
+) -> anyhow::Result<()> {
+    loop {
+        if let Some(notice) = follower.notice.take() {
+            pager.notice = Some(notice);
+        }
+        terminal.draw(|f| {
+            let [body, status] =
+                Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(f.area());
+            pager.height = body.height as usize;
+            let top = pager.view_top();
+            let lines: Vec<Line> = pager
+                .lines
+                .iter()
+                .skip(top)
+                .take(pager.height)
+                .map(|line| pager.styled_line(line))
+                .collect();
+            f.render_widget(Paragraph::new(lines), body);
+            f.render_widget(
+                Paragraph::new(pager.status())
+                    .style(Style::default().add_modifier(Modifier::REVERSED)),
+                status,
+            );
+        })?;
+
+        if event::poll(POLL_INTERVAL)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !pager.key(key.code, key.modifiers) {
+                    return Ok(());
+                }
+            }
+            continue;
+        }
+
+        let lines = follower.poll()?;
+        pager.append(lines, true);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn splits_decodes_and_strips() {
+        let mut splitter = LineSplitter::new(PaneEncoding::Gbk);
+        // "中文" in GBK, split in the middle of a character
+        let gbk = [0xd6, 0xd0, 0xce, 0xc4];
+        assert!(splitter.push(b"\x1b[31mred\x1b[0m\ta").is_empty());
+        assert!(splitter.push(&[b'\n', gbk[0]]).len() == 1);
+        let lines = splitter.push(&[gbk[1], gbk[2], gbk[3], b'\r', b'\n']);
+        assert_eq!(lines, vec!["中文".to_string()]);
+
+        let mut splitter = LineSplitter::new(PaneEncoding::Utf8);
+        assert_eq!(
+            splitter.push(b"\x1b[31mred\x1b[0m\tab\nc"),
+            vec!["red     ab".to_string()]
+        );
+
+        // Tabs stop at cell columns, so wide characters count twice
+        assert_eq!(
+            splitter.push("中文\tx\n".as_bytes()),
+            vec!["c中文   x".to_string()]
+        );
+    }
+
+    #[test]
+    fn follows_appends_and_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+        let mut follower = FileFollower::new(&path, PaneEncoding::Utf8);
+        assert_eq!(follower.read_initial(2).unwrap(), vec!["two", "three"]);
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"four\nfi").unwrap();
+        assert_eq!(follower.poll().unwrap(), vec!["four"]);
+
+        std::fs::write(&path, "new\n").unwrap();
+        assert_eq!(follower.poll().unwrap(), vec!["new"]);
+        assert!(follower.notice.take().is_some());
+    }
+
+    #[test]
+    fn pause_triggers() {
+        let rules = vec![TailHighlightRule {
+            regex: "ERROR".to_string(),
+            pause: true,
+            ..Default::default()
+        }];
+        let mut pager = Pager::new(
+            "test".to_string(),
+            100,
+            Highlighter::new(&rules, &[]).unwrap(),
+        );
+        pager.height = 2;
+        pager.append(vec!["a".to_string(), "b".to_string()], true);
+        assert!(pager.following);
+        pager.append(vec!["ERROR c".to_string(), "d".to_string()], true);
+        assert!(!pager.following);
+        assert_eq!(pager.unseen, 2);
+        assert_eq!(pager.view_top(), 1);
+    }
+
+    #[test]
+    fn searching() {
+        assert_eq!(search_matches("Error error", "ERROR"), vec![0..5, 6..11]);
+        assert!(search_matches("abc", "").is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{anyhow, bail, Context};
+use clap::Parser;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// The source of the `kaku` terminfo entry, compiled on the target
+/// machine with `tic` so that it lands in the right database format.
+const KAKU_TERMINFO: &str = include_str!("../../termwiz/data/kaku.terminfo");
+
+#[derive(Debug, Parser, Clone, Default)]
+pub struct InstallTerminfoCommand {
+    /// Install into this directory instead of `~/.terminfo`
+    #[arg(long, value_hint = clap::ValueHint::DirPath, conflicts_with_all = ["ssh", "print"])]
+    target_dir: Option<PathBuf>,
+
+    /// Install on a remote host by running `tic` there over ssh.
+    /// The value is passed to ssh as the destination, eg: `user@host`.
+    #[arg(long, value_hint = clap::ValueHint::Hostname, conflicts_with = "print")]
+    ssh: Option<String>,
+
+    /// Print the terminfo source instead of installing it
+    #[arg(long)]
+    print: bool,
+}
+
+impl InstallTerminfoCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        if self.print {
+            print!("{KAKU_TERMINFO}");
+            return Ok(());
+        }
+
+        if let Some(host) = &self.ssh {
+            return install_remote(host);
+        }
+
+        let target_dir = match &self.target_dir {
+            Some(dir) => dir.clone(),
+            None => config::HOME_DIR.join(".terminfo"),
+        };
+        install_local(&target_dir)?;
+        println!("Installed kaku terminfo into {}", target_dir.display());
+        println!("Set `term = \"kaku\"` in your config to advertise it to programs.");
+        Ok(())
+    }
+}
+
+fn install_local(target_dir: &std::path::Path) -> anyhow::Result<()> {
+    config::create_user_owned_dirs(target_dir)
+        .with_context(|| format!("create {}", target_dir.display()))?;
+
+    let mut source = tempfile::Builder::new()
+        .prefix("kaku-")
+        .suffix(".terminfo")
+        .tempfile()
+        .context("create temporary terminfo source")?;
+    source
+        .write_all(KAKU_TERMINFO.as_bytes())
+        .context("write temporary terminfo source")?;
+    source.flush()?;
+
+    let status = Command::new("tic")
+        .arg("-x")
+        .arg("-o")
+        .arg(target_dir)
+        .arg(source.path())
+        .status()
+        .map_err(|err| anyhow!("failed to run tic: {err}; is ncurses installed?"))?;
+    if !status.success() {
+        bail!("tic exited with {status}");
+    }
+    Ok(())
+}
+
+fn install_remote(host: &str) -> anyhow::Result<()> {
+    // tic cannot read the entry from a pipe on every platform, so stage
+    // it in a temporary file on the remote side first.
+    let script = "set -e; \
+        f=$(mktemp); trap 'rm -f \"$f\"' EXIT; \
+        cat > \"$f\"; \
+        mkdir -p \"$HOME/.terminfo\"; \
+        tic -x -o \"$HOME/.terminfo\" \"$f\"";
+
+    let mut child = Command::new("ssh")
+        // Keep a host starting with `-` from being taken as an option
+        .arg("--")
+        .arg(host)
+        .arg(script)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("failed to run ssh")?;
+    child
+        .stdin
+        .take()
+        .context("ssh stdin")?
+        .write_all(KAKU_TERMINFO.as_bytes())
+        .context("send terminfo source to ssh")?;
+    let status = child.wait().context("wait for ssh")?;
+    if !status.success() {
+        bail!("installing terminfo on {host} failed: ssh exited with {status}");
+    }
+    println!("Installed kaku terminfo on {host}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_entry_is_named_kaku() {
+        assert!(KAKU_TERMINFO.lines().any(|line| line.starts_with("kaku|")));
+    }
+}
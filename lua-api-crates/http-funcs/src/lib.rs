@@ -0,0 +1,156 @@
+//! `wezterm.http.get` and `wezterm.http.post`: small HTTP helpers for
+//! things like status bar segments.
+//! Requests run on the blocking thread pool so that the GUI thread is
+//! never stalled, and the lua caller resumes once the response is in.
+use config::lua::get_or_create_sub_module;
+use config::lua::mlua::{self, Lua};
+use http_req::request::{Method, Request};
+use http_req::uri::Uri;
+use luahelper::impl_lua_conversion_dynamic;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::Write;
+use std::time::Duration;
+use wezterm_dynamic::{FromDynamic, ToDynamic};
+
+#[derive(FromDynamic, ToDynamic, Debug, Clone)]
+struct RequestOptions {
+    #[dynamic(default)]
+    headers: HashMap<String, String>,
+    #[dynamic(default = "default_timeout_seconds")]
+    timeout_seconds: f64,
+    /// Responses larger than this are treated as an error
+    #[dynamic(default = "default_max_bytes")]
+    max_bytes: usize,
+}
+impl_lua_conversion_dynamic!(RequestOptions);
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self {
+            headers: HashMap::new(),
+            timeout_seconds: default_timeout_seconds(),
+            max_bytes: default_max_bytes(),
+        }
+    }
+}
+
+fn default_timeout_seconds() -> f64 {
+    10.
+}
+
+fn default_max_bytes() -> usize {
+    1024 * 1024
+}
+
+struct Response {
+    status: u16,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Collects the response body, failing once it grows beyond `limit`
+struct LimitedWriter {
+    buf: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        if self.buf.len() + data.len() > self.limit {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("response exceeds max_bytes={}", self.limit),
+            ));
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn perform(
+    method: Method,
+    url: &str,
+    body: Option<&[u8]>,
+    options: &RequestOptions,
+) -> anyhow::Result<Response> {
+    let uri = Uri::try_from(url)?;
+    anyhow::ensure!(
+        options.timeout_seconds.is_finite(),
+        "timeout_seconds must be a finite number, not {}",
+        options.timeout_seconds
+    );
+    let timeout = Duration::try_from_secs_f64(options.timeout_seconds.max(0.))?;
+    let mut writer = LimitedWriter {
+        buf: vec![],
+        limit: options.max_bytes,
+    };
+
+    let mut request = Request::new(&uri);
+    request
+        .method(method)
+        .timeout(timeout)
+        .header("User-Agent", &format!("kaku/{}", config::wezterm_version()));
+    for (name, value) in &options.headers {
+        request.header(name, value);
+    }
+    if let Some(body) = body {
+        request
+            .header("Content-Length", &body.len().to_string())
+            .body(body);
+    }
+    let response = request
+        .send(&mut writer)
+        .map_err(|err| anyhow::anyhow!("request to {url} failed: {err}"))?;
+
+    Ok(Response {
+        status: response.status_code().into(),
+        content_type: response.headers().get("Content-Type").cloned(),
+        body: writer.buf,
+    })
+}
+
+async fn request<'lua>(
+    lua: &'lua Lua,
+    method: Method,
+    url: String,
+    body: Option<mlua::String<'lua>>,
+    options: Option<RequestOptions>,
+) -> mlua::Result<mlua::Table<'lua>> {
+    let options = options.unwrap_or_default();
+    let body = body.map(|body| body.as_bytes().to_vec());
+    let response = smol::unblock(move || perform(method, &url, body.as_deref(), &options))
+        .await
+        .map_err(|err| mlua::Error::external(format!("{err:#}")))?;
+
+    let result = lua.create_table()?;
+    result.set("status", response.status)?;
+    result.set("content_type", response.content_type)?;
+    result.set("body", lua.create_string(&response.body)?)?;
+    Ok(result)
+}
+
+async fn get<'lua>(
+    lua: &'lua Lua,
+    (url, options): (String, Option<RequestOptions>),
+) -> mlua::Result<mlua::Table<'lua>> {
+    request(lua, Method::GET, url, None, options).await
+}
+
+async fn post<'lua>(
+    lua: &'lua Lua,
+    (url, body, options): (String, mlua::String<'lua>, Option<RequestOptions>),
+) -> mlua::Result<mlua::Table<'lua>> {
+    request(lua, Method::POST, url, Some(body), options).await
+}
+
+pub fn register(lua: &Lua) -> anyhow::Result<()> {
+    let http_mod = get_or_create_sub_module(lua, "http")?;
+    http_mod.set("get", lua.create_async_function(get)?)?;
+    http_mod.set("post", lua.create_async_function(post)?)?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use super::*;
+use config::keyassignment::ScrollbackEraseMode;
 use luahelper::mlua::LuaSerdeExt;
 use luahelper::{dynamic_to_lua_value, from_lua, to_lua};
 use mlua::Value;
@@ -237,6 +238,40 @@ impl UserData for MuxPane {
             Ok(text)
         });
 
+        methods.add_method("get_spilled_scrollback_text", |_, this, _: ()| {
+            let mux = get_mux()?;
+            let pane = this.resolve(&mux)?;
+            pane.get_spilled_scrollback_text()
+                .map_err(|err| mlua::Error::external(format!("{err:#}")))
+        });
+
+        methods.add_method("clear_scrollback", |_, this, mode: Value| {
+            let mux = get_mux()?;
+            let pane = this.resolve(&mux)?;
+            let mode: Option<ScrollbackEraseMode> = from_lua(mode)?;
+            pane.erase_scrollback(mode.unwrap_or_default());
+            Ok(())
+        });
+
+        methods.add_method("set_scrollback_lines", |_, this, lines: Option<usize>| {
+            let mux = get_mux()?;
+            let pane = this.resolve(&mux)?;
+            pane.set_scrollback_lines(lines);
+            Ok(())
+        });
+
+        methods.add_method("get_raw_output", |lua, this, n_bytes: Option<usize>| {
+            let mux = get_mux()?;
+            let pane = this.resolve(&mux)?;
+            match pane.raw_output_history() {
+                Some(history) => {
+                    let data = history.lock().tail(n_bytes.unwrap_or(usize::MAX));
+                    Ok(Some(lua.create_string(&data)?))
+                }
+                None => Ok(None),
+            }
+        });
+
         methods.add_method("get_lines_as_escapes", |_, this, nlines: Option<usize>| {
             let mux = get_mux()?;
             let pane = this.resolve(&mux)?;
@@ -46,6 +46,30 @@ impl UserData for MuxTab {
             let tab = this.resolve(&mux)?;
             Ok(tab.set_title(&title))
         });
+        methods.add_method("get_color", |_, this, _: ()| {
+            let mux = get_mux()?;
+            let tab = this.resolve(&mux)?;
+            Ok(tab.get_color().map(String::from))
+        });
+        methods.add_method("set_color", |_, this, color: Option<String>| {
+            let mux = get_mux()?;
+            let tab = this.resolve(&mux)?;
+            let color = color
+                .map(config::RgbaColor::try_from)
+                .transpose()
+                .map_err(|err| mlua::Error::external(format!("{err:#}")))?;
+            Ok(tab.set_color(color))
+        });
+        methods.add_method("get_group", |_, this, _: ()| {
+            let mux = get_mux()?;
+            let tab = this.resolve(&mux)?;
+            Ok(tab.get_group())
+        });
+        methods.add_method("set_group", |_, this, group: Option<String>| {
+            let mux = get_mux()?;
+            let tab = this.resolve(&mux)?;
+            Ok(tab.set_group(group))
+        });
         methods.add_method("active_pane", |_, this, _: ()| {
             let mux = get_mux()?;
             let tab = this.resolve(&mux)?;
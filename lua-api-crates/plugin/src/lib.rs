@@ -2,8 +2,8 @@ use anyhow::{anyhow, Context};
 use config::lua::get_or_create_sub_module;
 use config::lua::mlua::{self, Lua, Value};
 use git2::build::CheckoutBuilder;
-use git2::{Remote, Repository};
-use luahelper::to_lua;
+use git2::{AutotagOption, FetchOptions, Object, Remote, Repository};
+use luahelper::{impl_lua_conversion_dynamic, to_lua};
 use std::path::PathBuf;
 use tempfile::TempDir;
 use wezterm_dynamic::{FromDynamic, ToDynamic};
@@ -13,8 +13,23 @@ struct RepoSpec {
     url: String,
     component: String,
     plugin_dir: PathBuf,
+    /// The tag, branch or commit that the checkout is pinned to.
+    /// When unset, the checkout tracks the default branch.
+    #[dynamic(default)]
+    rev: Option<String>,
 }
 
+#[derive(FromDynamic, ToDynamic, Debug, Default)]
+struct RequireOptions {
+    #[dynamic(default)]
+    rev: Option<String>,
+}
+impl_lua_conversion_dynamic!(RequireOptions);
+
+/// The key in the checkout's git config that records its pinned rev,
+/// so that `update_all` knows about pins made by earlier `require` calls
+const PIN_CONFIG_KEY: &str = "kaku.pin";
+
 /// Given a URL, generate a string that can be used as a directory name.
 /// The returned name must be a single valid filesystem component
 fn compute_repo_dir(url: &str) -> String {
@@ -52,8 +67,29 @@ fn get_remote(repo: &Repository) -> anyhow::Result<Option<Remote<'_>>> {
     Ok(None)
 }
 
+fn remote_name(repo: &Repository) -> anyhow::Result<String> {
+    let remote = get_remote(repo)?.ok_or_else(|| anyhow!("no remotes!?"))?;
+    remote
+        .name()
+        .map(|name| name.to_string())
+        .ok_or_else(|| anyhow!("remote name is not utf8"))
+}
+
+fn read_pin(repo: &Repository) -> anyhow::Result<Option<String>> {
+    Ok(repo.config()?.snapshot()?.get_string(PIN_CONFIG_KEY).ok())
+}
+
+/// Resolves `rev`, preferring the remote tracking branch of that name
+/// so that a pinned branch follows what was most recently fetched
+fn resolve_rev<'r>(repo: &'r Repository, rev: &str) -> anyhow::Result<Object<'r>> {
+    let remote = remote_name(repo)?;
+    repo.revparse_single(&format!("{remote}/{rev}"))
+        .or_else(|_| repo.revparse_single(rev))
+        .with_context(|| format!("{rev} is not a tag, branch or commit"))
+}
+
 impl RepoSpec {
-    fn parse(url: String) -> anyhow::Result<Self> {
+    fn parse(url: String, rev: Option<String>) -> anyhow::Result<Self> {
         let component = compute_repo_dir(&url);
         if component.starts_with('.') {
             anyhow::bail!("invalid repo spec {url}");
@@ -65,6 +101,7 @@ impl RepoSpec {
             url,
             component,
             plugin_dir,
+            rev,
         })
     }
 
@@ -83,10 +120,12 @@ impl RepoSpec {
         let url = remote.url();
         if let Some(url) = url {
             let url = url.to_string();
+            let rev = read_pin(&repo)?;
             return Ok(Self {
                 component,
                 url,
                 plugin_dir,
+                rev,
             });
         }
         anyhow::bail!("Unable to create a complete RepoSpec for repo at {path:?}");
@@ -107,6 +146,9 @@ impl RepoSpec {
     fn update(&self) -> anyhow::Result<()> {
         let path = self.checkout_path();
         let repo = Repository::open(&path)?;
+        if let Some(rev) = &self.rev {
+            return self.update_pinned(&repo, rev);
+        }
         let mut remote = get_remote(&repo)?.ok_or_else(|| anyhow!("no remotes!?"))?;
         remote.connect(git2::Direction::Fetch).context("connect")?;
         let branch = remote
@@ -154,6 +196,74 @@ impl RepoSpec {
         Ok(())
     }
 
+    /// Fetches all branches and tags, then moves the checkout to
+    /// wherever the pinned rev now points
+    fn update_pinned(&self, repo: &Repository, rev: &str) -> anyhow::Result<()> {
+        let mut remote = get_remote(repo)?.ok_or_else(|| anyhow!("no remotes!?"))?;
+        let mut options = FetchOptions::new();
+        options.download_tags(AutotagOption::All);
+        remote
+            .fetch::<&str>(&[], Some(&mut options), None)
+            .context("fetch")?;
+        self.check_out_rev(repo, rev)
+    }
+
+    fn check_out_rev(&self, repo: &Repository, rev: &str) -> anyhow::Result<()> {
+        let commit = resolve_rev(repo, rev)?
+            .peel_to_commit()
+            .context("peel_to_commit")?;
+        if repo.head().ok().and_then(|head| head.target()) == Some(commit.id()) {
+            log::debug!("{} is already at {rev}", self.component);
+            return Ok(());
+        }
+        repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().force()))
+            .context("checkout_tree")?;
+        repo.set_head_detached(commit.id())
+            .context("set_head_detached")?;
+        log::info!("Checked out {} at {rev} ({})", self.component, commit.id());
+        Ok(())
+    }
+
+    /// Makes the checkout match `self.rev`, recording or clearing the pin
+    fn apply_pin(&self) -> anyhow::Result<()> {
+        let repo = Repository::open(self.checkout_path())?;
+        let pinned = read_pin(&repo)?;
+        if pinned == self.rev {
+            return Ok(());
+        }
+
+        match &self.rev {
+            Some(rev) => {
+                if resolve_rev(&repo, rev).is_err() {
+                    // Most likely a tag or commit newer than our last fetch
+                    self.update_pinned(&repo, rev)?;
+                } else {
+                    self.check_out_rev(&repo, rev)?;
+                }
+                repo.config()?.set_str(PIN_CONFIG_KEY, rev)?;
+            }
+            None => {
+                // Go back to tracking the default branch
+                let remote = remote_name(&repo)?;
+                let remote_head = repo
+                    .find_reference(&format!("refs/remotes/{remote}/HEAD"))
+                    .context("find remote HEAD")?;
+                let branch = remote_head
+                    .symbolic_target()
+                    .and_then(|target| target.strip_prefix(&format!("refs/remotes/{remote}/")))
+                    .ok_or_else(|| anyhow!("unable to determine the default branch"))?
+                    .to_string();
+                repo.set_head(&format!("refs/heads/{branch}"))
+                    .context("set_head")?;
+                repo.checkout_head(Some(CheckoutBuilder::new().force()))
+                    .context("checkout_head")?;
+                repo.config()?.remove(PIN_CONFIG_KEY)?;
+                log::info!("{} is no longer pinned to {pinned:?}", self.component);
+            }
+        }
+        Ok(())
+    }
+
     fn check_out(&self) -> anyhow::Result<()> {
         let plugins_dir = Self::plugins_dir();
         std::fs::create_dir_all(&plugins_dir)?;
@@ -184,12 +294,17 @@ impl RepoSpec {
     }
 }
 
-fn require_plugin(lua: &Lua, url: String) -> anyhow::Result<Value<'_>> {
-    let spec = RepoSpec::parse(url)?;
+fn require_plugin(
+    lua: &Lua,
+    url: String,
+    options: Option<RequireOptions>,
+) -> anyhow::Result<Value<'_>> {
+    let spec = RepoSpec::parse(url, options.unwrap_or_default().rev)?;
 
     if !spec.is_checked_out() {
         spec.check_out()?;
     }
+    spec.apply_pin()?;
 
     let require: mlua::Function = lua.globals().get("require")?;
     match require.call::<_, Value>(spec.component.to_string()) {
@@ -221,13 +336,32 @@ fn list_plugins() -> anyhow::Result<Vec<RepoSpec>> {
     Ok(plugins)
 }
 
+/// Updates every installed plugin, returning the url of each one
+/// along with the outcome of updating it
+pub fn update_all() -> anyhow::Result<Vec<(String, anyhow::Result<()>)>> {
+    Ok(list_plugins()?
+        .into_iter()
+        .map(|p| {
+            let result = p.update();
+            match &result {
+                Ok(_) => log::info!("Updated {p:?}"),
+                Err(err) => log::error!("Failed to update {p:?}: {err:#}"),
+            }
+            (p.url, result)
+        })
+        .collect())
+}
+
 pub fn register(lua: &Lua) -> anyhow::Result<()> {
     let plugin_mod = get_or_create_sub_module(lua, "plugin")?;
     plugin_mod.set(
         "require",
-        lua.create_function(|lua: &Lua, repo_spec: String| {
-            require_plugin(lua, repo_spec).map_err(|e| mlua::Error::external(format!("{e:#}")))
-        })?,
+        lua.create_function(
+            |lua: &Lua, (repo_spec, options): (String, Option<RequireOptions>)| {
+                require_plugin(lua, repo_spec, options)
+                    .map_err(|e| mlua::Error::external(format!("{e:#}")))
+            },
+        )?,
     )?;
 
     plugin_mod.set(
@@ -241,13 +375,7 @@ pub fn register(lua: &Lua) -> anyhow::Result<()> {
     plugin_mod.set(
         "update_all",
         lua.create_function(|_, _: ()| {
-            let plugins = list_plugins().map_err(|e| mlua::Error::external(format!("{e:#}")))?;
-            for p in plugins {
-                match p.update() {
-                    Ok(_) => log::info!("Updated {p:?}"),
-                    Err(err) => log::error!("Failed to update {p:?}: {err:#}"),
-                }
-            }
+            update_all().map_err(|e| mlua::Error::external(format!("{e:#}")))?;
             Ok(())
         })?,
     )?;
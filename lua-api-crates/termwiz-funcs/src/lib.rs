@@ -97,6 +97,7 @@ impl From<FormatColor> for ColorSpec {
 pub enum FormatItem {
     Foreground(FormatColor),
     Background(FormatColor),
+    UnderlineColor(FormatColor),
     Attribute(AttributeChange),
     ResetAttributes,
     Text(String),
@@ -110,6 +111,7 @@ impl From<FormatItem> for Change {
             FormatItem::Text(t) => t.into(),
             FormatItem::Foreground(c) => AttributeChange::Foreground(c.to_attr()).into(),
             FormatItem::Background(c) => AttributeChange::Background(c.to_attr()).into(),
+            FormatItem::UnderlineColor(c) => AttributeChange::UnderlineColor(c.to_attr()).into(),
             FormatItem::ResetAttributes => Change::AllAttributes(CellAttributes::default()),
         }
     }
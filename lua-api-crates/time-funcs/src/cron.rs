@@ -0,0 +1,270 @@
+//! A small parser for the classic 5-field cron syntax, used by
+//! `wezterm.time.schedule`.
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDateTime, TimeZone, Timelike};
+
+/// A parsed `minute hour day-of-month month day-of-week` spec.
+/// Each field is a bitmask of the values it allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSpec {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether the day-of-month and day-of-week fields were restricted;
+    /// when both are, cron matches a day satisfying either of them
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> anyhow::Result<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>()?),
+            None => (part, 1),
+        };
+        anyhow::ensure!(step > 0, "step in `{part}` must be non-zero");
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse()?, end.parse()?)
+        } else {
+            let value = range.parse()?;
+            // `5/15` means "from 5 onwards, every 15"
+            if step > 1 {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+        anyhow::ensure!(
+            min <= start && start <= end && end <= max,
+            "`{part}` is outside the range {min}-{max}"
+        );
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl CronSpec {
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let fields: Vec<&str> = spec.split_whitespace().collect();
+        anyhow::ensure!(
+            fields.len() == 5,
+            "expected 5 fields (minute hour day-of-month month day-of-week) in `{spec}`"
+        );
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // Both 0 and 7 mean Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    fn matches(&self, t: &NaiveDateTime) -> bool {
+        self.months_match(t)
+            && self.day_matches(t)
+            && self.hours & (1 << t.hour()) != 0
+            && self.minutes & (1 << t.minute()) != 0
+    }
+
+    /// Returns the first matching minute strictly after `after`, looking
+    /// no more than a little over four years ahead (enough for Feb 29).
+    /// Minutes are matched against the wall clock time of the zone of
+    /// `after`; see `resolve` for how daylight saving changes are handled.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let start = after.naive_local();
+        let mut t = start.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(4 * 366);
+        while t <= limit {
+            if self.matches(&t) {
+                if let Some(when) = resolve(&after.timezone(), &t).filter(|when| when > after) {
+                    return Some(when);
+                }
+            }
+            t = if !self.months_match(&t) || !self.day_matches(&t) {
+                // Skip the rest of the day
+                t.with_hour(0)?.with_minute(0)? + Duration::days(1)
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t.with_minute(0)? + Duration::hours(1)
+            } else {
+                t + Duration::minutes(1)
+            };
+        }
+        None
+    }
+
+    fn months_match(&self, t: &NaiveDateTime) -> bool {
+        self.months & (1 << t.month()) != 0
+    }
+
+    fn day_matches(&self, t: &NaiveDateTime) -> bool {
+        let dom = self.days_of_month & (1 << t.day()) != 0;
+        let dow = self.days_of_week & (1 << t.weekday().num_days_from_sunday()) != 0;
+        if self.dom_restricted && self.dow_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+/// Maps a wall clock minute to a time in `tz`.  A minute that occurs
+/// twice when the clocks go back runs the first time only; one that is
+/// skipped when they go forward runs as soon as the clocks have passed it,
+/// as cron does.
+fn resolve<Tz: TimeZone>(tz: &Tz, t: &NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(t) {
+        LocalResult::Single(when) | LocalResult::Ambiguous(when, _) => Some(when),
+        LocalResult::None => (1..=24 * 60).find_map(|minutes| {
+            tz.from_local_datetime(&(*t + Duration::minutes(minutes)))
+                .earliest()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{FixedOffset, NaiveDate, Utc};
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    /// Like Europe/London in 2024: the clocks go from 01:00 UTC+0 to
+    /// 02:00 UTC+1 on March 31st, and back from 02:00 UTC+1 to 01:00
+    /// UTC+0 on October 27th
+    #[derive(Clone, Copy, Debug)]
+    struct London;
+
+    impl London {
+        fn is_summer(utc: &NaiveDateTime) -> bool {
+            let start = NaiveDate::from_ymd_opt(2024, 3, 31)
+                .unwrap()
+                .and_hms_opt(1, 0, 0);
+            let end = NaiveDate::from_ymd_opt(2024, 10, 27)
+                .unwrap()
+                .and_hms_opt(1, 0, 0);
+            Some(*utc) >= start && Some(*utc) < end
+        }
+
+        fn offset(summer: bool) -> FixedOffset {
+            FixedOffset::east_opt(if summer { 3600 } else { 0 }).unwrap()
+        }
+    }
+
+    impl TimeZone for London {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            London
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let candidates: Vec<FixedOffset> = [false, true]
+                .into_iter()
+                .filter(|&summer| {
+                    let offset = Self::offset(summer);
+                    Self::is_summer(&(*local - Duration::seconds(offset.local_minus_utc().into())))
+                        == summer
+                })
+                .map(Self::offset)
+                .collect();
+            match candidates.as_slice() {
+                [] => LocalResult::None,
+                [offset] => LocalResult::Single(*offset),
+                [a, b] => LocalResult::Ambiguous(*b, *a),
+                _ => unreachable!(),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::offset(Self::is_summer(utc))
+        }
+    }
+
+    fn london(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<London> {
+        London
+            .with_ymd_and_hms(y, mo, d, h, mi, 0)
+            .earliest()
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(CronSpec::parse("* * * *").is_err());
+        assert!(CronSpec::parse("60 * * * *").is_err());
+        assert!(CronSpec::parse("*/0 * * * *").is_err());
+        assert!(CronSpec::parse("5-1 * * * *").is_err());
+    }
+
+    #[test]
+    fn next_minute_and_steps() {
+        let spec = CronSpec::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            spec.next_after(&local(2024, 3, 1, 10, 7)),
+            Some(local(2024, 3, 1, 10, 15))
+        );
+        assert_eq!(
+            spec.next_after(&local(2024, 3, 1, 10, 45)),
+            Some(local(2024, 3, 1, 11, 0))
+        );
+    }
+
+    #[test]
+    fn weekday_and_leap_day() {
+        // 2024-03-01 is a Friday; the next Monday 09:30 is the 4th
+        let spec = CronSpec::parse("30 9 * * 1").unwrap();
+        assert_eq!(
+            spec.next_after(&local(2024, 3, 1, 12, 0)),
+            Some(local(2024, 3, 4, 9, 30))
+        );
+
+        let spec = CronSpec::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            spec.next_after(&local(2024, 3, 1, 0, 0)),
+            Some(local(2028, 2, 29, 0, 0))
+        );
+    }
+
+    #[test]
+    fn daylight_saving_changes() {
+        // 01:30 doesn't exist on the day the clocks go forward, so the
+        // job runs at 02:00, once they have
+        let spec = CronSpec::parse("30 1 * * *").unwrap();
+        let after = london(2024, 3, 30, 12, 0);
+        let skipped = spec.next_after(&after).unwrap();
+        assert_eq!(skipped, london(2024, 3, 31, 2, 0));
+        assert_eq!(spec.next_after(&skipped), Some(london(2024, 4, 1, 1, 30)));
+
+        // 01:30 happens twice on the day they go back, but the job
+        // only runs the first time
+        let after = london(2024, 10, 26, 12, 0);
+        let repeated = spec.next_after(&after).unwrap();
+        assert_eq!(repeated.offset().local_minus_utc(), 3600);
+        assert_eq!(
+            spec.next_after(&repeated),
+            Some(london(2024, 10, 28, 1, 30))
+        );
+    }
+}
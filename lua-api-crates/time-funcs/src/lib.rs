@@ -1,3 +1,4 @@
+use crate::cron::CronSpec;
 use chrono::prelude::*;
 use config::lua::mlua::{self, Lua, MetaMethod, UserData, UserDataMethods, UserDataRef};
 use config::lua::{
@@ -5,7 +6,9 @@ use config::lua::{
 };
 use config::ConfigSubscription;
 use std::rc::Rc;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+
+mod cron;
 
 lazy_static::lazy_static! {
     static ref CONFIG_SUBSCRIPTION: Mutex<Option<ConfigSubscription>> = Mutex::new(None);
@@ -55,14 +58,24 @@ pub fn config_was_reloaded() -> bool {
     true
 }
 
-/// Keeps track of `call_after` state
+/// When a scheduled callback should run
+#[derive(Debug, Clone)]
+enum When {
+    /// Once, after this many seconds
+    After(f64),
+    /// Repeatedly, every this many seconds
+    Every(f64),
+    /// At each minute matched by a cron spec
+    Cron(Arc<CronSpec>),
+}
+
+/// Keeps track of `call_after` and `schedule` state
 #[derive(Debug, Clone)]
 struct ScheduledEvent {
     /// The name of the registry entry that will resolve to
     /// their callback function
     user_event_id: String,
-    /// The delay after which to run their callback
-    interval_seconds: f64,
+    when: When,
 }
 
 impl ScheduledEvent {
@@ -94,13 +107,29 @@ impl ScheduledEvent {
     }
 
     async fn run(self, lua: &Lua, generation: usize) -> mlua::Result<()> {
-        let duration = std::time::Duration::from_secs_f64(self.interval_seconds);
+        let duration = match &self.when {
+            // An interval too large to represent never elapses
+            When::After(seconds) | When::Every(seconds) => {
+                std::time::Duration::try_from_secs_f64(seconds.max(0.))
+                    .unwrap_or(std::time::Duration::MAX)
+            }
+            When::Cron(spec) => {
+                let now = Local::now();
+                match spec.next_after(&now) {
+                    Some(next) => (next - now).to_std().unwrap_or_default(),
+                    None => return Ok(()),
+                }
+            }
+        };
         smol::Timer::after(duration).await;
         // Skip doing anything of consequence if the generation has
         // changed.
         if config::configuration().generation() == generation {
             let args = lua.pack_multi(())?;
-            emit_event(&lua, (self.user_event_id, args)).await?;
+            emit_event(&lua, (self.user_event_id.clone(), args)).await?;
+            if !matches!(self.when, When::After(_)) {
+                self.schedule(generation);
+            }
         }
         Ok(())
     }
@@ -112,6 +141,30 @@ impl UserData for ScheduledEvent {
 
 const SCHEDULED_EVENTS: &str = "wezterm-scheduled-events";
 
+/// Schedules `func` right away when called from an event handler,
+/// otherwise defers it until the config has finished loading
+fn add_scheduled_event(lua: &Lua, func: mlua::Function, when: When) -> mlua::Result<()> {
+    let user_event_id = wrap_callback(lua, func)?;
+
+    let event = ScheduledEvent {
+        user_event_id,
+        when,
+    };
+
+    if is_event_emission(lua)? {
+        let generation = config::configuration().generation();
+        event.schedule(generation);
+    } else {
+        let scheduled_events: Vec<UserDataRef<ScheduledEvent>> =
+            lua.named_registry_value(SCHEDULED_EVENTS)?;
+        let mut scheduled_events: Vec<ScheduledEvent> =
+            scheduled_events.into_iter().map(|e| e.clone()).collect();
+        scheduled_events.push(event);
+        lua.set_named_registry_value(SCHEDULED_EVENTS, scheduled_events)?;
+    }
+    Ok(())
+}
+
 pub fn register(lua: &Lua) -> anyhow::Result<()> {
     {
         let mut sub = CONFIG_SUBSCRIPTION.lock().unwrap();
@@ -150,25 +203,35 @@ pub fn register(lua: &Lua) -> anyhow::Result<()> {
     time_mod.set(
         "call_after",
         lua.create_function(|lua, (interval_seconds, func): (f64, mlua::Function)| {
-            let user_event_id = wrap_callback(lua, func)?;
+            add_scheduled_event(lua, func, When::After(interval_seconds))
+        })?,
+    )?;
 
-            let event = ScheduledEvent {
-                user_event_id,
-                interval_seconds,
+    time_mod.set(
+        "schedule",
+        lua.create_function(|lua, (spec, func): (mlua::Value, mlua::Function)| {
+            let when = match spec {
+                mlua::Value::Integer(seconds) => When::Every(seconds as f64),
+                mlua::Value::Number(seconds) => When::Every(seconds),
+                mlua::Value::String(spec) => {
+                    let spec = CronSpec::parse(spec.to_str()?)
+                        .map_err(|err| mlua::Error::external(format!("{err:#}")))?;
+                    When::Cron(Arc::new(spec))
+                }
+                _ => {
+                    return Err(mlua::Error::external(
+                        "schedule expects an interval in seconds or a cron string",
+                    ))
+                }
             };
-
-            if is_event_emission(lua)? {
-                let generation = config::configuration().generation();
-                event.schedule(generation);
-            } else {
-                let scheduled_events: Vec<UserDataRef<ScheduledEvent>> =
-                    lua.named_registry_value(SCHEDULED_EVENTS)?;
-                let mut scheduled_events: Vec<ScheduledEvent> =
-                    scheduled_events.into_iter().map(|e| e.clone()).collect();
-                scheduled_events.push(event);
-                lua.set_named_registry_value(SCHEDULED_EVENTS, scheduled_events)?;
+            if let When::Every(seconds) = when {
+                if !seconds.is_finite() || seconds <= 0. {
+                    return Err(mlua::Error::external(
+                        "schedule interval must be a finite number greater than zero",
+                    ));
+                }
             }
-            Ok(())
+            add_scheduled_event(lua, func, when)
         })?,
     )?;
 
@@ -0,0 +1,178 @@
+//! Prompts for secrets on behalf of programs running in panes.
+//!
+//! When `ssh_askpass` is enabled, commands spawned into local panes have
+//! `SSH_ASKPASS` and `GIT_ASKPASS` pointed at a small script that runs
+//! `kaku askpass`.  That sends a `RequestSecret` PDU to the mux, which
+//! raises `MuxNotification::SecretRequested` so that the GUI window
+//! showing the pane can prompt for the secret in an overlay.  This keeps
+//! ssh and git prompts from getting lost when they have no terminal,
+//! such as when git is run by an editor or a script with piped output.
+use crate::pane::PaneId;
+use parking_lot::Mutex;
+use portable_pty::CommandBuilder;
+use std::sync::Arc;
+
+type Reply = Box<dyn FnOnce(anyhow::Result<Option<String>>) + Send>;
+
+/// A pending request for a secret.
+/// If it is dropped without being answered, for example because no
+/// window is showing the pane, the requester is told that nothing was
+/// able to prompt for it, so that it can fall back to another method.
+pub struct SecretRequest {
+    pub pane_id: PaneId,
+    pub prompt: String,
+    reply: Mutex<Option<Reply>>,
+}
+
+impl SecretRequest {
+    pub fn new(
+        pane_id: PaneId,
+        prompt: String,
+        reply: impl FnOnce(anyhow::Result<Option<String>>) + Send + 'static,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            pane_id,
+            prompt,
+            reply: Mutex::new(Some(Box::new(reply))),
+        })
+    }
+
+    /// Answers the request with the secret that was entered, or None
+    /// if the user cancelled the prompt.  Only the first answer is used.
+    pub fn answer(&self, secret: Option<String>) {
+        if let Some(reply) = self.reply.lock().take() {
+            reply(Ok(secret));
+        }
+    }
+
+    pub fn is_answered(&self) -> bool {
+        self.reply.lock().is_none()
+    }
+}
+
+impl Drop for SecretRequest {
+    fn drop(&mut self) {
+        if let Some(reply) = self.reply.get_mut().take() {
+            reply(Err(anyhow::anyhow!(
+                "no window is showing pane {}",
+                self.pane_id
+            )));
+        }
+    }
+}
+
+impl std::fmt::Debug for SecretRequest {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("SecretRequest")
+            .field("pane_id", &self.pane_id)
+            .field("prompt", &self.prompt)
+            .field("answered", &self.is_answered())
+            .finish()
+    }
+}
+
+/// Points `SSH_ASKPASS` and `GIT_ASKPASS` at `kaku askpass` for a command
+/// that is about to be spawned into a pane, if `ssh_askpass` is enabled.
+/// Variables that are already set, whether inherited or from
+/// `set_environment_variables`, are left alone.
+pub fn apply_askpass_environment(cmd: &mut CommandBuilder) {
+    if !config::configuration().ssh_askpass {
+        return;
+    }
+    if cmd.get_env("SSH_ASKPASS").is_some() && cmd.get_env("GIT_ASKPASS").is_some() {
+        return;
+    }
+
+    let script = match askpass_script() {
+        Ok(Some(script)) => script,
+        Ok(None) => return,
+        Err(err) => {
+            log::warn!("unable to set up the askpass helper: {err:#}");
+            return;
+        }
+    };
+
+    if cmd.get_env("SSH_ASKPASS").is_none() {
+        cmd.env("SSH_ASKPASS", &script);
+        // Without this, ssh only uses the helper when DISPLAY is set,
+        // which it usually isn't on macOS
+        if cmd.get_env("SSH_ASKPASS_REQUIRE").is_none() {
+            cmd.env("SSH_ASKPASS_REQUIRE", "force");
+        }
+    }
+    if cmd.get_env("GIT_ASKPASS").is_none() {
+        cmd.env("GIT_ASKPASS", &script);
+    }
+}
+
+/// SSH_ASKPASS and GIT_ASKPASS name a program that is run with the
+/// prompt as its only argument, so we write a script that runs the
+/// `kaku` cli from alongside our own executable.
+/// Returns None if there is no `kaku` cli to run.
+#[cfg(unix)]
+fn askpass_script() -> anyhow::Result<Option<std::path::PathBuf>> {
+    use anyhow::Context;
+    use std::os::unix::fs::PermissionsExt;
+
+    let exe = std::env::current_exe().context("resolve executable path")?;
+    let kaku = match exe.parent() {
+        Some(dir) => dir.join("kaku"),
+        None => return Ok(None),
+    };
+    if !kaku.is_file() {
+        return Ok(None);
+    }
+    let kaku = kaku
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("{} is not UTF-8", kaku.display()))?;
+    let script = format!(
+        "#!/bin/sh\nexec {} askpass \"$@\"\n",
+        shell_words::quote(kaku)
+    );
+
+    let path = config::RUNTIME_DIR.join("askpass.sh");
+    if std::fs::read_to_string(&path).ok().as_deref() != Some(script.as_str()) {
+        config::create_user_owned_dirs(&config::RUNTIME_DIR)?;
+        // Write and rename so that a concurrently running helper never
+        // sees a partial script
+        let temp = config::RUNTIME_DIR.join(format!("askpass.sh.{}", std::process::id()));
+        std::fs::write(&temp, &script).with_context(|| format!("writing {}", temp.display()))?;
+        std::fs::set_permissions(&temp, std::fs::Permissions::from_mode(0o700))?;
+        std::fs::rename(&temp, &path).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(Some(path))
+}
+
+#[cfg(not(unix))]
+fn askpass_script() -> anyhow::Result<Option<std::path::PathBuf>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unanswered_requests_report_an_error() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let request = SecretRequest::new(1, "Password:".to_string(), move |result| {
+            tx.send(result.map_err(|err| err.to_string())).unwrap();
+        });
+        drop(request);
+        assert_eq!(
+            rx.recv().unwrap(),
+            Err("no window is showing pane 1".to_string())
+        );
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let request = SecretRequest::new(2, "Password:".to_string(), move |result| {
+            tx.send(result.map_err(|err| err.to_string())).unwrap();
+        });
+        request.answer(Some("hunter2".to_string()));
+        assert!(request.is_answered());
+        request.answer(None);
+        drop(request);
+        assert_eq!(rx.recv().unwrap(), Ok(Some("hunter2".to_string())));
+        assert!(rx.try_recv().is_err());
+    }
+}
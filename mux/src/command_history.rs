@@ -0,0 +1,309 @@
+//! A per-workspace audit log of the commands run in panes.
+//!
+//! Shell integration (OSC 133) tells the terminal where each command
+//! line starts and when the command finishes; when `command_history_log`
+//! is enabled, each finished command is appended to
+//! `<data dir>/command-history/<workspace>.jsonl` as one JSON encoded
+//! `CommandHistoryEntry` per line.
+use crate::pane::{CachePolicy, PaneId};
+use crate::Mux;
+use crossbeam::channel::{unbounded, Sender};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandHistoryEntry {
+    /// The command line, as it was entered at the prompt
+    pub command: String,
+    /// The working directory of the pane when the command finished
+    pub cwd: Option<String>,
+    /// When the command started, in seconds since the unix epoch
+    pub started: u64,
+    /// How long the command ran for, in milliseconds
+    pub duration_ms: u64,
+    /// The exit status, if the shell reported one
+    pub exit_status: Option<i32>,
+    pub pane_id: PaneId,
+    pub domain: String,
+}
+
+/// The directory holding the history files for all workspaces
+pub fn history_dir() -> PathBuf {
+    config::DATA_DIR.join("command-history")
+}
+
+/// Returns the history file for `workspace`.
+/// Workspace names are free form, so every byte other than a lowercase
+/// letter, digit, `-` or `_` is escaped as `%xx`.  Escaping upper case
+/// letters too means that two workspaces never share a file, even on a
+/// case insensitive filesystem.
+pub fn history_path(workspace: &str) -> PathBuf {
+    let mut name = String::new();
+    for b in workspace.bytes() {
+        if b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_' {
+            name.push(b as char);
+        } else {
+            write!(name, "%{b:02x}").ok();
+        }
+    }
+    if name.is_empty() {
+        // Escaping never produces a lone `%`
+        name.push('%');
+    }
+    history_dir().join(format!("{name}.jsonl"))
+}
+
+/// The inverse of the escaping done by `history_path`
+fn workspace_from_file_stem(stem: &str) -> String {
+    if stem == "%" {
+        return String::new();
+    }
+    let mut bytes = vec![];
+    let mut rest = stem.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let escaped = match (b, tail.get(..2)) {
+            (b'%', Some(hex)) => std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                bytes.push(byte);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Appends `entry` to the history of `workspace`
+pub fn append(workspace: &str, entry: &CommandHistoryEntry) -> anyhow::Result<()> {
+    config::create_user_owned_dirs(&history_dir())?;
+    append_to(&history_path(workspace), entry)
+}
+
+/// Loads the history of `workspace`, oldest first.
+/// A workspace that has no history yet has an empty history.
+pub fn load(workspace: &str) -> anyhow::Result<Vec<CommandHistoryEntry>> {
+    load_from(&history_path(workspace))
+}
+
+/// Loads the history of every workspace, oldest first, along with the
+/// workspace each entry came from
+pub fn load_all() -> anyhow::Result<Vec<(String, CommandHistoryEntry)>> {
+    let dir = match std::fs::read_dir(history_dir()) {
+        Ok(dir) => dir,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => anyhow::bail!("reading {}: {err:#}", history_dir().display()),
+    };
+    let mut entries = vec![];
+    for dir_entry in dir {
+        let path = dir_entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let workspace = path
+            .file_stem()
+            .map(|stem| workspace_from_file_stem(&stem.to_string_lossy()))
+            .unwrap_or_default();
+        for entry in load_from(&path)? {
+            entries.push((workspace.clone(), entry));
+        }
+    }
+    entries.sort_by_key(|(_, entry)| entry.started);
+    Ok(entries)
+}
+
+fn append_to(path: &Path, entry: &CommandHistoryEntry) -> anyhow::Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        // The history holds command lines, which may include secrets
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|err| anyhow::anyhow!("opening {}: {err:#}", path.display()))?;
+    // A single write keeps concurrent appenders from interleaving lines
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+fn load_from(path: &Path) -> anyhow::Result<Vec<CommandHistoryEntry>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => anyhow::bail!("opening {}: {err:#}", path.display()),
+    };
+    let mut entries = vec![];
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            // Tolerate a line torn by a crash mid-write
+            Err(err) => log::warn!("skipping malformed entry in {}: {err:#}", path.display()),
+        }
+    }
+    Ok(entries)
+}
+
+lazy_static::lazy_static! {
+    /// Entries are appended on a dedicated thread so that the mux thread
+    /// never waits on the filesystem, while still being written in the
+    /// order that they were recorded
+    static ref WRITER: Sender<(String, CommandHistoryEntry)> = {
+        let (tx, rx) = unbounded::<(String, CommandHistoryEntry)>();
+        std::thread::spawn(move || {
+            for (workspace, entry) in rx {
+                if let Err(err) = append(&workspace, &entry) {
+                    log::error!(
+                        "recording command history for pane {}: {err:#}",
+                        entry.pane_id
+                    );
+                }
+            }
+        });
+        tx
+    };
+}
+
+/// Records a command that finished in `pane_id`, if the history is enabled
+pub(crate) fn record_command_finished(
+    pane_id: PaneId,
+    command: &str,
+    exit_status: Option<i32>,
+    started: SystemTime,
+    duration: Duration,
+) {
+    if !config::configuration().command_history_log {
+        return;
+    }
+    let mux = Mux::get();
+    let Some(pane) = mux.get_pane(pane_id) else {
+        return;
+    };
+    let Some((domain_id, window_id, _tab_id)) = mux.resolve_pane_id(pane_id) else {
+        return;
+    };
+    let workspace = match mux.get_window(window_id) {
+        Some(window) => window.get_workspace().to_string(),
+        None => return,
+    };
+    let domain = mux
+        .get_domain(domain_id)
+        .map(|domain| domain.domain_name().to_string())
+        .unwrap_or_default();
+    let cwd = pane
+        .get_current_working_dir(CachePolicy::AllowStale)
+        .map(|url| match url.to_file_path() {
+            Ok(path) => path.display().to_string(),
+            Err(()) => url.to_string(),
+        });
+
+    let entry = CommandHistoryEntry {
+        command: command.to_string(),
+        cwd,
+        started: started
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        duration_ms: duration.as_millis().try_into().unwrap_or(u64::MAX),
+        exit_status,
+        pane_id,
+        domain,
+    };
+    WRITER.send((workspace, entry)).ok();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("default.jsonl");
+        assert!(load_from(&path).unwrap().is_empty());
+
+        let entry = CommandHistoryEntry {
+            command: "cargo test".to_string(),
+            cwd: Some("/tmp".to_string()),
+            started: 1_700_000_000,
+            duration_ms: 1234,
+            exit_status: Some(101),
+            pane_id: 3,
+            domain: "local".to_string(),
+        };
+        append_to(&path, &entry).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"command\":")
+            .unwrap();
+        append_to(&path, &entry).unwrap();
+
+        assert_eq!(load_from(&path).unwrap(), vec![entry]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn history_is_only_readable_by_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("default.jsonl");
+        let entry = CommandHistoryEntry {
+            command: "export TOKEN=hunter2".to_string(),
+            cwd: None,
+            started: 0,
+            duration_ms: 0,
+            exit_status: Some(0),
+            pane_id: 0,
+            domain: "local".to_string(),
+        };
+        append_to(&path, &entry).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn workspace_names_stay_in_the_history_dir() {
+        let path = history_path("../../etc/passwd");
+        assert_eq!(path.parent().unwrap(), history_dir());
+        assert_eq!(
+            path.file_name().unwrap(),
+            "%2e%2e%2f%2e%2e%2fetc%2fpasswd.jsonl"
+        );
+    }
+
+    #[test]
+    fn workspace_names_do_not_collide() {
+        let names = ["a/b", "a_b", "a%2fb", "A_b", "a_B", "", "%", "caf\u{e9}"];
+        let mut paths: Vec<PathBuf> = names.iter().map(|name| history_path(name)).collect();
+        for (name, path) in names.iter().zip(&paths) {
+            let stem = path.file_stem().unwrap().to_str().unwrap();
+            assert_eq!(workspace_from_file_stem(stem), *name);
+            let folded = stem.to_lowercase();
+            assert_eq!(folded, stem, "{name:?} depends on case");
+        }
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), names.len());
+    }
+}
@@ -6,37 +6,40 @@ use promise::spawn::block_on;
 use promise::Promise;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
-use termwiz::cell::{unicode_column_width, CellAttributes};
+use termwiz::cell::CellAttributes;
 use termwiz::lineedit::*;
 use termwiz::surface::{Change, Position};
 use termwiz::terminal::*;
 use wezterm_term::TerminalSize;
 
+/// Reads the response to an authentication prompt.
+/// When `echo` is false the response is a secret and nothing at all is
+/// shown as it is typed, not even placeholder characters, so that its
+/// length isn't revealed to anyone watching the screen.
 #[derive(Default)]
-struct PasswordPromptHost {
+pub(crate) struct PasswordPromptHost {
     history: BasicHistory,
+    pub echo: bool,
 }
 impl LineEditorHost for PasswordPromptHost {
     fn history(&mut self) -> &mut dyn History {
         &mut self.history
     }
 
-    // Rewrite the input so that we can obscure the password
-    // characters when output to the terminal widget
     fn highlight_line(&self, line: &str, cursor_position: usize) -> (Vec<OutputElement>, usize) {
-        let placeholder = "🔑";
-        let grapheme_count = unicode_column_width(line, None);
-        let mut output = vec![];
-        for _ in 0..grapheme_count {
-            output.push(OutputElement::Text(placeholder.to_string()));
+        if self.echo {
+            (vec![OutputElement::Text(line.to_string())], cursor_position)
+        } else {
+            (vec![], 0)
         }
-        (
-            output,
-            unicode_column_width(placeholder, None) * cursor_position,
-        )
     }
 }
 
+/// Marks a prompt whose response won't be echoed
+pub(crate) fn secret_prompt(prompt: &str) -> String {
+    format!("🔒 {prompt}")
+}
+
 pub enum UIRequest {
     /// Display something
     Output(Vec<Change>),
@@ -101,7 +104,7 @@ impl ConnectionUIImpl {
 
     fn password_prompt(&mut self, prompt: &str) -> anyhow::Result<String> {
         let mut editor = LineEditor::new(&mut self.term);
-        editor.set_prompt(prompt);
+        editor.set_prompt(&secret_prompt(prompt));
 
         let mut host = PasswordPromptHost::default();
         if let Some(line) = editor.read_line(&mut host)? {
@@ -7,7 +7,8 @@
 
 use crate::localpane::LocalPane;
 use crate::pane::{alloc_pane_id, Pane, PaneId};
-use crate::pane_encoding::PaneInputEncoder;
+use crate::pane_encoding::{encoding_from_locale_env, EncodingErrorCounts, PaneInputEncoder};
+use crate::pane_log::PaneOutputLog;
 use crate::tab::{SplitRequest, Tab, TabId};
 use crate::window::WindowId;
 use crate::Mux;
@@ -141,12 +142,12 @@ pub trait Domain: Downcast + Send + Sync {
             None => anyhow::bail!("Invalid tab id {}", tab),
         };
 
-        let (pane_index, source_encoding) = match tab
+        let (pane_index, source_encoding, source_input_encoding) = match tab
             .iter_panes_ignoring_zoom()
             .iter()
             .find(|p| p.pane.pane_id() == pane_id)
         {
-            Some(p) => (p.index, p.pane.get_encoding()),
+            Some(p) => (p.index, p.pane.get_encoding(), p.pane.get_input_encoding()),
             None => anyhow::bail!("invalid pane id {}", pane_id),
         };
 
@@ -160,14 +161,19 @@ pub trait Domain: Downcast + Send + Sync {
                 command,
                 command_dir,
             } => {
-                self.spawn_pane(
-                    mux,
-                    split_size.second,
-                    command,
-                    command_dir,
-                    source_encoding,
-                )
-                .await?
+                let pane = self
+                    .spawn_pane(
+                        mux,
+                        split_size.second,
+                        command,
+                        command_dir,
+                        source_encoding,
+                    )
+                    .await?;
+                if source_input_encoding != source_encoding {
+                    pane.set_input_encoding(source_input_encoding);
+                }
+                pane
             }
             SplitSource::MovePane(src_pane_id) => {
                 let (_domain, _window, src_tab) = mux
@@ -529,16 +535,22 @@ impl LocalDomain {
 
         let mut cmd = match command {
             Some(mut cmd) => {
+                config.apply_domain_environment(&mut cmd, &self.name);
                 config.apply_cmd_defaults(&mut cmd, default_prog, config.default_cwd.as_ref());
                 cmd
             }
-            None => config.build_prog(
-                None,
-                default_prog,
-                wsl.as_ref()
-                    .map(|wsl| wsl.default_cwd.as_ref())
-                    .unwrap_or(config.default_cwd.as_ref()),
-            )?,
+            None => {
+                let mut cmd = CommandBuilder::new_default_prog();
+                config.apply_domain_environment(&mut cmd, &self.name);
+                config.apply_cmd_defaults(
+                    &mut cmd,
+                    default_prog,
+                    wsl.as_ref()
+                        .map(|wsl| wsl.default_cwd.as_ref())
+                        .unwrap_or(config.default_cwd.as_ref()),
+                );
+                cmd
+            }
         };
         if let Some(dir) = command_dir {
             cmd.cwd(dir);
@@ -550,11 +562,67 @@ impl LocalDomain {
         if let Some(agent) = mux.agent.as_ref() {
             cmd.env("SSH_AUTH_SOCK", agent.path());
         }
+        crate::askpass::apply_askpass_environment(&mut cmd);
+
+        let env: HashMap<String, String> = cmd
+            .iter_full_env_as_str()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        if let Some(adjusted) = adjust_spawn_environment(&self.name, env.clone()).await {
+            for k in env.keys().filter(|k| !adjusted.contains_key(*k)) {
+                cmd.env_remove(k);
+            }
+            for (k, v) in adjusted {
+                if env.get(&k) != Some(&v) {
+                    cmd.env(k, v);
+                }
+            }
+        }
+
         self.fixup_command(&mut cmd).await?;
         Ok(cmd)
     }
 }
 
+/// Emits the `adjust-spawn-environment` event with the environment of a
+/// command that is about to be spawned into `domain_name`.
+/// Returns the replacement environment if the handler returned one,
+/// or None if the environment should be used as-is.  A failing handler
+/// is logged rather than preventing the spawn.
+pub(crate) async fn adjust_spawn_environment(
+    domain_name: &str,
+    env: HashMap<String, String>,
+) -> Option<HashMap<String, String>> {
+    let result: anyhow::Result<Option<HashMap<String, String>>> =
+        config::with_lua_config_on_main_thread(|lua| async move {
+            let Some(lua) = lua else {
+                return Ok(None);
+            };
+            let value = config::lua::emit_async_callback(
+                &*lua,
+                (
+                    "adjust-spawn-environment".to_string(),
+                    (env, domain_name.to_string()),
+                ),
+            )
+            .await?;
+            let env: Option<HashMap<String, String>> = luahelper::from_lua_value_dynamic(value)
+                .context("interpreting result of adjust-spawn-environment")?;
+            Ok(env)
+        })
+        .await;
+    match result {
+        Ok(env) => env,
+        Err(err) => {
+            log::error!(
+                "calling adjust-spawn-environment for domain {domain_name}: {err:#}; \
+                 spawning with the unmodified environment"
+            );
+            None
+        }
+    }
+}
+
 /// Allows sharing the writer between the Pane and the Terminal.
 /// This could potentially be eliminated in the future if we can
 /// teach the Pane impl to reference the writer in the Termninal,
@@ -565,6 +633,8 @@ pub(crate) struct WriterWrapper {
     writer: Arc<Mutex<Box<dyn Write + Send>>>,
     encoding: Arc<AtomicU8>,
     input_encoder: Arc<Mutex<PaneInputEncoder>>,
+    encoding_errors: Arc<EncodingErrorCounts>,
+    output_log: Arc<PaneOutputLog>,
 }
 
 impl WriterWrapper {
@@ -573,15 +643,36 @@ impl WriterWrapper {
             writer: Arc::new(Mutex::new(writer)),
             encoding,
             input_encoder: Arc::new(Mutex::new(PaneInputEncoder::default())),
+            encoding_errors: Arc::new(EncodingErrorCounts::default()),
+            output_log: Arc::new(PaneOutputLog::default()),
         }
     }
+
+    /// The error counts that this writer adds its encoding errors to
+    pub fn encoding_errors(&self) -> Arc<EncodingErrorCounts> {
+        Arc::clone(&self.encoding_errors)
+    }
+
+    /// The log that this writer records the encoded input in
+    pub fn output_log(&self) -> Arc<PaneOutputLog> {
+        Arc::clone(&self.output_log)
+    }
 }
 
 impl std::io::Write for WriterWrapper {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         let encoding = PaneEncoding::from_u8(self.encoding.load(Ordering::Relaxed));
-        let encoded = self.input_encoder.lock().encode(encoding, buf);
+        let encoded = {
+            let mut encoder = self.input_encoder.lock();
+            let encoded = encoder.encode(encoding, buf);
+            let errors = encoder.take_error_count();
+            if errors > 0 {
+                self.encoding_errors.add_encode_errors(errors);
+            }
+            encoded
+        };
         self.writer.lock().write_all(&encoded)?;
+        self.output_log.log_input(&encoded);
         Ok(buf.len())
     }
 
@@ -674,6 +765,12 @@ impl Domain for LocalDomain {
             .build_command(mux, command, command_dir, pane_id)
             .await
             .context("build_command")?;
+        let encoding =
+            if encoding == PaneEncoding::Utf8 && configuration().guess_encoding_from_locale {
+                encoding_from_locale_env(|name| cmd.get_env(name)).unwrap_or(encoding)
+            } else {
+                encoding
+            };
         let pair = self
             .pty_system
             .lock()
@@ -694,6 +791,8 @@ impl Domain for LocalDomain {
         let child_result = pair.slave.spawn_command(cmd);
         let encoding = Arc::new(AtomicU8::new(encoding.to_u8()));
         let mut writer = WriterWrapper::new(pair.master.take_writer()?, Arc::clone(&encoding));
+        let encoding_errors = writer.encoding_errors();
+        let output_log = writer.output_log();
 
         let mut terminal = wezterm_term::Terminal::new(
             size,
@@ -715,6 +814,8 @@ impl Domain for LocalDomain {
                 Box::new(writer),
                 self.id,
                 encoding,
+                encoding_errors,
+                output_log,
                 command_description,
             )),
             Err(err) => {
@@ -734,6 +835,8 @@ impl Domain for LocalDomain {
                     Box::new(writer),
                     self.id,
                     encoding,
+                    encoding_errors,
+                    output_log,
                     command_description,
                 ))
             }
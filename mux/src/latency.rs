@@ -0,0 +1,137 @@
+//! Tracks how long it takes for input sent to a pane to be echoed back.
+//!
+//! The time between a key press being written to the pane and the next
+//! output arriving from it is a good approximation of the latency that
+//! the user perceives while typing, whether that is dominated by the
+//! application itself, an ssh connection or a mux server.
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The number of samples that the rolling statistics are computed over
+const MAX_SAMPLES: usize = 64;
+
+/// Responses that take longer than this are assumed to be unrelated to
+/// the input, for example because the application didn't echo it
+const MAX_LATENCY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub last: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub p90: Duration,
+}
+
+#[derive(Default)]
+struct Inner {
+    pending: Option<Instant>,
+    samples: VecDeque<Duration>,
+}
+
+#[derive(Default)]
+pub struct EchoLatency {
+    inner: Mutex<Inner>,
+}
+
+impl EchoLatency {
+    /// Records that input was sent to the pane.  If we are already waiting
+    /// for a response the earlier time is kept, so that typing ahead
+    /// doesn't hide a slow response.
+    pub fn input_sent(&self) {
+        let mut inner = self.inner.lock();
+        if inner.pending.is_none() {
+            inner.pending = Some(Instant::now());
+        }
+    }
+
+    /// Records that output was received from the pane, completing the
+    /// sample for any outstanding input
+    pub fn output_received(&self) {
+        let pending = self.inner.lock().pending.take();
+        if let Some(sent) = pending {
+            self.record(sent.elapsed());
+        }
+    }
+
+    /// Adds a round trip time that was measured elsewhere
+    pub fn record(&self, latency: Duration) {
+        if latency > MAX_LATENCY {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        if inner.samples.len() == MAX_SAMPLES {
+            inner.samples.pop_front();
+        }
+        inner.samples.push_back(latency);
+    }
+
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock();
+        inner.pending.take();
+        inner.samples.clear();
+    }
+
+    /// Returns statistics over the most recent samples, or None if
+    /// nothing has been measured yet
+    pub fn stats(&self) -> Option<LatencyStats> {
+        let inner = self.inner.lock();
+        let last = *inner.samples.back()?;
+        let mut sorted: Vec<Duration> = inner.samples.iter().copied().collect();
+        sorted.sort();
+        let total: Duration = sorted.iter().sum();
+        let p90_idx = (sorted.len() * 9).div_ceil(10).saturating_sub(1);
+        Some(LatencyStats {
+            samples: sorted.len(),
+            last,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: total / sorted.len() as u32,
+            p90: sorted[p90_idx],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rolling_stats() {
+        let latency = EchoLatency::default();
+        assert_eq!(latency.stats(), None);
+
+        // Output with no outstanding input doesn't produce a sample
+        latency.output_received();
+        assert_eq!(latency.stats(), None);
+
+        for ms in 1..=10 {
+            latency.record(Duration::from_millis(ms));
+        }
+        latency.record(Duration::from_secs(10));
+
+        let stats = latency.stats().unwrap();
+        assert_eq!(stats.samples, 10);
+        assert_eq!(stats.last, Duration::from_millis(10));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(10));
+        assert_eq!(stats.mean, Duration::from_micros(5500));
+        assert_eq!(stats.p90, Duration::from_millis(9));
+
+        for _ in 0..MAX_SAMPLES {
+            latency.record(Duration::from_millis(20));
+        }
+        let stats = latency.stats().unwrap();
+        assert_eq!(stats.samples, MAX_SAMPLES);
+        assert_eq!(stats.min, Duration::from_millis(20));
+
+        latency.input_sent();
+        latency.output_received();
+        assert_eq!(latency.stats().unwrap().samples, MAX_SAMPLES);
+
+        latency.reset();
+        assert_eq!(latency.stats(), None);
+    }
+}
@@ -2,17 +2,14 @@ use crate::client::{ClientId, ClientInfo};
 use crate::pane::{CachePolicy, Pane, PaneId, PaneReader};
 use crate::pane_encoding::{decode_bytes_to_string, PaneOutputDecoder};
 use crate::ssh_agent::AgentProxy;
-use crate::tab::{SplitRequest, Tab, TabId};
+use crate::tab::{PaneNode, SplitDirection, SplitRequest, SplitSize, Tab, TabId};
 use crate::window::{Window, WindowId};
 use anyhow::{anyhow, Context, Error};
 use config::keyassignment::{PaneEncoding, SpawnTabDomain};
 use config::{configuration, ExitBehavior, GuiPosition};
+use crossbeam::channel::{bounded, Receiver, RecvTimeoutError, Sender};
 use domain::{Domain, DomainId, DomainState, SplitSource};
-use filedescriptor::{
-    poll, pollfd, socketpair, AsRawSocketDescriptor, FileDescriptor, POLLHUP, POLLIN,
-};
-#[cfg(unix)]
-use libc::{c_int, SOL_SOCKET, SO_RCVBUF, SO_SNDBUF};
+use filedescriptor::{poll, pollfd, POLLHUP, POLLIN};
 use log::error;
 use metrics::histogram;
 use parking_lot::{
@@ -23,8 +20,6 @@ use portable_pty::{CommandBuilder, ExitStatus, PtySize};
 use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::io::{Read, Write};
-#[cfg(windows)]
-use std::os::raw::c_int;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use std::thread;
@@ -33,16 +28,18 @@ use termwiz::escape::csi::{DecPrivateMode, DecPrivateModeCode, Device, Mode};
 use termwiz::escape::{Action, CSI};
 use thiserror::*;
 use wezterm_term::{Clipboard, ClipboardSelection, DownloadHandler, TerminalSize};
-#[cfg(windows)]
-use winapi::um::winsock2::{SOL_SOCKET, SO_RCVBUF, SO_SNDBUF};
 
 pub mod activity;
+pub mod askpass;
 pub mod client;
+pub mod command_history;
 pub mod connui;
 pub mod domain;
+pub mod latency;
 pub mod localpane;
 pub mod pane;
 pub mod pane_encoding;
+pub mod pane_log;
 pub mod renderable;
 pub mod ssh;
 pub mod ssh_agent;
@@ -86,11 +83,16 @@ pub enum MuxNotification {
         window_id: WindowId,
     },
     PaneFocused(PaneId),
+    /// The number of encoding errors in a pane has reached
+    /// `encoding_error_event_threshold`
+    PaneEncodingErrors(PaneId),
     TabResized(TabId),
     TabTitleChanged {
         tab_id: TabId,
         title: String,
     },
+    TabColorChanged(TabId),
+    TabGroupChanged(TabId),
     WindowTitleChanged {
         window_id: WindowId,
         title: String,
@@ -99,6 +101,8 @@ pub enum MuxNotification {
         old_workspace: String,
         new_workspace: String,
     },
+    /// A program in a pane is asking for a secret via `kaku askpass`
+    SecretRequested(Arc<askpass::SecretRequest>),
 }
 
 static SUB_ID: AtomicUsize = AtomicUsize::new(0);
@@ -119,21 +123,91 @@ pub struct Mux {
     agent: Option<AgentProxy>,
     /// Dead flags for pane reader threads, used to signal thread termination
     pane_dead_flags: RwLock<HashMap<PaneId, Arc<AtomicBool>>>,
+    /// Output read from each pane's pty that is waiting to be parsed
+    pane_output_queues: RwLock<HashMap<PaneId, Arc<PaneOutputQueue>>>,
 }
 
 // Reduced from 1MB to 256KB to lower per-pane memory overhead.
-// This affects read buffer and socketpair kernel buffers.
+// This is the size of each read from the pty.
 const BUFSIZE: usize = 256 * 1024;
 
+/// How many reads from the pty may be waiting to be parsed before the
+/// reader stops reading, leaving the child blocked on a full pty.
+const OUTPUT_QUEUE_CHUNKS: usize = 8;
+
+/// Tracks the output that has been read from a pane's pty, but not yet
+/// parsed and applied to its terminal model.
+#[derive(Default)]
+pub struct PaneOutputQueue {
+    chunks: AtomicUsize,
+    bytes: AtomicUsize,
+    /// Set while a PaneOutput notification for the pane is waiting to
+    /// be processed by the main thread
+    notify_pending: AtomicBool,
+}
+
+/// A snapshot of a `PaneOutputQueue`, for diagnostics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneOutputQueueDepth {
+    pub chunks: usize,
+    pub bytes: usize,
+    pub capacity: usize,
+}
+
+impl PaneOutputQueue {
+    fn enqueued(&self, len: usize) {
+        self.chunks.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(len, Ordering::Relaxed);
+    }
+
+    fn dequeued(&self, len: usize) {
+        self.chunks.fetch_sub(1, Ordering::Relaxed);
+        self.bytes.fetch_sub(len, Ordering::Relaxed);
+    }
+
+    pub fn depth(&self) -> PaneOutputQueueDepth {
+        PaneOutputQueueDepth {
+            chunks: self.chunks.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            capacity: OUTPUT_QUEUE_CHUNKS,
+        }
+    }
+
+    /// Let the main thread know that the pane has new output.
+    /// At most one notification per pane is queued at a time, so that
+    /// a pane producing a lot of output cannot flood the main thread
+    /// and delay input handling and rendering for everything else.
+    fn notify_output(self: &Arc<Self>, pane_id: PaneId) {
+        if self.notify_pending.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let queue = Arc::clone(self);
+        promise::spawn::spawn_into_main_thread(async move {
+            // Clear first, so that output arriving while subscribers
+            // are being notified schedules another notification
+            queue.notify_pending.store(false, Ordering::Release);
+            if let Some(mux) = Mux::try_get() {
+                mux.notify(MuxNotification::PaneOutput(pane_id));
+            }
+        })
+        .detach();
+    }
+}
+
 /// This function applies parsed actions to the pane and notifies any
 /// mux subscribers about the output event
-fn send_actions_to_mux(pane: &Weak<dyn Pane>, dead: &Arc<AtomicBool>, actions: Vec<Action>) {
+fn send_actions_to_mux(
+    pane: &Weak<dyn Pane>,
+    dead: &Arc<AtomicBool>,
+    queue: &Arc<PaneOutputQueue>,
+    actions: Vec<Action>,
+) {
     let start = Instant::now();
     match pane.upgrade() {
         Some(pane) => {
             pane.perform_actions(actions);
             histogram!("send_actions_to_mux.perform_actions.latency").record(start.elapsed());
-            Mux::notify_from_any_thread(MuxNotification::PaneOutput(pane.pane_id()));
+            queue.notify_output(pane.pane_id());
         }
         None => {
             // Something else removed the pane from
@@ -145,14 +219,21 @@ fn send_actions_to_mux(pane: &Weak<dyn Pane>, dead: &Arc<AtomicBool>, actions: V
     histogram!("send_actions_to_mux.rate").record(1.);
 }
 
-fn parse_buffered_data(pane: Weak<dyn Pane>, dead: &Arc<AtomicBool>, mut rx: FileDescriptor) {
-    let mut buf = vec![0; configuration().mux_output_parser_buffer_size];
+fn parse_buffered_data(
+    pane: Weak<dyn Pane>,
+    dead: &Arc<AtomicBool>,
+    queue: &Arc<PaneOutputQueue>,
+    rx: Receiver<Vec<u8>>,
+) {
     let mut parser = termwiz::escape::parser::Parser::new();
     let mut actions = vec![];
     let mut hold = false;
     let mut action_size = 0;
+    let mut buffer_size = configuration().mux_output_parser_buffer_size;
     let mut delay = Duration::from_millis(configuration().mux_output_parser_coalesce_delay_ms);
     let mut deadline = None;
+    // Data that has been received, but not yet parsed
+    let mut pending: Option<Vec<u8>> = None;
 
     loop {
         // Check dead flag at the start of each iteration
@@ -161,114 +242,104 @@ fn parse_buffered_data(pane: Weak<dyn Pane>, dead: &Arc<AtomicBool>, mut rx: Fil
             break;
         }
 
-        // Use poll with 200ms timeout to balance CPU overhead and close responsiveness.
-        // POLLHUP will wake us immediately when writer closes.
-        let mut pfd = [pollfd {
-            fd: rx.as_socket_descriptor(),
-            events: POLLIN,
-            revents: 0,
-        }];
-        match poll(&mut pfd, Some(Duration::from_millis(200))) {
-            Ok(0) => {
-                // Timeout, loop back to check dead flag
-                continue;
-            }
-            Ok(_) if pfd[0].revents & POLLIN == 0 => {
-                // No data available (POLLHUP, POLLERR, etc.)
-                break;
-            }
-            Err(_) => {
-                break;
-            }
-            Ok(_) => {
-                // Data available, proceed to read
-            }
-        }
+        // Use a 200ms timeout to balance CPU overhead and close responsiveness.
+        // The channel disconnects and wakes us immediately when the reader exits.
+        let mut data = match pending.take() {
+            Some(data) => data,
+            None => match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(data) => {
+                    queue.dequeued(data.len());
+                    data
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    // Loop back to check dead flag
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    dead.store(true, Ordering::Release);
+                    break;
+                }
+            },
+        };
+        // Keep each batch of actions to the configured size, so that
+        // applying it doesn't hold the terminal for too long
+        if data.len() > buffer_size {
+            pending = Some(data.split_off(buffer_size));
+        }
+
+        parser.parse_coalesced(&data, |action| {
+            let mut flush = false;
+            match &action {
+                Action::CSI(CSI::Mode(Mode::SetDecPrivateMode(DecPrivateMode::Code(
+                    DecPrivateModeCode::SynchronizedOutput,
+                )))) => {
+                    hold = true;
+
+                    // Flush prior actions
+                    if !actions.is_empty() {
+                        send_actions_to_mux(&pane, &dead, queue, std::mem::take(&mut actions));
+                        action_size = 0;
+                    }
+                }
+                Action::CSI(CSI::Mode(Mode::ResetDecPrivateMode(DecPrivateMode::Code(
+                    DecPrivateModeCode::SynchronizedOutput,
+                )))) => {
+                    hold = false;
+                    flush = true;
+                }
+                Action::CSI(CSI::Device(dev)) if matches!(**dev, Device::SoftReset) => {
+                    hold = false;
+                    flush = true;
+                }
+                _ => {}
+            };
+            action.append_to(&mut actions);
 
-        match rx.read(&mut buf) {
-            Ok(size) if size == 0 => {
-                dead.store(true, Ordering::Release);
-                break;
+            if flush && !actions.is_empty() {
+                send_actions_to_mux(&pane, &dead, queue, std::mem::take(&mut actions));
+                action_size = 0;
             }
-            Err(_) => {
-                dead.store(true, Ordering::Release);
-                break;
-            }
-            Ok(size) => {
-                parser.parse(&buf[0..size], |action| {
-                    let mut flush = false;
-                    match &action {
-                        Action::CSI(CSI::Mode(Mode::SetDecPrivateMode(DecPrivateMode::Code(
-                            DecPrivateModeCode::SynchronizedOutput,
-                        )))) => {
-                            hold = true;
-
-                            // Flush prior actions
-                            if !actions.is_empty() {
-                                send_actions_to_mux(&pane, &dead, std::mem::take(&mut actions));
-                                action_size = 0;
-                            }
-                        }
-                        Action::CSI(CSI::Mode(Mode::ResetDecPrivateMode(
-                            DecPrivateMode::Code(DecPrivateModeCode::SynchronizedOutput),
-                        ))) => {
-                            hold = false;
-                            flush = true;
-                        }
-                        Action::CSI(CSI::Device(dev)) if matches!(**dev, Device::SoftReset) => {
-                            hold = false;
-                            flush = true;
-                        }
-                        _ => {}
-                    };
-                    action.append_to(&mut actions);
-
-                    if flush && !actions.is_empty() {
-                        send_actions_to_mux(&pane, &dead, std::mem::take(&mut actions));
-                        action_size = 0;
+        });
+        action_size += data.len();
+        if !actions.is_empty() && !hold {
+            // If we haven't accumulated too much data,
+            // pause for a short while to increase the chances
+            // that we coalesce a full "frame" from an unoptimized
+            // TUI program
+            if action_size < buffer_size {
+                if pending.is_some() {
+                    // There is more to parse right away
+                    continue;
+                }
+                let poll_delay = match deadline {
+                    None => {
+                        deadline.replace(Instant::now() + delay);
+                        Some(delay)
                     }
-                });
-                action_size += size;
-                if !actions.is_empty() && !hold {
-                    // If we haven't accumulated too much data,
-                    // pause for a short while to increase the chances
-                    // that we coalesce a full "frame" from an unoptimized
-                    // TUI program
-                    if action_size < buf.len() {
-                        let poll_delay = match deadline {
-                            None => {
-                                deadline.replace(Instant::now() + delay);
-                                Some(delay)
-                            }
-                            Some(target) => target.checked_duration_since(Instant::now()),
-                        };
-                        if poll_delay.is_some() {
-                            let mut pfd = [pollfd {
-                                fd: rx.as_socket_descriptor(),
-                                events: POLLIN,
-                                revents: 0,
-                            }];
-                            if let Ok(1) = poll(&mut pfd, poll_delay) {
-                                // We can read now without blocking, so accumulate
-                                // more data into actions
-                                continue;
-                            }
-
-                            // Not readable in time: let the data we have flow into
-                            // the terminal model
-                        }
+                    Some(target) => target.checked_duration_since(Instant::now()),
+                };
+                if let Some(poll_delay) = poll_delay {
+                    if let Ok(data) = rx.recv_timeout(poll_delay) {
+                        // More data arrived in time, so accumulate
+                        // it into actions
+                        queue.dequeued(data.len());
+                        pending = Some(data);
+                        continue;
                     }
 
-                    send_actions_to_mux(&pane, &dead, std::mem::take(&mut actions));
-                    deadline = None;
-                    action_size = 0;
+                    // Not readable in time: let the data we have flow into
+                    // the terminal model
                 }
-
-                let config = configuration();
-                buf.resize(config.mux_output_parser_buffer_size, 0);
-                delay = Duration::from_millis(config.mux_output_parser_coalesce_delay_ms);
             }
+
+            send_actions_to_mux(&pane, &dead, queue, std::mem::take(&mut actions));
+            deadline = None;
+            action_size = 0;
         }
+
+        let config = configuration();
+        buffer_size = config.mux_output_parser_buffer_size;
+        delay = Duration::from_millis(config.mux_output_parser_coalesce_delay_ms);
     }
 
     // Don't forget to send anything that we might have buffered
@@ -276,49 +347,35 @@ fn parse_buffered_data(pane: Weak<dyn Pane>, dead: &Arc<AtomicBool>, mut rx: Fil
     // for very short lived commands so that we don't forget to
     // display what they displayed.
     if !actions.is_empty() {
-        send_actions_to_mux(&pane, &dead, std::mem::take(&mut actions));
+        send_actions_to_mux(&pane, &dead, queue, std::mem::take(&mut actions));
     }
 }
 
-fn set_socket_buffer(fd: &mut FileDescriptor, option: i32, size: usize) -> anyhow::Result<()> {
-    let size = size as c_int;
-    let socklen = std::mem::size_of_val(&size);
-    unsafe {
-        let res = libc::setsockopt(
-            fd.as_socket_descriptor(),
-            SOL_SOCKET,
-            option,
-            &size as *const c_int as *const _,
-            socklen as _,
-        );
-        if res == 0 {
-            Ok(())
-        } else {
-            Err(std::io::Error::last_os_error()).context("setsockopt")
-        }
-    }
-}
-
-fn allocate_socketpair() -> anyhow::Result<(FileDescriptor, FileDescriptor)> {
-    let (mut tx, mut rx) = socketpair().context("socketpair")?;
-    set_socket_buffer(&mut tx, SO_SNDBUF, BUFSIZE)
-        .context("SO_SNDBUF")
-        .ok();
-    set_socket_buffer(&mut rx, SO_RCVBUF, BUFSIZE)
-        .context("SO_RCVBUF")
-        .ok();
-    Ok((tx, rx))
+/// Queue output read from the pty for the parser thread, blocking while
+/// the queue is full.  Returns false if the parser has gone away.
+fn send_to_parser(tx: &Sender<Vec<u8>>, queue: &PaneOutputQueue, data: Vec<u8>) -> bool {
+    let len = data.len();
+    // Count it before sending, so that the parser never sees the
+    // depth go below zero
+    queue.enqueued(len);
+    if tx.send(data).is_err() {
+        queue.dequeued(len);
+        return false;
+    }
+    true
 }
 
 /// This function is run in a separate thread; its purpose is to perform
 /// blocking reads from the pty (non-blocking reads are not portable to
-/// all platforms and pty/tty types), parse the escape sequences and
-/// relay the actions to the mux thread to apply them to the pane.
+/// all platforms and pty/tty types) and decode them.  The decoded output
+/// is passed through a bounded channel to a second thread that parses the
+/// escape sequences and applies the actions to the pane.
 fn read_from_pane_pty(
     pane: Weak<dyn Pane>,
     banner: Option<String>,
     pane_reader: PaneReader,
     dead: Arc<AtomicBool>,
+    queue: Arc<PaneOutputQueue>,
 ) {
     let mut reader = pane_reader.reader;
     #[cfg(unix)]
@@ -327,41 +384,54 @@ fn read_from_pane_pty(
     let mut buf = vec![0; BUFSIZE];
     let mut decoder = PaneOutputDecoder::default();
 
-    let (pane_id, exit_behavior) = match pane.upgrade() {
-        Some(pane) => (pane.pane_id(), pane.exit_behavior()),
+    let (
+        pane_id,
+        exit_behavior,
+        encoding_errors,
+        raw_output,
+        output_log,
+        echo_latency,
+        output_paused,
+    ) = match pane.upgrade() {
+        Some(pane) => (
+            pane.pane_id(),
+            pane.exit_behavior(),
+            pane.encoding_error_counts(),
+            pane.raw_output_history(),
+            pane.output_log(),
+            pane.echo_latency(),
+            pane.output_paused(),
+        ),
         None => return,
     };
 
-    let (mut tx, rx) = match allocate_socketpair() {
-        Ok(pair) => pair,
-        Err(err) => {
-            log::error!("read_from_pane_pty: Unable to allocate a socketpair: {err:#}");
-            localpane::emit_output_for_pane(
-                pane_id,
-                &format!(
-                    "⚠️  wezterm: read_from_pane_pty: \
-                    Unable to allocate a socketpair: {err:#}"
-                ),
-            );
-            return;
-        }
-    };
+    let (tx, rx) = bounded(OUTPUT_QUEUE_CHUNKS);
 
     let parse_pane = pane.clone();
     let parse_handle = std::thread::spawn({
         let dead = Arc::clone(&dead);
-        move || parse_buffered_data(parse_pane, &dead, rx)
+        let queue = Arc::clone(&queue);
+        move || parse_buffered_data(parse_pane, &dead, &queue, rx)
     });
 
     if let Some(banner) = banner {
-        if let Err(err) = tx.write_all(banner.as_bytes()) {
-            log::warn!("failed to write startup banner to pane parser: {err:#}");
+        if !send_to_parser(&tx, &queue, banner.into_bytes()) {
+            log::warn!("failed to send startup banner to pane parser");
         }
     }
 
+    // Read once rather than on every read from the pty; a changed
+    // threshold applies to panes spawned after the change
+    let encoding_error_event_threshold = configuration().encoding_error_event_threshold;
+
+    let pane_read_rate =
+        histogram!("read_from_pane_pty.pane.bytes.rate", "pane" => pane_id.to_string());
+
     // Poll timeout in milliseconds. Using 200ms as a balance between
     // responsiveness when closing panes and CPU overhead.
     const POLL_TIMEOUT_MS: u64 = 200;
+    // How often to check whether paused output has been resumed
+    const PAUSED_POLL_MS: u64 = 50;
 
     loop {
         if dead.load(Ordering::Acquire) {
@@ -369,6 +439,16 @@ fn read_from_pane_pty(
             break;
         }
 
+        // While output is paused, leave the data in the pty; the child
+        // blocks once the pty buffer is full, and catches up on resume
+        if output_paused
+            .as_ref()
+            .is_some_and(|paused| paused.load(Ordering::Relaxed))
+        {
+            std::thread::sleep(Duration::from_millis(PAUSED_POLL_MS));
+            continue;
+        }
+
         // On Unix, poll before read to avoid blocking indefinitely.
         // This allows the dead flag check to happen even if no data arrives.
         // Note: Non-Unix platforms will block on read(); acceptable for macOS-only Kaku.
@@ -407,17 +487,35 @@ fn read_from_pane_pty(
             }
             Ok(size) => {
                 histogram!("read_from_pane_pty.bytes.rate").record(size as f64);
+                pane_read_rate.record(size as f64);
                 log::trace!("read_pty pane {pane_id} read {size} bytes");
+                if let Some(raw_output) = &raw_output {
+                    raw_output.lock().push(&buf[..size]);
+                }
+                if let Some(output_log) = &output_log {
+                    output_log.log_output(&buf[..size]);
+                }
+                if let Some(echo_latency) = &echo_latency {
+                    echo_latency.output_received();
+                }
                 let decoded = if let Some(pane) = pane.upgrade() {
                     decoder.decode(pane.get_encoding(), &buf[..size])
                 } else {
                     buf[..size].to_vec()
                 };
-                if let Err(err) = tx.write_all(&decoded) {
-                    error!(
-                        "read_pty failed to write to parser: pane {} {:?}",
-                        pane_id, err
-                    );
+                if let Some(counts) = &encoding_errors {
+                    let errors = decoder.take_error_count();
+                    if errors > 0 {
+                        counts.add_decode_errors(errors);
+                    }
+                    // Also picks up errors recorded while encoding input,
+                    // which is typically echoed back to us
+                    if counts.should_report(encoding_error_event_threshold) {
+                        Mux::notify_from_any_thread(MuxNotification::PaneEncodingErrors(pane_id));
+                    }
+                }
+                if !send_to_parser(&tx, &queue, decoded) {
+                    error!("read_pty failed to send to parser: pane {}", pane_id);
                     break;
                 }
             }
@@ -446,7 +544,7 @@ fn read_from_pane_pty(
 
     dead.store(true, Ordering::Release);
 
-    // Close the write end to signal EOF to parse thread, then wait for it
+    // Disconnect the channel to signal EOF to parse thread, then wait for it
     drop(tx);
     if let Err(e) = parse_handle.join() {
         log::warn!("parse_buffered_data thread panicked: {:?}", e);
@@ -539,6 +637,7 @@ impl Mux {
             main_thread_id: std::thread::current().id(),
             agent,
             pane_dead_flags: RwLock::new(HashMap::new()),
+            pane_output_queues: RwLock::new(HashMap::new()),
         }
     }
 
@@ -906,13 +1005,26 @@ impl Mux {
             self.pane_dead_flags
                 .write()
                 .insert(pane_id, Arc::clone(&dead));
-            thread::spawn(move || read_from_pane_pty(pane_weak, banner, reader, dead));
+            let queue = Arc::new(PaneOutputQueue::default());
+            self.pane_output_queues
+                .write()
+                .insert(pane_id, Arc::clone(&queue));
+            thread::spawn(move || read_from_pane_pty(pane_weak, banner, reader, dead, queue));
         }
         self.recompute_pane_count();
         self.notify(MuxNotification::PaneAdded(pane_id));
         Ok(())
     }
 
+    /// Returns how much output from the pty of a local pane is waiting
+    /// to be parsed, or None if the pane has no local reader
+    pub fn pane_output_queue_depth(&self, pane_id: PaneId) -> Option<PaneOutputQueueDepth> {
+        self.pane_output_queues
+            .read()
+            .get(&pane_id)
+            .map(|queue| queue.depth())
+    }
+
     pub fn add_tab_no_panes(&self, tab: &Arc<Tab>) {
         self.tabs.write().insert(tab.tab_id(), Arc::clone(tab));
         self.recompute_pane_count();
@@ -936,6 +1048,7 @@ impl Mux {
             log::debug!("setting dead flag for pane {} reader threads", pane_id);
             dead.store(true, Ordering::Release);
         }
+        self.pane_output_queues.write().remove(&pane_id);
 
         if let Some(pane) = self.panes.write().remove(&pane_id) {
             log::debug!("killing pane {}", pane_id);
@@ -1363,6 +1476,7 @@ impl Mux {
         if let Some(config) = term_config {
             pane.set_config(config);
         }
+        self.write_post_spawn_commands(&pane, domain.domain_name());
 
         // FIXME: clipboard
 
@@ -1379,6 +1493,26 @@ impl Mux {
         Ok((pane, size))
     }
 
+    /// Writes the `post_spawn_commands` configured for the named domain
+    /// to a newly spawned pane.  The pane writer applies the pane's
+    /// input encoding.
+    fn write_post_spawn_commands(&self, pane: &Arc<dyn Pane>, domain_name: &str) {
+        let commands = configuration().post_spawn_commands_for_domain(domain_name);
+        if commands.is_empty() {
+            return;
+        }
+        let mut writer = pane.writer();
+        for command in commands {
+            if let Err(err) = write!(writer, "{command}\r") {
+                log::warn!(
+                    "failed to write post_spawn_commands to pane {}: {err:#}",
+                    pane.pane_id()
+                );
+                break;
+            }
+        }
+    }
+
     pub async fn move_pane_to_new_tab(
         &self,
         pane_id: PaneId,
@@ -1438,6 +1572,80 @@ impl Mux {
         Ok((tab, window_id))
     }
 
+    /// Spawns a tab in `domain` with the same split layout and working
+    /// directories as `tab_id`.  The processes in the source tab are not
+    /// carried over, and the source tab is left untouched.
+    pub async fn recreate_tab_in_domain(
+        &self,
+        tab_id: TabId,
+        domain: SpawnTabDomain,
+        window_id: WindowId,
+    ) -> anyhow::Result<Arc<Tab>> {
+        fn first_leaf_cwd(node: &PaneNode) -> Option<String> {
+            match node {
+                PaneNode::Leaf(entry) => entry.working_dir.as_ref().map(|dir| {
+                    percent_decode_str(dir.url.path())
+                        .decode_utf8_lossy()
+                        .into_owned()
+                }),
+                PaneNode::Split { left, .. } => first_leaf_cwd(left),
+                PaneNode::Empty => None,
+            }
+        }
+
+        let tab = self
+            .get_tab(tab_id)
+            .ok_or_else(|| anyhow!("tab {tab_id} not found"))?;
+        let tree = tab.codec_pane_tree();
+
+        let (new_tab, pane, _window_id) = self
+            .spawn_tab_or_window(
+                Some(window_id),
+                domain.clone(),
+                None,
+                first_leaf_cwd(&tree),
+                None,
+                tab.get_size(),
+                None,
+                self.active_workspace(),
+                None,
+            )
+            .await?;
+
+        // Each subtree is rebuilt from a pane that was spawned in the
+        // working directory of the subtree's first leaf
+        let mut pending = vec![(tree, pane.pane_id())];
+        while let Some((node, pane_id)) = pending.pop() {
+            if let PaneNode::Split { left, right, node } = node {
+                let (first, second) = match node.direction {
+                    SplitDirection::Horizontal => (node.first.cols, node.second.cols),
+                    SplitDirection::Vertical => (node.first.rows, node.second.rows),
+                };
+                let percent = (second * 100 / (first + second).max(1)).clamp(1, 99) as u8;
+                let (new_pane, _size) = self
+                    .split_pane(
+                        pane_id,
+                        SplitRequest {
+                            direction: node.direction,
+                            target_is_second: true,
+                            top_level: false,
+                            size: SplitSize::Percent(percent),
+                        },
+                        SplitSource::Spawn {
+                            command: None,
+                            command_dir: first_leaf_cwd(&right),
+                        },
+                        domain.clone(),
+                    )
+                    .await?;
+                pending.push((*left, pane_id));
+                pending.push((*right, new_pane.pane_id()));
+            }
+        }
+
+        Ok(new_tab)
+    }
+
     pub async fn spawn_tab_or_window(
         &self,
         window_id: Option<WindowId>,
@@ -1520,7 +1728,9 @@ impl Mux {
                 size,
                 command.clone(),
                 cwd.clone(),
-                encoding.unwrap_or_else(|| configuration().default_encoding),
+                encoding.unwrap_or_else(|| {
+                    configuration().default_encoding_for_domain(domain.domain_name())
+                }),
                 window_id,
             )
             .await
@@ -1538,6 +1748,7 @@ impl Mux {
         if let Some(config) = term_config {
             pane.set_config(config);
         }
+        self.write_post_spawn_commands(&pane, domain.domain_name());
 
         // FIXME: clipboard?
 
@@ -1,8 +1,11 @@
 use crate::domain::DomainId;
+use crate::latency::EchoLatency;
 use crate::pane::{
     CachePolicy, CloseReason, ForEachPaneLogicalLine, LogicalLine, Pane, PaneId, PaneReader,
     Pattern, SearchResult, WithPaneLines,
 };
+use crate::pane_encoding::{EncodingErrorCounts, PaneOutputDecoder, RawOutputHistory};
+use crate::pane_log::PaneOutputLog;
 use crate::renderable::*;
 use crate::tmux::{TmuxDomain, TmuxDomainState};
 use crate::{Domain, Mux, MuxNotification};
@@ -21,11 +24,11 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::io::{Result as IoResult, Write};
 use std::ops::Range;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use termwiz::escape::csi::{Sgr, CSI};
-use termwiz::escape::{Action, DeviceControlMode};
+use termwiz::escape::csi::{Cursor, Edit, EraseInDisplay, Sgr, CSI};
+use termwiz::escape::{Action, ControlCode, DeviceControlMode, OneBased};
 use termwiz::input::KeyboardEncoding;
 use termwiz::surface::{Line, SequenceNo};
 use url::Url;
@@ -133,7 +136,14 @@ pub struct LocalPane {
     proc_list: Mutex<Option<CachedProcInfo>>,
     #[cfg(unix)]
     leader: Arc<Mutex<Option<CachedLeaderInfo>>>,
-    encoding: Arc<AtomicU8>,
+    /// Shared with the pty writer, which encodes input using it
+    input_encoding: Arc<AtomicU8>,
+    output_encoding: AtomicU8,
+    encoding_errors: Arc<EncodingErrorCounts>,
+    raw_output: Arc<Mutex<RawOutputHistory>>,
+    output_log: Arc<PaneOutputLog>,
+    echo_latency: Arc<EchoLatency>,
+    output_paused: Arc<AtomicBool>,
     command_description: String,
 }
 
@@ -162,6 +172,15 @@ impl Pane for LocalPane {
             );
         }
 
+        map.insert(
+            Value::String("encoding_decode_errors".to_string()),
+            Value::U64(self.encoding_errors.decode_errors() as u64),
+        );
+        map.insert(
+            Value::String("encoding_encode_errors".to_string()),
+            Value::U64(self.encoding_errors.encode_errors() as u64),
+        );
+
         Value::Object(map.into())
     }
 
@@ -182,11 +201,83 @@ impl Pane for LocalPane {
     }
 
     fn get_encoding(&self) -> PaneEncoding {
-        PaneEncoding::from_u8(self.encoding.load(Ordering::Relaxed))
+        PaneEncoding::from_u8(self.output_encoding.load(Ordering::Relaxed))
     }
 
     fn set_encoding(&self, encoding: PaneEncoding) {
-        self.encoding.store(encoding.to_u8(), Ordering::Relaxed);
+        self.set_input_encoding(encoding);
+        self.set_output_encoding(encoding);
+    }
+
+    fn get_input_encoding(&self) -> PaneEncoding {
+        PaneEncoding::from_u8(self.input_encoding.load(Ordering::Relaxed))
+    }
+
+    fn set_input_encoding(&self, encoding: PaneEncoding) {
+        self.encoding_errors.reset();
+        self.input_encoding
+            .store(encoding.to_u8(), Ordering::Relaxed);
+    }
+
+    fn set_output_encoding(&self, encoding: PaneEncoding) {
+        self.encoding_errors.reset();
+        self.output_encoding
+            .store(encoding.to_u8(), Ordering::Relaxed);
+    }
+
+    fn encoding_error_counts(&self) -> Option<Arc<EncodingErrorCounts>> {
+        Some(Arc::clone(&self.encoding_errors))
+    }
+
+    fn raw_output_history(&self) -> Option<Arc<Mutex<RawOutputHistory>>> {
+        Some(Arc::clone(&self.raw_output))
+    }
+
+    fn output_log(&self) -> Option<Arc<PaneOutputLog>> {
+        Some(Arc::clone(&self.output_log))
+    }
+
+    fn echo_latency(&self) -> Option<Arc<EchoLatency>> {
+        Some(Arc::clone(&self.echo_latency))
+    }
+
+    fn output_paused(&self) -> Option<Arc<AtomicBool>> {
+        Some(Arc::clone(&self.output_paused))
+    }
+
+    fn reinterpret_output(&self, encoding: PaneEncoding) -> anyhow::Result<()> {
+        let raw = self.raw_output.lock().contents();
+        if raw.is_empty() {
+            anyhow::bail!(
+                "no raw output has been retained for this pane; \
+                 set raw_output_retention_bytes to enable this"
+            );
+        }
+        self.set_output_encoding(encoding);
+
+        let decoded = PaneOutputDecoder::default().decode(encoding, &raw);
+        let mut actions = vec![
+            Action::CSI(CSI::Edit(Edit::EraseInDisplay(
+                EraseInDisplay::EraseScrollback,
+            ))),
+            Action::CSI(CSI::Edit(Edit::EraseInDisplay(
+                EraseInDisplay::EraseDisplay,
+            ))),
+            Action::CSI(CSI::Cursor(Cursor::Position {
+                line: OneBased::new(1),
+                col: OneBased::new(1),
+            })),
+            Action::CSI(CSI::Sgr(Sgr::Reset)),
+        ];
+        let mut parser = termwiz::escape::parser::Parser::new();
+        parser.parse(&decoded, |action| {
+            if is_replayable_action(&action) {
+                actions.push(action);
+            }
+        });
+        self.terminal.lock().perform_actions(actions);
+        Mux::notify_from_any_thread(MuxNotification::PaneOutput(self.pane_id));
+        Ok(())
     }
 
     fn get_current_seqno(&self) -> SequenceNo {
@@ -415,6 +506,7 @@ impl Pane for LocalPane {
             }
             return Ok(());
         } else {
+            self.echo_latency.input_sent();
             self.terminal.lock().key_down(key, mods)
         }
     }
@@ -508,6 +600,10 @@ impl Pane for LocalPane {
         }
     }
 
+    fn set_scrollback_lines(&self, lines: Option<usize>) {
+        self.terminal.lock().set_scrollback_limit(lines);
+    }
+
     fn focus_changed(&self, focused: bool) {
         self.terminal.lock().focus_changed(focused);
     }
@@ -674,6 +770,10 @@ impl Pane for LocalPane {
         term.get_semantic_zones()
     }
 
+    fn get_spilled_scrollback_text(&self) -> anyhow::Result<String> {
+        self.terminal.lock().spilled_scrollback_text()
+    }
+
     async fn search(
         &self,
         pattern: Pattern,
@@ -779,13 +879,6 @@ impl Pane for LocalPane {
             true
         });
 
-        #[derive(Copy, Clone, Debug)]
-        struct Coord {
-            byte_idx: usize,
-            grapheme_idx: usize,
-            stable_row: StableRowIndex,
-        }
-
         fn found_match(
             text: &str,
             byte_idx: usize,
@@ -819,41 +912,78 @@ impl Pane for LocalPane {
             });
         }
 
-        fn make_coords(lines: &[&Line], stable_row: StableRowIndex) -> Vec<Coord> {
-            let mut byte_idx = 0;
-            let mut coords = vec![];
-
-            for (row_idx, line) in lines.iter().enumerate() {
-                for cell in line.visible_cells() {
-                    coords.push(Coord {
-                        byte_idx,
-                        grapheme_idx: cell.cell_index(),
-                        stable_row: stable_row + row_idx as StableRowIndex,
-                    });
-                    byte_idx += cell.str().len();
-                }
-            }
+        Ok(results)
+    }
+}
 
-            coords
-        }
+/// Returns true if `action` only affects the display, and so is safe to
+/// replay when reinterpreting output; anything that could produce a
+/// response to the application, or other side effects, is excluded.
+fn is_replayable_action(action: &Action) -> bool {
+    match action {
+        Action::Print(_) | Action::PrintString(_) => true,
+        Action::Control(code) => matches!(
+            code,
+            ControlCode::LineFeed
+                | ControlCode::CarriageReturn
+                | ControlCode::Backspace
+                | ControlCode::HorizontalTab
+                | ControlCode::VerticalTab
+                | ControlCode::FormFeed
+        ),
+        Action::CSI(CSI::Sgr(_)) | Action::CSI(CSI::Edit(_)) => true,
+        Action::CSI(CSI::Cursor(cursor)) => !matches!(
+            cursor,
+            Cursor::RequestActivePositionReport | Cursor::ActivePositionReport { .. }
+        ),
+        _ => false,
+    }
+}
 
-        fn haystack_idx_to_coord(idx: usize, coords: &[Coord]) -> (usize, StableRowIndex) {
-            let c = coords
-                .binary_search_by(|ele| ele.byte_idx.cmp(&idx))
-                .or_else(|i| -> Result<usize, usize> { Ok(i) })
-                .unwrap();
-            let coord = coords.get(c).map(|c| *c).unwrap_or_else(|| {
-                let last = coords.last().unwrap();
-                Coord {
-                    grapheme_idx: last.grapheme_idx + 1,
-                    ..*last
-                }
+/// Maps a byte offset in the text of a logical line to a cell
+#[derive(Copy, Clone, Debug)]
+struct Coord {
+    byte_idx: usize,
+    grapheme_idx: usize,
+    width: usize,
+    stable_row: StableRowIndex,
+}
+
+fn make_coords(lines: &[&Line], stable_row: StableRowIndex) -> Vec<Coord> {
+    let mut byte_idx = 0;
+    let mut coords = vec![];
+
+    for (row_idx, line) in lines.iter().enumerate() {
+        for cell in line.visible_cells() {
+            coords.push(Coord {
+                byte_idx,
+                grapheme_idx: cell.cell_index(),
+                width: cell.width(),
+                stable_row: stable_row + row_idx as StableRowIndex,
             });
-            (coord.grapheme_idx, coord.stable_row)
+            byte_idx += cell.str().len();
         }
-
-        Ok(results)
     }
+
+    coords
+}
+
+fn haystack_idx_to_coord(idx: usize, coords: &[Coord]) -> (usize, StableRowIndex) {
+    let c = coords
+        .binary_search_by(|ele| ele.byte_idx.cmp(&idx))
+        .or_else(|i| -> Result<usize, usize> { Ok(i) })
+        .unwrap();
+    let coord = coords.get(c).map(|c| *c).unwrap_or_else(|| {
+        // Just past the end of the text; step over the full width of the
+        // last cell so that a trailing double-width character is not cut
+        // in half.
+        let last = coords.last().unwrap();
+        Coord {
+            grapheme_idx: last.grapheme_idx + last.width.max(1),
+            ..*last
+        }
+    });
+    (coord.grapheme_idx, coord.stable_row)
 }
 
 struct LocalPaneDCSHandler {
@@ -973,6 +1103,27 @@ impl AlertHandler for LocalPaneNotifHandler {
                         }
                     }
                 }
+                Alert::TabColorChanged(color) => {
+                    if let Some((_domain, _window_id, tab_id)) = mux.resolve_pane_id(pane_id) {
+                        if let Some(tab) = mux.get_tab(tab_id) {
+                            tab.set_color(color.map(Into::into));
+                        }
+                    }
+                }
+                Alert::CommandFinished {
+                    command,
+                    exit_status,
+                    started,
+                    duration,
+                } => {
+                    crate::command_history::record_command_finished(
+                        pane_id,
+                        command,
+                        *exit_status,
+                        *started,
+                        *duration,
+                    );
+                }
                 _ => {}
             }
 
@@ -1024,6 +1175,8 @@ impl LocalPane {
         writer: Box<dyn Write + Send>,
         domain_id: DomainId,
         encoding: Arc<AtomicU8>,
+        encoding_errors: Arc<EncodingErrorCounts>,
+        output_log: Arc<PaneOutputLog>,
         command_description: String,
     ) -> Self {
         let (process, signaller, pid) = split_child(process);
@@ -1050,7 +1203,15 @@ impl LocalPane {
             proc_list: Mutex::new(None),
             #[cfg(unix)]
             leader: Arc::new(Mutex::new(None)),
-            encoding,
+            output_encoding: AtomicU8::new(encoding.load(Ordering::Relaxed)),
+            input_encoding: encoding,
+            encoding_errors,
+            raw_output: Arc::new(Mutex::new(RawOutputHistory::new(
+                configuration().raw_output_retention_bytes,
+            ))),
+            output_log,
+            echo_latency: Arc::new(EchoLatency::default()),
+            output_paused: Arc::new(AtomicBool::new(false)),
             command_description,
         }
     }
@@ -1172,3 +1333,48 @@ impl Drop for LocalPane {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pane_encoding::PaneOutputDecoder;
+
+    fn decoded_line(encoding: PaneEncoding, bytes: &[u8]) -> Line {
+        let mut decoder = PaneOutputDecoder::default();
+        let text = String::from_utf8(decoder.decode(encoding, bytes)).unwrap();
+        Line::from_text(&text, &Default::default(), SequenceNo::MAX, None)
+    }
+
+    #[test]
+    fn search_coords_for_decoded_wide_text() {
+        // "ab你好" in GBK
+        let line = decoded_line(PaneEncoding::Gbk, &[b'a', b'b', 0xc4, 0xe3, 0xba, 0xc3]);
+        let text = line.as_str();
+        assert_eq!(text, "ab你好");
+
+        let coords = make_coords(&[&line], 10);
+        assert_eq!(
+            haystack_idx_to_coord(text.find("你").unwrap(), &coords),
+            (2, 10)
+        );
+        assert_eq!(
+            haystack_idx_to_coord(text.find("好").unwrap(), &coords),
+            (4, 10)
+        );
+        // A match that ends with a double-width character covers both of its cells
+        assert_eq!(haystack_idx_to_coord(text.len(), &coords), (6, 10));
+    }
+
+    #[test]
+    fn search_coords_across_wrapped_decoded_lines() {
+        // "x你" and "好y" in Big5, as a single logical line
+        let first = decoded_line(PaneEncoding::Big5, &[b'x', 0xa7, 0x41]);
+        let second = decoded_line(PaneEncoding::Big5, &[0xa6, 0x6e, b'y']);
+        let haystack = format!("{}{}", first.as_str(), second.as_str());
+
+        let coords = make_coords(&[&first, &second], 0);
+        let start = haystack.find("你好").unwrap();
+        assert_eq!(haystack_idx_to_coord(start, &coords), (1, 0));
+        assert_eq!(haystack_idx_to_coord(start + "你好".len(), &coords), (2, 1));
+    }
+}
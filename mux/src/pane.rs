@@ -1,16 +1,20 @@
 use crate::domain::DomainId;
+use crate::latency::EchoLatency;
+use crate::pane_encoding::{EncodingErrorCounts, RawOutputHistory};
+use crate::pane_log::PaneOutputLog;
 use crate::renderable::*;
 use crate::ExitBehavior;
 use async_trait::async_trait;
 use config::keyassignment::{KeyAssignment, PaneEncoding, ScrollbackEraseMode};
 use downcast_rs::{impl_downcast, Downcast};
-use parking_lot::MappedMutexGuard;
+use parking_lot::{MappedMutexGuard, Mutex};
 use rangeset::RangeSet;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::Range;
 #[cfg(unix)]
 use std::os::unix::io::RawFd;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use termwiz::hyperlink::Rule;
 use termwiz::input::KeyboardEncoding;
@@ -328,18 +332,71 @@ pub trait Pane: Downcast + Send + Sync {
         KeyboardEncoding::Xterm
     }
 
+    /// Returns the encoding used to decode output from the pane
     fn get_encoding(&self) -> PaneEncoding {
         PaneEncoding::Utf8
     }
 
+    /// Sets both the input and output encodings of the pane
     fn set_encoding(&self, _encoding: PaneEncoding) {}
 
+    /// Returns the encoding used to encode input sent to the pane
+    fn get_input_encoding(&self) -> PaneEncoding {
+        self.get_encoding()
+    }
+
+    /// Changes only the encoding used for input sent to the pane
+    fn set_input_encoding(&self, _encoding: PaneEncoding) {}
+
+    /// Changes only the encoding used to decode output from the pane
+    fn set_output_encoding(&self, _encoding: PaneEncoding) {}
+
+    /// Returns the counts of characters that could not be converted
+    /// to or from the pane encoding, if the pane tracks them
+    fn encoding_error_counts(&self) -> Option<Arc<EncodingErrorCounts>> {
+        None
+    }
+
+    /// Returns the record of undecoded output, if the pane retains one
+    fn raw_output_history(&self) -> Option<Arc<Mutex<RawOutputHistory>>> {
+        None
+    }
+
+    /// Decodes the retained raw output again using `encoding`, replacing
+    /// the current contents of the pane, and decodes future output
+    /// using `encoding` too
+    fn reinterpret_output(&self, _encoding: PaneEncoding) -> anyhow::Result<()> {
+        anyhow::bail!("this pane does not retain its raw output")
+    }
+
+    /// Returns the `script(1)` style log of the pty traffic, if the
+    /// pane supports logging
+    fn output_log(&self) -> Option<Arc<PaneOutputLog>> {
+        None
+    }
+
+    /// Returns the echo latency measurements for this pane, if the pane
+    /// tracks them
+    fn echo_latency(&self) -> Option<Arc<EchoLatency>> {
+        None
+    }
+
+    /// Returns the flag that, while set, stops the pane from reading
+    /// any more output from its pty, if the pane can be paused
+    fn output_paused(&self) -> Option<Arc<AtomicBool>> {
+        None
+    }
+
     fn copy_user_vars(&self) -> HashMap<String, String> {
         HashMap::new()
     }
 
     fn erase_scrollback(&self, _erase_mode: ScrollbackEraseMode) {}
 
+    /// Overrides the number of scrollback lines retained by this pane,
+    /// or restores the configured `scrollback_lines` when None
+    fn set_scrollback_lines(&self, _lines: Option<usize>) {}
+
     /// Called to advise on whether this tab has focus
     fn focus_changed(&self, _focused: bool) {}
 
@@ -380,6 +437,12 @@ pub trait Pane: Downcast + Send + Sync {
         Ok(vec![])
     }
 
+    /// Returns the text of scrollback lines that were spilled to disk
+    /// because they scrolled beyond `scrollback_lines`
+    fn get_spilled_scrollback_text(&self) -> anyhow::Result<String> {
+        Ok(String::new())
+    }
+
     /// Returns true if the terminal has grabbed the mouse and wants to
     /// give the embedded application a chance to process events.
     /// In practice this controls whether the gui will perform local
@@ -1,5 +1,8 @@
 use config::keyassignment::PaneEncoding;
-use encoding_rs::Encoding;
+use encoding_rs::{DecoderResult, EncoderResult, Encoding};
+use std::collections::VecDeque;
+use std::ffi::OsStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 const MAX_TRAILING_ENCODED_BYTES: usize = 4;
 
@@ -87,6 +90,162 @@ fn begin_escape(state: &mut EscapeState, escape_bytes: &mut Vec<u8>, byte: u8) {
     };
 }
 
+/// Tracks how many characters could not be converted to or from the
+/// pane encoding, which is a strong hint that the wrong encoding
+/// has been selected for the pane.
+#[derive(Debug, Default)]
+pub struct EncodingErrorCounts {
+    decode: AtomicUsize,
+    encode: AtomicUsize,
+    reported: AtomicBool,
+}
+
+impl EncodingErrorCounts {
+    /// The number of malformed sequences in the output of the pane
+    pub fn decode_errors(&self) -> usize {
+        self.decode.load(Ordering::Relaxed)
+    }
+
+    /// The number of characters in the input to the pane that could
+    /// not be represented in the pane encoding
+    pub fn encode_errors(&self) -> usize {
+        self.encode.load(Ordering::Relaxed)
+    }
+
+    pub fn add_decode_errors(&self, count: usize) {
+        self.decode.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_encode_errors(&self, count: usize) {
+        self.encode.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Returns true exactly once after the combined error count
+    /// reaches `threshold`.  A threshold of 0 never reports.
+    pub fn should_report(&self, threshold: usize) -> bool {
+        if threshold == 0 || self.reported.load(Ordering::Relaxed) {
+            return false;
+        }
+        if self.decode_errors() + self.encode_errors() < threshold {
+            return false;
+        }
+        !self.reported.swap(true, Ordering::Relaxed)
+    }
+
+    /// Clears the counts and re-arms reporting; used when the pane
+    /// encoding is changed.
+    pub fn reset(&self) {
+        self.decode.store(0, Ordering::Relaxed);
+        self.encode.store(0, Ordering::Relaxed);
+        self.reported.store(false, Ordering::Relaxed);
+    }
+}
+
+/// A bounded record of the undecoded output of a pane, retained so that
+/// it can be decoded again after switching to a different encoding.
+#[derive(Debug)]
+pub struct RawOutputHistory {
+    data: VecDeque<u8>,
+    limit: usize,
+    truncated: bool,
+}
+
+impl RawOutputHistory {
+    /// Creates a history that retains at most `limit` bytes; a limit
+    /// of 0 retains nothing
+    pub fn new(limit: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            limit,
+            truncated: false,
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        if self.limit == 0 {
+            return;
+        }
+        if bytes.len() >= self.limit {
+            self.data.clear();
+            self.data.extend(&bytes[bytes.len() - self.limit..]);
+            self.truncated = true;
+            return;
+        }
+        let excess = (self.data.len() + bytes.len()).saturating_sub(self.limit);
+        if excess > 0 {
+            self.data.drain(..excess);
+            self.truncated = true;
+        }
+        self.data.extend(bytes);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the retained bytes.  If older output has been discarded,
+    /// the partial first line is skipped so that decoding doesn't start
+    /// in the middle of a multibyte character or escape sequence.
+    pub fn contents(&self) -> Vec<u8> {
+        let skip = if self.truncated {
+            self.data
+                .iter()
+                .position(|&b| b == b'\n')
+                .map(|idx| idx + 1)
+                .unwrap_or(self.data.len())
+        } else {
+            0
+        };
+        self.data.iter().skip(skip).copied().collect()
+    }
+
+    /// Returns at most the last `max_bytes` retained bytes, exactly as
+    /// they were read from the pty
+    pub fn tail(&self, max_bytes: usize) -> Vec<u8> {
+        let skip = self.data.len().saturating_sub(max_bytes);
+        self.data.iter().skip(skip).copied().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.truncated = false;
+    }
+}
+
+/// Guesses the pane encoding from a POSIX locale name such as `zh_CN.GBK`
+/// or `ja_JP.SJIS`.  Returns `None` for UTF-8 locales and for locales
+/// that don't name a codeset that we support.
+pub fn encoding_from_locale(locale: &str) -> Option<PaneEncoding> {
+    let (_, codeset) = locale.split_once('.')?;
+    let codeset = codeset.split('@').next().unwrap_or(codeset);
+    let codeset: String = codeset
+        .chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    match codeset.as_str() {
+        "gbk" | "gb2312" | "euccn" | "cp936" => Some(PaneEncoding::Gbk),
+        "gb18030" => Some(PaneEncoding::Gb18030),
+        "big5" | "big5hkscs" | "cp950" => Some(PaneEncoding::Big5),
+        "euckr" | "cp949" => Some(PaneEncoding::EucKr),
+        "sjis" | "shiftjis" | "cp932" => Some(PaneEncoding::ShiftJis),
+        _ => None,
+    }
+}
+
+/// Returns the encoding implied by a locale environment, following the
+/// usual LC_ALL, LC_CTYPE, LANG precedence.  `get_env` looks up the
+/// value of an environment variable.
+pub fn encoding_from_locale_env<S: AsRef<OsStr>>(
+    get_env: impl Fn(&str) -> Option<S>,
+) -> Option<PaneEncoding> {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| get_env(name))
+        .find(|value| !value.as_ref().is_empty())?;
+    encoding_from_locale(&locale.as_ref().to_string_lossy())
+}
+
 pub fn decode_bytes_to_string(encoding: PaneEncoding, raw: &[u8]) -> String {
     if let Ok(text) = std::str::from_utf8(raw) {
         return text.to_string();
@@ -107,6 +266,7 @@ pub struct PaneInputEncoder {
     state: EscapeState,
     escape_bytes: Vec<u8>,
     pending_utf8: Vec<u8>,
+    errors: usize,
 }
 
 impl Default for PaneInputEncoder {
@@ -116,11 +276,18 @@ impl Default for PaneInputEncoder {
             state: EscapeState::Ground,
             escape_bytes: Vec::new(),
             pending_utf8: Vec::new(),
+            errors: 0,
         }
     }
 }
 
 impl PaneInputEncoder {
+    /// Returns the number of characters that were replaced since
+    /// the last call, and resets the count
+    pub fn take_error_count(&mut self) -> usize {
+        std::mem::take(&mut self.errors)
+    }
+
     pub fn encode(&mut self, encoding: PaneEncoding, data: &[u8]) -> Vec<u8> {
         if self.encoding != encoding {
             self.encoding = encoding;
@@ -192,18 +359,41 @@ impl PaneInputEncoder {
                     }
 
                     output.push(b'?');
+                    self.errors += 1;
                     cursor += err.error_len().unwrap_or(1);
                 }
             }
         }
     }
 
-    fn push_encoded(&self, encoding: PaneEncoding, text: &str, output: &mut Vec<u8>) {
-        if let Some(enc) = get_encoding(encoding) {
-            let (encoded, _, _) = enc.encode(text);
-            output.extend_from_slice(&encoded);
-        } else {
+    fn push_encoded(&mut self, encoding: PaneEncoding, text: &str, output: &mut Vec<u8>) {
+        let Some(enc) = get_encoding(encoding) else {
             output.extend_from_slice(text.as_bytes());
+            return;
+        };
+
+        // Equivalent to `enc.encode(text)`, which replaces unmappable
+        // characters with numeric character references, but lets us
+        // count the replacements.
+        let mut encoder = enc.new_encoder();
+        let mut src = text;
+        loop {
+            output.reserve(
+                encoder
+                    .max_buffer_length_from_utf8_without_replacement(src.len())
+                    .unwrap_or(src.len() * 4),
+            );
+            let (result, read) =
+                encoder.encode_from_utf8_to_vec_without_replacement(src, output, true);
+            src = &src[read..];
+            match result {
+                EncoderResult::InputEmpty => return,
+                EncoderResult::OutputFull => {}
+                EncoderResult::Unmappable(c) => {
+                    self.errors += 1;
+                    output.extend_from_slice(format!("&#{};", c as u32).as_bytes());
+                }
+            }
         }
     }
 }
@@ -214,6 +404,7 @@ pub struct PaneOutputDecoder {
     state: EscapeState,
     escape_bytes: Vec<u8>,
     pending_encoded: Vec<u8>,
+    errors: usize,
 }
 
 impl Default for PaneOutputDecoder {
@@ -223,11 +414,18 @@ impl Default for PaneOutputDecoder {
             state: EscapeState::Ground,
             escape_bytes: Vec::new(),
             pending_encoded: Vec::new(),
+            errors: 0,
         }
     }
 }
 
 impl PaneOutputDecoder {
+    /// Returns the number of malformed sequences that were replaced
+    /// since the last call, and resets the count
+    pub fn take_error_count(&mut self) -> usize {
+        std::mem::take(&mut self.errors)
+    }
+
     pub fn decode(&mut self, encoding: PaneEncoding, data: &[u8]) -> Vec<u8> {
         if self.encoding != encoding {
             self.encoding = encoding;
@@ -302,7 +500,29 @@ impl PaneOutputDecoder {
             return;
         }
 
-        let (decoded, _, _) = enc.decode(&pending);
+        // Equivalent to `enc.decode(&pending)` without BOM sniffing,
+        // but lets us count the replacements.
+        let mut decoder = enc.new_decoder_without_bom_handling();
+        let mut decoded = String::new();
+        let mut src = &pending[..];
+        loop {
+            decoded.reserve(
+                decoder
+                    .max_utf8_buffer_length_without_replacement(src.len())
+                    .unwrap_or(src.len() * 3),
+            );
+            let (result, read) =
+                decoder.decode_to_string_without_replacement(src, &mut decoded, true);
+            src = &src[read..];
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => {}
+                DecoderResult::Malformed(_, _) => {
+                    self.errors += 1;
+                    decoded.push(char::REPLACEMENT_CHARACTER);
+                }
+            }
+        }
         output.extend_from_slice(decoded.as_bytes());
     }
 }
@@ -319,6 +539,21 @@ mod tests {
         assert_eq!(decoded, text.as_bytes().to_vec());
     }
 
+    #[test]
+    fn locale_env_precedence() {
+        let env = |name: &str| match name {
+            "LC_ALL" => Some(""),
+            "LC_CTYPE" => Some("zh_TW.Big5"),
+            "LANG" => Some("zh_CN.GBK"),
+            _ => None,
+        };
+        assert_eq!(encoding_from_locale_env(env), Some(PaneEncoding::Big5));
+        assert_eq!(
+            encoding_from_locale_env(|name: &str| (name == "LANG").then_some("en_US.UTF-8")),
+            None
+        );
+    }
+
     #[test]
     fn utf8_passthrough() {
         let mut encoder = PaneInputEncoder::default();
@@ -403,6 +638,84 @@ mod tests {
         assert_eq!(result2, vec![0xc4, 0xe3]);
     }
 
+    #[test]
+    fn counts_conversion_errors() {
+        let mut decoder = PaneOutputDecoder::default();
+        // 0xff is not valid as a lead byte in Shift-JIS
+        let decoded = decoder.decode(PaneEncoding::ShiftJis, b"ab\xff\xffcdefg");
+        assert_eq!(decoded, "ab\u{fffd}\u{fffd}cdefg".as_bytes().to_vec());
+        assert_eq!(decoder.take_error_count(), 2);
+        assert_eq!(decoder.take_error_count(), 0);
+
+        let mut encoder = PaneInputEncoder::default();
+        // Hangul can't be represented in GBK
+        let encoded = encoder.encode(PaneEncoding::Gbk, "a한b".as_bytes());
+        assert_eq!(encoded, b"a&#54620;b".to_vec());
+        assert_eq!(encoder.take_error_count(), 1);
+
+        let counts = EncodingErrorCounts::default();
+        counts.add_decode_errors(2);
+        assert!(!counts.should_report(3));
+        counts.add_encode_errors(1);
+        assert!(counts.should_report(3));
+        assert!(!counts.should_report(3));
+        counts.reset();
+        assert_eq!(counts.decode_errors(), 0);
+        assert!(!counts.should_report(0));
+    }
+
+    #[test]
+    fn raw_output_history_is_bounded() {
+        let mut history = RawOutputHistory::new(0);
+        history.push(b"ignored");
+        assert!(history.is_empty());
+
+        let mut history = RawOutputHistory::new(8);
+        history.push(b"abc\n");
+        history.push(b"de");
+        assert_eq!(history.contents(), b"abc\nde".to_vec());
+
+        // Discarding the oldest bytes also drops the partial first line
+        history.push(b"fgh");
+        assert_eq!(history.contents(), b"defgh".to_vec());
+        assert_eq!(history.tail(4), b"efgh".to_vec());
+        assert_eq!(history.tail(100), b"bc\ndefgh".to_vec());
+        history.push(b"i\njk");
+        assert_eq!(history.contents(), b"jk".to_vec());
+
+        history.push(b"0123456789");
+        assert!(history.contents().is_empty());
+
+        history.clear();
+        history.push(b"xyz");
+        assert_eq!(history.contents(), b"xyz".to_vec());
+    }
+
+    #[test]
+    fn guesses_encoding_from_locale() {
+        assert_eq!(encoding_from_locale("zh_CN.GBK"), Some(PaneEncoding::Gbk));
+        assert_eq!(
+            encoding_from_locale("zh_CN.gb2312"),
+            Some(PaneEncoding::Gbk)
+        );
+        assert_eq!(
+            encoding_from_locale("zh_CN.GB18030"),
+            Some(PaneEncoding::Gb18030)
+        );
+        assert_eq!(encoding_from_locale("zh_TW.Big5"), Some(PaneEncoding::Big5));
+        assert_eq!(
+            encoding_from_locale("ko_KR.EUC-KR"),
+            Some(PaneEncoding::EucKr)
+        );
+        assert_eq!(
+            encoding_from_locale("ja_JP.SJIS@variant"),
+            Some(PaneEncoding::ShiftJis)
+        );
+        assert_eq!(encoding_from_locale("en_US.UTF-8"), None);
+        assert_eq!(encoding_from_locale("C"), None);
+        assert_eq!(encoding_from_locale("fr_FR.ISO8859-1"), None);
+    }
+
     #[test]
     fn decode_bytes_to_string_works_for_utf8_and_non_utf8() {
         let utf8 = decode_bytes_to_string(PaneEncoding::Utf8, "hello世界".as_bytes());
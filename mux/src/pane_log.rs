@@ -0,0 +1,152 @@
+//! `script(1)` style logging of the raw bytes that flow through a pane.
+//!
+//! The raw output (and optionally the input) is appended to the log file
+//! exactly as it was read from or written to the pty, and a companion
+//! `<path>.timing` file records when each chunk arrived using the
+//! multi-stream timing format understood by `scriptreplay`:
+//! one `<I|O> <seconds since previous chunk> <length>` entry per line.
+use parking_lot::Mutex;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+struct ActiveLog {
+    path: PathBuf,
+    data: File,
+    timing: File,
+    include_input: bool,
+    last: Instant,
+}
+
+impl ActiveLog {
+    fn append(&mut self, kind: char, bytes: &[u8]) -> std::io::Result<()> {
+        let now = Instant::now();
+        let delay = now.duration_since(self.last).as_secs_f64();
+        self.last = now;
+        self.data.write_all(bytes)?;
+        writeln!(self.timing, "{kind} {delay:.6} {}", bytes.len())
+    }
+}
+
+/// Shared between a pane, its reader thread and its writer so that
+/// logging can be switched on and off while the pane is running
+#[derive(Default)]
+pub struct PaneOutputLog {
+    active: Mutex<Option<ActiveLog>>,
+}
+
+impl PaneOutputLog {
+    /// Returns the path of the current log file, if logging is enabled
+    pub fn path(&self) -> Option<PathBuf> {
+        self.active.lock().as_ref().map(|log| log.path.clone())
+    }
+
+    /// Starts appending to `path`, replacing any log that was already
+    /// in progress
+    pub fn start(&self, path: &Path, include_input: bool) -> anyhow::Result<()> {
+        let open = |path: &Path| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|err| anyhow::anyhow!("opening {}: {err:#}", path.display()))
+        };
+        let mut timing_path = path.as_os_str().to_owned();
+        timing_path.push(".timing");
+
+        let log = ActiveLog {
+            path: path.to_path_buf(),
+            data: open(path)?,
+            timing: open(Path::new(&timing_path))?,
+            include_input,
+            last: Instant::now(),
+        };
+        self.active.lock().replace(log);
+        Ok(())
+    }
+
+    /// Stops logging, returning the path of the log that was in progress
+    pub fn stop(&self) -> Option<PathBuf> {
+        self.active.lock().take().map(|log| log.path)
+    }
+
+    /// Stops logging if it is active, otherwise starts logging to `path`.
+    /// Returns true if logging is now enabled.
+    pub fn toggle(&self, path: &Path, include_input: bool) -> anyhow::Result<bool> {
+        if self.stop().is_some() {
+            return Ok(false);
+        }
+        self.start(path, include_input)?;
+        Ok(true)
+    }
+
+    pub fn log_output(&self, bytes: &[u8]) {
+        self.append('O', bytes);
+    }
+
+    pub fn log_input(&self, bytes: &[u8]) {
+        self.append('I', bytes);
+    }
+
+    fn append(&self, kind: char, bytes: &[u8]) {
+        let mut active = self.active.lock();
+        let Some(log) = active.as_mut() else {
+            return;
+        };
+        if kind == 'I' && !log.include_input {
+            return;
+        }
+        if let Err(err) = log.append(kind, bytes) {
+            log::error!(
+                "writing to pane log {} failed, logging stopped: {err:#}",
+                log.path.display()
+            );
+            active.take();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn logs_output_and_timing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pane.log");
+        let log = PaneOutputLog::default();
+
+        log.log_output(b"not logged");
+        assert!(log.toggle(&path, false).unwrap());
+        log.log_output(b"hello");
+        log.log_input(b"ignored");
+        log.log_output(b"\x1b[0m");
+        assert!(!log.toggle(&path, false).unwrap());
+        log.log_output(b"after");
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello\x1b[0m".to_vec());
+        let timing = std::fs::read_to_string(dir.path().join("pane.log.timing")).unwrap();
+        let lengths: Vec<&str> = timing
+            .lines()
+            .map(|line| {
+                let mut fields = line.split(' ');
+                assert_eq!(fields.next(), Some("O"));
+                fields.nth(1).unwrap()
+            })
+            .collect();
+        assert_eq!(lengths, vec!["5", "4"]);
+    }
+
+    #[test]
+    fn logs_input_when_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pane.log");
+        let log = PaneOutputLog::default();
+        log.start(&path, true).unwrap();
+        log.log_output(b"$ ");
+        log.log_input(b"ls\r");
+        assert_eq!(log.stop(), Some(path.clone()));
+        assert_eq!(std::fs::read(&path).unwrap(), b"$ ls\r".to_vec());
+    }
+}
@@ -106,7 +106,7 @@ pub fn terminal_for_each_logical_line_in_stable_range_mut(
 }
 
 /// Implements Pane::with_lines for Terminal
-pub fn terminal_with_lines<F>(term: &mut Terminal, lines: Range<StableRowIndex>, mut func: F)
+pub fn terminal_with_lines<F>(term: &mut Terminal, lines: Range<StableRowIndex>, func: F)
 where
     F: FnMut(StableRowIndex, &[&Line]),
 {
@@ -116,10 +116,7 @@ where
     } else {
         term.screen_mut()
     };
-    let phys_range = screen.stable_range(&lines);
-    let first = screen.phys_to_stable_row_index(phys_range.start);
-
-    screen.with_phys_lines(phys_range, |lines| func(first, lines));
+    screen.with_stable_lines(&lines, func);
 }
 
 /// Implements Pane::with_lines_mut for Terminal
@@ -134,10 +131,9 @@ pub fn terminal_with_lines_mut(
     } else {
         term.screen_mut()
     };
-    let phys_range = screen.stable_range(&lines);
-    let first = screen.phys_to_stable_row_index(phys_range.start);
-
-    screen.with_phys_lines_mut(phys_range, |lines| with_lines.with_lines_mut(first, lines));
+    screen.with_stable_lines_mut(&lines, |first, lines| {
+        with_lines.with_lines_mut(first, lines)
+    });
 }
 
 /// Implements Pane::get_lines for Terminal
@@ -151,12 +147,7 @@ pub fn terminal_get_lines(
     } else {
         term.screen_mut()
     };
-    let phys_range = screen.stable_range(&lines);
-
-    let first = screen.phys_to_stable_row_index(phys_range.start);
-    let lines = screen.lines_in_phys_range(phys_range);
-
-    (first, lines)
+    screen.lines_in_stable_range(&lines)
 }
 
 /// Implements Pane::get_dimensions for Terminal
@@ -171,9 +162,9 @@ pub fn terminal_get_dimensions(term: &mut Terminal) -> RenderableDimensions {
     RenderableDimensions {
         cols: screen.physical_cols,
         viewport_rows: screen.physical_rows,
-        scrollback_rows: screen.scrollback_rows(),
+        scrollback_rows: screen.scrollback_rows() + screen.spilled_scrollback_rows(),
         physical_top: screen.visible_row_to_stable_row(0),
-        scrollback_top: screen.phys_to_stable_row_index(0),
+        scrollback_top: screen.scrollback_top(),
         dpi: screen.dpi,
         pixel_width: size.pixel_width,
         pixel_height: size.pixel_height,
@@ -1,5 +1,7 @@
-use crate::connui::ConnectionUI;
-use crate::domain::{alloc_domain_id, Domain, DomainId, DomainState, WriterWrapper};
+use crate::connui::{secret_prompt, ConnectionUI, PasswordPromptHost};
+use crate::domain::{
+    adjust_spawn_environment, alloc_domain_id, Domain, DomainId, DomainState, WriterWrapper,
+};
 use crate::localpane::LocalPane;
 use crate::pane::{alloc_pane_id, Pane, PaneId};
 use crate::Mux;
@@ -18,47 +20,18 @@ use std::sync::atomic::AtomicU8;
 use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use termwiz::cell::{unicode_column_width, AttributeChange, Intensity};
+use termwiz::cell::{AttributeChange, Intensity};
 use termwiz::input::{InputEvent, InputParser};
 use termwiz::lineedit::*;
 use termwiz::render::terminfo::TerminfoRenderer;
 use termwiz::surface::{Change, LineAttribute};
 use termwiz::terminal::{ScreenSize, Terminal, TerminalWaker};
 use wezterm_ssh::{
-    ConfigMap, HostVerificationFailed, Session, SessionEvent, SshChildProcess, SshPty,
+    ConfigMap, HostVerificationDecision, HostVerificationEvent, HostVerificationFailed, Session,
+    SessionEvent, SshChildProcess, SshPty,
 };
 use wezterm_term::TerminalSize;
 
-#[derive(Default)]
-struct PasswordPromptHost {
-    history: BasicHistory,
-    echo: bool,
-}
-impl LineEditorHost for PasswordPromptHost {
-    fn history(&mut self) -> &mut dyn History {
-        &mut self.history
-    }
-
-    fn highlight_line(&self, line: &str, cursor_position: usize) -> (Vec<OutputElement>, usize) {
-        if self.echo {
-            (vec![OutputElement::Text(line.to_string())], cursor_position)
-        } else {
-            // Rewrite the input so that we can obscure the password
-            // characters when output to the terminal widget
-            let placeholder = "🔑";
-            let grapheme_count = unicode_column_width(line, None);
-            let mut output = vec![];
-            for _ in 0..grapheme_count {
-                output.push(OutputElement::Text(placeholder.to_string()));
-            }
-            (
-                output,
-                unicode_column_width(placeholder, None) * cursor_position,
-            )
-        }
-    }
-}
-
 pub fn ssh_connect_with_ui(
     ssh_config: wezterm_ssh::ConfigMap,
     ui: &mut ConnectionUI,
@@ -80,15 +53,11 @@ pub fn ssh_connect_with_ui(
                 }
                 SessionEvent::HostVerify(verify) => {
                     ui.output_str(&format!("{}\n", verify.message));
-                    let ok = if let Ok(line) = ui.input("Enter [y/n]> ") {
-                        match line.as_ref() {
-                            "y" | "Y" | "yes" | "YES" => true,
-                            "n" | "N" | "no" | "NO" | _ => false,
-                        }
-                    } else {
-                        false
+                    let decision = match ui.input(host_verify_prompt(&verify)) {
+                        Ok(line) => parse_host_verify_answer(&line, verify.key_changed),
+                        Err(_) => HostVerificationDecision::Reject,
                     };
-                    smol::block_on(verify.answer(ok)).context("send verify response")?;
+                    smol::block_on(verify.decide(decision)).context("send verify response")?;
                 }
                 SessionEvent::Authenticate(auth) => {
                     if !auth.username.is_empty() {
@@ -118,9 +87,10 @@ pub fn ssh_connect_with_ui(
                     smol::block_on(auth.answer(answers))?;
                 }
                 SessionEvent::HostVerificationFailed(failed) => {
+                    // The session follows this with a HostVerify event
+                    // that lets the user decide whether to continue
                     let message = format_host_verification_for_terminal(failed);
                     ui.output(message);
-                    anyhow::bail!("Host key verification failed");
                 }
                 SessionEvent::Error(err) => {
                     anyhow::bail!("Error: {}", err);
@@ -132,6 +102,23 @@ pub fn ssh_connect_with_ui(
     })
 }
 
+fn host_verify_prompt(verify: &HostVerificationEvent) -> &'static str {
+    if verify.key_changed {
+        "Enter [o]nce to connect this time only, or [n]o> "
+    } else {
+        "Enter [y]es to trust and remember this host, [o]nce to trust it \
+         for this connection only, or [n]o> "
+    }
+}
+
+fn parse_host_verify_answer(line: &str, key_changed: bool) -> HostVerificationDecision {
+    match line.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" if !key_changed => HostVerificationDecision::AcceptPermanently,
+        "o" | "once" => HostVerificationDecision::AcceptOnce,
+        _ => HostVerificationDecision::Reject,
+    }
+}
+
 fn format_host_verification_for_terminal(failed: HostVerificationFailed) -> Vec<Change> {
     vec![
         AttributeChange::Intensity(Intensity::Bold).into(),
@@ -225,6 +212,12 @@ pub fn ssh_domain_to_ssh_config(ssh_dom: &SshDomain) -> anyhow::Result<ConfigMap
     if ssh_dom.no_agent_auth {
         ssh_config.insert("identitiesonly".to_string(), "yes".to_string());
     }
+    if let Some(forward_agent) = ssh_dom.forward_agent {
+        ssh_config.insert(
+            "forwardagent".to_string(),
+            if forward_agent { "yes" } else { "no" }.to_string(),
+        );
+    }
     if let Some("true") = ssh_config.get("wezterm_ssh_verbose").map(|s| s.as_str()) {
         log::info!("Using ssh config: {ssh_config:#?}");
     }
@@ -246,20 +239,17 @@ impl RemoteSshDomain {
         ssh_domain_to_ssh_config(&self.dom)
     }
 
-    fn build_command(
+    async fn build_command(
         &self,
         pane_id: PaneId,
         command: Option<CommandBuilder>,
         command_dir: Option<String>,
     ) -> anyhow::Result<(Option<String>, HashMap<String, String>)> {
         let config = config::configuration();
-        let cmd = match command {
-            Some(mut cmd) => {
-                config.apply_cmd_defaults(&mut cmd, self.dom.default_prog.as_ref(), None);
-                cmd
-            }
-            None => config.build_prog(None, self.dom.default_prog.as_ref(), None)?,
-        };
+        let mut cmd = command.unwrap_or_else(CommandBuilder::new_default_prog);
+        config.apply_domain_environment(&mut cmd, &self.name);
+        let explicit_term = cmd.iter_extra_env_as_str().any(|(k, _)| k == "TERM");
+        config.apply_cmd_defaults(&mut cmd, self.dom.default_prog.as_ref(), None);
         let mut env: HashMap<String, String> = cmd
             .iter_extra_env_as_str()
             .map(|(k, v)| (k.to_string(), v.to_string()))
@@ -267,11 +257,19 @@ impl RemoteSshDomain {
 
         // Remote servers won't have the "kaku" terminfo entry, which causes
         // garbled display over SSH.  Override to xterm-256color so the remote
-        // side can handle cursor movement, line wrapping, etc. correctly.
-        if env.get("TERM").map(|t| t.as_str()) == Some("kaku") {
+        // side can handle cursor movement, line wrapping, etc. correctly,
+        // unless TERM was chosen explicitly for this domain or spawn, eg:
+        // after `kaku install-terminfo --ssh`.
+        if !explicit_term && env.get("TERM").map(|t| t.as_str()) == Some("kaku") {
             env.insert("TERM".to_string(), "xterm-256color".to_string());
         }
 
+        // Only the variables that differ from our own environment are
+        // sent to the remote host, so that is all the handler gets to see.
+        if let Some(adjusted) = adjust_spawn_environment(&self.name, env.clone()).await {
+            env = adjusted;
+        }
+
         // FIXME: this isn't useful without a way to talk to the remote mux.
         // One option is to forward the mux via unix domain, another is to
         // embed the mux protocol in an escape sequence and just use the
@@ -611,16 +609,12 @@ fn connect_ssh_session(
                 let mut editor = LineEditor::new(&mut shim);
                 let mut host = PasswordPromptHost::default();
                 host.echo = true;
-                editor.set_prompt("Enter [y/n]> ");
-                let ok = if let Some(line) = editor.read_line(&mut host)? {
-                    match line.as_ref() {
-                        "y" | "Y" | "yes" | "YES" => true,
-                        "n" | "N" | "no" | "NO" | _ => false,
-                    }
-                } else {
-                    false
+                editor.set_prompt(host_verify_prompt(&verify));
+                let decision = match editor.read_line(&mut host)? {
+                    Some(line) => parse_host_verify_answer(&line, verify.key_changed),
+                    None => HostVerificationDecision::Reject,
                 };
-                smol::block_on(verify.answer(ok)).context("send verify response")?;
+                smol::block_on(verify.decide(decision)).context("send verify response")?;
             }
             SessionEvent::Authenticate(auth) => {
                 if !auth.username.is_empty() {
@@ -638,8 +632,12 @@ fn connect_ssh_session(
                     }
                     let mut editor = LineEditor::new(&mut shim);
                     let mut host = PasswordPromptHost::default();
-                    editor.set_prompt(editor_prompt);
                     host.echo = prompt.echo;
+                    if prompt.echo {
+                        editor.set_prompt(editor_prompt);
+                    } else {
+                        editor.set_prompt(&secret_prompt(editor_prompt));
+                    }
                     if let Some(line) = editor.read_line(&mut host)? {
                         answers.push(line);
                     } else {
@@ -720,6 +718,7 @@ impl Domain for RemoteSshDomain {
 
         let (command_line, env) = self
             .build_command(pane_id, command, command_dir)
+            .await
             .context("build_command")?;
 
         // This needs to be separate from the if let block below in order
@@ -770,6 +769,8 @@ impl Domain for RemoteSshDomain {
 
         let encoding = Arc::new(AtomicU8::new(encoding.to_u8()));
         let writer = WriterWrapper::new(writer, Arc::clone(&encoding));
+        let encoding_errors = writer.encoding_errors();
+        let output_log = writer.output_log();
 
         let terminal = wezterm_term::Terminal::new(
             size,
@@ -787,6 +788,8 @@ impl Domain for RemoteSshDomain {
             Box::new(writer),
             self.id,
             encoding,
+            encoding_errors,
+            output_log,
             "RemoteSshDomain".to_string(),
         ));
         let mux = Mux::get();
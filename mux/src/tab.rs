@@ -3,8 +3,8 @@ use crate::pane::*;
 use crate::renderable::StableCursorPosition;
 use crate::{Mux, MuxNotification, WindowId};
 use bintree::PathBranch;
-use config::configuration;
 use config::keyassignment::PaneDirection;
+use config::{configuration, RgbaColor};
 use parking_lot::Mutex;
 use rangeset::intersects_range;
 use serde::{Deserialize, Serialize};
@@ -98,6 +98,8 @@ struct TabInner {
     active: usize,
     zoomed: Option<Arc<dyn Pane>>,
     title: String,
+    color: Option<RgbaColor>,
+    group: Option<String>,
     recency: Recency,
 }
 
@@ -610,6 +612,33 @@ impl Tab {
         }
     }
 
+    /// Returns the color assigned to this tab, which tints its tab bar
+    /// entry and pane borders
+    pub fn get_color(&self) -> Option<RgbaColor> {
+        self.inner.lock().color
+    }
+
+    pub fn set_color(&self, color: Option<RgbaColor>) {
+        let mut inner = self.inner.lock();
+        if inner.color != color {
+            inner.color = color;
+            Mux::try_get().map(|mux| mux.notify(MuxNotification::TabColorChanged(inner.id)));
+        }
+    }
+
+    /// Returns the name of the tab group that this tab belongs to
+    pub fn get_group(&self) -> Option<String> {
+        self.inner.lock().group.clone()
+    }
+
+    pub fn set_group(&self, group: Option<String>) {
+        let mut inner = self.inner.lock();
+        if inner.group != group {
+            inner.group = group;
+            Mux::try_get().map(|mux| mux.notify(MuxNotification::TabGroupChanged(inner.id)));
+        }
+    }
+
     /// Called by the multiplexer client when building a local tab to
     /// mirror a remote tab.  The supplied `root` is the information
     /// about our counterpart in the the remote server.
@@ -874,6 +903,8 @@ impl TabInner {
             active: 0,
             zoomed: None,
             title: String::new(),
+            color: None,
+            group: None,
             recency: Recency::default(),
         }
     }
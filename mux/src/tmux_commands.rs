@@ -210,6 +210,8 @@ impl TmuxDomainState {
         let pane_encoding = Self::pane_encoding_for_spawn(spawn_encoding);
         let encoding = Arc::new(AtomicU8::new(pane_encoding.to_u8()));
         let writer = WriterWrapper::new(pane_pty.take_writer()?, Arc::clone(&encoding));
+        let encoding_errors = writer.encoding_errors();
+        let output_log = writer.output_log();
 
         let size = TerminalSize {
             rows: pane.pane_height as usize,
@@ -239,6 +241,8 @@ impl TmuxDomainState {
             Box::new(writer),
             self.domain_id,
             encoding,
+            encoding_errors,
+            output_log,
             "tmux pane".to_string(),
         )))
     }
@@ -2,6 +2,7 @@ use crate::color::ColorPalette;
 use downcast_rs::{impl_downcast, Downcast};
 use wezterm_bidi::ParagraphDirectionHint;
 use wezterm_cell::UnicodeVersion;
+use wezterm_escape_parser::csi::KittyKeyboardFlags;
 use wezterm_surface::{Line, SequenceNo};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -127,6 +128,19 @@ impl Default for NewlineCanon {
     }
 }
 
+/// How DECSCUSR requests from applications to change the cursor
+/// shape are handled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CursorStyleEscapes {
+    /// Apply the requested shape
+    #[default]
+    Allow,
+    /// Apply the requested shape, but never blink
+    SteadyOnly,
+    /// Keep the configured default shape
+    Ignore,
+}
+
 /// TerminalConfiguration allows for the embedding application to pass configuration
 /// information to the Terminal.
 /// The configuration can be changed at runtime; provided that the implementation
@@ -147,6 +161,18 @@ pub trait TerminalConfiguration: Downcast + std::fmt::Debug + Send + Sync {
         3500
     }
 
+    /// Returns true if lines evicted from the scrollback should be
+    /// retained on disk rather than discarded.
+    fn scrollback_spill_to_disk(&self) -> bool {
+        false
+    }
+
+    /// Returns the most disk space, in bytes, that the spilled lines
+    /// may take up before the oldest of them are discarded.
+    fn scrollback_spill_max_bytes(&self) -> usize {
+        256 * 1024 * 1024
+    }
+
     /// Return true if the embedding application wants to use CSI-u encoding
     /// for keys that would otherwise be ambiguous.
     /// <http://www.leonerd.org.uk/hacks/fixterms/>
@@ -154,6 +180,12 @@ pub trait TerminalConfiguration: Downcast + std::fmt::Debug + Send + Sync {
         false
     }
 
+    /// The highest xterm modifyOtherKeys level that applications are
+    /// allowed to enable; 0 prevents it from being enabled at all.
+    fn max_modify_other_keys_level(&self) -> i64 {
+        2
+    }
+
     /// Returns the default color palette for the application.
     /// Various escape sequences can dynamically modify the effective
     /// color palette for a terminal instance at runtime, but this method
@@ -164,6 +196,20 @@ pub trait TerminalConfiguration: Downcast + std::fmt::Debug + Send + Sync {
         NewlineCanon::default()
     }
 
+    /// Whether wheel events are sent as arrow keys while the alternate
+    /// screen is active and mouse reporting is not enabled.
+    /// DECSET 1007 enables this regardless.
+    fn alternate_buffer_wheel_sends_arrow_keys(&self) -> bool {
+        true
+    }
+
+    /// Whether lines in the primary screen are rewrapped to fit when
+    /// the terminal is resized narrower.  When false, lines that have
+    /// already scrolled into the scrollback keep their original width.
+    fn rewrap_on_resize(&self) -> bool {
+        true
+    }
+
     fn alternate_buffer_wheel_scroll_speed(&self) -> u8 {
         3
     }
@@ -172,6 +218,16 @@ pub trait TerminalConfiguration: Downcast + std::fmt::Debug + Send + Sync {
         "".to_string()
     }
 
+    /// Overrides the parameters sent in response to DA1, `CSI c`
+    fn primary_device_attributes(&self) -> Option<String> {
+        None
+    }
+
+    /// Overrides the parameters sent in response to DA2, `CSI > c`
+    fn secondary_device_attributes(&self) -> Option<String> {
+        None
+    }
+
     fn enable_kitty_graphics(&self) -> bool {
         false
     }
@@ -180,6 +236,12 @@ pub trait TerminalConfiguration: Downcast + std::fmt::Debug + Send + Sync {
         false
     }
 
+    /// The subset of kitty keyboard protocol flags that applications
+    /// may enable; requested flags outside of this set are ignored.
+    fn kitty_keyboard_flags(&self) -> KittyKeyboardFlags {
+        KittyKeyboardFlags::all()
+    }
+
     /// The default unicode version to assume.
     /// This affects how the width of certain sequences is interpreted.
     /// At the time of writing, we default to 9 even though the current
@@ -220,6 +282,43 @@ pub trait TerminalConfiguration: Downcast + std::fmt::Debug + Send + Sync {
         false
     }
 
+    /// Whether applications may enable focus in/out reporting
+    fn enable_focus_reporting(&self) -> bool {
+        true
+    }
+
+    /// Whether applications may enable in-band resize notifications
+    /// <https://gist.github.com/rockorager/e695fb2924d36b2bcf1fff4a3704bd83>
+    fn enable_in_band_resize(&self) -> bool {
+        true
+    }
+
+    /// Whether to answer XTWINOPS queries for the text area and cell size
+    fn enable_window_size_reporting(&self) -> bool {
+        true
+    }
+
+    /// How applications may change the cursor shape via DECSCUSR
+    fn cursor_style_escapes(&self) -> CursorStyleEscapes {
+        CursorStyleEscapes::default()
+    }
+
+    /// Whether applications may change the cursor color via OSC 12
+    fn allow_cursor_color_escapes(&self) -> bool {
+        true
+    }
+
+    /// The minimum contrast ratio against the background that a cursor
+    /// color set via OSC 12 must have; less visible colors are ignored
+    fn cursor_color_min_contrast(&self) -> f32 {
+        1.0
+    }
+
+    /// Whether applications may hide the cursor via DECTCEM
+    fn allow_cursor_hiding(&self) -> bool {
+        true
+    }
+
     fn log_unknown_escape_sequences(&self) -> bool {
         false
     }
@@ -1,6 +1,7 @@
 #![allow(clippy::range_plus_one)]
 use super::*;
 use crate::config::BidiMode;
+use crate::spill::ScrollbackSpill;
 use log::debug;
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -35,6 +36,9 @@ pub struct Screen {
     /// that we're the primary rather than the alternate screen.
     allow_scrollback: bool,
 
+    /// If set, used in place of the configured scrollback size
+    scrollback_limit: Option<usize>,
+
     pub(crate) keyboard_stack: Vec<KeyboardEncoding>,
 
     /// Physical, visible height of the screen (not including scrollback)
@@ -44,6 +48,13 @@ pub struct Screen {
     pub dpi: u32,
 
     pub(crate) saved_cursor: Option<SavedCursor>,
+
+    /// Lines that were evicted from the front of `lines` when
+    /// `scrollback_spill_to_disk` is enabled.  Created on first use.
+    spill: Option<Arc<ScrollbackSpill>>,
+    /// Set if we failed to create the spill file, so that we don't
+    /// keep retrying on every evicted line
+    spill_failed: bool,
 }
 
 fn scrollback_size(config: &Arc<dyn TerminalConfiguration>, allow_scrollback: bool) -> usize {
@@ -89,12 +100,15 @@ impl Screen {
             lines,
             config: Arc::clone(config),
             allow_scrollback,
+            scrollback_limit: None,
             physical_rows,
             physical_cols,
             stable_row_index_offset: 0,
             dpi: size.dpi,
             keyboard_stack: vec![],
             saved_cursor: None,
+            spill: None,
+            spill_failed: false,
         }
     }
 
@@ -103,7 +117,32 @@ impl Screen {
     }
 
     fn scrollback_size(&self) -> usize {
-        scrollback_size(&self.config, self.allow_scrollback)
+        match self.scrollback_limit {
+            Some(limit) if self.allow_scrollback => limit,
+            _ => scrollback_size(&self.config, self.allow_scrollback),
+        }
+    }
+
+    /// Overrides the configured scrollback size for this screen, or
+    /// restores it when `limit` is None.  Lines beyond the new size
+    /// are evicted immediately.
+    pub fn set_scrollback_limit(&mut self, limit: Option<usize>) {
+        self.scrollback_limit = limit;
+        self.evict_excess_lines();
+    }
+
+    /// Evicts lines from the top of the scrollback until the screen
+    /// fits within the scrollback size, spilling them if enabled
+    fn evict_excess_lines(&mut self) {
+        let max_lines = self.physical_rows + self.scrollback_size();
+        while self.lines.len() > max_lines {
+            if let Some(line) = self.lines.pop_front() {
+                self.spill_line(self.stable_row_index_offset as StableRowIndex, &line);
+            }
+            if self.allow_scrollback {
+                self.stable_row_index_offset += 1;
+            }
+        }
     }
 
     fn rewrap_lines(
@@ -239,11 +278,19 @@ impl Screen {
             // We only do this for the primary, and not for the alternate
             // screen (hence the check for allow_scrollback), to avoid
             // conflicting screen updates with full screen apps.
-            if self.allow_scrollback {
+            if self.allow_scrollback && self.config.rewrap_on_resize() {
                 self.rewrap_lines(physical_cols, physical_rows, cursor.x, cursor_phys, seqno)
             } else {
-                for line in &mut self.lines {
-                    if physical_cols < self.physical_cols {
+                // When not rewrapping the primary screen, the scrollback
+                // keeps its full width so that it can be scrolled
+                // horizontally; only the visible screen is pruned.
+                let first_visible = if self.allow_scrollback {
+                    self.lines.len().saturating_sub(self.physical_rows)
+                } else {
+                    0
+                };
+                for (idx, line) in self.lines.iter_mut().enumerate() {
+                    if physical_cols < self.physical_cols && idx >= first_visible {
                         // Do a simple prune of the lines instead
                         line.resize(physical_cols, seqno);
                     } else {
@@ -336,6 +383,16 @@ impl Screen {
 
         self.physical_rows = physical_rows;
         self.physical_cols = physical_cols;
+
+        // Rewrapping to a narrower width may have left more lines than
+        // the scrollback can hold.  Without spilling they are trimmed by
+        // the next scroll, which gives a resize back to a wider width a
+        // chance to recover them, but when spilling we can move them to
+        // the spill straight away without losing anything.
+        if self.config.scrollback_spill_to_disk() {
+            self.evict_excess_lines();
+        }
+
         CursorPosition {
             x: cursor_x,
             y: new_cursor_y,
@@ -466,6 +523,8 @@ impl Screen {
         let line = self.line_mut(line_idx);
         if cols.start == 0 {
             bidi_mode.apply_to_line(line, seqno);
+            // Whatever prompt was here is being erased
+            line.set_command_succeeded(None, seqno);
         }
         line.fill_range(cols, &Cell::blank_with_attrs(attr.clone()), seqno);
     }
@@ -722,9 +781,15 @@ impl Screen {
         // To avoid thrashing the heap, prefer to move lines that were
         // scrolled off the top and re-use them at the bottom.
         let to_move = lines_removed.min(num_rows);
+        // The stable row index of the next line to be evicted
+        let mut evicted_row = self.stable_row_index_offset as StableRowIndex;
         let (to_remove, to_add) = {
             for _ in 0..to_move {
                 let mut line = self.lines.remove(remove_idx).unwrap();
+                if remove_idx == 0 && scrollback_ok {
+                    self.spill_line(evicted_row, &line);
+                    evicted_row += 1;
+                }
                 let line = if default_blank == blank_attr {
                     Line::new(seqno)
                 } else {
@@ -746,7 +811,12 @@ impl Screen {
 
         // Perform the removal
         for _ in 0..to_remove {
-            self.lines.remove(remove_idx);
+            if let Some(line) = self.lines.remove(remove_idx) {
+                if remove_idx == 0 && scrollback_ok {
+                    self.spill_line(evicted_row, &line);
+                    evicted_row += 1;
+                }
+            }
         }
 
         if remove_idx == 0 && scrollback_ok {
@@ -779,7 +849,235 @@ impl Screen {
         }
     }
 
+    /// Hands a line that is about to be evicted from the scrollback
+    /// to the spill, if spilling is enabled.  `row` is the stable
+    /// row index of the line.
+    fn spill_line(&mut self, row: StableRowIndex, line: &Line) {
+        if !self.allow_scrollback || self.spill_failed {
+            return;
+        }
+        if !self.config.scrollback_spill_to_disk() {
+            // Spilled rows have to be contiguous with the scrollback,
+            // so anything spilled before is unreachable once we start
+            // discarding lines
+            self.spill = None;
+            return;
+        }
+        if self.spill.is_none() {
+            match ScrollbackSpill::new() {
+                Ok(spill) => self.spill = Some(Arc::new(spill)),
+                Err(err) => {
+                    log::error!("Unable to create scrollback spill file: {err:#}");
+                    self.spill_failed = true;
+                    return;
+                }
+            }
+        }
+        if let Some(spill) = &self.spill {
+            spill.push(row, line, self.config.scrollback_spill_max_bytes());
+        }
+    }
+
+    /// Returns the text of the lines that were evicted from the
+    /// scrollback and spilled to disk, oldest first
+    pub fn spilled_scrollback_text(&self) -> anyhow::Result<String> {
+        if self.spilled_rows().is_empty() {
+            return Ok(String::new());
+        }
+        match &self.spill {
+            Some(spill) => spill.text(),
+            None => Ok(String::new()),
+        }
+    }
+
+    /// Returns the stable row indices of the lines that were spilled
+    /// to disk, which immediately precede the in-memory scrollback.
+    /// If the spill isn't contiguous with the scrollback (eg: a line
+    /// failed to spill) then none of it is reachable.
+    fn spilled_rows(&self) -> Range<StableRowIndex> {
+        let top = self.phys_to_stable_row_index(0);
+        match &self.spill {
+            Some(spill) => {
+                let rows = spill.rows();
+                if rows.end == top {
+                    rows
+                } else {
+                    top..top
+                }
+            }
+            None => top..top,
+        }
+    }
+
+    /// Returns the number of lines that have been spilled to disk
+    pub fn spilled_scrollback_rows(&self) -> usize {
+        let rows = self.spilled_rows();
+        (rows.end - rows.start) as usize
+    }
+
+    /// Returns the stable row index of the oldest row that is still
+    /// available, which may be one that was spilled to disk
+    pub fn scrollback_top(&self) -> StableRowIndex {
+        self.spilled_rows().start
+    }
+
+    /// Reads back the spilled lines in `rows`
+    fn spilled_lines(&self, rows: Range<StableRowIndex>) -> Vec<Line> {
+        match &self.spill {
+            Some(spill) if !rows.is_empty() => {
+                rows.map(|row| read_spilled_line(spill, row)).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Splits a StableRowIndex range into the rows that were spilled to
+    /// disk and the physical range of the in-memory rows that follow
+    /// them.  As with `stable_range`, a range that goes off the top of
+    /// the scrollback is moved down so that it starts at the top.
+    fn spilled_and_phys_range(
+        &self,
+        range: &Range<StableRowIndex>,
+    ) -> (Range<StableRowIndex>, Range<PhysRowIndex>) {
+        let spilled = self.spilled_rows();
+        if range.start >= spilled.end {
+            return (spilled.end..spilled.end, self.stable_range(range));
+        }
+
+        let start = range.start.max(spilled.start);
+        let end = start + (range.end - range.start).max(0);
+        let in_memory = (end - spilled.end).max(0) as usize;
+        (
+            start..end.min(spilled.end),
+            0..in_memory.min(self.lines.len()),
+        )
+    }
+
+    /// Returns a copy of the lines in the StableRowIndex range, including
+    /// those that were spilled to disk, along with the stable row index
+    /// of the first of them
+    pub fn lines_in_stable_range(
+        &self,
+        range: &Range<StableRowIndex>,
+    ) -> (StableRowIndex, Vec<Line>) {
+        let (spilled, phys_range) = self.spilled_and_phys_range(range);
+        let first = if spilled.is_empty() {
+            self.phys_to_stable_row_index(phys_range.start)
+        } else {
+            spilled.start
+        };
+        let mut lines = self.spilled_lines(spilled);
+        lines.extend(self.lines_in_phys_range(phys_range));
+        (first, lines)
+    }
+
+    /// Calls `func` with the lines in the StableRowIndex range, including
+    /// those that were spilled to disk, and the stable row index of the
+    /// first of them
+    pub fn with_stable_lines<F>(&self, range: &Range<StableRowIndex>, mut func: F)
+    where
+        F: FnMut(StableRowIndex, &[&Line]),
+    {
+        let (spilled, phys_range) = self.spilled_and_phys_range(range);
+        if spilled.is_empty() {
+            let first = self.phys_to_stable_row_index(phys_range.start);
+            self.with_phys_lines(phys_range, |lines| func(first, lines));
+        } else {
+            let spilled_lines = self.spilled_lines(spilled.clone());
+            self.with_phys_lines(phys_range, |lines| {
+                let lines: Vec<&Line> = spilled_lines.iter().chain(lines.iter().copied()).collect();
+                func(spilled.start, &lines)
+            });
+        }
+    }
+
+    /// Like `with_stable_lines`, but the lines are mutable.  Changes made
+    /// to spilled lines are not retained.
+    pub fn with_stable_lines_mut<F>(&mut self, range: &Range<StableRowIndex>, mut func: F)
+    where
+        F: FnMut(StableRowIndex, &mut [&mut Line]),
+    {
+        let (spilled, phys_range) = self.spilled_and_phys_range(range);
+        if spilled.is_empty() {
+            let first = self.phys_to_stable_row_index(phys_range.start);
+            self.with_phys_lines_mut(phys_range, |lines| func(first, lines));
+        } else {
+            let mut spilled_lines = self.spilled_lines(spilled.clone());
+            self.with_phys_lines_mut(phys_range, |lines| {
+                let mut lines: Vec<&mut Line> = spilled_lines
+                    .iter_mut()
+                    .chain(lines.iter_mut().map(|line| &mut **line))
+                    .collect();
+                func(spilled.start, &mut lines)
+            });
+        }
+    }
+
+    /// Calls `f` for each logical line of spilled rows that intersects
+    /// `stable_range`.  Logical lines are not joined across the boundary
+    /// between the spilled rows and the in-memory scrollback.
+    /// Returns false if `f` asked to stop iterating.
+    fn for_each_spilled_logical_line<F>(
+        &self,
+        stable_range: &Range<StableRowIndex>,
+        mut f: F,
+    ) -> bool
+    where
+        F: FnMut(Range<StableRowIndex>, &mut [Line]) -> bool,
+    {
+        const MAX_LOGICAL_LINE_LEN: usize = 1024;
+
+        let spilled = self.spilled_rows();
+        let spill = match &self.spill {
+            Some(spill) if !spilled.is_empty() => spill,
+            _ => return true,
+        };
+
+        // Look backwards to find the start of the first logical line
+        let mut row = stable_range.start.max(spilled.start);
+        let mut back_len = 0;
+        while row > spilled.start {
+            if spill.is_wrapped(row - 1) != Some(true) {
+                break;
+            }
+            let prior = read_spilled_line(spill, row - 1);
+            if prior.len() + back_len > MAX_LOGICAL_LINE_LEN {
+                break;
+            }
+            back_len += prior.len();
+            row -= 1;
+        }
+
+        let end = stable_range.end.min(spilled.end);
+        while row < end {
+            let start = row;
+            let mut total_len = 0;
+            let mut lines = vec![];
+            while row < spilled.end {
+                let line = read_spilled_line(spill, row);
+                if total_len > 0 && total_len + line.len() > MAX_LOGICAL_LINE_LEN {
+                    break;
+                }
+                row += 1;
+                total_len += line.len();
+                let wrapped = line.last_cell_was_wrapped();
+                lines.push(line);
+                if !wrapped {
+                    break;
+                }
+            }
+
+            if !f(start..row, &mut lines) {
+                return false;
+            }
+        }
+        true
+    }
+
     pub fn erase_scrollback(&mut self) {
+        if let Some(spill) = &self.spill {
+            spill.clear();
+        }
         let len = self.lines.len();
         let to_clear = len - self.physical_rows;
         for _ in 0..to_clear {
@@ -1015,6 +1313,19 @@ impl Screen {
     ) where
         F: FnMut(Range<StableRowIndex>, &mut [&mut Line]) -> bool,
     {
+        let mut stable_range = stable_range;
+        let spilled = self.spilled_rows();
+        if !spilled.is_empty() && stable_range.start < spilled.end {
+            let keep_going = self.for_each_spilled_logical_line(&stable_range, |range, lines| {
+                let mut lines: Vec<&mut Line> = lines.iter_mut().collect();
+                f(range, &mut lines)
+            });
+            if !keep_going || stable_range.end <= spilled.end {
+                return;
+            }
+            stable_range.start = spilled.end;
+        }
+
         let mut phys_range = self.stable_range(&stable_range);
 
         // Avoid pathological cases where we have eg: a really long logical line
@@ -1094,6 +1405,19 @@ impl Screen {
     ) where
         F: FnMut(Range<StableRowIndex>, &[&Line]) -> bool,
     {
+        let mut stable_range = stable_range;
+        let spilled = self.spilled_rows();
+        if !spilled.is_empty() && stable_range.start < spilled.end {
+            let keep_going = self.for_each_spilled_logical_line(&stable_range, |range, lines| {
+                let lines: Vec<&Line> = lines.iter().collect();
+                f(range, &lines)
+            });
+            if !keep_going || stable_range.end <= spilled.end {
+                return;
+            }
+            stable_range.start = spilled.end;
+        }
+
         let mut phys_range = self.stable_range(&stable_range);
 
         // Avoid pathological cases where we have eg: a really long logical line
@@ -1173,3 +1497,16 @@ fn phys_intersection(r1: &Range<PhysRowIndex>, r2: &Range<PhysRowIndex>) -> Rang
         0..0
     }
 }
+
+/// Reads the spilled line at `row`, producing a blank line if it can't be
+/// read back so that the caller still gets one line per row
+fn read_spilled_line(spill: &ScrollbackSpill, row: StableRowIndex) -> Line {
+    match spill.line(row) {
+        Ok(Some(line)) => line,
+        Ok(None) => Line::new(0),
+        Err(err) => {
+            log::error!("Failed to read spilled scrollback: {err:#}");
+            Line::new(0)
+        }
+    }
+}
@@ -0,0 +1,641 @@
+//! Storage for scrollback lines that have been evicted from the in-memory
+//! line store.
+//!
+//! When `scrollback_spill_to_disk` is enabled, lines that would otherwise
+//! be discarded once the scrollback reaches `scrollback_lines` are handed
+//! to a `ScrollbackSpill`.  They are kept in memory until a batch of them
+//! has built up, and then a background thread encodes, seals and appends
+//! the whole batch to an anonymous temporary file, so that the thread
+//! parsing the output of the pane never waits on the disk.
+//!
+//! The files are unlinked as soon as they are created, so they disappear
+//! along with the process even if we crash.  Each line is sealed with
+//! AES-256-GCM using a per-pane key that only lives in memory, so nothing
+//! readable is left in the temporary directory and a record that has been
+//! modified on disk fails to decrypt rather than being shown.
+//!
+//! The lines are spread over a handful of files; once they take up more
+//! than the configured number of bytes, the file holding the oldest of
+//! them is discarded.
+//!
+//! Spilled lines keep the stable row index that they had in the screen,
+//! which allows them to be read back, attributes and all, for display and
+//! search.
+//!
+//! Sealing the lines requires the `spill` feature.  Without it, a spill
+//! can't be created and evicted lines are discarded as usual.
+use crate::StableRowIndex;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use wezterm_surface::line::Line;
+
+/// The number of lines that are sealed and written together
+const BATCH_LINES: usize = 256;
+/// The number of files that the byte limit is divided between
+const SEGMENTS: u64 = 4;
+
+#[cfg(feature = "spill")]
+mod sealer {
+    use super::Line;
+    use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+    use serde::{Deserialize, Serialize};
+    use std::fs::File;
+
+    pub const KEY_LEN: usize = 32;
+    const NONCE_LEN: usize = 12;
+    pub const TAG_LEN: usize = 16;
+
+    /// Encodes lines and seals them with a key that only lives in memory
+    pub struct Sealer {
+        key: [u8; KEY_LEN],
+    }
+
+    impl Sealer {
+        pub fn new() -> anyhow::Result<Self> {
+            let mut key = [0u8; KEY_LEN];
+            openssl::rand::rand_bytes(&mut key)?;
+            Ok(Self { key })
+        }
+
+        #[cfg(test)]
+        pub fn with_key(key: [u8; KEY_LEN]) -> Self {
+            Self { key }
+        }
+
+        /// Creates an unlinked file to hold sealed lines
+        pub fn create_file(&self) -> anyhow::Result<File> {
+            Ok(tempfile::tempfile()?)
+        }
+
+        fn nonce(counter: u64) -> [u8; NONCE_LEN] {
+            let mut nonce = [0u8; NONCE_LEN];
+            nonce[NONCE_LEN - 8..].copy_from_slice(&counter.to_be_bytes());
+            nonce
+        }
+
+        /// Returns `line`, encoded and sealed, followed by its tag.
+        /// `nonce` must never be used again with this sealer.
+        pub fn seal(&self, nonce: u64, line: &Line) -> anyhow::Result<Vec<u8>> {
+            let mut data = vec![];
+            line.serialize(&mut varbincode::Serializer::new(&mut data))?;
+            let mut tag = [0u8; TAG_LEN];
+            let mut sealed = encrypt_aead(
+                Cipher::aes_256_gcm(),
+                &self.key,
+                Some(&Self::nonce(nonce)[..]),
+                &[],
+                &data,
+                &mut tag,
+            )?;
+            sealed.extend_from_slice(&tag);
+            Ok(sealed)
+        }
+
+        /// The inverse of `seal`
+        pub fn open(&self, nonce: u64, sealed: &[u8]) -> anyhow::Result<Line> {
+            let data_len = sealed
+                .len()
+                .checked_sub(TAG_LEN)
+                .ok_or_else(|| anyhow::anyhow!("sealed line is truncated"))?;
+            let (data, tag) = sealed.split_at(data_len);
+            let data = decrypt_aead(
+                Cipher::aes_256_gcm(),
+                &self.key,
+                Some(&Self::nonce(nonce)[..]),
+                &[],
+                data,
+                tag,
+            )?;
+            let line = Line::deserialize(&mut varbincode::Deserializer::new(&mut &data[..]))?;
+            Ok(line)
+        }
+    }
+}
+
+#[cfg(not(feature = "spill"))]
+mod sealer {
+    use super::Line;
+    use std::fs::File;
+
+    /// Lines can't be sealed without the `spill` feature, so there is
+    /// no way to make one of these
+    pub enum Sealer {}
+
+    impl Sealer {
+        pub fn new() -> anyhow::Result<Self> {
+            anyhow::bail!("wezterm-term was built without the spill feature")
+        }
+
+        pub fn create_file(&self) -> anyhow::Result<File> {
+            match *self {}
+        }
+
+        pub fn seal(&self, _nonce: u64, _line: &Line) -> anyhow::Result<Vec<u8>> {
+            match *self {}
+        }
+
+        pub fn open(&self, _nonce: u64, _sealed: &[u8]) -> anyhow::Result<Line> {
+            match *self {}
+        }
+    }
+}
+
+use sealer::Sealer;
+
+/// Location of a sealed line within its segment file
+#[derive(Debug, Clone, Copy)]
+struct SpilledLine {
+    offset: u64,
+    /// Length of the sealed line, including its tag
+    len: u32,
+    nonce: u64,
+    wrapped: bool,
+}
+
+/// One of the files holding sealed lines
+struct Segment {
+    file: File,
+    lines: Vec<SpilledLine>,
+    len: u64,
+}
+
+struct Shared {
+    /// The files holding the written lines, oldest first
+    segments: VecDeque<Segment>,
+    /// Lines that follow those in `segments`, but that haven't been
+    /// written yet
+    unwritten: VecDeque<Line>,
+    /// The stable row index of the first spilled line
+    first_row: StableRowIndex,
+    max_bytes: u64,
+    /// Counter from which the nonce of each line is derived.  It is
+    /// never reset, not even by `clear`, so that a nonce is never reused
+    /// with the same key.
+    next_nonce: u64,
+    /// Bumped whenever the lines are discarded, so that a batch that was
+    /// being sealed at the time is discarded too
+    generation: usize,
+}
+
+impl Shared {
+    fn written(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|segment| segment.lines.len())
+            .sum()
+    }
+
+    fn bytes(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.len).sum()
+    }
+
+    fn rows(&self) -> Range<StableRowIndex> {
+        let len = self.written() + self.unwritten.len();
+        self.first_row..self.first_row + len as StableRowIndex
+    }
+
+    fn clear(&mut self) {
+        self.segments.clear();
+        self.unwritten.clear();
+        self.generation += 1;
+    }
+
+    /// Returns the segment index and location of the written line at
+    /// `idx`, counting from the first spilled line
+    fn locate(&self, mut idx: usize) -> Option<(usize, SpilledLine)> {
+        for (segment_idx, segment) in self.segments.iter().enumerate() {
+            match segment.lines.get(idx) {
+                Some(entry) => return Some((segment_idx, *entry)),
+                None => idx -= segment.lines.len(),
+            }
+        }
+        None
+    }
+
+    /// Appends a sealed batch of lines, whose offsets are relative to
+    /// the start of `data`, and then discards the oldest segments until
+    /// the lines fit within `max_bytes`
+    fn append(
+        &mut self,
+        sealer: &Sealer,
+        data: &[u8],
+        entries: Vec<SpilledLine>,
+    ) -> anyhow::Result<()> {
+        let segment_limit = (self.max_bytes / SEGMENTS).max(1);
+        if self
+            .segments
+            .back()
+            .is_none_or(|segment| segment.len >= segment_limit)
+        {
+            self.segments.push_back(Segment {
+                file: sealer.create_file()?,
+                lines: vec![],
+                len: 0,
+            });
+        }
+
+        if let Some(segment) = self.segments.back_mut() {
+            segment.file.seek(SeekFrom::Start(segment.len))?;
+            segment.file.write_all(data)?;
+            for entry in entries {
+                segment.lines.push(SpilledLine {
+                    offset: segment.len + entry.offset,
+                    ..entry
+                });
+            }
+            segment.len += data.len() as u64;
+        }
+
+        while self.segments.len() > 1 && self.bytes() > self.max_bytes {
+            if let Some(oldest) = self.segments.pop_front() {
+                self.first_row += oldest.lines.len() as StableRowIndex;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Seals `batch`, returning the sealed lines along with their locations
+/// relative to the start of them
+fn seal_batch(
+    sealer: &Sealer,
+    first_nonce: u64,
+    batch: &[Line],
+) -> anyhow::Result<(Vec<u8>, Vec<SpilledLine>)> {
+    let mut data = vec![];
+    let mut entries = vec![];
+    for (idx, line) in batch.iter().enumerate() {
+        let nonce = first_nonce + idx as u64;
+        let sealed = sealer.seal(nonce, line)?;
+        entries.push(SpilledLine {
+            offset: data.len() as u64,
+            len: u32::try_from(sealed.len())?,
+            nonce,
+            wrapped: line.last_cell_was_wrapped(),
+        });
+        data.extend_from_slice(&sealed);
+    }
+    Ok((data, entries))
+}
+
+/// Seals and writes the unwritten lines a batch at a time.  Unless `all`
+/// is set, a final partial batch is left in memory.  The lock is only
+/// held to pick up a batch and to append it once it has been sealed.
+fn write_batches(shared: &Mutex<Shared>, sealer: &Sealer, all: bool) {
+    loop {
+        let (batch, first_nonce, generation) = {
+            let mut shared = shared.lock().unwrap();
+            let len = shared.unwritten.len().min(BATCH_LINES);
+            if len == 0 || (len < BATCH_LINES && !all) {
+                return;
+            }
+            let batch: Vec<Line> = shared.unwritten.iter().take(len).cloned().collect();
+            let first_nonce = shared.next_nonce;
+            shared.next_nonce += len as u64;
+            (batch, first_nonce, shared.generation)
+        };
+
+        let sealed = seal_batch(sealer, first_nonce, &batch);
+
+        let mut shared = shared.lock().unwrap();
+        if shared.generation != generation {
+            // Discarded while we were sealing it
+            continue;
+        }
+        let result = sealed.and_then(|(data, entries)| shared.append(sealer, &data, entries));
+        if let Err(err) = result {
+            log::error!("Failed to spill scrollback lines: {err:#}");
+            // The spilled rows have to be contiguous, so none of those
+            // before the failed batch can be reached any more
+            shared.clear();
+            return;
+        }
+        shared.unwritten.drain(..batch.len());
+    }
+}
+
+pub struct ScrollbackSpill {
+    shared: Arc<Mutex<Shared>>,
+    sealer: Arc<Sealer>,
+    /// Wakes the writer thread when a batch of lines is ready
+    wake_writer: Sender<()>,
+}
+
+impl std::fmt::Debug for ScrollbackSpill {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Deliberately omits the key
+        let shared = self.shared.lock().unwrap();
+        fmt.debug_struct("ScrollbackSpill")
+            .field("rows", &shared.rows())
+            .field("bytes", &shared.bytes())
+            .finish()
+    }
+}
+
+impl ScrollbackSpill {
+    pub fn new() -> anyhow::Result<Self> {
+        Self::with_sealer(Sealer::new()?)
+    }
+
+    fn with_sealer(sealer: Sealer) -> anyhow::Result<Self> {
+        let shared = Arc::new(Mutex::new(Shared {
+            segments: VecDeque::new(),
+            unwritten: VecDeque::new(),
+            first_row: 0,
+            max_bytes: u64::MAX,
+            next_nonce: 0,
+            generation: 0,
+        }));
+        let sealer = Arc::new(sealer);
+        let (wake_writer, wakeups) = channel::<()>();
+        std::thread::Builder::new()
+            .name("scrollback-spill".to_string())
+            .spawn({
+                let shared = Arc::clone(&shared);
+                let sealer = Arc::clone(&sealer);
+                // Exits once the spill, and so the sender, is dropped
+                move || {
+                    while wakeups.recv().is_ok() {
+                        write_batches(&shared, &sealer, false);
+                    }
+                }
+            })?;
+        Ok(Self {
+            shared,
+            sealer,
+            wake_writer,
+        })
+    }
+
+    /// Returns the range of stable row indices held by the spill
+    pub fn rows(&self) -> Range<StableRowIndex> {
+        self.shared.lock().unwrap().rows()
+    }
+
+    /// Returns whether the spilled line at `row` wrapped onto the next
+    /// row, without reading it back from the file
+    pub fn is_wrapped(&self, row: StableRowIndex) -> Option<bool> {
+        let shared = self.shared.lock().unwrap();
+        if !shared.rows().contains(&row) {
+            return None;
+        }
+        let idx = (row - shared.first_row) as usize;
+        let written = shared.written();
+        if idx < written {
+            shared.locate(idx).map(|(_, entry)| entry.wrapped)
+        } else {
+            shared
+                .unwritten
+                .get(idx - written)
+                .map(|line| line.last_cell_was_wrapped())
+        }
+    }
+
+    /// Adds `line`, which was at stable row `row`, to the spill.  The
+    /// oldest lines are discarded once the spill files would take up
+    /// more than `max_bytes`.  Spilled rows must be contiguous; if `row`
+    /// doesn't follow on from the last spilled row, the previously
+    /// spilled lines are discarded.
+    pub fn push(&self, row: StableRowIndex, line: &Line, max_bytes: usize) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.max_bytes = max_bytes as u64;
+        let rows = shared.rows();
+        if rows.is_empty() {
+            shared.first_row = row;
+        } else if row != rows.end {
+            log::debug!("spilled row {row} is not contiguous with {rows:?}, discarding");
+            shared.clear();
+            shared.first_row = row;
+        }
+
+        shared.unwritten.push_back(line.clone());
+        if shared.unwritten.len() % BATCH_LINES == 0 {
+            self.wake_writer.send(()).ok();
+        }
+    }
+
+    /// Returns the spilled line at stable row `row`
+    pub fn line(&self, row: StableRowIndex) -> anyhow::Result<Option<Line>> {
+        let mut shared = self.shared.lock().unwrap();
+        if !shared.rows().contains(&row) {
+            return Ok(None);
+        }
+        let idx = (row - shared.first_row) as usize;
+        let written = shared.written();
+        if idx >= written {
+            return Ok(shared.unwritten.get(idx - written).cloned());
+        }
+
+        let Some((segment_idx, entry)) = shared.locate(idx) else {
+            return Ok(None);
+        };
+        let file = &mut shared.segments[segment_idx].file;
+        let mut sealed = vec![0u8; entry.len as usize];
+        file.seek(SeekFrom::Start(entry.offset))?;
+        file.read_exact(&mut sealed)?;
+        let line = self
+            .sealer
+            .open(entry.nonce, &sealed)
+            .map_err(|err| anyhow::anyhow!("spilled row {row} failed to decrypt: {err:#}"))?;
+        Ok(Some(line))
+    }
+
+    /// Returns the spilled text with wrapped lines joined back into
+    /// their logical lines
+    pub fn text(&self) -> anyhow::Result<String> {
+        let mut result = String::new();
+        for row in self.rows() {
+            if let Some(line) = self.line(row)? {
+                let wrapped = line.last_cell_was_wrapped();
+                let text = line.as_str();
+                if wrapped {
+                    result.push_str(&text);
+                } else {
+                    result.push_str(text.trim_end());
+                    result.push('\n');
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Discards all spilled lines
+    pub fn clear(&self) {
+        self.shared.lock().unwrap().clear();
+    }
+}
+
+#[cfg(all(test, feature = "spill"))]
+mod test {
+    use super::sealer::{KEY_LEN, TAG_LEN};
+    use super::*;
+    use wezterm_cell::{CellAttributes, Intensity};
+    use wezterm_surface::SequenceNo;
+
+    fn line(text: &str, wrapped: bool) -> Line {
+        let mut line = Line::from_text(text, &Default::default(), SequenceNo::MAX, None);
+        line.set_last_cell_was_wrapped(wrapped, SequenceNo::MAX);
+        line
+    }
+
+    fn spill_with_key() -> ScrollbackSpill {
+        ScrollbackSpill::with_sealer(Sealer::with_key([7u8; KEY_LEN])).unwrap()
+    }
+
+    fn flush(spill: &ScrollbackSpill) {
+        write_batches(&spill.shared, &spill.sealer, true);
+    }
+
+    fn raw_contents(spill: &ScrollbackSpill) -> Vec<u8> {
+        let mut shared = spill.shared.lock().unwrap();
+        let mut raw = vec![];
+        for segment in shared.segments.iter_mut() {
+            segment.file.seek(SeekFrom::Start(0)).unwrap();
+            segment.file.read_to_end(&mut raw).unwrap();
+        }
+        raw
+    }
+
+    #[test]
+    fn round_trip() {
+        let spill = ScrollbackSpill::new().unwrap();
+        let mut bold = CellAttributes::default();
+        bold.set_intensity(Intensity::Bold);
+        let bold_line = Line::from_text("second line", &bold, SequenceNo::MAX, None);
+
+        spill.push(10, &line("hello ", true), usize::MAX);
+        spill.push(11, &line("world", false), usize::MAX);
+        // Not written yet, but can still be read back
+        assert_eq!(spill.line(11).unwrap().unwrap().as_str(), "world");
+
+        flush(&spill);
+        spill.push(12, &bold_line, usize::MAX);
+        flush(&spill);
+
+        assert_eq!(spill.rows(), 10..13);
+        assert_eq!(spill.line(9).unwrap(), None);
+        assert_eq!(spill.line(13).unwrap(), None);
+        assert_eq!(spill.is_wrapped(10), Some(true));
+        assert_eq!(spill.line(11).unwrap().unwrap().as_str(), "world");
+        let read_back = spill.line(12).unwrap().unwrap();
+        assert_eq!(read_back.as_str(), "second line");
+        assert_eq!(
+            read_back.get_cell(0).unwrap().attrs().intensity(),
+            Intensity::Bold
+        );
+        assert_eq!(spill.text().unwrap(), "hello world\nsecond line\n");
+
+        spill.clear();
+        assert!(spill.rows().is_empty());
+    }
+
+    #[test]
+    fn non_contiguous_rows_restart_the_spill() {
+        let spill = ScrollbackSpill::new().unwrap();
+        spill.push(0, &line("first", false), usize::MAX);
+        spill.push(5, &line("later", false), usize::MAX);
+
+        assert_eq!(spill.rows(), 5..6);
+        assert_eq!(spill.text().unwrap(), "later\n");
+    }
+
+    #[test]
+    fn file_contents_are_not_plain_text() {
+        let spill = spill_with_key();
+        let text = "a secret that should not hit the disk as plain text";
+        spill.push(0, &line(text, false), usize::MAX);
+        flush(&spill);
+
+        let raw = raw_contents(&spill);
+        assert!(raw.len() > text.len() + TAG_LEN);
+        assert!(!raw
+            .windows(text.len())
+            .any(|window| window == text.as_bytes()));
+    }
+
+    #[test]
+    fn nonces_are_not_reused_after_clear() {
+        let spill = spill_with_key();
+        spill.push(0, &line("same text", false), usize::MAX);
+        flush(&spill);
+        let before = raw_contents(&spill);
+
+        spill.clear();
+        spill.push(0, &line("same text", false), usize::MAX);
+        flush(&spill);
+        assert_ne!(raw_contents(&spill), before);
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let spill = ScrollbackSpill::new().unwrap();
+        spill.push(0, &line("hello", false), usize::MAX);
+        flush(&spill);
+
+        {
+            let mut shared = spill.shared.lock().unwrap();
+            let file = &mut shared.segments[0].file;
+            file.seek(SeekFrom::Start(0)).unwrap();
+            let mut byte = [0u8; 1];
+            file.read_exact(&mut byte).unwrap();
+            file.seek(SeekFrom::Start(0)).unwrap();
+            file.write_all(&[byte[0] ^ 1]).unwrap();
+        }
+
+        assert!(spill.line(0).is_err());
+    }
+
+    #[test]
+    fn oldest_lines_are_discarded_beyond_the_byte_limit() {
+        let spill = ScrollbackSpill::new().unwrap();
+        for row in 0..20 {
+            spill.push(row, &line(&format!("line {row}"), false), 4096);
+            flush(&spill);
+        }
+        assert_eq!(spill.rows().end, 20);
+
+        // Room for a handful of lines in each segment
+        let line_len = spill.sealer.seal(0, &line("line 99", false)).unwrap().len() as u64;
+        let max_bytes = line_len * 4 * SEGMENTS;
+        for row in 20..100 {
+            spill.push(
+                row,
+                &line(&format!("line {row}"), false),
+                max_bytes as usize,
+            );
+            flush(&spill);
+        }
+        let rows = spill.rows();
+        assert_eq!(rows.end, 100);
+        assert!(rows.start > 0);
+        assert!(spill.shared.lock().unwrap().bytes() <= max_bytes);
+        assert_eq!(
+            spill.line(rows.start).unwrap().unwrap().as_str(),
+            format!("line {}", rows.start)
+        );
+    }
+
+    #[test]
+    fn full_batches_are_written_in_the_background() {
+        let spill = ScrollbackSpill::new().unwrap();
+        for row in 0..BATCH_LINES as StableRowIndex {
+            spill.push(row, &line(&format!("line {row}"), false), usize::MAX);
+        }
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !spill.shared.lock().unwrap().unwritten.is_empty() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "batch was not written"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(spill.shared.lock().unwrap().written(), BATCH_LINES);
+        assert_eq!(spill.line(3).unwrap().unwrap().as_str(), "line 3");
+    }
+}
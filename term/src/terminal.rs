@@ -71,6 +71,20 @@ pub enum Alert {
     OutputSinceFocusLost,
     /// A change to the progress bar state
     Progress(Progress),
+    /// The tab color was set, or reset when None, using the iTerm2
+    /// `OSC 6 ; 1 ; bg` sequence
+    TabColorChanged(Option<termwiz::color::RgbColor>),
+    /// A command marked up by shell integration (OSC 133) has finished
+    CommandFinished {
+        /// The command line, as it was entered at the prompt
+        command: String,
+        /// The exit status, if the shell reported one
+        exit_status: Option<i32>,
+        /// When the command started running
+        started: std::time::SystemTime,
+        /// How long the command ran for
+        duration: std::time::Duration,
+    },
 }
 
 pub trait AlertHandler: Send + Sync {
@@ -168,7 +182,8 @@ impl Terminal {
 
             let mut performer = Performer::new(&mut self.state);
 
-            self.parser.parse(bytes, |action| performer.perform(action));
+            self.parser
+                .parse_coalesced(bytes, |action| performer.perform(action));
         }
         self.trigger_unseen_output_notif();
     }
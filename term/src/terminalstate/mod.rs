@@ -3,7 +3,7 @@
 #![allow(clippy::range_plus_one)]
 use super::*;
 use crate::color::{ColorPalette, RgbColor};
-use crate::config::{BidiMode, NewlineCanon};
+use crate::config::{BidiMode, CursorStyleEscapes, NewlineCanon};
 use log::debug;
 use num_traits::ToPrimitive;
 use std::collections::HashMap;
@@ -12,7 +12,7 @@ use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use terminfo::{Database, Value};
 use termwiz::input::KeyboardEncoding;
 use url::Url;
@@ -44,6 +44,9 @@ lazy_static::lazy_static! {
     };
 }
 
+/// xterm limits the title stack to this many entries
+const MAX_TITLE_STACK_DEPTH: usize = 10;
+
 const PTY_ERROR_LOG_THROTTLE_MS: u64 = 5_000;
 static PTY_WRITE_ALL_LAST_LOG_MS: AtomicU64 = AtomicU64::new(0);
 static PTY_WRITE_ALL_SUPPRESSED: AtomicU64 = AtomicU64::new(0);
@@ -324,6 +327,10 @@ pub struct TerminalState {
 
     clear_semantic_attribute_on_newline: bool,
 
+    /// The command line that the shell marked as about to run with
+    /// OSC 133;C, reported via `Alert::CommandFinished` when it ends
+    pending_command: Option<PendingCommand>,
+
     /// If true, writing a character inserts a new cell
     insert: bool,
 
@@ -374,6 +381,8 @@ pub struct TerminalState {
     /// Movement events enabled
     any_event_mouse: bool,
     focus_tracking: bool,
+    /// DECSET 2048: report size changes in-band
+    in_band_resize: bool,
     /// X10 (legacy), SGR, and SGR-Pixels style mouse tracking and
     /// reporting is enabled
     mouse_encoding: MouseEncoding,
@@ -402,7 +411,13 @@ pub struct TerminalState {
     title: String,
     /// The icon title string (OSC 1)
     icon_title: Option<String>,
+    /// Titles saved by XTWINOPS 22, restored by XTWINOPS 23
+    title_stack: Vec<String>,
+    icon_title_stack: Vec<Option<String>>,
     progress: Progress,
+    /// The red, green and blue components of the tab color set
+    /// via OSC 6, which arrive as separate sequences
+    tab_color: Option<[u8; 3]>,
 
     palette: Option<ColorPalette>,
 
@@ -467,6 +482,16 @@ struct UnicodeVersionStackEntry {
     label: Option<String>,
 }
 
+#[derive(Debug)]
+struct PendingCommand {
+    command: String,
+    started: SystemTime,
+    start: Instant,
+    /// The line of the primary screen holding the prompt at which the
+    /// command was entered; its outcome is recorded there
+    prompt_row: Option<StableRowIndex>,
+}
+
 fn default_color_map() -> HashMap<u16, RgbColor> {
     let mut color_map = HashMap::new();
     // Match colors to the VT340 color table:
@@ -589,6 +614,7 @@ impl TerminalState {
             left_and_right_margin_mode: false,
             wrap_next: false,
             clear_semantic_attribute_on_newline: false,
+            pending_command: None,
             // We default auto wrap to true even though the default for
             // a dec terminal is false, because it is more useful this way.
             dec_auto_wrap: true,
@@ -605,6 +631,7 @@ impl TerminalState {
             application_keypad: false,
             bracketed_paste: false,
             focus_tracking: false,
+            in_band_resize: false,
             mouse_encoding: MouseEncoding::X10,
             keyboard_encoding: KeyboardEncoding::Xterm,
             sixel_scrolls_right: false,
@@ -622,6 +649,8 @@ impl TerminalState {
             tabs: TabStop::new(size.cols, 8),
             title: "kaku".to_string(),
             icon_title: None,
+            title_stack: vec![],
+            icon_title_stack: vec![],
             palette: None,
             pixel_height: size.pixel_height,
             pixel_width: size.pixel_width,
@@ -649,6 +678,7 @@ impl TerminalState {
             bidi_enabled: None,
             bidi_hint: None,
             progress: Progress::default(),
+            tab_color: None,
             primary_peek: false,
         }
     }
@@ -761,6 +791,12 @@ impl TerminalState {
         }
     }
 
+    /// Returns the text of the primary screen lines that were evicted
+    /// from the scrollback and spilled to disk, oldest first.
+    pub fn spilled_scrollback_text(&self) -> anyhow::Result<String> {
+        self.screen.primary_screen().spilled_scrollback_text()
+    }
+
     /// Returns a reference to the active screen (either the primary or
     /// the alternate screen).
     pub fn screen(&self) -> &Screen {
@@ -813,6 +849,13 @@ impl TerminalState {
         self.screen_mut().erase_scrollback();
     }
 
+    /// Overrides `scrollback_lines` for the primary screen, or restores
+    /// the configured value when `limit` is None.
+    pub fn set_scrollback_limit(&mut self, limit: Option<usize>) {
+        self.increment_seqno();
+        self.screen.primary_screen_mut().set_scrollback_limit(limit);
+    }
+
     /// Returns true if the associated application has enabled any of the
     /// supported mouse reporting modes.
     /// This is useful for the hosting GUI application to decide how best
@@ -912,7 +955,7 @@ impl TerminalState {
                 });
             }
         }
-        if self.focus_tracking {
+        if self.focus_tracking && self.config.enable_focus_reporting() {
             self.write_fmt_to_pty(
                 "focus_changed: focus-tracking response",
                 format_args!("{}{}", CSI, if focused { "I" } else { "O" }),
@@ -1018,6 +1061,10 @@ impl TerminalState {
         self.dpi = size.dpi;
         self.tabs.resize(size.cols);
 
+        if self.in_band_resize {
+            self.in_band_resize_report();
+        }
+
         if self.screen.alt_screen_is_active {
             self.set_cursor_pos(
                 &Position::Absolute(adjusted_cursor_alt.x as i64),
@@ -1044,6 +1091,53 @@ impl TerminalState {
         }
     }
 
+    fn push_window_title(&mut self) {
+        if self.title_stack.len() >= MAX_TITLE_STACK_DEPTH {
+            self.title_stack.remove(0);
+        }
+        self.title_stack.push(self.title.clone());
+    }
+
+    fn push_icon_title(&mut self) {
+        if self.icon_title_stack.len() >= MAX_TITLE_STACK_DEPTH {
+            self.icon_title_stack.remove(0);
+        }
+        self.icon_title_stack.push(self.icon_title.clone());
+    }
+
+    /// Restores the most recently pushed title.
+    /// Popping an empty stack leaves the title unchanged, as in xterm.
+    fn pop_window_title(&mut self) {
+        if let Some(title) = self.title_stack.pop() {
+            self.title = title.clone();
+            if let Some(handler) = self.alert_handler.as_mut() {
+                handler.alert(Alert::WindowTitleChanged(title));
+            }
+        }
+    }
+
+    fn pop_icon_title(&mut self) {
+        if let Some(title) = self.icon_title_stack.pop() {
+            self.icon_title = title.clone();
+            if let Some(handler) = self.alert_handler.as_mut() {
+                handler.alert(Alert::IconTitleChanged(title));
+            }
+        }
+    }
+
+    /// Sends the in-band resize notification for the current size
+    fn in_band_resize_report(&mut self) {
+        let size = self.get_size();
+        self.write_fmt_to_pty(
+            "in-band resize notification",
+            format_args!(
+                "{}48;{};{};{};{}t",
+                CSI, size.rows, size.cols, size.pixel_height, size.pixel_width
+            ),
+        );
+        self.flush_pty("in-band resize notification");
+    }
+
     pub fn get_size(&self) -> TerminalSize {
         let screen = self.screen();
         TerminalSize {
@@ -1078,7 +1172,7 @@ impl TerminalState {
             x: self.cursor.x,
             y: self.cursor.y,
             shape: self.cursor.shape,
-            visibility: if self.cursor_visible {
+            visibility: if self.cursor_visible || !self.config.allow_cursor_hiding() {
                 CursorVisibility::Visible
             } else {
                 CursorVisibility::Hidden
@@ -1208,6 +1302,116 @@ impl TerminalState {
         )
     }
 
+    /// Called when the shell marks the start of command output
+    /// (OSC 133;C): remembers the command line so that it can be
+    /// reported once the shell tells us that it has finished.
+    fn start_pending_command(&mut self) {
+        let (command, prompt_phys) = self.input_text_before_cursor();
+        let prompt_row = match prompt_phys {
+            Some(phys) if !self.screen.is_alt_screen_active() => {
+                Some(self.screen().phys_to_stable_row_index(phys))
+            }
+            _ => None,
+        };
+        self.pending_command = if command.is_empty() {
+            None
+        } else {
+            Some(PendingCommand {
+                command,
+                started: SystemTime::now(),
+                start: Instant::now(),
+                prompt_row,
+            })
+        };
+    }
+
+    /// Called when the shell reports the exit status of the command
+    /// (OSC 133;D), or starts a new prompt without having done so.
+    fn finish_pending_command(&mut self, exit_status: Option<i32>) {
+        let Some(pending) = self.pending_command.take() else {
+            return;
+        };
+        if let (Some(status), Some(row)) = (exit_status, pending.prompt_row) {
+            self.mark_command_status(row, status == 0);
+        }
+        if let Some(handler) = self.alert_handler.as_mut() {
+            handler.alert(Alert::CommandFinished {
+                command: pending.command,
+                exit_status,
+                started: pending.started,
+                duration: pending.start.elapsed(),
+            });
+        }
+    }
+
+    /// Records the outcome of a command on the line of the primary
+    /// screen holding its prompt, so that it can be shown alongside it.
+    fn mark_command_status(&mut self, prompt_row: StableRowIndex, succeeded: bool) {
+        let seqno = self.seqno;
+        let screen = &mut self.screen.screen;
+        if let Some(phys) = screen.stable_row_to_phys(prompt_row) {
+            screen
+                .line_mut(phys)
+                .set_command_succeeded(Some(succeeded), seqno);
+        }
+    }
+
+    /// Collects the text of the Input zone that ends on the cursor line
+    /// or just above it, which is the command line the user just entered.
+    /// Also returns the physical row on which that command line starts.
+    fn input_text_before_cursor(&mut self) -> (String, Option<PhysRowIndex>) {
+        /// Don't go looking for input further back than this
+        const MAX_INPUT_LINES: usize = 64;
+
+        let cursor_y = self.cursor.y;
+        let screen = self.screen_mut();
+        let cursor_phys = screen.phys_row(cursor_y);
+        let first_phys = cursor_phys.saturating_sub(MAX_INPUT_LINES);
+
+        let mut lines: Vec<(String, bool)> = vec![];
+        let mut start_phys = None;
+        for idx in (first_phys..=cursor_phys).rev() {
+            let line = screen.line_mut(idx);
+            let wrapped = line.last_cell_was_wrapped();
+            let zones = line.semantic_zone_ranges().to_vec();
+            let mut text = String::new();
+            let mut saw_prompt = false;
+            for zone in &zones {
+                // Zone ranges are inclusive of their end
+                let range = zone.range.start as usize..zone.range.end as usize + 1;
+                match zone.semantic_type {
+                    SemanticType::Input => text.push_str(&line.columns_as_str(range)),
+                    SemanticType::Prompt => saw_prompt = true,
+                    SemanticType::Output => {}
+                }
+            }
+            if text.is_empty() && !lines.is_empty() {
+                // We've walked off the top of the input zone
+                break;
+            }
+            if !text.is_empty() {
+                lines.push((text, wrapped));
+                start_phys = Some(idx);
+            }
+            if saw_prompt {
+                start_phys = Some(idx);
+                // The prompt is where the command line begins
+                break;
+            }
+        }
+
+        let mut command = String::new();
+        for (text, wrapped) in lines.into_iter().rev() {
+            if wrapped {
+                command.push_str(&text);
+            } else {
+                command.push_str(text.trim_end());
+                command.push('\n');
+            }
+        }
+        (command.trim().to_string(), start_phys)
+    }
+
     /// Defined by FinalTermSemanticPrompt; a fresh-line is a NOP if the
     /// cursor is already at the left margin, otherwise it is the same as
     /// a new line.
@@ -1419,11 +1623,20 @@ impl TerminalState {
                 self.g1_charset = CharSet::Ascii;
             }
             Device::RequestPrimaryDeviceAttributes => {
+                if let Some(params) = self.config.primary_device_attributes() {
+                    self.write_fmt_to_pty(
+                        "primary device attributes",
+                        format_args!("\x1b[?{params}c"),
+                    );
+                    self.flush_pty("primary device attributes");
+                    return;
+                }
                 let mut ident = "\x1b[?65".to_string(); // Vt500
                 ident.push_str(";4"); // Sixel graphics
                 ident.push_str(";6"); // Selective erase
                 ident.push_str(";18"); // windowing extensions
                 ident.push_str(";22"); // ANSI color, vt525
+                ident.push_str(";28"); // Rectangular editing
                 ident.push_str(";52"); // Clipboard access
                 ident.push('c');
 
@@ -1442,7 +1655,13 @@ impl TerminalState {
                 // pv >= 95 < 277 -> ttymouse=xterm2
                 // pv >= 277 -> ttymouse=sgr
                 // pv >= 279 - xterm will probe for additional device settings.
-                self.write_all_to_pty("secondary device attributes", b"\x1b[>1;277;0c");
+                match self.config.secondary_device_attributes() {
+                    Some(params) => self.write_fmt_to_pty(
+                        "secondary device attributes",
+                        format_args!("\x1b[>{params}c"),
+                    ),
+                    None => self.write_all_to_pty("secondary device attributes", b"\x1b[>1;277;0c"),
+                }
                 self.flush_pty("secondary device attributes");
             }
             Device::RequestTertiaryDeviceAttributes => {
@@ -1957,7 +2176,29 @@ impl TerminalState {
                 self.last_mouse_move.take();
             }
             Mode::QueryDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::FocusTracking)) => {
-                self.decqrm_response(mode, true, self.focus_tracking);
+                let recognized = self.config.enable_focus_reporting();
+                self.decqrm_response(mode, recognized, self.focus_tracking);
+            }
+
+            Mode::SetDecPrivateMode(DecPrivateMode::Code(
+                DecPrivateModeCode::InBandResizeNotifications,
+            )) => {
+                if self.config.enable_in_band_resize() {
+                    self.in_band_resize = true;
+                    // The application is told the current size straight away
+                    self.in_band_resize_report();
+                }
+            }
+            Mode::ResetDecPrivateMode(DecPrivateMode::Code(
+                DecPrivateModeCode::InBandResizeNotifications,
+            )) => {
+                self.in_band_resize = false;
+            }
+            Mode::QueryDecPrivateMode(DecPrivateMode::Code(
+                DecPrivateModeCode::InBandResizeNotifications,
+            )) => {
+                let recognized = self.config.enable_in_band_resize();
+                self.decqrm_response(mode, recognized, self.in_band_resize);
             }
 
             Mode::SetDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::SGRMouse)) => {
@@ -2110,9 +2351,12 @@ impl TerminalState {
                 resource: XtermKeyModifierResource::OtherKeys,
                 value,
             } => {
+                let max_level = self.config.max_modify_other_keys_level();
                 self.modify_other_keys = match value {
                     Some(0) => None,
-                    _ => value,
+                    _ if max_level <= 0 => None,
+                    Some(level) => Some(level.min(max_level)),
+                    None => None,
                 };
                 log::debug!("XtermKeyMode OtherKeys -> {:?}", self.modify_other_keys);
             }
@@ -2129,6 +2373,170 @@ impl TerminalState {
         }
     }
 
+    /// Returns the rows and columns addressable by the DEC rectangular
+    /// area operations; the scroll margins in origin mode, otherwise
+    /// the whole page.
+    fn rectangle_extent(&self) -> (Range<VisibleRowIndex>, Range<usize>) {
+        if self.dec_origin_mode {
+            (
+                self.top_and_bottom_margins.clone(),
+                self.left_and_right_margins.clone(),
+            )
+        } else {
+            let screen = self.screen();
+            (
+                0..screen.physical_rows as VisibleRowIndex,
+                0..screen.physical_cols,
+            )
+        }
+    }
+
+    /// Resolves the edges of a DEC rectangular area to the rows and
+    /// columns that it covers, clamped to the addressable extent.
+    /// Returns None if the area is empty.
+    fn rectangle_bounds(
+        &self,
+        top: OneBased,
+        left: OneBased,
+        bottom: OneBased,
+        right: OneBased,
+    ) -> Option<(Range<VisibleRowIndex>, Range<usize>)> {
+        let (rows, cols) = self.rectangle_extent();
+        let row = |v: OneBased| {
+            rows.start
+                .saturating_add(v.as_zero_based() as VisibleRowIndex)
+                .min(rows.end)
+        };
+        let col = |v: OneBased| {
+            cols.start
+                .saturating_add(v.as_zero_based() as usize)
+                .min(cols.end)
+        };
+        let row_range = row(top)..(row(bottom) + 1).min(rows.end);
+        let col_range = col(left)..(col(right) + 1).min(cols.end);
+        if row_range.is_empty() || col_range.is_empty() {
+            None
+        } else {
+            Some((row_range, col_range))
+        }
+    }
+
+    /// Erases the cells in the area to the current background color.
+    /// When `selective` is true, cells protected by DECSCA are kept.
+    fn erase_rectangle(
+        &mut self,
+        rows: Range<VisibleRowIndex>,
+        cols: Range<usize>,
+        selective: bool,
+    ) {
+        let seqno = self.seqno;
+        let blank = Cell::blank_with_attrs(self.pen.clone_sgr_only());
+        let screen = self.screen_mut();
+        for y in rows {
+            let line_idx = screen.phys_row(y);
+            let line = screen.line_mut(line_idx);
+            if selective {
+                for x in cols.clone() {
+                    let protected = line
+                        .get_cell(x)
+                        .map(|cell| cell.attrs().protected())
+                        .unwrap_or(false);
+                    if !protected {
+                        line.set_cell(x, blank.clone(), seqno);
+                    }
+                }
+                line.prune_trailing_blanks(seqno);
+            } else {
+                line.fill_range(cols.clone(), &blank, seqno);
+            }
+        }
+    }
+
+    fn selective_erase_in_line(&mut self, erase: EraseInLine) {
+        let cx = self.cursor.x;
+        let cy = self.cursor.y;
+        let cols = self.screen().physical_cols;
+        let range = match erase {
+            // See the note about wrap_next in the EraseInLine handler
+            EraseInLine::EraseToEndOfLine => cx + if self.wrap_next { 1 } else { 0 }..cols,
+            EraseInLine::EraseToStartOfLine => 0..cx + 1,
+            EraseInLine::EraseLine => 0..cols,
+        };
+        self.erase_rectangle(cy..cy + 1, range.start.min(cols)..range.end.min(cols), true);
+    }
+
+    fn selective_erase_in_display(&mut self, erase: EraseInDisplay) {
+        let cy = self.cursor.y;
+        let rows = self.screen().physical_rows as VisibleRowIndex;
+        let cols = self.screen().physical_cols;
+        let row_range = match erase {
+            EraseInDisplay::EraseToEndOfDisplay => {
+                self.selective_erase_in_line(EraseInLine::EraseToEndOfLine);
+                cy + 1..rows
+            }
+            EraseInDisplay::EraseToStartOfDisplay => {
+                self.selective_erase_in_line(EraseInLine::EraseToStartOfLine);
+                0..cy
+            }
+            EraseInDisplay::EraseDisplay => 0..rows,
+            // There is no protected content in the scrollback to
+            // consider; there is nothing selective to do
+            EraseInDisplay::EraseScrollback => return,
+        };
+        self.erase_rectangle(row_range, 0..cols, true);
+    }
+
+    fn fill_rectangle(&mut self, ch: char, rows: Range<VisibleRowIndex>, cols: Range<usize>) {
+        // Only the single width graphic characters of the GL and GR
+        // sets are permitted
+        if !matches!(ch, ' '..='~' | '\u{a0}'..='\u{ff}') {
+            return;
+        }
+        let seqno = self.seqno;
+        let cell = Cell::new(ch, self.pen.clone());
+        let screen = self.screen_mut();
+        for y in rows {
+            for x in cols.clone() {
+                screen.set_cell(x, y, &cell, seqno);
+            }
+        }
+    }
+
+    /// Copies the cells in the area so that its top left corner is at
+    /// dest_top, dest_left, clipping the copy to the addressable extent.
+    /// The area may overlap its destination.
+    fn copy_rectangle(
+        &mut self,
+        rows: Range<VisibleRowIndex>,
+        cols: Range<usize>,
+        dest_top: OneBased,
+        dest_left: OneBased,
+    ) {
+        let seqno = self.seqno;
+        let (extent_rows, extent_cols) = self.rectangle_extent();
+        let dest_y = extent_rows
+            .start
+            .saturating_add(dest_top.as_zero_based() as VisibleRowIndex);
+        let dest_x = extent_cols
+            .start
+            .saturating_add(dest_left.as_zero_based() as usize);
+
+        let screen = self.screen_mut();
+        let cells: Vec<Vec<Cell>> = rows
+            .map(|y| {
+                cols.clone()
+                    .map(|x| screen.get_cell(x, y).cloned().unwrap_or_else(Cell::blank))
+                    .collect()
+            })
+            .collect();
+
+        for (y, row) in (dest_y..extent_rows.end).zip(cells) {
+            for (x, cell) in (dest_x..extent_cols.end).zip(row) {
+                screen.set_cell(x, y, &cell, seqno);
+            }
+        }
+    }
+
     fn checksum_rectangle(&mut self, left: u32, top: u32, right: u32, bottom: u32) -> u16 {
         let y_origin = if self.dec_origin_mode {
             self.top_and_bottom_margins.start
@@ -2181,6 +2589,12 @@ impl TerminalState {
 
     fn perform_csi_window(&mut self, window: Window) {
         match window {
+            Window::ReportTextAreaSizeCells
+            | Window::ReportScreenSizeCells
+            | Window::ReportCellSizePixels
+            | Window::ReportTextAreaSizePixels
+                if !self.config.enable_window_size_reporting() => {}
+
             Window::ReportTextAreaSizeCells => {
                 let screen = self.screen();
                 let height = Some(screen.physical_rows as i64);
@@ -2194,6 +2608,19 @@ impl TerminalState {
                 self.flush_pty("window report text area size cells");
             }
 
+            Window::ReportScreenSizeCells => {
+                // We don't know the size of the screen; the best we
+                // can offer is the largest the text area currently is.
+                let screen = self.screen();
+                let height = screen.physical_rows;
+                let width = screen.physical_cols;
+                self.write_fmt_to_pty(
+                    "window report screen size cells",
+                    format_args!("{}9;{};{}t", CSI, height, width),
+                );
+                self.flush_pty("window report screen size cells");
+            }
+
             Window::ReportCellSizePixels => {
                 let screen = self.screen();
                 let height = screen.physical_rows;
@@ -2254,17 +2681,68 @@ impl TerminalState {
                 );
                 self.flush_pty("window checksum rectangular area");
             }
+            Window::CopyRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+                dest_top,
+                dest_left,
+                ..
+            } => {
+                // We only have a single page, so the page numbers are moot
+                if let Some((rows, cols)) = self.rectangle_bounds(top, left, bottom, right) {
+                    self.copy_rectangle(rows, cols, dest_top, dest_left);
+                }
+            }
+            Window::FillRectangularArea {
+                ch,
+                top,
+                left,
+                bottom,
+                right,
+            } => {
+                if let Some((rows, cols)) = self.rectangle_bounds(top, left, bottom, right) {
+                    self.fill_rectangle(ch, rows, cols);
+                }
+            }
+            Window::EraseRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+            } => {
+                if let Some((rows, cols)) = self.rectangle_bounds(top, left, bottom, right) {
+                    self.erase_rectangle(rows, cols, false);
+                }
+            }
+            Window::SelectiveEraseRectangularArea {
+                top,
+                left,
+                bottom,
+                right,
+            } => {
+                if let Some((rows, cols)) = self.rectangle_bounds(top, left, bottom, right) {
+                    self.erase_rectangle(rows, cols, true);
+                }
+            }
             Window::ResizeWindowCells { .. } => {
                 // We don't allow the application to change the window size; that's
                 // up to the user!
             }
             Window::Iconify | Window::DeIconify => {}
-            Window::PopIconAndWindowTitle
-            | Window::PopWindowTitle
-            | Window::PopIconTitle
-            | Window::PushIconAndWindowTitle
-            | Window::PushIconTitle
-            | Window::PushWindowTitle => {}
+            Window::PushIconAndWindowTitle => {
+                self.push_window_title();
+                self.push_icon_title();
+            }
+            Window::PushWindowTitle => self.push_window_title(),
+            Window::PushIconTitle => self.push_icon_title(),
+            Window::PopIconAndWindowTitle => {
+                self.pop_window_title();
+                self.pop_icon_title();
+            }
+            Window::PopWindowTitle => self.pop_window_title(),
+            Window::PopIconTitle => self.pop_icon_title(),
 
             _ => {
                 if self.config.log_unknown_escape_sequences() {
@@ -2428,6 +2906,8 @@ impl TerminalState {
             Edit::ScrollDown(n) => self.scroll_down(n as usize),
             Edit::ScrollUp(n) => self.scroll_up(n as usize),
             Edit::EraseInDisplay(erase) => self.erase_in_display(erase),
+            Edit::SelectiveEraseInDisplay(erase) => self.selective_erase_in_display(erase),
+            Edit::SelectiveEraseInLine(erase) => self.selective_erase_in_line(erase),
             Edit::Repeat(n) => {
                 let mut y = self.cursor.y;
                 let mut x = self.cursor.x;
@@ -2753,14 +3233,24 @@ impl TerminalState {
             }
             Cursor::RestoreCursor => self.dec_restore_cursor(),
             Cursor::CursorStyle(style) => {
+                let policy = self.config.cursor_style_escapes();
+                if policy == CursorStyleEscapes::Ignore {
+                    log::debug!("Ignoring cursor style {:?} per config", style);
+                    return;
+                }
+                let steady = policy == CursorStyleEscapes::SteadyOnly;
                 self.cursor.shape = match style {
                     CursorStyle::Default => CursorShape::Default,
-                    CursorStyle::BlinkingBlock => CursorShape::BlinkingBlock,
-                    CursorStyle::SteadyBlock => CursorShape::SteadyBlock,
-                    CursorStyle::BlinkingUnderline => CursorShape::BlinkingUnderline,
-                    CursorStyle::SteadyUnderline => CursorShape::SteadyUnderline,
-                    CursorStyle::BlinkingBar => CursorShape::BlinkingBar,
-                    CursorStyle::SteadyBar => CursorShape::SteadyBar,
+                    CursorStyle::BlinkingBlock if !steady => CursorShape::BlinkingBlock,
+                    CursorStyle::BlinkingBlock | CursorStyle::SteadyBlock => {
+                        CursorShape::SteadyBlock
+                    }
+                    CursorStyle::BlinkingUnderline if !steady => CursorShape::BlinkingUnderline,
+                    CursorStyle::BlinkingUnderline | CursorStyle::SteadyUnderline => {
+                        CursorShape::SteadyUnderline
+                    }
+                    CursorStyle::BlinkingBar if !steady => CursorShape::BlinkingBar,
+                    CursorStyle::BlinkingBar | CursorStyle::SteadyBar => CursorShape::SteadyBar,
                 };
                 log::debug!("Cursor shape is now {:?}", self.cursor.shape);
             }
@@ -2825,9 +3315,12 @@ impl TerminalState {
             Sgr::Reset => {
                 let link = self.pen.hyperlink().map(Arc::clone);
                 let semantic_type = self.pen.semantic_type();
+                // DECSCA is independent of the graphic rendition
+                let protected = self.pen.protected();
                 self.pen = CellAttributes::default();
                 self.pen.set_hyperlink(link);
                 self.pen.set_semantic_type(semantic_type);
+                self.pen.set_protected(protected);
             }
             Sgr::Intensity(intensity) => {
                 self.pen.set_intensity(intensity);
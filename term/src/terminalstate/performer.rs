@@ -29,6 +29,7 @@ use wezterm_escape_parser::osc::{
 use wezterm_escape_parser::{
     Action, ControlCode, DeviceControlMode, Esc, EscCode, OperatingSystemCommand, CSI,
 };
+use wezterm_surface::CursorShape;
 
 /// A helper struct for implementing `vtparse::VTActor` while compartmentalizing
 /// the terminal state and the embedding/host terminal interface
@@ -341,11 +342,7 @@ impl<'a> Performer<'a> {
         }
         match action {
             Action::Print(c) => self.print(c),
-            Action::PrintString(s) => {
-                for c in s.chars() {
-                    self.print(c)
-                }
-            }
+            Action::PrintString(s) => self.print_str(&s),
             Action::Control(code) => self.control(code),
             Action::DeviceControl(ctrl) => self.device_control(ctrl),
             Action::OperatingSystemCommand(osc) => self.osc_dispatch(*osc),
@@ -399,6 +396,23 @@ impl<'a> Performer<'a> {
                                 );
                                 self.flush_pty("decrqss decstbm response");
                             }
+                            &[b' ', b'q'] => {
+                                // DECSCUSR - cursor style
+                                let style = match self.cursor.shape {
+                                    CursorShape::Default => 0,
+                                    CursorShape::BlinkingBlock => 1,
+                                    CursorShape::SteadyBlock => 2,
+                                    CursorShape::BlinkingUnderline => 3,
+                                    CursorShape::SteadyUnderline => 4,
+                                    CursorShape::BlinkingBar => 5,
+                                    CursorShape::SteadyBar => 6,
+                                };
+                                self.write_fmt_to_pty(
+                                    "decrqss decscusr response",
+                                    format_args!("{}1$r{} q{}", DCS, style, ST),
+                                );
+                                self.flush_pty("decrqss decscusr response");
+                            }
                             &[b's'] => {
                                 // DECSLRM - left and right margins
                                 let margins = self.left_and_right_margins.clone();
@@ -455,6 +469,14 @@ impl<'a> Performer<'a> {
         }
     }
 
+    fn print_str(&mut self, s: &str) {
+        if let Some(title) = self.accumulating_title.as_mut() {
+            title.push_str(s);
+        } else {
+            self.print.push_str(s);
+        }
+    }
+
     fn control(&mut self, control: ControlCode) {
         let seqno = self.seqno;
         self.pop_tmux_title_state();
@@ -603,6 +625,9 @@ impl<'a> Performer<'a> {
                     .bidi_hint
                     .replace(ParagraphDirectionHint::RightToLeft);
             }
+            CSI::SelectCharacterProtection(protect) => {
+                self.state.pen.set_protected(protect);
+            }
             CSI::Keyboard(Keyboard::SetKittyState { flags, mode }) => {
                 if self.config.enable_kitty_keyboard() {
                     let current_flags = match self.screen().keyboard_stack.last() {
@@ -613,7 +638,7 @@ impl<'a> Performer<'a> {
                         KittyKeyboardMode::AssignAll => flags,
                         KittyKeyboardMode::SetSpecified => current_flags | flags,
                         KittyKeyboardMode::ClearSpecified => current_flags - flags,
-                    };
+                    } & self.config.kitty_keyboard_flags();
                     self.screen_mut().keyboard_stack.pop();
                     self.screen_mut()
                         .keyboard_stack
@@ -630,7 +655,7 @@ impl<'a> Performer<'a> {
                         KittyKeyboardMode::AssignAll => flags,
                         KittyKeyboardMode::SetSpecified => current_flags | flags,
                         KittyKeyboardMode::ClearSpecified => current_flags - flags,
-                    };
+                    } & self.config.kitty_keyboard_flags();
                     let screen = self.screen_mut();
                     screen.keyboard_stack.push(KeyboardEncoding::Kitty(flags));
                     if screen.keyboard_stack.len() > 128 {
@@ -781,6 +806,9 @@ impl<'a> Performer<'a> {
                 self.application_keypad = false;
                 self.bracketed_paste = false;
                 self.focus_tracking = false;
+                self.in_band_resize = false;
+                self.title_stack.clear();
+                self.icon_title_stack.clear();
                 self.mouse_tracking = false;
                 self.alternate_scroll = false;
                 self.mouse_encoding = MouseEncoding::X10;
@@ -821,6 +849,40 @@ impl<'a> Performer<'a> {
         }
     }
 
+    /// Parses the iTerm2 tab color sequences, which set one component
+    /// at a time, `OSC 6 ; 1 ; bg ; red|green|blue ; brightness ; N ST`,
+    /// or reset the color, `OSC 6 ; 1 ; bg ; * ; default ST`.
+    /// Returns the tab color that results from applying it to `current`,
+    /// or None if `osc` is not one of them.
+    fn parse_tab_color(osc: &[Vec<u8>], current: Option<[u8; 3]>) -> Option<Option<[u8; 3]>> {
+        let fields: Vec<&[u8]> = osc.iter().map(|f| f.as_slice()).collect();
+        match fields.as_slice() {
+            [b"6", b"1", b"bg", _, b"default"] => Some(None),
+            [b"6", b"1", b"bg", component, b"brightness", value] => {
+                let idx = match *component {
+                    b"red" => 0,
+                    b"green" => 1,
+                    b"blue" => 2,
+                    _ => return None,
+                };
+                let value: u8 = std::str::from_utf8(value).ok()?.parse().ok()?;
+                let mut color = current.unwrap_or_default();
+                color[idx] = value;
+                Some(Some(color))
+            }
+            _ => None,
+        }
+    }
+
+    fn set_tab_color(&mut self, color: Option<[u8; 3]>) {
+        self.tab_color = color;
+        if let Some(handler) = self.alert_handler.as_mut() {
+            handler.alert(Alert::TabColorChanged(
+                color.map(|[r, g, b]| termwiz::color::RgbColor::new_8bpc(r, g, b)),
+            ));
+        }
+    }
+
     fn osc_dispatch(&mut self, osc: OperatingSystemCommand) {
         self.pop_tmux_title_state();
         self.flush_print();
@@ -857,7 +919,9 @@ impl<'a> Performer<'a> {
                 self.set_hyperlink(link);
             }
             OperatingSystemCommand::Unspecified(unspec) => {
-                if self.config.log_unknown_escape_sequences() {
+                if let Some(color) = Self::parse_tab_color(&unspec, self.tab_color) {
+                    self.set_tab_color(color);
+                } else if self.config.log_unknown_escape_sequences() {
                     let mut output = String::new();
                     write!(&mut output, "Unhandled OSC ").expect("writing to String cannot fail");
 
@@ -956,6 +1020,7 @@ impl<'a> Performer<'a> {
             OperatingSystemCommand::FinalTermSemanticPrompt(
                 FinalTermSemanticPrompt::FreshLineAndStartPrompt { .. },
             ) => {
+                self.finish_pending_command(None);
                 self.fresh_line();
                 self.pen.set_semantic_type(SemanticType::Prompt);
             }
@@ -967,6 +1032,7 @@ impl<'a> Performer<'a> {
             OperatingSystemCommand::FinalTermSemanticPrompt(
                 FinalTermSemanticPrompt::MarkEndOfCommandWithFreshLine { .. },
             ) => {
+                self.finish_pending_command(None);
                 self.fresh_line();
                 self.pen.set_semantic_type(SemanticType::Prompt);
             }
@@ -984,12 +1050,15 @@ impl<'a> Performer<'a> {
             OperatingSystemCommand::FinalTermSemanticPrompt(
                 FinalTermSemanticPrompt::MarkEndOfInputAndStartOfOutput { .. },
             ) => {
+                self.start_pending_command();
                 self.pen.set_semantic_type(SemanticType::Output);
             }
 
             OperatingSystemCommand::FinalTermSemanticPrompt(
-                FinalTermSemanticPrompt::CommandStatus { .. },
-            ) => {}
+                FinalTermSemanticPrompt::CommandStatus { status, .. },
+            ) => {
+                self.finish_pending_command(Some(status));
+            }
 
             OperatingSystemCommand::SystemNotification(message) => {
                 if let Some(handler) = self.alert_handler.as_mut() {
@@ -1037,6 +1106,7 @@ impl<'a> Performer<'a> {
             }
             OperatingSystemCommand::ChangeColorNumber(specs) => {
                 log::trace!("ChangeColorNumber: {:?}", specs);
+                let mut changed = false;
                 for pair in specs {
                     match pair.color {
                         ColorOrQuery::Query => {
@@ -1055,11 +1125,16 @@ impl<'a> Performer<'a> {
                         }
                         ColorOrQuery::Color(c) => {
                             self.palette_mut().colors.0[pair.palette_index as usize] = c;
+                            changed = true;
                         }
                     }
                 }
-                self.implicit_palette_reset_if_same_as_configured();
-                self.palette_did_change();
+                // A pure query (eg: a theme detection script probing the
+                // palette) leaves the screen as it was; don't force a redraw.
+                if changed {
+                    self.implicit_palette_reset_if_same_as_configured();
+                    self.palette_did_change();
+                }
             }
 
             OperatingSystemCommand::ResetColors(colors) => {
@@ -1087,6 +1162,7 @@ impl<'a> Performer<'a> {
                 log::trace!("ChangeDynamicColors: {:?} {:?}", first_color, colors);
                 use wezterm_escape_parser::osc::DynamicColorNumber;
                 let mut idx: u8 = first_color as u8;
+                let mut changed = false;
                 for color in colors {
                     let which_color: Option<DynamicColorNumber> = FromPrimitive::from_u8(idx);
                     log::trace!("ChangeDynamicColors item: {:?}", which_color);
@@ -1106,7 +1182,10 @@ impl<'a> Performer<'a> {
                                         );
                                         self.flush_pty("osc change dynamic color query response");
                                     }
-                                    ColorOrQuery::Color(c) => self.palette_mut().$name = c.into(),
+                                    ColorOrQuery::Color(c) => {
+                                        self.palette_mut().$name = c.into();
+                                        changed = true;
+                                    }
                                 }
                             };
                         }
@@ -1115,6 +1194,24 @@ impl<'a> Performer<'a> {
                             DynamicColorNumber::TextBackgroundColor => set_or_query!(background),
                             DynamicColorNumber::TextCursorColor => {
                                 if let ColorOrQuery::Color(c) = color {
+                                    if !self.config.allow_cursor_color_escapes() {
+                                        log::debug!("Ignoring cursor color {:?} per config", c);
+                                        idx += 1;
+                                        continue;
+                                    }
+                                    let min_contrast = self.config.cursor_color_min_contrast();
+                                    let contrast = c.contrast_ratio(&self.palette().background);
+                                    if contrast < min_contrast {
+                                        log::debug!(
+                                            "Ignoring cursor color {:?} with contrast \
+                                             {} below cursor_color_min_contrast {}",
+                                            c,
+                                            contrast,
+                                            min_contrast
+                                        );
+                                        idx += 1;
+                                        continue;
+                                    }
                                     // We set the border to the background color; we don't
                                     // have an escape that sets that independently, and this
                                     // way just looks better.
@@ -1137,8 +1234,10 @@ impl<'a> Performer<'a> {
                     }
                     idx += 1;
                 }
-                self.implicit_palette_reset_if_same_as_configured();
-                self.palette_did_change();
+                if changed {
+                    self.implicit_palette_reset_if_same_as_configured();
+                    self.palette_did_change();
+                }
             }
 
             OperatingSystemCommand::ResetDynamicColor(color) => {
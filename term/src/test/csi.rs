@@ -401,3 +401,59 @@ fn test_ed_erase_scrollback() {
     term.print("b");
     assert_all_contents(&term, file!(), line!(), &["111", "222", "ab"]);
 }
+
+#[test]
+fn test_decfra() {
+    let mut term = TestTerm::new(3, 4, 0);
+    term.print("\x1b[42;2;2;3;3$x");
+    assert_visible_contents(&term, file!(), line!(), &["", " **", " **"]);
+
+    // Control characters are not permitted as the fill character
+    term.print("\x1b[10;1;1;3;4$x");
+    assert_visible_contents(&term, file!(), line!(), &["", " **", " **"]);
+}
+
+#[test]
+fn test_decera() {
+    let mut term = TestTerm::new(3, 3, 0);
+    term.print("abc\r\ndef\r\nghi");
+    term.print("\x1b[1;2;2;3$z");
+    assert_visible_contents(&term, file!(), line!(), &["a", "d", "ghi"]);
+
+    // The bottom and right edges default to the edges of the page
+    term.print("\x1b[2;1$z");
+    assert_visible_contents(&term, file!(), line!(), &["a", "", ""]);
+}
+
+#[test]
+fn test_deccra() {
+    let mut term = TestTerm::new(3, 4, 0);
+    term.print("ab\r\ncd");
+    term.print("\x1b[1;1;2;2;1;2;3;1$v");
+    assert_visible_contents(&term, file!(), line!(), &["ab", "cdab", "  cd"]);
+
+    // The copy is clipped at the edge of the page
+    term.print("\x1b[1;1;1;4;1;3;3;1$v");
+    assert_visible_contents(&term, file!(), line!(), &["ab", "cdab", "  ab"]);
+}
+
+#[test]
+fn test_selective_erase() {
+    let mut term = TestTerm::new(2, 4, 0);
+    term.print("a\x1b[1\"qbc\x1b[0\"qd\r\nefgh");
+
+    // DECSEL and DECSED leave the protected "bc" alone
+    term.cup(0, 0);
+    term.print("\x1b[?2K");
+    assert_visible_contents(&term, file!(), line!(), &[" bc", "efgh"]);
+    term.print("\x1b[?2J");
+    assert_visible_contents(&term, file!(), line!(), &[" bc", ""]);
+
+    // DECSERA does too, but DECERA and ED do not
+    term.print("\x1b[1;1;1;4${");
+    assert_visible_contents(&term, file!(), line!(), &[" bc", ""]);
+    term.print("\x1b[1;1;1;2$z");
+    assert_visible_contents(&term, file!(), line!(), &["  c", ""]);
+    term.print("\x1b[2J");
+    assert_visible_contents(&term, file!(), line!(), &["", ""]);
+}
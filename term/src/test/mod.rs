@@ -7,7 +7,7 @@ use bitflags::bitflags;
 mod c1;
 mod csi;
 // mod selection; FIXME: port to render layer
-use crate::color::ColorPalette;
+use crate::color::{ColorPalette, SrgbaTuple};
 use k9::assert_equal as assert_eq;
 use std::sync::{Arc, Mutex};
 use wezterm_escape_parser::csi::{Edit, EraseInDisplay, EraseInLine};
@@ -40,6 +40,22 @@ impl Clipboard for LocalClip {
 
 struct TestTerm {
     term: Terminal,
+    output: CapturedOutput,
+}
+
+/// Collects what the terminal sends back to the application
+#[derive(Clone, Default)]
+struct CapturedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -58,11 +74,16 @@ impl TerminalConfiguration for TestTermConfig {
 
 impl TestTerm {
     fn new(height: usize, width: usize, scrollback: usize) -> Self {
+        Self::with_config(height, width, Arc::new(TestTermConfig { scrollback }))
+    }
+
+    fn with_config(height: usize, width: usize, config: Arc<dyn TerminalConfiguration>) -> Self {
         let _ = env_logger::Builder::new()
             .is_test(true)
             .filter_level(log::LevelFilter::Trace)
             .try_init();
 
+        let output = CapturedOutput::default();
         let mut term = Terminal::new(
             TerminalSize {
                 rows: height,
@@ -71,15 +92,15 @@ impl TestTerm {
                 pixel_height: height * 16,
                 dpi: 0,
             },
-            Arc::new(TestTermConfig { scrollback }),
+            config,
             "WezTerm",
             "O_o",
-            Box::new(Vec::new()),
+            Box::new(output.clone()),
         );
         let clip: Arc<dyn Clipboard> = Arc::new(LocalClip::new());
         term.set_clipboard(&clip);
 
-        let mut term = Self { term };
+        let mut term = Self { term, output };
 
         term.set_auto_wrap(true);
 
@@ -90,6 +111,31 @@ impl TestTerm {
         self.term.advance_bytes(bytes);
     }
 
+    /// Returns what was sent to the application since the last call.
+    /// Output is written from a separate thread, so this asks for a
+    /// device status report and waits for its answer to arrive last.
+    fn take_output(&mut self) -> String {
+        const STATUS_REPORT: &[u8] = b"\x1b[0n";
+        self.print("\x1b[5n");
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        loop {
+            {
+                let mut output = self.output.0.lock().unwrap();
+                if output.ends_with(STATUS_REPORT) {
+                    let len = output.len() - STATUS_REPORT.len();
+                    let text = String::from_utf8_lossy(&output[..len]).into_owned();
+                    output.clear();
+                    return text;
+                }
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "timed out waiting for output"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+
     fn set_mode(&mut self, mode: &str, enable: bool) {
         self.print(CSI);
         self.print(mode);
@@ -1022,6 +1068,42 @@ fn test_resize_wrap() {
     );
 }
 
+#[derive(Debug)]
+struct NoRewrapConfig;
+impl TerminalConfiguration for NoRewrapConfig {
+    fn scrollback_size(&self) -> usize {
+        10
+    }
+
+    fn color_palette(&self) -> ColorPalette {
+        ColorPalette::default()
+    }
+
+    fn rewrap_on_resize(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_resize_without_rewrap() {
+    let mut term = TestTerm::with_config(2, 10, Arc::new(NoRewrapConfig));
+    term.print("0123456789\r\nabcdefghij\r\n");
+    term.resize(TerminalSize {
+        rows: 2,
+        cols: 5,
+        ..Default::default()
+    });
+
+    // The scrollback keeps its width, while the screen is pruned
+    let lines: Vec<String> = term
+        .screen()
+        .lines_in_phys_range(0..3)
+        .iter()
+        .map(|line| line.as_str().into_owned())
+        .collect();
+    assert_eq!(lines, vec!["0123456789", "abcde", ""]);
+}
+
 #[test]
 fn test_resize_wrap_issue_971() {
     const LINES: usize = 4;
@@ -1078,6 +1160,78 @@ fn test_resize_wrap_escape_code_issue_978() {
     assert_visible_contents(&term, file!(), line!(), &["====", "SS", "", ""]);
 }
 
+#[cfg(feature = "spill")]
+#[derive(Debug)]
+struct SpillConfig;
+#[cfg(feature = "spill")]
+impl TerminalConfiguration for SpillConfig {
+    fn scrollback_size(&self) -> usize {
+        2
+    }
+
+    fn color_palette(&self) -> ColorPalette {
+        ColorPalette::default()
+    }
+
+    fn scrollback_spill_to_disk(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "spill")]
+fn logical_lines_from_scrollback_top(term: &TestTerm) -> Vec<String> {
+    let screen = term.screen();
+    let end = screen.visible_row_to_stable_row(screen.physical_rows as VisibleRowIndex);
+    let mut result = vec![];
+    screen.for_each_logical_line_in_stable_range(screen.scrollback_top()..end, |_, lines| {
+        let text: String = lines
+            .iter()
+            .map(|line| line.as_str().into_owned())
+            .collect();
+        let text = text.trim_end();
+        if !text.is_empty() {
+            result.push(text.to_string());
+        }
+        true
+    });
+    result
+}
+
+#[cfg(feature = "spill")]
+#[test]
+fn test_spilled_scrollback_is_reachable() {
+    let mut term = TestTerm::with_config(2, 10, Arc::new(SpillConfig));
+    for i in 0..8 {
+        term.print(format!("line {i}\r\n"));
+    }
+
+    // Only the last 4 rows are held in memory
+    let screen = term.screen();
+    assert_eq!(screen.scrollback_rows(), 4);
+    assert_eq!(screen.spilled_scrollback_rows(), 5);
+    assert_eq!(screen.scrollback_top(), 0);
+
+    let (first, lines) = screen.lines_in_stable_range(&(3..7));
+    assert_eq!(first, 3);
+    let lines: Vec<String> = lines
+        .iter()
+        .map(|line| line.as_str().trim_end().to_string())
+        .collect();
+    assert_eq!(lines, vec!["line 3", "line 4", "line 5", "line 6"]);
+
+    let expected: Vec<String> = (0..8).map(|i| format!("line {i}")).collect();
+    assert_eq!(logical_lines_from_scrollback_top(&term), expected);
+
+    // Rewrapping to a narrower width pushes more lines out of the
+    // scrollback, and they must end up in the spill too
+    term.resize(TerminalSize {
+        rows: 2,
+        cols: 5,
+        ..Default::default()
+    });
+    assert_eq!(logical_lines_from_scrollback_top(&term), expected);
+}
+
 #[test]
 fn test_scrollup() {
     let mut term = TestTerm::new(2, 1, 4);
@@ -1114,6 +1268,24 @@ fn test_scrollup() {
     assert_eq!(term.screen().visible_row_to_stable_row(0), 7);
 }
 
+#[test]
+fn test_scrollback_limit() {
+    let mut term = TestTerm::new(2, 1, 4);
+    term.print("1\n2\n3\n4\n5\n");
+    assert_all_contents(&term, file!(), line!(), &["1", "2", "3", "4", "5", ""]);
+
+    term.set_scrollback_limit(Some(1));
+    assert_all_contents(&term, file!(), line!(), &["4", "5", ""]);
+    assert_eq!(term.screen().visible_row_to_stable_row(0), 4);
+
+    term.print("6\n");
+    assert_all_contents(&term, file!(), line!(), &["5", "6", ""]);
+
+    term.set_scrollback_limit(None);
+    term.print("7\n8\n");
+    assert_all_contents(&term, file!(), line!(), &["5", "6", "7", "8", ""]);
+}
+
 #[test]
 fn test_ri() {
     let mut term = TestTerm::new(3, 1, 10);
@@ -1528,3 +1700,333 @@ fn test_alternate_scroll_mode_cleared_on_soft_reset() {
     term.soft_reset();
     assert!(!term.is_mouse_grabbed());
 }
+
+#[test]
+fn test_osc_palette_changes() {
+    let mut term = TestTerm::new(5, 10, 100);
+    let default = ColorPalette::default();
+
+    // Queries report the palette without changing it
+    term.print("\x1b]4;1;?\x1b\\\x1b]11;?\x1b\\");
+    assert_eq!(term.palette(), default);
+
+    term.print("\x1b]4;1;#ff0000;2;#00ff00\x1b\\");
+    term.print("\x1b]11;#102030\x07");
+    let palette = term.palette();
+    assert_eq!(palette.colors.0[1], SrgbaTuple(1., 0., 0., 1.));
+    assert_eq!(palette.colors.0[2], SrgbaTuple(0., 1., 0., 1.));
+    assert_eq!(palette.background, "#102030".parse::<SrgbaTuple>().unwrap());
+    assert_eq!(palette.colors.0[3], default.colors.0[3]);
+
+    term.print("\x1b]104;1\x1b\\");
+    assert_eq!(term.palette().colors.0[1], default.colors.0[1]);
+    assert_eq!(term.palette().colors.0[2], SrgbaTuple(0., 1., 0., 1.));
+
+    term.print("\x1b]104\x1b\\\x1b]111\x1b\\");
+    assert_eq!(term.palette(), default);
+}
+
+#[derive(Debug)]
+struct LockedCursorConfig;
+impl TerminalConfiguration for LockedCursorConfig {
+    fn color_palette(&self) -> ColorPalette {
+        ColorPalette::default()
+    }
+
+    fn cursor_style_escapes(&self) -> crate::config::CursorStyleEscapes {
+        crate::config::CursorStyleEscapes::SteadyOnly
+    }
+
+    fn cursor_color_min_contrast(&self) -> f32 {
+        1.5
+    }
+
+    fn allow_cursor_hiding(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_cursor_style_and_color() {
+    let mut term = TestTerm::new(5, 10, 100);
+    term.print("\x1b[5 q");
+    assert_eq!(term.cursor_pos().shape, CursorShape::BlinkingBar);
+    term.print("\x1b[2 q");
+    assert_eq!(term.cursor_pos().shape, CursorShape::SteadyBlock);
+    term.print("\x1b[0 q");
+    assert_eq!(term.cursor_pos().shape, CursorShape::Default);
+
+    term.print("\x1b]12;#ff0000\x07");
+    assert_eq!(term.palette().cursor_bg, SrgbaTuple(1., 0., 0., 1.));
+    term.print("\x1b]112\x07");
+    assert_eq!(term.palette(), ColorPalette::default());
+
+    term.print("\x1b[?25l");
+    assert_eq!(term.cursor_pos().visibility, CursorVisibility::Hidden);
+    term.print("\x1b[?25h");
+
+    term.set_config(Arc::new(LockedCursorConfig));
+    term.print("\x1b[5 q");
+    assert_eq!(term.cursor_pos().shape, CursorShape::SteadyBar);
+
+    // The cursor can't be made the same color as the background
+    let background = term.palette().background;
+    term.print(format!("\x1b]12;{}\x07", background.to_rgb_string()));
+    assert_eq!(term.palette(), ColorPalette::default());
+
+    term.print("\x1b[?25l");
+    assert_eq!(term.cursor_pos().visibility, CursorVisibility::Visible);
+}
+
+#[test]
+fn test_title_stack() {
+    let mut term = TestTerm::new(5, 10, 100);
+    term.print("\x1b]2;shell\x07");
+    assert_eq!(term.get_title(), "shell");
+
+    // Save both titles, then let the "application" change them
+    term.print("\x1b[22;0t");
+    term.print("\x1b]0;editor\x07");
+    assert_eq!(term.get_title(), "editor");
+
+    term.print("\x1b[23;0t");
+    assert_eq!(term.get_title(), "shell");
+
+    // Popping an empty stack leaves the title alone
+    term.print("\x1b[23;0t");
+    assert_eq!(term.get_title(), "shell");
+}
+
+#[test]
+fn test_in_band_resize() {
+    let mut term = TestTerm::new(5, 10, 0);
+    term.print("\x1b[?2048$p");
+    assert_eq!(term.take_output(), "\x1b[?2048;2$y");
+
+    // Enabling the mode reports the current size straight away
+    term.print("\x1b[?2048h");
+    assert_eq!(term.take_output(), "\x1b[48;5;10;80;80t");
+    term.print("\x1b[?2048$p");
+    assert_eq!(term.take_output(), "\x1b[?2048;1$y");
+
+    term.resize(TerminalSize {
+        rows: 6,
+        cols: 12,
+        pixel_width: 96,
+        pixel_height: 96,
+        dpi: 0,
+    });
+    assert_eq!(term.take_output(), "\x1b[48;6;12;96;96t");
+
+    term.print("\x1b[?2048l");
+    term.resize(TerminalSize {
+        rows: 5,
+        cols: 10,
+        pixel_width: 80,
+        pixel_height: 80,
+        dpi: 0,
+    });
+    assert_eq!(term.take_output(), "");
+}
+
+#[test]
+fn test_window_size_reports() {
+    let mut term = TestTerm::new(5, 10, 0);
+    term.print("\x1b[18t");
+    assert_eq!(term.take_output(), "\x1b[8;5;10t");
+    term.print("\x1b[19t");
+    assert_eq!(term.take_output(), "\x1b[9;5;10t");
+}
+
+#[derive(Debug)]
+struct NoReportsConfig;
+impl TerminalConfiguration for NoReportsConfig {
+    fn color_palette(&self) -> ColorPalette {
+        ColorPalette::default()
+    }
+
+    fn enable_focus_reporting(&self) -> bool {
+        false
+    }
+
+    fn enable_in_band_resize(&self) -> bool {
+        false
+    }
+
+    fn enable_window_size_reporting(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_reports_disabled_by_config() {
+    let mut term = TestTerm::new(5, 10, 0);
+    term.print("\x1b[?1004h");
+    term.focus_changed(false);
+    assert_eq!(term.take_output(), "\x1b[O");
+    term.print("\x1b[?1004$p");
+    assert_eq!(term.take_output(), "\x1b[?1004;1$y");
+
+    term.set_config(Arc::new(NoReportsConfig));
+    term.focus_changed(true);
+    term.print("\x1b[?2048h");
+    term.print("\x1b[14t\x1b[16t\x1b[18t\x1b[19t");
+    assert_eq!(term.take_output(), "");
+
+    // The disabled modes are reported as not recognized
+    term.print("\x1b[?1004$p\x1b[?2048$p");
+    assert_eq!(term.take_output(), "\x1b[?1004;0$y\x1b[?2048;0$y");
+}
+
+#[derive(Debug)]
+struct NoWheelArrowKeysConfig;
+impl TerminalConfiguration for NoWheelArrowKeysConfig {
+    fn color_palette(&self) -> ColorPalette {
+        ColorPalette::default()
+    }
+
+    fn alternate_buffer_wheel_sends_arrow_keys(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_alt_screen_wheel_arrow_keys() {
+    let wheel_up = MouseEvent {
+        kind: MouseEventKind::Press,
+        x: 0,
+        y: 0,
+        x_pixel_offset: 0,
+        y_pixel_offset: 0,
+        button: MouseButton::WheelUp(1),
+        modifiers: KeyModifiers::NONE,
+    };
+    let mut term = TestTerm::new(5, 10, 0);
+    term.mouse_event(wheel_up).unwrap();
+    assert_eq!(term.take_output(), "");
+
+    term.print("\x1b[?1049h");
+    term.mouse_event(wheel_up).unwrap();
+    assert_eq!(term.take_output(), "\x1b[A\x1b[A\x1b[A");
+
+    term.set_config(Arc::new(NoWheelArrowKeysConfig));
+    term.mouse_event(wheel_up).unwrap();
+    assert_eq!(term.take_output(), "");
+
+    // Applications can still ask for it with DECSET 1007
+    term.print("\x1b[?1007h");
+    term.mouse_event(wheel_up).unwrap();
+    assert_eq!(term.take_output(), "\x1b[A\x1b[A\x1b[A");
+}
+
+#[derive(Clone, Default)]
+struct CapturedAlerts(Arc<Mutex<Vec<Alert>>>);
+
+impl AlertHandler for CapturedAlerts {
+    fn alert(&mut self, alert: Alert) {
+        self.0.lock().unwrap().push(alert);
+    }
+}
+
+impl CapturedAlerts {
+    fn finished_commands(&self) -> Vec<(String, Option<i32>)> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|alert| match alert {
+                Alert::CommandFinished {
+                    command,
+                    exit_status,
+                    ..
+                } => Some((command.clone(), *exit_status)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_command_finished_alert() {
+    let mut term = TestTerm::new(5, 40, 100);
+    let alerts = CapturedAlerts::default();
+    term.set_notification_handler(Box::new(alerts.clone()));
+
+    term.print("\x1b]133;A\x07$ \x1b]133;B\x07ls -l\r\n\x1b]133;C\x07");
+    term.print("total 0\r\n\x1b]133;D;2\x07");
+    assert_eq!(
+        alerts.finished_commands(),
+        vec![("ls -l".to_string(), Some(2))]
+    );
+
+    // A new prompt without a status still finishes the command
+    term.print("\x1b]133;A\x07$ \x1b]133;B\x07true\r\n\x1b]133;C\x07");
+    term.print("\x1b]133;A\x07$ ");
+    assert_eq!(
+        alerts.finished_commands(),
+        vec![("ls -l".to_string(), Some(2)), ("true".to_string(), None)]
+    );
+
+    // Pressing enter on an empty prompt isn't a command
+    term.print("\x1b]133;B\x07\r\n\x1b]133;C\x07\x1b]133;D;0\x07");
+    assert_eq!(alerts.finished_commands().len(), 2);
+}
+
+#[test]
+fn test_tab_color_osc() {
+    use termwiz::color::RgbColor;
+
+    let mut term = TestTerm::new(5, 40, 100);
+    let alerts = CapturedAlerts::default();
+    term.set_notification_handler(Box::new(alerts.clone()));
+
+    let tab_colors = |alerts: &CapturedAlerts| -> Vec<Option<RgbColor>> {
+        alerts
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|alert| match alert {
+                Alert::TabColorChanged(color) => Some(*color),
+                _ => None,
+            })
+            .collect()
+    };
+
+    term.print("\x1b]6;1;bg;red;brightness;255\x07");
+    term.print("\x1b]6;1;bg;blue;brightness;128\x07");
+    // Not a tab color sequence, and an out of range component
+    term.print("\x1b]6;1;bg;alpha;brightness;1\x07");
+    term.print("\x1b]6;1;bg;green;brightness;256\x07");
+    term.print("\x1b]6;1;bg;*;default\x07");
+    assert_eq!(
+        tab_colors(&alerts),
+        vec![
+            Some(RgbColor::new_8bpc(255, 0, 0)),
+            Some(RgbColor::new_8bpc(255, 0, 128)),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_command_status_marks() {
+    let mut term = TestTerm::new(10, 40, 100);
+
+    term.print("\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07");
+    term.print("\x1b]133;D;1\x07");
+    term.print("\x1b]133;A\x07$ \x1b]133;B\x07true\r\n\x1b]133;C\x07");
+    term.print("\x1b]133;D;0\x07");
+    // A command whose status is never reported isn't marked
+    term.print("\x1b]133;A\x07$ \x1b]133;B\x07sleep 1\r\n\x1b]133;C\x07");
+    term.print("\x1b]133;A\x07$ ");
+
+    let marks: Vec<Option<bool>> = term
+        .screen()
+        .visible_lines()
+        .iter()
+        .take(4)
+        .map(|line| line.command_succeeded())
+        .collect();
+    assert_eq!(marks, vec![Some(false), Some(true), None, None]);
+}
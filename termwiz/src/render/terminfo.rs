@@ -74,11 +74,13 @@ impl TerminfoRenderer {
         if let Some(attr) = self.pending_attr.take() {
             let mut current_foreground = self.current_attr.foreground();
             let mut current_background = self.current_attr.background();
+            let mut current_underline_color = self.current_attr.underline_color();
 
             if !attr.attribute_bits_equal(&self.current_attr) {
                 // Updating the attribute bits also resets the colors.
                 current_foreground = ColorAttribute::Default;
                 current_background = ColorAttribute::Default;
+                current_underline_color = ColorAttribute::Default;
 
                 let sgr = if self.caps.force_terminfo_render_to_use_ansi_sgr() {
                     None
@@ -121,8 +123,11 @@ impl TerminfoRenderer {
                     }
                 }
 
-                if attr.underline() == Underline::Double {
-                    attr_on!(Sgr::Underline(Underline::Double));
+                // Extended underline styles have no terminfo capability,
+                // so they are always emitted as SGR 4:x.
+                match attr.underline() {
+                    Underline::None | Underline::Single => {}
+                    style => attr_on!(Sgr::Underline(style)),
                 }
 
                 if attr.blink() == Blink::Rapid {
@@ -224,6 +229,24 @@ impl TerminfoRenderer {
                 }
             }
 
+            if attr.underline_color() != current_underline_color
+                && self.caps.color_level() != ColorLevel::MonoChrome
+            {
+                let spec = match (has_true_color, attr.underline_color()) {
+                    (true, ColorAttribute::TrueColorWithPaletteFallback(tc, _))
+                    | (true, ColorAttribute::TrueColorWithDefaultFallback(tc)) => {
+                        ColorSpec::TrueColor(tc)
+                    }
+                    (false, ColorAttribute::TrueColorWithDefaultFallback(_))
+                    | (_, ColorAttribute::Default) => ColorSpec::Default,
+                    (false, ColorAttribute::TrueColorWithPaletteFallback(_, idx))
+                    | (_, ColorAttribute::PaletteIndex(idx)) => ColorSpec::PaletteIndex(idx),
+                };
+                // There is no terminfo capability for the underline color,
+                // so this is always SGR 58/59.
+                write!(out, "{}", CSI::Sgr(Sgr::UnderlineColor(spec)))?;
+            }
+
             if self.caps.hyperlinks() {
                 if let Some(link) = attr.hyperlink() {
                     let osc = OperatingSystemCommand::SetHyperlink(Some((**link).clone()));
@@ -460,6 +483,11 @@ impl TerminfoRenderer {
                         attr.set_background(*col);
                     });
                 }
+                Change::Attribute(AttributeChange::UnderlineColor(col)) => {
+                    self.attr_apply(|attr| {
+                        attr.set_underline_color(*col);
+                    });
+                }
                 Change::Attribute(AttributeChange::Hyperlink(link)) => {
                     self.attr_apply(|attr| {
                         attr.set_hyperlink(link.clone());
@@ -1294,4 +1322,37 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn curly_underline_with_color_no_terminfo() {
+        let mut out = FakeTerm::new(no_terminfo_all_enabled());
+        out.render(&[
+            Change::Attribute(AttributeChange::Underline(Underline::Curly)),
+            Change::Attribute(AttributeChange::UnderlineColor(AnsiColor::Red.into())),
+            Change::Text("A".into()),
+            Change::Attribute(AttributeChange::UnderlineColor(ColorAttribute::Default)),
+            Change::Text("B".into()),
+        ])
+        .unwrap();
+
+        let result = out.parse();
+        assert_eq!(
+            result,
+            vec![
+                Action::CSI(CSI::Sgr(Sgr::Reset)),
+                Action::CSI(CSI::Sgr(Sgr::Underline(Underline::Curly))),
+                Action::CSI(CSI::Sgr(Sgr::UnderlineColor(AnsiColor::Red.into()))),
+                Action::Print('A'),
+                Action::CSI(CSI::Sgr(Sgr::UnderlineColor(ColorSpec::Default))),
+                Action::Print('B'),
+            ]
+        );
+
+        assert_eq!(
+            out.renderer.current_attr,
+            CellAttributes::default()
+                .set_underline(Underline::Curly)
+                .clone()
+        );
+    }
 }
@@ -0,0 +1,250 @@
+use crate::{Rect, Size};
+use std::ops::Range;
+
+/// A line of `AccessibleText`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessibleLine {
+    pub text: String,
+    /// The cell column at which each char of `text` starts, followed by
+    /// the column just past its end.  Wide glyphs, such as CJK and emoji,
+    /// span more than one cell, and the chars of a grapheme share a cell.
+    pub columns: Vec<usize>,
+}
+
+impl AccessibleLine {
+    /// Returns the cell column at which the char at `idx` starts,
+    /// or the end of the line if `idx` is beyond its last char
+    fn column_for_char(&self, idx: usize) -> usize {
+        self.columns
+            .get(idx)
+            .or_else(|| self.columns.last())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of chars that start before cell `column`
+    fn chars_before_column(&self, column: usize) -> usize {
+        self.columns
+            .iter()
+            .take(self.text.chars().count())
+            .take_while(|&&start| start < column)
+            .count()
+    }
+}
+
+/// The text of the active pane, as exposed to screen readers and other
+/// assistive technology via `WindowOps::set_accessible_text`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessibleText {
+    /// The lines that are in view, from top to bottom
+    pub lines: Vec<AccessibleLine>,
+    /// The line holding the cursor, as an index into `lines`,
+    /// or None if the cursor is not in view
+    pub cursor_line: Option<usize>,
+    /// The cell column of the cursor
+    pub cursor_column: usize,
+    /// Where the text is drawn, in pixels relative to the window
+    pub bounds: Rect,
+    /// The size of a cell, in pixels
+    pub cell_size: Size,
+}
+
+/// `AccessibleText` indexed by UTF-16 code units, which is how the
+/// macOS accessibility APIs address text
+#[derive(Debug, Default)]
+pub struct Utf16AccessibleText {
+    pub source: AccessibleText,
+    /// The lines, each followed by a newline apart from the last
+    pub text: Vec<u16>,
+    /// The offset of the start of each line in `text`
+    line_starts: Vec<usize>,
+    /// The offset of the cursor in `text`
+    pub cursor: usize,
+}
+
+impl Utf16AccessibleText {
+    pub fn new(source: AccessibleText) -> Self {
+        let mut text = vec![];
+        let mut line_starts = vec![];
+        let mut cursor = None;
+        for (idx, line) in source.lines.iter().enumerate() {
+            if idx > 0 {
+                text.push(b'\n' as u16);
+            }
+            line_starts.push(text.len());
+            if source.cursor_line == Some(idx) {
+                // The cursor may be beyond the end of the line,
+                // in which case it is placed at the end
+                let before: usize = line
+                    .text
+                    .chars()
+                    .take(line.chars_before_column(source.cursor_column))
+                    .map(char::len_utf16)
+                    .sum();
+                cursor = Some(text.len() + before);
+            }
+            text.extend(line.text.encode_utf16());
+        }
+        let cursor = cursor.unwrap_or(text.len());
+        Self {
+            source,
+            text,
+            line_starts,
+            cursor,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn num_lines(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the line containing the offset `index`
+    pub fn line_for_index(&self, index: usize) -> usize {
+        match self.line_starts.binary_search(&index) {
+            Ok(line) => line,
+            Err(line) => line.saturating_sub(1),
+        }
+    }
+
+    /// Returns the range of `line`, including its trailing newline
+    pub fn range_for_line(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        Some(start..end)
+    }
+
+    /// Returns the text in `range`, clamped to the text that we have
+    pub fn string_for_range(&self, range: Range<usize>) -> String {
+        let end = range.end.min(self.text.len());
+        let start = range.start.min(end);
+        String::from_utf16_lossy(&self.text[start..end])
+    }
+
+    /// Returns the rectangle, in pixels relative to the window, covering
+    /// the first line of `range`
+    pub fn rect_for_range(&self, range: Range<usize>) -> Rect {
+        let line = self.line_for_index(range.start);
+        let line_start = self.line_starts.get(line).copied().unwrap_or(0);
+        let line_range = self.range_for_line(line).unwrap_or(0..0);
+        let line_text = &self.text[line_range.clone()];
+        let columns = self.source.lines.get(line);
+        let column = |offset: usize| {
+            let units = offset.clamp(line_start, line_range.end) - line_start;
+            let chars = char::decode_utf16(line_text[..units].iter().copied()).count();
+            columns.map_or(0, |line| line.column_for_char(chars))
+        };
+        let first = column(range.start);
+        let last = column(range.end).max(first + 1);
+
+        let cell = self.source.cell_size;
+        let origin = self.source.bounds.origin;
+        Rect::new(
+            crate::Point::new(
+                origin.x + first as isize * cell.width,
+                origin.y + line as isize * cell.height,
+            ),
+            Size::new((last - first) as isize * cell.width, cell.height),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Lays out `text` with the given cell width for each char
+    fn line(text: &str, widths: &[usize]) -> AccessibleLine {
+        let mut columns = vec![0];
+        for width in widths {
+            columns.push(columns.last().unwrap() + width);
+        }
+        AccessibleLine {
+            text: text.to_string(),
+            columns,
+        }
+    }
+
+    #[test]
+    fn utf16_indexing() {
+        let text = Utf16AccessibleText::new(AccessibleText {
+            lines: vec![
+                line("$ ls", &[1, 1, 1, 1]),
+                line("🦀 a", &[2, 1, 1]),
+                line("$", &[1]),
+            ],
+            // After the space that follows the 2 cell wide crab
+            cursor_line: Some(1),
+            cursor_column: 3,
+            bounds: Rect::new(crate::Point::new(10, 20), Size::new(100, 30)),
+            cell_size: Size::new(8, 10),
+        });
+        // "$ ls\n" is 5 units, the crab is 2 units
+        assert_eq!(text.len(), 5 + 5 + 1);
+        assert_eq!(text.cursor, 5 + 3);
+        assert_eq!(text.num_lines(), 3);
+        assert_eq!(text.line_for_index(0), 0);
+        assert_eq!(text.line_for_index(4), 0);
+        assert_eq!(text.line_for_index(5), 1);
+        assert_eq!(text.line_for_index(100), 2);
+        assert_eq!(text.range_for_line(1), Some(5..10));
+        assert_eq!(text.range_for_line(2), Some(10..11));
+        assert_eq!(text.range_for_line(3), None);
+        assert_eq!(text.string_for_range(5..9), "🦀 a");
+        assert_eq!(text.string_for_range(10..100), "$");
+
+        // " a" starts after the crab, in the third cell
+        let rect = text.rect_for_range(7..9);
+        assert_eq!(rect.origin, crate::Point::new(10 + 16, 20 + 10));
+        assert_eq!(rect.size, Size::new(16, 10));
+
+        // The crab itself covers two cells
+        let rect = text.rect_for_range(5..7);
+        assert_eq!(rect.origin, crate::Point::new(10, 20 + 10));
+        assert_eq!(rect.size, Size::new(16, 10));
+    }
+
+    #[test]
+    fn wide_chars_and_graphemes() {
+        // "e" and a combining acute accent share a cell
+        let text = Utf16AccessibleText::new(AccessibleText {
+            lines: vec![AccessibleLine {
+                text: "日本e\u{301}x".to_string(),
+                columns: vec![0, 2, 4, 4, 5, 6],
+            }],
+            cursor_line: Some(0),
+            cursor_column: 5,
+            cell_size: Size::new(8, 10),
+            ..Default::default()
+        });
+        assert_eq!(text.cursor, 4);
+
+        let rect = text.rect_for_range(1..2);
+        assert_eq!(rect.origin, crate::Point::new(16, 0));
+        assert_eq!(rect.size, Size::new(16, 10));
+
+        // The cursor beyond the end of the line is placed at its end
+        let text = Utf16AccessibleText::new(AccessibleText {
+            cursor_column: 40,
+            ..text.source
+        });
+        assert_eq!(text.cursor, 5);
+    }
+
+    #[test]
+    fn cursor_out_of_view() {
+        let text = Utf16AccessibleText::new(AccessibleText {
+            lines: vec![line("abc", &[1, 1, 1])],
+            cursor_line: None,
+            ..Default::default()
+        });
+        assert_eq!(text.cursor, 3);
+    }
+}
@@ -2,6 +2,7 @@ use crate::bitmaps::{BitmapImage, Texture2d, TextureRect};
 use crate::{Point, Rect, Size};
 use anyhow::{ensure, Result as Fallible};
 use guillotiere::{SimpleAtlasAllocator, Size as AtlasSize};
+use std::cell::Cell;
 use std::convert::TryInto;
 use std::rc::Rc;
 use thiserror::*;
@@ -15,16 +16,65 @@ pub struct OutOfTextureSpace {
     pub current_size: usize,
 }
 
+/// Atlases smaller than this are not split into pages
+const MIN_PAGE_SIZE: usize = 512;
+/// Upper bound on the number of pages along each side of an atlas
+const MAX_PAGES_PER_SIDE: usize = 4;
+
+fn pages_per_side(side: usize) -> usize {
+    (side / MIN_PAGE_SIZE).clamp(1, MAX_PAGES_PER_SIDE)
+}
+
+/// Records the most recent frame in which a sprite from a page was drawn.
+/// It is shared between the page and its sprites so that use can be
+/// noted wherever texture coordinates are taken from a sprite, without
+/// having to route every lookup back through the atlas.
+struct PageUsage {
+    index: usize,
+    frame: Rc<Cell<u64>>,
+    last_used: Cell<u64>,
+}
+
+impl PageUsage {
+    fn mark_used(&self) {
+        self.last_used.set(self.frame.get());
+    }
+}
+
+struct Page {
+    origin: Point,
+    allocator: SimpleAtlasAllocator,
+    usage: Rc<PageUsage>,
+    /// Set when the page holds sprites that must never be evicted
+    pinned: bool,
+    /// Whether anything has been allocated from the page since it
+    /// was last cleared
+    occupied: bool,
+}
+
 /// Atlases are bitmaps of srgba data that are sized as a power of 2.
-/// We allocate sprites out of the available space, using AtlasAllocator
+/// The texture is divided into a grid of square pages, and we allocate
+/// sprites out of the available space in each page, using AtlasAllocator
 /// to manage the available rectangles.
+/// When every page is full, the least recently drawn page can be evicted
+/// and reused, rather than throwing away the whole atlas.
 pub struct Atlas {
     texture: Rc<dyn Texture2d>,
 
-    allocator: SimpleAtlasAllocator,
+    pages: Vec<Page>,
 
     /// Dimensions of the texture
     side: usize,
+
+    /// Dimensions of each page
+    page_side: usize,
+
+    /// The current frame number; see `begin_frame`
+    frame: Rc<Cell<u64>>,
+
+    /// Whether the most recent failed allocation could have been
+    /// satisfied by an empty page
+    last_failure_fits_page: bool,
 }
 
 impl Atlas {
@@ -40,12 +90,36 @@ impl Atlas {
         let rect = Rect::new(Point::new(0, 0), Size::new(iside, iside));
         texture.write(rect, &image);
 
-        let allocator =
-            SimpleAtlasAllocator::new(AtlasSize::new(side.try_into()?, side.try_into()?));
+        let per_side = pages_per_side(side);
+        let page_side = side / per_side;
+        let frame = Rc::new(Cell::new(0));
+        let mut pages = vec![];
+        for row in 0..per_side {
+            for col in 0..per_side {
+                pages.push(Page {
+                    origin: Point::new((col * page_side) as isize, (row * page_side) as isize),
+                    allocator: SimpleAtlasAllocator::new(AtlasSize::new(
+                        page_side.try_into()?,
+                        page_side.try_into()?,
+                    )),
+                    usage: Rc::new(PageUsage {
+                        index: pages.len(),
+                        frame: Rc::clone(&frame),
+                        last_used: Cell::new(0),
+                    }),
+                    pinned: false,
+                    occupied: false,
+                });
+            }
+        }
+
         Ok(Self {
             texture: Rc::clone(texture),
             side,
-            allocator,
+            page_side,
+            pages,
+            frame,
+            last_failure_fits_page: false,
         })
     }
 
@@ -94,30 +168,42 @@ impl Atlas {
         let reserve_height = reserve_height + padding.unwrap_or(0) as i32 + PADDING * 2;
 
         let start = std::time::Instant::now();
-        let res = if let Some(allocation) = self
-            .allocator
-            .allocate(AtlasSize::new(reserve_width, reserve_height))
-        {
-            let left = allocation.min.x;
-            let top = allocation.min.y;
+        let allocation = self.pages.iter_mut().find_map(|page| {
+            let allocation = page
+                .allocator
+                .allocate(AtlasSize::new(reserve_width, reserve_height))?;
+            Some((page, allocation))
+        });
+        let res = if let Some((page, allocation)) = allocation {
+            let left = page.origin.x + allocation.min.x as isize;
+            let top = page.origin.y + allocation.min.y as isize;
             let rect = Rect::new(
-                Point::new((left + PADDING) as isize, (top + PADDING) as isize),
+                Point::new(left + PADDING as isize, top + PADDING as isize),
                 Size::new(width as isize, height as isize),
             );
 
             self.texture.write(rect, im);
+            page.occupied = true;
+            page.usage.mark_used();
 
             metrics::histogram!("window.atlas.allocate.success.rate").record(1.);
             Ok(Sprite {
                 texture: Rc::clone(&self.texture),
                 coords: rect,
+                page: Rc::clone(&page.usage),
             })
         } else {
             // It's not possible to satisfy that request
             let size = (reserve_width.max(reserve_height) as usize).next_power_of_two();
+            self.last_failure_fits_page =
+                reserve_width.max(reserve_height) as usize <= self.page_side;
+            let mut needed = (self.side * 2).max(size);
+            while needed / pages_per_side(needed) < size {
+                needed *= 2;
+            }
             metrics::histogram!("window.atlas.allocate.failure.rate").record(1.);
             Err(OutOfTextureSpace {
-                size: Some((self.side * 2).max(size)),
+                size: Some(needed),
                 current_size: self.side,
             })
         };
@@ -130,25 +216,72 @@ impl Atlas {
         self.side
     }
 
+    /// Advance the frame counter used to decide which page was least
+    /// recently used.  Call this once at the start of each frame, before
+    /// any sprites are drawn.
+    pub fn begin_frame(&mut self) {
+        self.frame.set(self.frame.get() + 1);
+    }
+
+    /// Prevent the page holding `sprite` from ever being evicted.
+    /// This is for sprites that are held for the lifetime of the atlas.
+    pub fn pin(&mut self, sprite: &Sprite) {
+        if let Some(page) = self.pages.get_mut(sprite.page.index) {
+            page.pinned = true;
+        }
+    }
+
+    /// Clear the least recently used page that has not been drawn from
+    /// in the current frame, so that its space can be reused.
+    /// Returns the index of the evicted page, or None if no page could
+    /// be evicted, or if evicting a page would not make room for the
+    /// most recent failed allocation.
+    pub fn evict_lru_page(&mut self) -> Option<usize> {
+        if !self.last_failure_fits_page {
+            return None;
+        }
+        let frame = self.frame.get();
+        let page = self
+            .pages
+            .iter_mut()
+            .filter(|page| page.occupied && !page.pinned && page.usage.last_used.get() < frame)
+            .min_by_key(|page| page.usage.last_used.get())?;
+
+        let iside = self.page_side as isize;
+        let image = crate::Image::new(self.page_side, self.page_side);
+        self.texture
+            .write(Rect::new(page.origin, Size::new(iside, iside)), &image);
+        page.allocator.clear();
+        page.occupied = false;
+        metrics::histogram!("window.atlas.evict.rate").record(1.);
+        Some(page.usage.index)
+    }
+
     /// Zero out the texture, and forget all allocated regions
     pub fn clear(&mut self) {
         let iside = self.side as isize;
         let image = crate::Image::new(self.side, self.side);
         let rect = Rect::new(Point::new(0, 0), Size::new(iside, iside));
         self.texture.write(rect, &image);
-        self.allocator.clear();
+        for page in &mut self.pages {
+            page.allocator.clear();
+            page.pinned = false;
+            page.occupied = false;
+        }
     }
 }
 
 pub struct Sprite {
     pub texture: Rc<dyn Texture2d>,
     pub coords: Rect,
+    page: Rc<PageUsage>,
 }
 
 impl std::fmt::Debug for Sprite {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
         fmt.debug_struct("Sprite")
             .field("coords", &self.coords)
+            .field("page", &self.page.index)
             .field("texture_width", &self.texture.width())
             .field("texture_height", &self.texture.height())
             .finish()
@@ -160,13 +293,69 @@ impl Clone for Sprite {
         Self {
             texture: Rc::clone(&self.texture),
             coords: self.coords,
+            page: Rc::clone(&self.page),
         }
     }
 }
 
 impl Sprite {
-    /// Returns the texture coordinates of the sprite
+    /// Returns the texture coordinates of the sprite.
+    /// This also records that the sprite is in use in the current frame.
     pub fn texture_coords(&self) -> TextureRect {
+        self.mark_used();
         self.texture.to_texture_coords(self.coords)
     }
+
+    /// Record that the sprite is in use in the current frame, so that
+    /// its atlas page is not evicted from under it
+    pub fn mark_used(&self) {
+        self.page.mark_used();
+    }
+
+    /// Returns the index of the atlas page that holds this sprite
+    pub fn page(&self) -> usize {
+        self.page.index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitmaps::ImageTexture;
+    use crate::Image;
+
+    #[test]
+    fn evicts_least_recently_used_page() {
+        let texture: Rc<dyn Texture2d> = Rc::new(ImageTexture::new(1024, 1024));
+        let mut atlas = Atlas::new(&texture).unwrap();
+        // Fills a whole 512x512 page once padding is added
+        let image = Image::new(510, 510);
+
+        let sprites: Vec<Sprite> = (0..4).map(|_| atlas.allocate(&image).unwrap()).collect();
+        let pages: Vec<usize> = sprites.iter().map(Sprite::page).collect();
+        assert_eq!(pages, vec![0, 1, 2, 3]);
+        atlas.pin(&sprites[0]);
+        assert!(atlas.allocate(&image).is_err());
+
+        // Everything was drawn in the current frame, so nothing can go
+        assert_eq!(atlas.evict_lru_page(), None);
+
+        atlas.begin_frame();
+        sprites[1].texture_coords();
+        sprites[3].texture_coords();
+        assert!(atlas.allocate(&image).is_err());
+        assert_eq!(atlas.evict_lru_page(), Some(2));
+        assert_eq!(atlas.allocate(&image).unwrap().page(), 2);
+        assert!(atlas.allocate(&image).is_err());
+        assert_eq!(atlas.evict_lru_page(), None);
+    }
+
+    #[test]
+    fn oversized_sprites_grow_the_atlas() {
+        let texture: Rc<dyn Texture2d> = Rc::new(ImageTexture::new(1024, 1024));
+        let mut atlas = Atlas::new(&texture).unwrap();
+        let err = atlas.allocate(&Image::new(600, 600)).unwrap_err();
+        assert_eq!(err.size, Some(4096));
+        assert_eq!(atlas.evict_lru_page(), None);
+    }
 }
@@ -36,6 +36,15 @@ pub fn shutdown() {
     CONN.with(|m| drop(m.borrow_mut().take()));
 }
 
+/// Progress shown over the application's Dock icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockProgress {
+    /// A percentage in the range 0-100
+    Percentage(u8),
+    /// Busy, with no indication of how far along
+    Indeterminate,
+}
+
 #[derive(Debug)]
 pub enum ApplicationEvent {
     /// The system wants to open a command in the terminal
@@ -95,6 +104,12 @@ pub trait ConnectionOps {
         Appearance::Light
     }
 
+    /// Retrieve the accessibility display settings of the system.
+    /// This is currently only implemented on macOS.
+    fn get_system_accessibility(&self) -> config::SystemAccessibility {
+        config::SystemAccessibility::default()
+    }
+
     /// Hide the application.
     /// This actions hides all of the windows of the application and switches
     /// focus away from it.
@@ -130,10 +145,24 @@ pub trait ConnectionOps {
     /// Perform the system beep/notification sound
     fn beep(&self) {}
 
+    /// Enable or disable secure keyboard entry, which prevents other
+    /// applications from observing keystrokes (macOS only).
+    /// Requests are counted: each enable must be balanced by a disable,
+    /// and secure entry stays on while any request is outstanding.
+    fn set_secure_keyboard_entry(&self, _enable: bool) {}
+
     /// Set the Dock badge label (macOS only).
     /// Pass None to clear the badge.
     fn set_dock_badge(&self, _label: Option<&str>) {}
 
+    /// Show a progress bar over the Dock icon (macOS only).
+    /// Pass None to remove it.
+    fn set_dock_progress(&self, _progress: Option<DockProgress>) {}
+
+    /// Ask the window manager/Dock to draw the user's attention to the
+    /// application, if it is not already active.
+    fn request_user_attention(&self) {}
+
     /// Returns information about the screens
     fn screens(&self) -> anyhow::Result<Screens> {
         anyhow::bail!("Unable to query screen information");
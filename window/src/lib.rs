@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use std::rc::Rc;
 use thiserror::Error;
 use url::Url;
+mod accessibility;
 pub mod bitmaps;
 pub use wezterm_color_types as color;
 mod configuration;
@@ -36,6 +37,7 @@ pub fn drain_spawn_queue_burst(max_funcs: usize) -> bool {
 
 mod egl;
 
+pub use accessibility::*;
 pub use bitmaps::{BitmapImage, Image};
 pub use connection::*;
 pub use glium;
@@ -164,6 +166,19 @@ pub enum DeadKeyStatus {
     Composing(String),
 }
 
+/// A trackpad gesture that has no equivalent mouse event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A multi-finger horizontal swipe that conventionally means "back",
+    /// such as swiping towards the right on macOS
+    SwipeBack,
+    /// A multi-finger horizontal swipe that conventionally means "forward"
+    SwipeForward,
+    /// A pinch; the value is the change in magnification since the
+    /// previous event, positive when zooming in
+    Magnify(f64),
+}
+
 #[derive(Debug)]
 pub enum WindowEvent {
     /// Called when the window close button is clicked.
@@ -206,6 +221,8 @@ pub enum WindowEvent {
     MouseEvent(MouseEvent),
     MouseLeave,
 
+    Gesture(Gesture),
+
     AppearanceChanged(Appearance),
 
     Notification(Box<dyn Any + Send + Sync>),
@@ -268,6 +285,10 @@ pub trait WindowOps {
 
     /// Setup opengl for rendering
     async fn enable_opengl(&self) -> anyhow::Result<Rc<glium::backend::Context>>;
+    /// Setup opengl for rendering with software rasterization, regardless
+    /// of the configured front_end.  This is used when falling back after
+    /// hardware rendering failed.
+    async fn enable_software_opengl(&self) -> anyhow::Result<Rc<glium::backend::Context>>;
     /// Advise the window that a frame is finished
     fn finish_frame(&self, frame: glium::Frame) -> anyhow::Result<()> {
         frame.finish()?;
@@ -330,6 +351,14 @@ pub trait WindowOps {
     /// the platform specific input method editor
     fn set_text_cursor_position(&self, _cursor: Rect) {}
 
+    /// Temporarily disable dead key composition, regardless of the
+    /// `use_dead_keys` configuration
+    fn set_dead_keys_suppressed(&self, _suppressed: bool) {}
+
+    /// Update the text exposed to screen readers and other assistive
+    /// technology.  This is currently only implemented on macOS.
+    fn set_accessible_text(&self, _text: AccessibleText) {}
+
     /// Initiate textual transfer from the clipboard
     fn get_clipboard(&self, clipboard: Clipboard) -> Future<String>;
 
@@ -352,6 +381,20 @@ pub trait WindowOps {
 
     fn toggle_fullscreen(&self) {}
 
+    /// Activate the native tab at `index` in the tab group of this
+    /// window; a negative index counts back from the last tab.
+    /// This is only implemented on macOS.
+    fn activate_native_tab(&self, _index: isize) {}
+
+    /// Activate the native tab `delta` tabs away from this window,
+    /// wrapping around the ends of the tab group if `wrap` is true.
+    /// This is only implemented on macOS.
+    fn activate_native_tab_relative(&self, _delta: isize, _wrap: bool) {}
+
+    /// Show or hide the overview of the native tabs of this window.
+    /// This is only implemented on macOS.
+    fn toggle_native_tab_overview(&self) {}
+
     fn config_did_change(&self, _config: &config::ConfigHandle) {}
 
     /// Check if the window is in a zoom (maximize/restore) animation.
@@ -463,6 +463,18 @@ extern "C" fn application_did_finish_launching(this: &mut Object, _sel: Sel, _no
         ];
         log::debug!("registered for NSWorkspaceScreensDidWakeNotification");
 
+        // Register for changes to reduce transparency, increase contrast
+        // and reduce motion, which are treated as appearance changes.
+        let notification_name =
+            nsstring("NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification");
+        let () = msg_send![notification_center,
+            addObserver: this as *mut Object
+            selector: sel!(accessibilityDisplayOptionsDidChange:)
+            name: *notification_name
+            object: nil
+        ];
+        log::debug!("registered for NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification");
+
         // Register for display topology changes (monitor connect/disconnect,
         // resolution updates) and refresh all window backends the same way.
         let app_notification_center: id = msg_send![class!(NSNotificationCenter), defaultCenter];
@@ -534,6 +546,27 @@ extern "C" fn screen_parameters_did_change(
     );
 }
 
+/// Called when the accessibility display options of the system change.
+/// Windows are told that their appearance changed so that the gui
+/// re-reads the settings and reloads the config.
+extern "C" fn accessibility_display_options_did_change(
+    _self: &mut Object,
+    _sel: Sel,
+    _notification: *mut Object,
+) {
+    log::debug!("NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification received");
+    let Some(conn) = Connection::get() else {
+        return;
+    };
+    let appearance = conn.get_appearance();
+    let windows: Vec<_> = conn.windows.borrow().values().cloned().collect();
+    for window in windows {
+        if let Ok(mut inner) = window.try_borrow_mut() {
+            inner.notify_appearance_changed(appearance);
+        }
+    }
+}
+
 extern "C" fn application_open_untitled_file(
     this: &mut Object,
     _sel: Sel,
@@ -991,6 +1024,11 @@ fn get_class() -> &'static Class {
                 sel!(screenParametersDidChange:),
                 screen_parameters_did_change as extern "C" fn(&mut Object, Sel, *mut Object),
             );
+            cls.add_method(
+                sel!(accessibilityDisplayOptionsDidChange:),
+                accessibility_display_options_did_change
+                    as extern "C" fn(&mut Object, Sel, *mut Object),
+            );
         }
 
         cls.register()
@@ -3,7 +3,7 @@
 
 use super::nsstring_to_str;
 use super::window::WindowInner;
-use crate::connection::ConnectionOps;
+use crate::connection::{ConnectionOps, DockProgress};
 use crate::os::macos::app::{
     create_app_delegate, flush_pending_service_opens, sync_global_hotkey_registration,
 };
@@ -12,13 +12,13 @@ use crate::spawn::*;
 use crate::Appearance;
 use cocoa::appkit::{NSApp, NSApplication, NSApplicationActivationPolicyRegular, NSScreen};
 use cocoa::base::{id, nil};
-use cocoa::foundation::{NSArray, NSInteger};
+use cocoa::foundation::{NSArray, NSInteger, NSPoint, NSRect, NSSize, NSUInteger};
 use core_foundation::base::TCFType;
 use core_foundation::string::{CFString, CFStringRef};
-use objc::runtime::{Object, BOOL, YES};
+use objc::runtime::{Object, BOOL, NO, YES};
 use objc::*;
 use serde::Deserialize;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::atomic::AtomicUsize;
@@ -28,9 +28,47 @@ pub struct Connection {
     pub(crate) windows: RefCell<HashMap<usize, Rc<RefCell<WindowInner>>>>,
     pub(crate) next_window_id: AtomicUsize,
     pub(crate) gl_connection: RefCell<Option<Rc<crate::egl::GlConnection>>>,
+    /// How many windows currently want secure keyboard entry
+    secure_keyboard_entry_requests: Cell<usize>,
+    /// The Dock tile content view and the progress indicator within it,
+    /// created the first time that progress is shown
+    dock_progress: RefCell<Option<(id, id)>>,
 }
 
 impl Connection {
+    /// Returns the progress indicator drawn over the Dock icon, making
+    /// the view that hosts it the content view of `dock_tile`.
+    /// Both views are created once and live as long as the app.
+    unsafe fn dock_progress_indicator(&self, dock_tile: id) -> id {
+        let (content, indicator) = *self.dock_progress.borrow_mut().get_or_insert_with(|| {
+            let size: NSSize = msg_send![dock_tile, size];
+            let content: id = msg_send![class!(NSImageView), alloc];
+            let content: id =
+                msg_send![content, initWithFrame: NSRect::new(NSPoint::new(0., 0.), size)];
+            let icon: id = msg_send![NSApp(), applicationIconImage];
+            let () = msg_send![content, setImage: icon];
+
+            // A bar across the bottom of the icon
+            let bar = NSRect::new(
+                NSPoint::new(size.width * 0.1, size.height * 0.08),
+                NSSize::new(size.width * 0.8, size.height * 0.12),
+            );
+            let indicator: id = msg_send![class!(NSProgressIndicator), alloc];
+            let indicator: id = msg_send![indicator, initWithFrame: bar];
+            // NSProgressIndicatorStyleBar
+            let () = msg_send![indicator, setStyle: 0 as NSUInteger];
+            let () = msg_send![indicator, setMinValue: 0.0f64];
+            let () = msg_send![indicator, setMaxValue: 100.0f64];
+            let () = msg_send![content, addSubview: indicator];
+            (content, indicator)
+        });
+        let current: id = msg_send![dock_tile, contentView];
+        if current != content {
+            let () = msg_send![dock_tile, setContentView: content];
+        }
+        indicator
+    }
+
     pub(crate) fn create_new() -> anyhow::Result<Self> {
         // Ensure that the SPAWN_QUEUE is created; it will have nothing
         // to run right now.
@@ -48,6 +86,8 @@ impl Connection {
                 windows: RefCell::new(HashMap::new()),
                 next_window_id: AtomicUsize::new(1),
                 gl_connection: RefCell::new(None),
+                secure_keyboard_entry_requests: Cell::new(0),
+                dock_progress: RefCell::new(None),
             };
             Ok(conn)
         }
@@ -200,6 +240,22 @@ impl ConnectionOps for Connection {
         }
     }
 
+    fn get_system_accessibility(&self) -> config::SystemAccessibility {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let reduce_transparency: BOOL =
+                msg_send![workspace, accessibilityDisplayShouldReduceTransparency];
+            let increase_contrast: BOOL =
+                msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+            let reduce_motion: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+            config::SystemAccessibility {
+                reduce_transparency: reduce_transparency == YES,
+                increase_contrast: increase_contrast == YES,
+                reduce_motion: reduce_motion == YES,
+            }
+        }
+    }
+
     fn run_message_loop(&self) -> anyhow::Result<()> {
         unsafe {
             self.ns_app.run();
@@ -220,6 +276,28 @@ impl ConnectionOps for Connection {
         }
     }
 
+    fn set_secure_keyboard_entry(&self, enable: bool) {
+        // Several windows may want secure entry at once, for example
+        // when focus moves from one password prompt to another and the
+        // newly focused window enables it before the other disables it.
+        // Only call through when the first request arrives or the last
+        // one goes away.
+        let requests = self.secure_keyboard_entry_requests.get();
+        let new_requests = if enable {
+            requests + 1
+        } else {
+            requests.saturating_sub(1)
+        };
+        self.secure_keyboard_entry_requests.set(new_requests);
+        unsafe {
+            if requests == 0 && new_requests == 1 {
+                EnableSecureEventInput();
+            } else if requests == 1 && new_requests == 0 {
+                DisableSecureEventInput();
+            }
+        }
+    }
+
     fn set_dock_badge(&self, label: Option<&str>) {
         unsafe {
             let app = NSApp();
@@ -233,6 +311,44 @@ impl ConnectionOps for Connection {
         }
     }
 
+    fn set_dock_progress(&self, progress: Option<DockProgress>) {
+        unsafe {
+            let app = NSApp();
+            let dock_tile: id = msg_send![app, dockTile];
+            match progress {
+                None => {
+                    let () = msg_send![dock_tile, setContentView: nil];
+                }
+                Some(progress) => {
+                    let indicator = self.dock_progress_indicator(dock_tile);
+                    match progress {
+                        DockProgress::Percentage(pct) => {
+                            let () = msg_send![indicator, setIndeterminate: NO];
+                            let () = msg_send![indicator, setDoubleValue: pct.min(100) as f64];
+                        }
+                        DockProgress::Indeterminate => {
+                            let () = msg_send![indicator, setIndeterminate: YES];
+                        }
+                    }
+                }
+            }
+            let () = msg_send![dock_tile, display];
+        }
+    }
+
+    fn request_user_attention(&self) {
+        unsafe {
+            let app = NSApp();
+            let active: BOOL = msg_send![app, isActive];
+            if active == NO {
+                // NSInformationalRequest bounces the Dock icon once; it is
+                // cancelled automatically when the application is activated
+                let request_type: NSInteger = 10;
+                let _: NSInteger = msg_send![app, requestUserAttention: request_type];
+            }
+        }
+    }
+
     fn alert(&self, title: &str, message: &str) {
         unsafe {
             let alert: id = msg_send![class!(NSAlert), alloc];
@@ -401,4 +517,6 @@ pub fn nsscreen_to_screen_info(screen: *mut Object) -> ScreenInfo {
 
 extern "C" {
     fn NSBeep();
+    fn EnableSecureEventInput() -> i32;
+    fn DisableSecureEventInput() -> i32;
 }
@@ -176,6 +176,61 @@ pub struct MenuItem {
     item: StrongPtr,
 }
 
+/// An item in the system status bar, on the right hand side of the
+/// menu bar.  The item is removed when this is dropped.
+pub struct StatusItem {
+    item: StrongPtr,
+}
+
+impl StatusItem {
+    pub fn new(title: &str) -> Self {
+        unsafe {
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            // NSVariableStatusItemLength
+            let length: f64 = -1.0;
+            let item: id = msg_send![status_bar, statusItemWithLength: length];
+            let item = Self {
+                item: StrongPtr::retain(item),
+            };
+            item.set_title(title);
+            item
+        }
+    }
+
+    pub fn set_title(&self, title: &str) {
+        unsafe {
+            let button: id = msg_send![*self.item, button];
+            if !button.is_null() {
+                let () = msg_send![button, setTitle:*nsstring(title)];
+            }
+        }
+    }
+
+    pub fn set_tool_tip(&self, tip: &str) {
+        unsafe {
+            let button: id = msg_send![*self.item, button];
+            if !button.is_null() {
+                let () = msg_send![button, setToolTip:*nsstring(tip)];
+            }
+        }
+    }
+
+    pub fn set_menu(&self, menu: &Menu) {
+        unsafe {
+            let () = msg_send![*self.item, setMenu:*menu.menu];
+        }
+    }
+}
+
+impl Drop for StatusItem {
+    fn drop(&mut self) {
+        unsafe {
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            let () = msg_send![status_bar, removeStatusItem:*self.item];
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum RepresentedItem {
     KeyAssignment(KeyAssignment),
@@ -8,10 +8,11 @@ use crate::connection::ConnectionOps;
 use crate::os::macos::menu::{MenuItem, RepresentedItem};
 use crate::parameters::{Border, Parameters, TitleBar};
 use crate::{
-    Clipboard, ClipboardData, Connection, DeadKeyStatus, Dimensions, Handled, KeyCode, KeyEvent,
-    Modifiers, MouseButtons, MouseCursor, MouseEvent, MouseEventKind, MousePress, Point,
-    RawKeyEvent, Rect, RequestedWindowGeometry, ResizeIncrement, ResolvedGeometry, ScreenPoint,
-    Size, ULength, WindowDecorations, WindowEvent, WindowEventSender, WindowOps, WindowState,
+    AccessibleText, Appearance, Clipboard, ClipboardData, Connection, DeadKeyStatus, Dimensions,
+    Gesture, Handled, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseCursor, MouseEvent,
+    MouseEventKind, MousePress, Point, RawKeyEvent, Rect, RequestedWindowGeometry, ResizeIncrement,
+    ResolvedGeometry, ScreenPoint, Size, ULength, Utf16AccessibleText, WindowDecorations,
+    WindowEvent, WindowEventSender, WindowOps, WindowState,
 };
 use anyhow::{anyhow, ensure};
 use async_trait::async_trait;
@@ -27,7 +28,7 @@ use cocoa::foundation::{
     NSString, NSUInteger,
 };
 use config::window::WindowLevel;
-use config::{ConfigHandle, RgbaColor, SrgbaTuple};
+use config::{ConfigHandle, RgbaColor, SrgbaTuple, WindowColorSpace};
 use core_foundation::base::{CFTypeID, TCFType};
 use core_foundation::bundle::{CFBundleGetBundleWithIdentifier, CFBundleGetFunctionPointerForName};
 use core_foundation::data::{CFData, CFDataGetBytePtr, CFDataRef};
@@ -57,6 +58,19 @@ use wezterm_input_types::{is_ascii_control, IntegratedTitleButtonStyle, Keyboard
 
 static APP_TERMINATING: AtomicBool = AtomicBool::new(false);
 
+/// macOS has no system-wide primary selection, so we keep our own
+/// process-local one that is shared across all windows. It is only
+/// populated by explicit copies to `Clipboard::PrimarySelection`,
+/// which keeps selecting text from clobbering the pasteboard.
+static PRIMARY_SELECTION: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+fn primary_selection() -> Option<String> {
+    PRIMARY_SELECTION
+        .lock()
+        .map(|primary| primary.clone())
+        .unwrap_or(None)
+}
+
 #[allow(non_upper_case_globals)]
 const NSViewLayerContentsPlacementTopLeft: NSInteger = 11;
 #[allow(non_upper_case_globals)]
@@ -70,6 +84,13 @@ const MOVE_PERSIST_DELAY_SECS: f64 = 0.35;
 // view is editable text.
 const AX_ROLE_TEXT_AREA: &[u8] = b"AXTextArea\0";
 const AX_ROLE_DESCRIPTION_TERMINAL_TEXT_AREA: &[u8] = b"terminal text area\0";
+const AX_VALUE_CHANGED_NOTIFICATION: &str = "AXValueChanged";
+const AX_SELECTED_TEXT_CHANGED_NOTIFICATION: &str = "AXSelectedTextChanged";
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSAccessibilityPostNotification(element: id, notification: id);
+}
 
 #[link(name = "CoreGraphics", kind = "framework")]
 extern "C" {
@@ -79,6 +100,69 @@ extern "C" {
         window_id: NSInteger,
         radius: i64,
     ) -> i32;
+
+    static kCGColorSpaceSRGB: CFStringRef;
+    static kCGColorSpaceDisplayP3: CFStringRef;
+    fn CGColorSpaceCreateWithName(name: CFStringRef) -> id;
+    fn CGColorSpaceCreateDeviceRGB() -> id;
+    fn CGColorSpaceRelease(space: id);
+    fn CGColorCreate(space: id, components: *const f64) -> id;
+    fn CGColorRelease(color: id);
+}
+
+/// Returns a retained CGColorSpaceRef for the configured color space.
+/// The caller must release it with CGColorSpaceRelease.
+fn create_cg_color_space(color_space: WindowColorSpace) -> id {
+    unsafe {
+        match color_space {
+            WindowColorSpace::Srgb => CGColorSpaceCreateWithName(kCGColorSpaceSRGB),
+            WindowColorSpace::DisplayP3 => CGColorSpaceCreateWithName(kCGColorSpaceDisplayP3),
+            WindowColorSpace::Display => CGColorSpaceCreateDeviceRGB(),
+        }
+    }
+}
+
+/// Tell Cocoa which color space we output in, so that it handles the
+/// conversion to the color profile of the display.
+fn apply_color_space(window: id, view: id, color_space: WindowColorSpace) {
+    unsafe {
+        let ns_color_space: id = match color_space {
+            WindowColorSpace::Srgb => msg_send![class!(NSColorSpace), sRGBColorSpace],
+            WindowColorSpace::DisplayP3 => msg_send![class!(NSColorSpace), displayP3ColorSpace],
+            WindowColorSpace::Display => {
+                let screen: id = msg_send![window, screen];
+                if screen.is_null() {
+                    msg_send![class!(NSColorSpace), deviceRGBColorSpace]
+                } else {
+                    msg_send![screen, colorSpace]
+                }
+            }
+        };
+        if !ns_color_space.is_null() {
+            let () = msg_send![window, setColorSpace: ns_color_space];
+        }
+
+        // A CAMetalLayer ignores the window color space, and performs no
+        // color matching at all unless it has a color space of its own.
+        if view.is_null() {
+            return;
+        }
+        let layer: id = msg_send![view, layer];
+        if layer.is_null() {
+            return;
+        }
+        let is_metal: BOOL = msg_send![layer, isKindOfClass: class!(CAMetalLayer)];
+        if is_metal == NO {
+            return;
+        }
+        if color_space == WindowColorSpace::Display {
+            let () = msg_send![layer, setColorspace: nil];
+        } else {
+            let space = create_cg_color_space(color_space);
+            let () = msg_send![layer, setColorspace: space];
+            CGColorSpaceRelease(space);
+        }
+    }
 }
 
 fn round_away_from_zerof(value: f64) -> f64 {
@@ -172,7 +256,10 @@ impl GlContextPair {
     /// project (and MetalANGLE) both provide implementations.
     /// The ANGLE EGL implementation wants a CALayer descendant passed
     /// as the EGLNativeWindowType.
-    pub fn create(view: id) -> anyhow::Result<Self> {
+    /// `software` requests software rasterization regardless of
+    /// the configured front_end.
+    pub fn create(view: id, software: bool) -> anyhow::Result<Self> {
+        let software = software || crate::configuration::prefer_swrast();
         let behavior = if cfg!(debug_assertions) {
             glium::debug::DebugCallbackBehavior::DebugMessageOnError
         } else {
@@ -180,7 +267,9 @@ impl GlContextPair {
         };
 
         // Let's first try to initialize EGL...
-        let (context, backend) = match if config::configuration().prefer_egl {
+        // ANGLE has no software renderer, so go straight to CGL when
+        // software rendering was requested.
+        let (context, backend) = match if config::configuration().prefer_egl && !software {
             // ANGLE wants a layer, so tell the view to create one.
             // Importantly, we must set its scale to 1.0 prior to initializing
             // EGL to prevent undesirable scaling.
@@ -239,7 +328,7 @@ impl GlContextPair {
                 unsafe {
                     let _: () = msg_send![view, setWantsLayer: NO];
                 }
-                let backend = Rc::new(cglbits::GlState::create(view)?);
+                let backend = Rc::new(cglbits::GlState::create(view, software)?);
                 let context =
                     unsafe { glium::backend::Context::new(Rc::clone(&backend), true, behavior) }?;
                 (context, BackendImpl::Cgl(backend))
@@ -253,13 +342,16 @@ impl GlContextPair {
 mod cglbits {
     use super::*;
 
+    /// The Apple software renderer; see CGLRenderers.h
+    const K_CGL_RENDERER_GENERIC_FLOAT_ID: u32 = 0x0002_0400;
+
     pub struct GlState {
         _pixel_format: StrongPtr,
         gl_context: StrongPtr,
     }
 
     impl GlState {
-        pub fn create(view: id) -> anyhow::Result<Self> {
+        pub fn create(view: id, software: bool) -> anyhow::Result<Self> {
             let make_pixel_format = |require_accelerated: bool| unsafe {
                 let mut attrs = vec![
                     appkit::NSOpenGLPFAOpenGLProfile as u32,
@@ -277,6 +369,9 @@ mod cglbits {
                 ];
                 if require_accelerated {
                     attrs.push(appkit::NSOpenGLPFAAccelerated as u32);
+                } else if software {
+                    attrs.push(appkit::NSOpenGLPFARendererID as u32);
+                    attrs.push(K_CGL_RENDERER_GENERIC_FLOAT_ID);
                 }
                 attrs.push(appkit::NSOpenGLPFADoubleBuffer as u32);
                 attrs.push(0);
@@ -284,7 +379,7 @@ mod cglbits {
             };
 
             log::trace!("Calling NSOpenGLPixelFormat::initWithAttributes");
-            let mut pixel_format = make_pixel_format(true);
+            let mut pixel_format = make_pixel_format(!software);
             if pixel_format.is_null() {
                 log::warn!(
                     "No accelerated NSOpenGL pixel format available; retrying without NSOpenGLPFAAccelerated"
@@ -915,6 +1010,7 @@ impl Window {
                 last_wheel: Instant::now(),
                 key_is_down: None,
                 dead_pending: None,
+                suppress_dead_keys: false,
                 fullscreen: None,
                 config: config.clone(),
                 ime_state: ImeDisposition::None,
@@ -923,6 +1019,7 @@ impl Window {
                 last_reported_dpi: None,
                 last_reported_window_state: WindowState::default(),
                 ime_text: String::new(),
+                accessible_text: Utf16AccessibleText::default(),
             }));
 
             let window: id = msg_send![get_window_class(), alloc];
@@ -941,16 +1038,13 @@ impl Window {
                 config.native_macos_fullscreen_mode,
             );
 
-            // Prevent Cocoa native tabs from being used
-            let _: () = msg_send![*window, setTabbingMode:2 /* NSWindowTabbingModeDisallowed */];
+            apply_tabbing_mode(*window, &config);
             let _: () = msg_send![*window, setRestorable: NO];
 
             window.setReleasedWhenClosed_(NO);
             window.setBackgroundColor_(cocoa::appkit::NSColor::clearColor(nil));
 
-            // Tell Cocoa that we output in sRGB, so it handles color space
-            // conversion for non-sRGB displays.
-            window.setColorSpace_(cocoa::appkit::NSColorSpace::sRGBColorSpace(nil));
+            apply_color_space(*window, nil, config.window_color_space);
 
             // We could set this, but it makes the entire window, including
             // its titlebar, opaque to this fixed degree.
@@ -1067,6 +1161,22 @@ impl Window {
     fn ns_window(&self) -> Option<*mut Object> {
         self.with_window_inner(|inner| *inner.window)
     }
+
+    async fn enable_opengl_impl(
+        &self,
+        software: bool,
+    ) -> anyhow::Result<Rc<glium::backend::Context>> {
+        let window_id = self.id;
+        promise::spawn::spawn(async move {
+            let conn = Connection::get().ok_or_else(|| anyhow!("connection not initialized"))?;
+            let handle = conn
+                .window_by_id(window_id)
+                .ok_or_else(|| anyhow!("invalid window"))?;
+            let mut inner = handle.borrow_mut();
+            inner.enable_opengl(software)
+        })
+        .await
+    }
 }
 
 impl HasDisplayHandle for Window {
@@ -1110,16 +1220,11 @@ pub fn window_level_to_nswindow_level(level: WindowLevel) -> NSWindowLevel {
 #[async_trait(?Send)]
 impl WindowOps for Window {
     async fn enable_opengl(&self) -> anyhow::Result<Rc<glium::backend::Context>> {
-        let window_id = self.id;
-        promise::spawn::spawn(async move {
-            let conn = Connection::get().ok_or_else(|| anyhow!("connection not initialized"))?;
-            let handle = conn
-                .window_by_id(window_id)
-                .ok_or_else(|| anyhow!("invalid window"))?;
-            let mut inner = handle.borrow_mut();
-            inner.enable_opengl()
-        })
-        .await
+        self.enable_opengl_impl(false).await
+    }
+
+    async fn enable_software_opengl(&self) -> anyhow::Result<Rc<glium::backend::Context>> {
+        self.enable_opengl_impl(true).await
     }
 
     fn notify<T: Any + Send + Sync>(&self, t: T)
@@ -1244,7 +1349,28 @@ impl WindowOps for Window {
         });
     }
 
-    fn get_clipboard(&self, _clipboard: Clipboard) -> Future<String> {
+    fn set_accessible_text(&self, text: AccessibleText) {
+        Connection::with_window_inner(self.id, move |inner| {
+            inner.set_accessible_text(text);
+            Ok(())
+        });
+    }
+
+    fn set_dead_keys_suppressed(&self, suppressed: bool) {
+        Connection::with_window_inner(self.id, move |inner| {
+            if let Some(window_view) = WindowView::get_this(unsafe { &**inner.view }) {
+                window_view.inner.borrow_mut().suppress_dead_keys = suppressed;
+            }
+            Ok(())
+        });
+    }
+
+    fn get_clipboard(&self, clipboard: Clipboard) -> Future<String> {
+        if clipboard == Clipboard::PrimarySelection {
+            if let Some(text) = primary_selection() {
+                return Future::ok(text);
+            }
+        }
         Future::result(
             ClipboardContext::new()
                 .read()
@@ -1252,7 +1378,12 @@ impl WindowOps for Window {
         )
     }
 
-    fn get_clipboard_data(&self, _clipboard: Clipboard) -> Future<ClipboardData> {
+    fn get_clipboard_data(&self, clipboard: Clipboard) -> Future<ClipboardData> {
+        if clipboard == Clipboard::PrimarySelection {
+            if let Some(text) = primary_selection() {
+                return Future::ok(ClipboardData::Text(text));
+            }
+        }
         Future::result(
             ClipboardContext::new()
                 .read_data()
@@ -1260,8 +1391,17 @@ impl WindowOps for Window {
         )
     }
 
-    fn set_clipboard(&self, _clipboard: Clipboard, text: String) {
-        ClipboardContext::new().write(text).ok();
+    fn set_clipboard(&self, clipboard: Clipboard, text: String) {
+        match clipboard {
+            Clipboard::Clipboard => {
+                ClipboardContext::new().write(text).ok();
+            }
+            Clipboard::PrimarySelection => {
+                if let Ok(mut primary) = PRIMARY_SELECTION.lock() {
+                    primary.replace(text);
+                }
+            }
+        }
     }
 
     fn toggle_fullscreen(&self) {
@@ -1271,6 +1411,27 @@ impl WindowOps for Window {
         });
     }
 
+    fn activate_native_tab(&self, index: isize) {
+        Connection::with_window_inner(self.id, move |inner| {
+            inner.activate_native_tab(index);
+            Ok(())
+        });
+    }
+
+    fn activate_native_tab_relative(&self, delta: isize, wrap: bool) {
+        Connection::with_window_inner(self.id, move |inner| {
+            inner.activate_native_tab_relative(delta, wrap);
+            Ok(())
+        });
+    }
+
+    fn toggle_native_tab_overview(&self) {
+        Connection::with_window_inner(self.id, move |inner| {
+            inner.toggle_native_tab_overview();
+            Ok(())
+        });
+    }
+
     fn maximize(&self) {
         Connection::with_window_inner(self.id, move |inner| {
             inner.maximize();
@@ -1490,9 +1651,9 @@ impl WindowInner {
         }
     }
 
-    fn enable_opengl(&mut self) -> anyhow::Result<Rc<glium::backend::Context>> {
+    fn enable_opengl(&mut self, software: bool) -> anyhow::Result<Rc<glium::backend::Context>> {
         if let Some(window_view) = WindowView::get_this(unsafe { &**self.view }) {
-            window_view.inner.borrow_mut().enable_opengl()
+            window_view.inner.borrow_mut().enable_opengl(software)
         } else {
             anyhow::bail!("window invalid");
         }
@@ -1707,15 +1868,20 @@ impl WindowInner {
                     return;
                 }
 
-                // We need to make sure to convert the config color into an sRGB CGColor or the color will be slightly off
-                let srgb_cgcolor = objc2_core_graphics::CGColor::new_srgb(
-                    color.0.into(),
-                    color.1.into(),
-                    color.2.into(),
-                    color.3.into(),
-                );
+                // We need to make sure to interpret the config color in the
+                // same color space as the terminal, or the color will be slightly off
+                let space = create_cg_color_space(self.config.window_color_space);
+                let components = [
+                    color.0 as f64,
+                    color.1 as f64,
+                    color.2 as f64,
+                    color.3 as f64,
+                ];
+                let cgcolor = CGColorCreate(space, components.as_ptr());
+                CGColorSpaceRelease(space);
 
-                let _: () = msg_send![layer, setBackgroundColor: srgb_cgcolor];
+                let _: () = msg_send![layer, setBackgroundColor: cgcolor];
+                CGColorRelease(cgcolor);
             } else {
                 log::trace!("failed to get titlebar view container from window");
             }
@@ -1757,6 +1923,16 @@ impl WindowInner {
         false
     }
 
+    pub(crate) fn notify_appearance_changed(&mut self, appearance: Appearance) {
+        if let Some(window_view) = WindowView::get_this(unsafe { &**self.view }) {
+            if let Ok(mut inner) = window_view.inner.try_borrow_mut() {
+                inner
+                    .events
+                    .dispatch(WindowEvent::AppearanceChanged(appearance));
+            }
+        }
+    }
+
     fn show(&mut self) {
         unsafe {
             let current_app = NSRunningApplication::currentApplication(nil);
@@ -1828,6 +2004,60 @@ impl WindowInner {
         }
     }
 
+    /// Returns the windows in the native tab group of this window and
+    /// the index of this window among them, or None if it isn't tabbed
+    fn native_tabs(&self) -> Option<(id, usize, usize)> {
+        unsafe {
+            let tabs: id = msg_send![*self.window, tabbedWindows];
+            if tabs.is_null() {
+                return None;
+            }
+            let count: usize = msg_send![tabs, count];
+            let active: usize = msg_send![tabs, indexOfObject: *self.window];
+            if active >= count {
+                return None;
+            }
+            Some((tabs, active, count))
+        }
+    }
+
+    fn activate_native_tab(&mut self, index: isize) {
+        let Some((tabs, _active, count)) = self.native_tabs() else {
+            return;
+        };
+        let index = if index < 0 {
+            count.saturating_sub(index.unsigned_abs())
+        } else {
+            index as usize
+        };
+        if index < count {
+            unsafe {
+                let tab: id = msg_send![tabs, objectAtIndex: index];
+                let () = msg_send![tab, makeKeyAndOrderFront: nil];
+            }
+        }
+    }
+
+    fn activate_native_tab_relative(&mut self, delta: isize, wrap: bool) {
+        let Some((_tabs, active, count)) = self.native_tabs() else {
+            return;
+        };
+        let count = count as isize;
+        let index = active as isize + delta;
+        let index = if wrap {
+            index.rem_euclid(count)
+        } else {
+            index.clamp(0, count - 1)
+        };
+        self.activate_native_tab(index);
+    }
+
+    fn toggle_native_tab_overview(&mut self) {
+        unsafe {
+            let () = msg_send![*self.window, toggleTabOverview: nil];
+        }
+    }
+
     fn set_cursor(&mut self, cursor: Option<MouseCursor>) {
         unsafe {
             let ns_cursor_cls = class!(NSCursor);
@@ -1897,6 +2127,33 @@ impl WindowInner {
     // request_drag_move moved to mouse_down for synchronous execution to avoid
     // modal drag loop swallowing subsequent events
 
+    fn set_accessible_text(&mut self, text: AccessibleText) {
+        let (value_changed, cursor_changed) = match WindowView::get_this(unsafe { &**self.view }) {
+            Some(window_view) => {
+                let mut inner = window_view.inner.borrow_mut();
+                let previous = &inner.accessible_text;
+                let value_changed = previous.source.lines != text.lines;
+                let cursor_changed = previous.source.cursor_line != text.cursor_line
+                    || previous.source.cursor_column != text.cursor_column;
+                inner.accessible_text = Utf16AccessibleText::new(text);
+                (value_changed, cursor_changed)
+            }
+            None => return,
+        };
+        // Post these after releasing the borrow, as assistive clients
+        // may query the view while they are being delivered
+        unsafe {
+            if value_changed {
+                let name = nsstring(AX_VALUE_CHANGED_NOTIFICATION);
+                NSAccessibilityPostNotification(*self.view, *name);
+            }
+            if value_changed || cursor_changed {
+                let name = nsstring(AX_SELECTED_TEXT_CHANGED_NOTIFICATION);
+                NSAccessibilityPostNotification(*self.view, *name);
+            }
+        }
+    }
+
     fn set_text_cursor_position(&mut self, cursor: Rect) {
         if let Some(window_view) = WindowView::get_this(unsafe { &**self.view }) {
             window_view.inner.borrow_mut().text_cursor_position = cursor;
@@ -1986,10 +2243,26 @@ impl WindowInner {
                 inner.screen_changed = true;
             }
         }
+        apply_color_space(*self.window, *self.view, config.window_color_space);
         self.update_window_shadow();
         self.update_window_background_blur();
         self.update_titlebar_background();
         self.apply_decorations();
+        apply_tabbing_mode(*self.window, config);
+    }
+}
+
+/// Native window tabbing is disallowed unless `use_native_macos_tabs`
+/// is set, in which case new windows prefer to join the tab group of
+/// the current key window
+fn apply_tabbing_mode(window: id, config: &ConfigHandle) {
+    unsafe {
+        if config.use_native_macos_tabs {
+            let _: () = msg_send![window, setTabbingIdentifier: *nsstring("Kaku")];
+            let _: () = msg_send![window, setTabbingMode:1 /* NSWindowTabbingModePreferred */];
+        } else {
+            let _: () = msg_send![window, setTabbingMode:2 /* NSWindowTabbingModeDisallowed */];
+        }
     }
 }
 
@@ -2208,6 +2481,8 @@ struct Inner {
 
     /// First in a dead-key sequence
     dead_pending: Option<DeadKeyState>,
+    /// Set by the application to disable dead keys for the time being
+    suppress_dead_keys: bool,
 
     /// When using simple fullscreen mode, this tracks
     /// the window dimensions that need to be restored
@@ -2231,6 +2506,9 @@ struct Inner {
     last_reported_window_state: WindowState,
 
     ime_text: String,
+
+    /// The text exposed to assistive technology
+    accessible_text: Utf16AccessibleText,
 }
 
 #[repr(C)]
@@ -2399,9 +2677,9 @@ impl Keyboard {
 }
 
 impl Inner {
-    fn enable_opengl(&mut self) -> anyhow::Result<Rc<glium::backend::Context>> {
+    fn enable_opengl(&mut self, software: bool) -> anyhow::Result<Rc<glium::backend::Context>> {
         let view = self.view_id.as_ref().unwrap().load();
-        let glium_context = GlContextPair::create(*view)?;
+        let glium_context = GlContextPair::create(*view, software)?;
 
         self.gl_context_pair.replace(glium_context.clone());
 
@@ -2424,7 +2702,7 @@ impl Inner {
 
         let config = &self.config;
 
-        let use_dead_keys = if !config.use_dead_keys {
+        let use_dead_keys = if !config.use_dead_keys || self.suppress_dead_keys {
             false
         } else if force_dead_keys {
             true
@@ -3462,6 +3740,127 @@ impl WindowView {
         }
     }
 
+    fn with_accessible_text<T>(
+        this: &Object,
+        default: T,
+        f: impl FnOnce(&Utf16AccessibleText) -> T,
+    ) -> T {
+        match Self::get_this(this) {
+            Some(this) => f(&this.inner.borrow().accessible_text),
+            None => default,
+        }
+    }
+
+    extern "C" fn accessibility_value(this: &Object, _sel: Sel) -> id {
+        let text = Self::with_accessible_text(this, String::new(), |text| {
+            text.string_for_range(0..text.len())
+        });
+        nsstring(&text).autorelease()
+    }
+
+    extern "C" fn accessibility_number_of_characters(this: &Object, _sel: Sel) -> NSInteger {
+        Self::with_accessible_text(this, 0, |text| text.len() as NSInteger)
+    }
+
+    extern "C" fn accessibility_selected_text(_this: &Object, _sel: Sel) -> id {
+        nsstring("").autorelease()
+    }
+
+    extern "C" fn accessibility_selected_text_range(this: &Object, _sel: Sel) -> NSRange {
+        Self::with_accessible_text(this, NSRange::new(0, 0), |text| {
+            NSRange::new(text.cursor as u64, 0)
+        })
+    }
+
+    extern "C" fn accessibility_visible_character_range(this: &Object, _sel: Sel) -> NSRange {
+        Self::with_accessible_text(this, NSRange::new(0, 0), |text| {
+            NSRange::new(0, text.len() as u64)
+        })
+    }
+
+    extern "C" fn accessibility_insertion_point_line_number(this: &Object, _sel: Sel) -> NSInteger {
+        Self::with_accessible_text(this, 0, |text| {
+            text.line_for_index(text.cursor) as NSInteger
+        })
+    }
+
+    extern "C" fn accessibility_line_for_index(
+        this: &Object,
+        _sel: Sel,
+        index: NSInteger,
+    ) -> NSInteger {
+        Self::with_accessible_text(this, 0, |text| {
+            text.line_for_index(index.max(0) as usize) as NSInteger
+        })
+    }
+
+    extern "C" fn accessibility_range_for_line(
+        this: &Object,
+        _sel: Sel,
+        line: NSInteger,
+    ) -> NSRange {
+        Self::with_accessible_text(this, NSRange::new(NSNotFound as u64, 0), |text| match text
+            .range_for_line(line.max(0) as usize)
+        {
+            Some(range) => NSRange::new(range.start as u64, range.len() as u64),
+            None => NSRange::new(NSNotFound as u64, 0),
+        })
+    }
+
+    fn accessible_string_for_range(this: &Object, range: NSRange) -> String {
+        let start = range.0.location as usize;
+        let end = start.saturating_add(range.0.length as usize);
+        Self::with_accessible_text(this, String::new(), |text| {
+            text.string_for_range(start..end)
+        })
+    }
+
+    extern "C" fn accessibility_string_for_range(this: &Object, _sel: Sel, range: NSRange) -> id {
+        nsstring(&Self::accessible_string_for_range(this, range)).autorelease()
+    }
+
+    extern "C" fn accessibility_attributed_string_for_range(
+        this: &Object,
+        _sel: Sel,
+        range: NSRange,
+    ) -> id {
+        let string = nsstring(&Self::accessible_string_for_range(this, range));
+        unsafe {
+            let attributed: id = msg_send![class!(NSAttributedString), alloc];
+            let attributed: id = msg_send![attributed, initWithString: *string];
+            msg_send![attributed, autorelease]
+        }
+    }
+
+    extern "C" fn accessibility_frame_for_range(
+        this: &Object,
+        _sel: Sel,
+        range: NSRange,
+    ) -> NSRect {
+        // Returns a rect in screen coordinates, like
+        // firstRectForCharacterRange:actualRange:
+        let window: id = unsafe { msg_send![this, window] };
+        let frame = unsafe { NSWindow::frame(window) };
+        let content: NSRect = unsafe { msg_send![window, contentRectForFrameRect: frame] };
+        let backing_frame: NSRect = unsafe { msg_send![this, convertRectToBacking: frame] };
+        let scale = frame.size.width / backing_frame.size.width;
+
+        let start = range.0.location as usize;
+        let end = start.saturating_add(range.0.length as usize);
+        let rect =
+            Self::with_accessible_text(this, Rect::zero(), |text| text.rect_for_range(start..end))
+                .to_f64()
+                .scale(scale, scale);
+
+        NSRect::new(
+            NSPoint::new(
+                content.origin.x + rect.min_x(),
+                content.origin.y + content.size.height - rect.max_y(),
+            ),
+            NSSize::new(rect.size.width, rect.size.height),
+        )
+    }
+
     extern "C" fn kaku_perform_key_assignment(
         this: &mut Object,
         _sel: Sel,
@@ -3718,6 +4117,35 @@ impl WindowView {
         Self::mouse_common(this, nsevent, kind);
     }
 
+    fn dispatch_gesture(this: &mut Object, gesture: Gesture) {
+        if let Some(myself) = Self::get_this(this) {
+            myself
+                .inner
+                .borrow_mut()
+                .events
+                .dispatch(WindowEvent::Gesture(gesture));
+        }
+    }
+
+    extern "C" fn swipe_with_event(this: &mut Object, _sel: Sel, nsevent: id) {
+        // Only delivered when the system trackpad preferences route
+        // "swipe between pages" to the application.  A positive deltaX
+        // is a swipe that navigates back.
+        let delta_x = unsafe { nsevent.deltaX() };
+        if delta_x > 0.0 {
+            Self::dispatch_gesture(this, Gesture::SwipeBack);
+        } else if delta_x < 0.0 {
+            Self::dispatch_gesture(this, Gesture::SwipeForward);
+        }
+    }
+
+    extern "C" fn magnify_with_event(this: &mut Object, _sel: Sel, nsevent: id) {
+        let magnification: CGFloat = unsafe { msg_send![nsevent, magnification] };
+        if magnification != 0.0 {
+            Self::dispatch_gesture(this, Gesture::Magnify(magnification));
+        }
+    }
+
     extern "C" fn right_mouse_down(this: &mut Object, _sel: Sel, nsevent: id) {
         Self::mouse_common(this, nsevent, MouseEventKind::Press(MousePress::Right));
     }
@@ -4208,6 +4636,13 @@ impl WindowView {
         }
     }
 
+    extern "C" fn did_change_miniaturized(this: &mut Object, sel: Sel, notification: id) {
+        log::trace!("did_change_miniaturized");
+        // Report the change through the resize path, which
+        // includes HIDDEN in the window state while miniaturized
+        Self::did_resize(this, sel, notification);
+    }
+
     extern "C" fn will_start_live_resize(this: &mut Object, _sel: Sel, _notification: id) {
         if let Some(this) = Self::get_this(this) {
             let mut inner = this.inner.borrow_mut();
@@ -4431,6 +4866,16 @@ impl WindowView {
                 _ => WindowState::default(),
             };
 
+            let is_miniaturized = inner.window.as_ref().map_or(false, |window| {
+                let window = window.load();
+                !window.is_null() && unsafe { msg_send![*window, isMiniaturized] }
+            });
+            let hidden_state = if is_miniaturized {
+                WindowState::HIDDEN
+            } else {
+                WindowState::default()
+            };
+
             let fallback_scale = inner
                 .window
                 .as_ref()
@@ -4473,7 +4918,7 @@ impl WindowView {
             };
             inner.last_reported_dpi = Some(dpi);
 
-            let window_state = screen_state | level_state;
+            let window_state = screen_state | level_state | hidden_state;
             let prior_window_state = inner.last_reported_window_state;
             let maximized_toggled = prior_window_state.contains(WindowState::MAXIMIZED)
                 != window_state.contains(WindowState::MAXIMIZED);
@@ -4596,9 +5041,15 @@ impl WindowView {
 
     extern "C" fn make_backing_layer(view: &mut Object, _: Sel) -> id {
         log::trace!("make_backing_layer");
-        let use_metal_backing_layer = Self::get_this(view)
-            .map(|this| this.inner.borrow().config.front_end == config::FrontEndSelection::WebGpu)
-            .unwrap_or(false);
+        let (use_metal_backing_layer, color_space) = Self::get_this(view)
+            .map(|this| {
+                let inner = this.inner.borrow();
+                (
+                    inner.config.front_end == config::FrontEndSelection::WebGpu,
+                    inner.config.window_color_space,
+                )
+            })
+            .unwrap_or((false, WindowColorSpace::default()));
         let class = if use_metal_backing_layer {
             class!(CAMetalLayer)
         } else {
@@ -4611,6 +5062,11 @@ impl WindowView {
             let () = msg_send![layer, setDelegate: view];
             let () = msg_send![layer, setContentsScale: 1.0];
             let () = msg_send![layer, setOpaque: NO];
+            if use_metal_backing_layer && color_space != WindowColorSpace::Display {
+                let space = create_cg_color_space(color_space);
+                let () = msg_send![layer, setColorspace: space];
+                CGColorSpaceRelease(space);
+            }
             layer
         }
     }
@@ -4893,6 +5349,14 @@ impl WindowView {
                 sel!(windowDidMove:),
                 Self::did_move as extern "C" fn(&mut Object, Sel, id),
             );
+            cls.add_method(
+                sel!(windowDidMiniaturize:),
+                Self::did_change_miniaturized as extern "C" fn(&mut Object, Sel, id),
+            );
+            cls.add_method(
+                sel!(windowDidDeminiaturize:),
+                Self::did_change_miniaturized as extern "C" fn(&mut Object, Sel, id),
+            );
             cls.add_method(
                 sel!(windowDidChangeScreen:),
                 Self::did_change_screen as extern "C" fn(&mut Object, Sel, id),
@@ -4959,6 +5423,14 @@ impl WindowView {
                 sel!(mouseExited:),
                 Self::mouse_exited as extern "C" fn(&mut Object, Sel, id),
             );
+            cls.add_method(
+                sel!(swipeWithEvent:),
+                Self::swipe_with_event as extern "C" fn(&mut Object, Sel, id),
+            );
+            cls.add_method(
+                sel!(magnifyWithEvent:),
+                Self::magnify_with_event as extern "C" fn(&mut Object, Sel, id),
+            );
 
             cls.add_method(
                 sel!(keyDown:),
@@ -5073,6 +5545,59 @@ impl WindowView {
                 sel!(accessibilityRoleDescription),
                 Self::accessibility_role_description as extern "C" fn(&Object, Sel) -> id,
             );
+
+            // Expose the text of the active pane to screen readers
+            cls.add_method(
+                sel!(accessibilityValue),
+                Self::accessibility_value as extern "C" fn(&Object, Sel) -> id,
+            );
+            cls.add_method(
+                sel!(accessibilityNumberOfCharacters),
+                Self::accessibility_number_of_characters
+                    as extern "C" fn(&Object, Sel) -> NSInteger,
+            );
+            cls.add_method(
+                sel!(accessibilitySelectedText),
+                Self::accessibility_selected_text as extern "C" fn(&Object, Sel) -> id,
+            );
+            cls.add_method(
+                sel!(accessibilitySelectedTextRange),
+                Self::accessibility_selected_text_range as extern "C" fn(&Object, Sel) -> NSRange,
+            );
+            cls.add_method(
+                sel!(accessibilityVisibleCharacterRange),
+                Self::accessibility_visible_character_range
+                    as extern "C" fn(&Object, Sel) -> NSRange,
+            );
+            cls.add_method(
+                sel!(accessibilityInsertionPointLineNumber),
+                Self::accessibility_insertion_point_line_number
+                    as extern "C" fn(&Object, Sel) -> NSInteger,
+            );
+            cls.add_method(
+                sel!(accessibilityLineForIndex:),
+                Self::accessibility_line_for_index
+                    as extern "C" fn(&Object, Sel, NSInteger) -> NSInteger,
+            );
+            cls.add_method(
+                sel!(accessibilityRangeForLine:),
+                Self::accessibility_range_for_line
+                    as extern "C" fn(&Object, Sel, NSInteger) -> NSRange,
+            );
+            cls.add_method(
+                sel!(accessibilityStringForRange:),
+                Self::accessibility_string_for_range as extern "C" fn(&Object, Sel, NSRange) -> id,
+            );
+            cls.add_method(
+                sel!(accessibilityAttributedStringForRange:),
+                Self::accessibility_attributed_string_for_range
+                    as extern "C" fn(&Object, Sel, NSRange) -> id,
+            );
+            cls.add_method(
+                sel!(accessibilityFrameForRange:),
+                Self::accessibility_frame_for_range
+                    as extern "C" fn(&Object, Sel, NSRange) -> NSRect,
+            );
         }
 
         cls.register()